@@ -0,0 +1,12 @@
+CREATE TABLE IF NOT EXISTS order_voids (
+    order_id           INTEGER PRIMARY KEY,
+    reversal_order_id  INTEGER NOT NULL UNIQUE,
+    operator_mdoc      INTEGER NOT NULL,
+    reason             TEXT    NOT NULL,
+    created_at         DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+
+    FOREIGN KEY(order_id)
+        REFERENCES customer_transactions(order_id),
+    FOREIGN KEY(reversal_order_id)
+        REFERENCES customer_transactions(order_id)
+);
@@ -0,0 +1,27 @@
+CREATE TABLE IF NOT EXISTS order_returns (
+    return_order_id  INTEGER PRIMARY KEY,
+    order_id         INTEGER NOT NULL,
+    operator_mdoc    INTEGER NOT NULL,
+    reason           TEXT,
+    created_at       DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+
+    FOREIGN KEY(return_order_id)
+        REFERENCES customer_transactions(order_id),
+    FOREIGN KEY(order_id)
+        REFERENCES customer_transactions(order_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_order_returns_order_id ON order_returns(order_id);
+
+CREATE TABLE IF NOT EXISTS order_return_lines (
+    id               INTEGER PRIMARY KEY AUTOINCREMENT,
+    return_order_id  INTEGER NOT NULL,
+    detail_id        INTEGER NOT NULL,
+    quantity         INTEGER NOT NULL CHECK (quantity > 0),
+    restocked        INTEGER NOT NULL DEFAULT 0,
+
+    FOREIGN KEY(return_order_id)
+        REFERENCES order_returns(return_order_id),
+    FOREIGN KEY(detail_id)
+        REFERENCES customer_tx_detail(detail_id)
+);
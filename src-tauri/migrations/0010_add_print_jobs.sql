@@ -0,0 +1,20 @@
+CREATE TABLE IF NOT EXISTS print_jobs (
+    id                INTEGER PRIMARY KEY AUTOINCREMENT,
+    order_id          INTEGER NOT NULL,
+    copies            TEXT    NOT NULL DEFAULT 'both',
+    printer_name      TEXT    NOT NULL,
+    sumatra_location  TEXT    NOT NULL,
+    operator_name     TEXT    NOT NULL DEFAULT '',
+    customer_name     TEXT    NOT NULL DEFAULT '',
+    reprint_by        INTEGER,
+    status            TEXT    NOT NULL DEFAULT 'pending',
+    attempts          INTEGER NOT NULL DEFAULT 0,
+    last_error        TEXT,
+    created_at        DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at        DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+
+    FOREIGN KEY(order_id)
+        REFERENCES customer_transactions(order_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_print_jobs_status ON print_jobs(status);
@@ -0,0 +1,33 @@
+CREATE TABLE IF NOT EXISTS customer_tiers (
+    id            INTEGER PRIMARY KEY AUTOINCREMENT,
+    name          TEXT    NOT NULL UNIQUE,
+    weekly_limit  INTEGER NOT NULL CHECK (weekly_limit >= 0)
+);
+
+-- at most one tier per customer; customers without a row use the default limit
+CREATE TABLE IF NOT EXISTS customer_tier_members (
+    customer_mdoc  INTEGER PRIMARY KEY,
+    tier_id        INTEGER NOT NULL,
+
+    FOREIGN KEY(customer_mdoc)
+        REFERENCES customer(mdoc),
+    FOREIGN KEY(tier_id)
+        REFERENCES customer_tiers(id)
+);
+
+CREATE TABLE IF NOT EXISTS customer_limit_overrides (
+    id             INTEGER PRIMARY KEY AUTOINCREMENT,
+    customer_mdoc  INTEGER NOT NULL,
+    weekly_limit   INTEGER NOT NULL CHECK (weekly_limit >= 0),
+    starts_on      DATE    NOT NULL,
+    ends_on        DATE,
+    reason         TEXT    NOT NULL,
+    operator_mdoc  INTEGER NOT NULL,
+    created_at     DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+
+    FOREIGN KEY(customer_mdoc)
+        REFERENCES customer(mdoc)
+);
+
+CREATE INDEX IF NOT EXISTS idx_customer_limit_overrides_mdoc
+    ON customer_limit_overrides(customer_mdoc);
@@ -0,0 +1,10 @@
+-- single-row settings for how spending limits are bucketed;
+-- defaults reproduce the original Sunday-midnight UTC week
+CREATE TABLE IF NOT EXISTS limit_settings (
+    period       TEXT    NOT NULL DEFAULT 'weekly',
+    week_start   INTEGER NOT NULL DEFAULT 0 CHECK (week_start BETWEEN 0 AND 6), -- 0 = Sunday
+    anchor_date  DATE    NOT NULL DEFAULT '2025-01-05',
+    time_zone    TEXT    NOT NULL DEFAULT 'UTC'
+);
+
+INSERT INTO limit_settings DEFAULT VALUES;
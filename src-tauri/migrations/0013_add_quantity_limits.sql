@@ -0,0 +1,17 @@
+-- caps on units bought per customer, for one product or a whole category
+CREATE TABLE IF NOT EXISTS quantity_limits (
+    id            INTEGER PRIMARY KEY AUTOINCREMENT,
+    upc           TEXT,
+    category      TEXT,
+    max_quantity  INTEGER NOT NULL CHECK (max_quantity >= 0),
+    period        TEXT    NOT NULL, -- 'rolling' or a calendar period: daily, weekly, ...
+    rolling_days  INTEGER CHECK (rolling_days > 0),
+    note          TEXT,
+    created_at    DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+
+    CHECK ((upc IS NULL) <> (category IS NULL)),
+    FOREIGN KEY(upc)
+        REFERENCES products(upc),
+    FOREIGN KEY(category)
+        REFERENCES categories(name)
+);
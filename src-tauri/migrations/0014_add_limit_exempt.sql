@@ -0,0 +1,4 @@
+-- purchases in exempt categories (legal supplies, postage, co-pays) don't count
+-- toward the spending limit; a product's own flag, when set, overrides its category
+ALTER TABLE categories ADD COLUMN limit_exempt INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE products ADD COLUMN limit_exempt INTEGER;
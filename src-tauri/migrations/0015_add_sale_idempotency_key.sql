@@ -0,0 +1,6 @@
+-- client-generated key sent with a sale; a resubmission with the same key
+-- returns the original order instead of recording a second one
+ALTER TABLE customer_transactions ADD COLUMN idempotency_key TEXT;
+CREATE UNIQUE INDEX IF NOT EXISTS idx_customer_transactions_idempotency_key
+    ON customer_transactions(idempotency_key)
+    WHERE idempotency_key IS NOT NULL;
@@ -0,0 +1,3 @@
+-- why an order was rung up; existing orders are regular sales
+ALTER TABLE customer_transactions ADD COLUMN reason_code TEXT NOT NULL DEFAULT 'regular'
+    CHECK (reason_code IN ('regular', 'indigent_kit', 'replacement', 'special_order'));
@@ -0,0 +1,39 @@
+CREATE TABLE IF NOT EXISTS vendors (
+    id          INTEGER PRIMARY KEY AUTOINCREMENT,
+    name        TEXT    NOT NULL UNIQUE,
+    contact     TEXT,
+    phone       TEXT,
+    deleted     DATETIME,
+    created_at  DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- open until every line is received in full (or the order is cancelled)
+CREATE TABLE IF NOT EXISTS purchase_orders (
+    id             INTEGER PRIMARY KEY AUTOINCREMENT,
+    vendor_id      INTEGER NOT NULL,
+    reference      TEXT,              -- vendor's order / invoice number
+    status         TEXT    NOT NULL DEFAULT 'open'
+                   CHECK (status IN ('open', 'received', 'cancelled')),
+    operator_mdoc  INTEGER NOT NULL,
+    created_at     DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    received_at    DATETIME,
+
+    FOREIGN KEY(vendor_id) REFERENCES vendors(id),
+    FOREIGN KEY(operator_mdoc) REFERENCES operators(mdoc)
+);
+
+-- lines the vendor shipped but we never ordered are added with expected_quantity 0
+CREATE TABLE IF NOT EXISTS purchase_order_lines (
+    id                 INTEGER PRIMARY KEY AUTOINCREMENT,
+    po_id              INTEGER NOT NULL,
+    upc                TEXT    NOT NULL,
+    expected_quantity  INTEGER NOT NULL CHECK (expected_quantity >= 0),
+    received_quantity  INTEGER NOT NULL DEFAULT 0,
+
+    UNIQUE(po_id, upc),
+    FOREIGN KEY(po_id) REFERENCES purchase_orders(id) ON DELETE CASCADE,
+    FOREIGN KEY(upc) REFERENCES products(upc)
+);
+
+ALTER TABLE inventory_transactions ADD COLUMN po_id INTEGER REFERENCES purchase_orders(id);
+CREATE INDEX IF NOT EXISTS idx_inventory_transactions_po_id ON inventory_transactions(po_id);
@@ -0,0 +1,6 @@
+-- what the canteen pays per unit, in cents. products.unit_cost is the moving
+-- average over receipts; receipts record what that delivery cost, and each sale
+-- line keeps the average at the time so later receipts don't rewrite its margin
+ALTER TABLE products ADD COLUMN unit_cost INTEGER CHECK (unit_cost >= 0);
+ALTER TABLE inventory_transactions ADD COLUMN unit_cost INTEGER CHECK (unit_cost >= 0);
+ALTER TABLE customer_tx_detail ADD COLUMN cost INTEGER;
@@ -0,0 +1,31 @@
+-- a physical count; expected quantities are frozen when the session opens
+CREATE TABLE IF NOT EXISTS stock_takes (
+    id             INTEGER PRIMARY KEY AUTOINCREMENT,
+    category       TEXT,              -- NULL counts every product
+    blind          INTEGER NOT NULL DEFAULT 0, -- counters aren't shown expected quantities
+    status         TEXT    NOT NULL DEFAULT 'open'
+                   CHECK (status IN ('open', 'posted', 'cancelled')),
+    note           TEXT,
+    operator_mdoc  INTEGER NOT NULL,
+    created_at     DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    posted_at      DATETIME,
+
+    FOREIGN KEY(operator_mdoc) REFERENCES operators(mdoc)
+);
+
+-- counted_quantity stays NULL until the product has been counted
+CREATE TABLE IF NOT EXISTS stock_take_lines (
+    id                 INTEGER PRIMARY KEY AUTOINCREMENT,
+    stock_take_id      INTEGER NOT NULL,
+    upc                TEXT    NOT NULL,
+    expected_quantity  INTEGER NOT NULL,
+    counted_quantity   INTEGER CHECK (counted_quantity >= 0),
+    counted_at         DATETIME,
+
+    UNIQUE(stock_take_id, upc),
+    FOREIGN KEY(stock_take_id) REFERENCES stock_takes(id) ON DELETE CASCADE,
+    FOREIGN KEY(upc) REFERENCES products(upc)
+);
+
+ALTER TABLE inventory_transactions ADD COLUMN stock_take_id INTEGER REFERENCES stock_takes(id);
+CREATE INDEX IF NOT EXISTS idx_inventory_transactions_stock_take_id ON inventory_transactions(stock_take_id);
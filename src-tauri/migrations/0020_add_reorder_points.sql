@@ -0,0 +1,3 @@
+-- a product is low on stock once on-hand falls to its reorder point
+ALTER TABLE products ADD COLUMN reorder_point INTEGER CHECK (reorder_point >= 0);
+ALTER TABLE products ADD COLUMN reorder_quantity INTEGER CHECK (reorder_quantity > 0);
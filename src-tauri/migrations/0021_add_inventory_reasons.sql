@@ -0,0 +1,28 @@
+-- why stock moved; earlier rows are classified from what their reference says
+ALTER TABLE inventory_transactions ADD COLUMN reason TEXT NOT NULL DEFAULT 'other'
+    CHECK (reason IN ('receipt', 'sale', 'customer_return', 'void', 'shrinkage',
+                      'damage', 'expiry', 'vendor_return', 'count_correction', 'other'));
+
+UPDATE inventory_transactions SET reason = CASE
+    WHEN reference LIKE 'void #%'                           THEN 'void'
+    WHEN reference LIKE 'return #%'                         THEN 'customer_return'
+    WHEN customer_mdoc IS NOT NULL                          THEN 'sale'
+    WHEN po_id IS NOT NULL OR reference LIKE 'PO #%'        THEN 'receipt'
+    WHEN stock_take_id IS NOT NULL
+      OR reference LIKE 'Stock take #%'
+      OR reference LIKE '%count%'                           THEN 'count_correction'
+    WHEN reference LIKE '%damage%'
+      OR reference LIKE '%broken%'                          THEN 'damage'
+    WHEN reference LIKE '%expir%'                           THEN 'expiry'
+    WHEN reference LIKE '%vendor%'
+      OR reference LIKE '%return to%'                       THEN 'vendor_return'
+    WHEN reference LIKE '%shrink%'
+      OR reference LIKE '%theft%'
+      OR reference LIKE '%stolen%'
+      OR reference LIKE '%missing%'                         THEN 'shrinkage'
+    WHEN quantity_change > 0                                THEN 'receipt'
+    ELSE 'other'
+END;
+
+CREATE INDEX IF NOT EXISTS idx_inventory_transactions_reason
+    ON inventory_transactions(reason, created_at);
@@ -0,0 +1,8 @@
+-- single-row inventory settings; sales already refused to oversell, so
+-- blocking is the default for adjustments too
+CREATE TABLE IF NOT EXISTS inventory_settings (
+    negative_stock_policy TEXT NOT NULL DEFAULT 'block'
+        CHECK (negative_stock_policy IN ('block', 'warn', 'allow'))
+);
+
+INSERT INTO inventory_settings DEFAULT VALUES;
@@ -0,0 +1,20 @@
+-- a batch of one product identified by lot number and/or expiry date;
+-- what's left of it is the sum of the inventory rows that carry its id
+CREATE TABLE IF NOT EXISTS inventory_lots (
+    id          INTEGER PRIMARY KEY AUTOINCREMENT,
+    upc         TEXT    NOT NULL,
+    lot_number  TEXT,
+    expires_on  DATE,
+    created_at  DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+
+    CHECK (lot_number IS NOT NULL OR expires_on IS NOT NULL),
+    FOREIGN KEY(upc) REFERENCES products(upc)
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_inventory_lots_label
+    ON inventory_lots(upc, COALESCE(lot_number, ''), COALESCE(expires_on, ''));
+
+ALTER TABLE inventory_transactions ADD COLUMN lot_id INTEGER REFERENCES inventory_lots(id);
+
+CREATE INDEX IF NOT EXISTS idx_inventory_transactions_lot
+    ON inventory_transactions(lot_id);
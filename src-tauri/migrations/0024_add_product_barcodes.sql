@@ -0,0 +1,12 @@
+-- alternate barcodes for a product, e.g. a vendor's case code or a upc the
+-- product used to carry; scanning one stands for `multiplier` units
+CREATE TABLE IF NOT EXISTS product_barcodes (
+    barcode     TEXT    PRIMARY KEY,
+    upc         TEXT    NOT NULL,
+    multiplier  INTEGER NOT NULL DEFAULT 1 CHECK (multiplier >= 1),
+    created_at  DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+
+    FOREIGN KEY(upc) REFERENCES products(upc)
+);
+
+CREATE INDEX IF NOT EXISTS idx_product_barcodes_upc ON product_barcodes(upc);
@@ -0,0 +1,14 @@
+-- a product with components is a kit: selling it takes its components out of
+-- stock instead of itself. Kits aren't nested.
+CREATE TABLE IF NOT EXISTS product_components (
+    bundle_upc     TEXT    NOT NULL,
+    component_upc  TEXT    NOT NULL,
+    quantity       INTEGER NOT NULL CHECK (quantity > 0),
+
+    PRIMARY KEY(bundle_upc, component_upc),
+    CHECK (bundle_upc <> component_upc),
+    FOREIGN KEY(bundle_upc) REFERENCES products(upc),
+    FOREIGN KEY(component_upc) REFERENCES products(upc)
+);
+
+CREATE INDEX IF NOT EXISTS idx_product_components_component ON product_components(component_upc);
@@ -0,0 +1,9 @@
+-- price changes can be keyed ahead of time: a pending row carries the moment
+-- it takes effect and is applied to the product then; rows from before this
+-- migration were all applied on entry
+ALTER TABLE price_adjustments ADD COLUMN effective_at DATETIME;
+ALTER TABLE price_adjustments ADD COLUMN status TEXT NOT NULL DEFAULT 'applied'
+    CHECK (status IN ('pending', 'applied', 'cancelled'));
+
+CREATE INDEX IF NOT EXISTS idx_price_adjustments_pending
+    ON price_adjustments(status, effective_at);
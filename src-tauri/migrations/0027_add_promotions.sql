@@ -0,0 +1,31 @@
+-- a promotion takes money off matching sale lines while it runs. value is the
+-- percent off, the cents off each unit or the sale price, depending on kind;
+-- buy_get promotions use buy_quantity / get_quantity instead
+CREATE TABLE IF NOT EXISTS promotions (
+    id            INTEGER PRIMARY KEY AUTOINCREMENT,
+    name          TEXT    NOT NULL,
+    kind          TEXT    NOT NULL CHECK (kind IN ('percent_off', 'amount_off', 'sale_price', 'buy_get')),
+    value         INTEGER NOT NULL DEFAULT 0 CHECK (value >= 0),
+    buy_quantity  INTEGER CHECK (buy_quantity > 0),
+    get_quantity  INTEGER CHECK (get_quantity > 0),
+    upc           TEXT,
+    category      TEXT,
+    starts_at     DATETIME,
+    ends_at       DATETIME,
+    created_at    DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    deleted       DATETIME,
+
+    CHECK ((upc IS NULL) <> (category IS NULL)),
+    CHECK (starts_at IS NULL OR ends_at IS NULL OR starts_at < ends_at),
+    FOREIGN KEY(upc)
+        REFERENCES products(upc),
+    FOREIGN KEY(category)
+        REFERENCES categories(name)
+);
+
+CREATE INDEX IF NOT EXISTS idx_promotions_running ON promotions(deleted, ends_at);
+
+-- what a sale line's promotion took off the line, in cents. Reversal and
+-- return lines carry the negated share, so sums net out like quantity does
+ALTER TABLE customer_tx_detail ADD COLUMN discount INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE customer_tx_detail ADD COLUMN promotion_id INTEGER REFERENCES promotions(id);
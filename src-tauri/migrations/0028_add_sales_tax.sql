@@ -0,0 +1,34 @@
+-- a sales tax rate on a category, or on one product to override its category's
+-- (0 exempts it). rate is in hundredths of a percent, so 825 is 8.25%. A new
+-- rate for the same category or product replaces the old one from effective_at
+CREATE TABLE IF NOT EXISTS tax_rates (
+    id            INTEGER PRIMARY KEY AUTOINCREMENT,
+    upc           TEXT,
+    category      TEXT,
+    rate          INTEGER NOT NULL CHECK (rate BETWEEN 0 AND 10000),
+    effective_at  DATETIME,
+    created_at    DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    deleted       DATETIME,
+
+    CHECK ((upc IS NULL) <> (category IS NULL)),
+    FOREIGN KEY(upc)
+        REFERENCES products(upc),
+    FOREIGN KEY(category)
+        REFERENCES categories(name)
+);
+
+CREATE INDEX IF NOT EXISTS idx_tax_rates_upc ON tax_rates(upc, deleted);
+CREATE INDEX IF NOT EXISTS idx_tax_rates_category ON tax_rates(category, deleted);
+
+-- single-row tax settings; tax always comes out of the balance, and counts
+-- toward the spending limit unless turned off here
+CREATE TABLE IF NOT EXISTS tax_settings (
+    counts_toward_limit INTEGER NOT NULL DEFAULT 1 CHECK (counts_toward_limit IN (0, 1))
+);
+
+INSERT INTO tax_settings DEFAULT VALUES;
+
+-- the tax charged on a sale line, in cents, and the rate it was charged at
+-- (NULL when untaxed). Reversal and return lines carry the negated share
+ALTER TABLE customer_tx_detail ADD COLUMN tax INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE customer_tx_detail ADD COLUMN tax_rate INTEGER;
@@ -0,0 +1,4 @@
+-- the customer's balance right after the order, captured when its receipt is
+-- queued so a delayed print or a reprint shows the balance as of the sale;
+-- jobs from before this migration have none
+ALTER TABLE print_jobs ADD COLUMN balance INTEGER;
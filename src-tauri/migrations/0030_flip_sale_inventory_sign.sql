@@ -0,0 +1,6 @@
+-- sales now take stock off with a negative quantity_change, like every other
+-- outflow; rows written before this were positive, as were the void rows that
+-- reversed them as negatives, so both are flipped to the new convention
+UPDATE inventory_transactions SET quantity_change = -quantity_change
+ WHERE (reason = 'sale' AND quantity_change > 0)
+    OR (reason = 'void' AND quantity_change < 0);
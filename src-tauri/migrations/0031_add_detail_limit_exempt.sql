@@ -0,0 +1,11 @@
+-- whether a sale line was kept off the spending limit, fixed when it's sold so
+-- a later change to the product or category flag doesn't rewrite past spending
+-- or receipts; earlier lines take the flags as they stand now
+ALTER TABLE customer_tx_detail ADD COLUMN limit_exempt INTEGER NOT NULL DEFAULT 0;
+
+UPDATE customer_tx_detail SET limit_exempt = COALESCE((
+    SELECT COALESCE(p.limit_exempt, c.limit_exempt, 0)
+    FROM products p
+    LEFT JOIN categories c ON c.name = p.category
+    WHERE p.upc = customer_tx_detail.upc
+), 0);
@@ -0,0 +1,9 @@
+-- the name of the promotion behind a sale line, fixed when it's sold so a
+-- renamed or deleted promotion doesn't change the line on a reprint; earlier
+-- lines take the name as it stands now
+ALTER TABLE customer_tx_detail ADD COLUMN promotion_name TEXT;
+
+UPDATE customer_tx_detail SET promotion_name = (
+    SELECT name FROM promotions WHERE id = customer_tx_detail.promotion_id
+)
+WHERE promotion_id IS NOT NULL;
@@ -0,0 +1,4 @@
+-- tier and override limits apply to whatever period the limit settings use,
+-- not only a week
+ALTER TABLE customer_tiers RENAME COLUMN weekly_limit TO limit_amount;
+ALTER TABLE customer_limit_overrides RENAME COLUMN weekly_limit TO limit_amount;
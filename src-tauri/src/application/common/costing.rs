@@ -0,0 +1,19 @@
+use crate::common::error::AppError;
+use crate::domain::repos::{InventoryTransactionRepoTrait, ProductRepoTrait};
+
+// Folds `quantity` units received at `unit_cost` into the product's average
+// cost. Must run before the receipt's inventory row so on-hand excludes it.
+pub fn apply_receipt_cost_with_tx(
+    prod_repo: &dyn ProductRepoTrait,
+    inv_repo: &dyn InventoryTransactionRepoTrait,
+    upc: &str,
+    quantity: i32,
+    unit_cost: i32,
+    tx: &rusqlite::Transaction<'_>,
+) -> Result<(), AppError> {
+    let product = prod_repo
+        .get_by_upc_with_tx(upc.to_string(), tx)?
+        .ok_or_else(|| AppError::NotFound(format!("product {upc} not found")))?;
+    let on_hand = inv_repo.get_available_with_tx(upc.to_string(), tx)?;
+    prod_repo.set_unit_cost_with_tx(upc, product.averaged_cost(on_hand, quantity, unit_cost), tx)
+}
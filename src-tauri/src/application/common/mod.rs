@@ -1 +1,3 @@
+pub mod costing;
 pub mod db;
+pub mod pricing;
@@ -0,0 +1,118 @@
+use crate::common::error::AppError;
+use crate::domain::models::{PriceAdjustment, PriceAdjustmentStatus};
+use crate::domain::repos::{PriceAdjustmentRepoTrait, ProductRepoTrait};
+use chrono::NaiveDateTime;
+use log::info;
+
+// Sets each product's price from the scheduled changes due by `now`, in
+// effective order, keeping the price each one replaced as its `old`.
+pub fn apply_due_price_changes_with_tx(
+    price_repo: &dyn PriceAdjustmentRepoTrait,
+    prod_repo: &dyn ProductRepoTrait,
+    now: NaiveDateTime,
+    tx: &rusqlite::Transaction<'_>,
+) -> Result<Vec<PriceAdjustment>, AppError> {
+    let mut applied = price_repo.due_with_tx(now, tx)?;
+    for a in &mut applied {
+        // a change for a product that's gone is closed without a price to set
+        if let Some(product) = prod_repo.get_by_upc_with_tx(a.upc.clone(), tx)? {
+            a.old = product.price;
+            prod_repo.set_price_with_tx(&a.upc, a.new, now, tx)?;
+        }
+        price_repo.mark_applied_with_tx(a.id, a.old, tx)?;
+        a.status = PriceAdjustmentStatus::Applied;
+        info!(
+            "scheduled price change applied: id={} upc={} old={} new={}",
+            a.id, a.upc, a.old, a.new
+        );
+    }
+    Ok(applied)
+}
+
+// Sets a product's price now. Changes still scheduled for it are cancelled,
+// so none of them later overwrites the price set here.
+pub fn set_price_now_with_tx(
+    price_repo: &dyn PriceAdjustmentRepoTrait,
+    prod_repo: &dyn ProductRepoTrait,
+    upc: &str,
+    price: i32,
+    now: NaiveDateTime,
+    tx: &rusqlite::Transaction<'_>,
+) -> Result<(), AppError> {
+    supersede_pending_with_tx(price_repo, upc, tx)?;
+    prod_repo.set_price_with_tx(upc, price, now, tx)
+}
+
+// Cancels the scheduled changes for `upc` once its price has been set by hand.
+pub fn supersede_pending_with_tx(
+    price_repo: &dyn PriceAdjustmentRepoTrait,
+    upc: &str,
+    tx: &rusqlite::Transaction<'_>,
+) -> Result<(), AppError> {
+    for id in price_repo.cancel_pending_for_upc_with_tx(upc, tx)? {
+        info!("scheduled price change superseded: id={id} upc={upc}");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::db::create_connection;
+    use crate::infrastructure::repos::{SqlitePriceAdjustmentRepo, SqliteProductRepo};
+    use std::sync::Arc;
+
+    fn dt(s: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S").unwrap()
+    }
+
+    #[test]
+    fn due_changes_set_the_price_in_effective_order() -> anyhow::Result<()> {
+        let conn = Arc::new(create_connection(":memory:")?);
+        conn.lock().unwrap().execute_batch(
+            "INSERT INTO operators (mdoc, name, start) VALUES (1,'Op','2025-01-01');
+             INSERT INTO products (upc, desc, category, price, updated, added) VALUES
+               ('000000000100', 'Soup', 'Food', 125, '2025-01-01 00:00:00', '2025-01-01 00:00:00');",
+        )?;
+        let price_repo = SqlitePriceAdjustmentRepo::new(Arc::clone(&conn));
+        let prod_repo = SqliteProductRepo::new(Arc::clone(&conn));
+        for (new, at) in [(150, "2025-03-01 12:00:00"), (140, "2025-02-01 12:00:00")] {
+            price_repo.create(&PriceAdjustment {
+                id: 0,
+                operator_mdoc: 1,
+                upc: "000000000100".into(),
+                old: 125,
+                new,
+                created_at: None,
+                effective_at: Some(dt(at)),
+                status: PriceAdjustmentStatus::Pending,
+            })?;
+        }
+        let apply = |at: &str| -> Result<Vec<PriceAdjustment>, AppError> {
+            let mut guard = conn.lock().unwrap();
+            let tx = guard.transaction()?;
+            let applied = apply_due_price_changes_with_tx(&price_repo, &prod_repo, dt(at), &tx)?;
+            tx.commit()?;
+            Ok(applied)
+        };
+
+        assert!(apply("2025-01-15 00:00:00")?.is_empty());
+        let moves: Vec<_> = apply("2025-04-01 00:00:00")?
+            .iter()
+            .map(|a| (a.old, a.new, a.status))
+            .collect();
+        assert_eq!(
+            moves,
+            vec![
+                (125, 140, PriceAdjustmentStatus::Applied),
+                (140, 150, PriceAdjustmentStatus::Applied)
+            ]
+        );
+        let soup = prod_repo.get_by_upc("000000000100".into())?.unwrap();
+        assert_eq!(soup.price, 150);
+        assert_eq!(soup.updated, Some(dt("2025-04-01 00:00:00")));
+        assert_eq!(soup.desc, "Soup");
+        assert!(apply("2025-07-01 00:00:00")?.is_empty());
+        Ok(())
+    }
+}
@@ -1,8 +1,8 @@
 use crate::common::error::AppError;
 use crate::domain::models::club_transaction::TransactionType;
 use crate::domain::models::{
-    ClubImport, ClubTransaction, Customer, CustomerTransaction, CustomerTxDetail,
-    InventoryTransaction, Operator, Product,
+    ClubImport, ClubTransaction, Customer, CustomerTransaction, CustomerTxDetail, InventoryReason,
+    InventoryTransaction, Operator, OrderReason, Product,
 };
 use crate::domain::repos::{
     CategoryRepoTrait, ClubImportRepoTrait, ClubTransactionRepoTrait, CustomerRepoTrait,
@@ -508,6 +508,10 @@ impl LegacyMigrationUseCases {
                 updated: Some(updated),
                 added: Some(added),
                 deleted,
+                limit_exempt: None,
+                unit_cost: None,
+                reorder_point: None,
+                reorder_quantity: None,
             };
             if let Err(e) = self.deps.product_repo.create(&prod) {
                 warn!("skip product: insert error: {e}");
@@ -1065,6 +1069,36 @@ impl LegacyMigrationUseCases {
         self.migrate_inventory_transactions_from_rows(raws)
     }
 
+    // Classifies a legacy adjustment by its reference text, the same way
+    // migration 0021 classified rows already in the table.
+    pub(crate) fn legacy_inventory_reason(
+        reference: Option<&str>,
+        quantity_change: i32,
+        has_customer: bool,
+    ) -> InventoryReason {
+        let r = reference.unwrap_or("").to_lowercase();
+        let has = |words: &[&str]| words.iter().any(|w| r.contains(w));
+        if has_customer {
+            InventoryReason::Sale
+        } else if r.starts_with("po #") {
+            InventoryReason::Receipt
+        } else if has(&["count"]) {
+            InventoryReason::CountCorrection
+        } else if has(&["damage", "broken"]) {
+            InventoryReason::Damage
+        } else if has(&["expir"]) {
+            InventoryReason::Expiry
+        } else if has(&["vendor", "return to"]) {
+            InventoryReason::VendorReturn
+        } else if has(&["shrink", "theft", "stolen", "missing"]) {
+            InventoryReason::Shrinkage
+        } else if quantity_change > 0 {
+            InventoryReason::Receipt
+        } else {
+            InventoryReason::Other
+        }
+    }
+
     // Testable logic for inventory migrations
     pub(crate) fn migrate_inventory_transactions_from_rows<I>(
         &self,
@@ -1114,6 +1148,11 @@ impl LegacyMigrationUseCases {
             let operator_mdoc = op_opt.unwrap_or(0);
             // customer_mdoc optional
             let customer_mdoc = cust_opt.filter(|&i| i > 0);
+            let reason = Self::legacy_inventory_reason(
+                reference.as_deref(),
+                quantity_change,
+                customer_mdoc.is_some(),
+            );
 
             // finally persist
             let itx = InventoryTransaction {
@@ -1123,6 +1162,11 @@ impl LegacyMigrationUseCases {
                 upc,
                 quantity_change,
                 created_at: Some(created_at),
+                po_id: None,
+                unit_cost: None,
+                stock_take_id: None,
+                reason,
+                lot_id: None,
                 customer_mdoc,
                 operator_mdoc,
             };
@@ -1274,6 +1318,8 @@ impl LegacyMigrationUseCases {
                 operator_mdoc,
                 date: Some(date),
                 note,
+                idempotency_key: None,
+                reason: OrderReason::Regular,
             };
 
             if let Err(e) = self.deps.customer_transaction_repo.create(&tx) {
@@ -1427,6 +1473,13 @@ impl LegacyMigrationUseCases {
                 upc,
                 quantity,
                 price: price_cents,
+                cost: None, // the legacy system kept no costs
+                discount: 0,
+                promotion_id: None,
+                promotion_name: None,
+                tax: 0,
+                tax_rate: None,
+                limit_exempt: false,
             };
 
             if let Err(e) = self.deps.cust_tx_detail_repo.create(&detail) {
@@ -2295,6 +2348,7 @@ mod tests {
         assert_eq!(tx.created_at, Some(dt));
         assert_eq!(tx.customer_mdoc, Some(7));
         assert_eq!(tx.operator_mdoc, 8);
+        assert_eq!(tx.reason, InventoryReason::Sale);
     }
 
     #[test]
@@ -2354,6 +2408,27 @@ mod tests {
         assert!(list[2].reference.is_none());
     }
 
+    #[test]
+    fn inv_reason_classified_from_reference() {
+        let reason = LegacyMigrationUseCases::legacy_inventory_reason;
+        assert_eq!(
+            reason(Some("Damaged in shipping"), -2, false),
+            InventoryReason::Damage
+        );
+        assert_eq!(reason(Some("EXPIRED"), -1, false), InventoryReason::Expiry);
+        assert_eq!(
+            reason(Some("recount"), 3, false),
+            InventoryReason::CountCorrection
+        );
+        assert_eq!(
+            reason(Some("stolen"), -1, false),
+            InventoryReason::Shrinkage
+        );
+        assert_eq!(reason(Some("truck"), 24, false), InventoryReason::Receipt);
+        assert_eq!(reason(None, -1, false), InventoryReason::Other);
+        assert_eq!(reason(Some("damaged"), -1, true), InventoryReason::Sale);
+    }
+
     #[test]
     fn inv_skip_invalid_upc_or_amount() {
         let uc = make_usecase();
@@ -0,0 +1,561 @@
+use crate::common::error::AppError;
+use crate::domain::models::{
+    ActiveQuantityLimit, CustomerTier, ItemAllowance, LimitOverride, LimitSettings, LimitSource,
+    LimitWindow, Product, QuantityAllowance, QuantityLimit, QuantityWindow, ResolvedLimit,
+};
+use crate::domain::repos::{CustomerLimitRepoTrait, QuantityLimitRepoTrait, WeeklyLimitRepoTrait};
+use crate::try_log;
+use chrono::{NaiveDate, NaiveDateTime, Utc};
+use log::info;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+// Decides which weekly limit applies to a customer: an active individual
+// override wins, then the customer's tier, then the store-wide default.
+// Also owns the per-product / per-category quantity limits.
+pub struct LimitUseCases {
+    limit_repo: Arc<dyn WeeklyLimitRepoTrait>,
+    customer_limit_repo: Arc<dyn CustomerLimitRepoTrait>,
+    quantity_limit_repo: Arc<dyn QuantityLimitRepoTrait>,
+}
+
+impl LimitUseCases {
+    pub fn new(
+        limit_repo: Arc<dyn WeeklyLimitRepoTrait>,
+        customer_limit_repo: Arc<dyn CustomerLimitRepoTrait>,
+        quantity_limit_repo: Arc<dyn QuantityLimitRepoTrait>,
+    ) -> Self {
+        Self {
+            limit_repo,
+            customer_limit_repo,
+            quantity_limit_repo,
+        }
+    }
+
+    pub fn resolve(&self, customer_mdoc: i32, on: NaiveDate) -> Result<ResolvedLimit, AppError> {
+        let tier = try_log!(
+            self.customer_limit_repo.get_customer_tier(customer_mdoc),
+            "LimitUseCases::resolve"
+        );
+        if let Some(o) = try_log!(
+            self.customer_limit_repo.active_override(customer_mdoc, on),
+            "LimitUseCases::resolve"
+        ) {
+            return Ok(ResolvedLimit {
+                amount: o.limit_amount,
+                source: LimitSource::Override,
+                tier,
+                limit_override: Some(o),
+            });
+        }
+        if let Some(t) = tier {
+            return Ok(ResolvedLimit {
+                amount: t.limit_amount,
+                source: LimitSource::Tier,
+                tier: Some(t),
+                limit_override: None,
+            });
+        }
+        Ok(ResolvedLimit {
+            amount: self.get_default_limit()?,
+            source: LimitSource::Default,
+            tier: None,
+            limit_override: None,
+        })
+    }
+
+    pub fn get_default_limit(&self) -> Result<i32, AppError> {
+        let res = try_log!(
+            self.limit_repo.get_limit(),
+            "LimitUseCases::get_default_limit"
+        );
+        Ok(res)
+    }
+
+    pub fn set_default_limit(&self, limit: i32) -> Result<(), AppError> {
+        try_log!(
+            self.limit_repo.set_limit(limit),
+            "LimitUseCases::set_default_limit"
+        );
+        Ok(())
+    }
+
+    pub fn get_settings(&self) -> Result<LimitSettings, AppError> {
+        let res = try_log!(
+            self.limit_repo.get_settings(),
+            "LimitUseCases::get_settings"
+        );
+        Ok(res)
+    }
+
+    pub fn set_settings(&self, settings: &LimitSettings) -> Result<(), AppError> {
+        // reject unknown zones before they can break every sale
+        settings.tz()?;
+        try_log!(
+            self.limit_repo.set_settings(settings),
+            "LimitUseCases::set_settings"
+        );
+        info!(
+            "limit settings updated: period={} week_start={} anchor={} tz={}",
+            settings.period.as_str(),
+            settings.week_start,
+            settings.anchor_date,
+            settings.time_zone
+        );
+        Ok(())
+    }
+
+    // The limit period we are in right now, per the configured settings.
+    pub fn current_window(&self) -> Result<LimitWindow, AppError> {
+        self.window_at(Utc::now().naive_utc())
+    }
+
+    pub fn window_at(&self, now: NaiveDateTime) -> Result<LimitWindow, AppError> {
+        let settings = self.get_settings()?;
+        let res = try_log!(settings.window_at(now), "LimitUseCases::window_at");
+        Ok(res)
+    }
+
+    pub fn list_tiers(&self) -> Result<Vec<CustomerTier>, AppError> {
+        let res = try_log!(
+            self.customer_limit_repo.list_tiers(),
+            "LimitUseCases::list_tiers"
+        );
+        Ok(res)
+    }
+
+    pub fn create_tier(&self, tier: &CustomerTier) -> Result<i32, AppError> {
+        let id = try_log!(
+            self.customer_limit_repo.create_tier(tier),
+            "LimitUseCases::create_tier"
+        );
+        info!(
+            "customer tier created: id={} name={} limit={}",
+            id, tier.name, tier.limit_amount
+        );
+        Ok(id)
+    }
+
+    pub fn update_tier(&self, tier: &CustomerTier) -> Result<(), AppError> {
+        try_log!(
+            self.customer_limit_repo.update_tier(tier),
+            "LimitUseCases::update_tier"
+        );
+        info!(
+            "customer tier updated: id={} name={} limit={}",
+            tier.id, tier.name, tier.limit_amount
+        );
+        Ok(())
+    }
+
+    pub fn delete_tier(&self, id: i32) -> Result<(), AppError> {
+        try_log!(
+            self.customer_limit_repo.delete_tier(id),
+            "LimitUseCases::delete_tier"
+        );
+        info!("customer tier deleted: id={id}");
+        Ok(())
+    }
+
+    pub fn set_customer_tier(
+        &self,
+        customer_mdoc: i32,
+        tier_id: Option<i32>,
+    ) -> Result<(), AppError> {
+        if let Some(id) = tier_id {
+            try_log!(
+                self.customer_limit_repo.get_tier(id),
+                "LimitUseCases::set_customer_tier"
+            )
+            .ok_or_else(|| AppError::NotFound(format!("tier {id} not found")))?;
+        }
+        try_log!(
+            self.customer_limit_repo
+                .set_customer_tier(customer_mdoc, tier_id),
+            "LimitUseCases::set_customer_tier"
+        );
+        info!("customer tier set: customer={customer_mdoc} tier={tier_id:?}");
+        Ok(())
+    }
+
+    pub fn list_overrides(&self, customer_mdoc: i32) -> Result<Vec<LimitOverride>, AppError> {
+        let res = try_log!(
+            self.customer_limit_repo.list_overrides(customer_mdoc),
+            "LimitUseCases::list_overrides"
+        );
+        Ok(res)
+    }
+
+    pub fn create_override(&self, mut o: LimitOverride) -> Result<i32, AppError> {
+        o.reason = o.reason.trim().to_string();
+        if o.reason.is_empty() {
+            return Err(AppError::Validation(
+                "a reason is required for a limit override".into(),
+            ));
+        }
+        if o.ends_on.is_some_and(|end| end < o.starts_on) {
+            return Err(AppError::Validation(
+                "override end date is before its start date".into(),
+            ));
+        }
+        let id = try_log!(
+            self.customer_limit_repo.create_override(&o),
+            "LimitUseCases::create_override"
+        );
+        info!(
+            "limit override created: id={} customer={} limit={} from={} to={:?} operator={}",
+            id, o.customer_mdoc, o.limit_amount, o.starts_on, o.ends_on, o.operator_mdoc
+        );
+        Ok(id)
+    }
+
+    pub fn delete_override(&self, id: i32) -> Result<(), AppError> {
+        try_log!(
+            self.customer_limit_repo.delete_override(id),
+            "LimitUseCases::delete_override"
+        );
+        info!("limit override deleted: id={id}");
+        Ok(())
+    }
+
+    pub fn list_quantity_limits(&self) -> Result<Vec<QuantityLimit>, AppError> {
+        let res = try_log!(
+            self.quantity_limit_repo.list(),
+            "LimitUseCases::list_quantity_limits"
+        );
+        Ok(res)
+    }
+
+    pub fn create_quantity_limit(&self, mut limit: QuantityLimit) -> Result<i32, AppError> {
+        limit.upc = limit
+            .upc
+            .map(|u| u.trim().to_string())
+            .filter(|u| !u.is_empty());
+        limit.category = limit
+            .category
+            .map(|c| c.trim().to_string())
+            .filter(|c| !c.is_empty());
+        if limit.upc.is_some() == limit.category.is_some() {
+            return Err(AppError::Validation(
+                "a quantity limit applies to either a upc or a category".into(),
+            ));
+        }
+        if limit.max_quantity < 0 {
+            return Err(AppError::Validation(
+                "max quantity cannot be negative".into(),
+            ));
+        }
+        if let QuantityWindow::Rolling(days) = limit.window {
+            if days <= 0 {
+                return Err(AppError::Validation(
+                    "a rolling window needs a positive number of days".into(),
+                ));
+            }
+        }
+        let id = try_log!(
+            self.quantity_limit_repo.create(&limit),
+            "LimitUseCases::create_quantity_limit"
+        );
+        info!(
+            "quantity limit created: id={} target={} max={} window={}",
+            id,
+            limit.target(),
+            limit.max_quantity,
+            limit.window.describe()
+        );
+        Ok(id)
+    }
+
+    pub fn delete_quantity_limit(&self, id: i32) -> Result<(), AppError> {
+        try_log!(
+            self.quantity_limit_repo.delete(id),
+            "LimitUseCases::delete_quantity_limit"
+        );
+        info!("quantity limit deleted: id={id}");
+        Ok(())
+    }
+
+    // Every quantity limit with its window resolved at `now`. Called before a
+    // sale's atomic_tx, since reading rules and settings takes the connection lock.
+    pub fn active_quantity_limits(
+        &self,
+        now: NaiveDateTime,
+    ) -> Result<Vec<ActiveQuantityLimit>, AppError> {
+        let limits = self.list_quantity_limits()?;
+        if limits.is_empty() {
+            return Ok(vec![]);
+        }
+        let settings = self.get_settings()?;
+        limits
+            .into_iter()
+            .map(|limit| {
+                let (start, end) = limit.window.bounds(&settings, now)?;
+                Ok(ActiveQuantityLimit { limit, start, end })
+            })
+            .collect()
+    }
+
+    // Rejects an order that would take the customer past any quantity limit.
+    // `lines` holds each product once with the total quantity requested.
+    pub fn check_quantities_with_tx(
+        &self,
+        customer_mdoc: i32,
+        limits: &[ActiveQuantityLimit],
+        lines: &[(Product, i32)],
+        tx: &rusqlite::Transaction<'_>,
+    ) -> Result<(), AppError> {
+        for active in limits {
+            let requested: i32 = lines
+                .iter()
+                .filter(|(p, _)| active.limit.applies_to(p))
+                .map(|(_, q)| q)
+                .sum();
+            if requested == 0 {
+                continue;
+            }
+            let purchased = self.quantity_limit_repo.purchased_with_tx(
+                customer_mdoc,
+                &active.limit,
+                active.start,
+                active.end,
+                tx,
+            )?;
+            if purchased + requested > active.limit.max_quantity {
+                return Err(AppError::Validation(format!(
+                    "quantity limit exceeded for {}: {} {}, already bought {purchased}, requested {requested}",
+                    active.limit.target(),
+                    active.limit.max_quantity,
+                    active.limit.window.describe()
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    // How many more of each product the customer may buy right now.
+    pub fn item_allowances(
+        &self,
+        customer_mdoc: i32,
+        products: &[Product],
+    ) -> Result<Vec<ItemAllowance>, AppError> {
+        let limits = self.active_quantity_limits(Utc::now().naive_utc())?;
+        let mut purchased: HashMap<i32, i32> = HashMap::new();
+        let mut res = Vec::with_capacity(products.len());
+        for product in products {
+            let mut allowances = vec![];
+            for active in limits.iter().filter(|a| a.limit.applies_to(product)) {
+                let bought = match purchased.get(&active.limit.id) {
+                    Some(n) => *n,
+                    None => {
+                        let n = try_log!(
+                            self.quantity_limit_repo.purchased(
+                                customer_mdoc,
+                                &active.limit,
+                                active.start,
+                                active.end
+                            ),
+                            "LimitUseCases::item_allowances"
+                        );
+                        purchased.insert(active.limit.id, n);
+                        n
+                    }
+                };
+                allowances.push(QuantityAllowance {
+                    limit: active.limit.clone(),
+                    purchased: bought,
+                    remaining: (active.limit.max_quantity - bought).max(0),
+                    window_start: active.start,
+                    window_end: active.end,
+                });
+            }
+            res.push(ItemAllowance {
+                upc: product.upc.clone(),
+                remaining: allowances.iter().map(|a| a.remaining).min(),
+                limits: allowances,
+            });
+        }
+        Ok(res)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::models::LimitPeriod;
+    use crate::test_support::mock_customer_limit_repo::MockCustomerLimitRepo;
+    use crate::test_support::mock_quantity_limit_repo::MockQuantityLimitRepo;
+    use crate::test_support::mock_weekly_limit_repo::MockWeeklyLimitRepo;
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    fn make_usecase() -> LimitUseCases {
+        let limit_repo = Arc::new(MockWeeklyLimitRepo::new());
+        limit_repo.set_limit(20000).unwrap();
+        LimitUseCases::new(
+            limit_repo,
+            Arc::new(MockCustomerLimitRepo::new()),
+            Arc::new(MockQuantityLimitRepo::new()),
+        )
+    }
+
+    fn limit_override(limit_amount: i32, starts_on: &str, ends_on: Option<&str>) -> LimitOverride {
+        LimitOverride {
+            id: 0,
+            customer_mdoc: 7,
+            limit_amount,
+            starts_on: date(starts_on),
+            ends_on: ends_on.map(date),
+            reason: "court order".into(),
+            operator_mdoc: 1,
+            created_at: None,
+        }
+    }
+
+    #[test]
+    fn override_beats_tier_beats_default() -> Result<(), AppError> {
+        let uc = make_usecase();
+        let on = date("2025-03-05");
+        let default = uc.resolve(7, on)?;
+        assert_eq!(
+            (default.amount, default.source),
+            (20000, LimitSource::Default)
+        );
+
+        let tier = uc.create_tier(&CustomerTier {
+            id: 0,
+            name: "Restricted housing".into(),
+            limit_amount: 5000,
+        })?;
+        uc.set_customer_tier(7, Some(tier))?;
+        let tiered = uc.resolve(7, on)?;
+        assert_eq!((tiered.amount, tiered.source), (5000, LimitSource::Tier));
+        // other customers are unaffected
+        assert_eq!(uc.resolve(8, on)?.source, LimitSource::Default);
+
+        uc.create_override(limit_override(9000, "2025-03-01", Some("2025-03-07")))?;
+        let overridden = uc.resolve(7, on)?;
+        assert_eq!(
+            (overridden.amount, overridden.source),
+            (9000, LimitSource::Override)
+        );
+        assert_eq!(overridden.tier.map(|t| t.id), Some(tier));
+
+        // outside the override window the tier applies again
+        assert_eq!(uc.resolve(7, date("2025-03-08"))?.amount, 5000);
+        uc.set_customer_tier(7, None)?;
+        assert_eq!(uc.resolve(7, date("2025-03-08"))?.amount, 20000);
+        Ok(())
+    }
+
+    #[test]
+    fn settings_reject_unknown_time_zones() -> Result<(), AppError> {
+        let uc = make_usecase();
+        let mut settings = uc.get_settings()?;
+        settings.time_zone = "Narnia/Cair_Paravel".into();
+        assert!(matches!(
+            uc.set_settings(&settings),
+            Err(AppError::Validation(_))
+        ));
+
+        settings.time_zone = "America/Chicago".into();
+        settings.period = LimitPeriod::Daily;
+        uc.set_settings(&settings)?;
+        let now = NaiveDate::from_ymd_opt(2025, 6, 15)
+            .unwrap()
+            .and_hms_opt(3, 30, 0)
+            .unwrap();
+        // 22:30 the previous evening in Chicago
+        assert_eq!(uc.window_at(now)?.local_date, date("2025-06-14"));
+        Ok(())
+    }
+
+    #[test]
+    fn override_and_tier_input_is_checked() {
+        let uc = make_usecase();
+        let mut blank = limit_override(100, "2025-03-01", None);
+        blank.reason = "  ".into();
+        assert!(matches!(
+            uc.create_override(blank),
+            Err(AppError::Validation(_))
+        ));
+        assert!(matches!(
+            uc.create_override(limit_override(100, "2025-03-02", Some("2025-03-01"))),
+            Err(AppError::Validation(_))
+        ));
+        assert!(matches!(
+            uc.set_customer_tier(7, Some(42)),
+            Err(AppError::NotFound(_))
+        ));
+    }
+
+    #[test]
+    fn item_allowances_report_the_tightest_limit() -> Result<(), AppError> {
+        let quantities = Arc::new(MockQuantityLimitRepo::new());
+        let uc = LimitUseCases::new(
+            Arc::new(MockWeeklyLimitRepo::new()),
+            Arc::new(MockCustomerLimitRepo::new()),
+            quantities.clone(),
+        );
+        let soap = Product {
+            upc: "SOAP".into(),
+            desc: "Soap".into(),
+            category: "Hygiene".into(),
+            price: 100,
+            updated: None,
+            added: None,
+            deleted: None,
+            limit_exempt: None,
+            unit_cost: None,
+            reorder_point: None,
+            reorder_quantity: None,
+        };
+        let chips = Product {
+            upc: "CHIPS".into(),
+            category: "Snacks".into(),
+            ..soap.clone()
+        };
+        let hygiene = QuantityLimit {
+            id: 0,
+            upc: None,
+            category: Some("Hygiene".into()),
+            max_quantity: 4,
+            window: QuantityWindow::Rolling(7),
+            note: None,
+            created_at: None,
+        };
+        uc.create_quantity_limit(hygiene.clone())?;
+        uc.create_quantity_limit(QuantityLimit {
+            upc: Some(" SOAP ".into()),
+            category: None,
+            max_quantity: 2,
+            ..hygiene.clone()
+        })?;
+        let now = Utc::now().naive_utc();
+        quantities.record_purchase(7, &soap, 1, now - chrono::Duration::days(1));
+        // outside the rolling window
+        quantities.record_purchase(7, &soap, 5, now - chrono::Duration::days(30));
+
+        let res = uc.item_allowances(7, &[soap, chips])?;
+        assert_eq!(res[0].limits.len(), 2);
+        assert_eq!(res[0].remaining, Some(1));
+        assert_eq!((res[1].upc.as_str(), res[1].remaining), ("CHIPS", None));
+
+        assert!(matches!(
+            uc.create_quantity_limit(QuantityLimit {
+                upc: Some("SOAP".into()),
+                ..hygiene.clone()
+            }),
+            Err(AppError::Validation(_))
+        ));
+        assert!(matches!(
+            uc.create_quantity_limit(QuantityLimit {
+                window: QuantityWindow::Rolling(0),
+                ..hygiene
+            }),
+            Err(AppError::Validation(_))
+        ));
+        Ok(())
+    }
+}
@@ -1,12 +1,18 @@
 pub mod auth_usecases;
 pub mod club_usecases;
 pub mod legacy_migration_usecases;
+pub mod limit_usecases;
 pub mod operator_usecases;
 pub mod pdf_parse_usecases;
 pub mod pos_usecases;
+pub mod print_queue_usecases;
 pub mod printer_usecases;
 pub mod product_usecases;
+pub mod promotion_usecases;
+pub mod purchasing_usecases;
 pub mod stats_usecases;
+pub mod stock_take_usecases;
+pub mod tax_usecases;
 pub mod transaction_usecases;
 
 pub use legacy_migration_usecases::LegacyMigrationDeps;
@@ -1,39 +1,66 @@
+use crate::application::common::db::atomic_tx;
+use crate::application::common::pricing::apply_due_price_changes_with_tx;
 use crate::common::error::AppError;
-use crate::domain::models::{Customer, Product};
-use crate::domain::repos::{CustomerRepoTrait, ProductRepoTrait};
+use crate::domain::models::{BarcodeAlias, Customer, Product};
+use crate::domain::repos::{CustomerRepoTrait, PriceAdjustmentRepoTrait, ProductRepoTrait};
 use crate::try_log;
-use std::sync::Arc;
+use chrono::Utc;
+use std::sync::{Arc, Mutex};
 
 pub struct PosInitData {
     pub products: Vec<Product>,
     pub customer_accounts: Vec<(Customer, i32)>,
+    pub barcodes: Vec<BarcodeAlias>, // scanned codes that aren't a product's own upc
 }
 
 pub struct PosUseCase {
     product_repo: Arc<dyn ProductRepoTrait>,
+    price_repo: Arc<dyn PriceAdjustmentRepoTrait>,
     customer_repo: Arc<dyn CustomerRepoTrait>,
+    conn: Arc<Mutex<rusqlite::Connection>>,
 }
 
 impl PosUseCase {
     pub fn new(
         product_repo: Arc<dyn ProductRepoTrait>,
+        price_repo: Arc<dyn PriceAdjustmentRepoTrait>,
         customer_repo: Arc<dyn CustomerRepoTrait>,
+        conn: Arc<Mutex<rusqlite::Connection>>,
     ) -> Self {
         Self {
             product_repo,
+            price_repo,
             customer_repo,
+            conn,
         }
     }
 
     pub fn get_pos_init_data(&self) -> Result<PosInitData, AppError> {
+        // the register loads its prices here, so due price changes go first
+        try_log!(
+            atomic_tx(&self.conn, |tx| {
+                apply_due_price_changes_with_tx(
+                    self.price_repo.as_ref(),
+                    self.product_repo.as_ref(),
+                    Utc::now().naive_utc(),
+                    tx,
+                )
+            }),
+            "PosUseCase::get_pos_init_data"
+        );
         let products = try_log!(self.product_repo.list(), "PosUseCase::get_pos_init_data");
         let customer_accounts = try_log!(
             self.customer_repo.list_customer_accounts(),
             "PosUseCase::get_pos_init_data"
         );
+        let barcodes = try_log!(
+            self.product_repo.list_barcodes(None),
+            "PosUseCase::get_pos_init_data"
+        );
         Ok(PosInitData {
             products,
             customer_accounts,
+            barcodes,
         })
     }
 }
@@ -0,0 +1,452 @@
+use crate::application::use_cases::printer_usecases::PrinterUseCases;
+use crate::common::error::AppError;
+use crate::domain::models::{PrintJob, PrintJobStatus};
+use crate::domain::repos::CustomerTransactionRepoTrait;
+use crate::domain::repos::OrderReturnRepoTrait;
+use crate::domain::repos::PrintJobRepoTrait;
+use crate::interface::dto::printer_dto::{PrintableSaleDto, ReceiptCopies, ReceiptKind};
+use crate::try_log;
+use log::{error, info, warn};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+// A job that fails this many times is parked as failed until an operator retries it.
+pub const MAX_PRINT_ATTEMPTS: i32 = 3;
+const LIST_LIMIT: i32 = 200;
+const WORKER_IDLE: Duration = Duration::from_secs(3);
+
+// Renders and sends the receipts for a queued job.
+pub trait ReceiptPrinter: Send + Sync {
+    fn print(&self, job: &PrintJob, printable: PrintableSaleDto) -> Result<(), AppError>;
+}
+
+impl ReceiptPrinter for PrinterUseCases {
+    fn print(&self, job: &PrintJob, printable: PrintableSaleDto) -> Result<(), AppError> {
+        let copies = ReceiptCopies::parse(&job.copies)?;
+        let printable = self.add_kit_components(printable)?;
+        let printable = self.add_promotion_names(printable)?;
+        match job.reprint_by {
+            Some(mdoc) => self.reprint_receipts(
+                printable,
+                copies,
+                mdoc,
+                &job.printer_name,
+                &job.sumatra_location,
+            ),
+            None => self.print_receipts(
+                &printable,
+                copies,
+                &job.printer_name,
+                &job.sumatra_location,
+                &job.operator_name,
+                &job.customer_name,
+            ),
+        }
+    }
+}
+
+pub struct PrintQueueUseCases {
+    job_repo: Arc<dyn PrintJobRepoTrait>,
+    cust_tx_repo: Arc<dyn CustomerTransactionRepoTrait>,
+    return_repo: Arc<dyn OrderReturnRepoTrait>,
+    printer: Arc<dyn ReceiptPrinter>,
+}
+
+impl PrintQueueUseCases {
+    pub fn new(
+        job_repo: Arc<dyn PrintJobRepoTrait>,
+        cust_tx_repo: Arc<dyn CustomerTransactionRepoTrait>,
+        return_repo: Arc<dyn OrderReturnRepoTrait>,
+        printer: Arc<dyn ReceiptPrinter>,
+    ) -> Self {
+        Self {
+            job_repo,
+            cust_tx_repo,
+            return_repo,
+            printer,
+        }
+    }
+
+    // Persist a pending job; the worker picks it up on its next pass. The
+    // customer's balance is captured now, right after the order, so a print
+    // that waits in the queue still shows it; a reprint reuses the original's.
+    pub fn enqueue(&self, mut job: PrintJob) -> Result<i32, AppError> {
+        ReceiptCopies::parse(&job.copies)?;
+        try_log!(
+            self.cust_tx_repo.get(job.order_id),
+            "PrintQueueUseCases::enqueue"
+        )
+        .ok_or_else(|| AppError::NotFound(format!("order {} not found", job.order_id)))?;
+        job.balance = if job.reprint_by.is_some() {
+            try_log!(
+                self.job_repo.sale_balance(job.order_id),
+                "PrintQueueUseCases::enqueue"
+            )
+        } else {
+            let (_, _, balance) = try_log!(
+                self.cust_tx_repo.get_with_details_and_balance(job.order_id),
+                "PrintQueueUseCases::enqueue"
+            );
+            Some(balance)
+        };
+
+        job.status = PrintJobStatus::Pending;
+        job.attempts = 0;
+        job.last_error = None;
+        let id = try_log!(self.job_repo.create(&job), "PrintQueueUseCases::enqueue");
+        info!(
+            "print job queued: id={} order={} copies={}",
+            id, job.order_id, job.copies
+        );
+        Ok(id)
+    }
+
+    // Queues an order's receipt unless it already was, so a replayed sale
+    // prints once. Returns the new job's id, or None if there was one.
+    pub fn enqueue_receipt(&self, job: PrintJob) -> Result<Option<i32>, AppError> {
+        if try_log!(
+            self.job_repo.has_receipt(job.order_id),
+            "PrintQueueUseCases::enqueue_receipt"
+        ) {
+            return Ok(None);
+        }
+        self.enqueue(job).map(Some)
+    }
+
+    // Claim and print the oldest pending job. Returns the job with its new
+    // status, or None when the queue is empty.
+    pub fn process_next(&self) -> Result<Option<PrintJob>, AppError> {
+        let Some(mut job) = try_log!(
+            self.job_repo.claim_next(),
+            "PrintQueueUseCases::process_next"
+        ) else {
+            return Ok(None);
+        };
+
+        match self
+            .load_printable(&job)
+            .and_then(|printable| self.printer.print(&job, printable))
+        {
+            Ok(()) => {
+                job.status = PrintJobStatus::Done;
+                job.last_error = None;
+                info!("print job done: id={} order={}", job.id, job.order_id);
+            }
+            Err(e) => {
+                job.attempts += 1;
+                job.status = if job.attempts >= MAX_PRINT_ATTEMPTS {
+                    PrintJobStatus::Failed
+                } else {
+                    PrintJobStatus::Pending
+                };
+                job.last_error = Some(e.to_string());
+                warn!(
+                    "print job {} attempt {} failed: {}",
+                    job.id, job.attempts, e
+                );
+            }
+        }
+        try_log!(
+            self.job_repo
+                .update_status(job.id, job.status, job.attempts, job.last_error.clone()),
+            "PrintQueueUseCases::process_next"
+        );
+        Ok(Some(job))
+    }
+
+    pub fn list_jobs(&self, status: Option<PrintJobStatus>) -> Result<Vec<PrintJob>, AppError> {
+        let res = try_log!(
+            self.job_repo.list(status, LIST_LIMIT),
+            "PrintQueueUseCases::list_jobs"
+        );
+        Ok(res)
+    }
+
+    // Put a failed or cancelled job back in the queue with a fresh attempt count.
+    pub fn retry_job(&self, id: i32) -> Result<(), AppError> {
+        let job = self.get_job(id)?;
+        if !matches!(
+            job.status,
+            PrintJobStatus::Failed | PrintJobStatus::Cancelled
+        ) {
+            return Err(AppError::Validation(format!(
+                "print job {id} is {}, only failed or cancelled jobs can be retried",
+                job.status.as_str()
+            )));
+        }
+        try_log!(
+            self.job_repo
+                .update_status(id, PrintJobStatus::Pending, 0, None),
+            "PrintQueueUseCases::retry_job"
+        );
+        info!("print job retried: id={id}");
+        Ok(())
+    }
+
+    pub fn cancel_job(&self, id: i32) -> Result<(), AppError> {
+        let job = self.get_job(id)?;
+        if !matches!(job.status, PrintJobStatus::Pending | PrintJobStatus::Failed) {
+            return Err(AppError::Validation(format!(
+                "print job {id} is {}, only pending or failed jobs can be cancelled",
+                job.status.as_str()
+            )));
+        }
+        try_log!(
+            self.job_repo.update_status(
+                id,
+                PrintJobStatus::Cancelled,
+                job.attempts,
+                job.last_error
+            ),
+            "PrintQueueUseCases::cancel_job"
+        );
+        info!("print job cancelled: id={id}");
+        Ok(())
+    }
+
+    // Background worker: requeue anything a previous run left mid-print, then
+    // drain the queue, backing off while it is empty or the printer is failing.
+    pub fn spawn_worker(queue: Arc<Self>) -> JoinHandle<()> {
+        std::thread::spawn(move || {
+            match queue.job_repo.requeue_interrupted() {
+                Ok(0) => {}
+                Ok(n) => info!("requeued {n} interrupted print jobs"),
+                Err(e) => error!("print queue requeue failed: {e:?}"),
+            }
+            loop {
+                match queue.process_next() {
+                    Ok(Some(job)) if job.status == PrintJobStatus::Done => {}
+                    Ok(_) => std::thread::sleep(WORKER_IDLE),
+                    Err(e) => {
+                        error!("print queue worker error: {e:?}");
+                        std::thread::sleep(WORKER_IDLE);
+                    }
+                }
+            }
+        })
+    }
+
+    fn get_job(&self, id: i32) -> Result<PrintJob, AppError> {
+        try_log!(self.job_repo.get(id), "PrintQueueUseCases::get_job")
+            .ok_or_else(|| AppError::NotFound(format!("print job {id} not found")))
+    }
+
+    fn load_printable(&self, job: &PrintJob) -> Result<PrintableSaleDto, AppError> {
+        let details = self
+            .cust_tx_repo
+            .get_with_details_and_balance(job.order_id)?;
+        let mut printable = PrintableSaleDto::from_details(details);
+        if let Some(balance) = job.balance {
+            printable.balance = balance;
+            printable.balance_is_current = false;
+        }
+        if self
+            .return_repo
+            .get_by_return_order(job.order_id)?
+            .is_some()
+        {
+            printable.kind = ReceiptKind::Return;
+        }
+        Ok(printable)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::models::{CustomerTransaction, OrderReason};
+    use crate::test_support::mock_customer_tx_repo::MockCustomerTransactionRepo;
+    use crate::test_support::mock_order_return_repo::MockOrderReturnRepo;
+    use crate::test_support::mock_print_job_repo::MockPrintJobRepo;
+    use std::sync::atomic::{AtomicI32, Ordering};
+    use std::sync::Mutex;
+
+    // Fails the first `failures` prints, then succeeds.
+    struct FlakyPrinter {
+        failures: AtomicI32,
+        printed: AtomicI32,
+        balances: Mutex<Vec<(i32, bool)>>, // balance printed, and whether it was current
+    }
+
+    impl ReceiptPrinter for FlakyPrinter {
+        fn print(&self, _job: &PrintJob, printable: PrintableSaleDto) -> Result<(), AppError> {
+            if self.failures.fetch_sub(1, Ordering::SeqCst) > 0 {
+                return Err(AppError::Unexpected("printer offline".into()));
+            }
+            self.printed.fetch_add(1, Ordering::SeqCst);
+            self.balances
+                .lock()
+                .unwrap()
+                .push((printable.balance, printable.balance_is_current));
+            Ok(())
+        }
+    }
+
+    fn make_queue(failures: i32) -> (PrintQueueUseCases, Arc<FlakyPrinter>) {
+        make_queue_with(failures, Arc::new(MockCustomerTransactionRepo::new()))
+    }
+
+    fn make_queue_with(
+        failures: i32,
+        cust_tx_repo: Arc<MockCustomerTransactionRepo>,
+    ) -> (PrintQueueUseCases, Arc<FlakyPrinter>) {
+        cust_tx_repo
+            .create(&CustomerTransaction {
+                order_id: 1,
+                customer_mdoc: 2,
+                operator_mdoc: 3,
+                date: None,
+                note: None,
+                idempotency_key: None,
+                reason: OrderReason::Regular,
+            })
+            .unwrap();
+        let printer = Arc::new(FlakyPrinter {
+            failures: AtomicI32::new(failures),
+            printed: AtomicI32::new(0),
+            balances: Mutex::new(vec![]),
+        });
+        let queue = PrintQueueUseCases::new(
+            Arc::new(MockPrintJobRepo::new()),
+            cust_tx_repo,
+            Arc::new(MockOrderReturnRepo::new()),
+            printer.clone(),
+        );
+        (queue, printer)
+    }
+
+    fn job(order_id: i32) -> PrintJob {
+        PrintJob {
+            id: 0,
+            order_id,
+            copies: ReceiptCopies::Both.as_str().into(),
+            printer_name: "Receipt".into(),
+            sumatra_location: "sumatra.exe".into(),
+            operator_name: "Op".into(),
+            customer_name: "Cust".into(),
+            reprint_by: None,
+            status: PrintJobStatus::Done,
+            attempts: 5,
+            last_error: None,
+            created_at: None,
+            updated_at: None,
+            balance: None,
+        }
+    }
+
+    #[test]
+    fn receipts_show_the_balance_as_of_the_order() {
+        let cust_tx_repo = Arc::new(MockCustomerTransactionRepo::new());
+        let (queue, printer) = make_queue_with(0, Arc::clone(&cust_tx_repo));
+        cust_tx_repo.set_balance(2, 900);
+        queue.enqueue(job(1)).unwrap();
+
+        // the customer spends more before the sale prints and is reprinted
+        cust_tx_repo.set_balance(2, 400);
+        queue
+            .enqueue(PrintJob {
+                reprint_by: Some(3),
+                ..job(1)
+            })
+            .unwrap();
+        while queue.process_next().unwrap().is_some() {}
+        assert_eq!(
+            *printer.balances.lock().unwrap(),
+            vec![(900, false), (900, false)]
+        );
+    }
+
+    #[test]
+    fn reprint_without_a_captured_balance_prints_the_current_one() {
+        let cust_tx_repo = Arc::new(MockCustomerTransactionRepo::new());
+        let (queue, printer) = make_queue_with(0, Arc::clone(&cust_tx_repo));
+        cust_tx_repo.set_balance(2, 400);
+        let id = queue
+            .enqueue(PrintJob {
+                reprint_by: Some(3),
+                ..job(1)
+            })
+            .unwrap();
+        assert_eq!(queue.get_job(id).unwrap().balance, None);
+        queue.process_next().unwrap();
+        assert_eq!(*printer.balances.lock().unwrap(), vec![(400, true)]);
+    }
+
+    #[test]
+    fn enqueue_resets_state_and_rejects_unknown_orders() {
+        let (queue, _) = make_queue(0);
+        let id = queue.enqueue(job(1)).unwrap();
+        let queued = queue.get_job(id).unwrap();
+        assert_eq!(queued.status, PrintJobStatus::Pending);
+        assert_eq!(queued.attempts, 0);
+
+        assert!(matches!(queue.enqueue(job(99)), Err(AppError::NotFound(_))));
+        let mut bad = job(1);
+        bad.copies = "all".into();
+        assert!(matches!(queue.enqueue(bad), Err(AppError::Validation(_))));
+    }
+
+    #[test]
+    fn failed_print_is_retried_until_it_succeeds() {
+        let (queue, printer) = make_queue(1);
+        let id = queue.enqueue(job(1)).unwrap();
+
+        let first = queue.process_next().unwrap().unwrap();
+        assert_eq!(first.status, PrintJobStatus::Pending);
+        assert_eq!(first.attempts, 1);
+        assert!(first.last_error.unwrap().contains("printer offline"));
+
+        let second = queue.process_next().unwrap().unwrap();
+        assert_eq!(second.status, PrintJobStatus::Done);
+        assert_eq!(queue.get_job(id).unwrap().status, PrintJobStatus::Done);
+        assert_eq!(printer.printed.load(Ordering::SeqCst), 1);
+        assert!(queue.process_next().unwrap().is_none());
+    }
+
+    #[test]
+    fn job_fails_after_max_attempts_and_can_be_retried() {
+        let (queue, printer) = make_queue(MAX_PRINT_ATTEMPTS);
+        let id = queue.enqueue(job(1)).unwrap();
+        for _ in 0..MAX_PRINT_ATTEMPTS {
+            queue.process_next().unwrap();
+        }
+        let failed = queue.get_job(id).unwrap();
+        assert_eq!(failed.status, PrintJobStatus::Failed);
+        assert_eq!(failed.attempts, MAX_PRINT_ATTEMPTS);
+        assert!(queue.process_next().unwrap().is_none());
+
+        queue.retry_job(id).unwrap();
+        let retried = queue.get_job(id).unwrap();
+        assert_eq!(retried.status, PrintJobStatus::Pending);
+        assert_eq!(retried.attempts, 0);
+        assert_eq!(
+            queue.process_next().unwrap().unwrap().status,
+            PrintJobStatus::Done
+        );
+        assert_eq!(printer.printed.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn cancel_and_retry_only_apply_to_matching_states() {
+        let (queue, printer) = make_queue(0);
+        let id = queue.enqueue(job(1)).unwrap();
+        assert!(matches!(queue.retry_job(id), Err(AppError::Validation(_))));
+
+        queue.cancel_job(id).unwrap();
+        assert!(queue.process_next().unwrap().is_none());
+        assert_eq!(printer.printed.load(Ordering::SeqCst), 0);
+        assert_eq!(
+            queue
+                .list_jobs(Some(PrintJobStatus::Cancelled))
+                .unwrap()
+                .len(),
+            1
+        );
+
+        queue.retry_job(id).unwrap();
+        queue.process_next().unwrap();
+        assert!(matches!(queue.cancel_job(id), Err(AppError::Validation(_))));
+        assert!(matches!(queue.cancel_job(42), Err(AppError::NotFound(_))));
+    }
+}
@@ -1,26 +1,40 @@
 use crate::common::error::AppError;
+use crate::domain::models::InventoryReason;
 use crate::domain::report_models::club_import_report::ClubTransactionRow;
 use crate::domain::repos::ClubImportRepoTrait;
 use crate::domain::repos::ClubTransactionRepoTrait;
 use crate::domain::repos::CustomerRepoTrait;
 use crate::domain::repos::CustomerTransactionRepoTrait;
 use crate::domain::repos::CustomerTxDetailRepoTrait;
+use crate::domain::repos::InventoryTransactionRepoTrait;
+use crate::domain::repos::OperatorRepoTrait;
+use crate::domain::repos::OrderReturnRepoTrait;
 use crate::domain::repos::ProductRepoTrait;
+use crate::domain::repos::PromotionRepoTrait;
 use crate::infrastructure::command_runner::CommandRunner;
 use crate::infrastructure::printing::reports::business_receipt::print_business_receipt;
 use crate::infrastructure::printing::reports::club_imports::print_club_import_report;
 use crate::infrastructure::printing::reports::customer_balance_report::print_customer_balance_report;
 use crate::infrastructure::printing::reports::customer_receipt::print_customer_receipt;
+use crate::infrastructure::printing::reports::margin_report::print_margin_report;
 use crate::infrastructure::printing::reports::print_daily_sales::print_daily_sales;
 use crate::infrastructure::printing::reports::prod_inv_report::print_inventory_report;
 use crate::infrastructure::printing::reports::product_catalog::print_product_catalog_report;
 use crate::infrastructure::printing::reports::product_sales::print_product_sales;
+use crate::infrastructure::printing::reports::reorder_report::print_reorder_report;
 use crate::infrastructure::printing::reports::sales_detail_report::print_sales_detail_report;
-use crate::interface::dto::printer_dto::PrintableSaleDto;
+use crate::interface::dto::printer_dto::{
+    PrintableComponent, PrintableSaleDto, ReceiptCopies, ReprintStamp,
+};
 use crate::try_log;
-use chrono::NaiveDateTime;
+use chrono::{Local, NaiveDateTime};
+use log::info;
+use std::collections::{BTreeMap, HashMap};
 use std::sync::Arc;
 
+// printed under a discounted line whose promotion can no longer be found
+const PROMOTION_FALLBACK_NAME: &str = "Promotion";
+
 pub enum ReportType {
     Receipt,
 }
@@ -33,9 +47,14 @@ pub struct PrinterUseCases {
     cust_tx_detail_repo: Arc<dyn crate::domain::repos::CustomerTxDetailRepoTrait>,
     club_import_repo: Arc<dyn ClubImportRepoTrait>,
     club_tx_repo: Arc<dyn ClubTransactionRepoTrait>,
+    op_repo: Arc<dyn OperatorRepoTrait>,
+    promo_repo: Arc<dyn PromotionRepoTrait>,
+    inv_repo: Arc<dyn InventoryTransactionRepoTrait>,
+    return_repo: Arc<dyn OrderReturnRepoTrait>,
 }
 
 impl PrinterUseCases {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         runner: Arc<dyn CommandRunner>,
         customer_repo: Arc<dyn CustomerRepoTrait>,
@@ -44,6 +63,10 @@ impl PrinterUseCases {
         cust_tx_detail_repo: Arc<dyn CustomerTxDetailRepoTrait>,
         club_import_repo: Arc<dyn ClubImportRepoTrait>,
         club_tx_repo: Arc<dyn ClubTransactionRepoTrait>,
+        op_repo: Arc<dyn OperatorRepoTrait>,
+        promo_repo: Arc<dyn PromotionRepoTrait>,
+        inv_repo: Arc<dyn InventoryTransactionRepoTrait>,
+        return_repo: Arc<dyn OrderReturnRepoTrait>,
     ) -> Self {
         Self {
             runner,
@@ -53,6 +76,10 @@ impl PrinterUseCases {
             cust_tx_detail_repo,
             club_import_repo,
             club_tx_repo,
+            op_repo,
+            promo_repo,
+            inv_repo,
+            return_repo,
         }
     }
 
@@ -87,45 +114,171 @@ impl PrinterUseCases {
         Ok(names)
     }
 
-    // Print both the customer & business receipts.
+    // Lists what each kit line was made of, from the component rows its sale
+    // wrote, so a kit changed since doesn't change the receipt. A return
+    // lists what the original sale took out.
+    pub fn add_kit_components(
+        &self,
+        mut printable: PrintableSaleDto,
+    ) -> Result<PrintableSaleDto, AppError> {
+        let ctx = "PrinterUseCases::add_kit_components";
+        let order_id = printable.transaction.order_id;
+        let sale_id = try_log!(self.return_repo.get_by_return_order(order_id), ctx)
+            .map_or(order_id, |r| r.order_id);
+
+        // units of each component per kit, and how many of each kit, across the sale
+        let mut taken: BTreeMap<String, BTreeMap<String, i32>> = BTreeMap::new();
+        for row in try_log!(self.inv_repo.list_for_order(sale_id), ctx) {
+            if row.reason != InventoryReason::Sale {
+                continue;
+            }
+            if let Some(kit) = row.kit_upc() {
+                *taken
+                    .entry(kit.to_string())
+                    .or_default()
+                    .entry(row.upc.clone())
+                    .or_insert(0) -= row.quantity_change;
+            }
+        }
+        if taken.is_empty() {
+            return Ok(printable);
+        }
+        let mut sold: HashMap<String, i32> = HashMap::new();
+        for (d, _) in try_log!(self.cust_tx_detail_repo.list_by_order(sale_id), ctx) {
+            *sold.entry(d.upc).or_insert(0) += d.quantity;
+        }
+
+        for item in &mut printable.items {
+            let (Some(parts), Some(&kits)) = (taken.get(&item.upc), sold.get(&item.upc)) else {
+                continue;
+            };
+            if kits == 0 {
+                continue;
+            }
+            let mut components = Vec::with_capacity(parts.len());
+            for (upc, units) in parts {
+                let desc = try_log!(self.product_repo.get_by_upc(upc.clone()), ctx)
+                    .map_or_else(|| upc.clone(), |p| p.desc);
+                components.push(PrintableComponent {
+                    desc,
+                    quantity: units * item.quantity / kits,
+                });
+            }
+            item.components = components;
+        }
+        Ok(printable)
+    }
+
+    // Names the promotion behind each discounted line that wasn't named when
+    // sold, falling back to a fixed label once the promotion is gone.
+    pub fn add_promotion_names(
+        &self,
+        mut printable: PrintableSaleDto,
+    ) -> Result<PrintableSaleDto, AppError> {
+        for item in &mut printable.items {
+            let Some(id) = item.promotion_id else {
+                continue;
+            };
+            if item.promotion.is_some() {
+                continue;
+            }
+            let name = try_log!(
+                self.promo_repo.get(id),
+                "PrinterUseCases::add_promotion_names"
+            )
+            .map_or_else(|| PROMOTION_FALLBACK_NAME.to_string(), |p| p.name);
+            item.promotion = Some(name);
+        }
+        Ok(printable)
+    }
+
+    // Print the customer and/or business receipts.
     pub fn print_receipts(
         &self,
         printable: &PrintableSaleDto,
+        copies: ReceiptCopies,
         printer_name: &str,
         sumatra_location: &str,
         operator_name: &str,
         customer_name: &str,
     ) -> Result<(), AppError> {
-        let PrintableSaleDto {
-            transaction,
-            items,
-            balance,
-        } = printable;
-
         // customer copy
-        print_customer_receipt(
-            transaction,
-            items,
-            operator_name,
-            customer_name,
-            printer_name,
-            sumatra_location,
-        )?;
+        if copies.customer() {
+            print_customer_receipt(
+                printable,
+                operator_name,
+                customer_name,
+                printer_name,
+                sumatra_location,
+            )?;
+        }
 
         // business copy
-        print_business_receipt(
-            transaction,
-            items,
-            operator_name,
-            customer_name,
-            *balance,
-            printer_name,
-            sumatra_location,
-        )?;
+        if copies.business() {
+            print_business_receipt(
+                printable,
+                operator_name,
+                customer_name,
+                printer_name,
+                sumatra_location,
+            )?;
+        }
 
         Ok(())
     }
 
+    // Reprint receipts for a past order, stamped with who reprinted it and when.
+    pub fn reprint_receipts(
+        &self,
+        mut printable: PrintableSaleDto,
+        copies: ReceiptCopies,
+        reprinted_by: i32,
+        printer_name: &str,
+        sumatra_location: &str,
+    ) -> Result<(), AppError> {
+        let reprint_operator = try_log!(
+            self.op_repo.get_by_mdoc(reprinted_by),
+            "PrinterUseCases::reprint_receipts"
+        )
+        .ok_or_else(|| AppError::NotFound(format!("operator {reprinted_by} not found")))?;
+        let order_operator = try_log!(
+            self.op_repo
+                .get_by_mdoc(printable.transaction.operator_mdoc),
+            "PrinterUseCases::reprint_receipts"
+        )
+        .map(|o| o.name)
+        .unwrap_or_default();
+        let customer_name = try_log!(
+            self.customer_repo
+                .get_by_mdoc(printable.transaction.customer_mdoc),
+            "PrinterUseCases::reprint_receipts"
+        )
+        .map(|c| c.name)
+        .unwrap_or_default();
+
+        printable.reprint = Some(ReprintStamp {
+            operator_mdoc: reprint_operator.mdoc,
+            operator_name: reprint_operator.name,
+            reprinted_at: Local::now().naive_local(),
+        });
+        try_log!(
+            self.print_receipts(
+                &printable,
+                copies,
+                printer_name,
+                sumatra_location,
+                &order_operator,
+                &customer_name,
+            ),
+            "PrinterUseCases::reprint_receipts"
+        );
+        info!(
+            "receipt reprint: order={} copies={:?} operator={}",
+            printable.transaction.order_id, copies, reprinted_by
+        );
+        Ok(())
+    }
+
     pub fn print_prod_inv_rpt(
         &self,
         printer_name: String,
@@ -186,6 +339,19 @@ impl PrinterUseCases {
         Ok(())
     }
 
+    pub fn print_reorder_report(
+        &self,
+        printer_name: String,
+        sumatra_location: String,
+    ) -> Result<(), AppError> {
+        let rows = try_log!(
+            self.product_repo.low_stock(),
+            "PrinterUseCases::print_reorder_report"
+        );
+        print_reorder_report(&rows, &printer_name, &sumatra_location)?;
+        Ok(())
+    }
+
     pub fn print_sales_detail_report(
         &self,
         start_date: chrono::NaiveDateTime,
@@ -202,6 +368,10 @@ impl PrinterUseCases {
         let grand_total = self
             .cust_tx_detail_repo
             .get_sales_totals(start_date, end_date)?;
+        let tax_rows = try_log!(
+            self.cust_tx_detail_repo.tax_summary(start_date, end_date),
+            "PrinterUseCases::print_sales_detail_report"
+        );
 
         let total_amount = try_log!(
             self.customer_repo.sum_all_balances(),
@@ -213,6 +383,7 @@ impl PrinterUseCases {
             start_date,
             end_date,
             grand_total,
+            &tax_rows,
             total_amount,
             &printer_name,
             &sumatra_location,
@@ -251,6 +422,21 @@ impl PrinterUseCases {
         Ok(())
     }
 
+    pub fn margin_by_category(
+        &self,
+        start: NaiveDateTime,
+        end: NaiveDateTime,
+        printer_name: String,
+        sumatra_location: String,
+    ) -> Result<(), AppError> {
+        let data = try_log!(
+            self.cust_tx_detail_repo.margin_by_category(start, end),
+            "PrinterUseCases::margin_by_category"
+        );
+        print_margin_report(&data, start, end, &printer_name, &sumatra_location)?;
+        Ok(())
+    }
+
     pub fn sales_by_day(
         &self,
         start: NaiveDateTime,
@@ -263,6 +449,10 @@ impl PrinterUseCases {
             "PrinterUseCases::sales_by_day"
         );
         let grand_total = self.cust_tx_detail_repo.get_sales_totals(start, end)?;
+        let tax_rows = try_log!(
+            self.cust_tx_detail_repo.tax_summary(start, end),
+            "PrinterUseCases::sales_by_day"
+        );
         let total_amount = try_log!(
             self.customer_repo.sum_all_balances(),
             "PrinterUseCases::sales_by_day"
@@ -273,6 +463,7 @@ impl PrinterUseCases {
             start,
             end,
             grand_total,
+            &tax_rows,
             total_amount,
             &printer_name,
             &sumatra_location,
@@ -325,3 +516,216 @@ impl PrinterUseCases {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::models::{
+        CustomerTransaction, CustomerTxDetail, InventoryTransaction, OrderReturn, Product,
+        Promotion, PromotionRule,
+    };
+    use crate::infrastructure::command_runner::WindowsCommandRunner;
+    use crate::test_support::mock_club_import_repo::MockClubImportRepo;
+    use crate::test_support::mock_club_tx_repo::MockClubTransactionRepo;
+    use crate::test_support::mock_customer_repo::MockCustomerRepo;
+    use crate::test_support::mock_customer_tx_detail_repo::MockCustomerTxDetailRepo;
+    use crate::test_support::mock_customer_tx_repo::MockCustomerTransactionRepo;
+    use crate::test_support::mock_inventory_transaction_repo::MockInventoryTransactionRepo;
+    use crate::test_support::mock_operator_repo::MockOperatorRepo;
+    use crate::test_support::mock_order_return_repo::MockOrderReturnRepo;
+    use crate::test_support::mock_product_repo::MockProductRepo;
+    use crate::test_support::mock_promotion_repo::MockPromotionRepo;
+
+    type UseCaseFixture = (
+        PrinterUseCases,
+        Arc<MockProductRepo>,
+        Arc<MockCustomerTxDetailRepo>,
+        Arc<MockInventoryTransactionRepo>,
+        Arc<MockOrderReturnRepo>,
+        Arc<MockPromotionRepo>,
+    );
+
+    fn make_use_cases() -> UseCaseFixture {
+        let prod_repo = Arc::new(MockProductRepo::default());
+        let detail_repo = Arc::new(MockCustomerTxDetailRepo::default());
+        let inv_repo = Arc::new(MockInventoryTransactionRepo::default());
+        let return_repo = Arc::new(MockOrderReturnRepo::default());
+        let promo_repo = Arc::new(MockPromotionRepo::default());
+        let uc = PrinterUseCases::new(
+            Arc::new(WindowsCommandRunner),
+            Arc::new(MockCustomerRepo::default()),
+            prod_repo.clone(),
+            Arc::new(MockCustomerTransactionRepo::default()),
+            detail_repo.clone(),
+            Arc::new(MockClubImportRepo::default()),
+            Arc::new(MockClubTransactionRepo::default()),
+            Arc::new(MockOperatorRepo::default()),
+            promo_repo.clone(),
+            inv_repo.clone(),
+            return_repo.clone(),
+        );
+        (
+            uc,
+            prod_repo,
+            detail_repo,
+            inv_repo,
+            return_repo,
+            promo_repo,
+        )
+    }
+
+    fn line(order_id: i32, upc: &str, quantity: i32) -> CustomerTxDetail {
+        CustomerTxDetail {
+            detail_id: 0,
+            order_id,
+            upc: upc.into(),
+            quantity,
+            price: 500,
+            cost: None,
+            discount: 0,
+            promotion_id: None,
+            promotion_name: None,
+            tax: 0,
+            tax_rate: None,
+            limit_exempt: false,
+        }
+    }
+
+    fn printable(order_id: i32, lines: Vec<CustomerTxDetail>) -> PrintableSaleDto {
+        PrintableSaleDto::from_details((
+            CustomerTransaction {
+                order_id,
+                customer_mdoc: 9,
+                operator_mdoc: 1,
+                date: None,
+                note: None,
+                idempotency_key: None,
+                reason: Default::default(),
+            },
+            lines.into_iter().map(|d| (d, "Kit".to_string())).collect(),
+            0,
+        ))
+    }
+
+    #[test]
+    fn kit_lines_list_what_their_sale_took_out() -> anyhow::Result<()> {
+        let (uc, prod_repo, detail_repo, inv_repo, return_repo, _) = make_use_cases();
+        for (upc, desc) in [("SOAP", "Soap"), ("COMB", "Comb")] {
+            prod_repo.create(&Product {
+                upc: upc.into(),
+                desc: desc.into(),
+                ..Default::default()
+            })?;
+        }
+
+        // order 1 sold three of the kit over two lines while it held two soaps
+        // and a comb; the soaps came off two lots. No kit is defined now.
+        detail_repo.create(&line(1, "KIT", 1))?;
+        detail_repo.create(&line(1, "KIT", 2))?;
+        for (upc, quantity_change, reference) in [
+            ("SOAP", -4, Some("kit KIT")),
+            ("SOAP", -2, Some("kit KIT")),
+            ("COMB", -3, Some("kit KIT")),
+            ("SOAP", 1, Some("return #2 (kit KIT)")),
+        ] {
+            inv_repo.create(&InventoryTransaction {
+                upc: upc.into(),
+                quantity_change,
+                operator_mdoc: 1,
+                ref_order_id: Some(1),
+                reference: reference.map(Into::into),
+                reason: if quantity_change < 0 {
+                    InventoryReason::Sale
+                } else {
+                    InventoryReason::CustomerReturn
+                },
+                ..Default::default()
+            })?;
+        }
+        let listed = |p: &PrintableSaleDto| {
+            p.items
+                .iter()
+                .map(|i| {
+                    i.components
+                        .iter()
+                        .map(|c| (c.desc.clone(), c.quantity))
+                        .collect::<Vec<_>>()
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let sale =
+            uc.add_kit_components(printable(1, vec![line(1, "KIT", 1), line(1, "KIT", 2)]))?;
+        assert_eq!(
+            listed(&sale),
+            [
+                vec![("Comb".to_string(), 1), ("Soap".to_string(), 2)],
+                vec![("Comb".to_string(), 2), ("Soap".to_string(), 4)],
+            ]
+        );
+
+        let mut conn = rusqlite::Connection::open_in_memory()?;
+        let tx = conn.transaction()?;
+        return_repo.create_with_tx(
+            &OrderReturn {
+                return_order_id: 2,
+                order_id: 1,
+                operator_mdoc: 1,
+                reason: None,
+                created_at: None,
+            },
+            &tx,
+        )?;
+        let returned = uc.add_kit_components(printable(2, vec![line(2, "KIT", -1)]))?;
+        assert_eq!(
+            listed(&returned),
+            [vec![("Comb".to_string(), -1), ("Soap".to_string(), -2)]]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn promotion_lines_print_the_name_they_sold_under() -> anyhow::Result<()> {
+        let (uc, _, _, _, _, promo_repo) = make_use_cases();
+        let renamed = promo_repo.create(&Promotion {
+            id: 0,
+            name: "Soap week".into(),
+            rule: PromotionRule::AmountOff(100),
+            upc: Some("SOAP".into()),
+            category: None,
+            starts_at: None,
+            ends_at: None,
+            created_at: None,
+            deleted: None,
+        })?;
+        let discounted = |promotion_id, promotion_name: Option<&str>| CustomerTxDetail {
+            discount: 100,
+            promotion_id: Some(promotion_id),
+            promotion_name: promotion_name.map(Into::into),
+            ..line(1, "SOAP", 1)
+        };
+
+        // the first line was sold as "Spring soap"; the second predates stored
+        // names; the third's promotion can't be found at all
+        let sale = uc.add_promotion_names(printable(
+            1,
+            vec![
+                discounted(renamed, Some("Spring soap")),
+                discounted(renamed, None),
+                discounted(99, None),
+                line(1, "COMB", 1),
+            ],
+        ))?;
+        let names: Vec<_> = sale.items.iter().map(|i| i.promotion.as_deref()).collect();
+        assert_eq!(
+            names,
+            [
+                Some("Spring soap"),
+                Some("Soap week"),
+                Some(PROMOTION_FALLBACK_NAME),
+                None
+            ]
+        );
+        Ok(())
+    }
+}
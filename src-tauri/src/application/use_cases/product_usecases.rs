@@ -1,11 +1,20 @@
 use crate::application::common::db::atomic_tx;
+use crate::application::common::pricing::{
+    apply_due_price_changes_with_tx, set_price_now_with_tx, supersede_pending_with_tx,
+};
 use crate::common::error::AppError;
-use crate::domain::models::{Category, PriceAdjustment, Product};
+use crate::domain::models::{
+    BarcodeAlias, BulkPriceChange, BulkPriceLine, BundleComponent, Category, ImportAction,
+    ImportFailure, PriceAdjustment, PriceAdjustmentStatus, Product, ProductImportLine,
+    ProductImportPlan, ProductSelection,
+};
+use crate::domain::report_models::reorder_report::LowStockItem;
 use crate::domain::repos::{CategoryRepoTrait, PriceAdjustmentRepoTrait, ProductRepoTrait};
 use crate::interface::dto::product_dto::UpdateProductDto;
 use crate::try_log;
 use chrono::Utc;
 use log::info;
+use std::collections::HashSet;
 use std::sync::{Arc, Mutex};
 
 pub struct ProductUseCases {
@@ -31,6 +40,17 @@ impl ProductUseCases {
     }
 
     pub fn create_product(&self, product: Product) -> Result<(), AppError> {
+        if let Some((aliased, _)) = try_log!(
+            self.repo.resolve_barcode(&product.upc),
+            "ProductUseCases::create_product"
+        )
+        .filter(|(p, _)| p.upc != product.upc)
+        {
+            return Err(AppError::Validation(format!(
+                "upc {} is already a barcode for {} ({})",
+                product.upc, aliased.upc, aliased.desc
+            )));
+        }
         let maybe_existing = try_log!(
             self.repo.get_by_upc(product.upc.clone()),
             "ProductUseCases::create_product"
@@ -85,22 +105,39 @@ impl ProductUseCases {
     }
 
     pub fn price_adjustment(&self, adj: PriceAdjustment) -> Result<PriceAdjustment, AppError> {
-        let mut p = try_log!(
-            self.repo.get_by_upc(adj.upc.clone()),
-            "ProductUseCases::price_adjustment"
-        )
-        .ok_or_else(|| AppError::NotFound(format!("Product {} not found", adj.upc)))?;
-
+        self.apply_due_price_changes()?;
+        let now = Utc::now().naive_utc();
         let mut adj = adj;
-        adj.created_at = Some(chrono::Utc::now().naive_utc());
+        adj.created_at = Some(now);
+        // a change dated in the future waits as pending; anything else takes effect now
+        let scheduled = adj.effective_at.is_some_and(|at| at > now);
+        if scheduled {
+            adj.status = PriceAdjustmentStatus::Pending;
+        } else {
+            adj.effective_at = None;
+            adj.status = PriceAdjustmentStatus::Applied;
+        }
 
         let adj_id = try_log!(
             atomic_tx(&self.conn, |tx| {
+                let p = self
+                    .repo
+                    .get_by_upc_with_tx(adj.upc.clone(), tx)?
+                    .ok_or_else(|| AppError::NotFound(format!("Product {} not found", adj.upc)))?;
+                adj.old = p.price;
                 self.price_repo.create_with_tx(&adj, tx)?;
-                p.price = adj.new;
-                p.updated = Some(chrono::Utc::now().naive_utc());
-                self.repo.update_by_upc_with_tx(&p, tx)?;
-                Ok(tx.last_insert_rowid() as i32)
+                let id = tx.last_insert_rowid() as i32;
+                if !scheduled {
+                    set_price_now_with_tx(
+                        self.price_repo.as_ref(),
+                        self.repo.as_ref(),
+                        &p.upc,
+                        adj.new,
+                        now,
+                        tx,
+                    )?;
+                }
+                Ok(id)
             }),
             "ProductUseCases::price_adjustment"
         );
@@ -112,6 +149,14 @@ impl ProductUseCases {
         );
 
         match &adj_loaded {
+            Some(a) if a.status == PriceAdjustmentStatus::Pending => log::info!(
+                "price change scheduled: id={} upc={} old={} new={} effective_at={:?}",
+                a.id,
+                a.upc,
+                a.old,
+                a.new,
+                a.effective_at
+            ),
             Some(a) => log::info!(
                 "price adjustment recorded: upc={} old={} new={}",
                 a.upc,
@@ -150,6 +195,175 @@ impl ProductUseCases {
         );
         Ok(())
     }
+    // Applies the scheduled price changes whose time has come. Runs at startup
+    // and ahead of anything that reads prices, so a change takes effect on the
+    // first use after its effective time.
+    pub fn apply_due_price_changes(&self) -> Result<Vec<PriceAdjustment>, AppError> {
+        Ok(try_log!(
+            atomic_tx(&self.conn, |tx| {
+                apply_due_price_changes_with_tx(
+                    self.price_repo.as_ref(),
+                    self.repo.as_ref(),
+                    Utc::now().naive_utc(),
+                    tx,
+                )
+            }),
+            "ProductUseCases::apply_due_price_changes"
+        ))
+    }
+
+    pub fn list_pending_price_changes(&self) -> Result<Vec<PriceAdjustment>, AppError> {
+        self.apply_due_price_changes()?;
+        let res = try_log!(
+            self.price_repo.list_pending(),
+            "ProductUseCases::list_pending_price_changes"
+        );
+        Ok(res)
+    }
+
+    // Withdraws a scheduled change before it takes effect.
+    pub fn cancel_price_change(&self, id: i32) -> Result<(), AppError> {
+        self.apply_due_price_changes()?;
+        try_log!(
+            self.price_repo.cancel(id),
+            "ProductUseCases::cancel_price_change"
+        );
+        info!("scheduled price change cancelled: id={id}");
+        Ok(())
+    }
+
+    // Old and new prices for every product a bulk change would reprice.
+    pub fn preview_bulk_price_change(
+        &self,
+        change: &BulkPriceChange,
+    ) -> Result<Vec<BulkPriceLine>, AppError> {
+        self.apply_due_price_changes()?;
+        self.bulk_price_lines(change, "ProductUseCases::preview_bulk_price_change")
+    }
+
+    // Reprices every selected product at once, each with its own price
+    // adjustment by `operator_mdoc`.
+    pub fn bulk_price_change(
+        &self,
+        change: &BulkPriceChange,
+        operator_mdoc: i32,
+    ) -> Result<Vec<PriceAdjustment>, AppError> {
+        let ctx = "ProductUseCases::bulk_price_change";
+        self.apply_due_price_changes()?;
+        let lines = self.bulk_price_lines(change, ctx)?;
+
+        let now = Utc::now().naive_utc();
+        let adjustments = try_log!(
+            atomic_tx(&self.conn, |tx| {
+                // priced again from what's stored now, in case a product
+                // changed since the lines were worked out
+                let mut moves = Vec::with_capacity(lines.len());
+                for l in &lines {
+                    let p = self
+                        .repo
+                        .get_by_upc_with_tx(l.upc.clone(), tx)?
+                        .filter(|p| p.deleted.is_none())
+                        .ok_or_else(|| {
+                            AppError::NotFound(format!("Product {} not found", l.upc))
+                        })?;
+                    let new = change.new_price(p.price);
+                    if new < 1 {
+                        return Err(AppError::Validation(format!(
+                            "{} ({}) would be priced at {new}",
+                            p.desc, p.upc
+                        )));
+                    }
+                    if new != p.price {
+                        moves.push((p.upc, p.price, new));
+                    }
+                }
+
+                let mut adjustments = Vec::with_capacity(moves.len());
+                for (upc, old, new) in moves {
+                    let mut adj = PriceAdjustment {
+                        id: 0,
+                        operator_mdoc,
+                        upc,
+                        old,
+                        new,
+                        created_at: Some(now),
+                        effective_at: None,
+                        status: PriceAdjustmentStatus::Applied,
+                    };
+                    adj.id = self.price_repo.create_with_tx(&adj, tx)?;
+                    set_price_now_with_tx(
+                        self.price_repo.as_ref(),
+                        self.repo.as_ref(),
+                        &adj.upc,
+                        new,
+                        now,
+                        tx,
+                    )?;
+                    adjustments.push(adj);
+                }
+                Ok(adjustments)
+            }),
+            ctx
+        );
+        info!(
+            "bulk price change: selection={:?} change={:?} products={} operator={operator_mdoc}",
+            change.selection,
+            change.change,
+            adjustments.len()
+        );
+        Ok(adjustments)
+    }
+
+    // Products whose price the change would move; none may end up below a cent.
+    fn bulk_price_lines(
+        &self,
+        change: &BulkPriceChange,
+        ctx: &str,
+    ) -> Result<Vec<BulkPriceLine>, AppError> {
+        let products = match &change.selection {
+            ProductSelection::Category(category) => try_log!(self.repo.list(), ctx)
+                .into_iter()
+                .filter(|p| &p.category == category)
+                .collect(),
+            ProductSelection::Upcs(upcs) => {
+                let mut seen = HashSet::new();
+                let mut products = vec![];
+                for upc in upcs {
+                    if seen.insert(upc.as_str()) {
+                        products.push(self.require_own_upc(upc, ctx)?);
+                    }
+                }
+                products
+            }
+        };
+        if products.is_empty() {
+            return Err(AppError::Validation(
+                "no products match the selection".into(),
+            ));
+        }
+
+        let mut lines = vec![];
+        for p in products {
+            let new = change.new_price(p.price);
+            if new < 1 {
+                return Err(AppError::Validation(format!(
+                    "{} ({}) would be priced at {new}",
+                    p.desc, p.upc
+                )));
+            }
+            if new != p.price {
+                lines.push(BulkPriceLine {
+                    upc: p.upc,
+                    desc: p.desc,
+                    category: p.category,
+                    old: p.price,
+                    new,
+                });
+            }
+        }
+        Ok(lines)
+    }
+
     pub fn list_price_adjust(&self) -> Result<Vec<PriceAdjustment>, AppError> {
         let res = try_log!(self.price_repo.list(), "ProductUseCases::list_price_adjust");
         Ok(res)
@@ -161,6 +375,7 @@ impl ProductUseCases {
         category: Option<String>,
         page: i32,
     ) -> Result<Vec<(Product, i32)>, AppError> {
+        self.apply_due_price_changes()?;
         let limit = 10;
         let offset = page.saturating_sub(1) * limit;
         let res = try_log!(
@@ -176,6 +391,7 @@ impl ProductUseCases {
         date: Option<String>,
         search: Option<String>,
     ) -> Result<Vec<(PriceAdjustment, String, String)>, AppError> {
+        self.apply_due_price_changes()?;
         let limit = 10;
         let offset = page.saturating_sub(1) * limit;
         let res = try_log!(
@@ -237,6 +453,389 @@ impl ProductUseCases {
         Ok(())
     }
 
+    pub fn set_category_limit_exempt(&self, id: i32, limit_exempt: bool) -> Result<(), AppError> {
+        try_log!(
+            self.category_repo.set_limit_exempt(id, limit_exempt),
+            "ProductUseCases::set_category_limit_exempt"
+        );
+        info!("category limit exemption set: id={id} exempt={limit_exempt}");
+        Ok(())
+    }
+
+    // None clears the product's own flag so it follows its category again.
+    pub fn set_product_limit_exempt(
+        &self,
+        upc: String,
+        limit_exempt: Option<bool>,
+    ) -> Result<(), AppError> {
+        let mut p = try_log!(
+            self.repo.get_by_upc(upc.clone()),
+            "ProductUseCases::set_product_limit_exempt"
+        )
+        .ok_or_else(|| AppError::NotFound(format!("Product {upc} not found")))?;
+        p.limit_exempt = limit_exempt;
+        p.updated = Some(Utc::now().naive_utc());
+        try_log!(
+            self.repo.update_by_upc(&p),
+            "ProductUseCases::set_product_limit_exempt"
+        );
+        info!("product limit exemption set: upc={upc} exempt={limit_exempt:?}");
+        Ok(())
+    }
+
+    // None clears the reorder point (and quantity) so the product never shows as low.
+    pub fn set_reorder_point(
+        &self,
+        upc: String,
+        reorder_point: Option<i32>,
+        reorder_quantity: Option<i32>,
+    ) -> Result<(), AppError> {
+        if reorder_point.is_some_and(|p| p < 0) {
+            return Err(AppError::Validation(
+                "reorder point cannot be negative".into(),
+            ));
+        }
+        if reorder_quantity.is_some_and(|q| q <= 0) {
+            return Err(AppError::Validation(
+                "reorder quantity must be positive".into(),
+            ));
+        }
+        if reorder_point.is_none() && reorder_quantity.is_some() {
+            return Err(AppError::Validation(
+                "a reorder quantity needs a reorder point".into(),
+            ));
+        }
+        let mut p = try_log!(
+            self.repo.get_by_upc(upc.clone()),
+            "ProductUseCases::set_reorder_point"
+        )
+        .ok_or_else(|| AppError::NotFound(format!("Product {upc} not found")))?;
+        p.reorder_point = reorder_point;
+        p.reorder_quantity = reorder_quantity;
+        p.updated = Some(Utc::now().naive_utc());
+        try_log!(
+            self.repo.update_by_upc(&p),
+            "ProductUseCases::set_reorder_point"
+        );
+        info!(
+            "product reorder point set: upc={upc} point={reorder_point:?} quantity={reorder_quantity:?}"
+        );
+        Ok(())
+    }
+
+    pub fn low_stock(&self) -> Result<Vec<LowStockItem>, AppError> {
+        let res = try_log!(self.repo.low_stock(), "ProductUseCases::low_stock");
+        Ok(res)
+    }
+
+    pub fn list_barcodes(&self, upc: String) -> Result<Vec<BarcodeAlias>, AppError> {
+        let res = try_log!(
+            self.repo.list_barcodes(Some(upc)),
+            "ProductUseCases::list_barcodes"
+        );
+        Ok(res)
+    }
+
+    // An alias must point at a product by its own upc and can't shadow
+    // another product's upc or alias.
+    pub fn add_barcode(&self, alias: BarcodeAlias) -> Result<(), AppError> {
+        if alias.multiplier < 1 {
+            return Err(AppError::Validation(
+                "a barcode stands for at least one unit".into(),
+            ));
+        }
+        if alias.barcode == alias.upc {
+            return Err(AppError::Validation(
+                "a product's own upc can't be its alias".into(),
+            ));
+        }
+        let target = self.require_own_upc(&alias.upc, "ProductUseCases::add_barcode")?;
+        if let Some((taken, _)) = try_log!(
+            self.repo.resolve_barcode(&alias.barcode),
+            "ProductUseCases::add_barcode"
+        ) {
+            return Err(AppError::Validation(format!(
+                "barcode {} already scans as {} ({})",
+                alias.barcode, taken.upc, taken.desc
+            )));
+        }
+        try_log!(
+            self.repo.add_barcode(&alias),
+            "ProductUseCases::add_barcode"
+        );
+        info!(
+            "barcode alias added: barcode={} upc={} multiplier={}",
+            alias.barcode, target.upc, alias.multiplier
+        );
+        Ok(())
+    }
+
+    pub fn remove_barcode(&self, barcode: String) -> Result<(), AppError> {
+        try_log!(
+            self.repo.remove_barcode(&barcode),
+            "ProductUseCases::remove_barcode"
+        );
+        info!("barcode alias removed: barcode={barcode}");
+        Ok(())
+    }
+
+    pub fn bundle_components(
+        &self,
+        upc: String,
+    ) -> Result<Vec<(BundleComponent, String)>, AppError> {
+        let res = try_log!(
+            self.repo.components(&upc),
+            "ProductUseCases::bundle_components"
+        );
+        Ok(res)
+    }
+
+    // Makes `upc` a kit of the given components (upc, units per kit), replacing
+    // any it had; an empty list makes it a plain product again. Components are
+    // stocked products in their own right, so kits can't hold other kits.
+    pub fn set_bundle_components(
+        &self,
+        upc: String,
+        components: Vec<(String, i32)>,
+    ) -> Result<(), AppError> {
+        let ctx = "ProductUseCases::set_bundle_components";
+        let mut seen = HashSet::new();
+        for (component, quantity) in &components {
+            if *quantity <= 0 {
+                return Err(AppError::Validation(format!(
+                    "invalid quantity for component {component}: {quantity}"
+                )));
+            }
+            if *component == upc {
+                return Err(AppError::Validation("a kit can't contain itself".into()));
+            }
+            if !seen.insert(component.as_str()) {
+                return Err(AppError::Validation(format!(
+                    "component {component} is listed more than once"
+                )));
+            }
+        }
+        let bundle = self.require_own_upc(&upc, ctx)?;
+        for (component, _) in &components {
+            self.require_own_upc(component, ctx)?;
+        }
+
+        try_log!(
+            atomic_tx(&self.conn, |tx| {
+                if !components.is_empty() {
+                    if let Some(kit) = self.repo.bundles_containing_with_tx(&upc, tx)?.first() {
+                        return Err(AppError::Validation(format!(
+                            "{upc} is a component of kit {kit} and can't be a kit itself"
+                        )));
+                    }
+                }
+                for (component, _) in &components {
+                    if !self.repo.components_with_tx(component, tx)?.is_empty() {
+                        return Err(AppError::Validation(format!(
+                            "{component} is a kit and can't be a component"
+                        )));
+                    }
+                }
+                let rows: Vec<BundleComponent> = components
+                    .iter()
+                    .map(|(component, quantity)| BundleComponent {
+                        bundle_upc: upc.clone(),
+                        component_upc: component.clone(),
+                        quantity: *quantity,
+                    })
+                    .collect();
+                self.repo.set_components_with_tx(&upc, &rows, tx)
+            }),
+            ctx
+        );
+        info!(
+            "kit components set: upc={} desc={} components={}",
+            bundle.upc,
+            bundle.desc,
+            components.len()
+        );
+        Ok(())
+    }
+
+    // Checks rows read from an import file against the catalog without
+    // writing anything.
+    pub fn preview_import(
+        &self,
+        rows: Vec<(usize, Product)>,
+    ) -> Result<ProductImportPlan, AppError> {
+        self.apply_due_price_changes()?;
+        let ctx = "ProductUseCases::preview_import";
+        let categories = self.active_category_names(ctx)?;
+        let plan = try_log!(
+            Self::plan_import(rows, &categories, |upc| self.repo.get_by_upc(upc)),
+            ctx
+        );
+        Ok(plan)
+    }
+
+    // Applies an import all at once, or nothing if any row fails. Price
+    // changes go through price adjustments by `operator_mdoc`.
+    pub fn import_products(
+        &self,
+        rows: Vec<(usize, Product)>,
+        operator_mdoc: i32,
+    ) -> Result<ProductImportPlan, AppError> {
+        let ctx = "ProductUseCases::import_products";
+        self.apply_due_price_changes()?;
+        let categories = self.active_category_names(ctx)?;
+        let plan = try_log!(
+            atomic_tx(&self.conn, |tx| {
+                let plan = Self::plan_import(rows, &categories, |upc| {
+                    self.repo.get_by_upc_with_tx(upc, tx)
+                })?;
+                if let Some(f) = plan.failures.first() {
+                    return Err(AppError::Validation(format!(
+                        "{} row(s) can't be imported; line {}: {}",
+                        plan.failures.len(),
+                        f.line,
+                        f.message
+                    )));
+                }
+                let now = Utc::now().naive_utc();
+                for l in &plan.lines {
+                    match l.action {
+                        ImportAction::Unchanged => continue,
+                        ImportAction::Create => self.repo.create_with_tx(&l.product, tx)?,
+                        ImportAction::Restore | ImportAction::Update => {
+                            self.repo.update_by_upc_with_tx(&l.product, tx)?;
+                        }
+                    }
+                    if let Some(old) = l.old_price {
+                        supersede_pending_with_tx(self.price_repo.as_ref(), &l.product.upc, tx)?;
+                        self.price_repo.create_with_tx(
+                            &PriceAdjustment {
+                                id: 0,
+                                operator_mdoc,
+                                upc: l.product.upc.clone(),
+                                old,
+                                new: l.product.price,
+                                created_at: Some(now),
+                                effective_at: None,
+                                status: PriceAdjustmentStatus::Applied,
+                            },
+                            tx,
+                        )?;
+                    }
+                }
+                Ok(plan)
+            }),
+            ctx
+        );
+        info!(
+            "products imported: created={} restored={} updated={} price_changes={} operator={operator_mdoc}",
+            plan.count(ImportAction::Create),
+            plan.count(ImportAction::Restore),
+            plan.count(ImportAction::Update),
+            plan.price_changes()
+        );
+        Ok(plan)
+    }
+
+    // Every live, priced product, for export.
+    pub fn export_products(&self) -> Result<Vec<Product>, AppError> {
+        self.apply_due_price_changes()?;
+        let res = try_log!(self.repo.list(), "ProductUseCases::export_products");
+        Ok(res)
+    }
+
+    fn active_category_names(&self, ctx: &str) -> Result<HashSet<String>, AppError> {
+        let categories = try_log!(self.category_repo.list_active(), ctx);
+        Ok(categories.into_iter().map(|c| c.name).collect())
+    }
+
+    // Decides what each row does, looking products up with `get`. A row fails
+    // on an unknown category, a upc that's another product's alternate barcode,
+    // or a upc already seen earlier in the file.
+    fn plan_import(
+        rows: Vec<(usize, Product)>,
+        categories: &HashSet<String>,
+        mut get: impl FnMut(String) -> Result<Option<Product>, AppError>,
+    ) -> Result<ProductImportPlan, AppError> {
+        let mut plan = ProductImportPlan::default();
+        let mut seen = HashSet::new();
+        let now = Some(Utc::now().naive_utc());
+        for (line, row) in rows {
+            let fail = |message: String| ImportFailure {
+                line,
+                upc: row.upc.clone(),
+                message,
+            };
+            if !seen.insert(row.upc.clone()) {
+                plan.failures
+                    .push(fail(format!("{} appears more than once", row.upc)));
+                continue;
+            }
+            if !categories.contains(&row.category) {
+                plan.failures
+                    .push(fail(format!("unknown category {}", row.category)));
+                continue;
+            }
+            let existing = get(row.upc.clone())?;
+            if let Some(p) = existing.as_ref().filter(|p| p.upc != row.upc) {
+                plan.failures.push(fail(format!(
+                    "{} is an alternate barcode for {}",
+                    row.upc, p.upc
+                )));
+                continue;
+            }
+            let (product, action, old_price) = match existing {
+                None => (
+                    Product {
+                        updated: now,
+                        added: now,
+                        deleted: None,
+                        ..row
+                    },
+                    ImportAction::Create,
+                    None,
+                ),
+                Some(p) => {
+                    let action = if p.deleted.is_some() {
+                        ImportAction::Restore
+                    } else if (&p.desc, &p.category, p.price)
+                        == (&row.desc, &row.category, row.price)
+                    {
+                        ImportAction::Unchanged
+                    } else {
+                        ImportAction::Update
+                    };
+                    let old_price = (p.price != row.price).then_some(p.price);
+                    (
+                        Product {
+                            desc: row.desc,
+                            category: row.category,
+                            price: row.price,
+                            updated: now,
+                            deleted: None,
+                            ..p
+                        },
+                        action,
+                        old_price,
+                    )
+                }
+            };
+            plan.lines.push(ProductImportLine {
+                line,
+                product,
+                action,
+                old_price,
+            });
+        }
+        Ok(plan)
+    }
+
+    // A live product looked up by its own upc, not an alias.
+    fn require_own_upc(&self, upc: &str, ctx: &str) -> Result<Product, AppError> {
+        try_log!(self.repo.get_by_upc(upc.to_string()), ctx)
+            .filter(|p| p.deleted.is_none())
+            .ok_or_else(|| AppError::NotFound(format!("Product {upc} not found")))
+    }
+
     pub fn count_products(
         &self,
         search: Option<String>,
@@ -254,7 +853,7 @@ impl ProductUseCases {
 mod tests {
     use super::*;
     use crate::application::use_cases::product_usecases::ProductUseCases;
-    use crate::domain::models::Operator;
+    use crate::domain::models::{Operator, PriceChange, PriceRounding, RoundingMode};
     use crate::domain::repos::OperatorRepoTrait;
     use crate::test_support::mock_category_repo::MockCategoryRepo;
     use crate::test_support::mock_operator_repo::MockOperatorRepo;
@@ -271,6 +870,10 @@ mod tests {
                 updated: None,
                 added: None,
                 deleted: None,
+                limit_exempt: None,
+                unit_cost: None,
+                reorder_point: None,
+                reorder_quantity: None,
             }
         }
     }
@@ -303,6 +906,10 @@ mod tests {
             updated: Some(chrono::Utc::now().naive_utc()),
             added: Some(chrono::Utc::now().naive_utc()),
             deleted: None,
+            limit_exempt: None,
+            unit_cost: None,
+            reorder_point: None,
+            reorder_quantity: None,
         })
         .unwrap();
         repo.create(&Product {
@@ -313,6 +920,10 @@ mod tests {
             updated: Some(chrono::Utc::now().naive_utc()),
             added: Some(chrono::Utc::now().naive_utc()),
             deleted: None,
+            limit_exempt: None,
+            unit_cost: None,
+            reorder_point: None,
+            reorder_quantity: None,
         })
         .unwrap();
         repo.create(&Product {
@@ -323,6 +934,10 @@ mod tests {
             updated: Some(chrono::Utc::now().naive_utc()),
             added: Some(chrono::Utc::now().naive_utc()),
             deleted: None,
+            limit_exempt: None,
+            unit_cost: None,
+            reorder_point: None,
+            reorder_quantity: None,
         })
         .unwrap();
         repo.create(&Product {
@@ -333,6 +948,10 @@ mod tests {
             updated: Some(chrono::Utc::now().naive_utc()),
             added: Some(chrono::Utc::now().naive_utc()),
             deleted: None,
+            limit_exempt: None,
+            unit_cost: None,
+            reorder_point: None,
+            reorder_quantity: None,
         })
         .unwrap();
 
@@ -379,6 +998,408 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn set_reorder_point_validates_and_survives_product_edits() -> anyhow::Result<()> {
+        let (uc, _op_repo, prod_repo) = make_use_cases();
+        uc.create_product(Product {
+            upc: "42".into(),
+            desc: "Soap".into(),
+            price: 100,
+            ..Default::default()
+        })?;
+
+        for (point, quantity) in [(Some(-1), None), (Some(5), Some(0)), (None, Some(12))] {
+            assert!(matches!(
+                uc.set_reorder_point("42".into(), point, quantity),
+                Err(AppError::Validation(_))
+            ));
+        }
+        assert!(matches!(
+            uc.set_reorder_point("nope".into(), Some(5), None),
+            Err(AppError::NotFound(_))
+        ));
+
+        uc.set_reorder_point("42".into(), Some(5), Some(24))?;
+        uc.update_product(UpdateProductDto {
+            upc: "42".into(),
+            desc: "Bar soap".into(),
+            category: String::new(),
+        })?;
+        let p = prod_repo.get_by_upc("42".into())?.unwrap();
+        assert_eq!((p.reorder_point, p.reorder_quantity), (Some(5), Some(24)));
+
+        prod_repo.set_available("42", 5);
+        assert_eq!(uc.low_stock()?.len(), 1);
+        uc.set_reorder_point("42".into(), None, None)?;
+        assert!(uc.low_stock()?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn barcode_aliases_cannot_shadow_other_codes() -> anyhow::Result<()> {
+        let (uc, _op_repo, prod_repo) = make_use_cases();
+        for upc in ["11", "22"] {
+            uc.create_product(Product {
+                upc: upc.into(),
+                desc: format!("Item {upc}"),
+                price: 100,
+                ..Default::default()
+            })?;
+        }
+        let alias = |barcode: &str, upc: &str, multiplier| BarcodeAlias {
+            barcode: barcode.into(),
+            upc: upc.into(),
+            multiplier,
+            created_at: None,
+        };
+
+        uc.add_barcode(alias("1124", "11", 24))?;
+        let (p, per_scan) = prod_repo.resolve_barcode("1124")?.unwrap();
+        assert_eq!((p.upc.as_str(), per_scan), ("11", 24));
+
+        for bad in [
+            alias("1100", "11", 0),
+            alias("11", "11", 1),
+            alias("22", "11", 1),
+            alias("1124", "22", 1),
+        ] {
+            assert!(matches!(uc.add_barcode(bad), Err(AppError::Validation(_))));
+        }
+        // a new product can't take a code that already scans as another
+        assert!(matches!(
+            uc.create_product(Product {
+                upc: "1124".into(),
+                desc: "Shadow".into(),
+                price: 100,
+                ..Default::default()
+            }),
+            Err(AppError::Validation(_))
+        ));
+        // aliases point at a product's own upc, never at another alias
+        assert!(matches!(
+            uc.add_barcode(alias("1148", "1124", 2)),
+            Err(AppError::NotFound(_))
+        ));
+        // and a new product can't take a code that's already an alias
+        assert!(uc
+            .create_product(Product {
+                upc: "1124".into(),
+                ..Default::default()
+            })
+            .is_err());
+
+        uc.remove_barcode("1124".into())?;
+        assert!(uc.list_barcodes("11".into())?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn kits_hold_plain_products_only() -> anyhow::Result<()> {
+        let (uc, _op_repo, _prod_repo) = make_use_cases();
+        for upc in ["10", "11", "12"] {
+            uc.create_product(Product {
+                upc: upc.into(),
+                desc: format!("Item {upc}"),
+                price: 100,
+                ..Default::default()
+            })?;
+        }
+
+        uc.set_bundle_components("10".into(), vec![("11".into(), 2), ("12".into(), 1)])?;
+        let parts = uc.bundle_components("10".into())?;
+        assert_eq!(parts.len(), 2);
+        assert!(parts
+            .iter()
+            .any(|(c, desc)| c.component_upc == "11" && c.quantity == 2 && desc == "Item 11"));
+
+        for bad in [
+            vec![("11".to_string(), 0)],
+            vec![("10".to_string(), 1)],
+            vec![("11".to_string(), 1), ("11".to_string(), 2)],
+        ] {
+            assert!(matches!(
+                uc.set_bundle_components("10".into(), bad),
+                Err(AppError::Validation(_))
+            ));
+        }
+        assert!(matches!(
+            uc.set_bundle_components("10".into(), vec![("99".into(), 1)]),
+            Err(AppError::NotFound(_))
+        ));
+        // no nesting either way
+        assert!(matches!(
+            uc.set_bundle_components("12".into(), vec![("10".into(), 1)]),
+            Err(AppError::Validation(_))
+        ));
+        assert!(matches!(
+            uc.set_bundle_components("11".into(), vec![("12".into(), 1)]),
+            Err(AppError::Validation(_))
+        ));
+
+        uc.set_bundle_components("10".into(), vec![])?;
+        assert!(uc.bundle_components("10".into())?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn import_previews_then_applies_all_or_nothing() -> anyhow::Result<()> {
+        let (uc, _op_repo, prod_repo) = make_use_cases();
+        uc.create_category("Hygiene".into())?;
+        uc.create_category("Snacks".into())?;
+        for (upc, desc, category, price) in [
+            ("00000011", "Soap", "Hygiene", 100),
+            ("00000022", "Chips", "Snacks", 150),
+            ("00000033", "Gum", "Snacks", 50),
+        ] {
+            uc.create_product(Product {
+                upc: upc.into(),
+                desc: desc.into(),
+                category: category.into(),
+                price,
+                ..Default::default()
+            })?;
+        }
+        uc.delete_product("00000022".into())?;
+        let row = |line, upc: &str, desc: &str, category: &str, price| {
+            (
+                line,
+                Product {
+                    upc: upc.into(),
+                    desc: desc.into(),
+                    category: category.into(),
+                    price,
+                    ..Default::default()
+                },
+            )
+        };
+        let good = vec![
+            row(2, "00000011", "Soap", "Hygiene", 120),
+            row(3, "00000022", "Chips", "Snacks", 150),
+            row(4, "00000033", "Gum", "Snacks", 50),
+            row(5, "00000044", "Candy", "Snacks", 75),
+        ];
+        let price_of = |upc: &str| prod_repo.get_price(upc.into()).unwrap();
+
+        let plan = uc.preview_import(good.clone())?;
+        let actions: Vec<_> = plan.lines.iter().map(|l| l.action).collect();
+        assert_eq!(
+            actions,
+            vec![
+                ImportAction::Update,
+                ImportAction::Restore,
+                ImportAction::Unchanged,
+                ImportAction::Create
+            ]
+        );
+        assert_eq!(plan.lines[0].old_price, Some(100));
+        assert_eq!(plan.price_changes(), 1);
+        assert!(plan.failures.is_empty());
+        assert_eq!(price_of("00000011"), 100);
+
+        let mut bad = good.clone();
+        bad.push(row(6, "00000055", "Bread", "Bakery", 300));
+        bad.push(row(7, "00000044", "Candy", "Snacks", 80));
+        let failed: Vec<_> = uc
+            .preview_import(bad.clone())?
+            .failures
+            .into_iter()
+            .map(|f| f.line)
+            .collect();
+        assert_eq!(failed, vec![6, 7]);
+        assert!(matches!(
+            uc.import_products(bad, 1),
+            Err(AppError::Validation(_))
+        ));
+        assert_eq!(price_of("00000011"), 100);
+        assert!(prod_repo.get_by_upc("00000044".into())?.is_none());
+
+        uc.import_products(good, 1)?;
+        assert_eq!(price_of("00000011"), 120);
+        assert!(prod_repo
+            .get_by_upc("00000022".into())?
+            .unwrap()
+            .deleted
+            .is_none());
+        assert_eq!(
+            prod_repo.get_by_upc("00000044".into())?.unwrap().desc,
+            "Candy"
+        );
+        let adjustments = uc.list_price_adjust()?;
+        assert_eq!(adjustments.len(), 1);
+        assert_eq!(
+            (
+                adjustments[0].upc.as_str(),
+                adjustments[0].old,
+                adjustments[0].new
+            ),
+            ("00000011", 100, 120)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn future_price_changes_wait_as_pending() -> anyhow::Result<()> {
+        let (uc, _op_repo, prod_repo) = make_use_cases();
+        uc.create_product(Product {
+            upc: "7".into(),
+            price: 1234,
+            ..Default::default()
+        })?;
+
+        let scheduled = uc.price_adjustment(PriceAdjustment {
+            id: 0,
+            operator_mdoc: 1,
+            upc: "7".into(),
+            old: 999, // stale; the current price is recorded instead
+            new: 1500,
+            created_at: None,
+            effective_at: Some(Utc::now().naive_utc() + chrono::Duration::days(7)),
+            status: PriceAdjustmentStatus::Applied,
+        })?;
+        assert_eq!(scheduled.status, PriceAdjustmentStatus::Pending);
+        assert_eq!(scheduled.old, 1234);
+        assert_eq!(prod_repo.get_price("7".into())?, 1234);
+
+        let pending = uc.list_pending_price_changes()?;
+        assert_eq!(pending.len(), 1);
+        uc.cancel_price_change(pending[0].id)?;
+        assert!(uc.list_pending_price_changes()?.is_empty());
+        assert!(matches!(
+            uc.cancel_price_change(pending[0].id),
+            Err(AppError::NotFound(_))
+        ));
+
+        // a time already past is simply applied now
+        let now = uc.price_adjustment(PriceAdjustment {
+            id: 0,
+            operator_mdoc: 1,
+            upc: "7".into(),
+            old: 1234,
+            new: 1300,
+            created_at: None,
+            effective_at: Some(Utc::now().naive_utc() - chrono::Duration::hours(1)),
+            status: PriceAdjustmentStatus::Pending,
+        });
+        assert!(now.is_ok());
+        assert_eq!(prod_repo.get_price("7".into())?, 1300);
+        Ok(())
+    }
+
+    #[test]
+    fn setting_a_price_now_supersedes_scheduled_changes() -> anyhow::Result<()> {
+        let (uc, _op_repo, prod_repo) = make_use_cases();
+        uc.create_category("Snacks".into())?;
+        uc.create_product(Product {
+            upc: "00000011".into(),
+            desc: "Chips".into(),
+            category: "Snacks".into(),
+            price: 100,
+            ..Default::default()
+        })?;
+        let change = |new, effective_at| PriceAdjustment {
+            id: 0,
+            operator_mdoc: 1,
+            upc: "00000011".into(),
+            old: 0,
+            new,
+            created_at: None,
+            effective_at,
+            status: PriceAdjustmentStatus::Pending,
+        };
+        let next_week = Some(Utc::now().naive_utc() + chrono::Duration::days(7));
+
+        uc.price_adjustment(change(150, next_week))?;
+        let applied = uc.price_adjustment(change(120, None))?;
+        assert_eq!(applied.old, 100);
+        assert_eq!(prod_repo.get_price("00000011".into())?, 120);
+        assert!(uc.list_pending_price_changes()?.is_empty());
+
+        uc.price_adjustment(change(150, next_week))?;
+        uc.import_products(
+            vec![(
+                2,
+                Product {
+                    upc: "00000011".into(),
+                    desc: "Chips".into(),
+                    category: "Snacks".into(),
+                    price: 130,
+                    ..Default::default()
+                },
+            )],
+            1,
+        )?;
+        assert_eq!(prod_repo.get_price("00000011".into())?, 130);
+        assert!(uc.list_pending_price_changes()?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn bulk_price_change_previews_then_reprices_each_product() -> anyhow::Result<()> {
+        let (uc, _op_repo, prod_repo) = make_use_cases();
+        for (upc, category, price) in [
+            ("1", "Snacks", 100),
+            ("2", "Snacks", 199),
+            ("3", "Soap", 250),
+        ] {
+            uc.create_product(Product {
+                upc: upc.into(),
+                desc: format!("item {upc}"),
+                category: category.into(),
+                price,
+                ..Default::default()
+            })?;
+        }
+        let mut change = BulkPriceChange {
+            selection: ProductSelection::Category("Snacks".into()),
+            change: PriceChange::Percent(10.0),
+            rounding: PriceRounding {
+                step: 5,
+                mode: RoundingMode::Up,
+            },
+        };
+
+        let preview = uc.preview_bulk_price_change(&change)?;
+        let prices: Vec<_> = preview.iter().map(|l| (l.old, l.new)).collect();
+        assert_eq!(prices, vec![(100, 110), (199, 220)]);
+        assert_eq!(prod_repo.get_price("1".into())?, 100);
+        assert!(uc.list_price_adjust()?.is_empty());
+
+        // a bulk change sets prices now, so it replaces what was scheduled
+        uc.price_adjustment(PriceAdjustment {
+            id: 0,
+            operator_mdoc: 1,
+            upc: "1".into(),
+            old: 100,
+            new: 90,
+            created_at: None,
+            effective_at: Some(Utc::now().naive_utc() + chrono::Duration::days(7)),
+            status: PriceAdjustmentStatus::Pending,
+        })?;
+        let saved = uc.bulk_price_change(&change, 1)?;
+        assert_eq!(saved.len(), 2);
+        assert_eq!(prod_repo.get_price("2".into())?, 220);
+        assert_eq!(prod_repo.get_price("3".into())?, 250);
+        assert_eq!(uc.list_price_adjust()?.len(), 3);
+        assert!(uc.list_pending_price_changes()?.is_empty());
+
+        // products already at the new price are left out
+        change.selection = ProductSelection::Upcs(vec!["3".into(), "1".into(), "3".into()]);
+        change.change = PriceChange::Amount(0);
+        assert!(uc.preview_bulk_price_change(&change)?.is_empty());
+
+        change.change = PriceChange::Amount(-200);
+        change.rounding.step = 1;
+        assert!(matches!(
+            uc.bulk_price_change(&change, 1),
+            Err(AppError::Validation(_))
+        ));
+        assert_eq!(prod_repo.get_price("3".into())?, 250);
+
+        change.selection = ProductSelection::Category("Nothing".into());
+        assert!(uc.preview_bulk_price_change(&change).is_err());
+        Ok(())
+    }
+
     #[test]
     fn price_adjustment_round_trip() -> anyhow::Result<()> {
         let (uc, operator_repo, _product_repo) = make_use_cases();
@@ -408,6 +1429,8 @@ mod tests {
             old: 1234,
             new: 2000,
             created_at: Some(Utc::now().naive_utc()),
+            effective_at: None,
+            status: PriceAdjustmentStatus::Applied,
         })?;
         assert_eq!(adj.upc, "7");
         assert_eq!(adj.old, 1234);
@@ -0,0 +1,364 @@
+use crate::common::error::AppError;
+use crate::domain::models::{CustomerTxDetail, LineDiscount, Product, Promotion};
+use crate::domain::repos::{ProductRepoTrait, PromotionRepoTrait};
+use crate::try_log;
+use chrono::{NaiveDateTime, Utc};
+use log::info;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+
+// Owns promotions and decides what each sale line gets off. Every product in
+// a sale gets the single running promotion worth the most on all its units;
+// promotions don't stack.
+pub struct PromotionUseCases {
+    promo_repo: Arc<dyn PromotionRepoTrait>,
+    prod_repo: Arc<dyn ProductRepoTrait>,
+}
+
+impl PromotionUseCases {
+    pub fn new(
+        promo_repo: Arc<dyn PromotionRepoTrait>,
+        prod_repo: Arc<dyn ProductRepoTrait>,
+    ) -> Self {
+        Self {
+            promo_repo,
+            prod_repo,
+        }
+    }
+
+    pub fn list_promotions(&self) -> Result<Vec<Promotion>, AppError> {
+        Ok(try_log!(
+            self.promo_repo.list(),
+            "PromotionUseCases::list_promotions"
+        ))
+    }
+
+    pub fn create_promotion(&self, mut promotion: Promotion) -> Result<i32, AppError> {
+        promotion.name = promotion.name.trim().to_string();
+        promotion.upc = promotion
+            .upc
+            .map(|u| u.trim().to_string())
+            .filter(|u| !u.is_empty());
+        promotion.category = promotion
+            .category
+            .map(|c| c.trim().to_string())
+            .filter(|c| !c.is_empty());
+        if promotion.name.is_empty() {
+            return Err(AppError::Validation("a promotion needs a name".into()));
+        }
+        if promotion.upc.is_some() == promotion.category.is_some() {
+            return Err(AppError::Validation(
+                "a promotion applies to either a upc or a category".into(),
+            ));
+        }
+        if let (Some(start), Some(end)) = (promotion.starts_at, promotion.ends_at) {
+            if end <= start {
+                return Err(AppError::Validation(
+                    "a promotion must end after it starts".into(),
+                ));
+            }
+        }
+        promotion.rule.check()?;
+        if let Some(upc) = &promotion.upc {
+            try_log!(
+                self.prod_repo.get_by_upc(upc.clone()),
+                "PromotionUseCases::create_promotion"
+            )
+            .filter(|p| p.deleted.is_none())
+            .ok_or_else(|| AppError::NotFound(format!("product {upc} not found")))?;
+        }
+        let id = try_log!(
+            self.promo_repo.create(&promotion),
+            "PromotionUseCases::create_promotion"
+        );
+        info!(
+            "promotion created: id={id} name={} rule={:?} upc={:?} category={:?}",
+            promotion.name, promotion.rule, promotion.upc, promotion.category
+        );
+        Ok(id)
+    }
+
+    pub fn delete_promotion(&self, id: i32) -> Result<(), AppError> {
+        try_log!(
+            self.promo_repo.delete(id),
+            "PromotionUseCases::delete_promotion"
+        );
+        info!("promotion deleted: id={id}");
+        Ok(())
+    }
+
+    // What the running promotions would take off each line, for the POS to
+    // show before the sale is submitted. Lines with no discount are left out.
+    pub fn quote(&self, lines: &[(String, i32)]) -> Result<Vec<LineDiscount>, AppError> {
+        let running = try_log!(
+            self.promo_repo.running(Utc::now().naive_utc()),
+            "PromotionUseCases::quote"
+        );
+        let mut products = HashMap::new();
+        for (upc, _) in lines {
+            if products.contains_key(upc) {
+                continue;
+            }
+            if let Some(product) = try_log!(
+                self.prod_repo.get_by_upc(upc.clone()),
+                "PromotionUseCases::quote"
+            ) {
+                products.insert(upc.clone(), product);
+            }
+        }
+        let quantities: Vec<(&str, i32)> = lines.iter().map(|(u, q)| (u.as_str(), *q)).collect();
+        Ok(Self::price_lines(&running, &products, &quantities)
+            .into_iter()
+            .zip(lines)
+            .filter_map(|(priced, (upc, _))| {
+                priced.map(|(promo, discount)| LineDiscount {
+                    upc: upc.clone(),
+                    promotion_id: promo.id,
+                    promotion: promo.name.clone(),
+                    discount,
+                })
+            })
+            .collect())
+    }
+
+    // Prices the sale's lines against the promotions running at `now`, setting
+    // each line's discount and promotion, with the promotion's name as it
+    // reads now. Whatever the client sent is replaced.
+    pub fn apply_with_tx(
+        &self,
+        details: &mut [CustomerTxDetail],
+        now: NaiveDateTime,
+        tx: &rusqlite::Transaction<'_>,
+    ) -> Result<(), AppError> {
+        for det in details.iter_mut() {
+            det.discount = 0;
+            det.promotion_id = None;
+            det.promotion_name = None;
+        }
+        let running = self.promo_repo.running_with_tx(now, tx)?;
+        if running.is_empty() {
+            return Ok(());
+        }
+        // an unknown product is rejected when the sale is validated
+        let mut products = HashMap::new();
+        for det in details.iter() {
+            if products.contains_key(&det.upc) {
+                continue;
+            }
+            if let Some(product) = self.prod_repo.get_by_upc_with_tx(det.upc.clone(), tx)? {
+                products.insert(det.upc.clone(), product);
+            }
+        }
+        let quantities: Vec<(&str, i32)> = details
+            .iter()
+            .map(|d| (d.upc.as_str(), d.quantity))
+            .collect();
+        let priced = Self::price_lines(&running, &products, &quantities);
+        for (det, priced) in details.iter_mut().zip(priced) {
+            if let Some((promo, discount)) = priced {
+                det.discount = discount;
+                det.promotion_id = Some(promo.id);
+                det.promotion_name = Some(promo.name.clone());
+            }
+        }
+        Ok(())
+    }
+
+    // Each line's promotion and discount. Lines of the same upc are priced as
+    // one, so a buy/get deal counts units scanned on separate lines, and the
+    // discount is spread back over them by quantity.
+    fn price_lines<'a>(
+        running: &'a [Promotion],
+        products: &HashMap<String, Product>,
+        lines: &[(&str, i32)],
+    ) -> Vec<Option<(&'a Promotion, i32)>> {
+        let mut by_upc: BTreeMap<&str, Vec<usize>> = BTreeMap::new();
+        for (i, (upc, _)) in lines.iter().enumerate() {
+            by_upc.entry(upc).or_default().push(i);
+        }
+        let mut out = vec![None; lines.len()];
+        for (upc, idx) in by_upc {
+            let Some(product) = products.get(upc) else {
+                continue;
+            };
+            let total: i32 = idx.iter().map(|&i| lines[i].1).sum();
+            let Some((promo, discount)) = Self::best(running, product, total) else {
+                continue;
+            };
+            // rounded on the running total, so no line gets more than its
+            // own units are worth and the shares add up to the discount
+            let (mut units, mut given) = (0, 0);
+            for i in idx {
+                units += lines[i].1;
+                let upto = (i64::from(discount) * i64::from(units) / i64::from(total)) as i32;
+                if upto > given {
+                    out[i] = Some((promo, upto - given));
+                }
+                given = upto;
+            }
+        }
+        out
+    }
+
+    // The promotion worth the most on `quantity` of `product`, and what it
+    // takes off; the earliest created wins a tie.
+    fn best<'a>(
+        running: &'a [Promotion],
+        product: &Product,
+        quantity: i32,
+    ) -> Option<(&'a Promotion, i32)> {
+        running
+            .iter()
+            .filter(|p| p.applies_to(product))
+            .map(|p| (p, p.rule.discount(product.price, quantity)))
+            .filter(|(_, discount)| *discount > 0)
+            .fold(None, |best, (p, d)| match best {
+                Some((_, bd)) if bd >= d => best,
+                _ => Some((p, d)),
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::models::PromotionRule;
+    use crate::test_support::mock_product_repo::MockProductRepo;
+    use crate::test_support::mock_promotion_repo::MockPromotionRepo;
+    use chrono::Duration;
+
+    fn promotion(name: &str, rule: PromotionRule, upc: Option<&str>) -> Promotion {
+        Promotion {
+            id: 0,
+            name: name.into(),
+            rule,
+            upc: upc.map(Into::into),
+            category: upc.is_none().then(|| "Snacks".to_string()),
+            starts_at: None,
+            ends_at: None,
+            created_at: None,
+            deleted: None,
+        }
+    }
+
+    #[test]
+    fn lines_get_the_best_running_promotion() -> Result<(), AppError> {
+        let prod_repo = Arc::new(MockProductRepo::new());
+        for (upc, category) in [("CHIPS", "Snacks"), ("SOAP", "Hygiene")] {
+            prod_repo.create(&Product {
+                upc: upc.into(),
+                desc: upc.into(),
+                category: category.into(),
+                price: 150,
+                updated: None,
+                added: None,
+                deleted: None,
+                limit_exempt: None,
+                unit_cost: None,
+                reorder_point: None,
+                reorder_quantity: None,
+            })?;
+        }
+        let uc = PromotionUseCases::new(Arc::new(MockPromotionRepo::new()), prod_repo);
+
+        uc.create_promotion(promotion(
+            "Snacks 10% off",
+            PromotionRule::PercentOff(10),
+            None,
+        ))?;
+        let b2g1 = uc.create_promotion(promotion(
+            "Chips 2+1",
+            PromotionRule::BuyGet { buy: 2, get: 1 },
+            Some("CHIPS"),
+        ))?;
+        let mut later = promotion("Later", PromotionRule::SalePrice(1), Some("SOAP"));
+        later.starts_at = Some(Utc::now().naive_utc() + Duration::days(1));
+        uc.create_promotion(later)?;
+
+        // one bag: only the percentage applies; three bags: the free one is worth more
+        let quote = uc.quote(&[("CHIPS".into(), 1), ("SOAP".into(), 1)])?;
+        assert_eq!(quote.len(), 1);
+        assert_eq!(
+            (quote[0].promotion.as_str(), quote[0].discount),
+            ("Snacks 10% off", 15)
+        );
+        let quote = uc.quote(&[("CHIPS".into(), 3)])?;
+        assert_eq!((quote[0].promotion_id, quote[0].discount), (b2g1, 150));
+
+        assert!(uc
+            .create_promotion(promotion(
+                "Nowhere",
+                PromotionRule::AmountOff(5),
+                Some("NOPE")
+            ))
+            .is_err());
+        let mut both = promotion("Both", PromotionRule::AmountOff(5), Some("SOAP"));
+        both.category = Some("Hygiene".into());
+        assert!(uc.create_promotion(both).is_err());
+
+        // bags scanned one at a time still make a group
+        let quote = uc.quote(&[("CHIPS".into(), 1), ("SOAP".into(), 1), ("CHIPS".into(), 2)])?;
+        let shares: Vec<_> = quote.iter().map(|q| (q.upc.as_str(), q.discount)).collect();
+        assert_eq!(shares, vec![("CHIPS", 50), ("CHIPS", 100)]);
+
+        uc.delete_promotion(b2g1)?;
+        assert_eq!(uc.quote(&[("CHIPS".into(), 3)])?[0].discount, 45);
+        assert_eq!(uc.list_promotions()?.len(), 2);
+        Ok(())
+    }
+
+    #[test]
+    fn a_deal_spans_separate_lines_of_the_sale() -> Result<(), AppError> {
+        let prod_repo = Arc::new(MockProductRepo::new());
+        prod_repo.create(&Product {
+            upc: "CHIPS".into(),
+            category: "Snacks".into(),
+            price: 150,
+            ..Default::default()
+        })?;
+        let uc = PromotionUseCases::new(Arc::new(MockPromotionRepo::new()), prod_repo);
+        let b2g1 = uc.create_promotion(promotion(
+            "Chips 2+1",
+            PromotionRule::BuyGet { buy: 2, get: 1 },
+            Some("CHIPS"),
+        ))?;
+
+        let line = |quantity| CustomerTxDetail {
+            detail_id: 0,
+            order_id: 0,
+            upc: "CHIPS".into(),
+            quantity,
+            price: 150,
+            cost: None,
+            discount: 0,
+            promotion_id: None,
+            promotion_name: None,
+            tax: 0,
+            tax_rate: None,
+            limit_exempt: false,
+        };
+        let mut details = vec![line(1), line(1), line(1), line(3)];
+        let mut conn = rusqlite::Connection::open_in_memory()?;
+        let tx = conn.transaction()?;
+        uc.apply_with_tx(&mut details, Utc::now().naive_utc(), &tx)?;
+
+        // six bags are two free ones, spread over the lines by quantity
+        let got: Vec<_> = details
+            .iter()
+            .map(|d| (d.discount, d.promotion_id))
+            .collect();
+        assert_eq!(
+            got,
+            vec![
+                (50, Some(b2g1)),
+                (50, Some(b2g1)),
+                (50, Some(b2g1)),
+                (150, Some(b2g1))
+            ]
+        );
+        assert!(details
+            .iter()
+            .all(|d| d.promotion_name.as_deref() == Some("Chips 2+1")));
+        Ok(())
+    }
+}
@@ -0,0 +1,693 @@
+use crate::application::common::costing::apply_receipt_cost_with_tx;
+use crate::application::common::db::atomic_tx;
+use crate::common::error::AppError;
+use crate::domain::models::{
+    per_unit_cost, InventoryReason, InventoryTransaction, PurchaseOrder, PurchaseOrderLine,
+    PurchaseOrderStatus, ReceivedItem, Vendor,
+};
+use crate::domain::report_models::receiving_report::{ReceivingReport, ReceivingReportLine};
+use crate::domain::repos::purchase_order_repo_trait::PurchaseOrderRow;
+use crate::domain::repos::{
+    InventoryTransactionRepoTrait, ProductRepoTrait, PurchaseOrderRepoTrait, VendorRepoTrait,
+};
+use crate::infrastructure::printing::reports::receiving_report::print_receiving_report;
+use crate::try_log;
+use chrono::Utc;
+use log::info;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+
+// Vendors, purchase orders and receiving deliveries against them. Receiving
+// posts stock the same way an inventory adjustment does, tagged with the PO.
+pub struct PurchasingUseCases {
+    vendor_repo: Arc<dyn VendorRepoTrait>,
+    po_repo: Arc<dyn PurchaseOrderRepoTrait>,
+    inv_repo: Arc<dyn InventoryTransactionRepoTrait>,
+    prod_repo: Arc<dyn ProductRepoTrait>,
+    conn: Arc<Mutex<rusqlite::Connection>>,
+}
+
+impl PurchasingUseCases {
+    pub fn new(
+        vendor_repo: Arc<dyn VendorRepoTrait>,
+        po_repo: Arc<dyn PurchaseOrderRepoTrait>,
+        inv_repo: Arc<dyn InventoryTransactionRepoTrait>,
+        prod_repo: Arc<dyn ProductRepoTrait>,
+        conn: Arc<Mutex<rusqlite::Connection>>,
+    ) -> Self {
+        Self {
+            vendor_repo,
+            po_repo,
+            inv_repo,
+            prod_repo,
+            conn,
+        }
+    }
+
+    pub fn list_vendors(&self) -> Result<Vec<Vendor>, AppError> {
+        let res = try_log!(self.vendor_repo.list(), "PurchasingUseCases::list_vendors");
+        Ok(res)
+    }
+
+    pub fn create_vendor(&self, vendor: &Vendor) -> Result<i32, AppError> {
+        if vendor.name.trim().is_empty() {
+            return Err(AppError::Validation("vendor name is required".into()));
+        }
+        let id = try_log!(
+            self.vendor_repo.create(vendor),
+            "PurchasingUseCases::create_vendor"
+        );
+        info!("vendor created: id={} name={}", id, vendor.name);
+        Ok(id)
+    }
+
+    pub fn update_vendor(&self, vendor: &Vendor) -> Result<(), AppError> {
+        if vendor.name.trim().is_empty() {
+            return Err(AppError::Validation("vendor name is required".into()));
+        }
+        try_log!(
+            self.vendor_repo.update(vendor),
+            "PurchasingUseCases::update_vendor"
+        );
+        info!("vendor updated: id={} name={}", vendor.id, vendor.name);
+        Ok(())
+    }
+
+    pub fn delete_vendor(&self, id: i32) -> Result<(), AppError> {
+        try_log!(
+            self.vendor_repo.delete(id),
+            "PurchasingUseCases::delete_vendor"
+        );
+        info!("vendor deleted: id={id}");
+        Ok(())
+    }
+
+    pub fn create_purchase_order(
+        &self,
+        vendor_id: i32,
+        operator_mdoc: i32,
+        reference: Option<String>,
+        lines: Vec<ReceivedItem>,
+    ) -> Result<i32, AppError> {
+        let lines = self.to_units(lines, "PurchasingUseCases::create_purchase_order")?;
+        let vendor = try_log!(
+            self.vendor_repo.get(vendor_id),
+            "PurchasingUseCases::create_purchase_order"
+        )
+        .filter(|v| v.deleted.is_none())
+        .ok_or_else(|| AppError::NotFound(format!("vendor {vendor_id} not found")))?;
+        if lines.is_empty() {
+            return Err(AppError::Validation(
+                "a purchase order needs at least one line".into(),
+            ));
+        }
+        let mut seen = HashSet::new();
+        for line in &lines {
+            if line.quantity <= 0 {
+                return Err(AppError::Validation(format!(
+                    "invalid quantity for upc {}: {}",
+                    line.upc, line.quantity
+                )));
+            }
+            if !seen.insert(line.upc.as_str()) {
+                return Err(AppError::Validation(format!(
+                    "upc {} is listed more than once",
+                    line.upc
+                )));
+            }
+            self.require_product(&line.upc, "PurchasingUseCases::create_purchase_order")?;
+        }
+        let reference = reference
+            .map(|r| r.trim().to_string())
+            .filter(|r| !r.is_empty());
+
+        let po_id = try_log!(
+            atomic_tx(&self.conn, |tx| {
+                let po_id = self.po_repo.create_with_tx(
+                    &PurchaseOrder {
+                        id: 0,
+                        vendor_id,
+                        reference: reference.clone(),
+                        status: PurchaseOrderStatus::Open,
+                        operator_mdoc,
+                        created_at: None,
+                        received_at: None,
+                    },
+                    tx,
+                )?;
+                for line in &lines {
+                    self.po_repo.add_line_with_tx(
+                        &PurchaseOrderLine {
+                            id: 0,
+                            po_id,
+                            upc: line.upc.clone(),
+                            expected_quantity: line.quantity,
+                            received_quantity: 0,
+                        },
+                        tx,
+                    )?;
+                }
+                Ok(po_id)
+            }),
+            "PurchasingUseCases::create_purchase_order"
+        );
+        info!(
+            "purchase order created: id={} vendor={} operator={} lines={}",
+            po_id,
+            vendor.name,
+            operator_mdoc,
+            lines.len()
+        );
+        Ok(po_id)
+    }
+
+    pub fn list_purchase_orders(
+        &self,
+        status: Option<PurchaseOrderStatus>,
+    ) -> Result<Vec<PurchaseOrderRow>, AppError> {
+        let res = try_log!(
+            self.po_repo.list(status),
+            "PurchasingUseCases::list_purchase_orders"
+        );
+        Ok(res)
+    }
+
+    pub fn cancel_purchase_order(&self, po_id: i32) -> Result<(), AppError> {
+        try_log!(
+            atomic_tx(&self.conn, |tx| {
+                self.require_open_with_tx(po_id, "cancelled", tx)?;
+                self.po_repo
+                    .close_with_tx(po_id, PurchaseOrderStatus::Cancelled, None, tx)
+            }),
+            "PurchasingUseCases::cancel_purchase_order"
+        );
+        info!("purchase order cancelled: id={po_id}");
+        Ok(())
+    }
+
+    // Posts one delivery. Every received quantity becomes an inventory
+    // transaction linked to the PO, all in a single transaction; items that
+    // weren't ordered are added as lines expecting 0 so they show as over.
+    // The PO closes once every line has been received in full.
+    pub fn receive_purchase_order(
+        &self,
+        po_id: i32,
+        operator_mdoc: i32,
+        items: Vec<ReceivedItem>,
+    ) -> Result<ReceivingReport, AppError> {
+        let po = self.require_po(po_id, "PurchasingUseCases::receive_purchase_order")?;
+        let items = self.to_units(items, "PurchasingUseCases::receive_purchase_order")?;
+        require_open(&po, "received")?;
+        let mut seen = HashSet::new();
+        for item in &items {
+            if item.quantity < 0 {
+                return Err(AppError::Validation(format!(
+                    "invalid quantity for upc {}: {}",
+                    item.upc, item.quantity
+                )));
+            }
+            if item.unit_cost.is_some_and(|c| c < 0) {
+                return Err(AppError::Validation(format!(
+                    "invalid unit cost for upc {}",
+                    item.upc
+                )));
+            }
+            if !seen.insert(item.upc.as_str()) {
+                return Err(AppError::Validation(format!(
+                    "upc {} is listed more than once",
+                    item.upc
+                )));
+            }
+        }
+        if items.iter().all(|i| i.quantity == 0) {
+            return Err(AppError::Validation("nothing was received".into()));
+        }
+        let ordered: HashSet<String> = try_log!(
+            self.po_repo.lines(po_id),
+            "PurchasingUseCases::receive_purchase_order"
+        )
+        .into_iter()
+        .map(|(l, _)| l.upc)
+        .collect();
+        for item in items.iter().filter(|i| !ordered.contains(&i.upc)) {
+            self.require_product(&item.upc, "PurchasingUseCases::receive_purchase_order")?;
+        }
+
+        try_log!(
+            atomic_tx(&self.conn, |tx| {
+                // Checked again here so a double-submitted delivery can't
+                // land on a PO that another receipt has just closed.
+                self.require_open_with_tx(po_id, "received", tx)?;
+                let now = Utc::now().naive_utc();
+                let mut line_ids: HashMap<String, i32> = self
+                    .po_repo
+                    .lines_with_tx(po_id, tx)?
+                    .into_iter()
+                    .map(|l| (l.upc, l.id))
+                    .collect();
+                for item in items.iter().filter(|i| i.quantity > 0) {
+                    let line_id = match line_ids.get(&item.upc) {
+                        Some(id) => *id,
+                        None => {
+                            let id = self.po_repo.add_line_with_tx(
+                                &PurchaseOrderLine {
+                                    id: 0,
+                                    po_id,
+                                    upc: item.upc.clone(),
+                                    expected_quantity: 0,
+                                    received_quantity: 0,
+                                },
+                                tx,
+                            )?;
+                            line_ids.insert(item.upc.clone(), id);
+                            id
+                        }
+                    };
+                    self.po_repo
+                        .add_received_with_tx(line_id, item.quantity, tx)?;
+                    if let Some(cost) = item.unit_cost {
+                        apply_receipt_cost_with_tx(
+                            self.prod_repo.as_ref(),
+                            self.inv_repo.as_ref(),
+                            &item.upc,
+                            item.quantity,
+                            cost,
+                            tx,
+                        )?;
+                    }
+                    let lot_id = item
+                        .lot
+                        .as_ref()
+                        .map(|l| self.inv_repo.lot_id_with_tx(&item.upc, l, tx))
+                        .transpose()?;
+                    self.inv_repo.create_with_tx(
+                        &InventoryTransaction {
+                            id: None,
+                            upc: item.upc.clone(),
+                            quantity_change: item.quantity,
+                            operator_mdoc,
+                            customer_mdoc: None,
+                            ref_order_id: None,
+                            reference: Some(format!("PO #{po_id}")),
+                            created_at: Some(now),
+                            po_id: Some(po_id),
+                            unit_cost: item.unit_cost,
+                            stock_take_id: None,
+                            reason: InventoryReason::Receipt,
+                            lot_id,
+                        },
+                        tx,
+                    )?;
+                }
+                let complete = self
+                    .po_repo
+                    .lines_with_tx(po_id, tx)?
+                    .iter()
+                    .all(|l| l.received_quantity >= l.expected_quantity);
+                if complete {
+                    self.po_repo.close_with_tx(
+                        po_id,
+                        PurchaseOrderStatus::Received,
+                        Some(now),
+                        tx,
+                    )?;
+                }
+                Ok(())
+            }),
+            "PurchasingUseCases::receive_purchase_order"
+        );
+        info!(
+            "purchase order received: id={} operator={} items={}",
+            po_id,
+            operator_mdoc,
+            items.len()
+        );
+
+        let received_now: HashMap<String, i32> =
+            items.into_iter().map(|i| (i.upc, i.quantity)).collect();
+        self.build_report(po_id, &received_now)
+    }
+
+    // The current state of a PO, as printed on a receiving report.
+    pub fn receiving_report(&self, po_id: i32) -> Result<ReceivingReport, AppError> {
+        self.build_report(po_id, &HashMap::new())
+    }
+
+    pub fn print_receiving_report(
+        &self,
+        report: &ReceivingReport,
+        printer_name: &str,
+        sumatra_location: &str,
+    ) -> Result<(), AppError> {
+        try_log!(
+            print_receiving_report(report, printer_name, sumatra_location),
+            "PurchasingUseCases::print_receiving_report"
+        );
+        Ok(())
+    }
+
+    fn build_report(
+        &self,
+        po_id: i32,
+        received_now: &HashMap<String, i32>,
+    ) -> Result<ReceivingReport, AppError> {
+        let po = self.require_po(po_id, "PurchasingUseCases::build_report")?;
+        let vendor_name = try_log!(
+            self.vendor_repo.get(po.vendor_id),
+            "PurchasingUseCases::build_report"
+        )
+        .map(|v| v.name)
+        .unwrap_or_default();
+        let lines = try_log!(
+            self.po_repo.lines(po_id),
+            "PurchasingUseCases::build_report"
+        )
+        .into_iter()
+        .map(|(line, product_name)| ReceivingReportLine {
+            received_now: received_now.get(&line.upc).copied().unwrap_or(0),
+            line,
+            product_name,
+        })
+        .collect();
+        Ok(ReceivingReport {
+            po,
+            vendor_name,
+            lines,
+        })
+    }
+
+    // Rewrites lines keyed by a barcode alias onto the product's own upc, in
+    // units: a case's quantity is multiplied out and its cost split per unit
+    // (to the nearest cent). Unknown codes pass through for the caller to reject.
+    fn to_units(&self, items: Vec<ReceivedItem>, ctx: &str) -> Result<Vec<ReceivedItem>, AppError> {
+        items
+            .into_iter()
+            .map(|item| {
+                let Some((product, per_scan)) =
+                    try_log!(self.prod_repo.resolve_barcode(&item.upc), ctx)
+                else {
+                    return Ok(item);
+                };
+                Ok(ReceivedItem {
+                    upc: product.upc,
+                    quantity: item.quantity * per_scan,
+                    unit_cost: item.unit_cost.map(|c| per_unit_cost(c, per_scan)),
+                    ..item
+                })
+            })
+            .collect()
+    }
+
+    fn require_po(&self, po_id: i32, ctx: &str) -> Result<PurchaseOrder, AppError> {
+        try_log!(self.po_repo.get(po_id), ctx)
+            .ok_or_else(|| AppError::NotFound(format!("purchase order {po_id} not found")))
+    }
+
+    fn require_open_with_tx(
+        &self,
+        po_id: i32,
+        action: &str,
+        tx: &rusqlite::Transaction<'_>,
+    ) -> Result<(), AppError> {
+        let po = self
+            .po_repo
+            .get_with_tx(po_id, tx)?
+            .ok_or_else(|| AppError::NotFound(format!("purchase order {po_id} not found")))?;
+        require_open(&po, action)
+    }
+
+    fn require_product(&self, upc: &str, ctx: &str) -> Result<(), AppError> {
+        try_log!(self.prod_repo.get_by_upc(upc.to_string()), ctx)
+            .filter(|p| p.deleted.is_none())
+            .map(|_| ())
+            .ok_or_else(|| AppError::NotFound(format!("product {upc} not found")))
+    }
+}
+
+fn require_open(po: &PurchaseOrder, action: &str) -> Result<(), AppError> {
+    if po.status != PurchaseOrderStatus::Open {
+        return Err(AppError::Validation(format!(
+            "purchase order {} is {} and can't be {action}",
+            po.id,
+            po.status.as_str()
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::models::{BarcodeAlias, Product};
+    use crate::test_support::mock_inventory_transaction_repo::MockInventoryTransactionRepo;
+    use crate::test_support::mock_product_repo::MockProductRepo;
+    use crate::test_support::mock_purchase_order_repo::MockPurchaseOrderRepo;
+    use crate::test_support::mock_vendor_repo::MockVendorRepo;
+
+    fn item(upc: &str, quantity: i32) -> ReceivedItem {
+        ReceivedItem {
+            upc: upc.into(),
+            quantity,
+            unit_cost: None,
+            lot: None,
+        }
+    }
+
+    fn make_use_cases() -> Result<
+        (
+            PurchasingUseCases,
+            Arc<MockInventoryTransactionRepo>,
+            Arc<MockPurchaseOrderRepo>,
+            i32,
+        ),
+        AppError,
+    > {
+        // Real DB only for atomic_tx; repos are all mocks
+        let conn = Arc::new(Mutex::new(rusqlite::Connection::open_in_memory()?));
+        let prod_repo = Arc::new(MockProductRepo::new());
+        for upc in ["SOAP", "PASTE", "COMB"] {
+            prod_repo.create(&Product {
+                upc: upc.into(),
+                desc: upc.to_lowercase(),
+                ..Default::default()
+            })?;
+        }
+        let inv_repo = Arc::new(MockInventoryTransactionRepo::default());
+        let po_repo = Arc::new(MockPurchaseOrderRepo::new());
+        let uc = PurchasingUseCases::new(
+            Arc::new(MockVendorRepo::new()),
+            po_repo.clone(),
+            inv_repo.clone(),
+            prod_repo,
+            conn,
+        );
+        let vendor_id = uc.create_vendor(&Vendor {
+            id: 0,
+            name: "Acme Supply".into(),
+            contact: None,
+            phone: None,
+            deleted: None,
+            created_at: None,
+        })?;
+        Ok((uc, inv_repo, po_repo, vendor_id))
+    }
+
+    #[test]
+    fn receiving_posts_stock_and_flags_variances() -> Result<(), AppError> {
+        let (uc, inv_repo, _, vendor_id) = make_use_cases()?;
+        let po_id = uc.create_purchase_order(
+            vendor_id,
+            1,
+            Some(" INV-42 ".into()),
+            vec![item("SOAP", 10), item("PASTE", 5)],
+        )?;
+
+        // short on soap, paste in full, and a comb nobody ordered
+        let report = uc.receive_purchase_order(
+            po_id,
+            2,
+            vec![item("SOAP", 8), item("PASTE", 5), item("COMB", 3)],
+        )?;
+        assert_eq!(report.po.status, PurchaseOrderStatus::Open);
+        assert_eq!(report.vendor_name, "Acme Supply");
+        let flags: HashMap<&str, (Option<&str>, i32)> = report
+            .lines
+            .iter()
+            .map(|l| (l.line.upc.as_str(), (l.flag(), l.received_now)))
+            .collect();
+        assert_eq!(flags["SOAP"], (Some("SHORT"), 8));
+        assert_eq!(flags["PASTE"], (None, 5));
+        assert_eq!(flags["COMB"], (Some("OVER"), 3));
+
+        let posted = inv_repo.list_for_product("SOAP".into())?;
+        assert_eq!(posted.len(), 1);
+        assert_eq!(posted[0].quantity_change, 8);
+        assert_eq!(posted[0].po_id, Some(po_id));
+        assert_eq!(posted[0].operator_mdoc, 2);
+
+        // the rest of the soap closes the order
+        let report = uc.receive_purchase_order(po_id, 2, vec![item("SOAP", 2)])?;
+        assert_eq!(report.po.status, PurchaseOrderStatus::Received);
+        assert!(report.po.received_at.is_some());
+        let soap = report.lines.iter().find(|l| l.line.upc == "SOAP").unwrap();
+        assert_eq!((soap.line.received_quantity, soap.received_now), (10, 2));
+        assert_eq!(soap.flag(), None);
+
+        let err = uc.receive_purchase_order(po_id, 2, vec![item("SOAP", 1)]);
+        assert!(matches!(err, Err(AppError::Validation(_))));
+        assert_eq!(uc.receiving_report(po_id)?.lines.len(), 3);
+        Ok(())
+    }
+
+    #[test]
+    fn costed_receipts_average_the_unit_cost() -> Result<(), AppError> {
+        let (uc, inv_repo, _, vendor_id) = make_use_cases()?;
+        let costed = |quantity, cost| ReceivedItem {
+            unit_cost: Some(cost),
+            ..item("SOAP", quantity)
+        };
+        let po_id = uc.create_purchase_order(vendor_id, 1, None, vec![item("SOAP", 20)])?;
+
+        // the first cost is taken as is, the second is averaged with the stock on hand
+        uc.receive_purchase_order(po_id, 1, vec![costed(10, 100)])?;
+        uc.receive_purchase_order(po_id, 1, vec![costed(5, 131)])?;
+        let soap = uc.prod_repo.get_by_upc("SOAP".into())?.unwrap();
+        assert_eq!(soap.unit_cost, Some(110)); // (10*100 + 5*131) / 15 = 110.33
+
+        // an uncosted receipt leaves the cost alone
+        uc.receive_purchase_order(po_id, 1, vec![item("SOAP", 5)])?;
+        let soap = uc.prod_repo.get_by_upc("SOAP".into())?.unwrap();
+        assert_eq!(soap.unit_cost, Some(110));
+        let costs: Vec<_> = inv_repo
+            .list_for_product("SOAP".into())?
+            .iter()
+            .map(|t| t.unit_cost)
+            .collect();
+        assert_eq!(costs, vec![Some(100), Some(131), None]);
+        Ok(())
+    }
+
+    #[test]
+    fn case_barcodes_are_received_as_units() -> Result<(), AppError> {
+        let (uc, inv_repo, _, vendor_id) = make_use_cases()?;
+        uc.prod_repo.add_barcode(&BarcodeAlias {
+            barcode: "SOAP-CASE".into(),
+            upc: "SOAP".into(),
+            multiplier: 24,
+            created_at: None,
+        })?;
+        let po_id = uc.create_purchase_order(vendor_id, 1, None, vec![item("SOAP-CASE", 2)])?;
+
+        // one case at $25.00 a case is 24 soaps at $1.04 each
+        let report = uc.receive_purchase_order(
+            po_id,
+            1,
+            vec![ReceivedItem {
+                unit_cost: Some(2500),
+                ..item("SOAP-CASE", 1)
+            }],
+        )?;
+        let soap = &report.lines[0];
+        assert_eq!(soap.line.upc, "SOAP");
+        assert_eq!((soap.line.expected_quantity, soap.received_now), (48, 24));
+        let posted = inv_repo.list_for_product("SOAP".into())?;
+        assert_eq!(
+            (posted[0].quantity_change, posted[0].unit_cost),
+            (24, Some(104))
+        );
+
+        // a case and a loose unit of the same product are the same line
+        let err = uc.receive_purchase_order(po_id, 1, vec![item("SOAP-CASE", 1), item("SOAP", 1)]);
+        assert!(matches!(err, Err(AppError::Validation(_))));
+        Ok(())
+    }
+
+    #[test]
+    fn receiving_rejects_bad_input_before_posting() -> Result<(), AppError> {
+        let (uc, inv_repo, po_repo, vendor_id) = make_use_cases()?;
+        let po_id = uc.create_purchase_order(vendor_id, 1, None, vec![item("SOAP", 4)])?;
+
+        for items in [
+            vec![],
+            vec![item("SOAP", 0)],
+            vec![item("SOAP", -1)],
+            vec![item("SOAP", 1), item("SOAP", 2)],
+        ] {
+            assert!(matches!(
+                uc.receive_purchase_order(po_id, 1, items),
+                Err(AppError::Validation(_))
+            ));
+        }
+        assert!(matches!(
+            uc.receive_purchase_order(po_id, 1, vec![item("SOAP", 1), item("NOPE", 1)]),
+            Err(AppError::NotFound(_))
+        ));
+        assert!(matches!(
+            uc.receive_purchase_order(99, 1, vec![item("SOAP", 1)]),
+            Err(AppError::NotFound(_))
+        ));
+        assert!(inv_repo.list_for_product("SOAP".into())?.is_empty());
+        assert_eq!(po_repo.lines(po_id)?[0].0.received_quantity, 0);
+
+        uc.cancel_purchase_order(po_id)?;
+        assert!(matches!(
+            uc.receive_purchase_order(po_id, 1, vec![item("SOAP", 4)]),
+            Err(AppError::Validation(_))
+        ));
+        assert!(uc.cancel_purchase_order(po_id).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn a_double_submitted_delivery_posts_once() -> Result<(), AppError> {
+        let (uc, inv_repo, po_repo, vendor_id) = make_use_cases()?;
+        let po_id = uc.create_purchase_order(vendor_id, 1, None, vec![item("SOAP", 4)])?;
+
+        uc.receive_purchase_order(po_id, 1, vec![item("SOAP", 4)])?;
+        assert!(matches!(
+            uc.receive_purchase_order(po_id, 1, vec![item("SOAP", 4)]),
+            Err(AppError::Validation(_))
+        ));
+        assert!(matches!(
+            uc.cancel_purchase_order(po_id),
+            Err(AppError::Validation(_))
+        ));
+        assert_eq!(inv_repo.list_for_product("SOAP".into())?.len(), 1);
+        assert_eq!(po_repo.lines(po_id)?[0].0.received_quantity, 4);
+        assert_eq!(
+            po_repo.get(po_id)?.unwrap().status,
+            PurchaseOrderStatus::Received
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn purchase_orders_need_a_live_vendor_and_real_lines() -> Result<(), AppError> {
+        let (uc, _, _, vendor_id) = make_use_cases()?;
+        assert!(matches!(
+            uc.create_purchase_order(vendor_id, 1, None, vec![]),
+            Err(AppError::Validation(_))
+        ));
+        assert!(matches!(
+            uc.create_purchase_order(vendor_id, 1, None, vec![item("SOAP", 0)]),
+            Err(AppError::Validation(_))
+        ));
+        assert!(matches!(
+            uc.create_purchase_order(vendor_id, 1, None, vec![item("SOAP", 1), item("SOAP", 1)]),
+            Err(AppError::Validation(_))
+        ));
+        assert!(matches!(
+            uc.create_purchase_order(vendor_id, 1, None, vec![item("NOPE", 1)]),
+            Err(AppError::NotFound(_))
+        ));
+
+        uc.delete_vendor(vendor_id)?;
+        assert!(uc.list_vendors()?.is_empty());
+        assert!(matches!(
+            uc.create_purchase_order(vendor_id, 1, None, vec![item("SOAP", 1)]),
+            Err(AppError::NotFound(_))
+        ));
+        assert!(uc.list_purchase_orders(None)?.is_empty());
+        Ok(())
+    }
+}
@@ -5,20 +5,24 @@ use std::sync::Arc;
 pub struct StatsUseCases {
     club_tx_repo: Arc<dyn crate::domain::repos::ClubTransactionRepoTrait>,
     customer_repo: Arc<dyn crate::domain::repos::CustomerRepoTrait>,
+    product_repo: Arc<dyn crate::domain::repos::ProductRepoTrait>,
 }
 
 impl StatsUseCases {
     pub fn new(
         club_tx_repo: Arc<dyn crate::domain::repos::ClubTransactionRepoTrait>,
         customer_repo: Arc<dyn crate::domain::repos::CustomerRepoTrait>,
+        product_repo: Arc<dyn crate::domain::repos::ProductRepoTrait>,
     ) -> Self {
         Self {
             club_tx_repo,
             customer_repo,
+            product_repo,
         }
     }
 
-    pub fn get_stats(&self) -> Result<(i64, i64), AppError> {
+    // (account total, customer balances, products at or below their reorder point)
+    pub fn get_stats(&self) -> Result<(i64, i64, i32), AppError> {
         let account_total = try_log!(
             self.club_tx_repo.get_account_total(),
             "StatsUseCases::get_stats::get_account_total"
@@ -29,7 +33,16 @@ impl StatsUseCases {
             "StatsUseCases::get_stats::sum_all_balances"
         );
 
-        Ok((account_total as i64, total_customer_balances as i64))
+        let low_stock_count = try_log!(
+            self.product_repo.count_low_stock(),
+            "StatsUseCases::get_stats::count_low_stock"
+        );
+
+        Ok((
+            account_total as i64,
+            total_customer_balances as i64,
+            low_stock_count,
+        ))
     }
 }
 
@@ -38,19 +51,49 @@ mod tests {
     use super::*;
     use crate::test_support::mock_club_tx_repo::MockClubTransactionRepo;
     use crate::test_support::mock_customer_repo::MockCustomerRepo;
+    use crate::test_support::mock_product_repo::MockProductRepo;
     use std::sync::Arc;
 
-    fn make_usecase() -> StatsUseCases {
+    fn make_usecase() -> (StatsUseCases, Arc<MockProductRepo>) {
         let club_repo = Arc::new(MockClubTransactionRepo::new());
         let cust_repo = Arc::new(MockCustomerRepo::new());
-        StatsUseCases::new(club_repo, cust_repo)
+        let prod_repo = Arc::new(MockProductRepo::new());
+        (
+            StatsUseCases::new(club_repo, cust_repo, prod_repo.clone()),
+            prod_repo,
+        )
     }
 
     #[test]
     fn smoke_get_stats() {
-        let uc = make_usecase();
-        let (acct, balances) = uc.get_stats().expect("get_stats should succeed");
+        let (uc, _) = make_usecase();
+        let (acct, balances, low_stock) = uc.get_stats().expect("get_stats should succeed");
         assert_eq!(acct, 0);
         assert_eq!(balances, 0);
+        assert_eq!(low_stock, 0);
+    }
+
+    #[test]
+    fn get_stats_counts_products_at_or_below_their_reorder_point() {
+        use crate::domain::models::Product;
+        use crate::domain::repos::ProductRepoTrait;
+
+        let (uc, prod_repo) = make_usecase();
+        for (upc, point, on_hand) in [
+            ("SOAP", Some(5), 5),
+            ("PASTE", Some(3), 4),
+            ("COMB", None, 0),
+        ] {
+            prod_repo
+                .create(&Product {
+                    upc: upc.into(),
+                    reorder_point: point,
+                    ..Default::default()
+                })
+                .unwrap();
+            prod_repo.set_available(upc, on_hand);
+        }
+        let (_, _, low_stock) = uc.get_stats().unwrap();
+        assert_eq!(low_stock, 1);
     }
 }
@@ -0,0 +1,505 @@
+use crate::application::common::db::atomic_tx;
+use crate::common::error::AppError;
+use crate::domain::models::{
+    fefo_rows, CountedItem, InventoryReason, InventoryTransaction, StockTake, StockTakeLine,
+    StockTakeStatus,
+};
+use crate::domain::report_models::stock_take_report::{StockTakeReport, StockTakeReportLine};
+use crate::domain::repos::stock_take_repo_trait::StockTakeLineRow;
+use crate::domain::repos::{InventoryTransactionRepoTrait, ProductRepoTrait, StockTakeRepoTrait};
+use crate::infrastructure::printing::reports::stock_take_report::print_stock_take_report;
+use crate::try_log;
+use chrono::Utc;
+use log::info;
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+
+// Physical stock counts. Opening a session freezes each product's on-hand
+// quantity; counts are keyed in against that sheet and posting writes every
+// variance as an inventory adjustment tagged with the session, all at once.
+pub struct StockTakeUseCases {
+    stock_take_repo: Arc<dyn StockTakeRepoTrait>,
+    inv_repo: Arc<dyn InventoryTransactionRepoTrait>,
+    prod_repo: Arc<dyn ProductRepoTrait>,
+    conn: Arc<Mutex<rusqlite::Connection>>,
+}
+
+impl StockTakeUseCases {
+    pub fn new(
+        stock_take_repo: Arc<dyn StockTakeRepoTrait>,
+        inv_repo: Arc<dyn InventoryTransactionRepoTrait>,
+        prod_repo: Arc<dyn ProductRepoTrait>,
+        conn: Arc<Mutex<rusqlite::Connection>>,
+    ) -> Self {
+        Self {
+            stock_take_repo,
+            inv_repo,
+            prod_repo,
+            conn,
+        }
+    }
+
+    // Opens a count of every active product, or of one category. Only one
+    // session may be open at a time so two counts can't post the same variance.
+    pub fn start_stock_take(
+        &self,
+        operator_mdoc: i32,
+        category: Option<String>,
+        blind: bool,
+        note: Option<String>,
+    ) -> Result<i32, AppError> {
+        if let Some(open) = try_log!(
+            self.stock_take_repo.list(Some(StockTakeStatus::Open)),
+            "StockTakeUseCases::start_stock_take"
+        )
+        .first()
+        {
+            return Err(AppError::Validation(format!(
+                "stock take {} is still open",
+                open.id
+            )));
+        }
+        let category = category
+            .map(|c| c.trim().to_string())
+            .filter(|c| !c.is_empty());
+        let note = note.map(|n| n.trim().to_string()).filter(|n| !n.is_empty());
+        let upcs: Vec<String> =
+            try_log!(self.prod_repo.list(), "StockTakeUseCases::start_stock_take")
+                .into_iter()
+                .filter(|p| category.as_ref().is_none_or(|c| &p.category == c))
+                .map(|p| p.upc)
+                .collect();
+        if upcs.is_empty() {
+            return Err(AppError::Validation(
+                "there are no products to count".into(),
+            ));
+        }
+
+        let id = try_log!(
+            atomic_tx(&self.conn, |tx| {
+                let id = self.stock_take_repo.create_with_tx(
+                    &StockTake {
+                        id: 0,
+                        category: category.clone(),
+                        blind,
+                        status: StockTakeStatus::Open,
+                        note: note.clone(),
+                        operator_mdoc,
+                        created_at: None,
+                        posted_at: None,
+                    },
+                    tx,
+                )?;
+                for upc in &upcs {
+                    let expected = self.inv_repo.get_available_with_tx(upc.clone(), tx)?;
+                    self.stock_take_repo.add_line_with_tx(
+                        &StockTakeLine {
+                            id: 0,
+                            stock_take_id: id,
+                            upc: upc.clone(),
+                            expected_quantity: expected,
+                            counted_quantity: None,
+                            counted_at: None,
+                        },
+                        tx,
+                    )?;
+                }
+                Ok(id)
+            }),
+            "StockTakeUseCases::start_stock_take"
+        );
+        info!(
+            "stock take started: id={} category={} blind={} operator={} lines={}",
+            id,
+            category.as_deref().unwrap_or("all"),
+            blind,
+            operator_mdoc,
+            upcs.len()
+        );
+        Ok(id)
+    }
+
+    pub fn list_stock_takes(
+        &self,
+        status: Option<StockTakeStatus>,
+    ) -> Result<Vec<StockTake>, AppError> {
+        let res = try_log!(
+            self.stock_take_repo.list(status),
+            "StockTakeUseCases::list_stock_takes"
+        );
+        Ok(res)
+    }
+
+    // The session and its lines; callers hide expected quantities on blind counts.
+    pub fn count_sheet(
+        &self,
+        stock_take_id: i32,
+    ) -> Result<(StockTake, Vec<StockTakeLineRow>), AppError> {
+        let stock_take =
+            self.require_stock_take(stock_take_id, "StockTakeUseCases::count_sheet")?;
+        let lines = try_log!(
+            self.stock_take_repo.lines(stock_take_id),
+            "StockTakeUseCases::count_sheet"
+        );
+        Ok((stock_take, lines))
+    }
+
+    // Records counted quantities; counting a product again replaces its count.
+    pub fn record_counts(
+        &self,
+        stock_take_id: i32,
+        items: Vec<CountedItem>,
+    ) -> Result<(), AppError> {
+        if items.is_empty() {
+            return Err(AppError::Validation("no counts were given".into()));
+        }
+        let mut seen = HashSet::new();
+        for item in &items {
+            if item.quantity < 0 {
+                return Err(AppError::Validation(format!(
+                    "invalid count for upc {}: {}",
+                    item.upc, item.quantity
+                )));
+            }
+            if !seen.insert(item.upc.as_str()) {
+                return Err(AppError::Validation(format!(
+                    "upc {} is listed more than once",
+                    item.upc
+                )));
+            }
+        }
+
+        try_log!(
+            // each count is guarded on the stock take still being open, so
+            // counts can't land on one that was posted or cancelled meanwhile
+            atomic_tx(&self.conn, |tx| {
+                let now = Utc::now().naive_utc();
+                for item in &items {
+                    self.stock_take_repo.record_count_with_tx(
+                        stock_take_id,
+                        &item.upc,
+                        item.quantity,
+                        now,
+                        tx,
+                    )?;
+                }
+                Ok(())
+            }),
+            "StockTakeUseCases::record_counts"
+        );
+        info!(
+            "stock take counts recorded: id={} items={}",
+            stock_take_id,
+            items.len()
+        );
+        Ok(())
+    }
+
+    pub fn cancel_stock_take(&self, stock_take_id: i32) -> Result<(), AppError> {
+        try_log!(
+            atomic_tx(&self.conn, |tx| {
+                self.stock_take_repo.close_with_tx(
+                    stock_take_id,
+                    StockTakeStatus::Cancelled,
+                    None,
+                    tx,
+                )
+            }),
+            "StockTakeUseCases::cancel_stock_take"
+        );
+        info!("stock take cancelled: id={stock_take_id}");
+        Ok(())
+    }
+
+    // Posts every counted variance as an adjustment and closes the session.
+    // Lines that were never counted are left as they are.
+    pub fn post_stock_take(
+        &self,
+        stock_take_id: i32,
+        operator_mdoc: i32,
+    ) -> Result<StockTakeReport, AppError> {
+        let posted = try_log!(
+            atomic_tx(&self.conn, |tx| {
+                let now = Utc::now().naive_utc();
+                let lines = self.stock_take_repo.lines_with_tx(stock_take_id, tx)?;
+                if lines.iter().all(|l| l.counted_quantity.is_none()) {
+                    return Err(AppError::Validation(format!(
+                        "nothing has been counted on stock take {stock_take_id}"
+                    )));
+                }
+                // closed before any variance is written: a second post of the
+                // same session stops here instead of applying them again
+                self.stock_take_repo.close_with_tx(
+                    stock_take_id,
+                    StockTakeStatus::Posted,
+                    Some(now),
+                    tx,
+                )?;
+                let mut posted = 0;
+                for line in &lines {
+                    let Some(variance) = line.variance().filter(|v| *v != 0) else {
+                        continue;
+                    };
+                    let row = InventoryTransaction {
+                        id: None,
+                        upc: line.upc.clone(),
+                        quantity_change: variance,
+                        operator_mdoc,
+                        customer_mdoc: None,
+                        ref_order_id: None,
+                        reference: Some(format!("Stock take #{stock_take_id}")),
+                        created_at: Some(now),
+                        po_id: None,
+                        unit_cost: None,
+                        stock_take_id: Some(stock_take_id),
+                        reason: InventoryReason::CountCorrection,
+                        lot_id: None,
+                    };
+                    // missing stock comes off the lots as a write-off would
+                    let rows = if variance < 0 {
+                        fefo_rows(&row, &self.inv_repo.open_lots_with_tx(&row.upc, tx)?, None)
+                    } else {
+                        vec![row]
+                    };
+                    for row in &rows {
+                        self.inv_repo.create_with_tx(row, tx)?;
+                    }
+                    posted += 1;
+                }
+                Ok(posted)
+            }),
+            "StockTakeUseCases::post_stock_take"
+        );
+        info!(
+            "stock take posted: id={stock_take_id} operator={operator_mdoc} adjustments={posted}"
+        );
+        self.variance_report(stock_take_id)
+    }
+
+    pub fn variance_report(&self, stock_take_id: i32) -> Result<StockTakeReport, AppError> {
+        let (stock_take, lines) = self.count_sheet(stock_take_id)?;
+        Ok(StockTakeReport {
+            stock_take,
+            lines: lines
+                .into_iter()
+                .map(|(line, product_name, price)| StockTakeReportLine {
+                    line,
+                    product_name,
+                    price,
+                })
+                .collect(),
+        })
+    }
+
+    pub fn print_variance_report(
+        &self,
+        report: &StockTakeReport,
+        printer_name: &str,
+        sumatra_location: &str,
+    ) -> Result<(), AppError> {
+        try_log!(
+            print_stock_take_report(report, printer_name, sumatra_location),
+            "StockTakeUseCases::print_variance_report"
+        );
+        Ok(())
+    }
+
+    fn require_stock_take(&self, id: i32, ctx: &str) -> Result<StockTake, AppError> {
+        try_log!(self.stock_take_repo.get(id), ctx)
+            .ok_or_else(|| AppError::NotFound(format!("stock take {id} not found")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::models::{LotLabel, Product};
+    use crate::test_support::mock_inventory_transaction_repo::MockInventoryTransactionRepo;
+    use crate::test_support::mock_product_repo::MockProductRepo;
+    use crate::test_support::mock_stock_take_repo::MockStockTakeRepo;
+
+    fn count(upc: &str, quantity: i32) -> CountedItem {
+        CountedItem {
+            upc: upc.into(),
+            quantity,
+        }
+    }
+
+    fn make_use_cases() -> Result<
+        (
+            StockTakeUseCases,
+            Arc<MockInventoryTransactionRepo>,
+            Arc<MockStockTakeRepo>,
+        ),
+        AppError,
+    > {
+        // Real DB only for atomic_tx; repos are all mocks
+        let conn = Arc::new(Mutex::new(rusqlite::Connection::open_in_memory()?));
+        let prod_repo = Arc::new(MockProductRepo::new());
+        let inv_repo = Arc::new(MockInventoryTransactionRepo::default());
+        let stock_take_repo = Arc::new(MockStockTakeRepo::new());
+        for (upc, category, price, on_hand) in [
+            ("SOAP", "Hygiene", 100, 10),
+            ("PASTE", "Hygiene", 150, 4),
+            ("CHIP", "Snacks", 200, 7),
+        ] {
+            prod_repo.create(&Product {
+                upc: upc.into(),
+                category: category.into(),
+                price,
+                ..Default::default()
+            })?;
+            stock_take_repo.set_price(upc, price);
+            inv_repo.create(&InventoryTransaction {
+                id: None,
+                upc: upc.into(),
+                quantity_change: on_hand,
+                operator_mdoc: 1,
+                customer_mdoc: None,
+                ref_order_id: None,
+                reference: None,
+                created_at: None,
+                po_id: None,
+                unit_cost: None,
+                stock_take_id: None,
+                reason: InventoryReason::Receipt,
+                lot_id: None,
+            })?;
+        }
+        let uc = StockTakeUseCases::new(stock_take_repo.clone(), inv_repo.clone(), prod_repo, conn);
+        Ok((uc, inv_repo, stock_take_repo))
+    }
+
+    #[test]
+    fn posting_writes_counted_variances_as_tagged_adjustments() -> Result<(), AppError> {
+        let (uc, inv_repo, _) = make_use_cases()?;
+        let id = uc.start_stock_take(1, Some(" Hygiene ".into()), true, None)?;
+        let (st, lines) = uc.count_sheet(id)?;
+        assert!(st.blind);
+        assert_eq!(st.category.as_deref(), Some("Hygiene"));
+        let expected: Vec<_> = lines
+            .iter()
+            .map(|(l, _, _)| (l.upc.as_str(), l.expected_quantity))
+            .collect();
+        assert_eq!(expected, vec![("SOAP", 10), ("PASTE", 4)]);
+
+        // chips aren't part of a hygiene count
+        assert!(matches!(
+            uc.record_counts(id, vec![count("CHIP", 7)]),
+            Err(AppError::NotFound(_))
+        ));
+        uc.record_counts(id, vec![count("SOAP", 9), count("PASTE", 4)])?;
+        // the recount of soap wins
+        uc.record_counts(id, vec![count("SOAP", 7)])?;
+
+        let report = uc.post_stock_take(id, 2)?;
+        assert_eq!(report.stock_take.status, StockTakeStatus::Posted);
+        assert!(report.stock_take.posted_at.is_some());
+        assert_eq!(report.uncounted(), 0);
+        assert_eq!(report.shrink_value(), -300);
+        assert_eq!(report.net_value(), -300);
+
+        let soap = inv_repo.list_for_product("SOAP".into())?;
+        assert_eq!(soap.len(), 2);
+        assert_eq!(soap[1].quantity_change, -3);
+        assert_eq!(soap[1].stock_take_id, Some(id));
+        assert_eq!(soap[1].operator_mdoc, 2);
+        // a matching count posts nothing
+        assert_eq!(inv_repo.list_for_product("PASTE".into())?.len(), 1);
+
+        assert!(matches!(
+            uc.record_counts(id, vec![count("SOAP", 1)]),
+            Err(AppError::Validation(_))
+        ));
+        // posting again is refused without writing the variances a second time
+        assert!(matches!(
+            uc.post_stock_take(id, 2),
+            Err(AppError::Validation(_))
+        ));
+        assert_eq!(inv_repo.list_for_product("SOAP".into())?.len(), 2);
+        Ok(())
+    }
+
+    #[test]
+    fn missing_stock_comes_off_the_lots() -> Result<(), AppError> {
+        let (uc, inv_repo, _) = make_use_cases()?;
+        let mut conn = rusqlite::Connection::open_in_memory()?;
+        let t = conn.transaction()?;
+        // 10 soap already on hand without a lot, 5 more in a dated lot
+        let lot_id = inv_repo.lot_id_with_tx(
+            "SOAP",
+            &LotLabel::new(Some("S-1".into()), Some(Utc::now().date_naive())).unwrap(),
+            &t,
+        )?;
+        inv_repo.create(&InventoryTransaction {
+            upc: "SOAP".into(),
+            quantity_change: 5,
+            reason: InventoryReason::Receipt,
+            lot_id: Some(lot_id),
+            ..Default::default()
+        })?;
+
+        let id = uc.start_stock_take(1, Some("Hygiene".into()), false, None)?;
+        uc.record_counts(id, vec![count("SOAP", 8)])?;
+        uc.post_stock_take(id, 2)?;
+
+        let posted: Vec<_> = inv_repo
+            .list_for_product("SOAP".into())?
+            .into_iter()
+            .filter(|r| r.stock_take_id == Some(id))
+            .map(|r| (r.lot_id, r.quantity_change))
+            .collect();
+        assert_eq!(posted, vec![(Some(lot_id), -5), (None, -2)]);
+        assert!(inv_repo.open_lots_with_tx("SOAP", &t)?.is_empty());
+        assert_eq!(inv_repo.get_available_with_tx("SOAP".into(), &t)?, 8);
+        Ok(())
+    }
+
+    #[test]
+    fn one_open_count_at_a_time_and_bad_counts_are_rejected() -> Result<(), AppError> {
+        let (uc, inv_repo, _) = make_use_cases()?;
+        assert!(matches!(
+            uc.start_stock_take(1, Some("Toys".into()), false, None),
+            Err(AppError::Validation(_))
+        ));
+        let id = uc.start_stock_take(1, None, false, Some("Q1".into()))?;
+        assert_eq!(uc.count_sheet(id)?.1.len(), 3);
+        assert!(matches!(
+            uc.start_stock_take(1, None, false, None),
+            Err(AppError::Validation(_))
+        ));
+
+        for items in [
+            vec![],
+            vec![count("SOAP", -1)],
+            vec![count("SOAP", 1), count("SOAP", 2)],
+        ] {
+            assert!(matches!(
+                uc.record_counts(id, items),
+                Err(AppError::Validation(_))
+            ));
+        }
+        // nothing counted yet
+        assert!(matches!(
+            uc.post_stock_take(id, 1),
+            Err(AppError::Validation(_))
+        ));
+
+        // uncounted lines are left alone when posting
+        uc.record_counts(id, vec![count("CHIP", 9)])?;
+        let report = uc.variance_report(id)?;
+        assert_eq!(report.uncounted(), 2);
+        assert_eq!(report.overage_value(), 400);
+        uc.cancel_stock_take(id)?;
+        assert!(uc.cancel_stock_take(id).is_err());
+        assert_eq!(inv_repo.list()?.len(), 3);
+
+        // the next count can start once the last one is closed
+        uc.start_stock_take(1, None, false, None)?;
+        assert_eq!(
+            uc.list_stock_takes(Some(StockTakeStatus::Cancelled))?.len(),
+            1
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,287 @@
+use crate::common::error::AppError;
+use crate::domain::models::{CustomerTxDetail, Product, TaxRate, TaxSettings};
+use crate::domain::repos::{CategoryRepoTrait, ProductRepoTrait, TaxRateRepoTrait};
+use crate::try_log;
+use chrono::NaiveDateTime;
+use log::info;
+use std::sync::Arc;
+
+// Owns sales tax rates and taxes each sale line. A product's own rate beats its
+// category's, and of several rates for the same one the latest in effect wins.
+pub struct TaxUseCases {
+    tax_repo: Arc<dyn TaxRateRepoTrait>,
+    prod_repo: Arc<dyn ProductRepoTrait>,
+    category_repo: Arc<dyn CategoryRepoTrait>,
+}
+
+impl TaxUseCases {
+    pub fn new(
+        tax_repo: Arc<dyn TaxRateRepoTrait>,
+        prod_repo: Arc<dyn ProductRepoTrait>,
+        category_repo: Arc<dyn CategoryRepoTrait>,
+    ) -> Self {
+        Self {
+            tax_repo,
+            prod_repo,
+            category_repo,
+        }
+    }
+
+    pub fn list_rates(&self) -> Result<Vec<TaxRate>, AppError> {
+        Ok(try_log!(self.tax_repo.list(), "TaxUseCases::list_rates"))
+    }
+
+    pub fn create_rate(&self, mut rate: TaxRate) -> Result<i32, AppError> {
+        rate.upc = rate
+            .upc
+            .map(|u| u.trim().to_string())
+            .filter(|u| !u.is_empty());
+        rate.category = rate
+            .category
+            .map(|c| c.trim().to_string())
+            .filter(|c| !c.is_empty());
+        if rate.upc.is_some() == rate.category.is_some() {
+            return Err(AppError::Validation(
+                "a tax rate applies to either a upc or a category".into(),
+            ));
+        }
+        if !(0..=10_000).contains(&rate.rate) {
+            return Err(AppError::Validation(format!(
+                "tax rate must be between 0 and 100%: {}",
+                rate.rate
+            )));
+        }
+        if let Some(upc) = &rate.upc {
+            try_log!(
+                self.prod_repo.get_by_upc(upc.clone()),
+                "TaxUseCases::create_rate"
+            )
+            .filter(|p| p.deleted.is_none())
+            .ok_or_else(|| AppError::NotFound(format!("product {upc} not found")))?;
+        }
+        if let Some(category) = &rate.category {
+            let known = try_log!(self.category_repo.list_active(), "TaxUseCases::create_rate")
+                .iter()
+                .any(|c| c.name == *category);
+            if !known {
+                return Err(AppError::NotFound(format!("category {category} not found")));
+            }
+        }
+        let id = try_log!(self.tax_repo.create(&rate), "TaxUseCases::create_rate");
+        info!(
+            "tax rate created: id={id} upc={:?} category={:?} rate={} effective_at={:?}",
+            rate.upc, rate.category, rate.rate, rate.effective_at
+        );
+        Ok(id)
+    }
+
+    pub fn delete_rate(&self, id: i32) -> Result<(), AppError> {
+        try_log!(self.tax_repo.delete(id), "TaxUseCases::delete_rate");
+        info!("tax rate deleted: id={id}");
+        Ok(())
+    }
+
+    pub fn get_settings(&self) -> Result<TaxSettings, AppError> {
+        Ok(try_log!(
+            self.tax_repo.get_settings(),
+            "TaxUseCases::get_settings"
+        ))
+    }
+
+    pub fn set_settings(&self, settings: &TaxSettings) -> Result<(), AppError> {
+        try_log!(
+            self.tax_repo.set_settings(settings),
+            "TaxUseCases::set_settings"
+        );
+        info!(
+            "tax settings updated: counts_toward_limit={}",
+            settings.counts_toward_limit
+        );
+        Ok(())
+    }
+
+    // for use with atomic_tx
+    pub fn settings_with_tx(
+        &self,
+        tx: &rusqlite::Transaction<'_>,
+    ) -> Result<TaxSettings, AppError> {
+        self.tax_repo.get_settings_with_tx(tx)
+    }
+
+    // Taxes the sale's lines at the rates in effect at `now`, on what each line
+    // charges after its discount. Whatever the client sent is replaced.
+    pub fn apply_with_tx(
+        &self,
+        details: &mut [CustomerTxDetail],
+        now: NaiveDateTime,
+        tx: &rusqlite::Transaction<'_>,
+    ) -> Result<(), AppError> {
+        let rates = self.tax_repo.in_effect_with_tx(now, tx)?;
+        for det in details.iter_mut() {
+            det.tax = 0;
+            det.tax_rate = None;
+            if rates.is_empty() {
+                continue;
+            }
+            // an unknown product is rejected when the sale is validated
+            let Some(product) = self.prod_repo.get_by_upc_with_tx(det.upc.clone(), tx)? else {
+                continue;
+            };
+            if let Some(rate) = Self::rate_for(&rates, &product).filter(|r| r.rate > 0) {
+                det.tax = rate.tax_on(det.net());
+                det.tax_rate = Some(rate.rate);
+            }
+        }
+        Ok(())
+    }
+
+    // The rate that applies to `product` out of those in effect (oldest first).
+    fn rate_for<'a>(rates: &'a [TaxRate], product: &Product) -> Option<&'a TaxRate> {
+        rates
+            .iter()
+            .rev()
+            .find(|r| r.upc.as_deref() == Some(product.upc.as_str()))
+            .or_else(|| {
+                rates
+                    .iter()
+                    .rev()
+                    .find(|r| r.category.as_deref() == Some(product.category.as_str()))
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::mock_category_repo::MockCategoryRepo;
+    use crate::test_support::mock_product_repo::MockProductRepo;
+    use crate::test_support::mock_tax_rate_repo::MockTaxRateRepo;
+    use chrono::{Duration, Utc};
+    use rusqlite::Connection;
+
+    fn rate(upc: Option<&str>, category: Option<&str>, rate: i32) -> TaxRate {
+        TaxRate {
+            id: 0,
+            upc: upc.map(Into::into),
+            category: category.map(Into::into),
+            rate,
+            effective_at: None,
+            created_at: None,
+            deleted: None,
+        }
+    }
+
+    // Snacks is a category; CHIPS and WATER are in it, SOAP is in Hygiene,
+    // which isn't. Everything sells at 200.
+    fn make_use_cases(
+    ) -> Result<(TaxUseCases, Arc<MockTaxRateRepo>, Arc<MockProductRepo>), AppError> {
+        let prod_repo = Arc::new(MockProductRepo::new());
+        let category_repo = Arc::new(MockCategoryRepo::new());
+        for (upc, category) in [
+            ("CHIPS", "Snacks"),
+            ("WATER", "Snacks"),
+            ("SOAP", "Hygiene"),
+        ] {
+            prod_repo.create(&Product {
+                upc: upc.into(),
+                desc: upc.into(),
+                category: category.into(),
+                price: 200,
+                ..Default::default()
+            })?;
+        }
+        category_repo.create("Snacks".into())?;
+        let tax_repo = Arc::new(MockTaxRateRepo::new());
+        let uc = TaxUseCases::new(tax_repo.clone(), prod_repo.clone(), category_repo);
+        Ok((uc, tax_repo, prod_repo))
+    }
+
+    #[test]
+    fn products_override_their_category_and_later_rates_replace_earlier() -> Result<(), AppError> {
+        let (uc, tax_repo, prod_repo) = make_use_cases()?;
+
+        uc.create_rate(rate(None, Some("Snacks"), 600))?;
+        uc.create_rate(rate(Some("WATER"), None, 0))?;
+        let mut raise = rate(None, Some("Snacks"), 700);
+        raise.effective_at = Some(Utc::now().naive_utc() + Duration::days(30));
+        uc.create_rate(raise)?;
+
+        assert!(uc.create_rate(rate(None, Some("Nowhere"), 500)).is_err());
+        assert!(uc.create_rate(rate(Some("NOPE"), None, 500)).is_err());
+        assert!(uc
+            .create_rate(rate(Some("SOAP"), Some("Hygiene"), 500))
+            .is_err());
+        assert!(uc.create_rate(rate(None, Some("Snacks"), 10_001)).is_err());
+
+        let snack = prod_repo.get_by_upc("CHIPS".into())?.unwrap();
+        let water = prod_repo.get_by_upc("WATER".into())?.unwrap();
+        let soap = prod_repo.get_by_upc("SOAP".into())?.unwrap();
+        let taxed = |at| {
+            let rates = tax_repo.in_effect(at).unwrap();
+            [&snack, &water, &soap].map(|p| TaxUseCases::rate_for(&rates, p).map(|r| r.rate))
+        };
+        let now = Utc::now().naive_utc();
+        assert_eq!(taxed(now), [Some(600), Some(0), None]);
+        assert_eq!(taxed(now + Duration::days(31)), [Some(700), Some(0), None]);
+        assert_eq!(uc.list_rates()?.len(), 3);
+        Ok(())
+    }
+
+    #[test]
+    fn sale_lines_are_taxed_on_what_they_charge_after_discount() -> anyhow::Result<()> {
+        let (uc, _, _) = make_use_cases()?;
+        uc.create_rate(rate(None, Some("Snacks"), 600))?;
+        uc.create_rate(rate(Some("WATER"), None, 0))?;
+        let mut raise = rate(None, Some("Snacks"), 700);
+        raise.effective_at = Some(Utc::now().naive_utc() + Duration::days(30));
+        uc.create_rate(raise)?;
+
+        let line = |upc: &str, quantity, discount, promotion_id| CustomerTxDetail {
+            detail_id: 0,
+            order_id: 0,
+            upc: upc.into(),
+            quantity,
+            price: 200,
+            cost: None,
+            discount,
+            promotion_id,
+            promotion_name: None,
+            tax: 99,
+            tax_rate: Some(1),
+            limit_exempt: false,
+        };
+        let sale = || {
+            vec![
+                line("CHIPS", 2, 0, None),
+                line("CHIPS", 3, 150, Some(1)),
+                line("WATER", 1, 0, None),
+                line("SOAP", 1, 0, None),
+            ]
+        };
+        let taxed = |details: &[CustomerTxDetail]| {
+            details
+                .iter()
+                .map(|d| (d.tax, d.tax_rate))
+                .collect::<Vec<_>>()
+        };
+
+        let mut conn = Connection::open_in_memory()?;
+        let tx = conn.transaction()?;
+        let now = Utc::now().naive_utc();
+        let mut details = sale();
+        uc.apply_with_tx(&mut details, now, &tx)?;
+        // the promotion line is taxed on 450, not its 600 list total
+        assert_eq!(
+            taxed(&details),
+            [(24, Some(600)), (27, Some(600)), (0, None), (0, None)]
+        );
+
+        let mut details = sale();
+        uc.apply_with_tx(&mut details, now + Duration::days(31), &tx)?;
+        assert_eq!(
+            taxed(&details),
+            [(28, Some(700)), (32, Some(700)), (0, None), (0, None)]
+        );
+        Ok(())
+    }
+}
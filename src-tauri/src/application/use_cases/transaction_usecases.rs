@@ -1,50 +1,177 @@
+use crate::application::common::costing::apply_receipt_cost_with_tx;
 use crate::application::common::db::atomic_tx;
+use crate::application::common::pricing::apply_due_price_changes_with_tx;
+use crate::application::use_cases::limit_usecases::LimitUseCases;
+use crate::application::use_cases::promotion_usecases::PromotionUseCases;
+use crate::application::use_cases::tax_usecases::TaxUseCases;
 use crate::common::error::AppError;
-use crate::domain::models::{CustomerTransaction, CustomerTxDetail, InventoryTransaction};
-use crate::domain::repos::customer_tx_repo_trait::SaleDetailsTuple;
+use crate::domain::models::{
+    fefo_rows, per_unit_cost, ActiveQuantityLimit, CustomerTransaction, CustomerTxDetail,
+    InventoryReason, InventoryTransaction, ItemAllowance, LimitWindow, LotLabel,
+    NegativeStockPolicy, OrderReason, OrderReturn, OrderReturnLine, OrderVoid, Product,
+    ResolvedLimit, SaleOutcome, StockShortfall,
+};
+use crate::domain::report_models::expiry_report::ExpiringLot;
+use crate::domain::report_models::shrinkage_report::ShrinkageLine;
+use crate::domain::repos::customer_tx_repo_trait::{CustomerTxSearchRow, SaleDetailsTuple};
 use crate::domain::repos::{
     CustomerTransactionRepoTrait, CustomerTxDetailRepoTrait, InventoryTransactionRepoTrait,
-    WeeklyLimitRepoTrait,
+    OrderReturnRepoTrait, PriceAdjustmentRepoTrait, ProductRepoTrait,
 };
-
+use crate::infrastructure::printing::reports::expiry_report::print_expiry_report;
+use crate::infrastructure::printing::reports::shrinkage_report::print_shrinkage_report;
 use crate::try_log;
-use chrono::{Datelike, Duration, Utc};
-use log::info;
+use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime, Utc};
+use log::{info, warn};
+use std::collections::{BTreeMap, HashMap};
 use std::sync::{Arc, Mutex};
 
 pub struct TransactionUseCases {
     inv_repo: Arc<dyn InventoryTransactionRepoTrait>,
     cust_tx_repo: Arc<dyn CustomerTransactionRepoTrait>,
     cust_tx_detail_repo: Arc<dyn CustomerTxDetailRepoTrait>,
-    limit_repo: Arc<dyn WeeklyLimitRepoTrait>,
+    limits: Arc<LimitUseCases>,
+    promotions: Arc<PromotionUseCases>,
+    taxes: Arc<TaxUseCases>,
+    prod_repo: Arc<dyn ProductRepoTrait>,
+    return_repo: Arc<dyn OrderReturnRepoTrait>,
+    price_repo: Arc<dyn PriceAdjustmentRepoTrait>,
     conn: Arc<Mutex<rusqlite::Connection>>,
 }
 
 impl TransactionUseCases {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         inv_repo: Arc<dyn InventoryTransactionRepoTrait>,
         cust_tx_repo: Arc<dyn CustomerTransactionRepoTrait>,
         cust_tx_detail_repo: Arc<dyn CustomerTxDetailRepoTrait>,
-        limit_repo: Arc<dyn WeeklyLimitRepoTrait>,
+        limits: Arc<LimitUseCases>,
+        promotions: Arc<PromotionUseCases>,
+        taxes: Arc<TaxUseCases>,
+        prod_repo: Arc<dyn ProductRepoTrait>,
+        return_repo: Arc<dyn OrderReturnRepoTrait>,
+        price_repo: Arc<dyn PriceAdjustmentRepoTrait>,
         conn: Arc<Mutex<rusqlite::Connection>>,
     ) -> Self {
         Self {
             inv_repo,
             cust_tx_repo,
             cust_tx_detail_repo,
-            limit_repo,
+            limits,
+            promotions,
+            taxes,
+            prod_repo,
+            return_repo,
+            price_repo,
             conn,
         }
     }
 
     pub fn inventory_adjustment(
+        &self,
+        tx: InventoryTransaction,
+    ) -> Result<InventoryTransaction, AppError> {
+        self.adjust_inventory(tx, None)
+    }
+
+    // Stock received into a lot, so it can be sold soonest-expiry first and
+    // pulled when it expires.
+    pub fn receive_into_lot(
+        &self,
+        tx: InventoryTransaction,
+        lot: LotLabel,
+    ) -> Result<InventoryTransaction, AppError> {
+        if tx.quantity_change <= 0 {
+            return Err(AppError::Validation(
+                "a lot number or expiry date is only recorded on stock received".into(),
+            ));
+        }
+        self.adjust_inventory(tx, Some(lot))
+    }
+
+    fn adjust_inventory(
         &self,
         mut tx: InventoryTransaction,
+        lot: Option<LotLabel>,
     ) -> Result<InventoryTransaction, AppError> {
+        if tx.reason.is_sale_side() {
+            return Err(AppError::Validation(format!(
+                "{} stock movements are recorded by the sale workflow",
+                tx.reason.as_str()
+            )));
+        }
+        if tx.reason.is_write_off() && tx.quantity_change >= 0 {
+            return Err(AppError::Validation(format!(
+                "a {} adjustment must take stock out",
+                tx.reason.as_str()
+            )));
+        }
+        if tx.reason == InventoryReason::Receipt && tx.quantity_change <= 0 {
+            return Err(AppError::Validation("a receipt must add stock".into()));
+        }
+        if tx
+            .unit_cost
+            .is_some_and(|cost| cost < 0 || tx.quantity_change <= 0)
+        {
+            return Err(AppError::Validation(
+                "a unit cost must be non-negative and given only for stock received".into(),
+            ));
+        }
         tx.created_at = Some(chrono::Utc::now().naive_utc());
 
-        try_log!(
-            self.inv_repo.create(&tx),
+        tx.lot_id = try_log!(
+            atomic_tx(&self.conn, |t| {
+                // a case or alternate barcode posts as units of its product;
+                // an unknown code is left for the insert to reject
+                if let Some((product, per_scan)) =
+                    self.prod_repo.resolve_barcode_with_tx(&tx.upc, t)?
+                {
+                    tx.upc = product.upc;
+                    tx.quantity_change *= per_scan;
+                    tx.unit_cost = tx.unit_cost.map(|c| per_unit_cost(c, per_scan));
+                }
+                if !self.prod_repo.components_with_tx(&tx.upc, t)?.is_empty() {
+                    return Err(AppError::Validation(format!(
+                        "{} is a kit; its stock is its components' stock",
+                        tx.upc
+                    )));
+                }
+                if tx.quantity_change < 0 {
+                    let taken = BTreeMap::from([(tx.upc.as_str(), -tx.quantity_change)]);
+                    self.check_stock_with_tx(&taken, None, t)?;
+                }
+                // a costed receipt moves the product's average cost with it
+                if let Some(cost) = tx.unit_cost {
+                    apply_receipt_cost_with_tx(
+                        self.prod_repo.as_ref(),
+                        self.inv_repo.as_ref(),
+                        &tx.upc,
+                        tx.quantity_change,
+                        cost,
+                        t,
+                    )?;
+                }
+                let rows = match &lot {
+                    Some(l) => vec![InventoryTransaction {
+                        lot_id: Some(self.inv_repo.lot_id_with_tx(&tx.upc, l, t)?),
+                        ..tx.clone()
+                    }],
+                    // stock written off comes off the lots soonest expiry
+                    // first, expired ones included
+                    None if tx.quantity_change < 0 => {
+                        fefo_rows(&tx, &self.inv_repo.open_lots_with_tx(&tx.upc, t)?, None)
+                    }
+                    None => vec![tx.clone()],
+                };
+                for row in &rows {
+                    self.inv_repo.create_with_tx(row, t)?;
+                }
+                // a row split over several lots is reported without one
+                Ok(match rows.as_slice() {
+                    [row] => row.lot_id,
+                    _ => None,
+                })
+            }),
             "TransactionUseCases::inventory_adjustment"
         );
         info!(
@@ -59,9 +186,89 @@ impl TransactionUseCases {
         cust_tx: CustomerTransaction,
         mut invs: Vec<InventoryTransaction>,
         mut details: Vec<CustomerTxDetail>,
-    ) -> Result<i32, AppError> {
+    ) -> Result<SaleOutcome, AppError> {
+        if cust_tx
+            .idempotency_key
+            .as_deref()
+            .is_some_and(|k| k.trim().is_empty())
+        {
+            return Err(AppError::Validation(
+                "an idempotency key may not be blank".into(),
+            ));
+        }
+        let now = Utc::now().naive_utc();
+        let window = try_log!(
+            self.limits.window_at(now),
+            "TransactionUseCases::sale_transaction"
+        );
+        let quantity_limits = try_log!(
+            self.limits.active_quantity_limits(now),
+            "TransactionUseCases::sale_transaction"
+        );
+        let limit = try_log!(
+            self.limits
+                .resolve(cust_tx.customer_mdoc, window.local_date),
+            "TransactionUseCases::sale_transaction"
+        )
+        .amount;
         Ok(try_log!(
             atomic_tx(&self.conn, |tx| {
+                // a resubmitted sale (double-click, retried call) gets the order it
+                // already created; checked first, since it would now fail the limits
+                if let Some(key) = cust_tx.idempotency_key.as_deref() {
+                    if let Some(existing) =
+                        self.cust_tx_repo.find_by_idempotency_key_with_tx(key, tx)?
+                    {
+                        if existing.customer_mdoc != cust_tx.customer_mdoc {
+                            return Err(AppError::Validation(format!(
+                                "idempotency key {key} was already used for order {} of another customer",
+                                existing.order_id
+                            )));
+                        }
+                        // the same key with a different cart is a new sale, not a retry
+                        self.to_units_with_tx(&mut invs, &mut details, tx)?;
+                        let recorded = self
+                            .cust_tx_detail_repo
+                            .list_by_order_with_tx(existing.order_id, tx)?;
+                        let same_lines = recorded.len() == details.len()
+                            && recorded.iter().zip(&details).all(|((r, _), d)| {
+                                (r.upc.as_str(), r.quantity, r.price)
+                                    == (d.upc.as_str(), d.quantity, d.price)
+                            });
+                        if !same_lines {
+                            return Err(AppError::Validation(format!(
+                                "idempotency key {key} was already used for order {} with different items",
+                                existing.order_id
+                            )));
+                        }
+                        info!("sale replayed: key={key} order={}", existing.order_id);
+                        return Ok(SaleOutcome::Replayed(existing.order_id));
+                    }
+                }
+
+                // a scheduled price change that has come due applies before this sale
+                apply_due_price_changes_with_tx(
+                    self.price_repo.as_ref(),
+                    self.prod_repo.as_ref(),
+                    now,
+                    tx,
+                )?;
+
+                // scanned case and alternate barcodes are sold as units of their product
+                self.to_units_with_tx(&mut invs, &mut details, tx)?;
+
+                // exemption is settled now, so later flag changes don't reach this sale
+                for det in &mut details {
+                    det.limit_exempt = self.prod_repo.is_limit_exempt_with_tx(&det.upc, tx)?;
+                }
+
+                // promotions and tax are priced here, never taken from the client
+                self.promotions.apply_with_tx(&mut details, now, tx)?;
+                self.taxes.apply_with_tx(&mut details, now, tx)?;
+
+                // the backend is authoritative: reject anything the rules don't allow
+                self.validate_sale(&cust_tx, &details, limit, &window, &quantity_limits, tx)?;
+
                 // add timestamp
                 let mut tx_to_insert = cust_tx.clone();
                 if tx_to_insert.date.is_none() {
@@ -71,20 +278,543 @@ impl TransactionUseCases {
 
                 for inv in &mut invs {
                     inv.ref_order_id = Some(order_id);
-                    self.inv_repo.create_with_tx(inv, tx)?;
+                    inv.reason = InventoryReason::Sale;
+                    for part in self.component_rows_with_tx(inv, tx)? {
+                        let lots = self.inv_repo.open_lots_with_tx(&part.upc, tx)?;
+                        for row in fefo_rows(&part, &lots, Some(window.local_date)) {
+                            self.inv_repo.create_with_tx(&row, tx)?;
+                        }
+                    }
                 }
 
                 for det in &mut details {
                     det.order_id = order_id;
+                    // cost is taken now so later receipts don't change this sale's margin
+                    det.cost = self.unit_cost_with_tx(&det.upc, tx)?;
                     self.cust_tx_detail_repo.create_with_tx(det, tx)?;
                 }
 
-                Ok(order_id)
+                Ok(SaleOutcome::Created(order_id))
             }),
             "TransactionUseCases::sale_transaction"
         ))
     }
 
+    // Cancels a committed order by recording a reversal order: negated detail
+    // lines and inventory rows offsetting the original's. The original stays untouched.
+    pub fn void_order(
+        &self,
+        order_id: i32,
+        operator_mdoc: i32,
+        reason: String,
+    ) -> Result<i32, AppError> {
+        let reason = reason.trim().to_string();
+        if reason.is_empty() {
+            return Err(AppError::Validation("a void reason is required".into()));
+        }
+        let original = try_log!(
+            self.cust_tx_repo.get(order_id),
+            "TransactionUseCases::void_order"
+        )
+        .ok_or_else(|| AppError::NotFound(format!("order {order_id} not found")))?;
+        let details = try_log!(
+            self.cust_tx_detail_repo.list_by_order(order_id),
+            "TransactionUseCases::void_order"
+        );
+        let invs = try_log!(
+            self.inv_repo.list_for_order(order_id),
+            "TransactionUseCases::void_order"
+        );
+
+        let reversal_id = try_log!(
+            atomic_tx(&self.conn, |tx| {
+                // read here so a return or void committed meanwhile is seen
+                if let Some(existing) = self.cust_tx_repo.find_void_with_tx(order_id, tx)? {
+                    return Err(AppError::Validation(if existing.order_id == order_id {
+                        format!(
+                            "order {order_id} is already voided by order {}",
+                            existing.reversal_order_id
+                        )
+                    } else {
+                        format!("order {order_id} is a void and cannot be voided")
+                    }));
+                }
+                if self
+                    .return_repo
+                    .get_by_return_order_with_tx(order_id, tx)?
+                    .is_some()
+                {
+                    return Err(AppError::Validation(format!(
+                        "order {order_id} is a return and cannot be voided"
+                    )));
+                }
+                if !self
+                    .return_repo
+                    .list_for_order_with_tx(order_id, tx)?
+                    .is_empty()
+                {
+                    return Err(AppError::Validation(format!(
+                        "order {order_id} has returns; return the remaining items instead"
+                    )));
+                }
+
+                let reversal = CustomerTransaction {
+                    order_id: 0,
+                    customer_mdoc: original.customer_mdoc,
+                    operator_mdoc,
+                    date: Some(Utc::now().naive_utc()),
+                    note: Some(format!("Void of order #{order_id}: {reason}")),
+                    idempotency_key: None,
+                    reason: original.reason,
+                };
+                let reversal_id = self.cust_tx_repo.create_with_tx(&reversal, tx)?;
+
+                for (det, _) in &details {
+                    self.cust_tx_detail_repo.create_with_tx(
+                        &CustomerTxDetail {
+                            detail_id: 0,
+                            order_id: reversal_id,
+                            upc: det.upc.clone(),
+                            quantity: -det.quantity,
+                            price: det.price,
+                            cost: det.cost,
+                            discount: -det.discount,
+                            promotion_id: det.promotion_id,
+                            promotion_name: det.promotion_name.clone(),
+                            tax: -det.tax,
+                            tax_rate: det.tax_rate,
+                            limit_exempt: det.limit_exempt,
+                        },
+                        tx,
+                    )?;
+                }
+
+                for inv in &invs {
+                    self.inv_repo.create_with_tx(
+                        &InventoryTransaction {
+                            id: None,
+                            upc: inv.upc.clone(),
+                            quantity_change: -inv.quantity_change,
+                            operator_mdoc,
+                            customer_mdoc: inv.customer_mdoc,
+                            ref_order_id: Some(order_id),
+                            reference: Some(format!("void #{reversal_id}: {reason}")),
+                            created_at: None,
+                            po_id: None,
+                            unit_cost: None,
+                            stock_take_id: None,
+                            reason: InventoryReason::Void,
+                            lot_id: inv.lot_id,
+                        },
+                        tx,
+                    )?;
+                }
+
+                self.cust_tx_repo.create_void_with_tx(
+                    &OrderVoid {
+                        order_id,
+                        reversal_order_id: reversal_id,
+                        operator_mdoc,
+                        reason: reason.clone(),
+                        created_at: None,
+                    },
+                    tx,
+                )?;
+                Ok(reversal_id)
+            }),
+            "TransactionUseCases::void_order"
+        );
+        info!(
+            "order voided: order={} reversal={} operator={} reason={}",
+            order_id, reversal_id, operator_mdoc, reason
+        );
+        Ok(reversal_id)
+    }
+
+    // Returns some lines of a committed order. Each line is capped at what was sold
+    // minus earlier returns and credited at the price originally charged.
+    pub fn return_items(
+        &self,
+        order_id: i32,
+        operator_mdoc: i32,
+        lines: Vec<OrderReturnLine>,
+        reason: Option<String>,
+    ) -> Result<i32, AppError> {
+        if lines.is_empty() {
+            return Err(AppError::Validation("nothing to return".into()));
+        }
+        if let Some(line) = lines.iter().find(|l| l.quantity <= 0) {
+            return Err(AppError::Validation(format!(
+                "invalid return quantity for detail {}: {}",
+                line.detail_id, line.quantity
+            )));
+        }
+        let reason = reason
+            .map(|r| r.trim().to_string())
+            .filter(|r| !r.is_empty());
+
+        let original = try_log!(
+            self.cust_tx_repo.get(order_id),
+            "TransactionUseCases::return_items"
+        )
+        .ok_or_else(|| AppError::NotFound(format!("order {order_id} not found")))?;
+        let sold: HashMap<i32, CustomerTxDetail> = try_log!(
+            self.cust_tx_detail_repo.list_by_order(order_id),
+            "TransactionUseCases::return_items"
+        )
+        .into_iter()
+        .map(|(d, _)| (d.detail_id, d))
+        .collect();
+
+        let return_id = try_log!(
+            atomic_tx(&self.conn, |tx| {
+                // read here so a void committed meanwhile is seen
+                if self.cust_tx_repo.find_void_with_tx(order_id, tx)?.is_some() {
+                    return Err(AppError::Validation(format!(
+                        "order {order_id} is voided or a void; nothing can be returned"
+                    )));
+                }
+                if self
+                    .return_repo
+                    .get_by_return_order_with_tx(order_id, tx)?
+                    .is_some()
+                {
+                    return Err(AppError::Validation(format!(
+                        "order {order_id} is itself a return"
+                    )));
+                }
+
+                // cap each line at sold minus everything returned so far (this request included)
+                let mut returned = self.return_repo.returned_by_detail_with_tx(order_id, tx)?;
+                let mut returned_before = returned.clone(); // for each line's share of the discount
+                for line in &lines {
+                    let det = sold.get(&line.detail_id).ok_or_else(|| {
+                        AppError::Validation(format!(
+                            "detail {} is not part of order {order_id}",
+                            line.detail_id
+                        ))
+                    })?;
+                    let already = returned.entry(line.detail_id).or_insert(0);
+                    let remaining = det.quantity - *already;
+                    if line.quantity > remaining {
+                        return Err(AppError::Validation(format!(
+                            "cannot return {} of upc {}: only {remaining} left to return",
+                            line.quantity, det.upc
+                        )));
+                    }
+                    *already += line.quantity;
+                }
+
+                let note = match &reason {
+                    Some(r) => format!("Return from order #{order_id}: {r}"),
+                    None => format!("Return from order #{order_id}"),
+                };
+                let return_id = self.cust_tx_repo.create_with_tx(
+                    &CustomerTransaction {
+                        order_id: 0,
+                        customer_mdoc: original.customer_mdoc,
+                        operator_mdoc,
+                        date: Some(Utc::now().naive_utc()),
+                        note: Some(note),
+                        idempotency_key: None,
+                        reason: original.reason,
+                    },
+                    tx,
+                )?;
+                self.return_repo.create_with_tx(
+                    &OrderReturn {
+                        return_order_id: return_id,
+                        order_id,
+                        operator_mdoc,
+                        reason: reason.clone(),
+                        created_at: None,
+                    },
+                    tx,
+                )?;
+
+                for line in &lines {
+                    let det = &sold[&line.detail_id];
+                    let before = returned_before.entry(line.detail_id).or_insert(0);
+                    let discount = det.returned_discount(*before, line.quantity);
+                    let tax = det.returned_tax(*before, line.quantity);
+                    *before += line.quantity;
+                    self.cust_tx_detail_repo.create_with_tx(
+                        &CustomerTxDetail {
+                            detail_id: 0,
+                            order_id: return_id,
+                            upc: det.upc.clone(),
+                            quantity: -line.quantity,
+                            price: det.price,
+                            cost: det.cost,
+                            discount: -discount,
+                            promotion_id: det.promotion_id,
+                            promotion_name: det.promotion_name.clone(),
+                            tax: -tax,
+                            tax_rate: det.tax_rate,
+                            limit_exempt: det.limit_exempt,
+                        },
+                        tx,
+                    )?;
+                    if line.restocked {
+                        let restock = InventoryTransaction {
+                            id: None,
+                            upc: det.upc.clone(),
+                            quantity_change: line.quantity,
+                            operator_mdoc,
+                            customer_mdoc: Some(original.customer_mdoc),
+                            ref_order_id: Some(order_id),
+                            reference: Some(format!("return #{return_id}")),
+                            created_at: None,
+                            po_id: None,
+                            unit_cost: None,
+                            stock_take_id: None,
+                            reason: InventoryReason::CustomerReturn,
+                            lot_id: None, // which lot the units came from isn't tracked per unit
+                        };
+                        // a returned kit goes back on the shelf as its components
+                        for row in self.component_rows_with_tx(&restock, tx)? {
+                            self.inv_repo.create_with_tx(&row, tx)?;
+                        }
+                    }
+                    self.return_repo.create_line_with_tx(
+                        &OrderReturnLine {
+                            return_order_id: return_id,
+                            ..line.clone()
+                        },
+                        tx,
+                    )?;
+                }
+                Ok(return_id)
+            }),
+            "TransactionUseCases::return_items"
+        );
+        info!(
+            "order return: order={} return={} operator={} lines={}",
+            order_id,
+            return_id,
+            operator_mdoc,
+            lines.len()
+        );
+        Ok(return_id)
+    }
+
+    // Applies the negative stock policy to units about to leave stock, per upc.
+    // Blocking fails with every short upc at once so the POS can list them all.
+    // With `sell_on`, stock in lots expired by that day isn't available.
+    fn check_stock_with_tx(
+        &self,
+        taken: &BTreeMap<&str, i32>,
+        sell_on: Option<NaiveDate>,
+        tx: &rusqlite::Transaction<'_>,
+    ) -> Result<(), AppError> {
+        let policy = self.inv_repo.negative_stock_policy_with_tx(tx)?;
+        if policy == NegativeStockPolicy::Allow {
+            return Ok(());
+        }
+        let mut short = Vec::new();
+        for (&upc, &requested) in taken {
+            let mut available = self.inv_repo.get_available_with_tx(upc.to_string(), tx)?;
+            if let Some(today) = sell_on {
+                available -= self
+                    .inv_repo
+                    .open_lots_with_tx(upc, tx)?
+                    .iter()
+                    .filter(|l| l.is_expired(today))
+                    .map(|l| l.on_hand)
+                    .sum::<i32>();
+            }
+            if requested > available {
+                short.push(StockShortfall {
+                    upc: upc.to_string(),
+                    requested,
+                    available,
+                });
+            }
+        }
+        if short.is_empty() {
+            return Ok(());
+        }
+        let err = AppError::InsufficientStock(short);
+        if policy == NegativeStockPolicy::Block {
+            return Err(err);
+        }
+        warn!("allowed by negative stock policy: {err}");
+        Ok(())
+    }
+
+    // A kit's stock movement as one row per component, scaled by how many of it
+    // the kit holds; any other product's row is kept as is.
+    fn component_rows_with_tx(
+        &self,
+        inv: &InventoryTransaction,
+        tx: &rusqlite::Transaction<'_>,
+    ) -> Result<Vec<InventoryTransaction>, AppError> {
+        let components = self.prod_repo.components_with_tx(&inv.upc, tx)?;
+        if components.is_empty() {
+            return Ok(vec![inv.clone()]);
+        }
+        Ok(components
+            .into_iter()
+            .map(|c| InventoryTransaction {
+                upc: c.component_upc,
+                quantity_change: inv.quantity_change * c.quantity,
+                reference: Some(InventoryTransaction::kit_reference(
+                    inv.reference.as_deref(),
+                    &inv.upc,
+                )),
+                ..inv.clone()
+            })
+            .collect())
+    }
+
+    // Rewrites sale lines keyed by a barcode alias onto the product's own upc,
+    // multiplying out the quantity. Unknown codes are left for validation.
+    fn to_units_with_tx(
+        &self,
+        invs: &mut [InventoryTransaction],
+        details: &mut [CustomerTxDetail],
+        tx: &rusqlite::Transaction<'_>,
+    ) -> Result<(), AppError> {
+        for inv in invs.iter_mut() {
+            if let Some((product, per_scan)) =
+                self.prod_repo.resolve_barcode_with_tx(&inv.upc, tx)?
+            {
+                inv.upc = product.upc;
+                inv.quantity_change *= per_scan;
+            }
+        }
+        for det in details.iter_mut() {
+            if let Some((product, per_scan)) =
+                self.prod_repo.resolve_barcode_with_tx(&det.upc, tx)?
+            {
+                det.upc = product.upc;
+                det.quantity *= per_scan;
+            }
+        }
+        Ok(())
+    }
+
+    // What one unit costs at sale time. A kit costs the sum of its components,
+    // once every one of them has a cost.
+    fn unit_cost_with_tx(
+        &self,
+        upc: &str,
+        tx: &rusqlite::Transaction<'_>,
+    ) -> Result<Option<i32>, AppError> {
+        let cost_of = |upc: &str| -> Result<Option<i32>, AppError> {
+            Ok(self
+                .prod_repo
+                .get_by_upc_with_tx(upc.to_string(), tx)?
+                .and_then(|p| p.unit_cost))
+        };
+        let components = self.prod_repo.components_with_tx(upc, tx)?;
+        if components.is_empty() {
+            return cost_of(upc);
+        }
+        let mut total = 0;
+        for c in components {
+            let Some(cost) = cost_of(&c.component_upc)? else {
+                return Ok(None);
+            };
+            total += cost * c.quantity;
+        }
+        Ok(Some(total))
+    }
+
+    // Checks pricing, stock, funds, the spending limit and quantity limits against
+    // committed data. Runs inside the sale's atomic_tx so the checks and the insert
+    // see the same state.
+    fn validate_sale(
+        &self,
+        cust_tx: &CustomerTransaction,
+        details: &[CustomerTxDetail],
+        limit: i32,
+        window: &LimitWindow,
+        quantity_limits: &[ActiveQuantityLimit],
+        tx: &rusqlite::Transaction<'_>,
+    ) -> Result<(), AppError> {
+        if details.is_empty() {
+            return Err(AppError::Validation("sale has no line items".into()));
+        }
+
+        // re-price each line and total the requested quantity per upc; totals are
+        // what the lines charge after their discounts, plus tax
+        let tax_counts = self.taxes.settings_with_tx(tx)?.counts_toward_limit;
+        let mut order_total = 0;
+        let mut limited_total = 0; // the part that counts toward the spending limit
+        let mut requested: BTreeMap<&str, i32> = BTreeMap::new();
+        let mut stocked: BTreeMap<String, i32> = BTreeMap::new(); // kits counted as their components
+        let mut products: HashMap<&str, Product> = HashMap::new();
+        for det in details {
+            if det.quantity <= 0 {
+                return Err(AppError::Validation(format!(
+                    "invalid quantity for upc {}: {}",
+                    det.upc, det.quantity
+                )));
+            }
+            let product = self
+                .prod_repo
+                .get_by_upc_with_tx(det.upc.clone(), tx)?
+                .filter(|p| p.deleted.is_none())
+                .ok_or_else(|| {
+                    AppError::Validation(format!("unknown or deleted product: upc {}", det.upc))
+                })?;
+            if det.price != product.price {
+                return Err(AppError::Validation(format!(
+                    "price mismatch for upc {}: submitted {}, current {}",
+                    det.upc, det.price, product.price
+                )));
+            }
+            order_total += det.charged();
+            if !det.limit_exempt {
+                limited_total += if tax_counts { det.charged() } else { det.net() };
+            }
+            *requested.entry(det.upc.as_str()).or_insert(0) += det.quantity;
+            let components = self.prod_repo.components_with_tx(&det.upc, tx)?;
+            if components.is_empty() {
+                *stocked.entry(det.upc.clone()).or_insert(0) += det.quantity;
+            }
+            for c in components {
+                *stocked.entry(c.component_upc).or_insert(0) += det.quantity * c.quantity;
+            }
+            products.insert(det.upc.as_str(), product);
+        }
+
+        let taken = stocked.iter().map(|(upc, q)| (upc.as_str(), *q)).collect();
+        self.check_stock_with_tx(&taken, Some(window.local_date), tx)?;
+
+        let balance = self
+            .cust_tx_repo
+            .get_balance_with_tx(cust_tx.customer_mdoc, tx)?;
+        if order_total > balance {
+            return Err(AppError::Validation(format!(
+                "insufficient funds: order total {order_total}, balance {balance}"
+            )));
+        }
+
+        let spent = self.cust_tx_repo.get_spent_with_tx(
+            cust_tx.customer_mdoc,
+            window.start,
+            window.end,
+            tx,
+        )?;
+        if spent + limited_total > limit {
+            return Err(AppError::Validation(format!(
+                "{} limit exceeded: limit {limit}, spent {spent}, order counts {limited_total}",
+                window.period.label()
+            )));
+        }
+
+        let lines: Vec<(Product, i32)> = requested
+            .into_iter()
+            .map(|(upc, quantity)| (products[upc].clone(), quantity))
+            .collect();
+        self.limits
+            .check_quantities_with_tx(cust_tx.customer_mdoc, quantity_limits, &lines, tx)?;
+
+        Ok(())
+    }
+
     pub fn list_for_product(&self, upc: String) -> Result<Vec<InventoryTransaction>, AppError> {
         let res = try_log!(
             self.inv_repo.list_for_product(upc),
@@ -98,11 +828,12 @@ impl TransactionUseCases {
         page: i32,
         date: Option<String>,
         search: Option<String>,
+        reason: Option<InventoryReason>,
     ) -> Result<Vec<(InventoryTransaction, String, String)>, AppError> {
         let limit = 10;
         let offset = page.saturating_sub(1) * limit;
         let res = try_log!(
-            self.inv_repo.search(limit, offset, date, search),
+            self.inv_repo.search(limit, offset, date, search, reason),
             "TransactionUseCases::search_inventory_transactions"
         );
         Ok(res)
@@ -112,14 +843,158 @@ impl TransactionUseCases {
         &self,
         date: Option<String>,
         search: Option<String>,
+        reason: Option<InventoryReason>,
     ) -> Result<i32, AppError> {
         let res = try_log!(
-            self.inv_repo.count(date, search),
+            self.inv_repo.count(date, search, reason),
             "TransactionUseCases::count_inventory_transactions"
         );
         Ok(res)
     }
 
+    // Stock written off between start and end, by reason and category.
+    pub fn shrinkage_report(
+        &self,
+        start: NaiveDateTime,
+        end: NaiveDateTime,
+    ) -> Result<Vec<ShrinkageLine>, AppError> {
+        if end < start {
+            return Err(AppError::Validation(
+                "the report's end date is before its start date".into(),
+            ));
+        }
+        let res = try_log!(
+            self.inv_repo.shrinkage(start, end),
+            "TransactionUseCases::shrinkage_report"
+        );
+        Ok(res)
+    }
+
+    pub fn negative_stock_policy(&self) -> Result<NegativeStockPolicy, AppError> {
+        let res = try_log!(
+            self.inv_repo.negative_stock_policy(),
+            "TransactionUseCases::negative_stock_policy"
+        );
+        Ok(res)
+    }
+
+    pub fn set_negative_stock_policy(&self, policy: NegativeStockPolicy) -> Result<(), AppError> {
+        try_log!(
+            self.inv_repo.set_negative_stock_policy(policy),
+            "TransactionUseCases::set_negative_stock_policy"
+        );
+        info!("negative stock policy set to {}", policy.as_str());
+        Ok(())
+    }
+
+    pub fn print_shrinkage_report(
+        &self,
+        start: NaiveDateTime,
+        end: NaiveDateTime,
+        printer_name: &str,
+        sumatra_location: &str,
+    ) -> Result<(), AppError> {
+        let lines = self.shrinkage_report(start, end)?;
+        try_log!(
+            print_shrinkage_report(&lines, start, end, printer_name, sumatra_location),
+            "TransactionUseCases::print_shrinkage_report"
+        );
+        Ok(())
+    }
+
+    // Lots with stock left that expire within `days` of the facility's local
+    // date, already-expired ones first. Returns the date the window was taken
+    // from so callers count days left the same way.
+    pub fn expiring_lots(&self, days: i64) -> Result<(NaiveDate, Vec<ExpiringLot>), AppError> {
+        if days < 0 {
+            return Err(AppError::Validation("days cannot be negative".into()));
+        }
+        let today = self.limits.window_at(Utc::now().naive_utc())?.local_date;
+        let res = try_log!(
+            self.inv_repo.expiring_lots(today + Duration::days(days)),
+            "TransactionUseCases::expiring_lots"
+        );
+        Ok((today, res))
+    }
+
+    pub fn print_expiring_lots(
+        &self,
+        days: i64,
+        printer_name: &str,
+        sumatra_location: &str,
+    ) -> Result<(), AppError> {
+        let (today, lots) = self.expiring_lots(days)?;
+        try_log!(
+            print_expiry_report(&lots, today, days, printer_name, sumatra_location),
+            "TransactionUseCases::print_expiring_lots"
+        );
+        Ok(())
+    }
+
+    // Pulls whatever is left of each expired lot out of stock as an expiry
+    // write-off, all or nothing.
+    pub fn write_off_expired_lots(
+        &self,
+        lot_ids: &[i32],
+        operator_mdoc: i32,
+    ) -> Result<Vec<InventoryTransaction>, AppError> {
+        if lot_ids.is_empty() {
+            return Err(AppError::Validation("no lots to write off".into()));
+        }
+        let now = Utc::now().naive_utc();
+        let today = self.limits.window_at(now)?.local_date;
+        let rows = try_log!(
+            atomic_tx(&self.conn, |tx| {
+                let mut rows = Vec::with_capacity(lot_ids.len());
+                for &id in lot_ids {
+                    let lot = self
+                        .inv_repo
+                        .get_lot_with_tx(id, tx)?
+                        .ok_or_else(|| AppError::NotFound(format!("lot {id} not found")))?;
+                    if !lot.is_expired(today) {
+                        return Err(AppError::Validation(format!(
+                            "lot {id} of {} hasn't expired",
+                            lot.upc
+                        )));
+                    }
+                    if lot.on_hand <= 0 {
+                        return Err(AppError::Validation(format!(
+                            "lot {id} of {} has nothing left to write off",
+                            lot.upc
+                        )));
+                    }
+                    let row = InventoryTransaction {
+                        id: None,
+                        upc: lot.upc.clone(),
+                        quantity_change: -lot.on_hand,
+                        reference: Some(format!(
+                            "Expired lot {}",
+                            lot.label.lot_number.as_deref().unwrap_or("(unnumbered)")
+                        )),
+                        operator_mdoc,
+                        customer_mdoc: None,
+                        ref_order_id: None,
+                        created_at: Some(now),
+                        po_id: None,
+                        unit_cost: None,
+                        stock_take_id: None,
+                        reason: InventoryReason::Expiry,
+                        lot_id: Some(lot.id),
+                    };
+                    self.inv_repo.create_with_tx(&row, tx)?;
+                    rows.push(row);
+                }
+                Ok(rows)
+            }),
+            "TransactionUseCases::write_off_expired_lots"
+        );
+        info!(
+            "operator {operator_mdoc} wrote off {} expired lot(s)",
+            rows.len()
+        );
+        Ok(rows)
+    }
+
     pub fn list_for_customer(
         &self,
         customer_mdoc: i32,
@@ -148,7 +1023,7 @@ impl TransactionUseCases {
         mdoc: Option<i32>,
         date: Option<String>,
         search: Option<String>,
-    ) -> Result<Vec<(CustomerTransaction, String, i32)>, AppError> {
+    ) -> Result<Vec<CustomerTxSearchRow>, AppError> {
         let limit = 10;
         let offset = page.saturating_sub(1) * limit;
         let res = try_log!(
@@ -180,32 +1055,50 @@ impl TransactionUseCases {
     }
 
     pub fn get_weekly_limit(&self) -> Result<i32, AppError> {
-        let res = try_log!(
-            self.limit_repo.get_limit(),
-            "TransactionUseCases::get_weekly_limit"
-        );
-        Ok(res)
+        self.limits.get_default_limit()
     }
 
     pub fn set_weekly_limit(&self, limit: i32) -> Result<(), AppError> {
-        try_log!(
-            self.limit_repo.set_limit(limit),
-            "TransactionUseCases::set_weekly_limit"
+        self.limits.set_default_limit(limit)
+    }
+
+    // The limit that applies to this customer today, in facility-local terms.
+    pub fn get_customer_limit(&self, customer_mdoc: i32) -> Result<ResolvedLimit, AppError> {
+        let window = self.limits.current_window()?;
+        self.limits.resolve(customer_mdoc, window.local_date)
+    }
+
+    // Remaining quantity-limit allowance for each upc, for the selected customer.
+    pub fn get_item_allowances(
+        &self,
+        customer_mdoc: i32,
+        upcs: Vec<String>,
+    ) -> Result<Vec<ItemAllowance>, AppError> {
+        let mut products = Vec::with_capacity(upcs.len());
+        for upc in upcs {
+            let product = try_log!(
+                self.prod_repo.get_by_upc(upc.clone()),
+                "TransactionUseCases::get_item_allowances"
+            )
+            .ok_or_else(|| AppError::NotFound(format!("product {upc} not found")))?;
+            products.push(product);
+        }
+        let res = try_log!(
+            self.limits.item_allowances(customer_mdoc, &products),
+            "TransactionUseCases::get_item_allowances"
         );
-        Ok(())
+        Ok(res)
     }
 
+    // Spent in the current limit period (kept under its original name for the UI).
     pub fn get_weekly_spent(&self, customer_mdoc: i32) -> Result<i32, AppError> {
-        let now = Utc::now().naive_utc();
-        let weekday = now.weekday().num_days_from_sunday() as i64;
-        let date = now.date();
-        let midnight = date
-            .and_hms_opt(0, 0, 0)
-            .ok_or_else(|| AppError::Unexpected("Could not construct midnight time".to_string()))?;
-        let week_start = midnight - Duration::days(weekday);
+        let window = try_log!(
+            self.limits.current_window(),
+            "TransactionUseCases::get_weekly_spent"
+        );
         let res = try_log!(
             self.cust_tx_repo
-                .get_weekly_spent(customer_mdoc, week_start),
+                .get_spent(customer_mdoc, window.start, window.end),
             "TransactionUseCases::get_weekly_spent"
         );
         Ok(res)
@@ -217,14 +1110,30 @@ mod tests {
     use super::*;
     use crate::domain::models::operator::Operator;
     use crate::domain::models::product::Product;
+    use crate::domain::models::{
+        BarcodeAlias, BundleComponent, CustomerTier, LimitOverride, Promotion, PromotionRule,
+        TaxRate, TaxSettings,
+    };
     use crate::domain::report_models::daily_sales::DailySales;
+    use crate::domain::report_models::margin_report::CategoryMargin;
     use crate::domain::report_models::product_sales::{ProductSalesByCategory, SalesTotals};
-    use crate::domain::repos::{OperatorRepoTrait, ProductRepoTrait};
+    use crate::domain::report_models::tax_summary::TaxSummaryRow;
+    use crate::domain::repos::{
+        CustomerLimitRepoTrait, OperatorRepoTrait, ProductRepoTrait, PromotionRepoTrait,
+        TaxRateRepoTrait, WeeklyLimitRepoTrait,
+    };
+    use crate::test_support::mock_category_repo::MockCategoryRepo;
+    use crate::test_support::mock_customer_limit_repo::MockCustomerLimitRepo;
     use crate::test_support::mock_customer_tx_detail_repo::MockCustomerTxDetailRepo;
     use crate::test_support::mock_customer_tx_repo::MockCustomerTransactionRepo;
     use crate::test_support::mock_inventory_transaction_repo::MockInventoryTransactionRepo;
     use crate::test_support::mock_operator_repo::MockOperatorRepo;
+    use crate::test_support::mock_order_return_repo::MockOrderReturnRepo;
+    use crate::test_support::mock_price_adjustment_repo::MockPriceAdjustmentRepo;
     use crate::test_support::mock_product_repo::MockProductRepo;
+    use crate::test_support::mock_promotion_repo::MockPromotionRepo;
+    use crate::test_support::mock_quantity_limit_repo::MockQuantityLimitRepo;
+    use crate::test_support::mock_tax_rate_repo::MockTaxRateRepo;
     use crate::test_support::mock_weekly_limit_repo::MockWeeklyLimitRepo;
     use chrono::NaiveDateTime;
     use rusqlite::{Connection, Transaction};
@@ -240,6 +1149,11 @@ mod tests {
                 ref_order_id: None,
                 reference: None,
                 created_at: None,
+                po_id: None,
+                unit_cost: None,
+                stock_take_id: None,
+                reason: InventoryReason::Other,
+                lot_id: None,
             }
         }
     }
@@ -270,6 +1184,13 @@ mod tests {
         ) -> Result<Vec<(CustomerTxDetail, String)>, AppError> {
             Ok(vec![])
         }
+        fn list_by_order_with_tx(
+            &self,
+            _order_id: i32,
+            _tx: &Transaction<'_>,
+        ) -> Result<Vec<(CustomerTxDetail, String)>, AppError> {
+            Ok(vec![])
+        }
         fn sales_by_category(
             &self,
             _from: NaiveDateTime,
@@ -295,17 +1216,60 @@ mod tests {
         ) -> Result<Vec<DailySales>, AppError> {
             Err(AppError::Unexpected("sales_by_day not implemented".into()))
         }
+        fn margin_by_category(
+            &self,
+            _from: NaiveDateTime,
+            _to: NaiveDateTime,
+        ) -> Result<Vec<CategoryMargin>, AppError> {
+            Err(AppError::Unexpected(
+                "margin_by_category not implemented".into(),
+            ))
+        }
+        fn tax_summary(
+            &self,
+            _from: NaiveDateTime,
+            _to: NaiveDateTime,
+        ) -> Result<Vec<TaxSummaryRow>, AppError> {
+            Err(AppError::Unexpected("tax_summary not implemented".into()))
+        }
     }
 
-    fn make_use_cases() -> (
+    type UseCaseFixture = (
         TransactionUseCases,
         Arc<dyn OperatorRepoTrait>,
         Arc<dyn ProductRepoTrait>,
         Arc<dyn InventoryTransactionRepoTrait>,
-        Arc<dyn CustomerTransactionRepoTrait>,
+        Arc<MockCustomerTransactionRepo>,
         Arc<dyn CustomerTxDetailRepoTrait>,
         Arc<dyn WeeklyLimitRepoTrait>,
-    ) {
+    );
+
+    fn no_taxes(prod_repo: Arc<dyn ProductRepoTrait>) -> Arc<TaxUseCases> {
+        Arc::new(TaxUseCases::new(
+            Arc::new(MockTaxRateRepo::default()),
+            prod_repo,
+            Arc::new(MockCategoryRepo::default()),
+        ))
+    }
+
+    fn no_promotions(prod_repo: Arc<dyn ProductRepoTrait>) -> Arc<PromotionUseCases> {
+        Arc::new(PromotionUseCases::new(
+            Arc::new(MockPromotionRepo::default()),
+            prod_repo,
+        ))
+    }
+
+    fn make_use_cases() -> UseCaseFixture {
+        make_use_cases_with(
+            Arc::new(MockPromotionRepo::default()),
+            Arc::new(MockTaxRateRepo::default()),
+        )
+    }
+
+    fn make_use_cases_with(
+        promo_repo: Arc<MockPromotionRepo>,
+        tax_repo: Arc<MockTaxRateRepo>,
+    ) -> UseCaseFixture {
         // Real DB only for atomic_tx; repos are all mocks
         let conn = Arc::new(Mutex::new(Connection::open_in_memory().unwrap()));
 
@@ -313,8 +1277,7 @@ mod tests {
         let prod_repo: Arc<dyn ProductRepoTrait> = Arc::new(MockProductRepo::default());
         let inv_repo: Arc<dyn InventoryTransactionRepoTrait> =
             Arc::new(MockInventoryTransactionRepo::default());
-        let cust_tx_repo: Arc<dyn CustomerTransactionRepoTrait> =
-            Arc::new(MockCustomerTransactionRepo::default());
+        let cust_tx_repo = Arc::new(MockCustomerTransactionRepo::default());
         let cust_tx_detail_repo: Arc<dyn CustomerTxDetailRepoTrait> =
             Arc::new(MockCustomerTxDetailRepo::default());
         let limit_repo: Arc<dyn WeeklyLimitRepoTrait> = Arc::new(MockWeeklyLimitRepo::default());
@@ -323,7 +1286,20 @@ mod tests {
             inv_repo.clone(),
             cust_tx_repo.clone(),
             cust_tx_detail_repo.clone(),
-            limit_repo.clone(),
+            Arc::new(LimitUseCases::new(
+                limit_repo.clone(),
+                Arc::new(MockCustomerLimitRepo::default()),
+                Arc::new(MockQuantityLimitRepo::default()),
+            )),
+            Arc::new(PromotionUseCases::new(promo_repo, prod_repo.clone())),
+            Arc::new(TaxUseCases::new(
+                tax_repo,
+                prod_repo.clone(),
+                Arc::new(MockCategoryRepo::default()),
+            )),
+            prod_repo.clone(),
+            Arc::new(MockOrderReturnRepo::default()),
+            Arc::new(MockPriceAdjustmentRepo::default()),
             conn.clone(),
         );
         (
@@ -339,7 +1315,7 @@ mod tests {
 
     #[test]
     fn inventory_and_sale_and_stock_flows() -> anyhow::Result<()> {
-        let (uc, op_repo, prod_repo, _, _, _, _) = make_use_cases();
+        let (uc, op_repo, prod_repo, _, cust_tx_repo, _, limit_repo) = make_use_cases();
         // seed FK tables
         op_repo.create(&Operator {
             mdoc: 10,
@@ -349,8 +1325,11 @@ mod tests {
         })?;
         prod_repo.create(&Product {
             upc: "000000000555".into(),
+            price: 1000,
             ..Default::default()
         })?;
+        cust_tx_repo.set_balance(20, 5000);
+        limit_repo.set_limit(5000)?;
         // inventory adjust
         let itx1 = uc.inventory_adjustment(InventoryTransaction {
             operator_mdoc: 10,
@@ -368,6 +1347,8 @@ mod tests {
                 operator_mdoc: 10,
                 date: None,
                 note: None,
+                idempotency_key: None,
+                reason: OrderReason::Regular,
             },
             vec![InventoryTransaction {
                 operator_mdoc: 10,
@@ -382,9 +1363,16 @@ mod tests {
                 upc: "000000000555".into(),
                 quantity: 2,
                 price: 1000,
+                cost: None,
+                discount: 0,
+                promotion_id: None,
+                promotion_name: None,
+                tax: 0,
+                tax_rate: None,
+                limit_exempt: false,
             }],
         )?;
-        assert_eq!(order_id, 1);
+        assert_eq!(order_id, SaleOutcome::Created(1));
         Ok(())
     }
 
@@ -424,9 +1412,57 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn adjustment_reason_must_fit_the_movement() -> anyhow::Result<()> {
+        let (uc, _, _, _, _, _, _) = make_use_cases();
+        let adjust = |quantity_change, reason| {
+            uc.inventory_adjustment(InventoryTransaction {
+                operator_mdoc: 1,
+                upc: "000000000001".into(),
+                quantity_change,
+                reason,
+                ..Default::default()
+            })
+        };
+        // write-offs only take stock out, receipts only add it
+        assert!(matches!(
+            adjust(3, InventoryReason::Damage),
+            Err(AppError::Validation(_))
+        ));
+        assert!(matches!(
+            adjust(-3, InventoryReason::Receipt),
+            Err(AppError::Validation(_))
+        ));
+        // sale-side reasons belong to the sale workflow
+        assert!(matches!(
+            adjust(-1, InventoryReason::Sale),
+            Err(AppError::Validation(_))
+        ));
+
+        adjust(10, InventoryReason::Receipt)?;
+        adjust(-2, InventoryReason::Damage)?;
+        adjust(-1, InventoryReason::Damage)?;
+        adjust(-4, InventoryReason::Expiry)?;
+        let start = Utc::now().naive_utc() - Duration::days(1);
+        let lines = uc.shrinkage_report(start, Utc::now().naive_utc())?;
+        let units: Vec<_> = lines.iter().map(|l| (l.reason, l.units)).collect();
+        assert_eq!(
+            units,
+            vec![(InventoryReason::Damage, 3), (InventoryReason::Expiry, 4)]
+        );
+        assert_eq!(
+            uc.count_inventory_transactions(None, None, Some(InventoryReason::Damage))?,
+            2
+        );
+        assert!(uc
+            .shrinkage_report(start, start - Duration::days(1))
+            .is_err());
+        Ok(())
+    }
+
     #[test]
     fn sale_transaction_commits_all_repos() -> Result<(), Box<dyn std::error::Error>> {
-        let (uc, op_repo, prod_repo, inv, cust_tx, det, _) = make_use_cases();
+        let (uc, op_repo, prod_repo, inv, cust_tx, det, limit_repo) = make_use_cases();
         // seed operator and product so we don't violate FKs
         op_repo.create(&Operator {
             mdoc: 1,
@@ -436,17 +1472,31 @@ mod tests {
         })?;
         prod_repo.create(&Product {
             upc: "A".into(),
+            price: 50,
+            ..Default::default()
+        })?;
+        uc.inventory_adjustment(InventoryTransaction {
+            operator_mdoc: 1,
+            upc: "A".into(),
+            quantity_change: 3,
             ..Default::default()
         })?;
+        cust_tx.set_balance(2, 100);
+        limit_repo.set_limit(100)?;
         let invs = vec![InventoryTransaction {
             id: None,
             upc: "A".into(),
-            quantity_change: 1,
+            quantity_change: -1,
             operator_mdoc: 1,
             customer_mdoc: Some(2),
             ref_order_id: None,
             reference: None,
             created_at: None,
+            po_id: None,
+            unit_cost: None,
+            stock_take_id: None,
+            reason: InventoryReason::Sale,
+            lot_id: None,
         }];
         let details = vec![CustomerTxDetail {
             detail_id: 0,
@@ -454,6 +1504,13 @@ mod tests {
             upc: "A".into(),
             quantity: 1,
             price: 50,
+            cost: None,
+            discount: 0,
+            promotion_id: None,
+            promotion_name: None,
+            tax: 0,
+            tax_rate: None,
+            limit_exempt: false,
         }];
         let ct = CustomerTransaction {
             order_id: 0,
@@ -461,10 +1518,12 @@ mod tests {
             operator_mdoc: 1,
             date: None,
             note: None,
+            idempotency_key: None,
+            reason: OrderReason::Regular,
         };
-        let order_id = uc.sale_transaction(ct, invs, details).unwrap();
+        let order_id = uc.sale_transaction(ct, invs, details).unwrap().order_id();
         assert!(order_id > 0);
-        assert_eq!(inv.list_for_product("A".into())?.len(), 1);
+        assert_eq!(inv.list_for_product("A".into())?.len(), 2);
         assert_eq!(cust_tx.list()?.len(), 1);
         let dets = det.list_by_order(order_id)?;
         assert_eq!(dets.len(), 1);
@@ -473,27 +1532,55 @@ mod tests {
 
     #[test]
     fn sale_transaction_rolls_back_on_detail_error() -> Result<(), AppError> {
-        let (_, _, _, inv, cust_tx, _, _) = make_use_cases();
+        let (_, _, prod_repo, inv, cust_tx, _, limit_repo) = make_use_cases();
         let fail_det = FailingDetailRepo::new();
         let conn = Arc::new(Mutex::new(Connection::open_in_memory()?));
         let uc = TransactionUseCases::new(
             inv.clone(),
             cust_tx.clone(),
             Arc::new(fail_det),
-            Arc::new(MockWeeklyLimitRepo::new()),
+            Arc::new(LimitUseCases::new(
+                limit_repo.clone(),
+                Arc::new(MockCustomerLimitRepo::default()),
+                Arc::new(MockQuantityLimitRepo::default()),
+            )),
+            no_promotions(prod_repo.clone()),
+            no_taxes(prod_repo.clone()),
+            prod_repo.clone(),
+            Arc::new(MockOrderReturnRepo::default()),
+            Arc::new(MockPriceAdjustmentRepo::default()),
             conn,
         );
 
-        // prepare one inventory‐tx + one bad detail (price=0)
-        let invs = vec![InventoryTransaction {
-            id: None,
+        // seed enough so validation passes and the detail insert is reached
+        prod_repo.create(&Product {
             upc: "B".into(),
-            quantity_change: 1,
-            operator_mdoc: 1,
-            customer_mdoc: Some(2),
-            ref_order_id: None,
+            price: 999,
+            ..Default::default()
+        })?;
+        uc.inventory_adjustment(InventoryTransaction {
+            operator_mdoc: 1,
+            upc: "B".into(),
+            quantity_change: 1,
+            ..Default::default()
+        })?;
+        cust_tx.set_balance(2, 999);
+        limit_repo.set_limit(999)?;
+
+        let invs = vec![InventoryTransaction {
+            id: None,
+            upc: "B".into(),
+            quantity_change: -1,
+            operator_mdoc: 1,
+            customer_mdoc: Some(2),
+            ref_order_id: None,
             reference: None,
             created_at: None,
+            po_id: None,
+            unit_cost: None,
+            stock_take_id: None,
+            reason: InventoryReason::Sale,
+            lot_id: None,
         }];
 
         // build a dummy customer transaction
@@ -503,6 +1590,8 @@ mod tests {
             operator_mdoc: 1,
             date: None,
             note: None,
+            idempotency_key: None,
+            reason: OrderReason::Regular,
         };
         // FailingDetailRepo will fail on create_with_tx
         let details = vec![CustomerTxDetail {
@@ -511,6 +1600,13 @@ mod tests {
             upc: "B".into(),
             quantity: 1,
             price: 999,
+            cost: None,
+            discount: 0,
+            promotion_id: None,
+            promotion_name: None,
+            tax: 0,
+            tax_rate: None,
+            limit_exempt: false,
         }];
         let result = uc.sale_transaction(cust_tx, invs, details);
         assert!(
@@ -519,4 +1615,896 @@ mod tests {
         );
         Ok(())
     }
+
+    type ValidatingFixture = (
+        TransactionUseCases,
+        Arc<dyn InventoryTransactionRepoTrait>,
+        Arc<MockCustomerTransactionRepo>,
+        Arc<dyn WeeklyLimitRepoTrait>,
+    );
+
+    // one product (upc "C", price 200) with 5 on hand; customer 2 has 1000 and a 1000 limit
+    fn make_validating_use_cases() -> Result<ValidatingFixture, AppError> {
+        make_validating_use_cases_with(
+            Arc::new(MockPromotionRepo::default()),
+            Arc::new(MockTaxRateRepo::default()),
+        )
+    }
+
+    fn make_validating_use_cases_with(
+        promo_repo: Arc<MockPromotionRepo>,
+        tax_repo: Arc<MockTaxRateRepo>,
+    ) -> Result<ValidatingFixture, AppError> {
+        let (uc, _, prod_repo, inv, cust_tx, _, limit_repo) =
+            make_use_cases_with(promo_repo, tax_repo);
+        prod_repo.create(&Product {
+            upc: "C".into(),
+            price: 200,
+            ..Default::default()
+        })?;
+        uc.inventory_adjustment(InventoryTransaction {
+            operator_mdoc: 1,
+            upc: "C".into(),
+            quantity_change: 5,
+            ..Default::default()
+        })?;
+        cust_tx.set_balance(2, 1000);
+        limit_repo.set_limit(1000)?;
+        Ok((uc, inv, cust_tx, limit_repo))
+    }
+
+    fn sell(uc: &TransactionUseCases, quantity: i32, price: i32) -> Result<i32, AppError> {
+        sell_keyed(uc, quantity, price, None).map(SaleOutcome::order_id)
+    }
+
+    fn sell_keyed(
+        uc: &TransactionUseCases,
+        quantity: i32,
+        price: i32,
+        idempotency_key: Option<&str>,
+    ) -> Result<SaleOutcome, AppError> {
+        uc.sale_transaction(
+            CustomerTransaction {
+                order_id: 0,
+                customer_mdoc: 2,
+                operator_mdoc: 1,
+                date: None,
+                note: None,
+                idempotency_key: idempotency_key.map(Into::into),
+                reason: OrderReason::Regular,
+            },
+            vec![InventoryTransaction {
+                upc: "C".into(),
+                quantity_change: -quantity,
+                operator_mdoc: 1,
+                customer_mdoc: Some(2),
+                ..Default::default()
+            }],
+            vec![CustomerTxDetail {
+                detail_id: 0,
+                order_id: 0,
+                upc: "C".into(),
+                quantity,
+                price,
+                cost: None,
+                discount: 0,
+                promotion_id: None,
+                promotion_name: None,
+                tax: 0,
+                tax_rate: None,
+                limit_exempt: false,
+            }],
+        )
+    }
+
+    fn assert_rejected<T: std::fmt::Debug>(result: Result<T, AppError>, needle: &str) {
+        match result {
+            Err(AppError::Validation(msg)) => {
+                assert!(msg.contains(needle), "unexpected message: {msg}")
+            }
+            other => panic!("expected validation error containing {needle:?}, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn sale_rejects_insufficient_funds() -> Result<(), AppError> {
+        let (uc, _, cust_tx, _) = make_validating_use_cases()?;
+        cust_tx.set_balance(2, 300);
+        assert_rejected(sell(&uc, 2, 200), "insufficient funds");
+        assert!(cust_tx.list()?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn sale_rejects_over_weekly_limit() -> Result<(), AppError> {
+        let (uc, _, _, limit_repo) = make_validating_use_cases()?;
+        limit_repo.set_limit(300)?;
+        assert_rejected(sell(&uc, 2, 200), "weekly limit exceeded");
+        Ok(())
+    }
+
+    #[test]
+    fn sale_uses_the_customers_resolved_limit() -> Result<(), AppError> {
+        let (_, _, prod_repo, inv, cust_tx, det, limit_repo) = make_use_cases();
+        let customer_limits = Arc::new(MockCustomerLimitRepo::default());
+        let uc = TransactionUseCases::new(
+            inv,
+            cust_tx.clone(),
+            det,
+            Arc::new(LimitUseCases::new(
+                limit_repo.clone(),
+                customer_limits.clone(),
+                Arc::new(MockQuantityLimitRepo::default()),
+            )),
+            no_promotions(prod_repo.clone()),
+            no_taxes(prod_repo.clone()),
+            prod_repo.clone(),
+            Arc::new(MockOrderReturnRepo::default()),
+            Arc::new(MockPriceAdjustmentRepo::default()),
+            Arc::new(Mutex::new(Connection::open_in_memory()?)),
+        );
+        prod_repo.create(&Product {
+            upc: "C".into(),
+            price: 200,
+            ..Default::default()
+        })?;
+        uc.inventory_adjustment(InventoryTransaction {
+            operator_mdoc: 1,
+            upc: "C".into(),
+            quantity_change: 5,
+            ..Default::default()
+        })?;
+        cust_tx.set_balance(2, 1000);
+        limit_repo.set_limit(1000)?;
+
+        // a restricted tier caps customer 2 below the store default
+        let tier = customer_limits.create_tier(&CustomerTier {
+            id: 0,
+            name: "Restricted".into(),
+            limit_amount: 300,
+        })?;
+        customer_limits.set_customer_tier(2, Some(tier))?;
+        assert_rejected(sell(&uc, 2, 200), "weekly limit exceeded");
+        assert_eq!(uc.get_customer_limit(2)?.amount, 300);
+
+        // an open-ended override lifts it again
+        customer_limits.create_override(&LimitOverride {
+            id: 0,
+            customer_mdoc: 2,
+            limit_amount: 600,
+            starts_on: Utc::now().date_naive(),
+            ends_on: None,
+            reason: "approved exception".into(),
+            operator_mdoc: 1,
+            created_at: None,
+        })?;
+        assert!(sell(&uc, 2, 200).is_ok());
+        Ok(())
+    }
+
+    #[test]
+    fn sale_respects_quantity_limits() -> Result<(), AppError> {
+        let (_, _, prod_repo, inv, cust_tx, det, limit_repo) = make_use_cases();
+        let quantities = Arc::new(MockQuantityLimitRepo::default());
+        let limits = Arc::new(LimitUseCases::new(
+            limit_repo.clone(),
+            Arc::new(MockCustomerLimitRepo::default()),
+            quantities.clone(),
+        ));
+        let uc = TransactionUseCases::new(
+            inv,
+            cust_tx.clone(),
+            det,
+            limits.clone(),
+            no_promotions(prod_repo.clone()),
+            no_taxes(prod_repo.clone()),
+            prod_repo.clone(),
+            Arc::new(MockOrderReturnRepo::default()),
+            Arc::new(MockPriceAdjustmentRepo::default()),
+            Arc::new(Mutex::new(Connection::open_in_memory()?)),
+        );
+        let fan = Product {
+            upc: "C".into(),
+            category: "Appliances".into(),
+            price: 200,
+            ..Default::default()
+        };
+        prod_repo.create(&fan)?;
+        uc.inventory_adjustment(InventoryTransaction {
+            operator_mdoc: 1,
+            upc: "C".into(),
+            quantity_change: 5,
+            ..Default::default()
+        })?;
+        cust_tx.set_balance(2, 1000);
+        limit_repo.set_limit(1000)?;
+
+        limits.create_quantity_limit(crate::domain::models::QuantityLimit {
+            id: 0,
+            upc: None,
+            category: Some("Appliances".into()),
+            max_quantity: 2,
+            window: crate::domain::models::QuantityWindow::Calendar(
+                crate::domain::models::LimitPeriod::Yearly,
+            ),
+            note: Some("2 fans per year".into()),
+            created_at: None,
+        })?;
+        quantities.record_purchase(2, &fan, 1, Utc::now().naive_utc() - Duration::hours(1));
+
+        assert_rejected(
+            sell(&uc, 2, 200),
+            "quantity limit exceeded for category Appliances",
+        );
+        assert_eq!(
+            uc.get_item_allowances(2, vec!["C".into()])?[0].remaining,
+            Some(1)
+        );
+        assert!(sell(&uc, 1, 200).is_ok());
+        Ok(())
+    }
+
+    #[test]
+    fn limit_exempt_lines_do_not_count_toward_the_limit() -> Result<(), AppError> {
+        let (_, _, _, inv, cust_tx, det, limit_repo) = make_use_cases();
+        let prod_repo = Arc::new(MockProductRepo::default());
+        let uc = TransactionUseCases::new(
+            inv,
+            cust_tx.clone(),
+            det.clone(),
+            Arc::new(LimitUseCases::new(
+                limit_repo.clone(),
+                Arc::new(MockCustomerLimitRepo::default()),
+                Arc::new(MockQuantityLimitRepo::default()),
+            )),
+            no_promotions(prod_repo.clone()),
+            no_taxes(prod_repo.clone()),
+            prod_repo.clone(),
+            Arc::new(MockOrderReturnRepo::default()),
+            Arc::new(MockPriceAdjustmentRepo::default()),
+            Arc::new(Mutex::new(Connection::open_in_memory()?)),
+        );
+        prod_repo.create(&Product {
+            upc: "C".into(),
+            category: "Postage".into(),
+            price: 200,
+            ..Default::default()
+        })?;
+        uc.inventory_adjustment(InventoryTransaction {
+            operator_mdoc: 1,
+            upc: "C".into(),
+            quantity_change: 5,
+            ..Default::default()
+        })?;
+        cust_tx.set_balance(2, 1000);
+        limit_repo.set_limit(300)?;
+
+        assert_rejected(sell(&uc, 2, 200), "weekly limit exceeded");
+        prod_repo.set_category_limit_exempt("Postage", true);
+        let exempt_order = sell(&uc, 2, 200)?;
+        // the line keeps its exemption even once the category loses it
+        prod_repo.set_category_limit_exempt("Postage", false);
+        assert!(det.list_by_order(exempt_order)?[0].0.limit_exempt);
+        // funds still apply to exempt items
+        cust_tx.set_balance(2, 500);
+        assert_rejected(sell(&uc, 3, 200), "insufficient funds");
+        Ok(())
+    }
+
+    #[test]
+    fn sale_rejects_more_than_on_hand() -> Result<(), AppError> {
+        let (uc, inv, _, _) = make_validating_use_cases()?;
+        match sell(&uc, 6, 200) {
+            Err(AppError::InsufficientStock(short)) => assert_eq!(
+                short,
+                vec![StockShortfall {
+                    upc: "C".into(),
+                    requested: 6,
+                    available: 5,
+                }]
+            ),
+            other => panic!("expected insufficient stock, got {other:?}"),
+        }
+        // only the seeding adjustment exists
+        assert_eq!(inv.list_for_product("C".into())?.len(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn negative_stock_policy_governs_sales_and_adjustments() -> Result<(), AppError> {
+        let (uc, inv, _, _) = make_validating_use_cases()?;
+        let write_off = |quantity_change| {
+            uc.inventory_adjustment(InventoryTransaction {
+                operator_mdoc: 1,
+                upc: "C".into(),
+                quantity_change,
+                reason: InventoryReason::Damage,
+                ..Default::default()
+            })
+        };
+        assert!(matches!(write_off(-6), Err(AppError::InsufficientStock(_))));
+        write_off(-2)?;
+
+        // warn and allow both let on-hand go negative
+        uc.set_negative_stock_policy(NegativeStockPolicy::Warn)?;
+        sell(&uc, 4, 200)?;
+        uc.set_negative_stock_policy(NegativeStockPolicy::Allow)?;
+        write_off(-1)?;
+        let on_hand: i32 = inv
+            .list_for_product("C".into())?
+            .iter()
+            .map(|t| t.quantity_change)
+            .sum();
+        assert_eq!(on_hand, -2);
+        assert_eq!(uc.negative_stock_policy()?, NegativeStockPolicy::Allow);
+        Ok(())
+    }
+
+    #[test]
+    fn sales_consume_lots_soonest_expiry_first() -> Result<(), AppError> {
+        let (uc, inv, _, _) = make_validating_use_cases()?;
+        let today = Utc::now().date_naive();
+        let receive = |quantity_change, lot_number: &str, days: i64| {
+            uc.receive_into_lot(
+                InventoryTransaction {
+                    operator_mdoc: 1,
+                    upc: "C".into(),
+                    quantity_change,
+                    reason: InventoryReason::Receipt,
+                    ..Default::default()
+                },
+                LotLabel::new(Some(lot_number.into()), Some(today + Duration::days(days))).unwrap(),
+            )
+        };
+        let late = receive(2, "L-LATE", 30)?.lot_id;
+        let soon = receive(3, "L-SOON", 5)?.lot_id;
+        let stale = receive(2, "L-OLD", -10)?.lot_id;
+        assert!(matches!(
+            receive(-1, "L-OLD", -10),
+            Err(AppError::Validation(_))
+        ));
+
+        let (_, expiring) = uc.expiring_lots(10)?;
+        let ids: Vec<_> = expiring.iter().map(|e| Some(e.lot.id)).collect();
+        assert_eq!(ids, vec![stale, soon]);
+
+        // 12 on hand but the expired lot can't be sold
+        assert!(matches!(
+            sell(&uc, 11, 200),
+            Err(AppError::InsufficientStock(_))
+        ));
+        let first = sell(&uc, 4, 200)?;
+        let second = sell(&uc, 2, 200)?;
+        let taken = |order| {
+            inv.list_for_product("C".into()).map(|rows| {
+                rows.into_iter()
+                    .filter(|t| t.ref_order_id == Some(order))
+                    .map(|t| (t.lot_id, t.quantity_change))
+                    .collect::<Vec<_>>()
+            })
+        };
+        assert_eq!(taken(first)?, vec![(soon, -3), (late, -1)]);
+        assert_eq!(taken(second)?, vec![(late, -1), (None, -1)]);
+
+        // only expired lots can be written off, and only once
+        let (stale, late) = (stale.unwrap(), late.unwrap());
+        assert_rejected(uc.write_off_expired_lots(&[late], 1), "hasn't expired");
+        let rows = uc.write_off_expired_lots(&[stale], 1)?;
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].quantity_change, -2);
+        assert_eq!(rows[0].reason, InventoryReason::Expiry);
+        assert_rejected(uc.write_off_expired_lots(&[stale], 1), "nothing left");
+        assert!(matches!(
+            uc.write_off_expired_lots(&[99], 1),
+            Err(AppError::NotFound(_))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn adjustments_keep_lot_stock_in_step_with_product_stock() -> Result<(), AppError> {
+        let (uc, _, prod_repo, inv, _, _, _) = make_use_cases();
+        prod_repo.create(&Product {
+            upc: "L".into(),
+            price: 100,
+            ..Default::default()
+        })?;
+        let today = Utc::now().date_naive();
+        let mut lot_ids = vec![];
+        for (quantity_change, lot_number, days) in [(4, "L-LATE", 30), (3, "L-OLD", -2)] {
+            let row = uc.receive_into_lot(
+                InventoryTransaction {
+                    operator_mdoc: 1,
+                    upc: "L".into(),
+                    quantity_change,
+                    reason: InventoryReason::Receipt,
+                    ..Default::default()
+                },
+                LotLabel::new(Some(lot_number.into()), Some(today + Duration::days(days))).unwrap(),
+            )?;
+            lot_ids.push(row.lot_id);
+        }
+        let (late, old) = (lot_ids[0], lot_ids[1]);
+
+        // the expired lot goes first, then the next to expire
+        let damaged = uc.inventory_adjustment(InventoryTransaction {
+            operator_mdoc: 1,
+            upc: "L".into(),
+            quantity_change: -5,
+            reason: InventoryReason::Damage,
+            ..Default::default()
+        })?;
+        assert_eq!(damaged.lot_id, None);
+        let taken: Vec<_> = inv
+            .list_for_product("L".into())?
+            .into_iter()
+            .filter(|t| t.reason == InventoryReason::Damage)
+            .map(|t| (t.lot_id, t.quantity_change))
+            .collect();
+        assert_eq!(taken, vec![(old, -3), (late, -2)]);
+
+        let mut conn = Connection::open_in_memory()?;
+        let t = conn.transaction()?;
+        let lot_stock: i32 = inv
+            .open_lots_with_tx("L", &t)?
+            .iter()
+            .map(|l| l.on_hand)
+            .sum();
+        assert_eq!(lot_stock, 2);
+        assert_eq!(lot_stock, inv.get_available_with_tx("L".into(), &t)?);
+        Ok(())
+    }
+
+    #[test]
+    fn kit_sales_draw_down_their_components() -> Result<(), AppError> {
+        let (uc, _, prod_repo, inv, cust_tx, det, limit_repo) = make_use_cases();
+        for (upc, price, unit_cost) in
+            [("K", 500, None), ("S", 150, Some(40)), ("T", 100, Some(25))]
+        {
+            prod_repo.create(&Product {
+                upc: upc.into(),
+                price,
+                unit_cost,
+                ..Default::default()
+            })?;
+        }
+        for (upc, quantity_change) in [("S", 5), ("T", 1)] {
+            uc.inventory_adjustment(InventoryTransaction {
+                operator_mdoc: 1,
+                upc: upc.into(),
+                quantity_change,
+                ..Default::default()
+            })?;
+        }
+        let mut conn = Connection::open_in_memory()?;
+        let t = conn.transaction()?;
+        let part = |component_upc: &str, quantity| BundleComponent {
+            bundle_upc: "K".into(),
+            component_upc: component_upc.into(),
+            quantity,
+        };
+        prod_repo.set_components_with_tx("K", &[part("S", 2), part("T", 1)], &t)?;
+        t.commit()?;
+        cust_tx.set_balance(2, 2000);
+        limit_repo.set_limit(2000)?;
+
+        // a kit's own stock can't be adjusted
+        assert_rejected(
+            uc.inventory_adjustment(InventoryTransaction {
+                operator_mdoc: 1,
+                upc: "K".into(),
+                quantity_change: 3,
+                ..Default::default()
+            }),
+            "is a kit",
+        );
+
+        let sell_kits = |quantity: i32| {
+            uc.sale_transaction(
+                CustomerTransaction {
+                    order_id: 0,
+                    customer_mdoc: 2,
+                    operator_mdoc: 1,
+                    date: None,
+                    note: None,
+                    idempotency_key: None,
+                    reason: OrderReason::Regular,
+                },
+                vec![InventoryTransaction {
+                    upc: "K".into(),
+                    quantity_change: -quantity,
+                    operator_mdoc: 1,
+                    customer_mdoc: Some(2),
+                    ..Default::default()
+                }],
+                vec![CustomerTxDetail {
+                    detail_id: 0,
+                    order_id: 0,
+                    upc: "K".into(),
+                    quantity,
+                    price: 500,
+                    cost: None,
+                    discount: 0,
+                    promotion_id: None,
+                    promotion_name: None,
+                    tax: 0,
+                    tax_rate: None,
+                    limit_exempt: false,
+                }],
+            )
+        };
+        // only one T on hand, so only one kit
+        assert!(matches!(sell_kits(2), Err(AppError::InsufficientStock(_))));
+        let order_id = sell_kits(1)?.order_id();
+
+        let dets = det.list_by_order(order_id)?;
+        assert_eq!(dets.len(), 1);
+        assert_eq!((dets[0].0.upc.as_str(), dets[0].0.price), ("K", 500));
+        assert_eq!(dets[0].0.cost, Some(2 * 40 + 25));
+        let sold = |upc: &str| {
+            inv.list_for_product(upc.into()).map(|rows| {
+                rows.into_iter()
+                    .filter(|r| r.ref_order_id == Some(order_id))
+                    .map(|r| r.quantity_change)
+                    .collect::<Vec<_>>()
+            })
+        };
+        assert_eq!(sold("S")?, vec![-2]);
+        assert_eq!(sold("T")?, vec![-1]);
+        assert!(inv.list_for_product("K".into())?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn sale_rejects_stale_price() -> Result<(), AppError> {
+        let (uc, _, _, _) = make_validating_use_cases()?;
+        assert_rejected(sell(&uc, 1, 150), "price mismatch");
+        Ok(())
+    }
+
+    #[test]
+    fn sale_rejects_unknown_product_and_bad_quantity() -> Result<(), AppError> {
+        let (uc, _, _, _) = make_validating_use_cases()?;
+        assert_rejected(sell(&uc, 0, 200), "invalid quantity");
+        let res = uc.sale_transaction(
+            CustomerTransaction {
+                order_id: 0,
+                customer_mdoc: 2,
+                operator_mdoc: 1,
+                date: None,
+                note: None,
+                idempotency_key: None,
+                reason: OrderReason::Regular,
+            },
+            vec![],
+            vec![CustomerTxDetail {
+                detail_id: 0,
+                order_id: 0,
+                upc: "missing".into(),
+                quantity: 1,
+                price: 1,
+                cost: None,
+                discount: 0,
+                promotion_id: None,
+                promotion_name: None,
+                tax: 0,
+                tax_rate: None,
+                limit_exempt: false,
+            }],
+        );
+        assert_rejected(res, "unknown or deleted product");
+        Ok(())
+    }
+
+    #[test]
+    fn resubmitted_sale_returns_the_original_order() -> Result<(), AppError> {
+        let (uc, inv, cust_tx, _) = make_validating_use_cases()?;
+        let first = sell_keyed(&uc, 4, 200, Some("till-1:0001"))?;
+        assert!(matches!(first, SaleOutcome::Created(_)));
+
+        // only 1 left on hand, so re-validating the retry would reject it
+        let again = sell_keyed(&uc, 4, 200, Some("till-1:0001"))?;
+        assert_eq!(again, SaleOutcome::Replayed(first.order_id()));
+        assert_eq!(cust_tx.list()?.len(), 1);
+        assert_eq!(inv.list_for_product("C".into())?.len(), 2);
+
+        // a new key is a new sale
+        let next = sell_keyed(&uc, 1, 200, Some("till-1:0002"))?;
+        assert_eq!(next, SaleOutcome::Created(first.order_id() + 1));
+        assert_rejected(sell_keyed(&uc, 1, 200, Some("  ")), "may not be blank");
+        Ok(())
+    }
+
+    #[test]
+    fn sale_within_rules_succeeds() -> Result<(), AppError> {
+        let (uc, _, _, _) = make_validating_use_cases()?;
+        assert!(sell(&uc, 5, 200)? > 0);
+        Ok(())
+    }
+
+    #[test]
+    fn void_order_records_reversal_and_restores_stock() -> Result<(), AppError> {
+        let (uc, inv, cust_tx, _) = make_validating_use_cases()?;
+        let order_id = sell(&uc, 2, 200)?;
+
+        let reversal_id = uc.void_order(order_id, 7, "miskeyed".into())?;
+        assert_ne!(reversal_id, order_id);
+
+        // original untouched, reversal carries the reason
+        assert!(cust_tx.get(order_id)?.unwrap().note.is_none());
+        let reversal = cust_tx.get(reversal_id)?.unwrap();
+        assert_eq!(reversal.operator_mdoc, 7);
+        assert!(reversal.note.unwrap().contains("miskeyed"));
+
+        // offsetting inventory row points back at the original order
+        let rows = inv.list_for_order(order_id)?;
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows.iter().map(|r| r.quantity_change).sum::<i32>(), 0);
+
+        // voided flag surfaces in search
+        let found = uc.search_customer_transactions(1, Some(2), None, None)?;
+        let voided: Vec<bool> = found
+            .iter()
+            .filter(|r| r.0.order_id == order_id)
+            .map(|r| r.3)
+            .collect();
+        assert_eq!(voided, vec![true]);
+
+        // neither side of the void counts against the week
+        assert_eq!(uc.get_weekly_spent(2)?, 0);
+        Ok(())
+    }
+
+    #[test]
+    fn promotions_are_priced_at_sale_time_and_prorated_on_return() -> Result<(), AppError> {
+        let promos = Arc::new(MockPromotionRepo::default());
+        let promo_id = promos.create(&Promotion {
+            id: 0,
+            name: "Quarter off".into(),
+            rule: PromotionRule::PercentOff(25),
+            upc: Some("C".into()),
+            category: None,
+            starts_at: None,
+            ends_at: None,
+            created_at: None,
+            deleted: None,
+        })?;
+        let (uc, _, cust_tx, _) =
+            make_validating_use_cases_with(promos, Arc::new(MockTaxRateRepo::default()))?;
+
+        // 4 x 2.00 less 25% fits a 6.00 balance only because of the promotion
+        cust_tx.set_balance(2, 600);
+        let order_id = sell(&uc, 4, 200)?;
+        let sold = uc.list_order_details(order_id)?[0].0.clone();
+        assert_eq!((sold.discount, sold.promotion_id), (200, Some(promo_id)));
+        assert_eq!(sold.net(), 600);
+
+        // each return gets back its share of the discount, and together they undo it
+        let first = uc.return_items(
+            order_id,
+            1,
+            vec![return_line(sold.detail_id, 1, true)],
+            None,
+        )?;
+        let second = uc.return_items(
+            order_id,
+            1,
+            vec![return_line(sold.detail_id, 3, true)],
+            None,
+        )?;
+        let refunds: Vec<i32> = [first, second]
+            .into_iter()
+            .map(|id| Ok(uc.list_order_details(id)?[0].0.discount))
+            .collect::<Result<_, AppError>>()?;
+        assert_eq!(refunds, vec![-50, -150]);
+        Ok(())
+    }
+
+    #[test]
+    fn case_barcodes_sell_and_adjust_as_units() -> Result<(), AppError> {
+        let (uc, inv, _, _) = make_validating_use_cases()?;
+        uc.prod_repo.add_barcode(&BarcodeAlias {
+            barcode: "C-PACK".into(),
+            upc: "C".into(),
+            multiplier: 2,
+            created_at: None,
+        })?;
+
+        // three 2-packs counted in are six units of the product
+        let adj = uc.inventory_adjustment(InventoryTransaction {
+            operator_mdoc: 1,
+            upc: "C-PACK".into(),
+            quantity_change: 3,
+            ..Default::default()
+        })?;
+        assert_eq!((adj.upc.as_str(), adj.quantity_change), ("C", 6));
+
+        let order_id = match uc.sale_transaction(
+            CustomerTransaction {
+                order_id: 0,
+                customer_mdoc: 2,
+                operator_mdoc: 1,
+                date: None,
+                note: None,
+                idempotency_key: None,
+                reason: OrderReason::Regular,
+            },
+            vec![InventoryTransaction {
+                upc: "C-PACK".into(),
+                quantity_change: -1,
+                operator_mdoc: 1,
+                customer_mdoc: Some(2),
+                ..Default::default()
+            }],
+            vec![CustomerTxDetail {
+                detail_id: 0,
+                order_id: 0,
+                upc: "C-PACK".into(),
+                quantity: 1,
+                price: 200,
+                cost: None,
+                discount: 0,
+                promotion_id: None,
+                promotion_name: None,
+                tax: 0,
+                tax_rate: None,
+                limit_exempt: false,
+            }],
+        )? {
+            SaleOutcome::Created(id) => id,
+            SaleOutcome::Replayed(_) => panic!("expected a new order"),
+        };
+        let sold = &uc.list_order_details(order_id)?[0].0;
+        assert_eq!((sold.upc.as_str(), sold.quantity), ("C", 2));
+        let taken: Vec<_> = inv
+            .list_for_order(order_id)?
+            .into_iter()
+            .map(|r| (r.upc, r.quantity_change))
+            .collect();
+        assert_eq!(taken, vec![("C".to_string(), -2)]);
+        Ok(())
+    }
+
+    #[test]
+    fn tax_is_charged_on_sale_and_refunded_on_return() -> Result<(), AppError> {
+        let taxes = Arc::new(MockTaxRateRepo::default());
+        taxes.create(&TaxRate {
+            id: 0,
+            upc: Some("C".into()),
+            category: None,
+            rate: 1000,
+            effective_at: None,
+            created_at: None,
+            deleted: None,
+        })?;
+        let (uc, _, cust_tx, limit_repo) =
+            make_validating_use_cases_with(Arc::new(MockPromotionRepo::default()), taxes.clone())?;
+
+        // 2 x 2.00 plus 10% tax is 4.40, which comes out of the balance either way
+        cust_tx.set_balance(2, 430);
+        assert_rejected(sell(&uc, 2, 200), "insufficient funds");
+        cust_tx.set_balance(2, 1000);
+        limit_repo.set_limit(420)?;
+        assert_rejected(sell(&uc, 2, 200), "weekly limit exceeded");
+        taxes.set_settings(&TaxSettings {
+            counts_toward_limit: false,
+        })?;
+        let order_id = sell(&uc, 2, 200)?;
+        let sold = uc.list_order_details(order_id)?[0].0.clone();
+        assert_eq!((sold.tax, sold.tax_rate), (40, Some(1000)));
+        assert_eq!(sold.charged(), 440);
+
+        let return_id = uc.return_items(
+            order_id,
+            1,
+            vec![return_line(sold.detail_id, 1, true)],
+            None,
+        )?;
+        assert_eq!(uc.list_order_details(return_id)?[0].0.tax, -20);
+        Ok(())
+    }
+
+    fn return_line(detail_id: i32, quantity: i32, restocked: bool) -> OrderReturnLine {
+        OrderReturnLine {
+            return_order_id: 0,
+            detail_id,
+            quantity,
+            restocked,
+        }
+    }
+
+    #[test]
+    fn return_items_caps_at_sold_minus_prior_returns() -> Result<(), AppError> {
+        let (uc, inv, _, _) = make_validating_use_cases()?;
+        let order_id = sell(&uc, 5, 200)?;
+        let detail_id = uc.list_order_details(order_id)?[0].0.detail_id;
+
+        let first = uc.return_items(
+            order_id,
+            1,
+            vec![return_line(detail_id, 2, false)],
+            Some("damaged".into()),
+        )?;
+        let lines = uc.list_order_details(first)?;
+        assert_eq!(lines.len(), 1);
+        assert_eq!(lines[0].0.quantity, -2);
+        assert_eq!(lines[0].0.price, 200);
+        // damaged goods are not put back on the shelf; only the sale row references the order
+        assert_eq!(inv.list_for_order(order_id)?.len(), 1);
+
+        assert_rejected(
+            uc.return_items(order_id, 1, vec![return_line(detail_id, 4, true)], None),
+            "only 3 left",
+        );
+        // duplicate lines in one request count together
+        assert_rejected(
+            uc.return_items(
+                order_id,
+                1,
+                vec![
+                    return_line(detail_id, 2, true),
+                    return_line(detail_id, 2, true),
+                ],
+                None,
+            ),
+            "only 1 left",
+        );
+
+        uc.return_items(order_id, 1, vec![return_line(detail_id, 3, true)], None)?;
+        let restock = inv.list_for_order(order_id)?;
+        assert_eq!(restock.len(), 2);
+        assert_eq!(restock[1].quantity_change, 3);
+        assert_rejected(
+            uc.return_items(order_id, 1, vec![return_line(detail_id, 1, true)], None),
+            "only 0 left",
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn return_items_blocks_void_and_foreign_lines() -> Result<(), AppError> {
+        let (uc, _, _, _) = make_validating_use_cases()?;
+        let order_id = sell(&uc, 2, 200)?;
+        let detail_id = uc.list_order_details(order_id)?[0].0.detail_id;
+
+        let return_id =
+            uc.return_items(order_id, 1, vec![return_line(detail_id, 1, true)], None)?;
+
+        assert_rejected(uc.void_order(order_id, 1, "oops".into()), "has returns");
+        assert_rejected(
+            uc.return_items(return_id, 1, vec![return_line(detail_id, 1, true)], None),
+            "itself a return",
+        );
+        assert_rejected(
+            uc.return_items(order_id, 1, vec![return_line(999, 1, true)], None),
+            "not part of order",
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn void_order_rejects_repeat_and_bad_input() -> Result<(), AppError> {
+        let (uc, _, _, _) = make_validating_use_cases()?;
+        let order_id = sell(&uc, 1, 200)?;
+
+        assert!(matches!(
+            uc.void_order(order_id, 1, "  ".into()),
+            Err(AppError::Validation(_))
+        ));
+        assert!(matches!(
+            uc.void_order(999, 1, "typo".into()),
+            Err(AppError::NotFound(_))
+        ));
+
+        let reversal_id = uc.void_order(order_id, 1, "typo".into())?;
+        assert!(matches!(
+            uc.void_order(order_id, 1, "again".into()),
+            Err(AppError::Validation(msg)) if msg.contains("already voided")
+        ));
+        assert!(matches!(
+            uc.void_order(reversal_id, 1, "undo".into()),
+            Err(AppError::Validation(msg)) if msg.contains("cannot be voided")
+        ));
+        Ok(())
+    }
 }
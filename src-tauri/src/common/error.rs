@@ -1,3 +1,4 @@
+use crate::domain::models::StockShortfall;
 use std::io;
 use tauri::ipc::InvokeError;
 use thiserror::Error;
@@ -19,6 +20,10 @@ pub enum AppError {
     #[error("validation error: {0}")]
     Validation(String),
 
+    // a sale or adjustment would take these upcs below zero on hand
+    #[error("insufficient stock: {}", .0.iter().map(ToString::to_string).collect::<Vec<_>>().join("; "))]
+    InsufficientStock(Vec<StockShortfall>),
+
     #[error("mutex was poisoned: {0}")]
     LockPoisoned(String),
 
@@ -37,6 +42,13 @@ pub enum AppError {
 
 impl From<AppError> for InvokeError {
     fn from(val: AppError) -> Self {
-        Self::from(val.to_string())
+        match val {
+            // the POS lists the short items, so they go over as data
+            AppError::InsufficientStock(ref items) => Self::from(serde_json::json!({
+                "message": val.to_string(),
+                "insufficient_stock": items,
+            })),
+            _ => Self::from(val.to_string()),
+        }
     }
 }
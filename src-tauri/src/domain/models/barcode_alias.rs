@@ -0,0 +1,18 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+// Another barcode that scans as `upc`: a case code counts as `multiplier`
+// units, an old or alternate unit code as 1.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BarcodeAlias {
+    pub barcode: String,
+    pub upc: String,
+    pub multiplier: i32,
+    pub created_at: Option<NaiveDateTime>,
+}
+
+// The cost of one unit out of a scan of `per_scan` units, to the nearest cent.
+#[must_use]
+pub const fn per_unit_cost(scan_cost: i32, per_scan: i32) -> i32 {
+    (scan_cost * 2 + per_scan) / (per_scan * 2)
+}
@@ -0,0 +1,125 @@
+use crate::common::error::AppError;
+
+// Which products a bulk price change covers.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ProductSelection {
+    Category(String),
+    Upcs(Vec<String>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PriceChange {
+    Percent(f64), // e.g. 5.0 raises by 5%, -10.0 lowers by 10%
+    Amount(i32),  // cents added (or taken off when negative)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RoundingMode {
+    Nearest,
+    Up,
+    Down,
+}
+
+impl RoundingMode {
+    pub fn parse(s: &str) -> Result<Self, AppError> {
+        match s {
+            "nearest" => Ok(Self::Nearest),
+            "up" => Ok(Self::Up),
+            "down" => Ok(Self::Down),
+            other => Err(AppError::Validation(format!(
+                "unknown rounding mode: {other}"
+            ))),
+        }
+    }
+}
+
+// New prices land on a multiple of `step` cents, e.g. 5 for nickels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PriceRounding {
+    pub step: i32,
+    pub mode: RoundingMode,
+}
+
+impl PriceRounding {
+    #[must_use]
+    pub const fn apply(self, cents: i32) -> i32 {
+        let step = if self.step > 0 { self.step } else { 1 };
+        let down = cents - cents.rem_euclid(step);
+        let up = if down == cents { cents } else { down + step };
+        match self.mode {
+            RoundingMode::Down => down,
+            RoundingMode::Up => up,
+            RoundingMode::Nearest if (cents - down) * 2 >= step => up,
+            RoundingMode::Nearest => down,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BulkPriceChange {
+    pub selection: ProductSelection,
+    pub change: PriceChange,
+    pub rounding: PriceRounding,
+}
+
+impl BulkPriceChange {
+    // The rounded new price for a product now at `old`; percentages go to the
+    // nearest cent before the rounding rule applies.
+    #[must_use]
+    pub fn new_price(&self, old: i32) -> i32 {
+        let raw = match self.change {
+            PriceChange::Percent(pct) => (f64::from(old) * (100.0 + pct) / 100.0).round() as i32,
+            PriceChange::Amount(delta) => old.saturating_add(delta),
+        };
+        self.rounding.apply(raw)
+    }
+}
+
+// One product's price before and after a bulk change.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BulkPriceLine {
+    pub upc: String,
+    pub desc: String,
+    pub category: String,
+    pub old: i32,
+    pub new: i32,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn change(change: PriceChange, step: i32, mode: RoundingMode) -> BulkPriceChange {
+        BulkPriceChange {
+            selection: ProductSelection::Category("Snacks".into()),
+            change,
+            rounding: PriceRounding { step, mode },
+        }
+    }
+
+    #[test]
+    fn percentages_and_rounding_rules() {
+        // 10% on $1.00 is exactly $1.10, not a hair over
+        assert_eq!(
+            change(PriceChange::Percent(10.0), 1, RoundingMode::Up).new_price(100),
+            110
+        );
+        assert_eq!(
+            change(PriceChange::Percent(7.0), 5, RoundingMode::Nearest).new_price(125),
+            135
+        );
+        assert_eq!(
+            change(PriceChange::Percent(7.0), 25, RoundingMode::Up).new_price(125),
+            150
+        );
+        assert_eq!(
+            change(PriceChange::Percent(-10.0), 10, RoundingMode::Down).new_price(199),
+            170
+        );
+        assert_eq!(
+            change(PriceChange::Amount(-30), 100, RoundingMode::Nearest).new_price(180),
+            200
+        );
+        assert!(RoundingMode::parse("sideways").is_err());
+    }
+}
@@ -0,0 +1,9 @@
+use serde::{Deserialize, Serialize};
+
+// One stocked item inside a kit, and how many of it a single kit holds.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BundleComponent {
+    pub bundle_upc: String,
+    pub component_upc: String,
+    pub quantity: i32,
+}
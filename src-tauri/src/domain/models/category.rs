@@ -3,4 +3,5 @@ pub struct Category {
     pub id: i32,
     pub name: String,
     pub deleted: Option<chrono::NaiveDateTime>,
+    pub limit_exempt: bool, // sales in this category don't count toward the spending limit
 }
@@ -0,0 +1,51 @@
+use chrono::{NaiveDate, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+
+// A class of customers (e.g. honor dorm, restricted housing) sharing a spending limit.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CustomerTier {
+    pub id: i32,
+    pub name: String,
+    pub limit_amount: i32,
+}
+
+// An individual limit that replaces the tier/default limit between two dates.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LimitOverride {
+    pub id: i32,
+    pub customer_mdoc: i32,
+    pub limit_amount: i32,
+    pub starts_on: NaiveDate,
+    pub ends_on: Option<NaiveDate>, // inclusive; open-ended when None
+    pub reason: String,
+    pub operator_mdoc: i32,
+    pub created_at: Option<NaiveDateTime>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LimitSource {
+    Default,
+    Tier,
+    Override,
+}
+
+impl LimitSource {
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Default => "default",
+            Self::Tier => "tier",
+            Self::Override => "override",
+        }
+    }
+}
+
+// The limit that applies to a customer on a given day and where it came from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResolvedLimit {
+    pub amount: i32,
+    pub source: LimitSource,
+    pub tier: Option<CustomerTier>,
+    pub limit_override: Option<LimitOverride>,
+}
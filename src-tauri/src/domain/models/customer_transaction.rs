@@ -1,3 +1,4 @@
+use crate::common::error::AppError;
 use chrono::NaiveDateTime;
 
 #[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
@@ -7,4 +8,69 @@ pub struct CustomerTransaction {
     pub operator_mdoc: i32,
     pub date: Option<NaiveDateTime>,
     pub note: Option<String>,
+    pub idempotency_key: Option<String>, // client-generated, unique per submitted sale
+    pub reason: OrderReason,
+}
+
+// Reason code an operator picks when ringing up an order.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OrderReason {
+    #[default]
+    Regular,
+    IndigentKit,
+    Replacement,
+    SpecialOrder,
+}
+
+impl OrderReason {
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Regular => "regular",
+            Self::IndigentKit => "indigent_kit",
+            Self::Replacement => "replacement",
+            Self::SpecialOrder => "special_order",
+        }
+    }
+
+    pub fn parse(s: &str) -> Result<Self, AppError> {
+        match s {
+            "regular" => Ok(Self::Regular),
+            "indigent_kit" => Ok(Self::IndigentKit),
+            "replacement" => Ok(Self::Replacement),
+            "special_order" => Ok(Self::SpecialOrder),
+            other => Err(AppError::Validation(format!(
+                "unknown order reason: {other}"
+            ))),
+        }
+    }
+
+    // Shown on receipts and reports.
+    #[must_use]
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Regular => "Regular sale",
+            Self::IndigentKit => "Indigent kit",
+            Self::Replacement => "Replacement",
+            Self::SpecialOrder => "Special order",
+        }
+    }
+}
+
+// What submitting a sale did: recorded a new order, or matched the idempotency
+// key of one already recorded and returned it untouched.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SaleOutcome {
+    Created(i32),
+    Replayed(i32),
+}
+
+impl SaleOutcome {
+    #[must_use]
+    pub const fn order_id(self) -> i32 {
+        match self {
+            Self::Created(id) | Self::Replayed(id) => id,
+        }
+    }
 }
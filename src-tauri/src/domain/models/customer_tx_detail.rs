@@ -7,4 +7,46 @@ pub struct CustomerTxDetail {
     pub upc: String,
     pub quantity: i32,
     pub price: i32,
+    pub cost: Option<i32>, // product's unit cost when sold; None if it had none
+    pub discount: i32,     // cents off the whole line; negative on reversal and return lines
+    pub promotion_id: Option<i32>, // the promotion that gave the discount
+    pub promotion_name: Option<String>, // its name when sold, for receipts
+    pub tax: i32,          // cents of sales tax on the line; negative like discount
+    pub tax_rate: Option<i32>, // hundredths of a percent it was taxed at; None if untaxed
+    pub limit_exempt: bool, // kept off the spending limit, as the product was when sold
+}
+
+impl CustomerTxDetail {
+    // What the line charged: quantity at price, less its discount.
+    #[must_use]
+    pub const fn net(&self) -> i32 {
+        self.quantity * self.price - self.discount
+    }
+
+    // What the line took from the balance: its net plus tax.
+    #[must_use]
+    pub const fn charged(&self) -> i32 {
+        self.net() + self.tax
+    }
+
+    // The part of the discount that goes back with `quantity` more units once
+    // `already` have been returned. Shares are taken off cumulative totals so a
+    // line returned in pieces gives back exactly its whole discount.
+    #[must_use]
+    pub const fn returned_discount(&self, already: i32, quantity: i32) -> i32 {
+        self.returned_share(self.discount, already, quantity)
+    }
+
+    // Likewise for the line's tax.
+    #[must_use]
+    pub const fn returned_tax(&self, already: i32, quantity: i32) -> i32 {
+        self.returned_share(self.tax, already, quantity)
+    }
+
+    const fn returned_share(&self, amount: i32, already: i32, quantity: i32) -> i32 {
+        if self.quantity == 0 {
+            return 0;
+        }
+        amount * (already + quantity) / self.quantity - amount * already / self.quantity
+    }
 }
@@ -0,0 +1,79 @@
+use crate::domain::models::InventoryTransaction;
+use chrono::{NaiveDate, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+
+// What's printed on a delivered batch; at least one of the two is set.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct LotLabel {
+    pub lot_number: Option<String>,
+    pub expires_on: Option<NaiveDate>,
+}
+
+impl LotLabel {
+    // None when neither field carries anything.
+    #[must_use]
+    pub fn new(lot_number: Option<String>, expires_on: Option<NaiveDate>) -> Option<Self> {
+        let lot_number = lot_number
+            .map(|n| n.trim().to_string())
+            .filter(|n| !n.is_empty());
+        (lot_number.is_some() || expires_on.is_some()).then_some(Self {
+            lot_number,
+            expires_on,
+        })
+    }
+}
+
+// A received lot and how much of it is still on hand.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InventoryLot {
+    pub id: i32,
+    pub upc: String,
+    pub label: LotLabel,
+    pub on_hand: i32,
+    pub created_at: Option<NaiveDateTime>,
+}
+
+impl InventoryLot {
+    // A lot may still be sold on its expiry date.
+    #[must_use]
+    pub fn is_expired(&self, today: NaiveDate) -> bool {
+        self.label.expires_on.is_some_and(|d| d < today)
+    }
+}
+
+// Splits a stock-out row over the product's open lots (soonest expiry first),
+// so lot on-hand moves with product on-hand. A sale passes the day it's sold
+// on and passes over expired lots; whatever the lots don't cover comes out of
+// stock that was received without a lot.
+#[must_use]
+pub fn fefo_rows(
+    row: &InventoryTransaction,
+    lots: &[InventoryLot],
+    sellable_on: Option<NaiveDate>,
+) -> Vec<InventoryTransaction> {
+    let mut left = -row.quantity_change;
+    let mut rows = Vec::new();
+    for lot in lots {
+        if left <= 0 {
+            break;
+        }
+        if sellable_on.is_some_and(|today| lot.is_expired(today)) {
+            continue;
+        }
+        let take = left.min(lot.on_hand);
+        rows.push(InventoryTransaction {
+            quantity_change: -take,
+            lot_id: Some(lot.id),
+            ..row.clone()
+        });
+        left -= take;
+    }
+    if left != 0 || rows.is_empty() {
+        rows.push(InventoryTransaction {
+            quantity_change: -left,
+            lot_id: None,
+            ..row.clone()
+        });
+    }
+    rows
+}
@@ -1,3 +1,4 @@
+use crate::common::error::AppError;
 use chrono::NaiveDateTime;
 
 #[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
@@ -10,4 +11,166 @@ pub struct InventoryTransaction {
     pub ref_order_id: Option<i32>,
     pub reference: Option<String>,
     pub created_at: Option<NaiveDateTime>,
+    pub po_id: Option<i32>, // set on stock received against a purchase order
+    pub unit_cost: Option<i32>, // cents per unit paid, on costed receipts
+    pub stock_take_id: Option<i32>, // set on variances posted from a stock take
+    pub reason: InventoryReason,
+    pub lot_id: Option<i32>, // the lot stock came from or went into, when tracked
+}
+
+impl InventoryTransaction {
+    // The reference carried by a kit's component rows, naming the kit.
+    #[must_use]
+    pub fn kit_reference(reference: Option<&str>, kit_upc: &str) -> String {
+        match reference {
+            Some(r) => format!("{r} (kit {kit_upc})"),
+            None => format!("kit {kit_upc}"),
+        }
+    }
+
+    // The kit this row moved stock for, read back from its reference.
+    #[must_use]
+    pub fn kit_upc(&self) -> Option<&str> {
+        let r = self.reference.as_deref()?;
+        let (head, upc) = r.strip_suffix(')').unwrap_or(r).rsplit_once("kit ")?;
+        (head.is_empty() || head.ends_with(" (")).then_some(upc)
+    }
+}
+
+// Why stock moved. Sales, voids and returns are tagged by the sale workflow;
+// the rest are picked by the operator making an adjustment.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum InventoryReason {
+    Receipt,
+    Sale,
+    CustomerReturn,
+    Void,
+    Shrinkage,
+    Damage,
+    Expiry,
+    VendorReturn,
+    CountCorrection,
+    #[default]
+    Other,
+}
+
+impl InventoryReason {
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Receipt => "receipt",
+            Self::Sale => "sale",
+            Self::CustomerReturn => "customer_return",
+            Self::Void => "void",
+            Self::Shrinkage => "shrinkage",
+            Self::Damage => "damage",
+            Self::Expiry => "expiry",
+            Self::VendorReturn => "vendor_return",
+            Self::CountCorrection => "count_correction",
+            Self::Other => "other",
+        }
+    }
+
+    pub fn parse(s: &str) -> Result<Self, AppError> {
+        match s {
+            "receipt" => Ok(Self::Receipt),
+            "sale" => Ok(Self::Sale),
+            "customer_return" => Ok(Self::CustomerReturn),
+            "void" => Ok(Self::Void),
+            "shrinkage" => Ok(Self::Shrinkage),
+            "damage" => Ok(Self::Damage),
+            "expiry" => Ok(Self::Expiry),
+            "vendor_return" => Ok(Self::VendorReturn),
+            "count_correction" => Ok(Self::CountCorrection),
+            "other" => Ok(Self::Other),
+            other => Err(AppError::Validation(format!(
+                "unknown inventory reason: {other}"
+            ))),
+        }
+    }
+
+    // Shown on reports.
+    #[must_use]
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Receipt => "Receipt",
+            Self::Sale => "Sale",
+            Self::CustomerReturn => "Customer return",
+            Self::Void => "Void",
+            Self::Shrinkage => "Shrinkage",
+            Self::Damage => "Damage",
+            Self::Expiry => "Expired",
+            Self::VendorReturn => "Vendor return",
+            Self::CountCorrection => "Count correction",
+            Self::Other => "Other",
+        }
+    }
+
+    // Reasons only the sale workflow records; a manual adjustment can't use them.
+    #[must_use]
+    pub const fn is_sale_side(self) -> bool {
+        matches!(self, Self::Sale | Self::CustomerReturn | Self::Void)
+    }
+
+    // Reasons that only ever take stock out.
+    #[must_use]
+    pub const fn is_write_off(self) -> bool {
+        matches!(
+            self,
+            Self::Shrinkage | Self::Damage | Self::Expiry | Self::VendorReturn
+        )
+    }
+}
+
+// What to do when a sale or adjustment would take a upc below zero on hand.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NegativeStockPolicy {
+    #[default]
+    Block,
+    Warn, // let it through and log the shortfall
+    Allow,
+}
+
+impl NegativeStockPolicy {
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Block => "block",
+            Self::Warn => "warn",
+            Self::Allow => "allow",
+        }
+    }
+
+    pub fn parse(s: &str) -> Result<Self, AppError> {
+        match s {
+            "block" => Ok(Self::Block),
+            "warn" => Ok(Self::Warn),
+            "allow" => Ok(Self::Allow),
+            other => Err(AppError::Validation(format!(
+                "unknown negative stock policy: {other}"
+            ))),
+        }
+    }
+}
+
+// A upc asked for more than is on hand.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct StockShortfall {
+    pub upc: String,
+    pub requested: i32,
+    pub available: i32,
+}
+
+impl std::fmt::Display for StockShortfall {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "upc {}: requested {}, available {}",
+            self.upc, self.requested, self.available
+        )
+    }
 }
@@ -0,0 +1,247 @@
+use crate::common::error::AppError;
+use chrono::{
+    Datelike, Duration, LocalResult, Months, NaiveDate, NaiveDateTime, TimeZone, Weekday,
+};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LimitPeriod {
+    Daily,
+    Weekly,
+    BiWeekly,
+    Monthly,
+    Yearly,
+}
+
+impl LimitPeriod {
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Daily => "daily",
+            Self::Weekly => "weekly",
+            Self::BiWeekly => "biweekly",
+            Self::Monthly => "monthly",
+            Self::Yearly => "yearly",
+        }
+    }
+
+    pub fn parse(s: &str) -> Result<Self, AppError> {
+        match s {
+            "daily" => Ok(Self::Daily),
+            "weekly" => Ok(Self::Weekly),
+            "biweekly" => Ok(Self::BiWeekly),
+            "monthly" => Ok(Self::Monthly),
+            "yearly" => Ok(Self::Yearly),
+            other => Err(AppError::Validation(format!(
+                "unknown limit period: {other}"
+            ))),
+        }
+    }
+
+    // Used in operator-facing messages, e.g. "weekly limit exceeded".
+    #[must_use]
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Daily => "daily",
+            Self::Weekly => "weekly",
+            Self::BiWeekly => "bi-weekly",
+            Self::Monthly => "monthly",
+            Self::Yearly => "yearly",
+        }
+    }
+}
+
+// How spending is bucketed for the limit, evaluated in the facility's time zone.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LimitSettings {
+    pub period: LimitPeriod,
+    pub week_start: Weekday, // weekly / bi-weekly periods begin on this day
+    pub anchor_date: NaiveDate, // any day a bi-weekly period starts on
+    pub time_zone: String,   // IANA name, e.g. "America/New_York"
+}
+
+impl Default for LimitSettings {
+    fn default() -> Self {
+        Self {
+            period: LimitPeriod::Weekly,
+            week_start: Weekday::Sun,
+            anchor_date: NaiveDate::from_ymd_opt(2025, 1, 5).unwrap_or_default(),
+            time_zone: "UTC".into(),
+        }
+    }
+}
+
+// One limit period: [start, end) in UTC, plus the facility-local date it was computed for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LimitWindow {
+    pub period: LimitPeriod,
+    pub start: NaiveDateTime,
+    pub end: NaiveDateTime,
+    pub local_date: NaiveDate,
+}
+
+impl LimitSettings {
+    pub fn tz(&self) -> Result<Tz, AppError> {
+        self.time_zone
+            .parse::<Tz>()
+            .map_err(|_| AppError::Validation(format!("unknown time zone: {}", self.time_zone)))
+    }
+
+    // The period containing `now` (UTC). Boundaries are local midnights converted
+    // back to UTC, so a period spanning a DST change is an hour shorter or longer.
+    pub fn window_at(&self, now: NaiveDateTime) -> Result<LimitWindow, AppError> {
+        self.window_for(self.period, now)
+    }
+
+    // Same as `window_at` but for any period, using this facility's week start,
+    // anchor and time zone (quantity limits carry their own period).
+    pub fn window_for(
+        &self,
+        period: LimitPeriod,
+        now: NaiveDateTime,
+    ) -> Result<LimitWindow, AppError> {
+        let tz = self.tz()?;
+        let local_date = tz.from_utc_datetime(&now).date_naive();
+        let (first, next) = match period {
+            LimitPeriod::Daily => (local_date, local_date + Duration::days(1)),
+            LimitPeriod::Weekly => {
+                let back = local_date.weekday().days_since(self.week_start);
+                let first = local_date - Duration::days(i64::from(back));
+                (first, first + Duration::days(7))
+            }
+            LimitPeriod::BiWeekly => {
+                let back = (local_date - self.anchor_date).num_days().rem_euclid(14);
+                let first = local_date - Duration::days(back);
+                (first, first + Duration::days(14))
+            }
+            LimitPeriod::Monthly => {
+                let first = local_date.with_day(1).unwrap_or(local_date);
+                (first, first + Months::new(1))
+            }
+            LimitPeriod::Yearly => {
+                let first = local_date.with_ordinal(1).unwrap_or(local_date);
+                (first, first + Months::new(12))
+            }
+        };
+        Ok(LimitWindow {
+            period,
+            start: local_midnight_utc(&tz, first)?,
+            end: local_midnight_utc(&tz, next)?,
+            local_date,
+        })
+    }
+}
+
+// First instant of `date` in `tz`, as UTC. Where midnight is skipped by a DST
+// jump the day starts at the first local time that exists.
+fn local_midnight_utc(tz: &Tz, date: NaiveDate) -> Result<NaiveDateTime, AppError> {
+    let midnight = date
+        .and_hms_opt(0, 0, 0)
+        .ok_or_else(|| AppError::Unexpected("Could not construct midnight time".to_string()))?;
+    for minutes in (0..=180).step_by(15) {
+        match tz.from_local_datetime(&(midnight + Duration::minutes(minutes))) {
+            LocalResult::Single(dt) | LocalResult::Ambiguous(dt, _) => return Ok(dt.naive_utc()),
+            LocalResult::None => {}
+        }
+    }
+    Err(AppError::Unexpected(format!(
+        "no local midnight for {date} in {}",
+        tz.name()
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utc(s: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    fn settings(period: LimitPeriod, time_zone: &str) -> LimitSettings {
+        LimitSettings {
+            period,
+            time_zone: time_zone.into(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn late_saturday_local_sale_stays_in_its_week() {
+        let s = settings(LimitPeriod::Weekly, "America/Chicago");
+        // Saturday 2025-06-14 22:30 CDT is already Sunday in UTC
+        let w = s.window_at(utc("2025-06-15 03:30")).unwrap();
+        assert_eq!(w.local_date, NaiveDate::from_ymd_opt(2025, 6, 14).unwrap());
+        assert_eq!(w.start, utc("2025-06-08 05:00"));
+        assert_eq!(w.end, utc("2025-06-15 05:00"));
+    }
+
+    #[test]
+    fn weeks_spanning_dst_changes_follow_local_midnight() {
+        let s = settings(LimitPeriod::Weekly, "America/New_York");
+        // spring forward on Sunday 2025-03-09: starts in EST, ends in EDT
+        let w = s.window_at(utc("2025-03-12 15:00")).unwrap();
+        assert_eq!(w.start, utc("2025-03-09 05:00"));
+        assert_eq!(w.end, utc("2025-03-16 04:00"));
+        assert_eq!(w.end - w.start, Duration::hours(167));
+
+        // fall back on Sunday 2025-11-02: starts in EDT, ends in EST
+        let w = s.window_at(utc("2025-11-05 15:00")).unwrap();
+        assert_eq!(w.start, utc("2025-11-02 04:00"));
+        assert_eq!(w.end, utc("2025-11-09 05:00"));
+        assert_eq!(w.end - w.start, Duration::hours(169));
+
+        // the hour before the spring-forward week starts belongs to the prior week
+        let w = s.window_at(utc("2025-03-09 04:59")).unwrap();
+        assert_eq!(w.end, utc("2025-03-09 05:00"));
+    }
+
+    #[test]
+    fn skipped_midnight_starts_the_day_at_the_first_valid_time() {
+        // Chile jumped from 00:00 to 01:00 on 2024-09-08
+        let s = settings(LimitPeriod::Daily, "America/Santiago");
+        let w = s.window_at(utc("2024-09-08 12:00")).unwrap();
+        assert_eq!(w.start, utc("2024-09-08 04:00"));
+        assert_eq!(w.end, utc("2024-09-09 03:00"));
+    }
+
+    #[test]
+    fn other_periods_and_start_days() {
+        let mut s = settings(LimitPeriod::Weekly, "UTC");
+        s.week_start = Weekday::Mon;
+        let w = s.window_at(utc("2025-06-15 12:00")).unwrap(); // a Sunday
+        assert_eq!(w.start, utc("2025-06-09 00:00"));
+
+        s.period = LimitPeriod::BiWeekly;
+        s.anchor_date = NaiveDate::from_ymd_opt(2025, 1, 6).unwrap();
+        let w = s.window_at(utc("2025-01-19 12:00")).unwrap();
+        assert_eq!(
+            (w.start, w.end),
+            (utc("2025-01-06 00:00"), utc("2025-01-20 00:00"))
+        );
+        // dates before the anchor still land on the same two-week grid
+        let w = s.window_at(utc("2024-12-30 00:00")).unwrap();
+        assert_eq!(w.start, utc("2024-12-23 00:00"));
+
+        s.period = LimitPeriod::Monthly;
+        let w = s.window_at(utc("2024-02-29 23:59")).unwrap();
+        assert_eq!(
+            (w.start, w.end),
+            (utc("2024-02-01 00:00"), utc("2024-03-01 00:00"))
+        );
+
+        let w = s
+            .window_for(LimitPeriod::Yearly, utc("2025-07-04 12:00"))
+            .unwrap();
+        assert_eq!(
+            (w.start, w.end),
+            (utc("2025-01-01 00:00"), utc("2026-01-01 00:00"))
+        );
+
+        assert!(settings(LimitPeriod::Daily, "Mars/Olympus")
+            .window_at(utc("2025-01-01 00:00"))
+            .is_err());
+    }
+}
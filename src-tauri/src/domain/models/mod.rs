@@ -1,23 +1,61 @@
+pub mod barcode_alias;
+pub mod bulk_price_change;
+pub mod bundle_component;
 pub mod category;
 pub mod club_import;
 pub mod club_transaction;
 pub mod customer;
+pub mod customer_limit;
 pub mod customer_transaction;
 pub mod customer_tx_detail;
+pub mod inventory_lot;
 pub mod inventory_transaction;
+pub mod limit_settings;
 pub mod operator;
+pub mod order_return;
+pub mod order_void;
 pub mod parsed_pdf;
 pub mod price_adjustment;
+pub mod print_job;
 pub mod product;
+pub mod product_import;
+pub mod promotion;
+pub mod purchase_order;
+pub mod quantity_limit;
+pub mod stock_take;
+pub mod tax_rate;
+pub mod vendor;
 
+pub use barcode_alias::{per_unit_cost, BarcodeAlias};
+pub use bulk_price_change::{
+    BulkPriceChange, BulkPriceLine, PriceChange, PriceRounding, ProductSelection, RoundingMode,
+};
+pub use bundle_component::BundleComponent;
 pub use category::Category;
 pub use club_import::ClubImport;
 pub use club_transaction::ClubTransaction;
 pub use customer::Customer;
-pub use customer_transaction::CustomerTransaction;
+pub use customer_limit::{CustomerTier, LimitOverride, LimitSource, ResolvedLimit};
+pub use customer_transaction::{CustomerTransaction, OrderReason, SaleOutcome};
 pub use customer_tx_detail::CustomerTxDetail;
-pub use inventory_transaction::InventoryTransaction;
+pub use inventory_lot::{fefo_rows, InventoryLot, LotLabel};
+pub use inventory_transaction::{
+    InventoryReason, InventoryTransaction, NegativeStockPolicy, StockShortfall,
+};
+pub use limit_settings::{LimitPeriod, LimitSettings, LimitWindow};
 pub use operator::Operator;
+pub use order_return::{OrderReturn, OrderReturnLine};
+pub use order_void::OrderVoid;
 pub use parsed_pdf::ParsedPdf;
-pub use price_adjustment::PriceAdjustment;
+pub use price_adjustment::{PriceAdjustment, PriceAdjustmentStatus};
+pub use print_job::{PrintJob, PrintJobStatus};
 pub use product::Product;
+pub use product_import::{ImportAction, ImportFailure, ProductImportLine, ProductImportPlan};
+pub use promotion::{LineDiscount, Promotion, PromotionRule};
+pub use purchase_order::{PurchaseOrder, PurchaseOrderLine, PurchaseOrderStatus, ReceivedItem};
+pub use quantity_limit::{
+    ActiveQuantityLimit, ItemAllowance, QuantityAllowance, QuantityLimit, QuantityWindow,
+};
+pub use stock_take::{CountedItem, StockTake, StockTakeLine, StockTakeStatus};
+pub use tax_rate::{TaxRate, TaxSettings};
+pub use vendor::Vendor;
@@ -0,0 +1,18 @@
+use chrono::NaiveDateTime;
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct OrderReturn {
+    pub return_order_id: i32,
+    pub order_id: i32, // the original sale
+    pub operator_mdoc: i32,
+    pub reason: Option<String>,
+    pub created_at: Option<NaiveDateTime>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct OrderReturnLine {
+    pub return_order_id: i32,
+    pub detail_id: i32, // line on the original sale
+    pub quantity: i32,
+    pub restocked: bool,
+}
@@ -0,0 +1,10 @@
+use chrono::NaiveDateTime;
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct OrderVoid {
+    pub order_id: i32,
+    pub reversal_order_id: i32,
+    pub operator_mdoc: i32,
+    pub reason: String,
+    pub created_at: Option<NaiveDateTime>,
+}
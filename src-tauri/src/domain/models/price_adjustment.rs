@@ -1,11 +1,44 @@
+use crate::common::error::AppError;
 use chrono::NaiveDateTime;
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PriceAdjustmentStatus {
+    Pending, // waiting for its effective time
+    Applied,
+    Cancelled,
+}
+
+impl PriceAdjustmentStatus {
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Applied => "applied",
+            Self::Cancelled => "cancelled",
+        }
+    }
+
+    pub fn parse(s: &str) -> Result<Self, AppError> {
+        match s {
+            "pending" => Ok(Self::Pending),
+            "applied" => Ok(Self::Applied),
+            "cancelled" => Ok(Self::Cancelled),
+            other => Err(AppError::Validation(format!(
+                "unknown price adjustment status: {other}"
+            ))),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub struct PriceAdjustment {
     pub id: i32, // auto‑assigned primary key
     pub operator_mdoc: i32,
     pub upc: String,
-    pub old: i32,
+    pub old: i32, // for a pending change, the price when it was scheduled
     pub new: i32,
     pub created_at: Option<NaiveDateTime>,
+    pub effective_at: Option<NaiveDateTime>, // None took effect when entered
+    pub status: PriceAdjustmentStatus,
 }
@@ -0,0 +1,56 @@
+use crate::common::error::AppError;
+use chrono::NaiveDateTime;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PrintJobStatus {
+    Pending,
+    Printing,
+    Done,
+    Failed,
+    Cancelled,
+}
+
+impl PrintJobStatus {
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Printing => "printing",
+            Self::Done => "done",
+            Self::Failed => "failed",
+            Self::Cancelled => "cancelled",
+        }
+    }
+
+    pub fn parse(s: &str) -> Result<Self, AppError> {
+        match s {
+            "pending" => Ok(Self::Pending),
+            "printing" => Ok(Self::Printing),
+            "done" => Ok(Self::Done),
+            "failed" => Ok(Self::Failed),
+            "cancelled" => Ok(Self::Cancelled),
+            other => Err(AppError::Validation(format!(
+                "unknown print job status: {other}"
+            ))),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct PrintJob {
+    pub id: i32,
+    pub order_id: i32,
+    pub copies: String, // customer | business | both
+    pub printer_name: String,
+    pub sumatra_location: String,
+    pub operator_name: String,
+    pub customer_name: String,
+    pub reprint_by: Option<i32>, // operator mdoc when this is a reprint
+    pub status: PrintJobStatus,
+    pub attempts: i32,
+    pub last_error: Option<String>,
+    pub balance: Option<i32>, // customer's balance right after the order, taken when queued
+    pub created_at: Option<NaiveDateTime>,
+    pub updated_at: Option<NaiveDateTime>,
+}
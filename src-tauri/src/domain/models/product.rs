@@ -9,4 +9,27 @@ pub struct Product {
     pub updated: Option<NaiveDateTime>,
     pub added: Option<NaiveDateTime>,
     pub deleted: Option<NaiveDateTime>,
+    pub limit_exempt: Option<bool>,    // None follows the category
+    pub unit_cost: Option<i32>,        // average cost in cents; None until first costed receipt
+    pub reorder_point: Option<i32>,    // low on stock at or below this; None never warns
+    pub reorder_quantity: Option<i32>, // units to order once low, when known
+}
+
+impl Product {
+    // Moving average cost after `quantity` units arrive at `unit_cost` on top of
+    // `on_hand`. With nothing (or less than nothing) on hand, or no cost yet,
+    // the receipt's cost simply becomes the product's cost.
+    #[must_use]
+    pub fn averaged_cost(&self, on_hand: i32, quantity: i32, unit_cost: i32) -> i32 {
+        match self.unit_cost {
+            Some(current) if on_hand > 0 && quantity > 0 => {
+                let total = i64::from(on_hand) * i64::from(current)
+                    + i64::from(quantity) * i64::from(unit_cost);
+                let units = i64::from(on_hand) + i64::from(quantity);
+                // round half up to the nearest cent
+                ((total * 2 + units) / (units * 2)) as i32
+            }
+            _ => unit_cost,
+        }
+    }
 }
@@ -0,0 +1,59 @@
+use crate::domain::models::Product;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportAction {
+    Create,    // upc not in the catalog
+    Restore,   // a deleted product comes back
+    Update,    // description, category or price differs
+    Unchanged, // already matches; nothing is written
+}
+
+impl ImportAction {
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Create => "create",
+            Self::Restore => "restore",
+            Self::Update => "update",
+            Self::Unchanged => "unchanged",
+        }
+    }
+}
+
+// What importing one row will do. `product` is the row as it will be saved;
+// `old_price` is set when the price changes, which is recorded as a price
+// adjustment.
+#[derive(Debug, Clone)]
+pub struct ProductImportLine {
+    pub line: usize, // 1-based line in the file
+    pub product: Product,
+    pub action: ImportAction,
+    pub old_price: Option<i32>,
+}
+
+// A row that can't be imported and why.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImportFailure {
+    pub line: usize,
+    pub upc: String,
+    pub message: String,
+}
+
+// An import checked against the catalog; only applied when `failures` is empty.
+#[derive(Debug, Clone, Default)]
+pub struct ProductImportPlan {
+    pub lines: Vec<ProductImportLine>,
+    pub failures: Vec<ImportFailure>,
+}
+
+impl ProductImportPlan {
+    #[must_use]
+    pub fn count(&self, action: ImportAction) -> usize {
+        self.lines.iter().filter(|l| l.action == action).count()
+    }
+
+    #[must_use]
+    pub fn price_changes(&self) -> usize {
+        self.lines.iter().filter(|l| l.old_price.is_some()).count()
+    }
+}
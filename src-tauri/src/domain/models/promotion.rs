@@ -0,0 +1,157 @@
+use crate::common::error::AppError;
+use crate::domain::models::Product;
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+// How a promotion takes money off a sale line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PromotionRule {
+    PercentOff(i32),               // whole percent off the line
+    AmountOff(i32),                // cents off each unit
+    SalePrice(i32),                // each unit sells for this many cents
+    BuyGet { buy: i32, get: i32 }, // of every buy + get units, `get` are free
+}
+
+impl PromotionRule {
+    // Stored as a kind plus a value; buy/get promotions keep their counts apart.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::PercentOff(_) => "percent_off",
+            Self::AmountOff(_) => "amount_off",
+            Self::SalePrice(_) => "sale_price",
+            Self::BuyGet { .. } => "buy_get",
+        }
+    }
+
+    #[must_use]
+    pub const fn value(self) -> i32 {
+        match self {
+            Self::PercentOff(v) | Self::AmountOff(v) | Self::SalePrice(v) => v,
+            Self::BuyGet { .. } => 0,
+        }
+    }
+
+    #[must_use]
+    pub const fn buy_get(self) -> Option<(i32, i32)> {
+        match self {
+            Self::BuyGet { buy, get } => Some((buy, get)),
+            _ => None,
+        }
+    }
+
+    pub fn parse(kind: &str, value: i32, buy_get: Option<(i32, i32)>) -> Result<Self, AppError> {
+        let rule = match (kind, buy_get) {
+            ("percent_off", _) => Self::PercentOff(value),
+            ("amount_off", _) => Self::AmountOff(value),
+            ("sale_price", _) => Self::SalePrice(value),
+            ("buy_get", Some((buy, get))) => Self::BuyGet { buy, get },
+            ("buy_get", None) => {
+                return Err(AppError::Validation(
+                    "a buy/get promotion needs both quantities".into(),
+                ))
+            }
+            (other, _) => {
+                return Err(AppError::Validation(format!(
+                    "unknown promotion kind: {other}"
+                )))
+            }
+        };
+        rule.check()?;
+        Ok(rule)
+    }
+
+    pub fn check(self) -> Result<(), AppError> {
+        let ok = match self {
+            Self::PercentOff(p) => (1..=100).contains(&p),
+            Self::AmountOff(c) => c > 0,
+            Self::SalePrice(c) => c >= 0,
+            Self::BuyGet { buy, get } => buy > 0 && get > 0,
+        };
+        if ok {
+            Ok(())
+        } else {
+            Err(AppError::Validation(format!(
+                "invalid {} promotion: {self:?}",
+                self.as_str()
+            )))
+        }
+    }
+
+    // Cents off `quantity` units at `price`; never more than the line is worth.
+    #[must_use]
+    pub fn discount(self, price: i32, quantity: i32) -> i32 {
+        let gross = price * quantity;
+        let off = match self {
+            Self::PercentOff(p) => (gross * p + 50) / 100,
+            Self::AmountOff(c) => c * quantity,
+            Self::SalePrice(c) => (price - c) * quantity,
+            Self::BuyGet { buy, get } => quantity / (buy + get) * get * price,
+        };
+        off.clamp(0, gross.max(0))
+    }
+}
+
+// A discount on one product or on every product in a category while it runs.
+// Exactly one of `upc` / `category` is set.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Promotion {
+    pub id: i32,
+    pub name: String, // printed on receipts
+    pub rule: PromotionRule,
+    pub upc: Option<String>,
+    pub category: Option<String>,
+    pub starts_at: Option<NaiveDateTime>, // None: already running
+    pub ends_at: Option<NaiveDateTime>,   // exclusive; None: runs until deleted
+    pub created_at: Option<NaiveDateTime>,
+    pub deleted: Option<NaiveDateTime>,
+}
+
+impl Promotion {
+    #[must_use]
+    pub fn applies_to(&self, product: &Product) -> bool {
+        self.upc.as_deref() == Some(product.upc.as_str())
+            || self.category.as_deref() == Some(product.category.as_str())
+    }
+
+    #[must_use]
+    pub fn is_running(&self, now: NaiveDateTime) -> bool {
+        self.deleted.is_none()
+            && self.starts_at.is_none_or(|s| s <= now)
+            && self.ends_at.is_none_or(|e| now < e)
+    }
+}
+
+// The promotion a sale line got and what it took off.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineDiscount {
+    pub upc: String,
+    pub promotion_id: i32,
+    pub promotion: String,
+    pub discount: i32,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rules_take_off_at_most_the_line() {
+        assert_eq!(PromotionRule::PercentOff(15).discount(199, 3), 90);
+        assert_eq!(PromotionRule::AmountOff(25).discount(100, 2), 50);
+        assert_eq!(PromotionRule::AmountOff(250).discount(100, 2), 200);
+        assert_eq!(PromotionRule::SalePrice(80).discount(100, 3), 60);
+        assert_eq!(PromotionRule::SalePrice(120).discount(100, 3), 0);
+        // buy 2 get 1: 7 units is two full groups
+        let b2g1 = PromotionRule::BuyGet { buy: 2, get: 1 };
+        assert_eq!(b2g1.discount(150, 7), 300);
+        assert_eq!(b2g1.discount(150, 2), 0);
+
+        assert!(PromotionRule::parse("percent_off", 0, None).is_err());
+        assert!(PromotionRule::parse("buy_get", 0, None).is_err());
+        assert_eq!(
+            PromotionRule::parse("buy_get", 0, Some((1, 1))).unwrap(),
+            PromotionRule::BuyGet { buy: 1, get: 1 }
+        );
+    }
+}
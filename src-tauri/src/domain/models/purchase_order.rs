@@ -0,0 +1,74 @@
+use crate::common::error::AppError;
+use crate::domain::models::LotLabel;
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PurchaseOrderStatus {
+    Open,
+    Received,
+    Cancelled,
+}
+
+impl PurchaseOrderStatus {
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Open => "open",
+            Self::Received => "received",
+            Self::Cancelled => "cancelled",
+        }
+    }
+
+    pub fn parse(s: &str) -> Result<Self, AppError> {
+        match s {
+            "open" => Ok(Self::Open),
+            "received" => Ok(Self::Received),
+            "cancelled" => Ok(Self::Cancelled),
+            other => Err(AppError::Validation(format!(
+                "unknown purchase order status: {other}"
+            ))),
+        }
+    }
+}
+
+// An order placed with a vendor. It stays open through partial shipments and
+// becomes received once every line has arrived in full.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PurchaseOrder {
+    pub id: i32,
+    pub vendor_id: i32,
+    pub reference: Option<String>, // vendor's order / invoice number
+    pub status: PurchaseOrderStatus,
+    pub operator_mdoc: i32,
+    pub created_at: Option<NaiveDateTime>,
+    pub received_at: Option<NaiveDateTime>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PurchaseOrderLine {
+    pub id: i32,
+    pub po_id: i32,
+    pub upc: String,
+    pub expected_quantity: i32, // 0 for items shipped but never ordered
+    pub received_quantity: i32,
+}
+
+impl PurchaseOrderLine {
+    // Positive when over-shipped, negative while short.
+    #[must_use]
+    pub const fn variance(&self) -> i32 {
+        self.received_quantity - self.expected_quantity
+    }
+}
+
+// One product counted off a delivery (or ordered, when creating a PO). Keyed
+// by a case barcode, quantity and unit_cost are per case until converted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReceivedItem {
+    pub upc: String,
+    pub quantity: i32,
+    pub unit_cost: Option<i32>, // cents per unit on the invoice, when known
+    pub lot: Option<LotLabel>,  // lot number / expiry printed on the delivery
+}
@@ -0,0 +1,124 @@
+use crate::common::error::AppError;
+use crate::domain::models::{LimitPeriod, LimitSettings, Product};
+use chrono::{Duration, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+
+// How far back a quantity limit looks: the last N days, or the current
+// calendar period in the facility's time zone.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum QuantityWindow {
+    Rolling(i32),
+    Calendar(LimitPeriod),
+}
+
+impl QuantityWindow {
+    // Stored as a window name plus a day count that only rolling windows use.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Rolling(_) => "rolling",
+            Self::Calendar(p) => p.as_str(),
+        }
+    }
+
+    pub fn parse(window: &str, rolling_days: Option<i32>) -> Result<Self, AppError> {
+        match (window, rolling_days) {
+            ("rolling", Some(days)) if days > 0 => Ok(Self::Rolling(days)),
+            ("rolling", _) => Err(AppError::Validation(
+                "a rolling window needs a positive number of days".into(),
+            )),
+            (other, _) => Ok(Self::Calendar(LimitPeriod::parse(other)?)),
+        }
+    }
+
+    #[must_use]
+    pub const fn rolling_days(self) -> Option<i32> {
+        match self {
+            Self::Rolling(days) => Some(days),
+            Self::Calendar(_) => None,
+        }
+    }
+
+    #[must_use]
+    pub fn describe(self) -> String {
+        match self {
+            Self::Rolling(1) => "per rolling day".into(),
+            Self::Rolling(days) => format!("per rolling {days} days"),
+            Self::Calendar(p) => p.label().to_string(),
+        }
+    }
+
+    // [start, end) in UTC for purchases counted against the limit at `now`.
+    // A rolling window ends now; nothing is dated later than the sale being checked.
+    pub fn bounds(
+        self,
+        settings: &LimitSettings,
+        now: NaiveDateTime,
+    ) -> Result<(NaiveDateTime, NaiveDateTime), AppError> {
+        match self {
+            Self::Rolling(days) => Ok((now - Duration::days(i64::from(days)), now)),
+            Self::Calendar(period) => {
+                let w = settings.window_for(period, now)?;
+                Ok((w.start, w.end))
+            }
+        }
+    }
+}
+
+// A cap on units of one product (upc) or of any product in a category.
+// Exactly one of `upc` / `category` is set.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QuantityLimit {
+    pub id: i32,
+    pub upc: Option<String>,
+    pub category: Option<String>,
+    pub max_quantity: i32,
+    pub window: QuantityWindow,
+    pub note: Option<String>,
+    pub created_at: Option<NaiveDateTime>,
+}
+
+impl QuantityLimit {
+    #[must_use]
+    pub fn applies_to(&self, product: &Product) -> bool {
+        self.upc.as_deref() == Some(product.upc.as_str())
+            || self.category.as_deref() == Some(product.category.as_str())
+    }
+
+    // e.g. "upc 0123456789" or "category Hygiene", for operator-facing messages
+    #[must_use]
+    pub fn target(&self) -> String {
+        match (&self.upc, &self.category) {
+            (Some(upc), _) => format!("upc {upc}"),
+            (None, Some(category)) => format!("category {category}"),
+            (None, None) => "nothing".into(),
+        }
+    }
+}
+
+// A quantity limit with its window resolved for a particular moment.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ActiveQuantityLimit {
+    pub limit: QuantityLimit,
+    pub start: NaiveDateTime,
+    pub end: NaiveDateTime,
+}
+
+// What a customer can still buy under one quantity limit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QuantityAllowance {
+    pub limit: QuantityLimit,
+    pub purchased: i32,
+    pub remaining: i32,
+    pub window_start: NaiveDateTime,
+    pub window_end: NaiveDateTime,
+}
+
+// All quantity limits touching one product; `remaining` is the tightest of them,
+// or None when the product is not limited.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ItemAllowance {
+    pub upc: String,
+    pub remaining: Option<i32>,
+    pub limits: Vec<QuantityAllowance>,
+}
@@ -0,0 +1,72 @@
+use crate::common::error::AppError;
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StockTakeStatus {
+    Open,
+    Posted,
+    Cancelled,
+}
+
+impl StockTakeStatus {
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Open => "open",
+            Self::Posted => "posted",
+            Self::Cancelled => "cancelled",
+        }
+    }
+
+    pub fn parse(s: &str) -> Result<Self, AppError> {
+        match s {
+            "open" => Ok(Self::Open),
+            "posted" => Ok(Self::Posted),
+            "cancelled" => Ok(Self::Cancelled),
+            other => Err(AppError::Validation(format!(
+                "unknown stock take status: {other}"
+            ))),
+        }
+    }
+}
+
+// A physical count session. Expected quantities are frozen when it opens;
+// posting turns every counted variance into an inventory adjustment.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StockTake {
+    pub id: i32,
+    pub category: Option<String>, // None counts every product
+    pub blind: bool,              // counters aren't shown expected quantities
+    pub status: StockTakeStatus,
+    pub note: Option<String>,
+    pub operator_mdoc: i32,
+    pub created_at: Option<NaiveDateTime>,
+    pub posted_at: Option<NaiveDateTime>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StockTakeLine {
+    pub id: i32,
+    pub stock_take_id: i32,
+    pub upc: String,
+    pub expected_quantity: i32,        // on hand when the session opened
+    pub counted_quantity: Option<i32>, // None until counted
+    pub counted_at: Option<NaiveDateTime>,
+}
+
+impl StockTakeLine {
+    // Counted minus expected; None while the line hasn't been counted.
+    #[must_use]
+    pub fn variance(&self) -> Option<i32> {
+        self.counted_quantity.map(|c| c - self.expected_quantity)
+    }
+}
+
+// One product's count as keyed in by a counter.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CountedItem {
+    pub upc: String,
+    pub quantity: i32,
+}
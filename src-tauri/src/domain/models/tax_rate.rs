@@ -0,0 +1,65 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+// A sales tax rate on every product in a category, or on one product to
+// override its category's. Exactly one of `upc` / `category` is set.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TaxRate {
+    pub id: i32,
+    pub upc: Option<String>,
+    pub category: Option<String>,
+    pub rate: i32, // hundredths of a percent: 825 is 8.25%; 0 exempts
+    pub effective_at: Option<NaiveDateTime>, // None: in effect since it was entered
+    pub created_at: Option<NaiveDateTime>,
+    pub deleted: Option<NaiveDateTime>,
+}
+
+impl TaxRate {
+    #[must_use]
+    pub fn in_effect(&self, now: NaiveDateTime) -> bool {
+        self.deleted.is_none() && self.effective_at.is_none_or(|e| e <= now)
+    }
+
+    // Tax on `amount` cents, to the nearest cent (half a cent rounds up).
+    #[must_use]
+    pub fn tax_on(&self, amount: i32) -> i32 {
+        let tax = (i64::from(amount) * i64::from(self.rate) + 5_000).div_euclid(10_000);
+        i32::try_from(tax).unwrap_or(i32::MAX)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TaxSettings {
+    pub counts_toward_limit: bool, // tax always comes out of the balance
+}
+
+impl Default for TaxSettings {
+    fn default() -> Self {
+        Self {
+            counts_toward_limit: true,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tax_rounds_to_the_nearest_cent() {
+        let rate = |rate| TaxRate {
+            id: 0,
+            upc: None,
+            category: Some("Snacks".into()),
+            rate,
+            effective_at: None,
+            created_at: None,
+            deleted: None,
+        };
+        assert_eq!(rate(825).tax_on(1000), 83); // 82.5
+        assert_eq!(rate(825).tax_on(999), 82); // 82.42
+        assert_eq!(rate(600).tax_on(0), 0);
+        assert_eq!(rate(0).tax_on(1000), 0);
+        assert_eq!(rate(10_000).tax_on(i32::MAX), i32::MAX);
+    }
+}
@@ -0,0 +1,14 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+// A supplier stock is ordered from. Deleting one only hides it; its purchase
+// orders keep pointing at it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Vendor {
+    pub id: i32,
+    pub name: String,
+    pub contact: Option<String>,
+    pub phone: Option<String>,
+    pub deleted: Option<NaiveDateTime>,
+    pub created_at: Option<NaiveDateTime>,
+}
@@ -0,0 +1,27 @@
+use crate::domain::models::InventoryLot;
+use chrono::NaiveDate;
+
+// A lot with stock left that expires within the report's window.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExpiringLot {
+    pub lot: InventoryLot,
+    pub product_name: String,
+    pub category: String,
+    pub price: i32,
+}
+
+impl ExpiringLot {
+    // Negative once the lot has expired.
+    #[must_use]
+    pub fn days_left(&self, today: NaiveDate) -> i64 {
+        self.lot
+            .label
+            .expires_on
+            .map_or(0, |d| (d - today).num_days())
+    }
+
+    #[must_use]
+    pub const fn value(&self) -> i32 {
+        self.lot.on_hand * self.price
+    }
+}
@@ -0,0 +1,47 @@
+// Revenue against cost of goods sold for one category over a date range.
+// Lines sold before the product had a cost count toward revenue but are left
+// out of the margin, so a partly costed category isn't shown as pure profit.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct CategoryMargin {
+    pub category: String,
+    pub quantity_sold: i32,
+    pub revenue: i32,
+    pub costed_revenue: i32, // revenue from lines that carry a cost
+    pub cogs: i32,
+}
+
+impl CategoryMargin {
+    #[must_use]
+    pub const fn margin(&self) -> i32 {
+        self.costed_revenue - self.cogs
+    }
+
+    // None when nothing sold in the category had a cost.
+    #[must_use]
+    pub fn margin_pct(&self) -> Option<f64> {
+        (self.costed_revenue != 0)
+            .then(|| f64::from(self.margin()) * 100.0 / f64::from(self.costed_revenue))
+    }
+
+    #[must_use]
+    pub const fn uncosted_revenue(&self) -> i32 {
+        self.revenue - self.costed_revenue
+    }
+
+    #[must_use]
+    pub fn total(rows: &[Self]) -> Self {
+        rows.iter().fold(
+            Self {
+                category: "Total".into(),
+                ..Self::default()
+            },
+            |mut acc, r| {
+                acc.quantity_sold += r.quantity_sold;
+                acc.revenue += r.revenue;
+                acc.costed_revenue += r.costed_revenue;
+                acc.cogs += r.cogs;
+                acc
+            },
+        )
+    }
+}
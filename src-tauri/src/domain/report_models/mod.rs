@@ -1,6 +1,13 @@
 pub mod accounts_rpt_footer;
 pub mod club_import_report;
 pub mod daily_sales;
+pub mod expiry_report;
+pub mod margin_report;
 pub mod product_inventory;
 pub mod product_sales;
+pub mod receiving_report;
+pub mod reorder_report;
 pub mod sales_details;
+pub mod shrinkage_report;
+pub mod stock_take_report;
+pub mod tax_summary;
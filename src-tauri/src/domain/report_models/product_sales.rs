@@ -4,12 +4,16 @@ pub struct ProductSalesByCategory {
     pub name: String,
     pub quantity_sold: i32,
     pub price: i32,
-    pub total_sales: i32,
+    pub gross_sales: i32, // at list price
+    pub discounts: i32,   // promotions taken off
+    pub total_sales: i32, // net: gross less discounts
     pub is_summary: bool,
 }
 
 #[derive(Clone, Copy)]
 pub struct SalesTotals {
     pub total_quantity: i32,
-    pub total_value: i32,
+    pub total_value: i32,    // net of discounts
+    pub total_discount: i32, // promotions taken off
+    pub total_tax: i32,      // charged on top of total_value
 }
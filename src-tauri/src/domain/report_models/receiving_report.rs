@@ -0,0 +1,26 @@
+use crate::domain::models::{PurchaseOrder, PurchaseOrderLine};
+use std::cmp::Ordering;
+
+pub struct ReceivingReport {
+    pub po: PurchaseOrder,
+    pub vendor_name: String,
+    pub lines: Vec<ReceivingReportLine>,
+}
+
+pub struct ReceivingReportLine {
+    pub line: PurchaseOrderLine,
+    pub product_name: String,
+    pub received_now: i32, // units posted by the receipt this report was printed for
+}
+
+impl ReceivingReportLine {
+    // "OVER" / "SHORT" for lines that don't match what was ordered.
+    #[must_use]
+    pub fn flag(&self) -> Option<&'static str> {
+        match self.line.variance().cmp(&0) {
+            Ordering::Greater => Some("OVER"),
+            Ordering::Less => Some("SHORT"),
+            Ordering::Equal => None,
+        }
+    }
+}
@@ -0,0 +1,12 @@
+// A product at or below its reorder point. The vendor is the one it was last
+// ordered from, when it has ever been on a purchase order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LowStockItem {
+    pub upc: String,
+    pub desc: String,
+    pub category: String,
+    pub available: i32,
+    pub reorder_point: i32,
+    pub reorder_quantity: Option<i32>,
+    pub vendor_name: Option<String>,
+}
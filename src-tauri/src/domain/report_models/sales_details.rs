@@ -14,5 +14,7 @@ pub struct SalesReportDetailRow {
     pub upc: String,
     pub quantity: i32,
     pub price: i32,
+    pub discount: i32, // promotion taken off the line
+    pub tax: i32,      // charged on top of the line
     pub product_name: String,
 }
@@ -0,0 +1,53 @@
+use crate::domain::models::InventoryReason;
+
+// Stock written off for one reason in one category over a date range.
+// Units are shown as a positive loss; value is at the product's current cost
+// and price, and units of products with no cost yet are left out of the cost.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ShrinkageLine {
+    pub reason: InventoryReason,
+    pub category: String,
+    pub units: i32,
+    pub retail_value: i32,
+    pub cost_value: i32,
+    pub uncosted_units: i32,
+}
+
+impl ShrinkageLine {
+    // One line per reason, summed across categories, in the order given.
+    #[must_use]
+    pub fn by_reason(lines: &[Self]) -> Vec<Self> {
+        let mut out: Vec<Self> = Vec::new();
+        for l in lines {
+            match out.iter_mut().find(|o| o.reason == l.reason) {
+                Some(o) => o.add(l),
+                None => out.push(Self {
+                    category: String::new(),
+                    ..l.clone()
+                }),
+            }
+        }
+        out
+    }
+
+    #[must_use]
+    pub fn total(lines: &[Self]) -> Self {
+        lines.iter().fold(
+            Self {
+                category: "Total".into(),
+                ..Self::default()
+            },
+            |mut acc, l| {
+                acc.add(l);
+                acc
+            },
+        )
+    }
+
+    fn add(&mut self, other: &Self) {
+        self.units += other.units;
+        self.retail_value += other.retail_value;
+        self.cost_value += other.cost_value;
+        self.uncosted_units += other.uncosted_units;
+    }
+}
@@ -0,0 +1,54 @@
+use crate::domain::models::{StockTake, StockTakeLine};
+
+pub struct StockTakeReport {
+    pub stock_take: StockTake,
+    pub lines: Vec<StockTakeReportLine>,
+}
+
+pub struct StockTakeReportLine {
+    pub line: StockTakeLine,
+    pub product_name: String,
+    pub price: i32, // current sale price in cents
+}
+
+impl StockTakeReportLine {
+    // Variance valued at the sale price; None while uncounted.
+    #[must_use]
+    pub fn value_impact(&self) -> Option<i32> {
+        self.line.variance().map(|v| v * self.price)
+    }
+}
+
+impl StockTakeReport {
+    #[must_use]
+    pub fn uncounted(&self) -> usize {
+        self.lines
+            .iter()
+            .filter(|l| l.line.counted_quantity.is_none())
+            .count()
+    }
+
+    // Value of counted lines that came up short, as a negative number.
+    #[must_use]
+    pub fn shrink_value(&self) -> i32 {
+        self.lines
+            .iter()
+            .filter_map(StockTakeReportLine::value_impact)
+            .filter(|v| *v < 0)
+            .sum()
+    }
+
+    #[must_use]
+    pub fn overage_value(&self) -> i32 {
+        self.lines
+            .iter()
+            .filter_map(StockTakeReportLine::value_impact)
+            .filter(|v| *v > 0)
+            .sum()
+    }
+
+    #[must_use]
+    pub fn net_value(&self) -> i32 {
+        self.shrink_value() + self.overage_value()
+    }
+}
@@ -0,0 +1,7 @@
+// Sales taxed at one rate over a report's period, net of voids and returns.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TaxSummaryRow {
+    pub rate: i32,          // hundredths of a percent
+    pub taxable_sales: i32, // what the lines charged before tax
+    pub tax: i32,
+}
@@ -9,4 +9,5 @@ pub trait CategoryRepoTrait: Send + Sync {
     fn soft_delete(&self, id: i32) -> Result<(), AppError>;
     fn get_by_name(&self, name: &str) -> Result<Option<Category>, AppError>;
     fn undelete(&self, id: i32) -> Result<(), AppError>;
+    fn set_limit_exempt(&self, id: i32, limit_exempt: bool) -> Result<(), AppError>;
 }
@@ -0,0 +1,26 @@
+use crate::common::error::AppError;
+use crate::domain::models::{CustomerTier, LimitOverride};
+use chrono::NaiveDate;
+
+pub trait CustomerLimitRepoTrait: Send + Sync {
+    fn list_tiers(&self) -> Result<Vec<CustomerTier>, AppError>;
+    fn get_tier(&self, id: i32) -> Result<Option<CustomerTier>, AppError>;
+    fn create_tier(&self, tier: &CustomerTier) -> Result<i32, AppError>;
+    fn update_tier(&self, tier: &CustomerTier) -> Result<(), AppError>;
+    // Also removes the tier from any customers assigned to it.
+    fn delete_tier(&self, id: i32) -> Result<(), AppError>;
+
+    fn get_customer_tier(&self, customer_mdoc: i32) -> Result<Option<CustomerTier>, AppError>;
+    // None clears the assignment.
+    fn set_customer_tier(&self, customer_mdoc: i32, tier_id: Option<i32>) -> Result<(), AppError>;
+
+    fn create_override(&self, o: &LimitOverride) -> Result<i32, AppError>;
+    fn list_overrides(&self, customer_mdoc: i32) -> Result<Vec<LimitOverride>, AppError>;
+    fn delete_override(&self, id: i32) -> Result<(), AppError>;
+    // The most recently created override covering `on`, if any.
+    fn active_override(
+        &self,
+        customer_mdoc: i32,
+        on: NaiveDate,
+    ) -> Result<Option<LimitOverride>, AppError>;
+}
@@ -1,7 +1,9 @@
 use crate::common::error::AppError;
 use crate::domain::models::customer_tx_detail::CustomerTxDetail;
 use crate::domain::report_models::daily_sales::DailySales;
+use crate::domain::report_models::margin_report::CategoryMargin;
 use crate::domain::report_models::product_sales::{ProductSalesByCategory, SalesTotals};
+use crate::domain::report_models::tax_summary::TaxSummaryRow;
 use chrono::NaiveDateTime;
 
 pub trait CustomerTxDetailRepoTrait: Send + Sync {
@@ -10,6 +12,11 @@ pub trait CustomerTxDetailRepoTrait: Send + Sync {
 
     // list all details for a given order_id
     fn list_by_order(&self, order_id: i32) -> Result<Vec<(CustomerTxDetail, String)>, AppError>;
+    fn list_by_order_with_tx(
+        &self,
+        order_id: i32,
+        tx: &rusqlite::Transaction<'_>,
+    ) -> Result<Vec<(CustomerTxDetail, String)>, AppError>;
     fn create_with_tx(
         &self,
         d: &CustomerTxDetail,
@@ -30,4 +37,16 @@ pub trait CustomerTxDetailRepoTrait: Send + Sync {
         start: NaiveDateTime,
         end: NaiveDateTime,
     ) -> Result<Vec<DailySales>, AppError>;
+    // Revenue and cost of goods sold per category, net of voids and returns.
+    fn margin_by_category(
+        &self,
+        start: NaiveDateTime,
+        end: NaiveDateTime,
+    ) -> Result<Vec<CategoryMargin>, AppError>;
+    // Taxable sales and tax per rate, net of voids and returns.
+    fn tax_summary(
+        &self,
+        start: NaiveDateTime,
+        end: NaiveDateTime,
+    ) -> Result<Vec<TaxSummaryRow>, AppError>;
 }
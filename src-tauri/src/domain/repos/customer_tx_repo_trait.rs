@@ -1,10 +1,12 @@
 use crate::common::error::AppError;
 use crate::domain::models::customer_tx_detail::CustomerTxDetail;
-use crate::domain::models::CustomerTransaction;
+use crate::domain::models::{CustomerTransaction, OrderVoid};
 use crate::domain::report_models::sales_details::SalesReportDetails;
 use chrono::NaiveDateTime;
 
 pub type SaleDetailsTuple = (CustomerTransaction, Vec<(CustomerTxDetail, String)>, i32);
+// (transaction, operator name, spent, voided)
+pub type CustomerTxSearchRow = (CustomerTransaction, String, i32, bool);
 
 pub trait CustomerTransactionRepoTrait: Send + Sync {
     fn create(&self, tx: &CustomerTransaction) -> Result<(), AppError>;
@@ -17,7 +19,7 @@ pub trait CustomerTransactionRepoTrait: Send + Sync {
         mdoc: Option<i32>,
         date: Option<String>,
         search: Option<String>,
-    ) -> Result<Vec<(CustomerTransaction, String, i32)>, AppError>;
+    ) -> Result<Vec<CustomerTxSearchRow>, AppError>;
 
     fn count(
         &self,
@@ -32,15 +34,51 @@ pub trait CustomerTransactionRepoTrait: Send + Sync {
         tx: &rusqlite::Transaction<'_>,
     ) -> Result<i32, AppError>;
 
+    // The order recorded under a sale's idempotency key, if any.
+    fn find_by_idempotency_key_with_tx(
+        &self,
+        key: &str,
+        tx: &rusqlite::Transaction<'_>,
+    ) -> Result<Option<CustomerTransaction>, AppError>;
+
     fn get_with_details_and_balance(&self, _order_id: i32) -> Result<SaleDetailsTuple, AppError>;
 
-    // Get total for mdoc between `week_start` (inclusive) and `week_start + 7 days` (exclusive).
-    fn get_weekly_spent(
+    // Total spent by mdoc between `start` (inclusive) and `end` (exclusive), both UTC.
+    fn get_spent(
+        &self,
+        customer_mdoc: i32,
+        start: NaiveDateTime,
+        end: NaiveDateTime,
+    ) -> Result<i32, AppError>;
+
+    fn get_spent_with_tx(
         &self,
         customer_mdoc: i32,
-        week_start: NaiveDateTime,
+        start: NaiveDateTime,
+        end: NaiveDateTime,
+        tx: &rusqlite::Transaction<'_>,
     ) -> Result<i32, AppError>;
 
+    // Deposits minus spending for a customer, read inside an atomic_tx.
+    fn get_balance_with_tx(
+        &self,
+        customer_mdoc: i32,
+        tx: &rusqlite::Transaction<'_>,
+    ) -> Result<i32, AppError>;
+
+    fn create_void_with_tx(
+        &self,
+        void: &OrderVoid,
+        tx: &rusqlite::Transaction<'_>,
+    ) -> Result<(), AppError>;
+
+    // Void record where `order_id` is either the voided order or its reversal.
+    fn find_void_with_tx(
+        &self,
+        order_id: i32,
+        tx: &rusqlite::Transaction<'_>,
+    ) -> Result<Option<OrderVoid>, AppError>;
+
     fn get_sales_details_data(
         &self,
         start: NaiveDateTime,
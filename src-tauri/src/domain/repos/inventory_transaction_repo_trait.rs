@@ -1,5 +1,10 @@
 use crate::common::error::AppError;
-use crate::domain::models::InventoryTransaction;
+use crate::domain::models::{
+    InventoryLot, InventoryReason, InventoryTransaction, LotLabel, NegativeStockPolicy,
+};
+use crate::domain::report_models::expiry_report::ExpiringLot;
+use crate::domain::report_models::shrinkage_report::ShrinkageLine;
+use chrono::{NaiveDate, NaiveDateTime};
 
 pub trait InventoryTransactionRepoTrait: Send + Sync {
     fn get_by_id(&self, id: i32) -> Result<Option<InventoryTransaction>, AppError>;
@@ -7,6 +12,7 @@ pub trait InventoryTransactionRepoTrait: Send + Sync {
     fn list_for_product(&self, upc: String) -> Result<Vec<InventoryTransaction>, AppError>;
     fn list_for_operator(&self, operator_mdoc: i32) -> Result<Vec<InventoryTransaction>, AppError>;
     fn list_for_customer(&self, customer_mdoc: i32) -> Result<Vec<InventoryTransaction>, AppError>;
+    fn list_for_order(&self, order_id: i32) -> Result<Vec<InventoryTransaction>, AppError>;
     fn list_for_today(&self) -> Result<Vec<InventoryTransaction>, AppError>;
     fn list(&self) -> Result<Vec<InventoryTransaction>, AppError>;
     fn search(
@@ -15,11 +21,57 @@ pub trait InventoryTransactionRepoTrait: Send + Sync {
         offset: i32,
         date: Option<String>,
         search: Option<String>,
+        reason: Option<InventoryReason>,
     ) -> Result<Vec<(InventoryTransaction, String, String)>, AppError>;
-    fn count(&self, date: Option<String>, search: Option<String>) -> Result<i32, AppError>;
+    fn count(
+        &self,
+        date: Option<String>,
+        search: Option<String>,
+        reason: Option<InventoryReason>,
+    ) -> Result<i32, AppError>;
     fn create_with_tx(
         &self,
         a: &InventoryTransaction,
         tx: &rusqlite::Transaction<'_>,
     ) -> Result<(), AppError>;
+    // on-hand quantity for a upc (sum of all quantity changes)
+    fn get_available_with_tx(
+        &self,
+        upc: String,
+        tx: &rusqlite::Transaction<'_>,
+    ) -> Result<i32, AppError>;
+    // What to do when stock would go below zero on hand.
+    fn negative_stock_policy(&self) -> Result<NegativeStockPolicy, AppError>;
+    fn set_negative_stock_policy(&self, policy: NegativeStockPolicy) -> Result<(), AppError>;
+    fn negative_stock_policy_with_tx(
+        &self,
+        tx: &rusqlite::Transaction<'_>,
+    ) -> Result<NegativeStockPolicy, AppError>;
+    // stock taken out other than by sales, by reason and category, between
+    // start and the end of the end day
+    fn shrinkage(
+        &self,
+        start: NaiveDateTime,
+        end: NaiveDateTime,
+    ) -> Result<Vec<ShrinkageLine>, AppError>;
+    // Id of the product's lot with this label, created on its first receipt.
+    fn lot_id_with_tx(
+        &self,
+        upc: &str,
+        label: &LotLabel,
+        tx: &rusqlite::Transaction<'_>,
+    ) -> Result<i32, AppError>;
+    // The product's lots with stock left, soonest expiry first and undated lots last.
+    fn open_lots_with_tx(
+        &self,
+        upc: &str,
+        tx: &rusqlite::Transaction<'_>,
+    ) -> Result<Vec<InventoryLot>, AppError>;
+    fn get_lot_with_tx(
+        &self,
+        id: i32,
+        tx: &rusqlite::Transaction<'_>,
+    ) -> Result<Option<InventoryLot>, AppError>;
+    // Lots with stock left expiring on or before `through`, already expired included.
+    fn expiring_lots(&self, through: NaiveDate) -> Result<Vec<ExpiringLot>, AppError>;
 }
@@ -2,24 +2,42 @@ pub mod category_repo_trait;
 pub mod club_import_repo_trait;
 pub mod club_transaction_repo_trait;
 pub mod credential_repo_trait;
+pub mod customer_limit_repo_trait;
 pub mod customer_repo_trait;
 pub mod customer_tx_detail_repo_trait;
 pub mod customer_tx_repo_trait;
 pub mod inventory_transaction_repo_trait;
 pub mod operator_repo_trait;
+pub mod order_return_repo_trait;
 pub mod price_adjustment_repo_trait;
+pub mod print_job_repo_trait;
 pub mod product_repo_trait;
+pub mod promotion_repo_trait;
+pub mod purchase_order_repo_trait;
+pub mod quantity_limit_repo_trait;
+pub mod stock_take_repo_trait;
+pub mod tax_rate_repo_trait;
+pub mod vendor_repo_trait;
 pub mod weekly_limit_repo_trait;
 
 pub use category_repo_trait::CategoryRepoTrait;
 pub use club_import_repo_trait::ClubImportRepoTrait;
 pub use club_transaction_repo_trait::ClubTransactionRepoTrait;
 pub use credential_repo_trait::CredentialRepoTrait;
+pub use customer_limit_repo_trait::CustomerLimitRepoTrait;
 pub use customer_repo_trait::CustomerRepoTrait;
 pub use customer_tx_detail_repo_trait::CustomerTxDetailRepoTrait;
 pub use customer_tx_repo_trait::CustomerTransactionRepoTrait;
 pub use inventory_transaction_repo_trait::InventoryTransactionRepoTrait;
 pub use operator_repo_trait::OperatorRepoTrait;
+pub use order_return_repo_trait::OrderReturnRepoTrait;
 pub use price_adjustment_repo_trait::PriceAdjustmentRepoTrait;
+pub use print_job_repo_trait::PrintJobRepoTrait;
 pub use product_repo_trait::ProductRepoTrait;
+pub use promotion_repo_trait::PromotionRepoTrait;
+pub use purchase_order_repo_trait::PurchaseOrderRepoTrait;
+pub use quantity_limit_repo_trait::QuantityLimitRepoTrait;
+pub use stock_take_repo_trait::StockTakeRepoTrait;
+pub use tax_rate_repo_trait::TaxRateRepoTrait;
+pub use vendor_repo_trait::VendorRepoTrait;
 pub use weekly_limit_repo_trait::WeeklyLimitRepoTrait;
@@ -0,0 +1,34 @@
+use crate::common::error::AppError;
+use crate::domain::models::{OrderReturn, OrderReturnLine};
+use std::collections::HashMap;
+
+pub trait OrderReturnRepoTrait: Send + Sync {
+    fn create_with_tx(
+        &self,
+        ret: &OrderReturn,
+        tx: &rusqlite::Transaction<'_>,
+    ) -> Result<(), AppError>;
+    fn create_line_with_tx(
+        &self,
+        line: &OrderReturnLine,
+        tx: &rusqlite::Transaction<'_>,
+    ) -> Result<(), AppError>;
+    // Quantity already returned per original detail_id of `order_id`.
+    fn returned_by_detail_with_tx(
+        &self,
+        order_id: i32,
+        tx: &rusqlite::Transaction<'_>,
+    ) -> Result<HashMap<i32, i32>, AppError>;
+    fn get_by_return_order(&self, return_order_id: i32) -> Result<Option<OrderReturn>, AppError>;
+    fn get_by_return_order_with_tx(
+        &self,
+        return_order_id: i32,
+        tx: &rusqlite::Transaction<'_>,
+    ) -> Result<Option<OrderReturn>, AppError>;
+    fn list_for_order_with_tx(
+        &self,
+        order_id: i32,
+        tx: &rusqlite::Transaction<'_>,
+    ) -> Result<Vec<OrderReturn>, AppError>;
+    fn list_lines(&self, return_order_id: i32) -> Result<Vec<OrderReturnLine>, AppError>;
+}
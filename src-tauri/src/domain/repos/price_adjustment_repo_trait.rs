@@ -1,5 +1,6 @@
 use crate::common::error::AppError;
 use crate::domain::models::PriceAdjustment;
+use chrono::NaiveDateTime;
 
 pub trait PriceAdjustmentRepoTrait: Send + Sync {
     fn create(&self, adj: &PriceAdjustment) -> Result<(), AppError>;
@@ -21,4 +22,27 @@ pub trait PriceAdjustmentRepoTrait: Send + Sync {
         search: Option<String>,
     ) -> Result<Vec<(PriceAdjustment, String, String)>, AppError>;
     fn count(&self, date: Option<String>, search: Option<String>) -> Result<i32, AppError>;
+    // Scheduled changes still waiting for their effective time, soonest first.
+    fn list_pending(&self) -> Result<Vec<PriceAdjustment>, AppError>;
+    // Pending changes due by `now`, in the order they take effect.
+    fn due_with_tx(
+        &self,
+        now: NaiveDateTime,
+        tx: &rusqlite::Transaction<'_>,
+    ) -> Result<Vec<PriceAdjustment>, AppError>;
+    // Marks a pending change applied, recording the price it replaced.
+    fn mark_applied_with_tx(
+        &self,
+        id: i32,
+        old: i32,
+        tx: &rusqlite::Transaction<'_>,
+    ) -> Result<(), AppError>;
+    // Only a pending change can be cancelled.
+    fn cancel(&self, id: i32) -> Result<(), AppError>;
+    // Cancels every pending change for `upc`, returning their ids.
+    fn cancel_pending_for_upc_with_tx(
+        &self,
+        upc: &str,
+        tx: &rusqlite::Transaction<'_>,
+    ) -> Result<Vec<i32>, AppError>;
 }
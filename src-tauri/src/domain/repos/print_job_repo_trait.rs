@@ -0,0 +1,24 @@
+use crate::common::error::AppError;
+use crate::domain::models::{PrintJob, PrintJobStatus};
+
+pub trait PrintJobRepoTrait: Send + Sync {
+    fn create(&self, job: &PrintJob) -> Result<i32, AppError>;
+    fn get(&self, id: i32) -> Result<Option<PrintJob>, AppError>;
+    // Newest first, optionally filtered by status.
+    fn list(&self, status: Option<PrintJobStatus>, limit: i32) -> Result<Vec<PrintJob>, AppError>;
+    // Marks the oldest pending job as printing and returns it.
+    fn claim_next(&self) -> Result<Option<PrintJob>, AppError>;
+    fn update_status(
+        &self,
+        id: i32,
+        status: PrintJobStatus,
+        attempts: i32,
+        last_error: Option<String>,
+    ) -> Result<(), AppError>;
+    // The balance captured with the order's receipt, for reprints.
+    fn sale_balance(&self, order_id: i32) -> Result<Option<i32>, AppError>;
+    // Whether the order's own receipt, as opposed to a reprint, was ever queued.
+    fn has_receipt(&self, order_id: i32) -> Result<bool, AppError>;
+    // Puts jobs left in `printing` by a crash back to `pending`.
+    fn requeue_interrupted(&self) -> Result<usize, AppError>;
+}
@@ -1,18 +1,53 @@
 use crate::common::error::AppError;
-use crate::domain::models::Product;
+use crate::domain::models::{BarcodeAlias, BundleComponent, Product};
 use crate::domain::report_models::product_inventory::ProductInventoryReport;
 use crate::domain::report_models::product_inventory::ProductInventoryTotals;
+use crate::domain::report_models::reorder_report::LowStockItem;
+use chrono::NaiveDateTime;
 
 pub trait ProductRepoTrait: Send + Sync {
+    // By the product's own upc only; scanned codes go through resolve_barcode.
     fn get_by_upc(&self, upc: String) -> Result<Option<Product>, AppError>;
+    fn get_by_upc_with_tx(
+        &self,
+        upc: String,
+        tx: &rusqlite::Transaction<'_>,
+    ) -> Result<Option<Product>, AppError>;
+    // Whether sales of this upc stay out of the spending limit (product flag, else category).
+    fn is_limit_exempt(&self, upc: &str) -> Result<bool, AppError>;
+    fn is_limit_exempt_with_tx(
+        &self,
+        upc: &str,
+        tx: &rusqlite::Transaction<'_>,
+    ) -> Result<bool, AppError>;
     fn get_price(&self, upc: String) -> Result<i32, AppError>;
     fn create(&self, product: &Product) -> Result<(), AppError>;
+    fn create_with_tx(
+        &self,
+        product: &Product,
+        tx: &rusqlite::Transaction<'_>,
+    ) -> Result<(), AppError>;
     fn update_by_upc(&self, product: &Product) -> Result<(), AppError>;
     fn update_by_upc_with_tx(
         &self,
         product: &Product,
         tx: &rusqlite::Transaction<'_>,
     ) -> Result<(), AppError>;
+    // Sets just the price and its `updated` time, leaving the rest of the row alone.
+    fn set_price_with_tx(
+        &self,
+        upc: &str,
+        price: i32,
+        updated: NaiveDateTime,
+        tx: &rusqlite::Transaction<'_>,
+    ) -> Result<(), AppError>;
+    // Unit cost is only changed by costed receipts, never by product edits.
+    fn set_unit_cost_with_tx(
+        &self,
+        upc: &str,
+        unit_cost: i32,
+        tx: &rusqlite::Transaction<'_>,
+    ) -> Result<(), AppError>;
     fn list(&self) -> Result<Vec<Product>, AppError>;
     fn search(
         &self,
@@ -24,4 +59,39 @@ pub trait ProductRepoTrait: Send + Sync {
     fn count(&self, desc_like: Option<String>, category: Option<String>) -> Result<i32, AppError>;
     fn report_by_category(&self) -> Result<Vec<ProductInventoryReport>, AppError>;
     fn get_inventory_totals(&self) -> Result<ProductInventoryTotals, AppError>;
+    // Active products at or below their reorder point, by category, vendor and name.
+    fn low_stock(&self) -> Result<Vec<LowStockItem>, AppError>;
+    fn count_low_stock(&self) -> Result<i32, AppError>;
+    // The product a upc or alias scans as, with how many units one scan is.
+    fn resolve_barcode(&self, code: &str) -> Result<Option<(Product, i32)>, AppError>;
+    fn resolve_barcode_with_tx(
+        &self,
+        code: &str,
+        tx: &rusqlite::Transaction<'_>,
+    ) -> Result<Option<(Product, i32)>, AppError>;
+    fn get_barcode(&self, barcode: &str) -> Result<Option<BarcodeAlias>, AppError>;
+    // None lists every product's aliases.
+    fn list_barcodes(&self, upc: Option<String>) -> Result<Vec<BarcodeAlias>, AppError>;
+    fn add_barcode(&self, alias: &BarcodeAlias) -> Result<(), AppError>;
+    fn remove_barcode(&self, barcode: &str) -> Result<(), AppError>;
+    // A kit's components with their product names; empty for a plain product.
+    fn components(&self, bundle_upc: &str) -> Result<Vec<(BundleComponent, String)>, AppError>;
+    fn components_with_tx(
+        &self,
+        bundle_upc: &str,
+        tx: &rusqlite::Transaction<'_>,
+    ) -> Result<Vec<BundleComponent>, AppError>;
+    // The kits that hold this product.
+    fn bundles_containing_with_tx(
+        &self,
+        component_upc: &str,
+        tx: &rusqlite::Transaction<'_>,
+    ) -> Result<Vec<String>, AppError>;
+    // Replaces the kit's components; none turns it back into a plain product.
+    fn set_components_with_tx(
+        &self,
+        bundle_upc: &str,
+        components: &[BundleComponent],
+        tx: &rusqlite::Transaction<'_>,
+    ) -> Result<(), AppError>;
 }
@@ -0,0 +1,21 @@
+use crate::common::error::AppError;
+use crate::domain::models::Promotion;
+use chrono::NaiveDateTime;
+
+pub trait PromotionRepoTrait: Send + Sync {
+    // Promotions not yet deleted, running or not.
+    fn list(&self) -> Result<Vec<Promotion>, AppError>;
+    fn get(&self, id: i32) -> Result<Option<Promotion>, AppError>;
+    fn create(&self, promotion: &Promotion) -> Result<i32, AppError>;
+    // Ends a promotion; sales it discounted keep pointing at it.
+    fn delete(&self, id: i32) -> Result<(), AppError>;
+
+    // Promotions running at `now`.
+    fn running(&self, now: NaiveDateTime) -> Result<Vec<Promotion>, AppError>;
+    // for use with atomic_tx
+    fn running_with_tx(
+        &self,
+        now: NaiveDateTime,
+        tx: &rusqlite::Transaction<'_>,
+    ) -> Result<Vec<Promotion>, AppError>;
+}
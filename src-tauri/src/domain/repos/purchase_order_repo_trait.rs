@@ -0,0 +1,51 @@
+use crate::common::error::AppError;
+use crate::domain::models::{PurchaseOrder, PurchaseOrderLine, PurchaseOrderStatus};
+use chrono::NaiveDateTime;
+
+// (order, vendor name)
+pub type PurchaseOrderRow = (PurchaseOrder, String);
+// (line, product description)
+pub type PurchaseOrderLineRow = (PurchaseOrderLine, String);
+
+pub trait PurchaseOrderRepoTrait: Send + Sync {
+    // Newest first; all statuses when `status` is None.
+    fn list(&self, status: Option<PurchaseOrderStatus>) -> Result<Vec<PurchaseOrderRow>, AppError>;
+    fn get(&self, id: i32) -> Result<Option<PurchaseOrder>, AppError>;
+    fn lines(&self, po_id: i32) -> Result<Vec<PurchaseOrderLineRow>, AppError>;
+
+    fn get_with_tx(
+        &self,
+        id: i32,
+        tx: &rusqlite::Transaction<'_>,
+    ) -> Result<Option<PurchaseOrder>, AppError>;
+    fn create_with_tx(
+        &self,
+        po: &PurchaseOrder,
+        tx: &rusqlite::Transaction<'_>,
+    ) -> Result<i32, AppError>;
+    fn add_line_with_tx(
+        &self,
+        line: &PurchaseOrderLine,
+        tx: &rusqlite::Transaction<'_>,
+    ) -> Result<i32, AppError>;
+    fn lines_with_tx(
+        &self,
+        po_id: i32,
+        tx: &rusqlite::Transaction<'_>,
+    ) -> Result<Vec<PurchaseOrderLine>, AppError>;
+    // Adds `quantity` to what has been received on a line.
+    fn add_received_with_tx(
+        &self,
+        line_id: i32,
+        quantity: i32,
+        tx: &rusqlite::Transaction<'_>,
+    ) -> Result<(), AppError>;
+    // Moves an open PO to `status`; fails if it's no longer open.
+    fn close_with_tx(
+        &self,
+        id: i32,
+        status: PurchaseOrderStatus,
+        received_at: Option<NaiveDateTime>,
+        tx: &rusqlite::Transaction<'_>,
+    ) -> Result<(), AppError>;
+}
@@ -0,0 +1,27 @@
+use crate::common::error::AppError;
+use crate::domain::models::QuantityLimit;
+use chrono::NaiveDateTime;
+
+pub trait QuantityLimitRepoTrait: Send + Sync {
+    fn list(&self) -> Result<Vec<QuantityLimit>, AppError>;
+    fn create(&self, limit: &QuantityLimit) -> Result<i32, AppError>;
+    fn delete(&self, id: i32) -> Result<(), AppError>;
+
+    // Net units of the limit's upc or category a customer bought in [start, end).
+    fn purchased(
+        &self,
+        customer_mdoc: i32,
+        limit: &QuantityLimit,
+        start: NaiveDateTime,
+        end: NaiveDateTime,
+    ) -> Result<i32, AppError>;
+    // for use with atomic_tx
+    fn purchased_with_tx(
+        &self,
+        customer_mdoc: i32,
+        limit: &QuantityLimit,
+        start: NaiveDateTime,
+        end: NaiveDateTime,
+        tx: &rusqlite::Transaction<'_>,
+    ) -> Result<i32, AppError>;
+}
@@ -0,0 +1,48 @@
+use crate::common::error::AppError;
+use crate::domain::models::{StockTake, StockTakeLine, StockTakeStatus};
+use chrono::NaiveDateTime;
+
+// (line, product description, product price in cents)
+pub type StockTakeLineRow = (StockTakeLine, String, i32);
+
+pub trait StockTakeRepoTrait: Send + Sync {
+    // Newest first; all statuses when `status` is None.
+    fn list(&self, status: Option<StockTakeStatus>) -> Result<Vec<StockTake>, AppError>;
+    fn get(&self, id: i32) -> Result<Option<StockTake>, AppError>;
+    fn lines(&self, stock_take_id: i32) -> Result<Vec<StockTakeLineRow>, AppError>;
+
+    fn create_with_tx(
+        &self,
+        stock_take: &StockTake,
+        tx: &rusqlite::Transaction<'_>,
+    ) -> Result<i32, AppError>;
+    fn add_line_with_tx(
+        &self,
+        line: &StockTakeLine,
+        tx: &rusqlite::Transaction<'_>,
+    ) -> Result<i32, AppError>;
+    fn lines_with_tx(
+        &self,
+        stock_take_id: i32,
+        tx: &rusqlite::Transaction<'_>,
+    ) -> Result<Vec<StockTakeLine>, AppError>;
+    // Replaces any earlier count of the line; NotFound if the upc isn't on the
+    // sheet, Validation once the stock take is no longer open.
+    fn record_count_with_tx(
+        &self,
+        stock_take_id: i32,
+        upc: &str,
+        counted_quantity: i32,
+        counted_at: NaiveDateTime,
+        tx: &rusqlite::Transaction<'_>,
+    ) -> Result<(), AppError>;
+    // Moves an open stock take to `status`; Validation if it was already closed,
+    // so two posts racing each other can't both apply the variances.
+    fn close_with_tx(
+        &self,
+        id: i32,
+        status: StockTakeStatus,
+        posted_at: Option<NaiveDateTime>,
+        tx: &rusqlite::Transaction<'_>,
+    ) -> Result<(), AppError>;
+}
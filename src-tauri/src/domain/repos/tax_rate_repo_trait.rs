@@ -0,0 +1,25 @@
+use crate::common::error::AppError;
+use crate::domain::models::{TaxRate, TaxSettings};
+use chrono::NaiveDateTime;
+
+pub trait TaxRateRepoTrait: Send + Sync {
+    // Rates not yet deleted, including ones already replaced or not yet in effect.
+    fn list(&self) -> Result<Vec<TaxRate>, AppError>;
+    fn create(&self, rate: &TaxRate) -> Result<i32, AppError>;
+    fn delete(&self, id: i32) -> Result<(), AppError>;
+
+    // Rates in effect at `now`, oldest first; later ones replace earlier ones
+    // for the same category or product.
+    fn in_effect(&self, now: NaiveDateTime) -> Result<Vec<TaxRate>, AppError>;
+    // for use with atomic_tx
+    fn in_effect_with_tx(
+        &self,
+        now: NaiveDateTime,
+        tx: &rusqlite::Transaction<'_>,
+    ) -> Result<Vec<TaxRate>, AppError>;
+
+    fn get_settings(&self) -> Result<TaxSettings, AppError>;
+    fn set_settings(&self, settings: &TaxSettings) -> Result<(), AppError>;
+    fn get_settings_with_tx(&self, tx: &rusqlite::Transaction<'_>)
+        -> Result<TaxSettings, AppError>;
+}
@@ -0,0 +1,12 @@
+use crate::common::error::AppError;
+use crate::domain::models::Vendor;
+
+pub trait VendorRepoTrait: Send + Sync {
+    // Vendors that haven't been deleted, by name.
+    fn list(&self) -> Result<Vec<Vendor>, AppError>;
+    fn get(&self, id: i32) -> Result<Option<Vendor>, AppError>;
+    fn create(&self, vendor: &Vendor) -> Result<i32, AppError>;
+    fn update(&self, vendor: &Vendor) -> Result<(), AppError>;
+    // Soft delete; the vendor's purchase orders are kept.
+    fn delete(&self, id: i32) -> Result<(), AppError>;
+}
@@ -1,6 +1,10 @@
 use crate::common::error::AppError;
+use crate::domain::models::LimitSettings;
 
 pub trait WeeklyLimitRepoTrait: Send + Sync {
     fn get_limit(&self) -> Result<i32, AppError>;
     fn set_limit(&self, limit: i32) -> Result<(), AppError>;
+    // Limit period, week start and facility time zone.
+    fn get_settings(&self) -> Result<LimitSettings, AppError>;
+    fn set_settings(&self, settings: &LimitSettings) -> Result<(), AppError>;
 }
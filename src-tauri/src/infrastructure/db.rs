@@ -33,6 +33,110 @@ static MIGRATIONS: LazyLock<Migrations> = LazyLock::new(|| {
             env!("CARGO_MANIFEST_DIR"),
             "/migrations/0007_add_weekly_limit.sql"
         ))),
+        M::up(include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/migrations/0008_add_order_voids.sql"
+        ))),
+        M::up(include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/migrations/0009_add_order_returns.sql"
+        ))),
+        M::up(include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/migrations/0010_add_print_jobs.sql"
+        ))),
+        M::up(include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/migrations/0011_add_customer_limits.sql"
+        ))),
+        M::up(include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/migrations/0012_add_limit_settings.sql"
+        ))),
+        M::up(include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/migrations/0013_add_quantity_limits.sql"
+        ))),
+        M::up(include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/migrations/0014_add_limit_exempt.sql"
+        ))),
+        M::up(include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/migrations/0015_add_sale_idempotency_key.sql"
+        ))),
+        M::up(include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/migrations/0016_add_order_reason.sql"
+        ))),
+        M::up(include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/migrations/0017_add_purchase_orders.sql"
+        ))),
+        M::up(include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/migrations/0018_add_unit_cost.sql"
+        ))),
+        M::up(include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/migrations/0019_add_stock_takes.sql"
+        ))),
+        M::up(include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/migrations/0020_add_reorder_points.sql"
+        ))),
+        M::up(include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/migrations/0021_add_inventory_reasons.sql"
+        ))),
+        M::up(include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/migrations/0022_add_inventory_settings.sql"
+        ))),
+        M::up(include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/migrations/0023_add_inventory_lots.sql"
+        ))),
+        M::up(include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/migrations/0024_add_product_barcodes.sql"
+        ))),
+        M::up(include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/migrations/0025_add_product_components.sql"
+        ))),
+        M::up(include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/migrations/0026_add_price_adjustment_schedule.sql"
+        ))),
+        M::up(include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/migrations/0027_add_promotions.sql"
+        ))),
+        M::up(include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/migrations/0028_add_sales_tax.sql"
+        ))),
+        M::up(include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/migrations/0029_add_print_job_balance.sql"
+        ))),
+        M::up(include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/migrations/0030_flip_sale_inventory_sign.sql"
+        ))),
+        M::up(include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/migrations/0031_add_detail_limit_exempt.sql"
+        ))),
+        M::up(include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/migrations/0032_add_detail_promotion_name.sql"
+        ))),
+        M::up(include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/migrations/0033_rename_limit_amount.sql"
+        ))),
     ])
 });
 
@@ -74,4 +178,93 @@ mod tests {
 
         Ok(())
     }
+
+    // Sale rows from before sales were written negative are flipped, along
+    // with the void rows that reversed them, so stock adds up the same.
+    #[test]
+    fn old_sale_rows_take_stock_off() -> anyhow::Result<()> {
+        let mut conn = Connection::open_in_memory()?;
+        MIGRATIONS.to_version(&mut conn, 29)?;
+        conn.execute_batch(
+            "INSERT INTO operators (mdoc, name, start) VALUES (1, 'Op', '2025-01-01');
+             INSERT INTO products (upc, desc, category, price, updated, added) VALUES
+               ('000000000100', 'Soup', 'Food', 125, '2025-01-01 00:00:00', '2025-01-01 00:00:00');
+             INSERT INTO inventory_transactions (upc, quantity_change, operator_mdoc, reason) VALUES
+               ('000000000100', 10, 1, 'receipt'),
+               ('000000000100', 3, 1, 'sale'),
+               ('000000000100', 2, 1, 'sale'),
+               ('000000000100', -2, 1, 'void'),
+               ('000000000100', -1, 1, 'damage');",
+        )?;
+        MIGRATIONS.to_latest(&mut conn)?;
+
+        let changes = conn
+            .prepare("SELECT quantity_change FROM inventory_transactions ORDER BY id")?
+            .query_map([], |r| r.get(0))?
+            .collect::<Result<Vec<i32>, _>>()?;
+        assert_eq!(changes, [10, -3, -2, 2, -1]);
+        Ok(())
+    }
+
+    // Lines sold before exemption was kept per line take the flags as they
+    // stood when the migration ran: the product's own, else its category's.
+    #[test]
+    fn old_sale_lines_take_their_exemption_from_the_catalog() -> anyhow::Result<()> {
+        let mut conn = Connection::open_in_memory()?;
+        MIGRATIONS.to_version(&mut conn, 30)?;
+        conn.execute_batch(
+            "INSERT INTO categories (name, limit_exempt) VALUES ('Legal', 1), ('Snacks', 0);
+             INSERT INTO products (upc, desc, category, price, updated, added, limit_exempt) VALUES
+               ('PAD', 'Legal pad', 'Legal', 300, '2025-01-01 00:00:00', '2025-01-01 00:00:00', NULL),
+               ('PEN', 'Pen', 'Legal', 50, '2025-01-01 00:00:00', '2025-01-01 00:00:00', 0),
+               ('STAMP', 'Stamp', 'Snacks', 60, '2025-01-01 00:00:00', '2025-01-01 00:00:00', 1),
+               ('CHIPS', 'Chips', 'Snacks', 100, '2025-01-01 00:00:00', '2025-01-01 00:00:00', NULL);
+             INSERT INTO operators (mdoc, name, start) VALUES (1, 'Op', '2025-01-01');
+             INSERT INTO customer_transactions (order_id, customer_mdoc, operator_mdoc, date)
+               VALUES (1, 9, 1, '2025-01-06 10:00:00');
+             INSERT INTO customer_tx_detail (order_id, upc, quantity, price) VALUES
+               (1, 'PAD', 1, 300), (1, 'PEN', 1, 50), (1, 'STAMP', 1, 60), (1, 'CHIPS', 1, 100);",
+        )?;
+        MIGRATIONS.to_latest(&mut conn)?;
+
+        let exempt = conn
+            .prepare("SELECT limit_exempt FROM customer_tx_detail ORDER BY detail_id")?
+            .query_map([], |r| r.get(0))?
+            .collect::<Result<Vec<bool>, _>>()?;
+        assert_eq!(exempt, [true, false, true, false]);
+        Ok(())
+    }
+
+    // Tier and override limits keep their amounts, and their checks, under
+    // the new column name.
+    #[test]
+    fn tier_and_override_limits_move_to_limit_amount() -> anyhow::Result<()> {
+        let mut conn = Connection::open_in_memory()?;
+        MIGRATIONS.to_version(&mut conn, 32)?;
+        conn.execute_batch(
+            "INSERT INTO customer (mdoc, name, added, updated)
+               VALUES (9, 'Pat', '2025-01-01 00:00:00', '2025-01-01 00:00:00');
+             INSERT INTO customer_tiers (name, weekly_limit) VALUES ('Honor', 30000);
+             INSERT INTO customer_limit_overrides
+               (customer_mdoc, weekly_limit, starts_on, reason, operator_mdoc)
+               VALUES (9, 5000, '2025-01-06', 'approved exception', 1);",
+        )?;
+        MIGRATIONS.to_latest(&mut conn)?;
+
+        let tier: i32 =
+            conn.query_row("SELECT limit_amount FROM customer_tiers", [], |r| r.get(0))?;
+        let limit_override: i32 = conn.query_row(
+            "SELECT limit_amount FROM customer_limit_overrides",
+            [],
+            |r| r.get(0),
+        )?;
+        assert_eq!((tier, limit_override), (30000, 5000));
+        assert!(conn
+            .execute(
+                "INSERT INTO customer_tiers (name, limit_amount) VALUES ('Bad', -1)",
+                [],
+            )
+            .is_err());
+        Ok(())
+    }
 }
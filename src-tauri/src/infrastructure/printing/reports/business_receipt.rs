@@ -1,8 +1,9 @@
 use crate::common::error::AppError;
-use crate::domain::models::CustomerTransaction;
+use crate::domain::models::OrderReason;
 use crate::infrastructure::printing::print::print_pdf_silently;
 use crate::infrastructure::printing::reports::common::receipt_header::receipt_header;
-use crate::interface::dto::printer_dto::PrintableLineItem;
+use crate::infrastructure::printing::reports::common::util::wrap_text;
+use crate::interface::dto::printer_dto::PrintableSaleDto;
 use chrono::Local;
 use printpdf::{Mm, PdfDocument};
 use std::fs::File;
@@ -10,15 +11,44 @@ use std::io::BufWriter;
 
 // Prints the business copy PDF and sends to printer.
 pub fn print_business_receipt(
-    tx: &CustomerTransaction,
-    details: &[PrintableLineItem],
+    sale: &PrintableSaleDto,
     operator_name: &str,
     customer_name: &str,
-    balance: i32,
     printer_name: &str,
     sumatra_location: &str,
 ) -> Result<(), AppError> {
-    let lines = 3 + details.len() + 2;
+    let details = &sale.items;
+    let stamp_lines = if sale.reprint.is_some() { 2 } else { 0 };
+    let exempt_total: i32 = details
+        .iter()
+        .filter(|d| d.limit_exempt)
+        .map(|d| d.quantity * d.price - d.discount + d.tax)
+        .sum();
+    let exempt_lines = if details.iter().any(|d| d.limit_exempt) {
+        1
+    } else {
+        0
+    };
+    // reason code (when not a regular sale) and the operator's note, above the items
+    let mut order_info = Vec::new();
+    if sale.transaction.reason != OrderReason::Regular {
+        order_info.push(format!("Reason: {}", sale.transaction.reason.label()));
+    }
+    if let Some(note) = &sale.transaction.note {
+        order_info.extend(wrap_text(&format!("Note: {note}"), 45));
+    }
+    let component_lines: usize = details.iter().map(|d| d.components.len()).sum();
+    let discount_lines = details.iter().filter(|d| d.discount != 0).count();
+    let tax_lines = usize::from(details.iter().any(|d| d.tax != 0));
+    let lines = 3
+        + details.len()
+        + component_lines
+        + discount_lines
+        + tax_lines
+        + 2
+        + stamp_lines
+        + exempt_lines
+        + order_info.len();
     let height = Mm((lines as f32 * 7.0) + 20.0).max(Mm(100.0));
     let (doc, page, layer) = PdfDocument::new("Business Receipt", Mm(80.0), height, "L1");
     let current = doc.get_page(page).get_layer(layer);
@@ -32,12 +62,20 @@ pub fn print_business_receipt(
         &current,
         &font,
         &bold_font,
-        tx,
+        sale,
         operator_name,
         customer_name,
         height,
     );
 
+    for line in &order_info {
+        current.use_text(line, 8.0, Mm(5.0), y, &font);
+        y -= Mm(4.0);
+    }
+    if !order_info.is_empty() {
+        y -= Mm(4.0);
+    }
+
     // column headers
     let header_font_size = 8.0;
 
@@ -47,11 +85,14 @@ pub fn print_business_receipt(
     y -= Mm(4.0);
 
     for d in details {
-        let desc = if d.desc.len() > 30 {
+        let mut desc = if d.desc.len() > 30 {
             format!("{}…", &d.desc[..29])
         } else {
             d.desc.clone()
         };
+        if d.limit_exempt {
+            desc.push_str(" *");
+        }
         current.use_text(&desc, 8.0, Mm(5.0), y, &font);
         current.use_text(d.quantity.to_string(), 8.0, Mm(50.0), y, &font);
         current.use_text(
@@ -62,17 +103,77 @@ pub fn print_business_receipt(
             &font,
         );
         y -= Mm(4.0);
+        // the promotion's name and what it took off, under the line
+        if d.discount != 0 {
+            current.use_text(
+                format!(
+                    "{} -{:.2}",
+                    d.promotion.as_deref().unwrap_or("Discount"),
+                    f64::from(d.discount) / 100.0
+                ),
+                7.0,
+                Mm(8.0),
+                y,
+                &font,
+            );
+            y -= Mm(3.5);
+        }
+        // a kit's contents, indented under it
+        for c in &d.components {
+            current.use_text(
+                format!("{} x {}", c.quantity, c.desc),
+                7.0,
+                Mm(8.0),
+                y,
+                &font,
+            );
+            y -= Mm(3.5);
+        }
     }
 
-    // total
+    // tax, then the total charged
+    let tax: i32 = details.iter().map(|d| d.tax).sum();
+    if tax != 0 {
+        y -= Mm(6.0);
+        current.use_text(
+            format!("Tax: {:.2}", f64::from(tax) / 100.0),
+            9.0,
+            Mm(5.0),
+            y,
+            &font,
+        );
+    }
     y -= Mm(8.0);
-    let total = f64::from(details.iter().map(|d| d.quantity * d.price).sum::<i32>()) / 100.0;
+    let total = f64::from(
+        details
+            .iter()
+            .map(|d| d.quantity * d.price - d.discount + d.tax)
+            .sum::<i32>(),
+    ) / 100.0;
     current.use_text(format!("Total: {total:.2}"), 10.0, Mm(5.0), y, &bold_font);
 
-    // balance
+    // limit-exempt items (marked *) are shown apart from what counts toward the limit
+    if exempt_lines > 0 {
+        y -= Mm(6.0);
+        current.use_text(
+            format!("* Limit-exempt: {:.2}", f64::from(exempt_total) / 100.0),
+            10.0,
+            Mm(5.0),
+            y,
+            &font,
+        );
+    }
+
+    // balance after this order; reprints of orders queued before balances were
+    // captured can only show what the customer has now
+    let balance_label = if sale.balance_is_current {
+        "Current balance"
+    } else {
+        "Balance"
+    };
     y -= Mm(6.0);
     current.use_text(
-        format!("Balance: {:.2}", f64::from(balance) / 100.0),
+        format!("{balance_label}: {:.2}", f64::from(sale.balance) / 100.0),
         10.0,
         Mm(5.0),
         y,
@@ -1,4 +1,5 @@
 pub mod account_footer;
 pub mod horizontal_line;
 pub mod receipt_header;
+pub mod tax_summary;
 pub mod util;
@@ -1,5 +1,5 @@
-use crate::domain::models::CustomerTransaction;
-use chrono::Local;
+use crate::interface::dto::printer_dto::PrintableSaleDto;
+use chrono::{Local, TimeZone};
 use printpdf::{IndirectFontRef, Mm, PdfLayerReference};
 
 // Draws the top receipt header and returns the new Y offset.
@@ -7,24 +7,41 @@ pub fn receipt_header(
     layer: &PdfLayerReference,
     font: &IndirectFontRef,
     bold_font: &IndirectFontRef,
-    cust_tx: &CustomerTransaction,
+    sale: &PrintableSaleDto,
     operator_name: &str,
     customer_name: &str,
     page_height: Mm,
 ) -> Mm {
     let mut y = Mm(page_height.0 - 10.0);
     let font_size = 9.0;
+    let cust_tx = &sale.transaction;
 
     // Title
-    layer.use_text("Annex Receipt", font_size, Mm(22.0), y, bold_font);
+    layer.use_text(sale.kind.title(), font_size, Mm(22.0), y, bold_font);
     y -= Mm(10.0);
 
-    // Timestamp
-    let now = Local::now();
-    let date_part = now.format("%-m/%-d/%Y").to_string();
-    let time_part = now.format("%-I:%M:%S %p").to_string();
-    layer.use_text(&date_part, font_size, Mm(5.0), y, font);
-    layer.use_text(&time_part, font_size, Mm(50.0), y, font);
+    // Reprint stamp
+    if let Some(reprint) = &sale.reprint {
+        layer.use_text("*** REPRINT ***", font_size, Mm(24.0), y, bold_font);
+        y -= Mm(4.0);
+        let stamp = format!(
+            "{} by {} ({})",
+            reprint.reprinted_at.format("%-m/%-d/%Y %-I:%M:%S %p"),
+            reprint.operator_name,
+            reprint.operator_mdoc
+        );
+        layer.use_text(&stamp, 7.0, Mm(5.0), y, font);
+        y -= Mm(8.0);
+    }
+
+    // Sale time, stored in UTC; a queued or reprinted receipt still shows when the sale happened
+    if let Some(date) = cust_tx.date {
+        let sold = Local.from_utc_datetime(&date);
+        let date_part = sold.format("%-m/%-d/%Y").to_string();
+        let time_part = sold.format("%-I:%M:%S %p").to_string();
+        layer.use_text(&date_part, font_size, Mm(5.0), y, font);
+        layer.use_text(&time_part, font_size, Mm(50.0), y, font);
+    }
     y -= Mm(8.0);
 
     // Order ID
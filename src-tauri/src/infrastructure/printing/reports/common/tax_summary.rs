@@ -0,0 +1,71 @@
+use crate::domain::report_models::tax_summary::TaxSummaryRow;
+use crate::infrastructure::printing::paginator::Paginator;
+use crate::infrastructure::printing::reports::common::util::format_cents;
+use printpdf::{IndirectFontRef, Mm};
+
+// Hundredths of a percent as a percentage, e.g. 825 -> "8.25%".
+pub fn format_rate(rate: i32) -> String {
+    format!("{}.{:02}%", rate / 100, rate % 100)
+}
+
+/// Draws the "Tax Summary" section (taxable sales and tax per rate, then the
+/// total) at the paginator's current position. Draws nothing without taxed sales.
+pub fn tax_summary(
+    pg: &mut Paginator<'_>,
+    rows: &[TaxSummaryRow],
+    font: &IndirectFontRef,
+    bold: &IndirectFontRef,
+) {
+    if rows.is_empty() {
+        return;
+    }
+    let line_height = Mm(6.0);
+    let (rate_x, sales_x, tax_x) = (Mm(20.0), Mm(60.0), Mm(110.0));
+
+    // keep the heading with at least the first row
+    pg.advance(Mm(8.0));
+    let layer = pg.layer_for(line_height * 3.0);
+    layer.use_text("Tax Summary", 11.0, rate_x, pg.current_y(), bold);
+    pg.advance(line_height);
+    layer.use_text("Rate", 9.0, rate_x, pg.current_y(), bold);
+    layer.use_text("Taxable Sales", 9.0, sales_x, pg.current_y(), bold);
+    layer.use_text("Tax", 9.0, tax_x, pg.current_y(), bold);
+    pg.advance(line_height);
+
+    for r in rows {
+        let layer = pg.layer_for(line_height);
+        layer.use_text(format_rate(r.rate), 9.0, rate_x, pg.current_y(), font);
+        layer.use_text(
+            format_cents(r.taxable_sales),
+            9.0,
+            sales_x,
+            pg.current_y(),
+            font,
+        );
+        layer.use_text(format_cents(r.tax), 9.0, tax_x, pg.current_y(), font);
+        pg.advance(line_height);
+    }
+
+    let layer = pg.layer_for(line_height);
+    layer.use_text("Total Tax:", 9.0, sales_x, pg.current_y(), bold);
+    layer.use_text(
+        format_cents(rows.iter().map(|r| r.tax).sum()),
+        9.0,
+        tax_x,
+        pg.current_y(),
+        bold,
+    );
+    pg.advance(line_height);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rates_print_as_percentages() {
+        assert_eq!(format_rate(825), "8.25%");
+        assert_eq!(format_rate(600), "6.00%");
+        assert_eq!(format_rate(5), "0.05%");
+    }
+}
@@ -32,3 +32,28 @@ pub fn truncate_desc(desc: &str, max_len: usize) -> Cow<'_, str> {
         Cow::Owned(s)
     }
 }
+
+// Splits `text` into lines of at most `width` characters, breaking between words
+// where possible.
+pub fn wrap_text(text: &str, width: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut line = String::new();
+    for word in text.split_whitespace() {
+        let word: Vec<char> = word.chars().collect();
+        for chunk in word.chunks(width.max(1)) {
+            let chunk: String = chunk.iter().collect();
+            let len = line.chars().count();
+            if len > 0 && len + 1 + chunk.chars().count() > width {
+                lines.push(std::mem::take(&mut line));
+            }
+            if !line.is_empty() {
+                line.push(' ');
+            }
+            line.push_str(&chunk);
+        }
+    }
+    if !line.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
@@ -1,7 +1,6 @@
 use crate::common::error::AppError;
-use crate::domain::models::CustomerTransaction;
 use crate::infrastructure::printing::reports::customer_receipt::receipt_header::receipt_header;
-use crate::interface::dto::printer_dto::PrintableLineItem;
+use crate::interface::dto::printer_dto::PrintableSaleDto;
 use chrono::Local;
 use printpdf::{Mm, PdfDocument};
 use std::fs::File;
@@ -12,14 +11,17 @@ use crate::infrastructure::printing::print::print_pdf_silently;
 
 // Prints the customer copy PDF and sends to printer.
 pub fn print_customer_receipt(
-    tx: &CustomerTransaction,
-    details: &[PrintableLineItem],
+    sale: &PrintableSaleDto,
     operator_name: &str,
     customer_name: &str,
     printer_name: &str,
     sumatra_location: &str,
 ) -> Result<(), AppError> {
-    let lines = 3 + details.len() + 2;
+    let details = &sale.items;
+    let stamp_lines = if sale.reprint.is_some() { 2 } else { 0 };
+    let discount_lines = details.iter().filter(|d| d.discount != 0).count();
+    let tax_lines = usize::from(details.iter().any(|d| d.tax != 0));
+    let lines = 3 + details.len() + discount_lines + tax_lines + 2 + stamp_lines;
     let height = Mm((lines as f32 * 7.0) + 20.0).max(Mm(100.0));
     let (doc, page, layer) = PdfDocument::new("Customer Receipt", Mm(80.0), height, "L1");
     let current = doc.get_page(page).get_layer(layer);
@@ -33,7 +35,7 @@ pub fn print_customer_receipt(
         &current,
         &font,
         &bold_font,
-        tx,
+        sale,
         operator_name,
         customer_name,
         height,
@@ -63,11 +65,42 @@ pub fn print_customer_receipt(
             &font,
         );
         y -= Mm(4.0);
+        // the promotion's name and what it took off, under the line
+        if d.discount != 0 {
+            current.use_text(
+                format!(
+                    "{} -{:.2}",
+                    d.promotion.as_deref().unwrap_or("Discount"),
+                    f64::from(d.discount) / 100.0
+                ),
+                7.0,
+                Mm(8.0),
+                y,
+                &font,
+            );
+            y -= Mm(3.5);
+        }
     }
 
-    // total
+    // tax, then the total charged
+    let tax: i32 = details.iter().map(|d| d.tax).sum();
+    if tax != 0 {
+        y -= Mm(6.0);
+        current.use_text(
+            format!("Tax: {:.2}", f64::from(tax) / 100.0),
+            9.0,
+            Mm(5.0),
+            y,
+            &font,
+        );
+    }
     y -= Mm(8.0);
-    let total = f64::from(details.iter().map(|d| d.quantity * d.price).sum::<i32>()) / 100.0;
+    let total = f64::from(
+        details
+            .iter()
+            .map(|d| d.quantity * d.price - d.discount + d.tax)
+            .sum::<i32>(),
+    ) / 100.0;
     current.use_text(format!("Total: {total:.2}"), 10.0, Mm(5.0), y, &bold_font);
 
     // printed timestamp
@@ -0,0 +1,144 @@
+use crate::common::error::AppError;
+use crate::domain::report_models::expiry_report::ExpiringLot;
+use crate::infrastructure::printing::paginator::Paginator;
+use crate::infrastructure::printing::print::print_pdf_silently;
+use crate::infrastructure::printing::reports::common::util::{format_cents, format_number};
+use chrono::NaiveDate;
+use dotenvy::var;
+use printpdf::{BuiltinFont, Mm, PdfDocument, PdfLayerReference};
+use std::io::Write;
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+
+/// Prints the lots that expire within `days` of `today`, soonest first, so
+/// they can be pulled from the shelves. Lots already past their date are
+/// marked as expired.
+pub fn print_expiry_report(
+    lots: &[ExpiringLot],
+    today: NaiveDate,
+    days: i64,
+    printer_name: &str,
+    sumatra_location: &str,
+) -> Result<(), AppError> {
+    let page_width = Mm(210.0);
+    let page_height = Mm(297.0);
+    let margin_top = Mm(15.0);
+    let margin_bottom = Mm(15.0);
+    let line_height = Mm(7.0);
+    let footer_height = Mm(12.0);
+
+    let (doc, first_page, first_layer) =
+        PdfDocument::new("Expiring Stock", page_width, page_height, "Layer1");
+    let font = doc.add_builtin_font(BuiltinFont::Helvetica)?;
+    let bold = doc.add_builtin_font(BuiltinFont::HelveticaBold)?;
+
+    let facility = var("CLUB_NAME").unwrap_or_default();
+    let title = format!(
+        "{facility} Stock Expiring by {}",
+        (today + chrono::Duration::days(days)).format("%Y-%m-%d")
+    );
+    let title_size = 14.0;
+    let avg_char_w_pt = title_size * 0.5;
+    let title_w_mm = Mm(avg_char_w_pt * (title.len() as f32) * 0.3528);
+    let title_x = Mm((page_width.0 - title_w_mm.0) / 2.0);
+
+    let first_flag = Arc::new(AtomicBool::new(true));
+    let draw_header = {
+        let first_flag = first_flag.clone();
+        let bold = bold.clone();
+        move |layer: &PdfLayerReference| {
+            let mut y = page_height - margin_top;
+            if first_flag.swap(false, Ordering::SeqCst) {
+                layer.use_text(&title, title_size, title_x, y, &bold);
+                y -= Mm(line_height.0 * 1.1);
+            }
+            layer.use_text("Expires", 11.0, Mm(10.0), y, &bold);
+            layer.use_text("Product", 11.0, Mm(35.0), y, &bold);
+            layer.use_text("Lot", 11.0, Mm(110.0), y, &bold);
+            layer.use_text("Days", 11.0, Mm(140.0), y, &bold);
+            layer.use_text("On Hand", 11.0, Mm(155.0), y, &bold);
+            layer.use_text("Retail", 11.0, Mm(178.0), y, &bold);
+        }
+    };
+    let draw_footer = |_: &PdfLayerReference| {};
+
+    {
+        let mut pg = Paginator::new(
+            &doc,
+            first_page,
+            first_layer,
+            page_width,
+            page_height,
+            margin_top,
+            margin_bottom,
+            line_height,
+            footer_height,
+            draw_header,
+            draw_footer,
+        );
+        pg.advance(line_height * 1.3);
+
+        let mut units = 0;
+        let mut value = 0;
+        for l in lots {
+            let layer = pg.layer_for(line_height);
+            let y = pg.current_y();
+            let expires = l
+                .lot
+                .label
+                .expires_on
+                .map(|d| d.format("%Y-%m-%d").to_string())
+                .unwrap_or_default();
+            let left = l.days_left(today);
+            let days_left = if left < 0 {
+                "expired".to_string()
+            } else {
+                left.to_string()
+            };
+            let name: String = l.product_name.chars().take(40).collect();
+            layer.use_text(expires, 9.0, Mm(10.0), y, &font);
+            layer.use_text(name, 9.0, Mm(35.0), y, &font);
+            layer.use_text(
+                l.lot.label.lot_number.as_deref().unwrap_or(""),
+                9.0,
+                Mm(110.0),
+                y,
+                &font,
+            );
+            layer.use_text(days_left, 9.0, Mm(140.0), y, &font);
+            layer.use_text(format_number(l.lot.on_hand), 9.0, Mm(155.0), y, &font);
+            layer.use_text(format_cents(l.value()), 9.0, Mm(178.0), y, &font);
+            pg.advance(line_height);
+            units += l.lot.on_hand;
+            value += l.value();
+        }
+
+        let sep_layer = pg.layer_for(Mm(7.0));
+        for x in [155.0, 178.0] {
+            sep_layer.use_text("________", 9.0, Mm(x), pg.current_y() + Mm(5.0), &font);
+        }
+        sep_layer.use_text("Total", 9.0, Mm(10.0), pg.current_y(), &bold);
+        sep_layer.use_text(format_number(units), 9.0, Mm(155.0), pg.current_y(), &bold);
+        sep_layer.use_text(format_cents(value), 9.0, Mm(178.0), pg.current_y(), &bold);
+
+        pg.finalize();
+        pg.draw_page_numbers(&font);
+    }
+
+    let path = "expiring_stock.pdf";
+    let mut f = std::io::BufWriter::new(std::fs::File::create(path)?);
+    doc.save(&mut f)?;
+    f.flush()?;
+
+    let printer = printer_name.to_string();
+    let sumatra_location = sumatra_location.to_string();
+    std::thread::spawn(move || {
+        if let Err(e) = print_pdf_silently(path, &printer, &sumatra_location) {
+            log::error!("Print failed: {e}");
+        }
+    });
+
+    Ok(())
+}
@@ -0,0 +1,145 @@
+use crate::common::error::AppError;
+use crate::domain::report_models::margin_report::CategoryMargin;
+use crate::infrastructure::printing::paginator::Paginator;
+use crate::infrastructure::printing::print::print_pdf_silently;
+use crate::infrastructure::printing::reports::common::util::{format_cents, format_number};
+use chrono::NaiveDateTime;
+use dotenvy::var;
+use printpdf::{BuiltinFont, IndirectFontRef, Mm, PdfDocument, PdfLayerReference};
+use std::io::Write;
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+
+fn format_pct(row: &CategoryMargin) -> String {
+    row.margin_pct()
+        .map_or_else(|| "-".to_string(), |p| format!("{p:.1}%"))
+}
+
+// One category (or the total) across the report's columns.
+fn draw_row(layer: &PdfLayerReference, y: Mm, row: &CategoryMargin, font: &IndirectFontRef) {
+    layer.use_text(&row.category, 9.0, Mm(10.0), y, font);
+    layer.use_text(format_number(row.quantity_sold), 9.0, Mm(70.0), y, font);
+    layer.use_text(format_cents(row.revenue), 9.0, Mm(90.0), y, font);
+    layer.use_text(format_cents(row.cogs), 9.0, Mm(120.0), y, font);
+    layer.use_text(format_cents(row.margin()), 9.0, Mm(150.0), y, font);
+    layer.use_text(format_pct(row), 9.0, Mm(180.0), y, font);
+}
+
+/// Prints revenue, cost of goods sold and margin per category between
+/// `start` and `end`.
+pub fn print_margin_report(
+    rows: &[CategoryMargin],
+    start: NaiveDateTime,
+    end: NaiveDateTime,
+    printer_name: &str,
+    sumatra_location: &str,
+) -> Result<(), AppError> {
+    let page_width = Mm(210.0);
+    let page_height = Mm(297.0);
+    let margin_top = Mm(15.0);
+    let margin_bottom = Mm(15.0);
+    let line_height = Mm(7.0);
+    let footer_height = Mm(12.0);
+
+    let (doc, first_page, first_layer) =
+        PdfDocument::new("Gross Margin", page_width, page_height, "Layer1");
+    let font = doc.add_builtin_font(BuiltinFont::Helvetica)?;
+    let bold = doc.add_builtin_font(BuiltinFont::HelveticaBold)?;
+
+    let facility = var("CLUB_NAME").unwrap_or_default();
+    let title = format!(
+        "{facility} Gross Margin from {} to {}",
+        start.format("%Y-%m-%d"),
+        end.format("%Y-%m-%d")
+    );
+    let title_size = 14.0;
+    let avg_char_w_pt = title_size * 0.5;
+    let title_w_mm = Mm(avg_char_w_pt * (title.len() as f32) * 0.3528);
+    let title_x = Mm((page_width.0 - title_w_mm.0) / 2.0);
+
+    let first_flag = Arc::new(AtomicBool::new(true));
+    let draw_header = {
+        let first_flag = first_flag.clone();
+        let bold = bold.clone();
+        move |layer: &PdfLayerReference| {
+            let mut y = page_height - margin_top;
+            if first_flag.swap(false, Ordering::SeqCst) {
+                layer.use_text(&title, title_size, title_x, y, &bold);
+                y -= Mm(line_height.0 * 1.1);
+            }
+            layer.use_text("Category", 11.0, Mm(10.0), y, &bold);
+            layer.use_text("Qty", 11.0, Mm(70.0), y, &bold);
+            layer.use_text("Revenue", 11.0, Mm(90.0), y, &bold);
+            layer.use_text("COGS", 11.0, Mm(120.0), y, &bold);
+            layer.use_text("Margin", 11.0, Mm(150.0), y, &bold);
+            layer.use_text("Margin %", 11.0, Mm(180.0), y, &bold);
+        }
+    };
+    let draw_footer = |_: &PdfLayerReference| {};
+
+    {
+        let mut pg = Paginator::new(
+            &doc,
+            first_page,
+            first_layer,
+            page_width,
+            page_height,
+            margin_top,
+            margin_bottom,
+            line_height,
+            footer_height,
+            draw_header,
+            draw_footer,
+        );
+        pg.advance(line_height * 1.3);
+
+        for row in rows {
+            let layer = pg.layer_for(line_height);
+            draw_row(&layer, pg.current_y(), row, &font);
+            pg.advance(line_height);
+        }
+
+        let total = CategoryMargin::total(rows);
+        let sep_layer = pg.layer_for(Mm(7.0));
+        for x in [70.0, 90.0, 120.0, 150.0, 180.0] {
+            sep_layer.use_text("________", 9.0, Mm(x), pg.current_y() + Mm(5.0), &font);
+        }
+        draw_row(&sep_layer, pg.current_y(), &total, &bold);
+        pg.advance(line_height * 1.5);
+
+        // sales of products that had no cost yet can't be given a margin
+        if total.uncosted_revenue() != 0 {
+            let layer = pg.layer_for(line_height);
+            layer.use_text(
+                format!(
+                    "{} of revenue came from products with no recorded cost and is left out of COGS and margin.",
+                    format_cents(total.uncosted_revenue())
+                ),
+                8.0,
+                Mm(10.0),
+                pg.current_y(),
+                &font,
+            );
+        }
+
+        pg.finalize();
+        pg.draw_page_numbers(&font);
+    }
+
+    let path = "gross_margin.pdf";
+    let mut f = std::io::BufWriter::new(std::fs::File::create(path)?);
+    doc.save(&mut f)?;
+    f.flush()?;
+
+    let printer = printer_name.to_string();
+    let sumatra_location = sumatra_location.to_string();
+    std::thread::spawn(move || {
+        if let Err(e) = print_pdf_silently(path, &printer, &sumatra_location) {
+            log::error!("Print failed: {e}");
+        }
+    });
+
+    Ok(())
+}
@@ -3,8 +3,14 @@ pub mod club_imports;
 pub mod common;
 pub mod customer_balance_report;
 pub mod customer_receipt;
+pub mod expiry_report;
+pub mod margin_report;
 pub mod print_daily_sales;
 pub mod prod_inv_report;
 pub mod product_catalog;
 pub mod product_sales;
+pub mod receiving_report;
+pub mod reorder_report;
 pub mod sales_detail_report;
+pub mod shrinkage_report;
+pub mod stock_take_report;
@@ -1,9 +1,11 @@
 use crate::common::error::AppError;
 use crate::domain::report_models::daily_sales::DailySales;
 use crate::domain::report_models::product_sales::SalesTotals;
+use crate::domain::report_models::tax_summary::TaxSummaryRow;
 use crate::infrastructure::printing::paginator::Paginator;
 use crate::infrastructure::printing::print::print_pdf_silently;
 use crate::infrastructure::printing::reports::common::account_footer;
+use crate::infrastructure::printing::reports::common::tax_summary::tax_summary;
 use crate::infrastructure::printing::reports::common::util::{format_cents, format_number};
 use chrono::NaiveDateTime;
 use dotenvy::var;
@@ -15,11 +17,13 @@ use std::sync::{
 };
 
 // Prints a “Sales by Day” report PDF and sends to printer.
+#[allow(clippy::too_many_arguments)]
 pub fn print_daily_sales(
     rows: &[DailySales],
     start: NaiveDateTime,
     end: NaiveDateTime,
     sales_totals: SalesTotals,
+    tax_rows: &[TaxSummaryRow],
     total_amount: i32,
     printer_name: &str,
     sumatra_location: &str,
@@ -198,6 +202,9 @@ pub fn print_daily_sales(
             pg.current_y(),
             &bold,
         );
+        pg.advance(line_height);
+
+        tax_summary(&mut pg, tax_rows, &font, &bold);
 
         // finish PDF
         pg.finalize();
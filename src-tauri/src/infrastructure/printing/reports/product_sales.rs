@@ -66,8 +66,9 @@ pub fn print_product_sales(
             // column labels
             layer.use_text("Qty", 11.0, Mm(10.0), y, &bold);
             layer.use_text("Product", 11.0, Mm(30.0), y, &bold);
-            layer.use_text("UPC", 11.0, Mm(100.0), y, &bold);
-            layer.use_text("Price", 11.0, Mm(140.0), y, &bold);
+            layer.use_text("UPC", 11.0, Mm(95.0), y, &bold);
+            layer.use_text("Price", 11.0, Mm(128.0), y, &bold);
+            layer.use_text("Disc.", 11.0, Mm(150.0), y, &bold);
             layer.use_text("Total", 11.0, Mm(170.0), y, &bold);
         }
     };
@@ -120,7 +121,7 @@ pub fn print_product_sales(
                     let total_line_layer = pg.layer_for(line_height);
                     total_line_layer.use_text("_______", 11.0, Mm(170.0), pg.current_y(), &font);
                 }
-                // category‐total line in bold (Qty + Total only)
+                // category‐total line in bold (Qty, Discount and Total only)
                 layer.use_text(
                     format_number(r.quantity_sold),
                     9.0,
@@ -128,6 +129,13 @@ pub fn print_product_sales(
                     pg.current_y() - Mm(line_height.0) + Mm(2.0),
                     &bold,
                 );
+                layer.use_text(
+                    format_cents(r.discounts),
+                    9.0,
+                    Mm(150.0),
+                    pg.current_y() - Mm(line_height.0) + Mm(2.0),
+                    &bold,
+                );
                 layer.use_text(
                     format_cents(r.total_sales),
                     9.0,
@@ -146,8 +154,17 @@ pub fn print_product_sales(
                     &font,
                 );
                 layer.use_text(&r.name, 9.0, Mm(30.0), pg.current_y(), &font);
-                layer.use_text(&r.upc, 9.0, Mm(100.0), pg.current_y(), &font);
-                layer.use_text(format_cents(r.price), 9.0, Mm(140.0), pg.current_y(), &font);
+                layer.use_text(&r.upc, 9.0, Mm(95.0), pg.current_y(), &font);
+                layer.use_text(format_cents(r.price), 9.0, Mm(128.0), pg.current_y(), &font);
+                if r.discounts != 0 {
+                    layer.use_text(
+                        format_cents(r.discounts),
+                        9.0,
+                        Mm(150.0),
+                        pg.current_y(),
+                        &font,
+                    );
+                }
                 layer.use_text(
                     format_cents(r.total_sales),
                     9.0,
@@ -182,7 +199,14 @@ pub fn print_product_sales(
             pg.current_y(),
             &bold,
         );
-        tot_layer.use_text("Grand Total:", 9.0, Mm(150.0), pg.current_y(), &bold);
+        tot_layer.use_text("Grand Total:", 9.0, Mm(125.0), pg.current_y(), &bold);
+        tot_layer.use_text(
+            format_cents(sales_totals.total_discount),
+            9.0,
+            Mm(150.0),
+            pg.current_y(),
+            &bold,
+        );
         tot_layer.use_text(
             format_cents(sales_totals.total_value),
             9.0,
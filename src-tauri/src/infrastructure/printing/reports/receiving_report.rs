@@ -0,0 +1,209 @@
+use crate::common::error::AppError;
+use crate::domain::report_models::receiving_report::ReceivingReport;
+use crate::infrastructure::printing::paginator::Paginator;
+use crate::infrastructure::printing::print::print_pdf_silently;
+use crate::infrastructure::printing::reports::common::horizontal_line;
+use crate::infrastructure::printing::reports::common::util::{format_number, truncate_desc};
+use dotenvy::var;
+use printpdf::{BuiltinFont, Mm, PdfDocument, PdfLayerReference};
+use std::io::Write;
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+
+/// Prints a purchase order's lines with ordered, received and variance
+/// columns, flagging over- and under-shipped items.
+pub fn print_receiving_report(
+    report: &ReceivingReport,
+    printer_name: &str,
+    sumatra_location: &str,
+) -> Result<(), AppError> {
+    let page_width = Mm(210.0);
+    let page_height = Mm(297.0);
+    let margin_top = Mm(15.0);
+    let margin_bottom = Mm(15.0);
+    let line_height = Mm(7.0);
+    let footer_height = Mm(12.0);
+
+    let (doc, first_page, first_layer) =
+        PdfDocument::new("Receiving Report", page_width, page_height, "Layer1");
+    let font = doc.add_builtin_font(BuiltinFont::Helvetica)?;
+    let bold = doc.add_builtin_font(BuiltinFont::HelveticaBold)?;
+
+    let po = &report.po;
+    let facility = var("CLUB_NAME").unwrap_or_default();
+    let title = format!("{facility} Receiving Report - PO #{}", po.id);
+    let title_size = 14.0;
+    let avg_w_pt = title_size * 0.5;
+    let title_w_mm = Mm(avg_w_pt * (title.len() as f32) * 0.3528);
+    let title_x = Mm((page_width.0 - title_w_mm.0) / 2.0);
+
+    // title on the first page, column headers on every page
+    let first_flag = Arc::new(AtomicBool::new(true));
+    let draw_header = {
+        let first_flag = first_flag.clone();
+        let bold = bold.clone();
+        move |layer: &PdfLayerReference| {
+            let mut y = page_height - margin_top;
+            if first_flag.swap(false, Ordering::SeqCst) {
+                layer.use_text(&title, title_size, title_x, y, &bold);
+                y -= line_height * 4.0;
+            }
+            layer.use_text("UPC", 10.0, Mm(15.0), y, &bold);
+            layer.use_text("Product", 10.0, Mm(50.0), y, &bold);
+            layer.use_text("Ordered", 10.0, Mm(115.0), y, &bold);
+            layer.use_text("Received", 10.0, Mm(135.0), y, &bold);
+            layer.use_text("This Rcpt", 10.0, Mm(155.0), y, &bold);
+            layer.use_text("Var.", 10.0, Mm(175.0), y, &bold);
+        }
+    };
+    let draw_footer = |_: &PdfLayerReference| {};
+
+    {
+        let mut pg = Paginator::new(
+            &doc,
+            first_page,
+            first_layer,
+            page_width,
+            page_height,
+            margin_top,
+            margin_bottom,
+            line_height,
+            footer_height,
+            draw_header,
+            draw_footer,
+        );
+
+        // order summary between the title and the column headers
+        let received_at = po.received_at.map_or_else(
+            || "-".to_string(),
+            |d| d.format("%Y-%m-%d %H:%M").to_string(),
+        );
+        let summary = [
+            ("Vendor", report.vendor_name.clone()),
+            (
+                "Reference",
+                po.reference.clone().unwrap_or_else(|| "-".into()),
+            ),
+            ("Status", po.status.as_str().to_string()),
+        ];
+        let dates = [
+            (
+                "Ordered",
+                po.created_at
+                    .map(|d| d.format("%Y-%m-%d").to_string())
+                    .unwrap_or_default(),
+            ),
+            ("Received", received_at),
+            ("Operator", po.operator_mdoc.to_string()),
+        ];
+        for ((label_l, value_l), (label_r, value_r)) in summary.iter().zip(dates.iter()) {
+            let layer = pg.layer_for(line_height);
+            layer.use_text(format!("{label_l}:"), 10.0, Mm(15.0), pg.current_y(), &bold);
+            layer.use_text(value_l, 10.0, Mm(40.0), pg.current_y(), &font);
+            layer.use_text(
+                format!("{label_r}:"),
+                10.0,
+                Mm(115.0),
+                pg.current_y(),
+                &bold,
+            );
+            layer.use_text(value_r, 10.0, Mm(140.0), pg.current_y(), &font);
+            pg.advance(line_height);
+        }
+        pg.advance(line_height);
+        let layer = pg.layer_for(line_height);
+        horizontal_line::draw_line(&layer, &font, pg.current_y() + Mm(4.0));
+
+        let (mut ordered, mut received, mut flagged) = (0, 0, 0);
+        for row in &report.lines {
+            let line = &row.line;
+            let layer = pg.layer_for(line_height);
+            let f = if row.flag().is_some() { &bold } else { &font };
+            layer.use_text(&line.upc, 9.0, Mm(15.0), pg.current_y(), f);
+            layer.use_text(
+                truncate_desc(&row.product_name, 32),
+                9.0,
+                Mm(50.0),
+                pg.current_y(),
+                f,
+            );
+            layer.use_text(
+                format_number(line.expected_quantity),
+                9.0,
+                Mm(115.0),
+                pg.current_y(),
+                f,
+            );
+            layer.use_text(
+                format_number(line.received_quantity),
+                9.0,
+                Mm(135.0),
+                pg.current_y(),
+                f,
+            );
+            layer.use_text(
+                format_number(row.received_now),
+                9.0,
+                Mm(155.0),
+                pg.current_y(),
+                f,
+            );
+            let variance = match row.flag() {
+                Some(flag) => format!("{:+} {flag}", line.variance()),
+                None => "0".to_string(),
+            };
+            layer.use_text(variance, 9.0, Mm(175.0), pg.current_y(), f);
+            pg.advance(line_height);
+
+            ordered += line.expected_quantity;
+            received += line.received_quantity;
+            flagged += i32::from(row.flag().is_some());
+        }
+
+        let layer = pg.layer_for(line_height * 2.0);
+        horizontal_line::draw_line(&layer, &font, pg.current_y() + Mm(4.0));
+        layer.use_text("Totals:", 9.0, Mm(50.0), pg.current_y(), &bold);
+        layer.use_text(
+            format_number(ordered),
+            9.0,
+            Mm(115.0),
+            pg.current_y(),
+            &bold,
+        );
+        layer.use_text(
+            format_number(received),
+            9.0,
+            Mm(135.0),
+            pg.current_y(),
+            &bold,
+        );
+        pg.advance(line_height);
+        layer.use_text(
+            format!("{flagged} line(s) over or short"),
+            9.0,
+            Mm(50.0),
+            pg.current_y(),
+            &font,
+        );
+
+        pg.finalize();
+        pg.draw_page_numbers(&font);
+    }
+
+    let path = "receiving_report.pdf";
+    let mut f = std::io::BufWriter::new(std::fs::File::create(path)?);
+    doc.save(&mut f)?;
+    f.flush()?;
+
+    let printer = printer_name.to_string();
+    let sumatra_location = sumatra_location.to_string();
+    std::thread::spawn(move || {
+        if let Err(e) = print_pdf_silently(path, &printer, &sumatra_location) {
+            log::error!("Print failed: {e}");
+        }
+    });
+
+    Ok(())
+}
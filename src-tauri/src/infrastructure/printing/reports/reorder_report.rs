@@ -0,0 +1,144 @@
+use crate::common::error::AppError;
+use crate::domain::report_models::reorder_report::LowStockItem;
+use crate::infrastructure::printing::paginator::Paginator;
+use crate::infrastructure::printing::print::print_pdf_silently;
+use crate::infrastructure::printing::reports::common::util::{format_number, truncate_desc};
+use dotenvy::var;
+use printpdf::{BuiltinFont, Mm, PdfDocument, PdfLayerReference};
+use std::io::Write;
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+
+/// Prints every product at or below its reorder point, grouped by category
+/// and, within a category, by the vendor it was last ordered from.
+pub fn print_reorder_report(
+    rows: &[LowStockItem],
+    printer_name: &str,
+    sumatra_location: &str,
+) -> Result<(), AppError> {
+    let page_width = Mm(210.0);
+    let page_height = Mm(297.0);
+    let margin_top = Mm(15.0);
+    let margin_bottom = Mm(15.0);
+    let line_height = Mm(7.0);
+    let footer_height = Mm(12.0);
+
+    let (doc, first_page, first_layer) =
+        PdfDocument::new("Reorder Report", page_width, page_height, "Layer1");
+    let font = doc.add_builtin_font(BuiltinFont::Helvetica)?;
+    let bold = doc.add_builtin_font(BuiltinFont::HelveticaBold)?;
+
+    let facility = var("CLUB_NAME").unwrap_or_default();
+    let title = format!(
+        "{facility} Reorder Report - {}",
+        chrono::Local::now().format("%Y-%m-%d")
+    );
+    let title_size = 14.0;
+    let avg_w_pt = title_size * 0.5;
+    let title_w_mm = Mm(avg_w_pt * (title.len() as f32) * 0.3528);
+    let title_x = Mm((page_width.0 - title_w_mm.0) / 2.0);
+
+    let first_flag = Arc::new(AtomicBool::new(true));
+    let draw_header = {
+        let first_flag = first_flag.clone();
+        let bold = bold.clone();
+        move |layer: &PdfLayerReference| {
+            let mut y = page_height - margin_top;
+            if first_flag.swap(false, Ordering::SeqCst) {
+                layer.use_text(&title, title_size, title_x, y, &bold);
+                y -= Mm(line_height.0 * 1.1);
+            }
+            layer.use_text("UPC", 10.0, Mm(10.0), y, &bold);
+            layer.use_text("Product", 10.0, Mm(42.0), y, &bold);
+            layer.use_text("Vendor", 10.0, Mm(100.0), y, &bold);
+            layer.use_text("On Hand", 10.0, Mm(140.0), y, &bold);
+            layer.use_text("Reorder At", 10.0, Mm(158.0), y, &bold);
+            layer.use_text("Order", 10.0, Mm(182.0), y, &bold);
+        }
+    };
+    let draw_footer = |_: &PdfLayerReference| {};
+
+    {
+        let mut pg = Paginator::new(
+            &doc,
+            first_page,
+            first_layer,
+            page_width,
+            page_height,
+            margin_top,
+            margin_bottom,
+            line_height,
+            footer_height,
+            draw_header,
+            draw_footer,
+        );
+        pg.advance(line_height * 1.3);
+
+        if rows.is_empty() {
+            let layer = pg.layer_for(line_height);
+            layer.use_text(
+                "Nothing is at or below its reorder point.",
+                10.0,
+                Mm(10.0),
+                pg.current_y(),
+                &font,
+            );
+        }
+
+        let mut current_category: Option<&str> = None;
+        for row in rows {
+            if current_category != Some(row.category.as_str()) {
+                if current_category.is_some() {
+                    pg.advance(line_height * 0.5);
+                }
+                // keep a category heading with its first item
+                let layer = pg.layer_for(line_height * 2.0);
+                layer.use_text(&row.category, 11.0, Mm(10.0), pg.current_y(), &bold);
+                pg.advance(line_height);
+                current_category = Some(row.category.as_str());
+            }
+            let layer = pg.layer_for(line_height);
+            let y = pg.current_y();
+            layer.use_text(&row.upc, 9.0, Mm(10.0), y, &font);
+            layer.use_text(truncate_desc(&row.desc, 28), 9.0, Mm(42.0), y, &font);
+            layer.use_text(
+                truncate_desc(row.vendor_name.as_deref().unwrap_or("-"), 20),
+                9.0,
+                Mm(100.0),
+                y,
+                &font,
+            );
+            layer.use_text(format_number(row.available), 9.0, Mm(140.0), y, &bold);
+            layer.use_text(format_number(row.reorder_point), 9.0, Mm(158.0), y, &font);
+            layer.use_text(
+                row.reorder_quantity
+                    .map_or_else(|| "-".to_string(), format_number),
+                9.0,
+                Mm(182.0),
+                y,
+                &font,
+            );
+            pg.advance(line_height);
+        }
+
+        pg.finalize();
+        pg.draw_page_numbers(&font);
+    }
+
+    let path = "reorder_report.pdf";
+    let mut f = std::io::BufWriter::new(std::fs::File::create(path)?);
+    doc.save(&mut f)?;
+    f.flush()?;
+
+    let printer = printer_name.to_string();
+    let sumatra_location = sumatra_location.to_string();
+    std::thread::spawn(move || {
+        if let Err(e) = print_pdf_silently(path, &printer, &sumatra_location) {
+            log::error!("Print failed: {e}");
+        }
+    });
+
+    Ok(())
+}
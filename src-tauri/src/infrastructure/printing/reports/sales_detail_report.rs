@@ -1,9 +1,14 @@
 use crate::common::error::AppError;
+use crate::domain::models::OrderReason;
 use crate::domain::report_models::product_sales::SalesTotals;
 use crate::domain::report_models::sales_details::SalesReportDetails;
+use crate::domain::report_models::tax_summary::TaxSummaryRow;
 use crate::infrastructure::printing::reports::common::account_footer;
 use crate::infrastructure::printing::reports::common::horizontal_line;
-use crate::infrastructure::printing::reports::common::util::{format_cents, format_number};
+use crate::infrastructure::printing::reports::common::tax_summary::tax_summary;
+use crate::infrastructure::printing::reports::common::util::{
+    format_cents, format_number, truncate_desc,
+};
 use crate::infrastructure::printing::{paginator::Paginator, print::print_pdf_silently};
 use chrono::NaiveDateTime;
 use dotenvy::var;
@@ -14,11 +19,13 @@ use std::sync::Arc;
 
 /// Prints a chronological list of transactions (with their detail rows)
 /// between `start` and `end`.
+#[allow(clippy::too_many_arguments)]
 pub fn print_sales_detail_report(
     txs: &[SalesReportDetails],
     start: NaiveDateTime,
     end: NaiveDateTime,
     sales_totals: SalesTotals,
+    tax_rows: &[TaxSummaryRow],
     total_amount: i32,
     printer_name: &str,
     sumatra_location: &str,
@@ -129,7 +136,14 @@ pub fn print_sales_detail_report(
         let mut first_tx = true;
         for t in txs {
             let tx = &t.tx;
-            let lines_needed = 1 + t.details.len();
+            // reason code and note share one line under the order row
+            let order_info = match (tx.reason, tx.note.as_deref()) {
+                (OrderReason::Regular, None) => None,
+                (OrderReason::Regular, Some(note)) => Some(note.to_string()),
+                (reason, None) => Some(reason.label().to_string()),
+                (reason, Some(note)) => Some(format!("{}: {note}", reason.label())),
+            };
+            let lines_needed = 1 + usize::from(order_info.is_some()) + t.details.len();
             let needed_height = line_height * (lines_needed as f32) + line_height;
             // ask paginator for a page that can fit the whole block
             let layer = pg.layer_for(needed_height);
@@ -178,6 +192,18 @@ pub fn print_sales_detail_report(
             );
             pg.advance(line_height);
 
+            if let Some(info) = &order_info {
+                let layer = pg.layer_for(line_height);
+                layer.use_text(
+                    truncate_desc(info, 90),
+                    8.0,
+                    Mm(40.0),
+                    pg.current_y(),
+                    &font,
+                );
+                pg.advance(line_height);
+            }
+
             // print each detail row
             for d in &t.details {
                 let layer = pg.layer_for(line_height);
@@ -192,6 +218,24 @@ pub fn print_sales_detail_report(
                 );
                 layer.use_text(format_cents(d.price), 8.0, Mm(165.0), pg.current_y(), &font);
                 pg.advance(line_height);
+                if d.discount != 0 {
+                    let layer = pg.layer_for(line_height);
+                    layer.use_text("Promotion", 8.0, Mm(84.0), pg.current_y(), &font);
+                    layer.use_text(
+                        format_cents(-d.discount),
+                        8.0,
+                        Mm(165.0),
+                        pg.current_y(),
+                        &font,
+                    );
+                    pg.advance(line_height);
+                }
+                if d.tax != 0 {
+                    let layer = pg.layer_for(line_height);
+                    layer.use_text("Tax", 8.0, Mm(84.0), pg.current_y(), &font);
+                    layer.use_text(format_cents(d.tax), 8.0, Mm(165.0), pg.current_y(), &font);
+                    pg.advance(line_height);
+                }
             }
         }
 
@@ -222,6 +266,9 @@ pub fn print_sales_detail_report(
             pg.current_y(),
             &bold,
         );
+        pg.advance(line_height);
+
+        tax_summary(&mut pg, tax_rows, &font, &bold);
 
         // finish PDF
         pg.finalize();
@@ -0,0 +1,152 @@
+use crate::common::error::AppError;
+use crate::domain::report_models::shrinkage_report::ShrinkageLine;
+use crate::infrastructure::printing::paginator::Paginator;
+use crate::infrastructure::printing::print::print_pdf_silently;
+use crate::infrastructure::printing::reports::common::util::{format_cents, format_number};
+use chrono::NaiveDateTime;
+use dotenvy::var;
+use printpdf::{BuiltinFont, IndirectFontRef, Mm, PdfDocument, PdfLayerReference};
+use std::io::Write;
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+
+// One reason/category pair (or a subtotal) across the report's columns.
+fn draw_row(
+    layer: &PdfLayerReference,
+    y: Mm,
+    label: &str,
+    category: &str,
+    row: &ShrinkageLine,
+    font: &IndirectFontRef,
+) {
+    layer.use_text(label, 9.0, Mm(10.0), y, font);
+    layer.use_text(category, 9.0, Mm(55.0), y, font);
+    layer.use_text(format_number(row.units), 9.0, Mm(115.0), y, font);
+    layer.use_text(format_cents(row.retail_value), 9.0, Mm(140.0), y, font);
+    layer.use_text(format_cents(row.cost_value), 9.0, Mm(170.0), y, font);
+}
+
+/// Prints stock written off between `start` and `end`, grouped by reason with
+/// a line per category and a subtotal per reason.
+pub fn print_shrinkage_report(
+    lines: &[ShrinkageLine],
+    start: NaiveDateTime,
+    end: NaiveDateTime,
+    printer_name: &str,
+    sumatra_location: &str,
+) -> Result<(), AppError> {
+    let page_width = Mm(210.0);
+    let page_height = Mm(297.0);
+    let margin_top = Mm(15.0);
+    let margin_bottom = Mm(15.0);
+    let line_height = Mm(7.0);
+    let footer_height = Mm(12.0);
+
+    let (doc, first_page, first_layer) =
+        PdfDocument::new("Shrinkage", page_width, page_height, "Layer1");
+    let font = doc.add_builtin_font(BuiltinFont::Helvetica)?;
+    let bold = doc.add_builtin_font(BuiltinFont::HelveticaBold)?;
+
+    let facility = var("CLUB_NAME").unwrap_or_default();
+    let title = format!(
+        "{facility} Shrinkage from {} to {}",
+        start.format("%Y-%m-%d"),
+        end.format("%Y-%m-%d")
+    );
+    let title_size = 14.0;
+    let avg_char_w_pt = title_size * 0.5;
+    let title_w_mm = Mm(avg_char_w_pt * (title.len() as f32) * 0.3528);
+    let title_x = Mm((page_width.0 - title_w_mm.0) / 2.0);
+
+    let first_flag = Arc::new(AtomicBool::new(true));
+    let draw_header = {
+        let first_flag = first_flag.clone();
+        let bold = bold.clone();
+        move |layer: &PdfLayerReference| {
+            let mut y = page_height - margin_top;
+            if first_flag.swap(false, Ordering::SeqCst) {
+                layer.use_text(&title, title_size, title_x, y, &bold);
+                y -= Mm(line_height.0 * 1.1);
+            }
+            layer.use_text("Reason", 11.0, Mm(10.0), y, &bold);
+            layer.use_text("Category", 11.0, Mm(55.0), y, &bold);
+            layer.use_text("Units", 11.0, Mm(115.0), y, &bold);
+            layer.use_text("Retail", 11.0, Mm(140.0), y, &bold);
+            layer.use_text("Cost", 11.0, Mm(170.0), y, &bold);
+        }
+    };
+    let draw_footer = |_: &PdfLayerReference| {};
+
+    {
+        let mut pg = Paginator::new(
+            &doc,
+            first_page,
+            first_layer,
+            page_width,
+            page_height,
+            margin_top,
+            margin_bottom,
+            line_height,
+            footer_height,
+            draw_header,
+            draw_footer,
+        );
+        pg.advance(line_height * 1.3);
+
+        // lines arrive ordered by reason, so each reason's categories are together
+        for subtotal in ShrinkageLine::by_reason(lines) {
+            let label = subtotal.reason.label();
+            for row in lines.iter().filter(|l| l.reason == subtotal.reason) {
+                let layer = pg.layer_for(line_height);
+                draw_row(&layer, pg.current_y(), label, &row.category, row, &font);
+                pg.advance(line_height);
+            }
+            let layer = pg.layer_for(line_height);
+            draw_row(&layer, pg.current_y(), "", "Subtotal", &subtotal, &bold);
+            pg.advance(line_height * 1.5);
+        }
+
+        let total = ShrinkageLine::total(lines);
+        let sep_layer = pg.layer_for(Mm(7.0));
+        for x in [115.0, 140.0, 170.0] {
+            sep_layer.use_text("________", 9.0, Mm(x), pg.current_y() + Mm(5.0), &font);
+        }
+        draw_row(&sep_layer, pg.current_y(), "Total", "", &total, &bold);
+        pg.advance(line_height * 1.5);
+
+        // write-offs of products that had no cost yet can't be valued at cost
+        if total.uncosted_units != 0 {
+            let layer = pg.layer_for(line_height);
+            layer.use_text(
+                format!(
+                    "{} unit(s) of products with no recorded cost are left out of the cost column.",
+                    format_number(total.uncosted_units)
+                ),
+                8.0,
+                Mm(10.0),
+                pg.current_y(),
+                &font,
+            );
+        }
+
+        pg.finalize();
+        pg.draw_page_numbers(&font);
+    }
+
+    let path = "shrinkage.pdf";
+    let mut f = std::io::BufWriter::new(std::fs::File::create(path)?);
+    doc.save(&mut f)?;
+    f.flush()?;
+
+    let printer = printer_name.to_string();
+    let sumatra_location = sumatra_location.to_string();
+    std::thread::spawn(move || {
+        if let Err(e) = print_pdf_silently(path, &printer, &sumatra_location) {
+            log::error!("Print failed: {e}");
+        }
+    });
+
+    Ok(())
+}
@@ -0,0 +1,195 @@
+use crate::common::error::AppError;
+use crate::domain::report_models::stock_take_report::StockTakeReport;
+use crate::infrastructure::printing::paginator::Paginator;
+use crate::infrastructure::printing::print::print_pdf_silently;
+use crate::infrastructure::printing::reports::common::horizontal_line;
+use crate::infrastructure::printing::reports::common::util::{
+    format_cents, format_number, truncate_desc,
+};
+use dotenvy::var;
+use printpdf::{BuiltinFont, Mm, PdfDocument, PdfLayerReference};
+use std::io::Write;
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+
+/// Prints a stock take's discrepancies: every line whose count differs from
+/// the frozen expected quantity, valued at the sale price, plus lines that
+/// were never counted. Lines that matched are only totalled.
+pub fn print_stock_take_report(
+    report: &StockTakeReport,
+    printer_name: &str,
+    sumatra_location: &str,
+) -> Result<(), AppError> {
+    let page_width = Mm(210.0);
+    let page_height = Mm(297.0);
+    let margin_top = Mm(15.0);
+    let margin_bottom = Mm(15.0);
+    let line_height = Mm(7.0);
+    let footer_height = Mm(12.0);
+
+    let (doc, first_page, first_layer) =
+        PdfDocument::new("Stock Take Variance", page_width, page_height, "Layer1");
+    let font = doc.add_builtin_font(BuiltinFont::Helvetica)?;
+    let bold = doc.add_builtin_font(BuiltinFont::HelveticaBold)?;
+
+    let st = &report.stock_take;
+    let facility = var("CLUB_NAME").unwrap_or_default();
+    let title = format!("{facility} Stock Take Variance - #{}", st.id);
+    let title_size = 14.0;
+    let avg_w_pt = title_size * 0.5;
+    let title_w_mm = Mm(avg_w_pt * (title.len() as f32) * 0.3528);
+    let title_x = Mm((page_width.0 - title_w_mm.0) / 2.0);
+
+    // title on the first page, column headers on every page
+    let first_flag = Arc::new(AtomicBool::new(true));
+    let draw_header = {
+        let first_flag = first_flag.clone();
+        let bold = bold.clone();
+        move |layer: &PdfLayerReference| {
+            let mut y = page_height - margin_top;
+            if first_flag.swap(false, Ordering::SeqCst) {
+                layer.use_text(&title, title_size, title_x, y, &bold);
+                y -= line_height * 4.0;
+            }
+            layer.use_text("UPC", 10.0, Mm(10.0), y, &bold);
+            layer.use_text("Product", 10.0, Mm(42.0), y, &bold);
+            layer.use_text("Expected", 10.0, Mm(102.0), y, &bold);
+            layer.use_text("Counted", 10.0, Mm(122.0), y, &bold);
+            layer.use_text("Var.", 10.0, Mm(142.0), y, &bold);
+            layer.use_text("Price", 10.0, Mm(157.0), y, &bold);
+            layer.use_text("Value", 10.0, Mm(177.0), y, &bold);
+        }
+    };
+    let draw_footer = |_: &PdfLayerReference| {};
+
+    {
+        let mut pg = Paginator::new(
+            &doc,
+            first_page,
+            first_layer,
+            page_width,
+            page_height,
+            margin_top,
+            margin_bottom,
+            line_height,
+            footer_height,
+            draw_header,
+            draw_footer,
+        );
+
+        // session summary between the title and the column headers
+        let fmt_date = |d: Option<chrono::NaiveDateTime>| {
+            d.map_or_else(
+                || "-".to_string(),
+                |d| d.format("%Y-%m-%d %H:%M").to_string(),
+            )
+        };
+        let summary = [
+            (
+                "Category",
+                st.category.clone().unwrap_or_else(|| "All".into()),
+            ),
+            ("Status", st.status.as_str().to_string()),
+            ("Count", if st.blind { "Blind" } else { "Open" }.to_string()),
+        ];
+        let dates = [
+            ("Started", fmt_date(st.created_at)),
+            ("Posted", fmt_date(st.posted_at)),
+            ("Operator", st.operator_mdoc.to_string()),
+        ];
+        for ((label_l, value_l), (label_r, value_r)) in summary.iter().zip(dates.iter()) {
+            let layer = pg.layer_for(line_height);
+            layer.use_text(format!("{label_l}:"), 10.0, Mm(10.0), pg.current_y(), &bold);
+            layer.use_text(value_l, 10.0, Mm(35.0), pg.current_y(), &font);
+            layer.use_text(
+                format!("{label_r}:"),
+                10.0,
+                Mm(115.0),
+                pg.current_y(),
+                &bold,
+            );
+            layer.use_text(value_r, 10.0, Mm(140.0), pg.current_y(), &font);
+            pg.advance(line_height);
+        }
+        pg.advance(line_height);
+        let layer = pg.layer_for(line_height);
+        horizontal_line::draw_line(&layer, &font, pg.current_y() + Mm(4.0));
+
+        let mut matched = 0;
+        for row in &report.lines {
+            let line = &row.line;
+            if line.variance() == Some(0) {
+                matched += 1;
+                continue;
+            }
+            let layer = pg.layer_for(line_height);
+            let y = pg.current_y();
+            layer.use_text(&line.upc, 9.0, Mm(10.0), y, &font);
+            layer.use_text(
+                truncate_desc(&row.product_name, 30),
+                9.0,
+                Mm(42.0),
+                y,
+                &font,
+            );
+            layer.use_text(
+                format_number(line.expected_quantity),
+                9.0,
+                Mm(102.0),
+                y,
+                &font,
+            );
+            match (line.counted_quantity, line.variance(), row.value_impact()) {
+                (Some(counted), Some(variance), Some(value)) => {
+                    layer.use_text(format_number(counted), 9.0, Mm(122.0), y, &font);
+                    layer.use_text(format!("{variance:+}"), 9.0, Mm(142.0), y, &bold);
+                    layer.use_text(format_cents(row.price), 9.0, Mm(157.0), y, &font);
+                    layer.use_text(format_cents(value), 9.0, Mm(177.0), y, &bold);
+                }
+                _ => layer.use_text("not counted", 9.0, Mm(122.0), y, &font),
+            }
+            pg.advance(line_height);
+        }
+
+        let layer = pg.layer_for(line_height * 5.0);
+        horizontal_line::draw_line(&layer, &font, pg.current_y() + Mm(4.0));
+        let totals = [
+            (
+                "Lines counted:",
+                format!(
+                    "{} of {} ({matched} matched)",
+                    report.lines.len() - report.uncounted(),
+                    report.lines.len()
+                ),
+            ),
+            ("Shrinkage:", format_cents(report.shrink_value())),
+            ("Overage:", format_cents(report.overage_value())),
+            ("Net impact:", format_cents(report.net_value())),
+        ];
+        for (label, value) in totals {
+            layer.use_text(label, 9.0, Mm(122.0), pg.current_y(), &bold);
+            layer.use_text(value, 9.0, Mm(157.0), pg.current_y(), &font);
+            pg.advance(line_height);
+        }
+
+        pg.finalize();
+        pg.draw_page_numbers(&font);
+    }
+
+    let path = "stock_take_variance.pdf";
+    let mut f = std::io::BufWriter::new(std::fs::File::create(path)?);
+    doc.save(&mut f)?;
+    f.flush()?;
+
+    let printer = printer_name.to_string();
+    let sumatra_location = sumatra_location.to_string();
+    std::thread::spawn(move || {
+        if let Err(e) = print_pdf_silently(path, &printer, &sumatra_location) {
+            log::error!("Print failed: {e}");
+        }
+    });
+
+    Ok(())
+}
@@ -20,12 +20,13 @@ impl CategoryRepoTrait for SqliteCategoryRepo {
             .conn
             .lock()
             .map_err(|e| AppError::LockPoisoned(e.to_string()))?;
-        let mut stmt = conn.prepare("SELECT id, name, deleted FROM categories")?;
+        let mut stmt = conn.prepare("SELECT id, name, deleted, limit_exempt FROM categories")?;
         let rows = stmt.query_map([], |r| {
             Ok(Category {
                 id: r.get(0)?,
                 name: r.get(1)?,
                 deleted: r.get(2)?,
+                limit_exempt: r.get(3)?,
             })
         })?;
         rows.collect::<Result<_, _>>().map_err(Into::into)
@@ -36,13 +37,15 @@ impl CategoryRepoTrait for SqliteCategoryRepo {
             .conn
             .lock()
             .map_err(|e| AppError::LockPoisoned(e.to_string()))?;
-        let mut stmt =
-            conn.prepare("SELECT id, name, deleted FROM categories WHERE deleted IS NULL")?;
+        let mut stmt = conn.prepare(
+            "SELECT id, name, deleted, limit_exempt FROM categories WHERE deleted IS NULL",
+        )?;
         let rows = stmt.query_map([], |r| {
             Ok(Category {
                 id: r.get(0)?,
                 name: r.get(1)?,
                 deleted: r.get(2)?,
+                limit_exempt: r.get(3)?,
             })
         })?;
         rows.collect::<Result<_, _>>().map_err(Into::into)
@@ -53,13 +56,15 @@ impl CategoryRepoTrait for SqliteCategoryRepo {
             .conn
             .lock()
             .map_err(|e| AppError::LockPoisoned(e.to_string()))?;
-        let mut stmt = conn.prepare("SELECT id, name, deleted FROM categories WHERE id = ?1")?;
+        let mut stmt =
+            conn.prepare("SELECT id, name, deleted, limit_exempt FROM categories WHERE id = ?1")?;
         let mut rows = stmt.query(params![id])?;
         if let Some(r) = rows.next()? {
             Ok(Some(Category {
                 id: r.get(0)?,
                 name: r.get(1)?,
                 deleted: r.get(2)?,
+                limit_exempt: r.get(3)?,
             }))
         } else {
             Ok(None)
@@ -92,13 +97,15 @@ impl CategoryRepoTrait for SqliteCategoryRepo {
             .conn
             .lock()
             .map_err(|e| AppError::LockPoisoned(e.to_string()))?;
-        let mut stmt = conn.prepare("SELECT id, name, deleted FROM categories WHERE name = ?1")?;
+        let mut stmt =
+            conn.prepare("SELECT id, name, deleted, limit_exempt FROM categories WHERE name = ?1")?;
         let mut rows = stmt.query(params![name])?;
         if let Some(r) = rows.next()? {
             Ok(Some(Category {
                 id: r.get(0)?,
                 name: r.get(1)?,
                 deleted: r.get(2)?,
+                limit_exempt: r.get(3)?,
             }))
         } else {
             Ok(None)
@@ -116,4 +123,19 @@ impl CategoryRepoTrait for SqliteCategoryRepo {
         )?;
         Ok(())
     }
+
+    fn set_limit_exempt(&self, id: i32, limit_exempt: bool) -> Result<(), AppError> {
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|e| AppError::LockPoisoned(e.to_string()))?;
+        let changed = conn.execute(
+            "UPDATE categories SET limit_exempt = ?2 WHERE id = ?1",
+            params![id, limit_exempt],
+        )?;
+        if changed == 0 {
+            return Err(AppError::NotFound(format!("category {id} not found")));
+        }
+        Ok(())
+    }
 }
@@ -0,0 +1,275 @@
+use crate::common::error::AppError;
+use crate::common::mutex_ext::MutexExt;
+use crate::domain::models::{CustomerTier, LimitOverride};
+use crate::domain::repos::CustomerLimitRepoTrait;
+use chrono::NaiveDate;
+use rusqlite::{params, Connection, OptionalExtension};
+use std::sync::{Arc, Mutex};
+
+const OVERRIDE_COLUMNS: &str =
+    "id, customer_mdoc, limit_amount, starts_on, ends_on, reason, operator_mdoc, created_at";
+
+pub struct SqliteCustomerLimitRepo {
+    conn: Arc<Mutex<Connection>>,
+}
+
+impl SqliteCustomerLimitRepo {
+    pub const fn new(conn: Arc<Mutex<Connection>>) -> Self {
+        Self { conn }
+    }
+}
+
+fn map_tier(r: &rusqlite::Row<'_>) -> rusqlite::Result<CustomerTier> {
+    Ok(CustomerTier {
+        id: r.get(0)?,
+        name: r.get(1)?,
+        limit_amount: r.get(2)?,
+    })
+}
+
+fn map_override(r: &rusqlite::Row<'_>) -> rusqlite::Result<LimitOverride> {
+    Ok(LimitOverride {
+        id: r.get(0)?,
+        customer_mdoc: r.get(1)?,
+        limit_amount: r.get(2)?,
+        starts_on: r.get(3)?,
+        ends_on: r.get(4)?,
+        reason: r.get(5)?,
+        operator_mdoc: r.get(6)?,
+        created_at: r.get(7)?,
+    })
+}
+
+impl CustomerLimitRepoTrait for SqliteCustomerLimitRepo {
+    fn list_tiers(&self) -> Result<Vec<CustomerTier>, AppError> {
+        let conn = self.conn.safe_lock()?;
+        let mut stmt =
+            conn.prepare("SELECT id, name, limit_amount FROM customer_tiers ORDER BY name")?;
+        let rows = stmt.query_map([], map_tier)?;
+        rows.collect::<Result<_, _>>().map_err(Into::into)
+    }
+
+    fn get_tier(&self, id: i32) -> Result<Option<CustomerTier>, AppError> {
+        let conn = self.conn.safe_lock()?;
+        let tier = conn
+            .query_row(
+                "SELECT id, name, limit_amount FROM customer_tiers WHERE id = ?1",
+                params![id],
+                map_tier,
+            )
+            .optional()?;
+        Ok(tier)
+    }
+
+    fn create_tier(&self, tier: &CustomerTier) -> Result<i32, AppError> {
+        let conn = self.conn.safe_lock()?;
+        conn.execute(
+            "INSERT INTO customer_tiers (name, limit_amount) VALUES (?1, ?2)",
+            params![tier.name, tier.limit_amount],
+        )?;
+        Ok(conn.last_insert_rowid() as i32)
+    }
+
+    fn update_tier(&self, tier: &CustomerTier) -> Result<(), AppError> {
+        let conn = self.conn.safe_lock()?;
+        let changed = conn.execute(
+            "UPDATE customer_tiers SET name = ?2, limit_amount = ?3 WHERE id = ?1",
+            params![tier.id, tier.name, tier.limit_amount],
+        )?;
+        if changed == 0 {
+            return Err(AppError::NotFound(format!("tier {} not found", tier.id)));
+        }
+        Ok(())
+    }
+
+    fn delete_tier(&self, id: i32) -> Result<(), AppError> {
+        let mut conn = self.conn.safe_lock()?;
+        let tx = conn.transaction()?;
+        tx.execute(
+            "DELETE FROM customer_tier_members WHERE tier_id = ?1",
+            params![id],
+        )?;
+        tx.execute("DELETE FROM customer_tiers WHERE id = ?1", params![id])?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn get_customer_tier(&self, customer_mdoc: i32) -> Result<Option<CustomerTier>, AppError> {
+        let conn = self.conn.safe_lock()?;
+        let tier = conn
+            .query_row(
+                "SELECT t.id, t.name, t.limit_amount
+                 FROM customer_tier_members m
+                 JOIN customer_tiers t ON t.id = m.tier_id
+                 WHERE m.customer_mdoc = ?1",
+                params![customer_mdoc],
+                map_tier,
+            )
+            .optional()?;
+        Ok(tier)
+    }
+
+    fn set_customer_tier(&self, customer_mdoc: i32, tier_id: Option<i32>) -> Result<(), AppError> {
+        let conn = self.conn.safe_lock()?;
+        match tier_id {
+            Some(tier_id) => conn.execute(
+                "INSERT INTO customer_tier_members (customer_mdoc, tier_id) VALUES (?1, ?2)
+                 ON CONFLICT(customer_mdoc) DO UPDATE SET tier_id = excluded.tier_id",
+                params![customer_mdoc, tier_id],
+            )?,
+            None => conn.execute(
+                "DELETE FROM customer_tier_members WHERE customer_mdoc = ?1",
+                params![customer_mdoc],
+            )?,
+        };
+        Ok(())
+    }
+
+    fn create_override(&self, o: &LimitOverride) -> Result<i32, AppError> {
+        let conn = self.conn.safe_lock()?;
+        conn.execute(
+            "INSERT INTO customer_limit_overrides
+             (customer_mdoc, limit_amount, starts_on, ends_on, reason, operator_mdoc)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                o.customer_mdoc,
+                o.limit_amount,
+                o.starts_on,
+                o.ends_on,
+                o.reason,
+                o.operator_mdoc
+            ],
+        )?;
+        Ok(conn.last_insert_rowid() as i32)
+    }
+
+    fn list_overrides(&self, customer_mdoc: i32) -> Result<Vec<LimitOverride>, AppError> {
+        let conn = self.conn.safe_lock()?;
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {OVERRIDE_COLUMNS} FROM customer_limit_overrides
+             WHERE customer_mdoc = ?1 ORDER BY starts_on DESC, id DESC"
+        ))?;
+        let rows = stmt.query_map(params![customer_mdoc], map_override)?;
+        rows.collect::<Result<_, _>>().map_err(Into::into)
+    }
+
+    fn delete_override(&self, id: i32) -> Result<(), AppError> {
+        let conn = self.conn.safe_lock()?;
+        let changed = conn.execute(
+            "DELETE FROM customer_limit_overrides WHERE id = ?1",
+            params![id],
+        )?;
+        if changed == 0 {
+            return Err(AppError::NotFound(format!("limit override {id} not found")));
+        }
+        Ok(())
+    }
+
+    fn active_override(
+        &self,
+        customer_mdoc: i32,
+        on: NaiveDate,
+    ) -> Result<Option<LimitOverride>, AppError> {
+        let conn = self.conn.safe_lock()?;
+        let o = conn
+            .query_row(
+                &format!(
+                    "SELECT {OVERRIDE_COLUMNS} FROM customer_limit_overrides
+                     WHERE customer_mdoc = ?1
+                       AND starts_on <= ?2
+                       AND (ends_on IS NULL OR ends_on >= ?2)
+                     ORDER BY id DESC LIMIT 1"
+                ),
+                params![customer_mdoc, on],
+                map_override,
+            )
+            .optional()?;
+        Ok(o)
+    }
+}
+
+#[cfg(test)]
+mod repo_tests {
+    use super::*;
+    use crate::infrastructure::db::create_connection;
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    fn limit_override(limit_amount: i32, starts_on: &str, ends_on: Option<&str>) -> LimitOverride {
+        LimitOverride {
+            id: 0,
+            customer_mdoc: 7,
+            limit_amount,
+            starts_on: date(starts_on),
+            ends_on: ends_on.map(date),
+            reason: "medical".into(),
+            operator_mdoc: 1,
+            created_at: None,
+        }
+    }
+
+    #[test]
+    fn tiers_members_and_active_overrides() {
+        let conn = Arc::new(create_connection(":memory:").unwrap());
+        conn.lock()
+            .unwrap()
+            .execute_batch(
+                "INSERT INTO customer (mdoc, name, added, updated)
+                 VALUES (7, 'Cust', '2025-01-01 00:00:00', '2025-01-01 00:00:00');",
+            )
+            .unwrap();
+        let repo = SqliteCustomerLimitRepo::new(Arc::clone(&conn));
+
+        let honor = repo
+            .create_tier(&CustomerTier {
+                id: 0,
+                name: "Honor dorm".into(),
+                limit_amount: 30000,
+            })
+            .unwrap();
+        repo.set_customer_tier(7, Some(honor)).unwrap();
+        assert_eq!(
+            repo.get_customer_tier(7).unwrap().unwrap().limit_amount,
+            30000
+        );
+
+        // deleting the tier drops the assignment with it
+        repo.delete_tier(honor).unwrap();
+        assert!(repo.get_customer_tier(7).unwrap().is_none());
+        assert!(repo.list_tiers().unwrap().is_empty());
+
+        repo.create_override(&limit_override(5000, "2025-03-01", Some("2025-03-10")))
+            .unwrap();
+        let open = repo
+            .create_override(&limit_override(8000, "2025-03-05", None))
+            .unwrap();
+
+        assert!(repo
+            .active_override(7, date("2025-02-28"))
+            .unwrap()
+            .is_none());
+        assert_eq!(
+            repo.active_override(7, date("2025-03-02"))
+                .unwrap()
+                .unwrap()
+                .limit_amount,
+            5000
+        );
+        // overlapping: the newer override wins, ends_on is inclusive
+        assert_eq!(
+            repo.active_override(7, date("2025-03-10"))
+                .unwrap()
+                .unwrap()
+                .id,
+            open
+        );
+        repo.delete_override(open).unwrap();
+        assert!(repo
+            .active_override(7, date("2025-03-11"))
+            .unwrap()
+            .is_none());
+        assert_eq!(repo.list_overrides(7).unwrap().len(), 1);
+    }
+}
@@ -78,7 +78,7 @@ impl CustomerRepoTrait for SqliteCustomerRepo {
             ) ct ON c.mdoc = ct.mdoc
             LEFT JOIN (
               SELECT t.customer_mdoc AS mdoc,
-                     SUM(d.quantity * d.price) AS spent
+                     SUM(d.quantity * d.price - d.discount + d.tax) AS spent
               FROM customer_transactions t
               JOIN customer_tx_detail d 
                 ON t.order_id = d.order_id
@@ -188,7 +188,7 @@ impl CustomerRepoTrait for SqliteCustomerRepo {
             ) ct ON c.mdoc = ct.mdoc
             LEFT JOIN (
               SELECT t.customer_mdoc AS mdoc,
-                     SUM(d.quantity * d.price) AS spent
+                     SUM(d.quantity * d.price - d.discount + d.tax) AS spent
               FROM customer_transactions t
               JOIN customer_tx_detail d ON t.order_id = d.order_id AND d.order_id IS NOT NULL
               GROUP BY t.customer_mdoc
@@ -260,7 +260,7 @@ impl CustomerRepoTrait for SqliteCustomerRepo {
           ) ct ON c.mdoc = ct.mdoc \
           LEFT JOIN (\
             SELECT t.customer_mdoc AS mdoc, \
-                   SUM(d.quantity * d.price) AS spent \
+                   SUM(d.quantity * d.price - d.discount + d.tax) AS spent \
             FROM customer_transactions t \
             JOIN customer_tx_detail d \
               ON t.order_id = d.order_id \
@@ -1,19 +1,70 @@
 use crate::common::error::AppError;
 use crate::common::mutex_ext::MutexExt;
 use crate::domain::models::customer_tx_detail::CustomerTxDetail;
-use crate::domain::models::CustomerTransaction;
+use crate::domain::models::{CustomerTransaction, OrderReason, OrderVoid};
 use crate::domain::report_models::sales_details::SalesReportDetailRow;
 use crate::domain::report_models::sales_details::SalesReportDetails;
+use crate::domain::repos::customer_tx_repo_trait::CustomerTxSearchRow;
 use crate::domain::repos::CustomerTransactionRepoTrait;
 use chrono::{Duration, NaiveDateTime};
-use rusqlite::{params, Connection};
+use rusqlite::{params, Connection, OptionalExtension};
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
 
+// Sum of detail lines for a customer in [start, end).
+// Voided orders and their reversals are left out, and returns count against the
+// period of the original sale, so corrections give the allowance back to the period
+// the sale was made in. Lines sold as limit exempt don't count; tax counts when the
+// tax settings say so, or when there are none, as a fresh install has it.
+const SPENT_SQL: &str = "
+    SELECT COALESCE(SUM(d.quantity * d.price - d.discount
+                        + CASE WHEN COALESCE((SELECT counts_toward_limit FROM tax_settings), 1)
+                               THEN d.tax ELSE 0 END), 0)
+    FROM customer_transactions t
+    JOIN customer_tx_detail d ON t.order_id = d.order_id
+    LEFT JOIN order_returns r ON r.return_order_id = t.order_id
+    LEFT JOIN customer_transactions o ON o.order_id = r.order_id
+    WHERE t.customer_mdoc = ?1
+      AND COALESCE(o.date, t.date) >= ?2
+      AND COALESCE(o.date, t.date) < ?3
+      AND d.limit_exempt = 0
+      AND t.order_id NOT IN (SELECT order_id FROM order_voids)
+      AND t.order_id NOT IN (SELECT reversal_order_id FROM order_voids)";
+
+// Club deposits/withdrawals (withdrawals are stored negative) minus all spending.
+const BALANCE_SQL: &str = "
+    SELECT COALESCE((
+             SELECT SUM(CASE
+                          WHEN tx_type = 'Deposit'    THEN amount
+                          WHEN tx_type = 'Withdrawal' THEN amount
+                          ELSE 0
+                        END)
+             FROM club_transactions
+             WHERE mdoc = ?1
+           ), 0)
+         - COALESCE((
+             SELECT SUM(d.quantity * d.price - d.discount + d.tax)
+             FROM customer_transactions t
+             JOIN customer_tx_detail d ON t.order_id = d.order_id
+             WHERE t.customer_mdoc = ?1
+           ), 0)";
+
 pub struct SqliteCustomerTransactionRepo {
     conn: Arc<Mutex<Connection>>,
 }
 
+// reason_code column of a customer_transactions row
+fn reason_at(r: &rusqlite::Row<'_>, idx: usize) -> rusqlite::Result<OrderReason> {
+    let code: String = r.get(idx)?;
+    OrderReason::parse(&code).map_err(|e| {
+        rusqlite::Error::FromSqlConversionFailure(
+            idx,
+            rusqlite::types::Type::Text,
+            Box::new(std::io::Error::other(e.to_string())),
+        )
+    })
+}
+
 impl SqliteCustomerTransactionRepo {
     pub const fn new(conn: Arc<Mutex<Connection>>) -> Self {
         Self { conn }
@@ -26,22 +77,31 @@ impl CustomerTransactionRepoTrait for SqliteCustomerTransactionRepo {
         if tx.order_id > 0 {
             conn.execute(
                 "INSERT INTO customer_transactions
-                 (order_id, customer_mdoc, operator_mdoc, date, note)
-                 VALUES (?1,?2,?3,?4,?5)",
+                 (order_id, customer_mdoc, operator_mdoc, date, note, idempotency_key, reason_code)
+                 VALUES (?1,?2,?3,?4,?5,?6,?7)",
                 params![
                     tx.order_id,
                     tx.customer_mdoc,
                     tx.operator_mdoc,
                     tx.date,
                     tx.note,
+                    tx.idempotency_key,
+                    tx.reason.as_str(),
                 ],
             )?;
         } else {
             conn.execute(
                 "INSERT INTO customer_transactions
-                 (customer_mdoc, operator_mdoc, date, note)
-                 VALUES (?1,?2,?3,?4)",
-                params![tx.customer_mdoc, tx.operator_mdoc, tx.date, tx.note,],
+                 (customer_mdoc, operator_mdoc, date, note, idempotency_key, reason_code)
+                 VALUES (?1,?2,?3,?4,?5,?6)",
+                params![
+                    tx.customer_mdoc,
+                    tx.operator_mdoc,
+                    tx.date,
+                    tx.note,
+                    tx.idempotency_key,
+                    tx.reason.as_str(),
+                ],
             )?;
         }
         Ok(())
@@ -57,14 +117,16 @@ impl CustomerTransactionRepoTrait for SqliteCustomerTransactionRepo {
             // Caller provided a specific order_id; preserve it
             tx.execute(
                 "INSERT INTO customer_transactions
-             (order_id, customer_mdoc, operator_mdoc, date, note)
-             VALUES (?1, ?2, ?3, ?4, ?5)",
+             (order_id, customer_mdoc, operator_mdoc, date, note, idempotency_key, reason_code)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
                 rusqlite::params![
                     tx_data.order_id,
                     tx_data.customer_mdoc,
                     tx_data.operator_mdoc,
                     tx_data.date,
                     tx_data.note,
+                    tx_data.idempotency_key,
+                    tx_data.reason.as_str(),
                 ],
             )?;
             Ok(tx_data.order_id)
@@ -72,13 +134,15 @@ impl CustomerTransactionRepoTrait for SqliteCustomerTransactionRepo {
             // No order_id supplied; let SQLite autogenerate
             tx.execute(
                 "INSERT INTO customer_transactions
-             (customer_mdoc, operator_mdoc, date, note)
-             VALUES (?1, ?2, ?3, ?4)",
+             (customer_mdoc, operator_mdoc, date, note, idempotency_key, reason_code)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
                 rusqlite::params![
                     tx_data.customer_mdoc,
                     tx_data.operator_mdoc,
                     tx_data.date,
                     tx_data.note,
+                    tx_data.idempotency_key,
+                    tx_data.reason.as_str(),
                 ],
             )?;
             // Retrieve the autogenerated ID
@@ -87,10 +151,35 @@ impl CustomerTransactionRepoTrait for SqliteCustomerTransactionRepo {
         }
     }
 
+    fn find_by_idempotency_key_with_tx(
+        &self,
+        key: &str,
+        tx: &rusqlite::Transaction<'_>,
+    ) -> Result<Option<CustomerTransaction>, AppError> {
+        tx.query_row(
+            "SELECT order_id, customer_mdoc, operator_mdoc, date, note, idempotency_key, reason_code
+             FROM customer_transactions WHERE idempotency_key = ?1",
+            params![key],
+            |r| {
+                Ok(CustomerTransaction {
+                    order_id: r.get(0)?,
+                    customer_mdoc: r.get(1)?,
+                    operator_mdoc: r.get(2)?,
+                    date: r.get(3)?,
+                    note: r.get(4)?,
+                    idempotency_key: r.get(5)?,
+                    reason: reason_at(r, 6)?,
+                })
+            },
+        )
+        .optional()
+        .map_err(Into::into)
+    }
+
     fn get(&self, order_id: i32) -> Result<Option<CustomerTransaction>, AppError> {
         let conn = self.conn.safe_lock()?;
         let mut stmt = conn.prepare(
-            "SELECT order_id, customer_mdoc, operator_mdoc, date, note
+            "SELECT order_id, customer_mdoc, operator_mdoc, date, note, idempotency_key, reason_code
              FROM customer_transactions WHERE order_id = ?1",
         )?;
         let mut rows = stmt.query(params![order_id])?;
@@ -101,6 +190,8 @@ impl CustomerTransactionRepoTrait for SqliteCustomerTransactionRepo {
                 operator_mdoc: r.get(2)?,
                 date: r.get(3)?,
                 note: r.get(4)?,
+                idempotency_key: r.get(5)?,
+                reason: reason_at(r, 6)?,
             }))
         } else {
             Ok(None)
@@ -110,7 +201,7 @@ impl CustomerTransactionRepoTrait for SqliteCustomerTransactionRepo {
     fn list(&self) -> Result<Vec<CustomerTransaction>, AppError> {
         let conn = self.conn.safe_lock()?;
         let mut stmt = conn.prepare(
-            "SELECT order_id, customer_mdoc, operator_mdoc, date, note
+            "SELECT order_id, customer_mdoc, operator_mdoc, date, note, idempotency_key, reason_code
              FROM customer_transactions",
         )?;
         let txs = stmt
@@ -121,6 +212,8 @@ impl CustomerTransactionRepoTrait for SqliteCustomerTransactionRepo {
                     operator_mdoc: r.get(2)?,
                     date: r.get(3)?,
                     note: r.get(4)?,
+                    idempotency_key: r.get(5)?,
+                    reason: reason_at(r, 6)?,
                 })
             })?
             .collect::<Result<Vec<_>, _>>()?;
@@ -134,7 +227,7 @@ impl CustomerTransactionRepoTrait for SqliteCustomerTransactionRepo {
         mdoc: Option<i32>,
         date: Option<String>,
         search: Option<String>,
-    ) -> Result<Vec<(CustomerTransaction, String, i32)>, AppError> {
+    ) -> Result<Vec<CustomerTxSearchRow>, AppError> {
         let conn = self
             .conn
             .lock()
@@ -146,12 +239,15 @@ impl CustomerTransactionRepoTrait for SqliteCustomerTransactionRepo {
                    t.date,
                    t.note,
                    o.name       AS operator_name,
-                   COALESCE(d.spent,0) AS spent
+                   COALESCE(d.spent,0) AS spent,
+                   EXISTS (SELECT 1 FROM order_voids v WHERE v.order_id = t.order_id) AS voided,
+                   t.idempotency_key,
+                   t.reason_code
             FROM customer_transactions t
             JOIN operators o ON t.operator_mdoc = o.mdoc
             LEFT JOIN (
               SELECT order_id,
-                     SUM(quantity * price) AS spent
+                     SUM(quantity * price - discount + tax) AS spent
               FROM customer_tx_detail
               GROUP BY order_id
             ) d ON t.order_id = d.order_id
@@ -169,13 +265,15 @@ impl CustomerTransactionRepoTrait for SqliteCustomerTransactionRepo {
         }
         // search on multiple fields
         if let Some(ref s) = search {
-            sql.push_str(" AND (t.customer_mdoc LIKE ? OR t.operator_mdoc LIKE ? OR t.order_id LIKE ? OR t.note LIKE ?)");
+            // reason codes match as words, e.g. "special order"
+            sql.push_str(" AND (t.customer_mdoc LIKE ? OR t.operator_mdoc LIKE ? OR t.order_id LIKE ? OR t.note LIKE ? OR REPLACE(t.reason_code, '_', ' ') LIKE ?)");
             let pat = format!("%{s}%");
             string_params.push(pat);
             let p = string_params.last().ok_or_else(|| {
                 AppError::Unexpected("customer_transactions pattern missing".into())
             })?;
-            // push four times for each placeholder
+            // push five times for each placeholder
+            params.push(p);
             params.push(p);
             params.push(p);
             params.push(p);
@@ -195,10 +293,13 @@ impl CustomerTransactionRepoTrait for SqliteCustomerTransactionRepo {
                 operator_mdoc: r.get(2)?,
                 date: r.get(3)?,
                 note: r.get(4)?,
+                idempotency_key: r.get(8)?,
+                reason: reason_at(r, 9)?,
             };
             let operator_name: String = r.get(5)?;
             let spent: i64 = r.get(6)?;
-            Ok((ct, operator_name, spent as i32))
+            let voided: bool = r.get(7)?;
+            Ok((ct, operator_name, spent as i32, voided))
         })?;
         rows.collect::<Result<_, _>>().map_err(Into::into)
     }
@@ -224,7 +325,7 @@ impl CustomerTransactionRepoTrait for SqliteCustomerTransactionRepo {
             params.push(d);
         }
         if let Some(ref s) = search {
-            sql.push_str(" AND (customer_mdoc LIKE ? OR operator_mdoc LIKE ? OR order_id LIKE ? OR note LIKE ?)");
+            sql.push_str(" AND (customer_mdoc LIKE ? OR operator_mdoc LIKE ? OR order_id LIKE ? OR note LIKE ? OR REPLACE(reason_code, '_', ' ') LIKE ?)");
             let pat = format!("%{s}%");
             string_params.push(pat);
             let p = string_params.last().ok_or_else(|| {
@@ -234,6 +335,7 @@ impl CustomerTransactionRepoTrait for SqliteCustomerTransactionRepo {
             params.push(p);
             params.push(p);
             params.push(p);
+            params.push(p);
         }
 
         let mut stmt = conn.prepare(&sql)?;
@@ -252,7 +354,7 @@ impl CustomerTransactionRepoTrait for SqliteCustomerTransactionRepo {
 
         // fetch the transaction
         let tx: CustomerTransaction = conn.query_row(
-            "SELECT order_id, customer_mdoc, operator_mdoc, date, note FROM customer_transactions WHERE order_id = ?1",
+            "SELECT order_id, customer_mdoc, operator_mdoc, date, note, idempotency_key, reason_code FROM customer_transactions WHERE order_id = ?1",
             rusqlite::params![order_id],
             |row| Ok(CustomerTransaction {
                 order_id: row.get(0)?,
@@ -260,12 +362,16 @@ impl CustomerTransactionRepoTrait for SqliteCustomerTransactionRepo {
                 operator_mdoc: row.get(2)?,
                 date: row.get(3)?,
                 note: row.get(4)?,
+                idempotency_key: row.get(5)?,
+                reason: reason_at(row, 6)?,
             }),
         )?;
 
         // fetch the line items + product description
         let mut stmt = conn.prepare(
-            "SELECT d.detail_id, d.order_id, d.upc, d.quantity, d.price, p.desc
+            "SELECT d.detail_id, d.order_id, d.upc, d.quantity, d.price, p.desc, d.cost,
+                    d.discount, d.promotion_id, d.tax, d.tax_rate, d.limit_exempt,
+                    d.promotion_name
              FROM customer_tx_detail d
              JOIN products p ON p.upc = d.upc
              WHERE d.order_id = ?1",
@@ -279,7 +385,13 @@ impl CustomerTransactionRepoTrait for SqliteCustomerTransactionRepo {
                         upc: row.get(2)?,
                         quantity: row.get(3)?,
                         price: row.get(4)?,
-                        // no created_at here
+                        cost: row.get(6)?,
+                        discount: row.get(7)?,
+                        promotion_id: row.get(8)?,
+                        promotion_name: row.get(12)?,
+                        tax: row.get(9)?,
+                        tax_rate: row.get(10)?,
+                        limit_exempt: row.get(11)?,
                     },
                     row.get(5)?,
                 ))
@@ -296,7 +408,7 @@ impl CustomerTransactionRepoTrait for SqliteCustomerTransactionRepo {
               FROM club_transactions GROUP BY mdoc
             ) ct ON c.mdoc = ct.mdoc
             LEFT JOIN (
-              SELECT t.customer_mdoc AS mdoc, SUM(d.quantity*d.price) AS spent
+              SELECT t.customer_mdoc AS mdoc, SUM(d.quantity*d.price - d.discount + d.tax) AS spent
               FROM customer_transactions t
               JOIN customer_tx_detail d ON d.order_id = t.order_id
               GROUP BY t.customer_mdoc
@@ -310,29 +422,86 @@ impl CustomerTransactionRepoTrait for SqliteCustomerTransactionRepo {
         Ok((tx, details, balance))
     }
 
-    // Get total for mdoc between `week_start` (inclusive) and `week_start + 7 days` (exclusive).
-    fn get_weekly_spent(
+    // Get total for mdoc between `start` (inclusive) and `end` (exclusive).
+    fn get_spent(
         &self,
         customer_mdoc: i32,
-        week_start: NaiveDateTime,
+        start: NaiveDateTime,
+        end: NaiveDateTime,
     ) -> Result<i32, AppError> {
         let conn = self.conn.safe_lock()?;
-        let week_end = week_start + Duration::days(7);
-        let mut stmt = conn.prepare(
-            "SELECT COALESCE(SUM(d.quantity * d.price), 0)
-             FROM customer_transactions t
-             JOIN customer_tx_detail d ON t.order_id = d.order_id
-             WHERE t.customer_mdoc = ?1
-               AND t.date >= ?2
-               AND t.date < datetime(?3, '+1 day')",
-        )?;
-
         let spent: i32 =
-            stmt.query_row(params![customer_mdoc, week_start, week_end], |r| r.get(0))?;
+            conn.query_row(SPENT_SQL, params![customer_mdoc, start, end], |r| r.get(0))?;
+        Ok(spent)
+    }
 
+    // for use with atomic_tx
+    fn get_spent_with_tx(
+        &self,
+        customer_mdoc: i32,
+        start: NaiveDateTime,
+        end: NaiveDateTime,
+        tx: &rusqlite::Transaction<'_>,
+    ) -> Result<i32, AppError> {
+        let spent: i32 =
+            tx.query_row(SPENT_SQL, params![customer_mdoc, start, end], |r| r.get(0))?;
         Ok(spent)
     }
 
+    // for use with atomic_tx
+    fn get_balance_with_tx(
+        &self,
+        customer_mdoc: i32,
+        tx: &rusqlite::Transaction<'_>,
+    ) -> Result<i32, AppError> {
+        let balance: i64 = tx.query_row(BALANCE_SQL, params![customer_mdoc], |r| r.get(0))?;
+        Ok(balance as i32)
+    }
+
+    // for use with atomic_tx
+    fn create_void_with_tx(
+        &self,
+        void: &OrderVoid,
+        tx: &rusqlite::Transaction<'_>,
+    ) -> Result<(), AppError> {
+        tx.execute(
+            "INSERT INTO order_voids (order_id, reversal_order_id, operator_mdoc, reason)
+             VALUES (?1, ?2, ?3, ?4)",
+            params![
+                void.order_id,
+                void.reversal_order_id,
+                void.operator_mdoc,
+                void.reason,
+            ],
+        )?;
+        Ok(())
+    }
+
+    fn find_void_with_tx(
+        &self,
+        order_id: i32,
+        tx: &rusqlite::Transaction<'_>,
+    ) -> Result<Option<OrderVoid>, AppError> {
+        let void = tx
+            .query_row(
+                "SELECT order_id, reversal_order_id, operator_mdoc, reason, created_at
+                 FROM order_voids
+                 WHERE order_id = ?1 OR reversal_order_id = ?1",
+                params![order_id],
+                |r| {
+                    Ok(OrderVoid {
+                        order_id: r.get(0)?,
+                        reversal_order_id: r.get(1)?,
+                        operator_mdoc: r.get(2)?,
+                        reason: r.get(3)?,
+                        created_at: r.get(4)?,
+                    })
+                },
+            )
+            .optional()?;
+        Ok(void)
+    }
+
     fn get_sales_details_data(
         &self,
         start: NaiveDateTime,
@@ -350,7 +519,9 @@ impl CustomerTransactionRepoTrait for SqliteCustomerTransactionRepo {
                 t.note,
                 c.name,
                 SUM(d.quantity)    AS item_count,
-                SUM(d.quantity*d.price) AS order_total
+                SUM(d.quantity*d.price - d.discount) AS order_total,
+                t.idempotency_key,
+                t.reason_code
              FROM customer_transactions t
              JOIN customer_tx_detail d ON d.order_id = t.order_id
              JOIN customer c ON t.customer_mdoc = c.mdoc
@@ -368,6 +539,8 @@ impl CustomerTransactionRepoTrait for SqliteCustomerTransactionRepo {
                     operator_mdoc: r.get(2)?,
                     date: r.get(3)?,
                     note: r.get(4)?,
+                    idempotency_key: r.get(8)?,
+                    reason: reason_at(r, 9)?,
                 },
                 r.get::<_, String>(5)?,     // customer_name
                 r.get::<_, i64>(6)? as i32, // item_count
@@ -390,7 +563,8 @@ impl CustomerTransactionRepoTrait for SqliteCustomerTransactionRepo {
             .collect::<Vec<_>>()
             .join(", ");
         let query = format!(
-            "SELECT d.detail_id, d.order_id, d.upc, d.quantity, d.price, p.desc
+            "SELECT d.detail_id, d.order_id, d.upc, d.quantity, d.price, p.desc, d.discount,
+                    d.tax
              FROM customer_tx_detail d
              JOIN products p ON d.upc = p.upc
              WHERE d.order_id IN ({placeholders})
@@ -405,6 +579,8 @@ impl CustomerTransactionRepoTrait for SqliteCustomerTransactionRepo {
                 upc: r.get(2)?,
                 quantity: r.get(3)?,
                 price: r.get(4)?,
+                discount: r.get(6)?,
+                tax: r.get(7)?,
                 product_name: r.get(5)?,
             })
         })?;
@@ -474,6 +650,8 @@ mod repo_tests {
             operator_mdoc: 4,
             date: Some(Utc::now().naive_utc()),
             note: Some("hi".into()),
+            idempotency_key: None,
+            reason: OrderReason::Regular,
         };
         repo.create(&tx).unwrap();
         let all = repo.list().unwrap();
@@ -493,4 +671,332 @@ mod repo_tests {
         repo.create(&tx2).unwrap();
         assert!(repo.get(7).unwrap().is_some());
     }
+    #[test]
+    fn balance_and_weekly_spent_with_tx() {
+        let conn = Arc::new(create_connection(":memory:").unwrap());
+        let repo = SqliteCustomerTransactionRepo::new(Arc::clone(&conn));
+        let week_start =
+            NaiveDateTime::parse_from_str("2025-01-05 00:00:00", "%Y-%m-%d %H:%M:%S").unwrap();
+
+        let mut guard = conn.lock().unwrap();
+        // only the money math matters here, skip seeding the FK parents
+        guard.execute_batch("PRAGMA foreign_keys = OFF;").unwrap();
+        guard
+            .execute_batch(
+                "INSERT INTO club_transactions (import_id, mdoc, tx_type, amount, date)
+                   VALUES (1, 9, 'Deposit', 5000, '2025-01-01 00:00:00'),
+                          (1, 9, 'Withdrawal', -1000, '2025-01-02 00:00:00');
+                 INSERT INTO customer_transactions (order_id, customer_mdoc, operator_mdoc, date)
+                   VALUES (1, 9, 1, '2025-01-04 23:59:59'),
+                          (2, 9, 1, '2025-01-06 10:00:00'),
+                          (3, 9, 1, '2025-01-12 00:00:00');
+                 INSERT INTO customer_tx_detail (order_id, upc, quantity, price)
+                   VALUES (1, 'A', 1, 100), (2, 'A', 2, 150), (3, 'A', 1, 400);",
+            )
+            .unwrap();
+        let tx = guard.transaction().unwrap();
+
+        assert_eq!(repo.get_balance_with_tx(9, &tx).unwrap(), 5000 - 1000 - 800);
+        // the order on the following Sunday belongs to the next week
+        assert_eq!(
+            repo.get_spent_with_tx(9, week_start, week_start + Duration::days(7), &tx)
+                .unwrap(),
+            300
+        );
+    }
+
+    #[test]
+    fn spent_stops_where_the_next_period_starts() {
+        let conn = Arc::new(create_connection(":memory:").unwrap());
+        let repo = SqliteCustomerTransactionRepo::new(Arc::clone(&conn));
+        let at = |s| NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S").unwrap();
+        let week_start = at("2025-01-05 00:00:00");
+        let next_week = week_start + Duration::days(7);
+
+        {
+            let guard = conn.lock().unwrap();
+            guard.execute_batch("PRAGMA foreign_keys = OFF;").unwrap();
+            guard
+                .execute_batch(
+                    "INSERT INTO customer_transactions (order_id, customer_mdoc, operator_mdoc, date)
+                       VALUES (1, 9, 1, '2025-01-11 23:59:59'),
+                              (2, 9, 1, '2025-01-12 00:00:00'),
+                              (3, 9, 1, '2025-01-12 18:00:00');
+                     INSERT INTO customer_tx_detail (order_id, upc, quantity, price)
+                       VALUES (1, 'A', 1, 100), (2, 'A', 1, 200), (3, 'A', 1, 400);",
+                )
+                .unwrap();
+        }
+
+        // the last second of the week is in it; the whole of the next day,
+        // midnight included, belongs to the next week only
+        assert_eq!(repo.get_spent(9, week_start, next_week).unwrap(), 100);
+        assert_eq!(
+            repo.get_spent(9, next_week, next_week + Duration::days(7))
+                .unwrap(),
+            600
+        );
+        let mut guard = conn.lock().unwrap();
+        let tx = guard.transaction().unwrap();
+        assert_eq!(
+            repo.get_spent_with_tx(9, week_start, next_week, &tx)
+                .unwrap(),
+            100
+        );
+    }
+
+    #[test]
+    fn voided_orders_leave_weekly_spent_and_show_in_search() {
+        let conn = Arc::new(create_connection(":memory:").unwrap());
+        let repo = SqliteCustomerTransactionRepo::new(Arc::clone(&conn));
+        let week_start =
+            NaiveDateTime::parse_from_str("2025-01-05 00:00:00", "%Y-%m-%d %H:%M:%S").unwrap();
+
+        let mut guard = conn.lock().unwrap();
+        guard.execute_batch("PRAGMA foreign_keys = OFF;").unwrap();
+        guard
+            .execute_batch(
+                "INSERT INTO operators (mdoc, name, start) VALUES (1, 'Op', '2025-01-01');
+                 INSERT INTO customer_transactions (order_id, customer_mdoc, operator_mdoc, date)
+                   VALUES (1, 9, 1, '2025-01-06 10:00:00'),
+                          (2, 9, 1, '2025-01-06 11:00:00');
+                 INSERT INTO customer_tx_detail (order_id, upc, quantity, price)
+                   VALUES (1, 'A', 2, 150), (2, 'A', 1, 100);",
+            )
+            .unwrap();
+        let tx = guard.transaction().unwrap();
+        // order 2 is voided by order 3, recorded the following week
+        let reversal = repo
+            .create_with_tx(
+                &CustomerTransaction {
+                    order_id: 0,
+                    customer_mdoc: 9,
+                    operator_mdoc: 1,
+                    date: NaiveDateTime::parse_from_str("2025-01-13 09:00:00", "%Y-%m-%d %H:%M:%S")
+                        .ok(),
+                    note: Some("Void of order #2: typo".into()),
+                    idempotency_key: None,
+                    reason: OrderReason::Regular,
+                },
+                &tx,
+            )
+            .unwrap();
+        tx.execute(
+            "INSERT INTO customer_tx_detail (order_id, upc, quantity, price) VALUES (?1, 'A', -1, 100)",
+            params![reversal],
+        )
+        .unwrap();
+        repo.create_void_with_tx(
+            &OrderVoid {
+                order_id: 2,
+                reversal_order_id: reversal,
+                operator_mdoc: 1,
+                reason: "typo".into(),
+                created_at: None,
+            },
+            &tx,
+        )
+        .unwrap();
+        assert_eq!(
+            repo.get_spent_with_tx(9, week_start, week_start + Duration::days(7), &tx)
+                .unwrap(),
+            300
+        );
+        assert_eq!(
+            repo.get_spent_with_tx(
+                9,
+                week_start + Duration::days(7),
+                week_start + Duration::days(14),
+                &tx
+            )
+            .unwrap(),
+            0
+        );
+        assert_eq!(repo.get_balance_with_tx(9, &tx).unwrap(), -300);
+        assert_eq!(
+            repo.find_void_with_tx(reversal, &tx)
+                .unwrap()
+                .unwrap()
+                .order_id,
+            2
+        );
+        tx.commit().unwrap();
+        drop(guard);
+
+        let rows = repo.search(10, 0, Some(9), None, None).unwrap();
+        let voided: Vec<(i32, bool)> = rows.iter().map(|r| (r.0.order_id, r.3)).collect();
+        assert!(voided.contains(&(2, true)));
+        assert!(voided.contains(&(1, false)));
+        assert!(voided.contains(&(reversal, false)));
+    }
+
+    #[test]
+    fn limit_exempt_lines_stay_out_of_spent_but_not_balance() {
+        let conn = Arc::new(create_connection(":memory:").unwrap());
+        let repo = SqliteCustomerTransactionRepo::new(Arc::clone(&conn));
+        let week_start =
+            NaiveDateTime::parse_from_str("2025-01-05 00:00:00", "%Y-%m-%d %H:%M:%S").unwrap();
+        let spent = || {
+            repo.get_spent(9, week_start, week_start + Duration::days(7))
+                .unwrap()
+        };
+        conn.lock()
+            .unwrap()
+            .execute_batch(
+                "INSERT INTO categories (name, limit_exempt) VALUES ('Legal', 1), ('Snacks', 0);
+                 INSERT INTO products (upc, desc, category, price, updated, added, limit_exempt) VALUES
+                   ('PAD',   'Legal pad', 'Legal',  300, '2025-01-01 00:00:00', '2025-01-01 00:00:00', NULL),
+                   ('CHIPS', 'Chips',     'Snacks', 100, '2025-01-01 00:00:00', '2025-01-01 00:00:00', NULL),
+                   ('STAMP', 'Stamp',     'Snacks',  60, '2025-01-01 00:00:00', '2025-01-01 00:00:00', 1),
+                   ('PEN',   'Pen',       'Legal',   50, '2025-01-01 00:00:00', '2025-01-01 00:00:00', 0);
+                 INSERT INTO customer_transactions (order_id, customer_mdoc, operator_mdoc, date)
+                   VALUES (1, 9, 1, '2025-01-06 10:00:00');
+                 INSERT INTO customer_tx_detail (order_id, upc, quantity, price, limit_exempt)
+                   VALUES (1, 'PAD', 1, 300, 1), (1, 'CHIPS', 2, 100, 0), (1, 'STAMP', 5, 60, 1),
+                          (1, 'PEN', 1, 50, 0);",
+            )
+            .unwrap();
+
+        // chips and the pen, as they were flagged when sold
+        assert_eq!(spent(), 250);
+        conn.lock()
+            .unwrap()
+            .execute_batch(
+                "UPDATE products SET limit_exempt = 1 WHERE upc = 'CHIPS';
+                 UPDATE categories SET limit_exempt = 0 WHERE name = 'Legal';",
+            )
+            .unwrap();
+        assert_eq!(spent(), 250);
+        let mut guard = conn.lock().unwrap();
+        let tx = guard.transaction().unwrap();
+        assert_eq!(repo.get_balance_with_tx(9, &tx).unwrap(), -850);
+    }
+
+    #[test]
+    fn tax_counts_toward_spent_unless_turned_off() {
+        let conn = Arc::new(create_connection(":memory:").unwrap());
+        let repo = SqliteCustomerTransactionRepo::new(Arc::clone(&conn));
+        let week_start =
+            NaiveDateTime::parse_from_str("2025-01-05 00:00:00", "%Y-%m-%d %H:%M:%S").unwrap();
+        let spent = || {
+            repo.get_spent(9, week_start, week_start + Duration::days(7))
+                .unwrap()
+        };
+        let run = |sql: &str| conn.lock().unwrap().execute_batch(sql).unwrap();
+        run(
+            "INSERT INTO products (upc, desc, category, price, updated, added) VALUES
+               ('CHIPS', 'Chips', 'Snacks', 100, '2025-01-01 00:00:00', '2025-01-01 00:00:00');
+             INSERT INTO customer_transactions (order_id, customer_mdoc, operator_mdoc, date)
+               VALUES (1, 9, 1, '2025-01-06 10:00:00');
+             INSERT INTO customer_tx_detail (order_id, upc, quantity, price, tax)
+               VALUES (1, 'CHIPS', 2, 100, 12);",
+        );
+
+        assert_eq!(spent(), 212);
+        run("UPDATE tax_settings SET counts_toward_limit = 0;");
+        assert_eq!(spent(), 200);
+        // a missing settings row falls back to the default rather than
+        // dropping every line from the sum
+        run("DELETE FROM tax_settings;");
+        assert_eq!(spent(), 212);
+    }
+
+    #[test]
+    fn idempotency_key_is_unique_and_found_inside_atomic_tx() {
+        use crate::application::common::db::atomic_tx;
+
+        let conn = Arc::new(create_connection(":memory:").unwrap());
+        conn.lock()
+            .unwrap()
+            .execute_batch(
+                "INSERT INTO operators (mdoc, name, start) VALUES (1, 'Op', '2025-01-01');",
+            )
+            .unwrap();
+        let repo = SqliteCustomerTransactionRepo::new(Arc::clone(&conn));
+        let order = |key: Option<&str>| CustomerTransaction {
+            order_id: 0,
+            customer_mdoc: 9,
+            operator_mdoc: 1,
+            date: Some(Utc::now().naive_utc()),
+            note: None,
+            idempotency_key: key.map(Into::into),
+            reason: OrderReason::Regular,
+        };
+
+        let id = atomic_tx(&conn, |tx| repo.create_with_tx(&order(Some("abc")), tx)).unwrap();
+        // orders without a key are not constrained
+        repo.create(&order(None)).unwrap();
+        repo.create(&order(None)).unwrap();
+
+        let found = atomic_tx(&conn, |tx| repo.find_by_idempotency_key_with_tx("abc", tx)).unwrap();
+        assert_eq!(found.map(|t| t.order_id), Some(id));
+        assert_eq!(
+            repo.get(id).unwrap().unwrap().idempotency_key.as_deref(),
+            Some("abc")
+        );
+        assert!(
+            atomic_tx(&conn, |tx| repo.find_by_idempotency_key_with_tx("xyz", tx))
+                .unwrap()
+                .is_none()
+        );
+
+        // a second insert under the same key fails and rolls back
+        assert!(atomic_tx(&conn, |tx| repo.create_with_tx(&order(Some("abc")), tx)).is_err());
+        assert_eq!(repo.list().unwrap().len(), 3);
+    }
+
+    #[test]
+    fn reason_and_note_are_stored_and_searchable() {
+        let conn = Arc::new(create_connection(":memory:").unwrap());
+        conn.lock()
+            .unwrap()
+            .execute_batch(
+                "INSERT INTO operators (mdoc, name, start) VALUES (1, 'Op', '2025-01-01');",
+            )
+            .unwrap();
+        let repo = SqliteCustomerTransactionRepo::new(Arc::clone(&conn));
+        let order = |reason, note: Option<&str>| CustomerTransaction {
+            order_id: 0,
+            customer_mdoc: 9,
+            operator_mdoc: 1,
+            date: Some(Utc::now().naive_utc()),
+            note: note.map(Into::into),
+            idempotency_key: None,
+            reason,
+        };
+        repo.create(&order(OrderReason::IndigentKit, None)).unwrap();
+        repo.create(&order(OrderReason::SpecialOrder, Some("size 12 boots")))
+            .unwrap();
+        repo.create(&order(OrderReason::Regular, None)).unwrap();
+
+        let reasons: Vec<OrderReason> = repo.list().unwrap().iter().map(|t| t.reason).collect();
+        assert_eq!(
+            reasons,
+            [
+                OrderReason::IndigentKit,
+                OrderReason::SpecialOrder,
+                OrderReason::Regular
+            ]
+        );
+
+        let search = |s: &str| {
+            let rows = repo.search(10, 0, Some(9), None, Some(s.into())).unwrap();
+            assert_eq!(
+                repo.count(Some(9), None, Some(s.into())).unwrap() as usize,
+                rows.len()
+            );
+            rows.into_iter().map(|r| r.0.reason).collect::<Vec<_>>()
+        };
+        assert_eq!(search("indigent kit"), [OrderReason::IndigentKit]);
+        assert_eq!(search("boots"), [OrderReason::SpecialOrder]);
+        assert_eq!(search("special"), [OrderReason::SpecialOrder]);
+
+        // the schema only accepts known codes
+        let err = conn.lock().unwrap().execute(
+            "INSERT INTO customer_transactions (customer_mdoc, operator_mdoc, date, reason_code)
+             VALUES (9, 1, '2025-01-01', 'gift')",
+            [],
+        );
+        assert!(err.is_err());
+    }
 }
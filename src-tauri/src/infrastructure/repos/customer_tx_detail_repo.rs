@@ -2,7 +2,9 @@ use crate::common::error::AppError;
 use crate::common::mutex_ext::MutexExt;
 use crate::domain::models::customer_tx_detail::CustomerTxDetail;
 use crate::domain::report_models::daily_sales::DailySales;
+use crate::domain::report_models::margin_report::CategoryMargin;
 use crate::domain::report_models::product_sales::{ProductSalesByCategory, SalesTotals};
+use crate::domain::report_models::tax_summary::TaxSummaryRow;
 use crate::domain::repos::customer_tx_detail_repo_trait::CustomerTxDetailRepoTrait;
 use chrono::NaiveDateTime;
 use rusqlite::{params, Connection};
@@ -18,22 +20,96 @@ impl SqliteCustomerTxDetailRepo {
     }
 }
 
+// An order's lines in the order they were recorded, each with its product's name.
+fn list_by_order(
+    conn: &Connection,
+    order_id: i32,
+) -> Result<Vec<(CustomerTxDetail, String)>, AppError> {
+    let mut stmt = conn.prepare(
+        "SELECT d.detail_id,
+                d.order_id,
+                d.upc,
+                p.desc      AS product_name,
+                d.quantity,
+                d.price,
+                d.cost,
+                d.discount,
+                d.promotion_id,
+                d.tax,
+                d.tax_rate,
+                d.limit_exempt,
+                d.promotion_name
+         FROM customer_tx_detail d
+         JOIN products p ON d.upc = p.upc
+         WHERE d.order_id = ?1
+         ORDER BY d.detail_id",
+    )?;
+    let rows = stmt.query_map(params![order_id], |r| {
+        let raw_detail_id: i64 = r.get(0)?;
+        let raw_order_id: i64 = r.get(1)?;
+        let detail = CustomerTxDetail {
+            detail_id: raw_detail_id as i32,
+            order_id: raw_order_id as i32,
+            upc: r.get(2)?,
+            quantity: r.get(4)?,
+            price: r.get(5)?,
+            cost: r.get(6)?,
+            discount: r.get(7)?,
+            promotion_id: r.get(8)?,
+            promotion_name: r.get(12)?,
+            tax: r.get(9)?,
+            tax_rate: r.get(10)?,
+            limit_exempt: r.get(11)?,
+        };
+        let product_name: String = r.get(3)?;
+        Ok((detail, product_name))
+    })?;
+    rows.collect::<Result<_, _>>().map_err(Into::into)
+}
+
 impl CustomerTxDetailRepoTrait for SqliteCustomerTxDetailRepo {
     fn create(&self, d: &CustomerTxDetail) -> Result<(), AppError> {
         let conn = self.conn.safe_lock()?;
         if d.detail_id > 0 {
             conn.execute(
                 "INSERT INTO customer_tx_detail
-                 (detail_id, order_id, upc, quantity, price)
-                 VALUES (?1,?2,?3,?4,?5)",
-                params![d.detail_id, d.order_id, d.upc, d.quantity, d.price],
+                 (detail_id, order_id, upc, quantity, price, cost, discount, promotion_id,
+                  tax, tax_rate, limit_exempt, promotion_name)
+                 VALUES (?1,?2,?3,?4,?5,?6,?7,?8,?9,?10,?11,?12)",
+                params![
+                    d.detail_id,
+                    d.order_id,
+                    d.upc,
+                    d.quantity,
+                    d.price,
+                    d.cost,
+                    d.discount,
+                    d.promotion_id,
+                    d.tax,
+                    d.tax_rate,
+                    d.limit_exempt,
+                    d.promotion_name
+                ],
             )?;
         } else {
             conn.execute(
                 "INSERT INTO customer_tx_detail
-                 (order_id, upc, quantity, price)
-                 VALUES (?1,?2,?3,?4)",
-                params![d.order_id, d.upc, d.quantity, d.price],
+                 (order_id, upc, quantity, price, cost, discount, promotion_id, tax, tax_rate,
+                  limit_exempt, promotion_name)
+                 VALUES (?1,?2,?3,?4,?5,?6,?7,?8,?9,?10,?11)",
+                params![
+                    d.order_id,
+                    d.upc,
+                    d.quantity,
+                    d.price,
+                    d.cost,
+                    d.discount,
+                    d.promotion_id,
+                    d.tax,
+                    d.tax_rate,
+                    d.limit_exempt,
+                    d.promotion_name
+                ],
             )?;
         }
         Ok(())
@@ -48,16 +124,43 @@ impl CustomerTxDetailRepoTrait for SqliteCustomerTxDetailRepo {
         if d.detail_id > 0 {
             tx.execute(
                 "INSERT INTO customer_tx_detail
-                 (detail_id, order_id, upc, quantity, price)
-                 VALUES (?1,?2,?3,?4,?5)",
-                rusqlite::params![d.detail_id, d.order_id, d.upc, d.quantity, d.price],
+                 (detail_id, order_id, upc, quantity, price, cost, discount, promotion_id,
+                  tax, tax_rate, limit_exempt, promotion_name)
+                 VALUES (?1,?2,?3,?4,?5,?6,?7,?8,?9,?10,?11,?12)",
+                rusqlite::params![
+                    d.detail_id,
+                    d.order_id,
+                    d.upc,
+                    d.quantity,
+                    d.price,
+                    d.cost,
+                    d.discount,
+                    d.promotion_id,
+                    d.tax,
+                    d.tax_rate,
+                    d.limit_exempt,
+                    d.promotion_name
+                ],
             )?;
         } else {
             tx.execute(
                 "INSERT INTO customer_tx_detail
-                 (order_id, upc, quantity, price)
-                 VALUES (?1,?2,?3,?4)",
-                rusqlite::params![d.order_id, d.upc, d.quantity, d.price],
+                 (order_id, upc, quantity, price, cost, discount, promotion_id, tax, tax_rate,
+                  limit_exempt, promotion_name)
+                 VALUES (?1,?2,?3,?4,?5,?6,?7,?8,?9,?10,?11)",
+                rusqlite::params![
+                    d.order_id,
+                    d.upc,
+                    d.quantity,
+                    d.price,
+                    d.cost,
+                    d.discount,
+                    d.promotion_id,
+                    d.tax,
+                    d.tax_rate,
+                    d.limit_exempt,
+                    d.promotion_name
+                ],
             )?;
         }
         let detail_id = tx.last_insert_rowid() as i32;
@@ -66,32 +169,16 @@ impl CustomerTxDetailRepoTrait for SqliteCustomerTxDetailRepo {
 
     fn list_by_order(&self, order_id: i32) -> Result<Vec<(CustomerTxDetail, String)>, AppError> {
         let conn = self.conn.safe_lock()?;
-        // join to grab product_name
-        let mut stmt = conn.prepare(
-            "SELECT d.detail_id,
-                    d.order_id,
-                    d.upc,
-                    p.desc      AS product_name,
-                    d.quantity,
-                    d.price
-             FROM customer_tx_detail d
-             JOIN products p ON d.upc = p.upc
-             WHERE d.order_id = ?1",
-        )?;
-        let rows = stmt.query_map(params![order_id], |r| {
-            let raw_detail_id: i64 = r.get(0)?;
-            let raw_order_id: i64 = r.get(1)?;
-            let detail = CustomerTxDetail {
-                detail_id: raw_detail_id as i32,
-                order_id: raw_order_id as i32,
-                upc: r.get(2)?,
-                quantity: r.get(4)?,
-                price: r.get(5)?,
-            };
-            let product_name: String = r.get(3)?;
-            Ok((detail, product_name))
-        })?;
-        rows.collect::<Result<_, _>>().map_err(Into::into)
+        list_by_order(&conn, order_id)
+    }
+
+    // for use with atomic_tx
+    fn list_by_order_with_tx(
+        &self,
+        order_id: i32,
+        tx: &rusqlite::Transaction<'_>,
+    ) -> Result<Vec<(CustomerTxDetail, String)>, AppError> {
+        list_by_order(tx, order_id)
     }
 
     fn sales_by_category(
@@ -109,7 +196,9 @@ impl CustomerTxDetailRepoTrait for SqliteCustomerTxDetailRepo {
             p.desc    AS name,
             d.price,
             SUM(d.quantity)           AS quantity_sold,
-            SUM(d.quantity * d.price) AS total_sales,
+            SUM(d.quantity * d.price) AS gross_sales,
+            SUM(d.discount)           AS discounts,
+            SUM(d.quantity * d.price - d.discount) AS total_sales,
             0                          AS is_summary
           FROM customer_tx_detail d
           JOIN customer_transactions t
@@ -126,6 +215,8 @@ impl CustomerTxDetailRepoTrait for SqliteCustomerTxDetailRepo {
             'Category Total' AS name,
             0      AS price,
             SUM(quantity_sold)   AS quantity_sold,
+            SUM(gross_sales)     AS gross_sales,
+            SUM(discounts)       AS discounts,
             SUM(total_sales)     AS total_sales,
             1             AS is_summary
           FROM detail
@@ -137,6 +228,8 @@ impl CustomerTxDetailRepoTrait for SqliteCustomerTxDetailRepo {
             'Grand Total'   AS name,
             0            AS price,
             SUM(quantity_sold)   AS quantity_sold,
+            SUM(gross_sales)     AS gross_sales,
+            SUM(discounts)       AS discounts,
             SUM(total_sales)     AS total_sales,
             2             AS is_summary
           FROM detail
@@ -146,7 +239,7 @@ impl CustomerTxDetailRepoTrait for SqliteCustomerTxDetailRepo {
         SELECT *      FROM category_total
         UNION ALL
         SELECT *      FROM grand_total
-        ORDER BY 1, 7, 3
+        ORDER BY 1, 9, 3
         "#;
 
         let mut stmt = conn.prepare(sql)?;
@@ -157,6 +250,8 @@ impl CustomerTxDetailRepoTrait for SqliteCustomerTxDetailRepo {
                 name: r.get("name")?,
                 price: r.get::<_, i64>("price")? as i32,
                 quantity_sold: r.get::<_, i64>("quantity_sold")? as i32,
+                gross_sales: r.get::<_, i64>("gross_sales")? as i32,
+                discounts: r.get::<_, i64>("discounts")? as i32,
                 total_sales: r.get::<_, i64>("total_sales")? as i32,
                 is_summary: r.get::<_, i64>("is_summary")? != 0,
             })
@@ -178,7 +273,9 @@ impl CustomerTxDetailRepoTrait for SqliteCustomerTxDetailRepo {
         let sql = r#"
             SELECT
               COALESCE(SUM(d.quantity), 0)             AS total_quantity,
-              COALESCE(SUM(d.quantity * d.price), 0)   AS total_value
+              COALESCE(SUM(d.quantity * d.price - d.discount), 0) AS total_value,
+              COALESCE(SUM(d.discount), 0)             AS total_discount,
+              COALESCE(SUM(d.tax), 0)                  AS total_tax
             FROM customer_tx_detail d
             JOIN customer_transactions t
               ON d.order_id = t.order_id
@@ -190,6 +287,8 @@ impl CustomerTxDetailRepoTrait for SqliteCustomerTxDetailRepo {
             Ok(SalesTotals {
                 total_quantity: r.get::<_, i64>(0)? as i32,
                 total_value: r.get::<_, i64>(1)? as i32,
+                total_discount: r.get::<_, i64>(2)? as i32,
+                total_tax: r.get::<_, i64>(3)? as i32,
             })
         })?;
 
@@ -205,7 +304,7 @@ impl CustomerTxDetailRepoTrait for SqliteCustomerTxDetailRepo {
         let sql = r#"
             SELECT
                 t.date                   AS full_ts,
-                COALESCE(SUM(d.quantity * d.price), 0) AS total_sales
+                COALESCE(SUM(d.quantity * d.price - d.discount), 0) AS total_sales
             FROM customer_tx_detail d
             JOIN customer_transactions t
               ON d.order_id = t.order_id
@@ -232,6 +331,76 @@ impl CustomerTxDetailRepoTrait for SqliteCustomerTxDetailRepo {
         }
         Ok(out)
     }
+
+    fn margin_by_category(
+        &self,
+        start: NaiveDateTime,
+        end: NaiveDateTime,
+    ) -> Result<Vec<CategoryMargin>, AppError> {
+        let conn = self.conn.safe_lock()?;
+        // reversal and return lines are negative, so they net out on their own
+        let sql = r#"
+            SELECT
+              p.category,
+              SUM(d.quantity)                                              AS quantity_sold,
+              SUM(d.quantity * d.price - d.discount)                       AS revenue,
+              SUM(CASE WHEN d.cost IS NULL THEN 0 ELSE d.quantity * d.price - d.discount END) AS costed_revenue,
+              SUM(COALESCE(d.quantity * d.cost, 0))                        AS cogs
+            FROM customer_tx_detail d
+            JOIN customer_transactions t
+              ON d.order_id = t.order_id
+             AND t.date >= ?1
+             AND t.date < datetime(?2, '+1 day')
+            JOIN products p
+              ON d.upc = p.upc
+            GROUP BY p.category
+            ORDER BY p.category
+        "#;
+
+        let mut stmt = conn.prepare(sql)?;
+        let rows = stmt.query_map(params![start, end], |r| {
+            Ok(CategoryMargin {
+                category: r.get("category")?,
+                quantity_sold: r.get::<_, i64>("quantity_sold")? as i32,
+                revenue: r.get::<_, i64>("revenue")? as i32,
+                costed_revenue: r.get::<_, i64>("costed_revenue")? as i32,
+                cogs: r.get::<_, i64>("cogs")? as i32,
+            })
+        })?;
+        rows.collect::<Result<_, _>>().map_err(Into::into)
+    }
+
+    fn tax_summary(
+        &self,
+        start: NaiveDateTime,
+        end: NaiveDateTime,
+    ) -> Result<Vec<TaxSummaryRow>, AppError> {
+        let conn = self.conn.safe_lock()?;
+        let sql = r#"
+            SELECT
+              d.tax_rate,
+              SUM(d.quantity * d.price - d.discount) AS taxable_sales,
+              SUM(d.tax)                             AS tax
+            FROM customer_tx_detail d
+            JOIN customer_transactions t
+              ON d.order_id = t.order_id
+             AND t.date >= ?1
+             AND t.date < datetime(?2, '+1 day')
+            WHERE d.tax_rate IS NOT NULL
+            GROUP BY d.tax_rate
+            ORDER BY d.tax_rate
+        "#;
+
+        let mut stmt = conn.prepare(sql)?;
+        let rows = stmt.query_map(params![start, end], |r| {
+            Ok(TaxSummaryRow {
+                rate: r.get(0)?,
+                taxable_sales: r.get::<_, i64>(1)? as i32,
+                tax: r.get::<_, i64>(2)? as i32,
+            })
+        })?;
+        rows.collect::<Result<_, _>>().map_err(Into::into)
+    }
 }
 
 #[cfg(test)]
@@ -284,6 +453,13 @@ mod repo_tests {
             upc: "00000001".into(),
             quantity: 2,
             price: 150,
+            cost: None,
+            discount: 0,
+            promotion_id: None,
+            promotion_name: None,
+            tax: 0,
+            tax_rate: None,
+            limit_exempt: false,
         };
         repo.create(&d1).unwrap();
 
@@ -300,4 +476,98 @@ mod repo_tests {
         let list2 = repo.list_by_order(100).unwrap();
         assert!(list2.iter().any(|(d, _)| d.detail_id == 7));
     }
+
+    #[test]
+    fn margin_by_category_leaves_uncosted_lines_out_of_cogs() {
+        let conn = Arc::new(create_connection(":memory:").unwrap());
+        conn.lock()
+            .unwrap()
+            .execute_batch(
+                "INSERT INTO products (upc, desc, category, price, updated, added) VALUES
+                   ('SOAP', 'Soap', 'Hygiene', 100, '2025-01-01 00:00:00', '2025-01-01 00:00:00'),
+                   ('CHIP', 'Chips', 'Snacks', 150, '2025-01-01 00:00:00', '2025-01-01 00:00:00');
+                 INSERT INTO customer_transactions (order_id, customer_mdoc, operator_mdoc, date) VALUES
+                   (1, 7, 1, '2025-03-03 10:00:00'),
+                   (2, 7, 1, '2025-03-04 10:00:00'),
+                   (3, 7, 1, '2025-04-01 10:00:00');
+                 INSERT INTO customer_tx_detail (order_id, upc, quantity, price, cost) VALUES
+                   (1, 'SOAP', 4, 100, 60),
+                   (1, 'CHIP', 2, 150, NULL),
+                   (2, 'SOAP', -1, 100, 60),
+                   (2, 'CHIP', 3, 150, 90),
+                   (3, 'SOAP', 9, 100, 60);",
+            )
+            .unwrap();
+        let repo = SqliteCustomerTxDetailRepo::new(conn);
+        let start =
+            NaiveDateTime::parse_from_str("2025-03-01 00:00:00", "%Y-%m-%d %H:%M:%S").unwrap();
+        let end =
+            NaiveDateTime::parse_from_str("2025-03-31 00:00:00", "%Y-%m-%d %H:%M:%S").unwrap();
+
+        let rows = repo.margin_by_category(start, end).unwrap();
+        assert_eq!(rows.len(), 2);
+        let hygiene = &rows[0];
+        assert_eq!(hygiene.category, "Hygiene");
+        assert_eq!(
+            (hygiene.quantity_sold, hygiene.revenue, hygiene.cogs),
+            (3, 300, 180)
+        );
+        assert_eq!(hygiene.margin_pct(), Some(40.0));
+
+        // the chips sold before they had a cost stay in revenue only
+        let snacks = &rows[1];
+        assert_eq!(
+            (snacks.revenue, snacks.costed_revenue, snacks.cogs),
+            (750, 450, 270)
+        );
+        assert_eq!(snacks.uncosted_revenue(), 300);
+        assert_eq!(snacks.margin(), 180);
+        assert_eq!(CategoryMargin::total(&rows).cogs, 450);
+    }
+
+    #[test]
+    fn tax_summary_groups_by_rate_and_nets_out_returns() {
+        let conn = Arc::new(create_connection(":memory:").unwrap());
+        conn.lock()
+            .unwrap()
+            .execute_batch(
+                "INSERT INTO products (upc, desc, category, price, updated, added) VALUES
+                   ('SOAP', 'Soap', 'Hygiene', 100, '2025-01-01 00:00:00', '2025-01-01 00:00:00'),
+                   ('CHIP', 'Chips', 'Snacks', 150, '2025-01-01 00:00:00', '2025-01-01 00:00:00');
+                 INSERT INTO customer_transactions (order_id, customer_mdoc, operator_mdoc, date) VALUES
+                   (1, 7, 1, '2025-03-03 10:00:00'),
+                   (2, 7, 1, '2025-03-04 10:00:00');
+                 INSERT INTO customer_tx_detail
+                   (order_id, upc, quantity, price, cost, discount, tax, tax_rate) VALUES
+                   (1, 'SOAP', 4, 100, NULL, 0, 0, NULL),
+                   (1, 'CHIP', 2, 150, NULL, 30, 16, 600),
+                   (2, 'CHIP', -1, 150, NULL, -15, -8, 600),
+                   (2, 'CHIP', 1, 150, NULL, 0, 12, 825);",
+            )
+            .unwrap();
+        let repo = SqliteCustomerTxDetailRepo::new(conn);
+        let start =
+            NaiveDateTime::parse_from_str("2025-03-01 00:00:00", "%Y-%m-%d %H:%M:%S").unwrap();
+        let end =
+            NaiveDateTime::parse_from_str("2025-03-31 00:00:00", "%Y-%m-%d %H:%M:%S").unwrap();
+
+        let rows = repo.tax_summary(start, end).unwrap();
+        assert_eq!(
+            rows,
+            vec![
+                TaxSummaryRow {
+                    rate: 600,
+                    taxable_sales: 135,
+                    tax: 8
+                },
+                TaxSummaryRow {
+                    rate: 825,
+                    taxable_sales: 150,
+                    tax: 12
+                },
+            ]
+        );
+        let totals = repo.get_sales_totals(start, end).unwrap();
+        assert_eq!((totals.total_value, totals.total_tax), (685, 20));
+    }
 }
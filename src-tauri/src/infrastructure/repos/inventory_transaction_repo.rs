@@ -1,14 +1,62 @@
 use crate::common::error::AppError;
 use crate::common::mutex_ext::MutexExt;
-use crate::domain::models::InventoryTransaction;
+use crate::domain::models::{
+    InventoryLot, InventoryReason, InventoryTransaction, LotLabel, NegativeStockPolicy,
+};
+use crate::domain::report_models::expiry_report::ExpiringLot;
+use crate::domain::report_models::shrinkage_report::ShrinkageLine;
 use crate::domain::repos::InventoryTransactionRepoTrait;
-use rusqlite::{params, Connection};
+use chrono::{NaiveDate, NaiveDateTime};
+use rusqlite::{params, Connection, OptionalExtension};
 use std::sync::{Arc, Mutex};
 
 pub struct SqliteInventoryTransactionRepo {
     pub conn: Arc<Mutex<Connection>>,
 }
 
+// reason column of an inventory_transactions row
+fn reason_at(r: &rusqlite::Row<'_>, idx: usize) -> rusqlite::Result<InventoryReason> {
+    let code: String = r.get(idx)?;
+    InventoryReason::parse(&code).map_err(|e| {
+        rusqlite::Error::FromSqlConversionFailure(
+            idx,
+            rusqlite::types::Type::Text,
+            Box::new(std::io::Error::other(e.to_string())),
+        )
+    })
+}
+
+// A lot with its remaining stock, summed from the rows that carry its id.
+const LOT_SQL: &str = "
+    SELECT l.id, l.upc, l.lot_number, l.expires_on,
+           COALESCE(SUM(t.quantity_change), 0) AS on_hand, l.created_at
+    FROM inventory_lots l
+    LEFT JOIN inventory_transactions t ON t.lot_id = l.id";
+
+fn map_lot(r: &rusqlite::Row<'_>) -> rusqlite::Result<InventoryLot> {
+    Ok(InventoryLot {
+        id: r.get(0)?,
+        upc: r.get(1)?,
+        label: LotLabel {
+            lot_number: r.get(2)?,
+            expires_on: r.get(3)?,
+        },
+        on_hand: r.get::<_, i64>(4)? as i32,
+        created_at: r.get(5)?,
+    })
+}
+
+fn map_policy(r: &rusqlite::Row<'_>) -> rusqlite::Result<NegativeStockPolicy> {
+    let code: String = r.get(0)?;
+    NegativeStockPolicy::parse(&code).map_err(|e| {
+        rusqlite::Error::FromSqlConversionFailure(
+            0,
+            rusqlite::types::Type::Text,
+            Box::new(std::io::Error::other(e.to_string())),
+        )
+    })
+}
+
 impl SqliteInventoryTransactionRepo {
     pub const fn new(conn: Arc<Mutex<Connection>>) -> Self {
         Self { conn }
@@ -19,7 +67,7 @@ impl InventoryTransactionRepoTrait for SqliteInventoryTransactionRepo {
     fn get_by_id(&self, id: i32) -> Result<Option<InventoryTransaction>, AppError> {
         let conn = self.conn.safe_lock()?;
         let mut stmt = conn.prepare(
-            "SELECT id, upc, quantity_change, operator_mdoc, customer_mdoc, ref_order_id, reference, created_at \
+            "SELECT id, upc, quantity_change, operator_mdoc, customer_mdoc, ref_order_id, reference, created_at, po_id, unit_cost, stock_take_id, reason, lot_id \
           FROM inventory_transactions WHERE customer_mdoc = ?1",
         )?;
         let mut rows = stmt.query(params![id])?;
@@ -33,6 +81,11 @@ impl InventoryTransactionRepoTrait for SqliteInventoryTransactionRepo {
                 ref_order_id: r.get(5)?,
                 reference: r.get(6)?,
                 created_at: r.get(7)?,
+                po_id: r.get(8)?,
+                unit_cost: r.get(9)?,
+                stock_take_id: r.get(10)?,
+                reason: reason_at(r, 11)?,
+                lot_id: r.get(12)?,
             }))
         } else {
             Ok(None)
@@ -43,15 +96,20 @@ impl InventoryTransactionRepoTrait for SqliteInventoryTransactionRepo {
         let conn = self.conn.safe_lock()?;
         conn.execute(
             "INSERT INTO inventory_transactions \
-         (upc, quantity_change, operator_mdoc, customer_mdoc, ref_order_id, reference) \
-         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+         (upc, quantity_change, operator_mdoc, customer_mdoc, ref_order_id, reference, po_id, unit_cost, stock_take_id, reason, lot_id) \
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
             params![
                 a.upc,
                 a.quantity_change,
                 a.operator_mdoc,
                 a.customer_mdoc,
                 a.ref_order_id,
-                a.reference
+                a.reference,
+                a.po_id,
+                a.unit_cost,
+                a.stock_take_id,
+                a.reason.as_str(),
+                a.lot_id
             ],
         )?;
         Ok(())
@@ -65,26 +123,46 @@ impl InventoryTransactionRepoTrait for SqliteInventoryTransactionRepo {
     ) -> Result<(), AppError> {
         tx.execute(
             "INSERT INTO inventory_transactions \
-     (upc, quantity_change, operator_mdoc, customer_mdoc, ref_order_id, reference) \
-     VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+     (upc, quantity_change, operator_mdoc, customer_mdoc, ref_order_id, reference, po_id, unit_cost, stock_take_id, reason, lot_id) \
+     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
             rusqlite::params![
                 a.upc,
                 a.quantity_change,
                 a.operator_mdoc,
                 a.customer_mdoc,
                 a.ref_order_id,
-                a.reference
+                a.reference,
+                a.po_id,
+                a.unit_cost,
+                a.stock_take_id,
+                a.reason.as_str(),
+                a.lot_id
             ],
         )?;
         Ok(())
     }
 
+    // for use with atomic_tx
+    fn get_available_with_tx(
+        &self,
+        upc: String,
+        tx: &rusqlite::Transaction<'_>,
+    ) -> Result<i32, AppError> {
+        let available: i64 = tx.query_row(
+            "SELECT COALESCE(SUM(quantity_change), 0) FROM inventory_transactions WHERE upc = ?1",
+            params![upc],
+            |r| r.get(0),
+        )?;
+        Ok(available as i32)
+    }
+
     fn search(
         &self,
         limit: i32,
         offset: i32,
         date: Option<String>,
         search: Option<String>,
+        reason: Option<InventoryReason>,
     ) -> Result<Vec<(InventoryTransaction, String, String)>, AppError> {
         let conn = self
             .conn
@@ -100,7 +178,12 @@ impl InventoryTransactionRepoTrait for SqliteInventoryTransactionRepo {
                    tx.reference,
                    tx.created_at,
                    p.desc AS product_name,
-                   o.name AS operator_name
+                   o.name AS operator_name,
+                   tx.po_id,
+                   tx.unit_cost,
+                   tx.stock_take_id,
+                   tx.reason,
+                   tx.lot_id
             FROM inventory_transactions tx
             JOIN products  p ON tx.upc           = p.upc
             JOIN operators o ON tx.operator_mdoc = o.mdoc
@@ -116,6 +199,11 @@ impl InventoryTransactionRepoTrait for SqliteInventoryTransactionRepo {
             sql.push_str(" AND date(tx.created_at)=date(?)");
             params.push(d);
         }
+        let reason_code = reason.map(InventoryReason::as_str);
+        if let Some(ref r) = reason_code {
+            sql.push_str(" AND tx.reason = ?");
+            params.push(r);
+        }
         // multi‑field text search
         if let Some(ref s) = search {
             sql.push_str(
@@ -153,6 +241,11 @@ impl InventoryTransactionRepoTrait for SqliteInventoryTransactionRepo {
                 ref_order_id: r.get(5)?,
                 reference: r.get(6)?,
                 created_at: r.get(7)?,
+                po_id: r.get(10)?,
+                unit_cost: r.get(11)?,
+                stock_take_id: r.get(12)?,
+                reason: reason_at(r, 13)?,
+                lot_id: r.get(14)?,
             };
             let product_name: String = r.get(8)?;
             let operator_name: String = r.get(9)?;
@@ -161,7 +254,12 @@ impl InventoryTransactionRepoTrait for SqliteInventoryTransactionRepo {
         rows.collect::<Result<Vec<_>, _>>().map_err(Into::into)
     }
 
-    fn count(&self, date: Option<String>, search: Option<String>) -> Result<i32, AppError> {
+    fn count(
+        &self,
+        date: Option<String>,
+        search: Option<String>,
+        reason: Option<InventoryReason>,
+    ) -> Result<i32, AppError> {
         let conn = self
             .conn
             .lock()
@@ -178,6 +276,11 @@ impl InventoryTransactionRepoTrait for SqliteInventoryTransactionRepo {
             sql.push_str(" AND date(tx.created_at)=date(?)");
             params.push(d);
         }
+        let reason_code = reason.map(InventoryReason::as_str);
+        if let Some(ref r) = reason_code {
+            sql.push_str(" AND tx.reason = ?");
+            params.push(r);
+        }
         if let Some(ref s) = search {
             sql.push_str(" AND (upc LIKE ? OR operator_mdoc LIKE ?)");
             let pat = format!("%{s}%");
@@ -199,7 +302,7 @@ impl InventoryTransactionRepoTrait for SqliteInventoryTransactionRepo {
     fn list_for_customer(&self, customer_mdoc: i32) -> Result<Vec<InventoryTransaction>, AppError> {
         let conn = self.conn.safe_lock()?;
         let mut stmt = conn
-            .prepare(         "SELECT id, upc, quantity_change, operator_mdoc, customer_mdoc, ref_order_id, reference, created_at \
+            .prepare(         "SELECT id, upc, quantity_change, operator_mdoc, customer_mdoc, ref_order_id, reference, created_at, po_id, unit_cost, stock_take_id, reason, lot_id \
           FROM inventory_transactions WHERE customer_mdoc = ?1")?;
         let adjustments = stmt
             .query_map(params![customer_mdoc], |r| {
@@ -212,6 +315,11 @@ impl InventoryTransactionRepoTrait for SqliteInventoryTransactionRepo {
                     ref_order_id: r.get(5)?,
                     reference: r.get(6)?,
                     created_at: r.get(7)?,
+                    po_id: r.get(8)?,
+                    unit_cost: r.get(9)?,
+                    stock_take_id: r.get(10)?,
+                    reason: reason_at(r, 11)?,
+                    lot_id: r.get(12)?,
                 })
             })?
             .collect::<Result<Vec<_>, _>>()?;
@@ -221,7 +329,7 @@ impl InventoryTransactionRepoTrait for SqliteInventoryTransactionRepo {
     fn list(&self) -> Result<Vec<InventoryTransaction>, AppError> {
         let conn = self.conn.safe_lock()?;
         let mut stmt = conn.prepare(
-            "SELECT id, upc, quantity_change, operator_mdoc, customer_mdoc, ref_order_id, reference, created_at \
+            "SELECT id, upc, quantity_change, operator_mdoc, customer_mdoc, ref_order_id, reference, created_at, po_id, unit_cost, stock_take_id, reason, lot_id \
              FROM inventory_transactions"
         )?;
         let items = stmt
@@ -235,6 +343,11 @@ impl InventoryTransactionRepoTrait for SqliteInventoryTransactionRepo {
                     ref_order_id: r.get(5)?,
                     reference: r.get(6)?,
                     created_at: r.get(7)?,
+                    po_id: r.get(8)?,
+                    unit_cost: r.get(9)?,
+                    stock_take_id: r.get(10)?,
+                    reason: reason_at(r, 11)?,
+                    lot_id: r.get(12)?,
                 })
             })?
             .collect::<Result<Vec<_>, _>>()?;
@@ -244,7 +357,7 @@ impl InventoryTransactionRepoTrait for SqliteInventoryTransactionRepo {
     fn list_for_product(&self, upc: String) -> Result<Vec<InventoryTransaction>, AppError> {
         let conn = self.conn.safe_lock()?;
         let mut stmt = conn.prepare(
-            "SELECT id, upc, quantity_change, operator_mdoc, customer_mdoc, ref_order_id, reference, created_at \
+            "SELECT id, upc, quantity_change, operator_mdoc, customer_mdoc, ref_order_id, reference, created_at, po_id, unit_cost, stock_take_id, reason, lot_id \
              FROM inventory_transactions WHERE upc = ?1"
         )?;
         let mapped = stmt.query_map(params![upc], |r| {
@@ -257,6 +370,38 @@ impl InventoryTransactionRepoTrait for SqliteInventoryTransactionRepo {
                 ref_order_id: r.get(5)?,
                 reference: r.get(6)?,
                 created_at: r.get(7)?,
+                po_id: r.get(8)?,
+                unit_cost: r.get(9)?,
+                stock_take_id: r.get(10)?,
+                reason: reason_at(r, 11)?,
+                lot_id: r.get(12)?,
+            })
+        })?;
+        let collected = mapped.collect::<Result<Vec<_>, _>>()?;
+        Ok(collected)
+    }
+
+    fn list_for_order(&self, order_id: i32) -> Result<Vec<InventoryTransaction>, AppError> {
+        let conn = self.conn.safe_lock()?;
+        let mut stmt = conn.prepare(
+            "SELECT id, upc, quantity_change, operator_mdoc, customer_mdoc, ref_order_id, reference, created_at, po_id, unit_cost, stock_take_id, reason, lot_id \
+             FROM inventory_transactions WHERE ref_order_id = ?1"
+        )?;
+        let mapped = stmt.query_map(params![order_id], |r| {
+            Ok(InventoryTransaction {
+                id: r.get(0)?,
+                upc: r.get(1)?,
+                quantity_change: r.get(2)?,
+                operator_mdoc: r.get(3)?,
+                customer_mdoc: r.get(4)?,
+                ref_order_id: r.get(5)?,
+                reference: r.get(6)?,
+                created_at: r.get(7)?,
+                po_id: r.get(8)?,
+                unit_cost: r.get(9)?,
+                stock_take_id: r.get(10)?,
+                reason: reason_at(r, 11)?,
+                lot_id: r.get(12)?,
             })
         })?;
         let collected = mapped.collect::<Result<Vec<_>, _>>()?;
@@ -266,7 +411,7 @@ impl InventoryTransactionRepoTrait for SqliteInventoryTransactionRepo {
     fn list_for_operator(&self, operator_mdoc: i32) -> Result<Vec<InventoryTransaction>, AppError> {
         let conn = self.conn.safe_lock()?;
         let mut stmt = conn.prepare(
-         "SELECT id, upc, quantity_change, operator_mdoc, customer_mdoc, ref_order_id, reference, created_at \
+         "SELECT id, upc, quantity_change, operator_mdoc, customer_mdoc, ref_order_id, reference, created_at, po_id, unit_cost, stock_take_id, reason, lot_id \
           FROM inventory_transactions WHERE operator_mdoc = ?1"
         )?;
         let mapped = stmt.query_map(params![operator_mdoc], |r| {
@@ -279,6 +424,11 @@ impl InventoryTransactionRepoTrait for SqliteInventoryTransactionRepo {
                 ref_order_id: r.get(5)?,
                 reference: r.get(6)?,
                 created_at: r.get(7)?,
+                po_id: r.get(8)?,
+                unit_cost: r.get(9)?,
+                stock_take_id: r.get(10)?,
+                reason: reason_at(r, 11)?,
+                lot_id: r.get(12)?,
             })
         })?;
         let collected = mapped.collect::<Result<Vec<_>, _>>()?;
@@ -288,7 +438,7 @@ impl InventoryTransactionRepoTrait for SqliteInventoryTransactionRepo {
     fn list_for_today(&self) -> Result<Vec<InventoryTransaction>, AppError> {
         let conn = self.conn.safe_lock()?;
         let mut stmt = conn.prepare(
-            "SELECT id, upc, quantity_change, operator_mdoc, customer_mdoc, ref_order_id, reference, created_at \
+            "SELECT id, upc, quantity_change, operator_mdoc, customer_mdoc, ref_order_id, reference, created_at, po_id, unit_cost, stock_take_id, reason, lot_id \
              FROM inventory_transactions WHERE date(created_at) = date('now')"
         )?;
         let mapped = stmt.query_map([], |r| {
@@ -301,9 +451,307 @@ impl InventoryTransactionRepoTrait for SqliteInventoryTransactionRepo {
                 ref_order_id: r.get(5)?,
                 reference: r.get(6)?,
                 created_at: r.get(7)?,
+                po_id: r.get(8)?,
+                unit_cost: r.get(9)?,
+                stock_take_id: r.get(10)?,
+                reason: reason_at(r, 11)?,
+                lot_id: r.get(12)?,
             })
         })?;
         let collected = mapped.collect::<Result<Vec<_>, _>>()?;
         Ok(collected)
     }
+
+    fn negative_stock_policy(&self) -> Result<NegativeStockPolicy, AppError> {
+        let conn = self.conn.safe_lock()?;
+        conn.query_row(
+            "SELECT negative_stock_policy FROM inventory_settings",
+            [],
+            map_policy,
+        )
+        .map_err(Into::into)
+    }
+
+    fn set_negative_stock_policy(&self, policy: NegativeStockPolicy) -> Result<(), AppError> {
+        let conn = self.conn.safe_lock()?;
+        conn.execute(
+            "UPDATE inventory_settings SET negative_stock_policy = ?1",
+            params![policy.as_str()],
+        )?;
+        Ok(())
+    }
+
+    // for use with atomic_tx
+    fn negative_stock_policy_with_tx(
+        &self,
+        tx: &rusqlite::Transaction<'_>,
+    ) -> Result<NegativeStockPolicy, AppError> {
+        tx.query_row(
+            "SELECT negative_stock_policy FROM inventory_settings",
+            [],
+            map_policy,
+        )
+        .map_err(Into::into)
+    }
+
+    fn shrinkage(
+        &self,
+        start: NaiveDateTime,
+        end: NaiveDateTime,
+    ) -> Result<Vec<ShrinkageLine>, AppError> {
+        let conn = self.conn.safe_lock()?;
+        // sales, voids and returns carry a customer; receipts never take stock out
+        let sql = r#"
+            SELECT
+              tx.reason,
+              p.category,
+              -SUM(tx.quantity_change)                                           AS units,
+              -SUM(tx.quantity_change * p.price)                                 AS retail_value,
+              -SUM(COALESCE(tx.quantity_change * p.unit_cost, 0))                AS cost_value,
+              -SUM(CASE WHEN p.unit_cost IS NULL THEN tx.quantity_change ELSE 0 END) AS uncosted_units
+            FROM inventory_transactions tx
+            JOIN products p
+              ON tx.upc = p.upc
+            WHERE tx.customer_mdoc IS NULL
+              AND tx.quantity_change < 0
+              AND tx.reason <> 'receipt'
+              AND tx.created_at >= ?1
+              AND tx.created_at < datetime(?2, '+1 day')
+            GROUP BY tx.reason, p.category
+            ORDER BY tx.reason, p.category
+        "#;
+
+        let mut stmt = conn.prepare(sql)?;
+        let rows = stmt.query_map(params![start, end], |r| {
+            Ok(ShrinkageLine {
+                reason: reason_at(r, 0)?,
+                category: r.get("category")?,
+                units: r.get::<_, i64>("units")? as i32,
+                retail_value: r.get::<_, i64>("retail_value")? as i32,
+                cost_value: r.get::<_, i64>("cost_value")? as i32,
+                uncosted_units: r.get::<_, i64>("uncosted_units")? as i32,
+            })
+        })?;
+        rows.collect::<Result<_, _>>().map_err(Into::into)
+    }
+
+    // for use with atomic_tx
+    fn lot_id_with_tx(
+        &self,
+        upc: &str,
+        label: &LotLabel,
+        tx: &rusqlite::Transaction<'_>,
+    ) -> Result<i32, AppError> {
+        let existing: Option<i32> = tx
+            .query_row(
+                "SELECT id FROM inventory_lots
+                 WHERE upc = ?1 AND lot_number IS ?2 AND expires_on IS ?3",
+                params![upc, label.lot_number, label.expires_on],
+                |r| r.get(0),
+            )
+            .optional()?;
+        if let Some(id) = existing {
+            return Ok(id);
+        }
+        tx.execute(
+            "INSERT INTO inventory_lots (upc, lot_number, expires_on) VALUES (?1, ?2, ?3)",
+            params![upc, label.lot_number, label.expires_on],
+        )?;
+        Ok(tx.last_insert_rowid() as i32)
+    }
+
+    // for use with atomic_tx
+    fn open_lots_with_tx(
+        &self,
+        upc: &str,
+        tx: &rusqlite::Transaction<'_>,
+    ) -> Result<Vec<InventoryLot>, AppError> {
+        let mut stmt = tx.prepare(&format!(
+            "{LOT_SQL}
+             WHERE l.upc = ?1
+             GROUP BY l.id
+             HAVING on_hand > 0
+             ORDER BY l.expires_on IS NULL, l.expires_on, l.id"
+        ))?;
+        let rows = stmt.query_map(params![upc], map_lot)?;
+        rows.collect::<Result<_, _>>().map_err(Into::into)
+    }
+
+    // for use with atomic_tx
+    fn get_lot_with_tx(
+        &self,
+        id: i32,
+        tx: &rusqlite::Transaction<'_>,
+    ) -> Result<Option<InventoryLot>, AppError> {
+        tx.query_row(
+            &format!("{LOT_SQL} WHERE l.id = ?1 GROUP BY l.id"),
+            params![id],
+            map_lot,
+        )
+        .optional()
+        .map_err(Into::into)
+    }
+
+    fn expiring_lots(&self, through: NaiveDate) -> Result<Vec<ExpiringLot>, AppError> {
+        let conn = self.conn.safe_lock()?;
+        let mut stmt = conn.prepare(&format!(
+            "SELECT * FROM (
+                 {LOT_SQL}
+                 WHERE l.expires_on <= ?1
+                 GROUP BY l.id
+                 HAVING on_hand > 0
+             ) x
+             JOIN products p ON p.upc = x.upc
+             ORDER BY x.expires_on, p.desc, x.id"
+        ))?;
+        let rows = stmt.query_map(params![through], |r| {
+            Ok(ExpiringLot {
+                lot: map_lot(r)?,
+                product_name: r.get("desc")?,
+                category: r.get("category")?,
+                price: r.get("price")?,
+            })
+        })?;
+        rows.collect::<Result<_, _>>().map_err(Into::into)
+    }
+}
+
+#[cfg(test)]
+mod repo_tests {
+    use super::*;
+    use crate::infrastructure::db::create_connection;
+
+    fn dt(s: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S").unwrap()
+    }
+
+    #[test]
+    fn shrinkage_groups_write_offs_and_search_filters_by_reason() {
+        let conn = Arc::new(create_connection(":memory:").unwrap());
+        conn.lock()
+            .unwrap()
+            .execute_batch(
+                "INSERT INTO operators (mdoc, name, start) VALUES (1,'Op','2025-01-01');
+                 INSERT INTO products (upc, desc, category, price, unit_cost, updated, added) VALUES
+                   ('SOAP', 'Soap',  'Hygiene', 100, 60,   '2025-01-01 00:00:00', '2025-01-01 00:00:00'),
+                   ('CHIP', 'Chips', 'Snacks',  150, NULL, '2025-01-01 00:00:00', '2025-01-01 00:00:00');
+                 INSERT INTO inventory_transactions
+                   (upc, quantity_change, operator_mdoc, customer_mdoc, reason, created_at) VALUES
+                   ('SOAP', 40, 1, NULL, 'receipt',   '2025-03-01 09:00:00'),
+                   ('SOAP', -2, 1, NULL, 'damage',    '2025-03-02 09:00:00'),
+                   ('SOAP', -1, 1, NULL, 'damage',    '2025-03-03 09:00:00'),
+                   ('CHIP', -4, 1, NULL, 'expiry',    '2025-03-03 10:00:00'),
+                   ('SOAP', -5, 1, 7,    'sale',      '2025-03-03 11:00:00'),
+                   ('SOAP', -1, 1, NULL, 'shrinkage', '2025-04-01 09:00:00');",
+            )
+            .unwrap();
+        let repo = SqliteInventoryTransactionRepo::new(Arc::clone(&conn));
+
+        let lines = repo
+            .shrinkage(dt("2025-03-01 00:00:00"), dt("2025-03-31 00:00:00"))
+            .unwrap();
+        assert_eq!(lines.len(), 2);
+        let damage = &lines[0];
+        assert_eq!(damage.reason, InventoryReason::Damage);
+        assert_eq!(damage.category, "Hygiene");
+        assert_eq!(
+            (damage.units, damage.retail_value, damage.cost_value),
+            (3, 300, 180)
+        );
+        // chips have no cost yet
+        let expiry = &lines[1];
+        assert_eq!(expiry.reason, InventoryReason::Expiry);
+        assert_eq!(
+            (expiry.units, expiry.cost_value, expiry.uncosted_units),
+            (4, 0, 4)
+        );
+
+        let damaged = repo
+            .search(10, 0, None, None, Some(InventoryReason::Damage))
+            .unwrap();
+        assert_eq!(damaged.len(), 2);
+        assert!(damaged
+            .iter()
+            .all(|(t, _, _)| t.reason == InventoryReason::Damage));
+        assert_eq!(
+            repo.count(None, None, Some(InventoryReason::Shrinkage))
+                .unwrap(),
+            1
+        );
+        assert_eq!(repo.count(None, None, None).unwrap(), 4);
+    }
+
+    #[test]
+    fn negative_stock_policy_defaults_to_block_and_persists() {
+        let conn = Arc::new(create_connection(":memory:").unwrap());
+        let repo = SqliteInventoryTransactionRepo::new(Arc::clone(&conn));
+        assert_eq!(
+            repo.negative_stock_policy().unwrap(),
+            NegativeStockPolicy::Block
+        );
+        repo.set_negative_stock_policy(NegativeStockPolicy::Warn)
+            .unwrap();
+        let mut c = conn.lock().unwrap();
+        let tx = c.transaction().unwrap();
+        assert_eq!(
+            repo.negative_stock_policy_with_tx(&tx).unwrap(),
+            NegativeStockPolicy::Warn
+        );
+    }
+
+    #[test]
+    fn lots_are_found_or_created_and_expiring_ones_listed() {
+        let conn = Arc::new(create_connection(":memory:").unwrap());
+        conn.lock()
+            .unwrap()
+            .execute_batch(
+                "INSERT INTO operators (mdoc, name, start) VALUES (1,'Op','2025-01-01');
+                 INSERT INTO products (upc, desc, category, price, updated, added) VALUES
+                   ('MILK', 'Milk', 'Dairy', 250, '2025-01-01 00:00:00', '2025-01-01 00:00:00');",
+            )
+            .unwrap();
+        let repo = SqliteInventoryTransactionRepo::new(Arc::clone(&conn));
+        let day = |s| NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap();
+        let march = LotLabel::new(Some("A1".into()), Some(day("2025-03-10"))).unwrap();
+        let april = LotLabel::new(None, Some(day("2025-04-10"))).unwrap();
+        let undated = LotLabel::new(Some("B7".into()), None).unwrap();
+
+        let mut c = conn.lock().unwrap();
+        let tx = c.transaction().unwrap();
+        let a = repo.lot_id_with_tx("MILK", &march, &tx).unwrap();
+        assert_eq!(repo.lot_id_with_tx("MILK", &march, &tx).unwrap(), a);
+        let b = repo.lot_id_with_tx("MILK", &april, &tx).unwrap();
+        let u = repo.lot_id_with_tx("MILK", &undated, &tx).unwrap();
+        assert_ne!(a, b);
+        for (lot_id, qty) in [(u, 4), (b, 6), (a, 5), (a, -2)] {
+            let row = InventoryTransaction {
+                upc: "MILK".into(),
+                quantity_change: qty,
+                operator_mdoc: 1,
+                lot_id: Some(lot_id),
+                ..Default::default()
+            };
+            repo.create_with_tx(&row, &tx).unwrap();
+        }
+
+        // dated lots soonest first, undated last
+        let open: Vec<_> = repo
+            .open_lots_with_tx("MILK", &tx)
+            .unwrap()
+            .into_iter()
+            .map(|l| (l.id, l.on_hand))
+            .collect();
+        assert_eq!(open, vec![(a, 3), (b, 6), (u, 4)]);
+        let lot = repo.get_lot_with_tx(b, &tx).unwrap().unwrap();
+        assert_eq!(lot.label, april);
+        assert!(repo.get_lot_with_tx(99, &tx).unwrap().is_none());
+        tx.commit().unwrap();
+        drop(c);
+
+        let expiring = repo.expiring_lots(day("2025-03-31")).unwrap();
+        assert_eq!(expiring.len(), 1);
+        assert_eq!(expiring[0].lot.id, a);
+        assert_eq!(expiring[0].product_name, "Milk");
+        assert_eq!(expiring[0].value(), 750);
+    }
 }
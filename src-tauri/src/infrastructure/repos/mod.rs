@@ -2,24 +2,42 @@ pub mod category_repo;
 pub mod club_import_repo;
 pub mod club_transaction_repo;
 pub mod credential_repo;
+pub mod customer_limit_repo;
 pub mod customer_repo;
 pub mod customer_transaction_repo;
 pub mod customer_tx_detail_repo;
 pub mod inventory_transaction_repo;
 pub mod operator_repo;
+pub mod order_return_repo;
 pub mod price_adjustment_repo;
+pub mod print_job_repo;
 pub mod product_repo;
+pub mod promotion_repo;
+pub mod purchase_order_repo;
+pub mod quantity_limit_repo;
+pub mod stock_take_repo;
+pub mod tax_rate_repo;
+pub mod vendor_repo;
 pub mod weekly_limit_repo;
 
 pub use category_repo::SqliteCategoryRepo;
 pub use club_import_repo::SqliteClubImportRepo;
 pub use club_transaction_repo::SqliteClubTransactionRepo;
 pub use credential_repo::SqliteCredentialRepo;
+pub use customer_limit_repo::SqliteCustomerLimitRepo;
 pub use customer_repo::SqliteCustomerRepo;
 pub use customer_transaction_repo::SqliteCustomerTransactionRepo;
 pub use customer_tx_detail_repo::SqliteCustomerTxDetailRepo;
 pub use inventory_transaction_repo::SqliteInventoryTransactionRepo;
 pub use operator_repo::SqliteOperatorRepo;
+pub use order_return_repo::SqliteOrderReturnRepo;
 pub use price_adjustment_repo::SqlitePriceAdjustmentRepo;
+pub use print_job_repo::SqlitePrintJobRepo;
 pub use product_repo::SqliteProductRepo;
+pub use promotion_repo::SqlitePromotionRepo;
+pub use purchase_order_repo::SqlitePurchaseOrderRepo;
+pub use quantity_limit_repo::SqliteQuantityLimitRepo;
+pub use stock_take_repo::SqliteStockTakeRepo;
+pub use tax_rate_repo::SqliteTaxRateRepo;
+pub use vendor_repo::SqliteVendorRepo;
 pub use weekly_limit_repo::SqliteWeeklyLimitRepo;
@@ -0,0 +1,230 @@
+use crate::common::error::AppError;
+use crate::common::mutex_ext::MutexExt;
+use crate::domain::models::{OrderReturn, OrderReturnLine};
+use crate::domain::repos::OrderReturnRepoTrait;
+use rusqlite::{params, Connection, OptionalExtension};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+pub struct SqliteOrderReturnRepo {
+    conn: Arc<Mutex<Connection>>,
+}
+
+impl SqliteOrderReturnRepo {
+    pub const fn new(conn: Arc<Mutex<Connection>>) -> Self {
+        Self { conn }
+    }
+}
+
+const BY_RETURN_ORDER_SQL: &str =
+    "SELECT return_order_id, order_id, operator_mdoc, reason, created_at
+     FROM order_returns WHERE return_order_id = ?1";
+
+fn map_return(r: &rusqlite::Row<'_>) -> rusqlite::Result<OrderReturn> {
+    Ok(OrderReturn {
+        return_order_id: r.get(0)?,
+        order_id: r.get(1)?,
+        operator_mdoc: r.get(2)?,
+        reason: r.get(3)?,
+        created_at: r.get(4)?,
+    })
+}
+
+impl OrderReturnRepoTrait for SqliteOrderReturnRepo {
+    fn create_with_tx(
+        &self,
+        ret: &OrderReturn,
+        tx: &rusqlite::Transaction<'_>,
+    ) -> Result<(), AppError> {
+        tx.execute(
+            "INSERT INTO order_returns (return_order_id, order_id, operator_mdoc, reason)
+             VALUES (?1, ?2, ?3, ?4)",
+            params![
+                ret.return_order_id,
+                ret.order_id,
+                ret.operator_mdoc,
+                ret.reason
+            ],
+        )?;
+        Ok(())
+    }
+
+    fn create_line_with_tx(
+        &self,
+        line: &OrderReturnLine,
+        tx: &rusqlite::Transaction<'_>,
+    ) -> Result<(), AppError> {
+        tx.execute(
+            "INSERT INTO order_return_lines (return_order_id, detail_id, quantity, restocked)
+             VALUES (?1, ?2, ?3, ?4)",
+            params![
+                line.return_order_id,
+                line.detail_id,
+                line.quantity,
+                line.restocked
+            ],
+        )?;
+        Ok(())
+    }
+
+    fn returned_by_detail_with_tx(
+        &self,
+        order_id: i32,
+        tx: &rusqlite::Transaction<'_>,
+    ) -> Result<HashMap<i32, i32>, AppError> {
+        let mut stmt = tx.prepare(
+            "SELECT l.detail_id, SUM(l.quantity)
+             FROM order_return_lines l
+             JOIN order_returns r ON r.return_order_id = l.return_order_id
+             WHERE r.order_id = ?1
+             GROUP BY l.detail_id",
+        )?;
+        let rows = stmt.query_map(params![order_id], |r| {
+            Ok((r.get::<_, i32>(0)?, r.get::<_, i64>(1)? as i32))
+        })?;
+        rows.collect::<Result<_, _>>().map_err(Into::into)
+    }
+
+    fn get_by_return_order(&self, return_order_id: i32) -> Result<Option<OrderReturn>, AppError> {
+        let conn = self.conn.safe_lock()?;
+        let ret = conn
+            .query_row(BY_RETURN_ORDER_SQL, params![return_order_id], map_return)
+            .optional()?;
+        Ok(ret)
+    }
+
+    fn get_by_return_order_with_tx(
+        &self,
+        return_order_id: i32,
+        tx: &rusqlite::Transaction<'_>,
+    ) -> Result<Option<OrderReturn>, AppError> {
+        let ret = tx
+            .query_row(BY_RETURN_ORDER_SQL, params![return_order_id], map_return)
+            .optional()?;
+        Ok(ret)
+    }
+
+    fn list_for_order_with_tx(
+        &self,
+        order_id: i32,
+        tx: &rusqlite::Transaction<'_>,
+    ) -> Result<Vec<OrderReturn>, AppError> {
+        let mut stmt = tx.prepare(
+            "SELECT return_order_id, order_id, operator_mdoc, reason, created_at
+             FROM order_returns WHERE order_id = ?1
+             ORDER BY return_order_id",
+        )?;
+        let rows = stmt.query_map(params![order_id], map_return)?;
+        rows.collect::<Result<_, _>>().map_err(Into::into)
+    }
+
+    fn list_lines(&self, return_order_id: i32) -> Result<Vec<OrderReturnLine>, AppError> {
+        let conn = self.conn.safe_lock()?;
+        let mut stmt = conn.prepare(
+            "SELECT return_order_id, detail_id, quantity, restocked
+             FROM order_return_lines WHERE return_order_id = ?1
+             ORDER BY id",
+        )?;
+        let rows = stmt.query_map(params![return_order_id], |r| {
+            Ok(OrderReturnLine {
+                return_order_id: r.get(0)?,
+                detail_id: r.get(1)?,
+                quantity: r.get(2)?,
+                restocked: r.get(3)?,
+            })
+        })?;
+        rows.collect::<Result<_, _>>().map_err(Into::into)
+    }
+}
+
+#[cfg(test)]
+mod repo_tests {
+    use super::*;
+    use crate::domain::repos::CustomerTransactionRepoTrait;
+    use crate::infrastructure::db::create_connection;
+    use crate::infrastructure::repos::SqliteCustomerTransactionRepo;
+    use chrono::NaiveDateTime;
+
+    #[test]
+    fn returns_sum_per_line_and_count_in_the_sale_week() {
+        let conn = Arc::new(create_connection(":memory:").unwrap());
+        let repo = SqliteOrderReturnRepo::new(Arc::clone(&conn));
+        let cust_tx_repo = SqliteCustomerTransactionRepo::new(Arc::clone(&conn));
+        let week_start =
+            NaiveDateTime::parse_from_str("2025-01-05 00:00:00", "%Y-%m-%d %H:%M:%S").unwrap();
+
+        let mut guard = conn.lock().unwrap();
+        guard.execute_batch("PRAGMA foreign_keys = OFF;").unwrap();
+        guard
+            .execute_batch(
+                "INSERT INTO customer_transactions (order_id, customer_mdoc, operator_mdoc, date)
+                   VALUES (1, 9, 1, '2025-01-06 10:00:00'),
+                          (2, 9, 1, '2025-01-14 10:00:00'),
+                          (3, 9, 1, '2025-01-15 10:00:00');
+                 INSERT INTO customer_tx_detail (detail_id, order_id, upc, quantity, price)
+                   VALUES (10, 1, 'A', 5, 100), (11, 1, 'B', 1, 300),
+                          (20, 2, 'A', -2, 100), (30, 3, 'A', -1, 100);",
+            )
+            .unwrap();
+        let tx = guard.transaction().unwrap();
+        for (return_order_id, quantity) in [(2, 2), (3, 1)] {
+            repo.create_with_tx(
+                &OrderReturn {
+                    return_order_id,
+                    order_id: 1,
+                    operator_mdoc: 1,
+                    reason: None,
+                    created_at: None,
+                },
+                &tx,
+            )
+            .unwrap();
+            repo.create_line_with_tx(
+                &OrderReturnLine {
+                    return_order_id,
+                    detail_id: 10,
+                    quantity,
+                    restocked: true,
+                },
+                &tx,
+            )
+            .unwrap();
+        }
+
+        let returned = repo.returned_by_detail_with_tx(1, &tx).unwrap();
+        assert_eq!(returned.get(&10), Some(&3));
+        assert_eq!(returned.get(&11), None);
+
+        // returns made the following week still reduce the week of the sale
+        assert_eq!(
+            cust_tx_repo
+                .get_spent_with_tx(9, week_start, week_start + chrono::Duration::days(7), &tx)
+                .unwrap(),
+            500
+        );
+        assert_eq!(
+            cust_tx_repo
+                .get_spent_with_tx(
+                    9,
+                    week_start + chrono::Duration::days(7),
+                    week_start + chrono::Duration::days(14),
+                    &tx,
+                )
+                .unwrap(),
+            0
+        );
+        assert_eq!(repo.list_for_order_with_tx(1, &tx).unwrap().len(), 2);
+        assert_eq!(
+            repo.get_by_return_order_with_tx(3, &tx)
+                .unwrap()
+                .unwrap()
+                .order_id,
+            1
+        );
+        tx.commit().unwrap();
+        drop(guard);
+
+        assert_eq!(repo.list_lines(3).unwrap()[0].quantity, 1);
+        assert!(repo.get_by_return_order(1).unwrap().is_none());
+    }
+}
@@ -1,10 +1,14 @@
 use crate::common::error::AppError;
 use crate::common::mutex_ext::MutexExt;
-use crate::domain::models::PriceAdjustment;
+use crate::domain::models::{PriceAdjustment, PriceAdjustmentStatus};
 use crate::domain::repos::PriceAdjustmentRepoTrait;
-use rusqlite::{params, Connection};
+use chrono::NaiveDateTime;
+use rusqlite::{params, Connection, OptionalExtension};
 use std::sync::{Arc, Mutex};
 
+const ADJUSTMENT_COLUMNS: &str =
+    "id, operator_mdoc, upc, old, new, created_at, effective_at, status";
+
 pub struct SqlitePriceAdjustmentRepo {
     pub conn: Arc<Mutex<Connection>>,
 }
@@ -15,33 +19,48 @@ impl SqlitePriceAdjustmentRepo {
     }
 }
 
+fn map_adjustment(r: &rusqlite::Row<'_>) -> rusqlite::Result<PriceAdjustment> {
+    let status: String = r.get(7)?;
+    Ok(PriceAdjustment {
+        id: r.get(0)?,
+        operator_mdoc: r.get(1)?,
+        upc: r.get(2)?,
+        old: r.get(3)?,
+        new: r.get(4)?,
+        created_at: r.get(5)?,
+        effective_at: r.get(6)?,
+        status: PriceAdjustmentStatus::parse(&status).map_err(|e| {
+            rusqlite::Error::FromSqlConversionFailure(
+                7,
+                rusqlite::types::Type::Text,
+                Box::new(std::io::Error::other(e.to_string())),
+            )
+        })?,
+    })
+}
+
 impl PriceAdjustmentRepoTrait for SqlitePriceAdjustmentRepo {
     fn get_by_id(&self, id: i32) -> Result<Option<PriceAdjustment>, AppError> {
         let conn = self.conn.safe_lock()?;
-        let mut stmt = conn.prepare(
-            "SELECT id, operator_mdoc, upc, old, new, created_at \
-         FROM price_adjustments WHERE id = ?1",
-        )?;
-        let mut rows = stmt.query(params![id])?;
-        if let Some(r) = rows.next()? {
-            Ok(Some(PriceAdjustment {
-                id: r.get::<_, i32>(0)?,
-                operator_mdoc: r.get(1)?,
-                upc: r.get(2)?,
-                old: r.get(3)?,
-                new: r.get(4)?,
-                created_at: r.get(5)?,
-            }))
-        } else {
-            Ok(None)
-        }
+        let sql = format!("SELECT {ADJUSTMENT_COLUMNS} FROM price_adjustments WHERE id = ?1");
+        Ok(conn
+            .query_row(&sql, params![id], map_adjustment)
+            .optional()?)
     }
 
     fn create(&self, a: &PriceAdjustment) -> Result<(), AppError> {
         let conn = self.conn.safe_lock()?;
         conn.execute(
-            "INSERT INTO price_adjustments (operator_mdoc, upc, old, new) VALUES (?1, ?2, ?3, ?4)",
-            params![a.operator_mdoc, a.upc, a.old, a.new],
+            "INSERT INTO price_adjustments (operator_mdoc, upc, old, new, effective_at, status) \
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                a.operator_mdoc,
+                a.upc,
+                a.old,
+                a.new,
+                a.effective_at,
+                a.status.as_str()
+            ],
         )?;
         Ok(())
     }
@@ -52,90 +71,60 @@ impl PriceAdjustmentRepoTrait for SqlitePriceAdjustmentRepo {
         tx: &rusqlite::Transaction<'_>,
     ) -> Result<i32, AppError> {
         tx.execute(
-            "INSERT INTO price_adjustments (operator_mdoc, upc, old, new, created_at) \
-         VALUES (?1, ?2, ?3, ?4, datetime('now'))",
-            rusqlite::params![a.operator_mdoc, a.upc, a.old, a.new],
+            "INSERT INTO price_adjustments (operator_mdoc, upc, old, new, created_at, effective_at, status) \
+         VALUES (?1, ?2, ?3, ?4, datetime('now'), ?5, ?6)",
+            rusqlite::params![
+                a.operator_mdoc,
+                a.upc,
+                a.old,
+                a.new,
+                a.effective_at,
+                a.status.as_str()
+            ],
         )?;
         Ok(tx.last_insert_rowid() as i32)
     }
 
     fn list_for_operator(&self, operator_mdoc: i32) -> Result<Vec<PriceAdjustment>, AppError> {
         let conn = self.conn.safe_lock()?;
-        let mut stmt =
-            conn.prepare(
-              "SELECT id, operator_mdoc, upc, old, new, created_at FROM price_adjustments WHERE operator_mdoc = ?1"
-            )?;
-        let adjustments = stmt
-            .query_map(params![operator_mdoc], |r| {
-                Ok(PriceAdjustment {
-                    id: r.get(0)?,
-                    operator_mdoc: r.get(1)?,
-                    upc: r.get(2)?,
-                    old: r.get(3)?,
-                    new: r.get(4)?,
-                    created_at: r.get(5)?,
-                })
-            })?
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {ADJUSTMENT_COLUMNS} FROM price_adjustments WHERE operator_mdoc = ?1"
+        ))?;
+        let items = stmt
+            .query_map(params![operator_mdoc], map_adjustment)?
             .collect::<Result<Vec<_>, _>>()?;
-        Ok(adjustments)
+        Ok(items)
     }
 
     fn list_for_product(&self, upc: String) -> Result<Vec<PriceAdjustment>, AppError> {
         let conn = self.conn.safe_lock()?;
-        let mut stmt = conn.prepare(
-            "SELECT id, operator_mdoc, upc, old, new, created_at FROM price_adjustments WHERE upc = ?1"
-        )?;
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {ADJUSTMENT_COLUMNS} FROM price_adjustments WHERE upc = ?1"
+        ))?;
         let items = stmt
-            .query_map(params![upc], |r| {
-                Ok(PriceAdjustment {
-                    id: r.get(0)?,
-                    operator_mdoc: r.get(1)?,
-                    upc: r.get(2)?,
-                    old: r.get(3)?,
-                    new: r.get(4)?,
-                    created_at: r.get(5)?,
-                })
-            })?
+            .query_map(params![upc], map_adjustment)?
             .collect::<Result<Vec<_>, _>>()?;
         Ok(items)
     }
 
     fn list_for_today(&self) -> Result<Vec<PriceAdjustment>, AppError> {
         let conn = self.conn.safe_lock()?;
-        let mut stmt = conn.prepare(
-            "SELECT id, operator_mdoc, upc, old, new, created_at FROM price_adjustments WHERE date(created_at) = date('now')"
-        )?;
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {ADJUSTMENT_COLUMNS} FROM price_adjustments WHERE date(created_at) = date('now')"
+        ))?;
         let items = stmt
-            .query_map([], |r| {
-                Ok(PriceAdjustment {
-                    id: r.get(0)?,
-                    operator_mdoc: r.get(1)?,
-                    upc: r.get(2)?,
-                    old: r.get(3)?,
-                    new: r.get(4)?,
-                    created_at: r.get(5)?,
-                })
-            })?
+            .query_map([], map_adjustment)?
             .collect::<Result<Vec<_>, _>>()?;
         Ok(items)
     }
 
     fn list(&self) -> Result<Vec<PriceAdjustment>, AppError> {
         let conn = self.conn.safe_lock()?;
-        let mut stmt = conn.prepare(
-            "SELECT id, operator_mdoc, upc, old, new, created_at FROM price_adjustments",
-        )?;
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {ADJUSTMENT_COLUMNS} FROM price_adjustments"
+        ))?;
         let items = stmt
-            .query_map([], |r| {
-                Ok(PriceAdjustment {
-                    id: r.get(0)?,
-                    operator_mdoc: r.get(1)?,
-                    upc: r.get(2)?,
-                    old: r.get(3)?,
-                    new: r.get(4)?,
-                    created_at: r.get(5)?,
-                })
-            })?
+            .query_map([], map_adjustment)?
             .collect::<Result<Vec<_>, _>>()?;
         Ok(items)
     }
@@ -158,6 +147,8 @@ impl PriceAdjustmentRepoTrait for SqlitePriceAdjustmentRepo {
                    pa.old,
                    pa.new,
                    pa.created_at,
+                   pa.effective_at,
+                   pa.status,
                    p.desc AS product_name,
                    o.name AS operator_name
             FROM price_adjustments pa
@@ -199,16 +190,9 @@ impl PriceAdjustmentRepoTrait for SqlitePriceAdjustmentRepo {
 
         let mut stmt = conn.prepare(&sql)?;
         let rows = stmt.query_map(params.as_slice(), |r| {
-            let pa = PriceAdjustment {
-                id: r.get(0)?,
-                operator_mdoc: r.get(1)?,
-                upc: r.get(2)?,
-                old: r.get(3)?,
-                new: r.get(4)?,
-                created_at: r.get(5)?,
-            };
-            let product_name: String = r.get(6)?;
-            let operator_name: String = r.get(7)?;
+            let pa = map_adjustment(r)?;
+            let product_name: String = r.get(8)?;
+            let operator_name: String = r.get(9)?;
             Ok((pa, product_name, operator_name))
         })?;
         rows.collect::<Result<_, _>>().map_err(Into::into)
@@ -252,4 +236,194 @@ impl PriceAdjustmentRepoTrait for SqlitePriceAdjustmentRepo {
         stmt.query_row(params.as_slice(), |r| r.get(0))
             .map_err(Into::into)
     }
+
+    fn list_pending(&self) -> Result<Vec<PriceAdjustment>, AppError> {
+        let conn = self.conn.safe_lock()?;
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {ADJUSTMENT_COLUMNS} FROM price_adjustments \
+             WHERE status = 'pending' ORDER BY effective_at, id"
+        ))?;
+        let items = stmt
+            .query_map([], map_adjustment)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(items)
+    }
+
+    fn due_with_tx(
+        &self,
+        now: NaiveDateTime,
+        tx: &rusqlite::Transaction<'_>,
+    ) -> Result<Vec<PriceAdjustment>, AppError> {
+        let mut stmt = tx.prepare(&format!(
+            "SELECT {ADJUSTMENT_COLUMNS} FROM price_adjustments \
+             WHERE status = 'pending' AND effective_at <= ?1 ORDER BY effective_at, id"
+        ))?;
+        let items = stmt
+            .query_map(params![now], map_adjustment)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(items)
+    }
+
+    fn mark_applied_with_tx(
+        &self,
+        id: i32,
+        old: i32,
+        tx: &rusqlite::Transaction<'_>,
+    ) -> Result<(), AppError> {
+        let changed = tx.execute(
+            "UPDATE price_adjustments SET old = ?1, status = 'applied' WHERE id = ?2 AND status = 'pending'",
+            params![old, id],
+        )?;
+        if changed == 0 {
+            return Err(AppError::NotFound(format!(
+                "pending price adjustment {id} not found"
+            )));
+        }
+        Ok(())
+    }
+
+    fn cancel(&self, id: i32) -> Result<(), AppError> {
+        let conn = self.conn.safe_lock()?;
+        let changed = conn.execute(
+            "UPDATE price_adjustments SET status = 'cancelled' WHERE id = ?1 AND status = 'pending'",
+            params![id],
+        )?;
+        if changed == 0 {
+            return Err(AppError::NotFound(format!(
+                "pending price adjustment {id} not found"
+            )));
+        }
+        Ok(())
+    }
+
+    // for use with atomic_tx
+    fn cancel_pending_for_upc_with_tx(
+        &self,
+        upc: &str,
+        tx: &rusqlite::Transaction<'_>,
+    ) -> Result<Vec<i32>, AppError> {
+        let ids = tx
+            .prepare(
+                "SELECT id FROM price_adjustments WHERE upc = ?1 AND status = 'pending' ORDER BY id",
+            )?
+            .query_map(params![upc], |r| r.get(0))?
+            .collect::<Result<Vec<i32>, _>>()?;
+        tx.execute(
+            "UPDATE price_adjustments SET status = 'cancelled' WHERE upc = ?1 AND status = 'pending'",
+            params![upc],
+        )?;
+        Ok(ids)
+    }
+}
+
+#[cfg(test)]
+mod repo_tests {
+    use super::*;
+    use crate::infrastructure::db::create_connection;
+
+    fn dt(s: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S").unwrap()
+    }
+
+    #[test]
+    fn due_changes_come_in_order_and_pending_ones_can_be_cancelled() {
+        let conn = Arc::new(create_connection(":memory:").unwrap());
+        conn.lock()
+            .unwrap()
+            .execute_batch(
+                "INSERT INTO operators (mdoc, name, start) VALUES (1,'Op','2025-01-01');
+                 INSERT INTO products (upc, desc, category, price, updated, added) VALUES
+                   ('000000000100', 'Soup', 'Food', 125, '2025-01-01 00:00:00', '2025-01-01 00:00:00');
+                 INSERT INTO price_adjustments (operator_mdoc, upc, old, new) VALUES
+                   (1, '000000000100', 100, 125);",
+            )
+            .unwrap();
+        let repo = SqlitePriceAdjustmentRepo::new(Arc::clone(&conn));
+        let schedule = |new, at: &str| PriceAdjustment {
+            id: 0,
+            operator_mdoc: 1,
+            upc: "000000000100".into(),
+            old: 125,
+            new,
+            created_at: None,
+            effective_at: Some(dt(at)),
+            status: PriceAdjustmentStatus::Pending,
+        };
+        repo.create(&schedule(150, "2025-03-01 12:00:00")).unwrap();
+        repo.create(&schedule(140, "2025-02-01 12:00:00")).unwrap();
+        repo.create(&schedule(175, "2025-06-01 12:00:00")).unwrap();
+
+        // rows from before scheduling existed count as applied
+        assert_eq!(
+            repo.get_by_id(1).unwrap().unwrap().status,
+            PriceAdjustmentStatus::Applied
+        );
+
+        {
+            let mut guard = conn.lock().unwrap();
+            let tx = guard.transaction().unwrap();
+            assert!(repo
+                .due_with_tx(dt("2025-01-15 00:00:00"), &tx)
+                .unwrap()
+                .is_empty());
+            let due: Vec<_> = repo
+                .due_with_tx(dt("2025-04-01 00:00:00"), &tx)
+                .unwrap()
+                .iter()
+                .map(|a| (a.id, a.new))
+                .collect();
+            assert_eq!(due, vec![(3, 140), (2, 150)]);
+            repo.mark_applied_with_tx(3, 125, &tx).unwrap();
+            repo.mark_applied_with_tx(2, 140, &tx).unwrap();
+            assert!(matches!(
+                repo.mark_applied_with_tx(2, 140, &tx),
+                Err(AppError::NotFound(_))
+            ));
+            tx.commit().unwrap();
+        }
+        assert_eq!(repo.get_by_id(2).unwrap().unwrap().old, 140);
+
+        let pending = repo.list_pending().unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].new, 175);
+        repo.cancel(pending[0].id).unwrap();
+        assert!(matches!(
+            repo.cancel(pending[0].id),
+            Err(AppError::NotFound(_))
+        ));
+        assert!(matches!(repo.cancel(2), Err(AppError::NotFound(_))));
+        {
+            let mut guard = conn.lock().unwrap();
+            let tx = guard.transaction().unwrap();
+            assert!(repo
+                .due_with_tx(dt("2025-07-01 00:00:00"), &tx)
+                .unwrap()
+                .is_empty());
+        }
+
+        let statuses: Vec<_> = repo
+            .search(10, 0, None, None)
+            .unwrap()
+            .into_iter()
+            .map(|(a, _, _)| (a.id, a.status))
+            .collect();
+        assert_eq!(statuses.len(), 4);
+        assert!(statuses.contains(&(4, PriceAdjustmentStatus::Cancelled)));
+        assert!(statuses.contains(&(3, PriceAdjustmentStatus::Applied)));
+
+        // a price set by hand cancels whatever is still scheduled for the product
+        repo.create(&schedule(160, "2025-08-01 12:00:00")).unwrap();
+        repo.create(&schedule(165, "2025-09-01 12:00:00")).unwrap();
+        let mut guard = conn.lock().unwrap();
+        let tx = guard.transaction().unwrap();
+        assert_eq!(
+            repo.cancel_pending_for_upc_with_tx("000000000100", &tx)
+                .unwrap(),
+            vec![5, 6]
+        );
+        assert!(repo
+            .cancel_pending_for_upc_with_tx("000000000100", &tx)
+            .unwrap()
+            .is_empty());
+    }
 }
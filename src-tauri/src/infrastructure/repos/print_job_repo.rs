@@ -0,0 +1,242 @@
+use crate::common::error::AppError;
+use crate::common::mutex_ext::MutexExt;
+use crate::domain::models::{PrintJob, PrintJobStatus};
+use crate::domain::repos::PrintJobRepoTrait;
+use rusqlite::{params, Connection, OptionalExtension};
+use std::sync::{Arc, Mutex};
+
+const JOB_COLUMNS: &str = "id, order_id, copies, printer_name, sumatra_location, operator_name, \
+     customer_name, reprint_by, status, attempts, last_error, created_at, updated_at, balance";
+
+pub struct SqlitePrintJobRepo {
+    conn: Arc<Mutex<Connection>>,
+}
+
+impl SqlitePrintJobRepo {
+    pub const fn new(conn: Arc<Mutex<Connection>>) -> Self {
+        Self { conn }
+    }
+}
+
+fn map_job(r: &rusqlite::Row<'_>) -> rusqlite::Result<PrintJob> {
+    let status: String = r.get(8)?;
+    Ok(PrintJob {
+        id: r.get(0)?,
+        order_id: r.get(1)?,
+        copies: r.get(2)?,
+        printer_name: r.get(3)?,
+        sumatra_location: r.get(4)?,
+        operator_name: r.get(5)?,
+        customer_name: r.get(6)?,
+        reprint_by: r.get(7)?,
+        status: PrintJobStatus::parse(&status).map_err(|e| {
+            rusqlite::Error::FromSqlConversionFailure(
+                8,
+                rusqlite::types::Type::Text,
+                Box::new(std::io::Error::other(e.to_string())),
+            )
+        })?,
+        attempts: r.get(9)?,
+        last_error: r.get(10)?,
+        created_at: r.get(11)?,
+        updated_at: r.get(12)?,
+        balance: r.get(13)?,
+    })
+}
+
+impl PrintJobRepoTrait for SqlitePrintJobRepo {
+    fn create(&self, job: &PrintJob) -> Result<i32, AppError> {
+        let conn = self.conn.safe_lock()?;
+        conn.execute(
+            "INSERT INTO print_jobs
+             (order_id, copies, printer_name, sumatra_location, operator_name, customer_name,
+              reprint_by, status, attempts, balance)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+            params![
+                job.order_id,
+                job.copies,
+                job.printer_name,
+                job.sumatra_location,
+                job.operator_name,
+                job.customer_name,
+                job.reprint_by,
+                job.status.as_str(),
+                job.attempts,
+                job.balance,
+            ],
+        )?;
+        Ok(conn.last_insert_rowid() as i32)
+    }
+
+    fn get(&self, id: i32) -> Result<Option<PrintJob>, AppError> {
+        let conn = self.conn.safe_lock()?;
+        let job = conn
+            .query_row(
+                &format!("SELECT {JOB_COLUMNS} FROM print_jobs WHERE id = ?1"),
+                params![id],
+                map_job,
+            )
+            .optional()?;
+        Ok(job)
+    }
+
+    fn list(&self, status: Option<PrintJobStatus>, limit: i32) -> Result<Vec<PrintJob>, AppError> {
+        let conn = self.conn.safe_lock()?;
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {JOB_COLUMNS} FROM print_jobs
+             WHERE ?1 IS NULL OR status = ?1
+             ORDER BY id DESC LIMIT ?2"
+        ))?;
+        let rows = stmt.query_map(params![status.map(PrintJobStatus::as_str), limit], map_job)?;
+        rows.collect::<Result<_, _>>().map_err(Into::into)
+    }
+
+    fn claim_next(&self) -> Result<Option<PrintJob>, AppError> {
+        // single shared connection: holding the lock makes select + update atomic
+        let conn = self.conn.safe_lock()?;
+        let job = conn
+            .query_row(
+                &format!(
+                    "SELECT {JOB_COLUMNS} FROM print_jobs
+                     WHERE status = 'pending' ORDER BY id LIMIT 1"
+                ),
+                [],
+                map_job,
+            )
+            .optional()?;
+        let Some(mut job) = job else {
+            return Ok(None);
+        };
+        conn.execute(
+            "UPDATE print_jobs SET status = 'printing', updated_at = CURRENT_TIMESTAMP
+             WHERE id = ?1",
+            params![job.id],
+        )?;
+        job.status = PrintJobStatus::Printing;
+        Ok(Some(job))
+    }
+
+    fn update_status(
+        &self,
+        id: i32,
+        status: PrintJobStatus,
+        attempts: i32,
+        last_error: Option<String>,
+    ) -> Result<(), AppError> {
+        let conn = self.conn.safe_lock()?;
+        let changed = conn.execute(
+            "UPDATE print_jobs
+             SET status = ?2, attempts = ?3, last_error = ?4, updated_at = CURRENT_TIMESTAMP
+             WHERE id = ?1",
+            params![id, status.as_str(), attempts, last_error],
+        )?;
+        if changed == 0 {
+            return Err(AppError::NotFound(format!("print job {id} not found")));
+        }
+        Ok(())
+    }
+
+    fn sale_balance(&self, order_id: i32) -> Result<Option<i32>, AppError> {
+        let conn = self.conn.safe_lock()?;
+        let balance = conn
+            .query_row(
+                "SELECT balance FROM print_jobs
+                 WHERE order_id = ?1 AND balance IS NOT NULL
+                 ORDER BY id LIMIT 1",
+                params![order_id],
+                |r| r.get(0),
+            )
+            .optional()?;
+        Ok(balance)
+    }
+
+    fn has_receipt(&self, order_id: i32) -> Result<bool, AppError> {
+        let conn = self.conn.safe_lock()?;
+        let found = conn.query_row(
+            "SELECT EXISTS(SELECT 1 FROM print_jobs WHERE order_id = ?1 AND reprint_by IS NULL)",
+            params![order_id],
+            |r| r.get(0),
+        )?;
+        Ok(found)
+    }
+
+    fn requeue_interrupted(&self) -> Result<usize, AppError> {
+        let conn = self.conn.safe_lock()?;
+        let changed = conn.execute(
+            "UPDATE print_jobs SET status = 'pending', updated_at = CURRENT_TIMESTAMP
+             WHERE status = 'printing'",
+            [],
+        )?;
+        Ok(changed)
+    }
+}
+
+#[cfg(test)]
+mod repo_tests {
+    use super::*;
+    use crate::infrastructure::db::create_connection;
+
+    fn job(order_id: i32) -> PrintJob {
+        PrintJob {
+            id: 0,
+            order_id,
+            copies: "both".into(),
+            printer_name: "Receipt".into(),
+            sumatra_location: "C:/sumatra.exe".into(),
+            operator_name: "Op".into(),
+            customer_name: "Cust".into(),
+            reprint_by: None,
+            status: PrintJobStatus::Pending,
+            attempts: 0,
+            last_error: None,
+            created_at: None,
+            updated_at: None,
+            balance: None,
+        }
+    }
+
+    #[test]
+    fn claim_update_and_requeue() {
+        let conn = Arc::new(create_connection(":memory:").unwrap());
+        conn.lock()
+            .unwrap()
+            .execute_batch("PRAGMA foreign_keys = OFF;")
+            .unwrap();
+        let repo = SqlitePrintJobRepo::new(Arc::clone(&conn));
+
+        let first = repo
+            .create(&PrintJob {
+                balance: Some(750),
+                ..job(1)
+            })
+            .unwrap();
+        let second = repo.create(&job(2)).unwrap();
+        assert_eq!(repo.sale_balance(1).unwrap(), Some(750));
+        assert_eq!(repo.sale_balance(2).unwrap(), None);
+
+        // oldest pending first
+        let claimed = repo.claim_next().unwrap().unwrap();
+        assert_eq!(claimed.id, first);
+        assert_eq!(claimed.status, PrintJobStatus::Printing);
+        assert_eq!(repo.claim_next().unwrap().unwrap().id, second);
+        assert!(repo.claim_next().unwrap().is_none());
+
+        repo.update_status(first, PrintJobStatus::Failed, 3, Some("jam".into()))
+            .unwrap();
+        let failed = repo.list(Some(PrintJobStatus::Failed), 10).unwrap();
+        assert_eq!(failed.len(), 1);
+        assert_eq!(failed[0].last_error.as_deref(), Some("jam"));
+
+        // the second job was left mid-print
+        assert_eq!(repo.requeue_interrupted().unwrap(), 1);
+        assert_eq!(
+            repo.get(second).unwrap().unwrap().status,
+            PrintJobStatus::Pending
+        );
+        assert_eq!(repo.list(None, 10).unwrap().len(), 2);
+        assert!(matches!(
+            repo.update_status(99, PrintJobStatus::Done, 0, None),
+            Err(AppError::NotFound(_))
+        ));
+    }
+}
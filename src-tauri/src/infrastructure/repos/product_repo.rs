@@ -1,12 +1,48 @@
 use crate::common::error::AppError;
 use crate::common::mutex_ext::MutexExt;
-use crate::domain::models::Product;
+use crate::domain::models::{BarcodeAlias, BundleComponent, Product};
 use crate::domain::report_models::product_inventory::ProductInventoryReport;
 use crate::domain::report_models::product_inventory::ProductInventoryTotals;
+use crate::domain::report_models::reorder_report::LowStockItem;
 use crate::domain::repos::ProductRepoTrait;
-use rusqlite::{params, Connection};
+use chrono::NaiveDateTime;
+use rusqlite::{params, Connection, OptionalExtension};
 use std::sync::{Arc, Mutex};
 
+// A product's own flag wins; otherwise its category's. Unknown upcs are not exempt.
+const LIMIT_EXEMPT_SQL: &str = "
+    SELECT COALESCE(p.limit_exempt, c.limit_exempt, 0)
+    FROM products p
+    LEFT JOIN categories c ON c.name = p.category
+    WHERE p.upc = ?1";
+
+// Active products with a reorder point and their on-hand quantity; callers
+// keep the rows at or below the point.
+const STOCK_LEVELS_CTE: &str = "
+    WITH stock AS (
+      SELECT p.upc, p.desc, p.category, p.reorder_point, p.reorder_quantity,
+             COALESCE((SELECT SUM(it.quantity_change)
+                       FROM inventory_transactions it
+                       WHERE it.upc = p.upc), 0) AS available
+      FROM products p
+      WHERE p.deleted IS NULL AND p.reorder_point IS NOT NULL
+    )";
+
+// The upc a scanned code stands for and the units per scan; a code that isn't
+// an alias is taken as a product's own upc, one unit.
+const RESOLVE_BARCODE_SQL: &str = "
+    SELECT COALESCE((SELECT upc FROM product_barcodes WHERE barcode = ?1), ?1),
+           COALESCE((SELECT multiplier FROM product_barcodes WHERE barcode = ?1), 1)";
+
+fn map_alias(r: &rusqlite::Row<'_>) -> rusqlite::Result<BarcodeAlias> {
+    Ok(BarcodeAlias {
+        barcode: r.get(0)?,
+        upc: r.get(1)?,
+        multiplier: r.get(2)?,
+        created_at: r.get(3)?,
+    })
+}
+
 pub struct SqliteProductRepo {
     pub conn: Arc<Mutex<Connection>>,
 }
@@ -21,7 +57,39 @@ impl ProductRepoTrait for SqliteProductRepo {
     fn get_by_upc(&self, upc: String) -> Result<Option<Product>, AppError> {
         let conn = self.conn.safe_lock()?;
         let mut stmt = conn.prepare(
-            "SELECT upc, desc, category, price, updated, added, deleted 
+            "SELECT upc, desc, category, price, updated, added, deleted, limit_exempt, unit_cost,
+                    reorder_point, reorder_quantity
+             FROM products WHERE upc = ?1",
+        )?;
+        let mut rows = stmt.query(params![upc])?;
+        if let Some(r) = rows.next()? {
+            Ok(Some(Product {
+                upc: r.get(0)?,
+                desc: r.get(1)?,
+                category: r.get(2)?,
+                price: r.get(3)?,
+                updated: r.get(4)?,
+                added: r.get(5)?,
+                deleted: r.get(6)?,
+                limit_exempt: r.get(7)?,
+                unit_cost: r.get(8)?,
+                reorder_point: r.get(9)?,
+                reorder_quantity: r.get(10)?,
+            }))
+        } else {
+            Ok(None)
+        }
+    }
+
+    // for use with atomic_tx
+    fn get_by_upc_with_tx(
+        &self,
+        upc: String,
+        tx: &rusqlite::Transaction<'_>,
+    ) -> Result<Option<Product>, AppError> {
+        let mut stmt = tx.prepare(
+            "SELECT upc, desc, category, price, updated, added, deleted, limit_exempt, unit_cost,
+                    reorder_point, reorder_quantity
              FROM products WHERE upc = ?1",
         )?;
         let mut rows = stmt.query(params![upc])?;
@@ -34,12 +102,36 @@ impl ProductRepoTrait for SqliteProductRepo {
                 updated: r.get(4)?,
                 added: r.get(5)?,
                 deleted: r.get(6)?,
+                limit_exempt: r.get(7)?,
+                unit_cost: r.get(8)?,
+                reorder_point: r.get(9)?,
+                reorder_quantity: r.get(10)?,
             }))
         } else {
             Ok(None)
         }
     }
 
+    fn is_limit_exempt(&self, upc: &str) -> Result<bool, AppError> {
+        let conn = self.conn.safe_lock()?;
+        let exempt = conn
+            .query_row(LIMIT_EXEMPT_SQL, params![upc], |r| r.get(0))
+            .optional()?;
+        Ok(exempt.unwrap_or(false))
+    }
+
+    // for use with atomic_tx
+    fn is_limit_exempt_with_tx(
+        &self,
+        upc: &str,
+        tx: &rusqlite::Transaction<'_>,
+    ) -> Result<bool, AppError> {
+        let exempt = tx
+            .query_row(LIMIT_EXEMPT_SQL, params![upc], |r| r.get(0))
+            .optional()?;
+        Ok(exempt.unwrap_or(false))
+    }
+
     fn get_price(&self, upc: String) -> Result<i32, AppError> {
         let conn = self.conn.safe_lock()?;
         let price: i32 = conn.query_row(
@@ -53,9 +145,44 @@ impl ProductRepoTrait for SqliteProductRepo {
     fn create(&self, p: &Product) -> Result<(), AppError> {
         let conn = self.conn.safe_lock()?;
         conn.execute(
-            "INSERT INTO products (upc, desc, category, price, updated, added, deleted) 
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
-            params![p.upc, p.desc, p.category, p.price, p.updated, p.added, p.deleted],
+            "INSERT INTO products (upc, desc, category, price, updated, added, deleted, limit_exempt, unit_cost,
+                                   reorder_point, reorder_quantity)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+            params![
+                p.upc,
+                p.desc,
+                p.category,
+                p.price,
+                p.updated,
+                p.added,
+                p.deleted,
+                p.limit_exempt,
+                p.unit_cost,
+                p.reorder_point,
+                p.reorder_quantity
+            ],
+        )?;
+        Ok(())
+    }
+
+    fn create_with_tx(&self, p: &Product, tx: &rusqlite::Transaction<'_>) -> Result<(), AppError> {
+        tx.execute(
+            "INSERT INTO products (upc, desc, category, price, updated, added, deleted, limit_exempt, unit_cost, \
+                                   reorder_point, reorder_quantity) \
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+            params![
+                p.upc,
+                p.desc,
+                p.category,
+                p.price,
+                p.updated,
+                p.added,
+                p.deleted,
+                p.limit_exempt,
+                p.unit_cost,
+                p.reorder_point,
+                p.reorder_quantity
+            ],
         )?;
         Ok(())
     }
@@ -63,9 +190,20 @@ impl ProductRepoTrait for SqliteProductRepo {
     fn update_by_upc(&self, p: &Product) -> Result<(), AppError> {
         let conn = self.conn.safe_lock()?;
         conn.execute(
-            "UPDATE products SET desc = ?1, category = ?2, price = ?3, 
-             updated = ?4, deleted = ?5 WHERE upc = ?6",
-            params![p.desc, p.category, p.price, p.updated, p.deleted, p.upc],
+            "UPDATE products SET desc = ?1, category = ?2, price = ?3,
+             updated = ?4, deleted = ?5, limit_exempt = ?7,
+             reorder_point = ?8, reorder_quantity = ?9 WHERE upc = ?6",
+            params![
+                p.desc,
+                p.category,
+                p.price,
+                p.updated,
+                p.deleted,
+                p.upc,
+                p.limit_exempt,
+                p.reorder_point,
+                p.reorder_quantity
+            ],
         )?;
         Ok(())
     }
@@ -77,9 +215,53 @@ impl ProductRepoTrait for SqliteProductRepo {
     ) -> Result<(), AppError> {
         tx.execute(
             "UPDATE products SET desc = ?1, category = ?2, price = ?3, \
-         updated = ?4, deleted = ?5 WHERE upc = ?6",
-            rusqlite::params![p.desc, p.category, p.price, p.updated, p.deleted, p.upc],
+         updated = ?4, deleted = ?5, limit_exempt = ?7, \
+         reorder_point = ?8, reorder_quantity = ?9 WHERE upc = ?6",
+            rusqlite::params![
+                p.desc,
+                p.category,
+                p.price,
+                p.updated,
+                p.deleted,
+                p.upc,
+                p.limit_exempt,
+                p.reorder_point,
+                p.reorder_quantity
+            ],
+        )?;
+        Ok(())
+    }
+
+    fn set_price_with_tx(
+        &self,
+        upc: &str,
+        price: i32,
+        updated: NaiveDateTime,
+        tx: &rusqlite::Transaction<'_>,
+    ) -> Result<(), AppError> {
+        let changed = tx.execute(
+            "UPDATE products SET price = ?1, updated = ?2 WHERE upc = ?3",
+            params![price, updated, upc],
+        )?;
+        if changed == 0 {
+            return Err(AppError::NotFound(format!("product {upc} not found")));
+        }
+        Ok(())
+    }
+
+    fn set_unit_cost_with_tx(
+        &self,
+        upc: &str,
+        unit_cost: i32,
+        tx: &rusqlite::Transaction<'_>,
+    ) -> Result<(), AppError> {
+        let changed = tx.execute(
+            "UPDATE products SET unit_cost = ?1 WHERE upc = ?2",
+            params![unit_cost, upc],
         )?;
+        if changed == 0 {
+            return Err(AppError::NotFound(format!("product {upc} not found")));
+        }
         Ok(())
     }
 
@@ -87,7 +269,8 @@ impl ProductRepoTrait for SqliteProductRepo {
         let conn = self.conn.safe_lock()?;
         // filter: nonzero price, not deleted; sort by category then name
         let mut stmt = conn.prepare(
-            "SELECT upc, desc, category, price, updated, added, deleted
+            "SELECT upc, desc, category, price, updated, added, deleted, limit_exempt, unit_cost,
+                    reorder_point, reorder_quantity
             FROM products
             WHERE price != 0 AND deleted IS NULL
             ORDER BY category, desc",
@@ -102,6 +285,10 @@ impl ProductRepoTrait for SqliteProductRepo {
                     updated: r.get(4)?,
                     added: r.get(5)?,
                     deleted: r.get(6)?,
+                    limit_exempt: r.get(7)?,
+                    unit_cost: r.get(8)?,
+                    reorder_point: r.get(9)?,
+                    reorder_quantity: r.get(10)?,
                 })
             })?
             .collect::<Result<Vec<_>, _>>()?;
@@ -122,14 +309,28 @@ impl ProductRepoTrait for SqliteProductRepo {
 
         let mut sql = String::from(
             "SELECT p.upc, p.desc, p.category, p.price,
-                    p.updated, p.added, p.deleted,
-                    COALESCE(inv.available, 0) AS available
+                    p.updated, p.added, p.deleted, p.limit_exempt, p.unit_cost,
+                    p.reorder_point, p.reorder_quantity,
+                    CASE WHEN kit.bundle_upc IS NULL THEN COALESCE(inv.available, 0)
+                         ELSE kit.available END AS available
              FROM products p
              LEFT JOIN (
                SELECT upc, SUM(quantity_change) AS available
                FROM inventory_transactions
                GROUP BY upc
-             ) inv ON p.upc = inv.upc",
+             ) inv ON p.upc = inv.upc
+             -- a kit is available as many times as its scarcest component allows
+             LEFT JOIN (
+               SELECT pc.bundle_upc,
+                      MIN(MAX(COALESCE(ci.available, 0), 0) / pc.quantity) AS available
+               FROM product_components pc
+               LEFT JOIN (
+                 SELECT upc, SUM(quantity_change) AS available
+                 FROM inventory_transactions
+                 GROUP BY upc
+               ) ci ON ci.upc = pc.component_upc
+               GROUP BY pc.bundle_upc
+             ) kit ON p.upc = kit.bundle_upc",
         );
         let mut clauses = Vec::new();
         let mut params: Vec<&dyn rusqlite::ToSql> = Vec::new();
@@ -169,8 +370,12 @@ impl ProductRepoTrait for SqliteProductRepo {
                 updated: r.get(4)?,
                 added: r.get(5)?,
                 deleted: r.get(6)?,
+                limit_exempt: r.get(7)?,
+                unit_cost: r.get(8)?,
+                reorder_point: r.get(9)?,
+                reorder_quantity: r.get(10)?,
             };
-            let available: i64 = r.get(7)?;
+            let available: i64 = r.get(11)?;
             Ok((product, available as i32))
         })?;
 
@@ -281,4 +486,375 @@ impl ProductRepoTrait for SqliteProductRepo {
         })?;
         Ok(row)
     }
+
+    fn low_stock(&self) -> Result<Vec<LowStockItem>, AppError> {
+        let conn = self.conn.safe_lock()?;
+        // vendor of the most recent purchase order the product was on
+        let sql = format!(
+            "{STOCK_LEVELS_CTE}
+            SELECT s.upc, s.desc, s.category, s.available, s.reorder_point, s.reorder_quantity,
+                   (SELECT v.name
+                    FROM purchase_order_lines l
+                    JOIN purchase_orders po ON po.id = l.po_id
+                    JOIN vendors v ON v.id = po.vendor_id
+                    WHERE l.upc = s.upc AND po.status != 'cancelled'
+                    ORDER BY po.created_at DESC, po.id DESC
+                    LIMIT 1) AS vendor_name
+            FROM stock s
+            WHERE s.available <= s.reorder_point
+            ORDER BY s.category, vendor_name IS NULL, vendor_name, s.desc"
+        );
+        let mut stmt = conn.prepare(&sql)?;
+        let rows = stmt.query_map([], |r| {
+            Ok(LowStockItem {
+                upc: r.get(0)?,
+                desc: r.get(1)?,
+                category: r.get(2)?,
+                available: r.get::<_, i64>(3)? as i32,
+                reorder_point: r.get(4)?,
+                reorder_quantity: r.get(5)?,
+                vendor_name: r.get(6)?,
+            })
+        })?;
+        rows.collect::<Result<_, _>>().map_err(Into::into)
+    }
+
+    fn count_low_stock(&self) -> Result<i32, AppError> {
+        let conn = self.conn.safe_lock()?;
+        let count: i32 = conn.query_row(
+            &format!(
+                "{STOCK_LEVELS_CTE} SELECT COUNT(*) FROM stock WHERE available <= reorder_point"
+            ),
+            [],
+            |r| r.get(0),
+        )?;
+        Ok(count)
+    }
+
+    fn resolve_barcode(&self, code: &str) -> Result<Option<(Product, i32)>, AppError> {
+        let (upc, multiplier): (String, i32) =
+            self.conn
+                .safe_lock()?
+                .query_row(RESOLVE_BARCODE_SQL, params![code], |r| {
+                    Ok((r.get(0)?, r.get(1)?))
+                })?;
+        Ok(self.get_by_upc(upc)?.map(|p| (p, multiplier)))
+    }
+
+    // for use with atomic_tx
+    fn resolve_barcode_with_tx(
+        &self,
+        code: &str,
+        tx: &rusqlite::Transaction<'_>,
+    ) -> Result<Option<(Product, i32)>, AppError> {
+        let (upc, multiplier): (String, i32) =
+            tx.query_row(RESOLVE_BARCODE_SQL, params![code], |r| {
+                Ok((r.get(0)?, r.get(1)?))
+            })?;
+        Ok(self.get_by_upc_with_tx(upc, tx)?.map(|p| (p, multiplier)))
+    }
+
+    fn get_barcode(&self, barcode: &str) -> Result<Option<BarcodeAlias>, AppError> {
+        let conn = self.conn.safe_lock()?;
+        let alias = conn
+            .query_row(
+                "SELECT barcode, upc, multiplier, created_at
+                 FROM product_barcodes WHERE barcode = ?1",
+                params![barcode],
+                map_alias,
+            )
+            .optional()?;
+        Ok(alias)
+    }
+
+    fn list_barcodes(&self, upc: Option<String>) -> Result<Vec<BarcodeAlias>, AppError> {
+        let conn = self.conn.safe_lock()?;
+        let mut stmt = conn.prepare(
+            "SELECT barcode, upc, multiplier, created_at
+             FROM product_barcodes
+             WHERE ?1 IS NULL OR upc = ?1
+             ORDER BY upc, multiplier, barcode",
+        )?;
+        let aliases = stmt
+            .query_map(params![upc], map_alias)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(aliases)
+    }
+
+    fn add_barcode(&self, alias: &BarcodeAlias) -> Result<(), AppError> {
+        let conn = self.conn.safe_lock()?;
+        conn.execute(
+            "INSERT INTO product_barcodes (barcode, upc, multiplier) VALUES (?1, ?2, ?3)",
+            params![alias.barcode, alias.upc, alias.multiplier],
+        )?;
+        Ok(())
+    }
+
+    fn remove_barcode(&self, barcode: &str) -> Result<(), AppError> {
+        let conn = self.conn.safe_lock()?;
+        let removed = conn.execute(
+            "DELETE FROM product_barcodes WHERE barcode = ?1",
+            params![barcode],
+        )?;
+        if removed == 0 {
+            return Err(AppError::NotFound(format!("barcode {barcode} not found")));
+        }
+        Ok(())
+    }
+
+    fn components(&self, bundle_upc: &str) -> Result<Vec<(BundleComponent, String)>, AppError> {
+        let conn = self.conn.safe_lock()?;
+        let mut stmt = conn.prepare(
+            "SELECT pc.bundle_upc, pc.component_upc, pc.quantity, p.desc
+             FROM product_components pc
+             JOIN products p ON p.upc = pc.component_upc
+             WHERE pc.bundle_upc = ?1
+             ORDER BY p.desc",
+        )?;
+        let rows = stmt
+            .query_map(params![bundle_upc], |r| {
+                Ok((
+                    BundleComponent {
+                        bundle_upc: r.get(0)?,
+                        component_upc: r.get(1)?,
+                        quantity: r.get(2)?,
+                    },
+                    r.get(3)?,
+                ))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(rows)
+    }
+
+    // for use with atomic_tx
+    fn components_with_tx(
+        &self,
+        bundle_upc: &str,
+        tx: &rusqlite::Transaction<'_>,
+    ) -> Result<Vec<BundleComponent>, AppError> {
+        let mut stmt = tx.prepare(
+            "SELECT bundle_upc, component_upc, quantity
+             FROM product_components
+             WHERE bundle_upc = ?1
+             ORDER BY component_upc",
+        )?;
+        let rows = stmt
+            .query_map(params![bundle_upc], |r| {
+                Ok(BundleComponent {
+                    bundle_upc: r.get(0)?,
+                    component_upc: r.get(1)?,
+                    quantity: r.get(2)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(rows)
+    }
+
+    fn bundles_containing_with_tx(
+        &self,
+        component_upc: &str,
+        tx: &rusqlite::Transaction<'_>,
+    ) -> Result<Vec<String>, AppError> {
+        let mut stmt = tx.prepare(
+            "SELECT bundle_upc FROM product_components
+             WHERE component_upc = ?1
+             ORDER BY bundle_upc",
+        )?;
+        let rows = stmt
+            .query_map(params![component_upc], |r| r.get(0))?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(rows)
+    }
+
+    fn set_components_with_tx(
+        &self,
+        bundle_upc: &str,
+        components: &[BundleComponent],
+        tx: &rusqlite::Transaction<'_>,
+    ) -> Result<(), AppError> {
+        tx.execute(
+            "DELETE FROM product_components WHERE bundle_upc = ?1",
+            params![bundle_upc],
+        )?;
+        let mut stmt = tx.prepare(
+            "INSERT INTO product_components (bundle_upc, component_upc, quantity)
+             VALUES (?1, ?2, ?3)",
+        )?;
+        for c in components {
+            stmt.execute(params![bundle_upc, c.component_upc, c.quantity])?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod repo_tests {
+    use super::*;
+    use crate::infrastructure::db::create_connection;
+
+    #[test]
+    fn low_stock_lists_products_at_or_below_their_reorder_point() {
+        let conn = Arc::new(create_connection(":memory:").unwrap());
+        conn.lock()
+            .unwrap()
+            .execute_batch(
+                "INSERT INTO operators (mdoc, name, start) VALUES (1, 'Op', '2025-01-01');
+                 INSERT INTO vendors (id, name) VALUES (1, 'Acme'), (2, 'Bolt');
+                 INSERT INTO products (upc, desc, category, price, updated, added, reorder_point, reorder_quantity) VALUES
+                   ('SOAP',  'Soap',  'Hygiene', 100, '2025-01-01 00:00:00', '2025-01-01 00:00:00', 5, 24),
+                   ('PASTE', 'Paste', 'Hygiene', 150, '2025-01-01 00:00:00', '2025-01-01 00:00:00', 3, NULL),
+                   ('COMB',  'Comb',  'Hygiene', 150, '2025-01-01 00:00:00', '2025-01-01 00:00:00', NULL, NULL),
+                   ('CHIP',  'Chips', 'Snacks',  200, '2025-01-01 00:00:00', '2025-01-01 00:00:00', 10, 50);
+                 INSERT INTO inventory_transactions (upc, quantity_change, operator_mdoc) VALUES
+                   ('SOAP', 5, 1), ('PASTE', 9, 1), ('PASTE', -2, 1), ('CHIP', 4, 1);
+                 -- soap came from Acme, then Bolt; the cancelled order doesn't count
+                 INSERT INTO purchase_orders (id, vendor_id, status, operator_mdoc, created_at) VALUES
+                   (1, 1, 'received',  1, '2025-01-05 00:00:00'),
+                   (2, 2, 'received',  1, '2025-02-05 00:00:00'),
+                   (3, 1, 'cancelled', 1, '2025-03-05 00:00:00');
+                 INSERT INTO purchase_order_lines (po_id, upc, expected_quantity) VALUES
+                   (1, 'SOAP', 10), (2, 'SOAP', 10), (3, 'SOAP', 10);",
+            )
+            .unwrap();
+        let repo = SqliteProductRepo::new(Arc::clone(&conn));
+
+        // paste has 7 on hand, above its point of 3; comb has no point
+        let low = repo.low_stock().unwrap();
+        let rows: Vec<_> = low
+            .iter()
+            .map(|i| (i.upc.as_str(), i.available, i.vendor_name.as_deref()))
+            .collect();
+        assert_eq!(rows, vec![("SOAP", 5, Some("Bolt")), ("CHIP", 4, None)]);
+        assert_eq!(low[0].reorder_quantity, Some(24));
+        assert_eq!(repo.count_low_stock().unwrap(), 2);
+
+        conn.lock()
+            .unwrap()
+            .execute(
+                "UPDATE products SET deleted = '2025-04-01 00:00:00' WHERE upc = 'CHIP'",
+                [],
+            )
+            .unwrap();
+        assert_eq!(repo.count_low_stock().unwrap(), 1);
+    }
+
+    #[test]
+    fn barcode_aliases_resolve_to_their_product() {
+        let conn = Arc::new(create_connection(":memory:").unwrap());
+        conn.lock()
+            .unwrap()
+            .execute_batch(
+                "INSERT INTO products (upc, desc, category, price, updated, added) VALUES
+                   ('000000000100', 'Soup', 'Food', 125, '2025-01-01 00:00:00', '2025-01-01 00:00:00');",
+            )
+            .unwrap();
+        let repo = SqliteProductRepo::new(Arc::clone(&conn));
+        for (barcode, multiplier) in [("100000000100", 24), ("000000000099", 1)] {
+            repo.add_barcode(&BarcodeAlias {
+                barcode: barcode.into(),
+                upc: "000000000100".into(),
+                multiplier,
+                created_at: None,
+            })
+            .unwrap();
+        }
+
+        let (soup, per_scan) = repo.resolve_barcode("100000000100").unwrap().unwrap();
+        assert_eq!((soup.upc.as_str(), per_scan), ("000000000100", 24));
+        let (_, per_scan) = repo.resolve_barcode("000000000100").unwrap().unwrap();
+        assert_eq!(per_scan, 1);
+        let (soup, _) = repo.resolve_barcode("000000000099").unwrap().unwrap();
+        assert_eq!(soup.upc, "000000000100");
+        // plain lookups are by the product's own upc only
+        assert!(repo.get_by_upc("000000000099".into()).unwrap().is_none());
+        assert!(repo.resolve_barcode("999999999999").unwrap().is_none());
+
+        let listed: Vec<_> = repo
+            .list_barcodes(Some("000000000100".into()))
+            .unwrap()
+            .into_iter()
+            .map(|a| (a.barcode, a.multiplier))
+            .collect();
+        assert_eq!(
+            listed,
+            vec![("000000000099".into(), 1), ("100000000100".into(), 24)]
+        );
+        repo.remove_barcode("000000000099").unwrap();
+        assert!(repo.get_barcode("000000000099").unwrap().is_none());
+        assert!(matches!(
+            repo.remove_barcode("000000000099"),
+            Err(AppError::NotFound(_))
+        ));
+    }
+
+    #[test]
+    fn kit_availability_comes_from_its_components() {
+        let conn = Arc::new(create_connection(":memory:").unwrap());
+        conn.lock()
+            .unwrap()
+            .execute_batch(
+                "INSERT INTO operators (mdoc, name, start) VALUES (1,'Op','2025-01-01');
+                 INSERT INTO products (upc, desc, category, price, updated, added) VALUES
+                   ('KIT',  'Hygiene Kit', 'Hygiene', 500, '2025-01-01 00:00:00', '2025-01-01 00:00:00'),
+                   ('SOAP', 'Soap',        'Hygiene', 150, '2025-01-01 00:00:00', '2025-01-01 00:00:00'),
+                   ('TP',   'Toothpaste',  'Hygiene', 100, '2025-01-01 00:00:00', '2025-01-01 00:00:00');
+                 INSERT INTO inventory_transactions (upc, quantity_change, operator_mdoc, reason) VALUES
+                   ('SOAP', 7, 1, 'receipt'),
+                   ('TP',   5, 1, 'receipt');",
+            )
+            .unwrap();
+        let repo = SqliteProductRepo::new(Arc::clone(&conn));
+        {
+            let mut guard = conn.lock().unwrap();
+            let tx = guard.transaction().unwrap();
+            let part = |component_upc: &str, quantity| BundleComponent {
+                bundle_upc: "KIT".into(),
+                component_upc: component_upc.into(),
+                quantity,
+            };
+            repo.set_components_with_tx("KIT", &[part("SOAP", 2), part("TP", 1)], &tx)
+                .unwrap();
+            assert_eq!(
+                repo.bundles_containing_with_tx("TP", &tx).unwrap(),
+                vec!["KIT".to_string()]
+            );
+            tx.commit().unwrap();
+        }
+
+        let parts: Vec<_> = repo
+            .components("KIT")
+            .unwrap()
+            .into_iter()
+            .map(|(c, desc)| (c.component_upc, c.quantity, desc))
+            .collect();
+        assert_eq!(
+            parts,
+            vec![
+                ("SOAP".into(), 2, "Soap".into()),
+                ("TP".into(), 1, "Toothpaste".into())
+            ]
+        );
+
+        let available = |repo: &SqliteProductRepo| -> Vec<(String, i32)> {
+            repo.search(None, None, 10, 0)
+                .unwrap()
+                .into_iter()
+                .map(|(p, n)| (p.upc, n))
+                .collect()
+        };
+        // 7 soap makes 3 kits even though there's toothpaste for 5
+        let rows = available(&repo);
+        assert!(rows.contains(&("KIT".into(), 3)));
+        assert!(rows.contains(&("SOAP".into(), 7)));
+
+        conn.lock()
+            .unwrap()
+            .execute_batch(
+                "INSERT INTO inventory_transactions (upc, quantity_change, operator_mdoc, reason)
+                 VALUES ('TP', -6, 1, 'damage');",
+            )
+            .unwrap();
+        // a component below zero leaves none, not a negative count
+        assert!(available(&repo).contains(&("KIT".into(), 0)));
+    }
 }
@@ -0,0 +1,200 @@
+use crate::common::error::AppError;
+use crate::common::mutex_ext::MutexExt;
+use crate::domain::models::{Promotion, PromotionRule};
+use crate::domain::repos::PromotionRepoTrait;
+use chrono::NaiveDateTime;
+use rusqlite::{params, Connection, OptionalExtension};
+use std::sync::{Arc, Mutex};
+
+const COLUMNS: &str = "id, name, kind, value, buy_quantity, get_quantity, upc, category, \
+                       starts_at, ends_at, created_at, deleted";
+
+const RUNNING_SQL: &str = "
+    SELECT id, name, kind, value, buy_quantity, get_quantity, upc, category,
+           starts_at, ends_at, created_at, deleted
+    FROM promotions
+    WHERE deleted IS NULL
+      AND (starts_at IS NULL OR starts_at <= ?1)
+      AND (ends_at IS NULL OR ends_at > ?1)
+    ORDER BY id";
+
+pub struct SqlitePromotionRepo {
+    conn: Arc<Mutex<Connection>>,
+}
+
+impl SqlitePromotionRepo {
+    pub const fn new(conn: Arc<Mutex<Connection>>) -> Self {
+        Self { conn }
+    }
+}
+
+fn map_promotion(r: &rusqlite::Row<'_>) -> rusqlite::Result<Promotion> {
+    let kind: String = r.get(2)?;
+    let buy: Option<i32> = r.get(4)?;
+    let get: Option<i32> = r.get(5)?;
+    Ok(Promotion {
+        id: r.get(0)?,
+        name: r.get(1)?,
+        rule: PromotionRule::parse(&kind, r.get(3)?, buy.zip(get)).map_err(|e| {
+            rusqlite::Error::FromSqlConversionFailure(
+                2,
+                rusqlite::types::Type::Text,
+                Box::new(std::io::Error::other(e.to_string())),
+            )
+        })?,
+        upc: r.get(6)?,
+        category: r.get(7)?,
+        starts_at: r.get(8)?,
+        ends_at: r.get(9)?,
+        created_at: r.get(10)?,
+        deleted: r.get(11)?,
+    })
+}
+
+impl PromotionRepoTrait for SqlitePromotionRepo {
+    fn list(&self) -> Result<Vec<Promotion>, AppError> {
+        let conn = self.conn.safe_lock()?;
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {COLUMNS} FROM promotions WHERE deleted IS NULL
+             ORDER BY COALESCE(starts_at, created_at), id"
+        ))?;
+        let rows = stmt.query_map([], map_promotion)?;
+        rows.collect::<Result<_, _>>().map_err(Into::into)
+    }
+
+    fn get(&self, id: i32) -> Result<Option<Promotion>, AppError> {
+        let conn = self.conn.safe_lock()?;
+        conn.query_row(
+            &format!("SELECT {COLUMNS} FROM promotions WHERE id = ?1"),
+            params![id],
+            map_promotion,
+        )
+        .optional()
+        .map_err(Into::into)
+    }
+
+    fn create(&self, p: &Promotion) -> Result<i32, AppError> {
+        let conn = self.conn.safe_lock()?;
+        let buy_get = p.rule.buy_get();
+        conn.execute(
+            "INSERT INTO promotions
+             (name, kind, value, buy_quantity, get_quantity, upc, category, starts_at, ends_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            params![
+                p.name,
+                p.rule.as_str(),
+                p.rule.value(),
+                buy_get.map(|(buy, _)| buy),
+                buy_get.map(|(_, get)| get),
+                p.upc,
+                p.category,
+                p.starts_at,
+                p.ends_at
+            ],
+        )?;
+        Ok(conn.last_insert_rowid() as i32)
+    }
+
+    fn delete(&self, id: i32) -> Result<(), AppError> {
+        let conn = self.conn.safe_lock()?;
+        let changed = conn.execute(
+            "UPDATE promotions SET deleted = CURRENT_TIMESTAMP
+             WHERE id = ?1 AND deleted IS NULL",
+            params![id],
+        )?;
+        if changed == 0 {
+            return Err(AppError::NotFound(format!("promotion {id} not found")));
+        }
+        Ok(())
+    }
+
+    fn running(&self, now: NaiveDateTime) -> Result<Vec<Promotion>, AppError> {
+        let conn = self.conn.safe_lock()?;
+        let mut stmt = conn.prepare(RUNNING_SQL)?;
+        let rows = stmt.query_map(params![now], map_promotion)?;
+        rows.collect::<Result<_, _>>().map_err(Into::into)
+    }
+
+    fn running_with_tx(
+        &self,
+        now: NaiveDateTime,
+        tx: &rusqlite::Transaction<'_>,
+    ) -> Result<Vec<Promotion>, AppError> {
+        let mut stmt = tx.prepare(RUNNING_SQL)?;
+        let rows = stmt.query_map(params![now], map_promotion)?;
+        rows.collect::<Result<_, _>>().map_err(Into::into)
+    }
+}
+
+#[cfg(test)]
+mod repo_tests {
+    use super::*;
+    use crate::infrastructure::db::create_connection;
+
+    fn dt(s: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S").unwrap()
+    }
+
+    #[test]
+    fn running_promotions_respect_their_window() {
+        let conn = Arc::new(create_connection(":memory:").unwrap());
+        conn.lock()
+            .unwrap()
+            .execute_batch(
+                "INSERT INTO categories (name) VALUES ('Snacks');
+                 INSERT INTO products (upc, desc, category, price, updated, added) VALUES
+                   ('CHIPS', 'Chips', 'Snacks', 150, '2025-01-01 00:00:00', '2025-01-01 00:00:00');",
+            )
+            .unwrap();
+        let repo = SqlitePromotionRepo::new(conn);
+        let promo =
+            |name: &str, rule, upc: Option<&str>, window: (Option<&str>, Option<&str>)| Promotion {
+                id: 0,
+                name: name.into(),
+                rule,
+                upc: upc.map(Into::into),
+                category: upc.is_none().then(|| "Snacks".to_string()),
+                starts_at: window.0.map(dt),
+                ends_at: window.1.map(dt),
+                created_at: None,
+                deleted: None,
+            };
+        let holiday = repo
+            .create(&promo(
+                "Holiday chips",
+                PromotionRule::SalePrice(99),
+                Some("CHIPS"),
+                (Some("2025-12-20 00:00:00"), Some("2025-12-27 00:00:00")),
+            ))
+            .unwrap();
+        let b2g1 = repo
+            .create(&promo(
+                "Snacks 2+1",
+                PromotionRule::BuyGet { buy: 2, get: 1 },
+                None,
+                (None, None),
+            ))
+            .unwrap();
+
+        let ids = |at: &str| -> Vec<i32> {
+            repo.running(dt(at))
+                .unwrap()
+                .into_iter()
+                .map(|p| p.id)
+                .collect()
+        };
+        assert_eq!(ids("2025-12-01 12:00:00"), vec![b2g1]);
+        assert_eq!(ids("2025-12-24 12:00:00"), vec![holiday, b2g1]);
+        assert_eq!(ids("2025-12-27 00:00:00"), vec![b2g1]);
+
+        let saved = repo.get(b2g1).unwrap().unwrap();
+        assert_eq!(saved.rule, PromotionRule::BuyGet { buy: 2, get: 1 });
+        assert_eq!(saved.category.as_deref(), Some("Snacks"));
+
+        repo.delete(b2g1).unwrap();
+        assert!(ids("2025-12-01 12:00:00").is_empty());
+        assert_eq!(repo.list().unwrap().len(), 1);
+        assert!(repo.get(b2g1).unwrap().unwrap().deleted.is_some());
+        assert!(matches!(repo.delete(b2g1), Err(AppError::NotFound(_))));
+    }
+}
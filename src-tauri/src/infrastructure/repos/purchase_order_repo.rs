@@ -0,0 +1,292 @@
+use crate::common::error::AppError;
+use crate::common::mutex_ext::MutexExt;
+use crate::domain::models::{PurchaseOrder, PurchaseOrderLine, PurchaseOrderStatus};
+use crate::domain::repos::purchase_order_repo_trait::{PurchaseOrderLineRow, PurchaseOrderRow};
+use crate::domain::repos::PurchaseOrderRepoTrait;
+use chrono::NaiveDateTime;
+use rusqlite::{params, Connection, OptionalExtension};
+use std::sync::{Arc, Mutex};
+
+const COLUMNS: &str =
+    "po.id, po.vendor_id, po.reference, po.status, po.operator_mdoc, po.created_at, po.received_at";
+const LINE_COLUMNS: &str = "l.id, l.po_id, l.upc, l.expected_quantity, l.received_quantity";
+
+pub struct SqlitePurchaseOrderRepo {
+    conn: Arc<Mutex<Connection>>,
+}
+
+impl SqlitePurchaseOrderRepo {
+    pub const fn new(conn: Arc<Mutex<Connection>>) -> Self {
+        Self { conn }
+    }
+}
+
+fn map_po(r: &rusqlite::Row<'_>) -> rusqlite::Result<PurchaseOrder> {
+    let status: String = r.get(3)?;
+    Ok(PurchaseOrder {
+        id: r.get(0)?,
+        vendor_id: r.get(1)?,
+        reference: r.get(2)?,
+        status: PurchaseOrderStatus::parse(&status).map_err(|e| {
+            rusqlite::Error::FromSqlConversionFailure(
+                3,
+                rusqlite::types::Type::Text,
+                Box::new(std::io::Error::other(e.to_string())),
+            )
+        })?,
+        operator_mdoc: r.get(4)?,
+        created_at: r.get(5)?,
+        received_at: r.get(6)?,
+    })
+}
+
+fn map_line(r: &rusqlite::Row<'_>) -> rusqlite::Result<PurchaseOrderLine> {
+    Ok(PurchaseOrderLine {
+        id: r.get(0)?,
+        po_id: r.get(1)?,
+        upc: r.get(2)?,
+        expected_quantity: r.get(3)?,
+        received_quantity: r.get(4)?,
+    })
+}
+
+impl PurchaseOrderRepoTrait for SqlitePurchaseOrderRepo {
+    fn list(&self, status: Option<PurchaseOrderStatus>) -> Result<Vec<PurchaseOrderRow>, AppError> {
+        let conn = self.conn.safe_lock()?;
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {COLUMNS}, v.name
+             FROM purchase_orders po
+             JOIN vendors v ON v.id = po.vendor_id
+             WHERE ?1 IS NULL OR po.status = ?1
+             ORDER BY po.created_at DESC, po.id DESC"
+        ))?;
+        let rows = stmt.query_map(params![status.map(PurchaseOrderStatus::as_str)], |r| {
+            Ok((map_po(r)?, r.get(7)?))
+        })?;
+        rows.collect::<Result<_, _>>().map_err(Into::into)
+    }
+
+    fn get(&self, id: i32) -> Result<Option<PurchaseOrder>, AppError> {
+        let conn = self.conn.safe_lock()?;
+        let po = conn
+            .query_row(
+                &format!("SELECT {COLUMNS} FROM purchase_orders po WHERE po.id = ?1"),
+                params![id],
+                map_po,
+            )
+            .optional()?;
+        Ok(po)
+    }
+
+    fn lines(&self, po_id: i32) -> Result<Vec<PurchaseOrderLineRow>, AppError> {
+        let conn = self.conn.safe_lock()?;
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {LINE_COLUMNS}, p.desc
+             FROM purchase_order_lines l
+             JOIN products p ON p.upc = l.upc
+             WHERE l.po_id = ?1
+             ORDER BY l.id"
+        ))?;
+        let rows = stmt.query_map(params![po_id], |r| Ok((map_line(r)?, r.get(5)?)))?;
+        rows.collect::<Result<_, _>>().map_err(Into::into)
+    }
+
+    fn get_with_tx(
+        &self,
+        id: i32,
+        tx: &rusqlite::Transaction<'_>,
+    ) -> Result<Option<PurchaseOrder>, AppError> {
+        let po = tx
+            .query_row(
+                &format!("SELECT {COLUMNS} FROM purchase_orders po WHERE po.id = ?1"),
+                params![id],
+                map_po,
+            )
+            .optional()?;
+        Ok(po)
+    }
+
+    fn create_with_tx(
+        &self,
+        po: &PurchaseOrder,
+        tx: &rusqlite::Transaction<'_>,
+    ) -> Result<i32, AppError> {
+        tx.execute(
+            "INSERT INTO purchase_orders (vendor_id, reference, status, operator_mdoc)
+             VALUES (?1, ?2, ?3, ?4)",
+            params![
+                po.vendor_id,
+                po.reference,
+                po.status.as_str(),
+                po.operator_mdoc
+            ],
+        )?;
+        Ok(tx.last_insert_rowid() as i32)
+    }
+
+    fn add_line_with_tx(
+        &self,
+        line: &PurchaseOrderLine,
+        tx: &rusqlite::Transaction<'_>,
+    ) -> Result<i32, AppError> {
+        tx.execute(
+            "INSERT INTO purchase_order_lines (po_id, upc, expected_quantity, received_quantity)
+             VALUES (?1, ?2, ?3, ?4)",
+            params![
+                line.po_id,
+                line.upc,
+                line.expected_quantity,
+                line.received_quantity
+            ],
+        )?;
+        Ok(tx.last_insert_rowid() as i32)
+    }
+
+    fn lines_with_tx(
+        &self,
+        po_id: i32,
+        tx: &rusqlite::Transaction<'_>,
+    ) -> Result<Vec<PurchaseOrderLine>, AppError> {
+        let mut stmt = tx.prepare(&format!(
+            "SELECT {LINE_COLUMNS} FROM purchase_order_lines l WHERE l.po_id = ?1 ORDER BY l.id"
+        ))?;
+        let rows = stmt.query_map(params![po_id], map_line)?;
+        rows.collect::<Result<_, _>>().map_err(Into::into)
+    }
+
+    fn add_received_with_tx(
+        &self,
+        line_id: i32,
+        quantity: i32,
+        tx: &rusqlite::Transaction<'_>,
+    ) -> Result<(), AppError> {
+        let changed = tx.execute(
+            "UPDATE purchase_order_lines SET received_quantity = received_quantity + ?2
+             WHERE id = ?1",
+            params![line_id, quantity],
+        )?;
+        if changed == 0 {
+            return Err(AppError::NotFound(format!(
+                "purchase order line {line_id} not found"
+            )));
+        }
+        Ok(())
+    }
+
+    fn close_with_tx(
+        &self,
+        id: i32,
+        status: PurchaseOrderStatus,
+        received_at: Option<NaiveDateTime>,
+        tx: &rusqlite::Transaction<'_>,
+    ) -> Result<(), AppError> {
+        let changed = tx.execute(
+            "UPDATE purchase_orders SET status = ?2, received_at = ?3
+             WHERE id = ?1 AND status = 'open'",
+            params![id, status.as_str(), received_at],
+        )?;
+        if changed == 0 {
+            return match self.get_with_tx(id, tx)? {
+                None => Err(AppError::NotFound(format!("purchase order {id} not found"))),
+                Some(po) => Err(AppError::Validation(format!(
+                    "purchase order {id} is {}",
+                    po.status.as_str()
+                ))),
+            };
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod repo_tests {
+    use super::*;
+    use crate::infrastructure::db::create_connection;
+
+    #[test]
+    fn order_lines_and_receiving_round_trip() {
+        let conn = Arc::new(create_connection(":memory:").unwrap());
+        conn.lock()
+            .unwrap()
+            .execute_batch(
+                "INSERT INTO operators (mdoc, name, start) VALUES (1, 'Op', '2025-01-01');
+                 INSERT INTO vendors (id, name) VALUES (1, 'Acme'), (2, 'Bolt');
+                 INSERT INTO products (upc, desc, category, price, updated, added) VALUES
+                   ('SOAP',  'Soap',  'Hygiene', 100, '2025-01-01 00:00:00', '2025-01-01 00:00:00'),
+                   ('PASTE', 'Paste', 'Hygiene', 150, '2025-01-01 00:00:00', '2025-01-01 00:00:00');",
+            )
+            .unwrap();
+        let repo = SqlitePurchaseOrderRepo::new(Arc::clone(&conn));
+        let po = PurchaseOrder {
+            id: 0,
+            vendor_id: 1,
+            reference: Some("INV-77".into()),
+            status: PurchaseOrderStatus::Open,
+            operator_mdoc: 1,
+            created_at: None,
+            received_at: None,
+        };
+        let line = |po_id, upc: &str, expected| PurchaseOrderLine {
+            id: 0,
+            po_id,
+            upc: upc.into(),
+            expected_quantity: expected,
+            received_quantity: 0,
+        };
+
+        let (po_id, soap_id) = {
+            let mut guard = conn.lock().unwrap();
+            let tx = guard.transaction().unwrap();
+            let po_id = repo.create_with_tx(&po, &tx).unwrap();
+            let soap_id = repo
+                .add_line_with_tx(&line(po_id, "SOAP", 10), &tx)
+                .unwrap();
+            repo.add_line_with_tx(&line(po_id, "PASTE", 5), &tx)
+                .unwrap();
+            // one line per upc
+            assert!(repo.add_line_with_tx(&line(po_id, "SOAP", 1), &tx).is_err());
+            repo.add_received_with_tx(soap_id, 4, &tx).unwrap();
+            repo.add_received_with_tx(soap_id, 7, &tx).unwrap();
+            let lines = repo.lines_with_tx(po_id, &tx).unwrap();
+            assert_eq!(lines[0].received_quantity, 11);
+            assert_eq!(lines[0].variance(), 1);
+            assert_eq!(lines[1].variance(), -5);
+            tx.commit().unwrap();
+            (po_id, soap_id)
+        };
+
+        let lines = repo.lines(po_id).unwrap();
+        assert_eq!(lines.len(), 2);
+        assert_eq!((lines[0].0.id, lines[0].1.as_str()), (soap_id, "Soap"));
+
+        let open = repo.list(Some(PurchaseOrderStatus::Open)).unwrap();
+        assert_eq!(open.len(), 1);
+        assert_eq!(open[0].1, "Acme");
+        assert_eq!(open[0].0.reference.as_deref(), Some("INV-77"));
+
+        {
+            let mut guard = conn.lock().unwrap();
+            let tx = guard.transaction().unwrap();
+            repo.close_with_tx(po_id, PurchaseOrderStatus::Cancelled, None, &tx)
+                .unwrap();
+            assert!(matches!(
+                repo.close_with_tx(po_id, PurchaseOrderStatus::Received, None, &tx),
+                Err(AppError::Validation(_))
+            ));
+            assert!(matches!(
+                repo.close_with_tx(99, PurchaseOrderStatus::Cancelled, None, &tx),
+                Err(AppError::NotFound(_))
+            ));
+            tx.commit().unwrap();
+        }
+        assert!(repo
+            .list(Some(PurchaseOrderStatus::Open))
+            .unwrap()
+            .is_empty());
+        assert_eq!(repo.list(None).unwrap().len(), 1);
+        assert_eq!(
+            repo.get(po_id).unwrap().unwrap().status,
+            PurchaseOrderStatus::Cancelled
+        );
+    }
+}
@@ -0,0 +1,209 @@
+use crate::common::error::AppError;
+use crate::common::mutex_ext::MutexExt;
+use crate::domain::models::{QuantityLimit, QuantityWindow};
+use crate::domain::repos::QuantityLimitRepoTrait;
+use chrono::NaiveDateTime;
+use rusqlite::{params, Connection};
+use std::sync::{Arc, Mutex};
+
+const COLUMNS: &str = "id, upc, category, max_quantity, period, rolling_days, note, created_at";
+
+// Net units of a upc (?2) or of any product in a category (?3) bought in [?4, ?5).
+// Voids and returns are treated as in the spending total: voided orders and their
+// reversals drop out, and returns count against the original sale's date.
+const PURCHASED_SQL: &str = "
+    SELECT COALESCE(SUM(d.quantity), 0)
+    FROM customer_transactions t
+    JOIN customer_tx_detail d ON t.order_id = d.order_id
+    JOIN products p ON p.upc = d.upc
+    LEFT JOIN order_returns r ON r.return_order_id = t.order_id
+    LEFT JOIN customer_transactions o ON o.order_id = r.order_id
+    WHERE t.customer_mdoc = ?1
+      AND (d.upc = ?2 OR p.category = ?3)
+      AND COALESCE(o.date, t.date) >= ?4
+      AND COALESCE(o.date, t.date) < ?5
+      AND t.order_id NOT IN (SELECT order_id FROM order_voids)
+      AND t.order_id NOT IN (SELECT reversal_order_id FROM order_voids)";
+
+pub struct SqliteQuantityLimitRepo {
+    conn: Arc<Mutex<Connection>>,
+}
+
+impl SqliteQuantityLimitRepo {
+    pub const fn new(conn: Arc<Mutex<Connection>>) -> Self {
+        Self { conn }
+    }
+}
+
+fn map_limit(r: &rusqlite::Row<'_>) -> rusqlite::Result<QuantityLimit> {
+    let period: String = r.get(4)?;
+    Ok(QuantityLimit {
+        id: r.get(0)?,
+        upc: r.get(1)?,
+        category: r.get(2)?,
+        max_quantity: r.get(3)?,
+        window: QuantityWindow::parse(&period, r.get(5)?).map_err(|e| {
+            rusqlite::Error::FromSqlConversionFailure(
+                4,
+                rusqlite::types::Type::Text,
+                Box::new(std::io::Error::other(e.to_string())),
+            )
+        })?,
+        note: r.get(6)?,
+        created_at: r.get(7)?,
+    })
+}
+
+impl QuantityLimitRepoTrait for SqliteQuantityLimitRepo {
+    fn list(&self) -> Result<Vec<QuantityLimit>, AppError> {
+        let conn = self.conn.safe_lock()?;
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {COLUMNS} FROM quantity_limits ORDER BY category, upc, id"
+        ))?;
+        let rows = stmt.query_map([], map_limit)?;
+        rows.collect::<Result<_, _>>().map_err(Into::into)
+    }
+
+    fn create(&self, limit: &QuantityLimit) -> Result<i32, AppError> {
+        let conn = self.conn.safe_lock()?;
+        conn.execute(
+            "INSERT INTO quantity_limits (upc, category, max_quantity, period, rolling_days, note)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                limit.upc,
+                limit.category,
+                limit.max_quantity,
+                limit.window.as_str(),
+                limit.window.rolling_days(),
+                limit.note
+            ],
+        )?;
+        Ok(conn.last_insert_rowid() as i32)
+    }
+
+    fn delete(&self, id: i32) -> Result<(), AppError> {
+        let conn = self.conn.safe_lock()?;
+        let changed = conn.execute("DELETE FROM quantity_limits WHERE id = ?1", params![id])?;
+        if changed == 0 {
+            return Err(AppError::NotFound(format!("quantity limit {id} not found")));
+        }
+        Ok(())
+    }
+
+    fn purchased(
+        &self,
+        customer_mdoc: i32,
+        limit: &QuantityLimit,
+        start: NaiveDateTime,
+        end: NaiveDateTime,
+    ) -> Result<i32, AppError> {
+        let conn = self.conn.safe_lock()?;
+        let n: i32 = conn.query_row(
+            PURCHASED_SQL,
+            params![customer_mdoc, limit.upc, limit.category, start, end],
+            |r| r.get(0),
+        )?;
+        Ok(n)
+    }
+
+    fn purchased_with_tx(
+        &self,
+        customer_mdoc: i32,
+        limit: &QuantityLimit,
+        start: NaiveDateTime,
+        end: NaiveDateTime,
+        tx: &rusqlite::Transaction<'_>,
+    ) -> Result<i32, AppError> {
+        let n: i32 = tx.query_row(
+            PURCHASED_SQL,
+            params![customer_mdoc, limit.upc, limit.category, start, end],
+            |r| r.get(0),
+        )?;
+        Ok(n)
+    }
+}
+
+#[cfg(test)]
+mod repo_tests {
+    use super::*;
+    use crate::domain::models::LimitPeriod;
+    use crate::infrastructure::db::create_connection;
+
+    fn dt(s: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S").unwrap()
+    }
+
+    #[test]
+    fn purchased_counts_upc_or_category_net_of_returns_and_voids() {
+        let conn = Arc::new(create_connection(":memory:").unwrap());
+        conn.lock()
+            .unwrap()
+            .execute_batch(
+                "INSERT INTO categories (name) VALUES ('Hygiene');
+                 INSERT INTO products (upc, desc, category, price, updated, added) VALUES
+                   ('SOAP',  'Soap',  'Hygiene',    100, '2025-01-01 00:00:00', '2025-01-01 00:00:00'),
+                   ('PASTE', 'Paste', 'Hygiene',    150, '2025-01-01 00:00:00', '2025-01-01 00:00:00'),
+                   ('FAN',   'Fan',   'Appliances', 2500, '2025-01-01 00:00:00', '2025-01-01 00:00:00');
+                 INSERT INTO customer_transactions (order_id, customer_mdoc, operator_mdoc, date) VALUES
+                   (1, 7, 1, '2025-03-03 10:00:00'),
+                   (2, 7, 1, '2025-03-04 10:00:00'),
+                   (3, 7, 1, '2025-03-12 10:00:00'),
+                   (4, 7, 1, '2025-03-05 10:00:00'),
+                   (5, 7, 1, '2025-03-05 11:00:00'),
+                   (6, 8, 1, '2025-03-03 10:00:00');
+                 INSERT INTO customer_tx_detail (order_id, upc, quantity, price) VALUES
+                   (1, 'SOAP', 2, 100), (1, 'FAN', 1, 2500),
+                   (2, 'PASTE', 3, 150),
+                   (3, 'PASTE', -1, 150),
+                   (4, 'SOAP', 5, 100),
+                   (5, 'SOAP', -5, 100),
+                   (6, 'SOAP', 9, 100);
+                 -- order 3 returns a paste from order 2 a week later
+                 INSERT INTO order_returns (return_order_id, order_id, operator_mdoc) VALUES (3, 2, 1);
+                 INSERT INTO order_voids (order_id, reversal_order_id, operator_mdoc, reason)
+                   VALUES (4, 5, 1, 'wrong customer');",
+            )
+            .unwrap();
+        let repo = SqliteQuantityLimitRepo::new(Arc::clone(&conn));
+
+        let hygiene = QuantityLimit {
+            id: 0,
+            upc: None,
+            category: Some("Hygiene".into()),
+            max_quantity: 4,
+            window: QuantityWindow::Calendar(LimitPeriod::Weekly),
+            note: None,
+            created_at: None,
+        };
+        let fans = QuantityLimit {
+            upc: Some("FAN".into()),
+            category: None,
+            max_quantity: 2,
+            window: QuantityWindow::Rolling(365),
+            ..hygiene.clone()
+        };
+        repo.create(&hygiene).unwrap();
+        let fan_id = repo.create(&fans).unwrap();
+        let listed = repo.list().unwrap();
+        assert_eq!(listed.len(), 2);
+        assert!(listed
+            .iter()
+            .any(|l| l.window == QuantityWindow::Rolling(365)));
+
+        let (start, end) = (dt("2025-03-02 00:00:00"), dt("2025-03-09 00:00:00"));
+        // 2 soap + 3 paste - 1 returned paste; the voided soap order is ignored
+        assert_eq!(repo.purchased(7, &hygiene, start, end).unwrap(), 4);
+        assert_eq!(repo.purchased(7, &fans, start, end).unwrap(), 1);
+        assert_eq!(repo.purchased(8, &fans, start, end).unwrap(), 0);
+
+        // a category rule may not also name a upc
+        let both = QuantityLimit {
+            upc: Some("SOAP".into()),
+            ..hygiene
+        };
+        assert!(repo.create(&both).is_err());
+
+        repo.delete(fan_id).unwrap();
+        assert!(matches!(repo.delete(fan_id), Err(AppError::NotFound(_))));
+    }
+}
@@ -0,0 +1,303 @@
+use crate::common::error::AppError;
+use crate::common::mutex_ext::MutexExt;
+use crate::domain::models::{StockTake, StockTakeLine, StockTakeStatus};
+use crate::domain::repos::stock_take_repo_trait::StockTakeLineRow;
+use crate::domain::repos::StockTakeRepoTrait;
+use chrono::NaiveDateTime;
+use rusqlite::{params, Connection, OptionalExtension};
+use std::sync::{Arc, Mutex};
+
+const COLUMNS: &str =
+    "s.id, s.category, s.blind, s.status, s.note, s.operator_mdoc, s.created_at, s.posted_at";
+const LINE_COLUMNS: &str =
+    "l.id, l.stock_take_id, l.upc, l.expected_quantity, l.counted_quantity, l.counted_at";
+
+pub struct SqliteStockTakeRepo {
+    conn: Arc<Mutex<Connection>>,
+}
+
+impl SqliteStockTakeRepo {
+    pub const fn new(conn: Arc<Mutex<Connection>>) -> Self {
+        Self { conn }
+    }
+}
+
+fn map_stock_take(r: &rusqlite::Row<'_>) -> rusqlite::Result<StockTake> {
+    let status: String = r.get(3)?;
+    Ok(StockTake {
+        id: r.get(0)?,
+        category: r.get(1)?,
+        blind: r.get(2)?,
+        status: StockTakeStatus::parse(&status).map_err(|e| {
+            rusqlite::Error::FromSqlConversionFailure(
+                3,
+                rusqlite::types::Type::Text,
+                Box::new(std::io::Error::other(e.to_string())),
+            )
+        })?,
+        note: r.get(4)?,
+        operator_mdoc: r.get(5)?,
+        created_at: r.get(6)?,
+        posted_at: r.get(7)?,
+    })
+}
+
+fn map_line(r: &rusqlite::Row<'_>) -> rusqlite::Result<StockTakeLine> {
+    Ok(StockTakeLine {
+        id: r.get(0)?,
+        stock_take_id: r.get(1)?,
+        upc: r.get(2)?,
+        expected_quantity: r.get(3)?,
+        counted_quantity: r.get(4)?,
+        counted_at: r.get(5)?,
+    })
+}
+
+impl StockTakeRepoTrait for SqliteStockTakeRepo {
+    fn list(&self, status: Option<StockTakeStatus>) -> Result<Vec<StockTake>, AppError> {
+        let conn = self.conn.safe_lock()?;
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {COLUMNS}
+             FROM stock_takes s
+             WHERE ?1 IS NULL OR s.status = ?1
+             ORDER BY s.created_at DESC, s.id DESC"
+        ))?;
+        let rows = stmt.query_map(params![status.map(StockTakeStatus::as_str)], map_stock_take)?;
+        rows.collect::<Result<_, _>>().map_err(Into::into)
+    }
+
+    fn get(&self, id: i32) -> Result<Option<StockTake>, AppError> {
+        let conn = self.conn.safe_lock()?;
+        let stock_take = conn
+            .query_row(
+                &format!("SELECT {COLUMNS} FROM stock_takes s WHERE s.id = ?1"),
+                params![id],
+                map_stock_take,
+            )
+            .optional()?;
+        Ok(stock_take)
+    }
+
+    fn lines(&self, stock_take_id: i32) -> Result<Vec<StockTakeLineRow>, AppError> {
+        let conn = self.conn.safe_lock()?;
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {LINE_COLUMNS}, p.desc, p.price
+             FROM stock_take_lines l
+             JOIN products p ON p.upc = l.upc
+             WHERE l.stock_take_id = ?1
+             ORDER BY p.category, p.desc, l.upc"
+        ))?;
+        let rows = stmt.query_map(params![stock_take_id], |r| {
+            Ok((map_line(r)?, r.get(6)?, r.get(7)?))
+        })?;
+        rows.collect::<Result<_, _>>().map_err(Into::into)
+    }
+
+    fn create_with_tx(
+        &self,
+        stock_take: &StockTake,
+        tx: &rusqlite::Transaction<'_>,
+    ) -> Result<i32, AppError> {
+        tx.execute(
+            "INSERT INTO stock_takes (category, blind, status, note, operator_mdoc)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                stock_take.category,
+                stock_take.blind,
+                stock_take.status.as_str(),
+                stock_take.note,
+                stock_take.operator_mdoc
+            ],
+        )?;
+        Ok(tx.last_insert_rowid() as i32)
+    }
+
+    fn add_line_with_tx(
+        &self,
+        line: &StockTakeLine,
+        tx: &rusqlite::Transaction<'_>,
+    ) -> Result<i32, AppError> {
+        tx.execute(
+            "INSERT INTO stock_take_lines
+               (stock_take_id, upc, expected_quantity, counted_quantity, counted_at)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                line.stock_take_id,
+                line.upc,
+                line.expected_quantity,
+                line.counted_quantity,
+                line.counted_at
+            ],
+        )?;
+        Ok(tx.last_insert_rowid() as i32)
+    }
+
+    fn lines_with_tx(
+        &self,
+        stock_take_id: i32,
+        tx: &rusqlite::Transaction<'_>,
+    ) -> Result<Vec<StockTakeLine>, AppError> {
+        let mut stmt = tx.prepare(&format!(
+            "SELECT {LINE_COLUMNS} FROM stock_take_lines l
+             WHERE l.stock_take_id = ?1 ORDER BY l.id"
+        ))?;
+        let rows = stmt.query_map(params![stock_take_id], map_line)?;
+        rows.collect::<Result<_, _>>().map_err(Into::into)
+    }
+
+    fn record_count_with_tx(
+        &self,
+        stock_take_id: i32,
+        upc: &str,
+        counted_quantity: i32,
+        counted_at: NaiveDateTime,
+        tx: &rusqlite::Transaction<'_>,
+    ) -> Result<(), AppError> {
+        let changed = tx.execute(
+            "UPDATE stock_take_lines SET counted_quantity = ?3, counted_at = ?4
+             WHERE stock_take_id = ?1 AND upc = ?2
+               AND EXISTS (SELECT 1 FROM stock_takes WHERE id = ?1 AND status = 'open')",
+            params![stock_take_id, upc, counted_quantity, counted_at],
+        )?;
+        if changed == 0 {
+            require_open_with_tx(stock_take_id, tx)?;
+            return Err(AppError::NotFound(format!(
+                "upc {upc} is not on stock take {stock_take_id}"
+            )));
+        }
+        Ok(())
+    }
+
+    fn close_with_tx(
+        &self,
+        id: i32,
+        status: StockTakeStatus,
+        posted_at: Option<NaiveDateTime>,
+        tx: &rusqlite::Transaction<'_>,
+    ) -> Result<(), AppError> {
+        let changed = tx.execute(
+            "UPDATE stock_takes SET status = ?2, posted_at = ?3
+             WHERE id = ?1 AND status = 'open'",
+            params![id, status.as_str(), posted_at],
+        )?;
+        if changed == 0 {
+            require_open_with_tx(id, tx)?;
+        }
+        Ok(())
+    }
+}
+
+// Explains why a write guarded on an open stock take changed nothing.
+fn require_open_with_tx(id: i32, tx: &rusqlite::Transaction<'_>) -> Result<(), AppError> {
+    let status: Option<String> = tx
+        .query_row(
+            "SELECT status FROM stock_takes WHERE id = ?1",
+            params![id],
+            |r| r.get(0),
+        )
+        .optional()?;
+    match status.as_deref() {
+        None => Err(AppError::NotFound(format!("stock take {id} not found"))),
+        Some("open") => Ok(()),
+        Some(other) => Err(AppError::Validation(format!("stock take {id} is {other}"))),
+    }
+}
+
+#[cfg(test)]
+mod repo_tests {
+    use super::*;
+    use crate::infrastructure::db::create_connection;
+
+    #[test]
+    fn sheet_counts_and_status_round_trip() {
+        let conn = Arc::new(create_connection(":memory:").unwrap());
+        conn.lock()
+            .unwrap()
+            .execute_batch(
+                "INSERT INTO operators (mdoc, name, start) VALUES (1, 'Op', '2025-01-01');
+                 INSERT INTO products (upc, desc, category, price, updated, added) VALUES
+                   ('SOAP',  'Soap',  'Hygiene', 100, '2025-01-01 00:00:00', '2025-01-01 00:00:00'),
+                   ('PASTE', 'Paste', 'Hygiene', 150, '2025-01-01 00:00:00', '2025-01-01 00:00:00');",
+            )
+            .unwrap();
+        let repo = SqliteStockTakeRepo::new(Arc::clone(&conn));
+        let session = StockTake {
+            id: 0,
+            category: Some("Hygiene".into()),
+            blind: true,
+            status: StockTakeStatus::Open,
+            note: Some("Q1 count".into()),
+            operator_mdoc: 1,
+            created_at: None,
+            posted_at: None,
+        };
+        let line = |id, upc: &str, expected| StockTakeLine {
+            id: 0,
+            stock_take_id: id,
+            upc: upc.into(),
+            expected_quantity: expected,
+            counted_quantity: None,
+            counted_at: None,
+        };
+        let counted_at =
+            NaiveDateTime::parse_from_str("2025-03-01 09:30:00", "%Y-%m-%d %H:%M:%S").unwrap();
+
+        let id = {
+            let mut guard = conn.lock().unwrap();
+            let tx = guard.transaction().unwrap();
+            let id = repo.create_with_tx(&session, &tx).unwrap();
+            repo.add_line_with_tx(&line(id, "SOAP", 12), &tx).unwrap();
+            repo.add_line_with_tx(&line(id, "PASTE", 4), &tx).unwrap();
+            // one line per upc
+            assert!(repo.add_line_with_tx(&line(id, "SOAP", 1), &tx).is_err());
+            repo.record_count_with_tx(id, "SOAP", 9, counted_at, &tx)
+                .unwrap();
+            // a recount replaces the first count
+            repo.record_count_with_tx(id, "SOAP", 10, counted_at, &tx)
+                .unwrap();
+            assert!(matches!(
+                repo.record_count_with_tx(id, "COMB", 1, counted_at, &tx),
+                Err(AppError::NotFound(_))
+            ));
+            let lines = repo.lines_with_tx(id, &tx).unwrap();
+            assert_eq!(lines[0].variance(), Some(-2));
+            assert_eq!(lines[1].variance(), None);
+            tx.commit().unwrap();
+            id
+        };
+
+        // sheet order is by product description, with the price joined in
+        let lines = repo.lines(id).unwrap();
+        assert_eq!(lines.len(), 2);
+        assert_eq!((lines[0].1.as_str(), lines[0].2), ("Paste", 150));
+        assert_eq!(lines[1].0.counted_quantity, Some(10));
+        assert_eq!(lines[1].0.counted_at, Some(counted_at));
+
+        let stored = repo.get(id).unwrap().unwrap();
+        assert!(stored.blind);
+        assert_eq!(stored.category.as_deref(), Some("Hygiene"));
+        assert_eq!(repo.list(Some(StockTakeStatus::Open)).unwrap().len(), 1);
+
+        let mut conn = conn.lock().unwrap();
+        let tx = conn.transaction().unwrap();
+        repo.close_with_tx(id, StockTakeStatus::Cancelled, None, &tx)
+            .unwrap();
+        // once closed, neither a second close nor a late count gets through
+        assert!(matches!(
+            repo.close_with_tx(id, StockTakeStatus::Posted, None, &tx),
+            Err(AppError::Validation(_))
+        ));
+        assert!(matches!(
+            repo.record_count_with_tx(id, "SOAP", 1, counted_at, &tx),
+            Err(AppError::Validation(_))
+        ));
+        assert!(matches!(
+            repo.close_with_tx(99, StockTakeStatus::Cancelled, None, &tx),
+            Err(AppError::NotFound(_))
+        ));
+        tx.commit().unwrap();
+        drop(conn);
+        assert!(repo.list(Some(StockTakeStatus::Open)).unwrap().is_empty());
+        assert_eq!(repo.list(None).unwrap().len(), 1);
+    }
+}
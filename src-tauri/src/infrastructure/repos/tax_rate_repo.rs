@@ -0,0 +1,190 @@
+use crate::common::error::AppError;
+use crate::common::mutex_ext::MutexExt;
+use crate::domain::models::{TaxRate, TaxSettings};
+use crate::domain::repos::TaxRateRepoTrait;
+use chrono::NaiveDateTime;
+use rusqlite::{params, Connection};
+use std::sync::{Arc, Mutex};
+
+const COLUMNS: &str = "id, upc, category, rate, effective_at, created_at, deleted";
+
+const IN_EFFECT_SQL: &str = "
+    SELECT id, upc, category, rate, effective_at, created_at, deleted
+    FROM tax_rates
+    WHERE deleted IS NULL
+      AND (effective_at IS NULL OR effective_at <= ?1)
+    ORDER BY COALESCE(effective_at, created_at), id";
+
+const SETTINGS_SQL: &str = "SELECT counts_toward_limit FROM tax_settings";
+
+pub struct SqliteTaxRateRepo {
+    conn: Arc<Mutex<Connection>>,
+}
+
+impl SqliteTaxRateRepo {
+    pub const fn new(conn: Arc<Mutex<Connection>>) -> Self {
+        Self { conn }
+    }
+}
+
+fn map_tax_rate(r: &rusqlite::Row<'_>) -> rusqlite::Result<TaxRate> {
+    Ok(TaxRate {
+        id: r.get(0)?,
+        upc: r.get(1)?,
+        category: r.get(2)?,
+        rate: r.get(3)?,
+        effective_at: r.get(4)?,
+        created_at: r.get(5)?,
+        deleted: r.get(6)?,
+    })
+}
+
+fn map_settings(r: &rusqlite::Row<'_>) -> rusqlite::Result<TaxSettings> {
+    Ok(TaxSettings {
+        counts_toward_limit: r.get(0)?,
+    })
+}
+
+impl TaxRateRepoTrait for SqliteTaxRateRepo {
+    fn list(&self) -> Result<Vec<TaxRate>, AppError> {
+        let conn = self.conn.safe_lock()?;
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {COLUMNS} FROM tax_rates WHERE deleted IS NULL
+             ORDER BY COALESCE(category, upc), COALESCE(effective_at, created_at), id"
+        ))?;
+        let rows = stmt.query_map([], map_tax_rate)?;
+        rows.collect::<Result<_, _>>().map_err(Into::into)
+    }
+
+    fn create(&self, rate: &TaxRate) -> Result<i32, AppError> {
+        let conn = self.conn.safe_lock()?;
+        conn.execute(
+            "INSERT INTO tax_rates (upc, category, rate, effective_at)
+             VALUES (?1, ?2, ?3, ?4)",
+            params![rate.upc, rate.category, rate.rate, rate.effective_at],
+        )?;
+        Ok(conn.last_insert_rowid() as i32)
+    }
+
+    fn delete(&self, id: i32) -> Result<(), AppError> {
+        let conn = self.conn.safe_lock()?;
+        let changed = conn.execute(
+            "UPDATE tax_rates SET deleted = CURRENT_TIMESTAMP
+             WHERE id = ?1 AND deleted IS NULL",
+            params![id],
+        )?;
+        if changed == 0 {
+            return Err(AppError::NotFound(format!("tax rate {id} not found")));
+        }
+        Ok(())
+    }
+
+    fn in_effect(&self, now: NaiveDateTime) -> Result<Vec<TaxRate>, AppError> {
+        let conn = self.conn.safe_lock()?;
+        let mut stmt = conn.prepare(IN_EFFECT_SQL)?;
+        let rows = stmt.query_map(params![now], map_tax_rate)?;
+        rows.collect::<Result<_, _>>().map_err(Into::into)
+    }
+
+    fn in_effect_with_tx(
+        &self,
+        now: NaiveDateTime,
+        tx: &rusqlite::Transaction<'_>,
+    ) -> Result<Vec<TaxRate>, AppError> {
+        let mut stmt = tx.prepare(IN_EFFECT_SQL)?;
+        let rows = stmt.query_map(params![now], map_tax_rate)?;
+        rows.collect::<Result<_, _>>().map_err(Into::into)
+    }
+
+    fn get_settings(&self) -> Result<TaxSettings, AppError> {
+        let conn = self.conn.safe_lock()?;
+        conn.query_row(SETTINGS_SQL, [], map_settings)
+            .map_err(Into::into)
+    }
+
+    fn set_settings(&self, settings: &TaxSettings) -> Result<(), AppError> {
+        let conn = self.conn.safe_lock()?;
+        conn.execute(
+            "UPDATE tax_settings SET counts_toward_limit = ?1",
+            params![settings.counts_toward_limit],
+        )?;
+        Ok(())
+    }
+
+    fn get_settings_with_tx(
+        &self,
+        tx: &rusqlite::Transaction<'_>,
+    ) -> Result<TaxSettings, AppError> {
+        tx.query_row(SETTINGS_SQL, [], map_settings)
+            .map_err(Into::into)
+    }
+}
+
+#[cfg(test)]
+mod repo_tests {
+    use super::*;
+    use crate::infrastructure::db::create_connection;
+
+    fn dt(s: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S").unwrap()
+    }
+
+    #[test]
+    fn rates_take_effect_in_order_and_settings_round_trip() {
+        let conn = Arc::new(create_connection(":memory:").unwrap());
+        conn.lock()
+            .unwrap()
+            .execute_batch(
+                "INSERT INTO categories (name) VALUES ('Snacks');
+                 INSERT INTO products (upc, desc, category, price, updated, added) VALUES
+                   ('CHIPS', 'Chips', 'Snacks', 150, '2025-01-01 00:00:00', '2025-01-01 00:00:00');",
+            )
+            .unwrap();
+        let repo = SqliteTaxRateRepo::new(conn);
+        let rate = |upc: Option<&str>, rate, effective_at: Option<&str>| TaxRate {
+            id: 0,
+            upc: upc.map(Into::into),
+            category: upc.is_none().then(|| "Snacks".to_string()),
+            rate,
+            effective_at: effective_at.map(dt),
+            created_at: None,
+            deleted: None,
+        };
+        let old = repo
+            .create(&rate(None, 600, Some("2025-01-01 00:00:00")))
+            .unwrap();
+        let new = repo
+            .create(&rate(None, 700, Some("2025-07-01 00:00:00")))
+            .unwrap();
+        let exempt = repo.create(&rate(Some("CHIPS"), 0, None)).unwrap();
+
+        let ids = |at: &str| -> Vec<i32> {
+            repo.in_effect(dt(at))
+                .unwrap()
+                .into_iter()
+                .map(|r| r.id)
+                .collect()
+        };
+        // an unscheduled rate is already in effect and sorts by when it was entered
+        assert_eq!(ids("2025-03-01 00:00:00"), vec![old, exempt]);
+        assert_eq!(ids("2025-07-01 00:00:00"), vec![old, new, exempt]);
+        assert!(repo
+            .create(&TaxRate {
+                category: Some("Snacks".into()),
+                ..rate(Some("CHIPS"), 500, None)
+            })
+            .is_err());
+
+        repo.delete(new).unwrap();
+        assert_eq!(ids("2025-07-01 00:00:00"), vec![old, exempt]);
+        assert_eq!(repo.list().unwrap().len(), 2);
+        assert!(matches!(repo.delete(new), Err(AppError::NotFound(_))));
+
+        assert_eq!(repo.get_settings().unwrap(), TaxSettings::default());
+        let settings = TaxSettings {
+            counts_toward_limit: false,
+        };
+        repo.set_settings(&settings).unwrap();
+        assert_eq!(repo.get_settings().unwrap(), settings);
+    }
+}
@@ -0,0 +1,126 @@
+use crate::common::error::AppError;
+use crate::common::mutex_ext::MutexExt;
+use crate::domain::models::Vendor;
+use crate::domain::repos::VendorRepoTrait;
+use rusqlite::{params, Connection, OptionalExtension};
+use std::sync::{Arc, Mutex};
+
+const COLUMNS: &str = "id, name, contact, phone, deleted, created_at";
+
+pub struct SqliteVendorRepo {
+    conn: Arc<Mutex<Connection>>,
+}
+
+impl SqliteVendorRepo {
+    pub const fn new(conn: Arc<Mutex<Connection>>) -> Self {
+        Self { conn }
+    }
+}
+
+fn map_vendor(r: &rusqlite::Row<'_>) -> rusqlite::Result<Vendor> {
+    Ok(Vendor {
+        id: r.get(0)?,
+        name: r.get(1)?,
+        contact: r.get(2)?,
+        phone: r.get(3)?,
+        deleted: r.get(4)?,
+        created_at: r.get(5)?,
+    })
+}
+
+impl VendorRepoTrait for SqliteVendorRepo {
+    fn list(&self) -> Result<Vec<Vendor>, AppError> {
+        let conn = self.conn.safe_lock()?;
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {COLUMNS} FROM vendors WHERE deleted IS NULL ORDER BY name"
+        ))?;
+        let rows = stmt.query_map([], map_vendor)?;
+        rows.collect::<Result<_, _>>().map_err(Into::into)
+    }
+
+    fn get(&self, id: i32) -> Result<Option<Vendor>, AppError> {
+        let conn = self.conn.safe_lock()?;
+        let vendor = conn
+            .query_row(
+                &format!("SELECT {COLUMNS} FROM vendors WHERE id = ?1"),
+                params![id],
+                map_vendor,
+            )
+            .optional()?;
+        Ok(vendor)
+    }
+
+    fn create(&self, vendor: &Vendor) -> Result<i32, AppError> {
+        let conn = self.conn.safe_lock()?;
+        conn.execute(
+            "INSERT INTO vendors (name, contact, phone) VALUES (?1, ?2, ?3)",
+            params![vendor.name, vendor.contact, vendor.phone],
+        )?;
+        Ok(conn.last_insert_rowid() as i32)
+    }
+
+    fn update(&self, vendor: &Vendor) -> Result<(), AppError> {
+        let conn = self.conn.safe_lock()?;
+        let changed = conn.execute(
+            "UPDATE vendors SET name = ?2, contact = ?3, phone = ?4
+             WHERE id = ?1 AND deleted IS NULL",
+            params![vendor.id, vendor.name, vendor.contact, vendor.phone],
+        )?;
+        if changed == 0 {
+            return Err(AppError::NotFound(format!(
+                "vendor {} not found",
+                vendor.id
+            )));
+        }
+        Ok(())
+    }
+
+    fn delete(&self, id: i32) -> Result<(), AppError> {
+        let conn = self.conn.safe_lock()?;
+        let changed = conn.execute(
+            "UPDATE vendors SET deleted = CURRENT_TIMESTAMP WHERE id = ?1 AND deleted IS NULL",
+            params![id],
+        )?;
+        if changed == 0 {
+            return Err(AppError::NotFound(format!("vendor {id} not found")));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod repo_tests {
+    use super::*;
+    use crate::infrastructure::db::create_connection;
+
+    #[test]
+    fn vendor_crud_and_soft_delete() {
+        let conn = Arc::new(create_connection(":memory:").unwrap());
+        let repo = SqliteVendorRepo::new(Arc::clone(&conn));
+        let acme = Vendor {
+            id: 0,
+            name: "Acme Wholesale".into(),
+            contact: Some("Pat".into()),
+            phone: None,
+            deleted: None,
+            created_at: None,
+        };
+        let id = repo.create(&acme).unwrap();
+        assert!(repo.create(&acme).is_err(), "names are unique");
+
+        repo.update(&Vendor {
+            id,
+            phone: Some("555-0100".into()),
+            ..acme
+        })
+        .unwrap();
+        let got = repo.get(id).unwrap().unwrap();
+        assert_eq!(got.phone.as_deref(), Some("555-0100"));
+        assert!(got.created_at.is_some());
+
+        repo.delete(id).unwrap();
+        assert!(repo.list().unwrap().is_empty());
+        assert!(repo.get(id).unwrap().unwrap().deleted.is_some());
+        assert!(matches!(repo.delete(id), Err(AppError::NotFound(_))));
+    }
+}
@@ -1,6 +1,8 @@
 use crate::common::error::AppError;
 use crate::common::mutex_ext::MutexExt;
+use crate::domain::models::{LimitPeriod, LimitSettings};
 use crate::domain::repos::WeeklyLimitRepoTrait;
+use chrono::{NaiveDate, Weekday};
 use rusqlite::{params, Connection};
 use std::sync::{Arc, Mutex};
 
@@ -14,6 +16,14 @@ impl SqliteWeeklyLimitRepo {
     }
 }
 
+// week_start is stored as days from Sunday
+fn weekday_from_sunday(n: u32) -> Result<Weekday, AppError> {
+    if n > 6 {
+        return Err(AppError::Unexpected(format!("invalid week_start: {n}")));
+    }
+    Ok((0..n).fold(Weekday::Sun, |d, _| d.succ()))
+}
+
 impl WeeklyLimitRepoTrait for SqliteWeeklyLimitRepo {
     fn set_limit(&self, amount: i32) -> Result<(), AppError> {
         let conn = self.conn.safe_lock()?;
@@ -30,4 +40,56 @@ impl WeeklyLimitRepoTrait for SqliteWeeklyLimitRepo {
         conn.query_row("SELECT amount FROM weekly_limit", [], |row| row.get(0))
             .map_err(Into::into)
     }
+
+    fn get_settings(&self) -> Result<LimitSettings, AppError> {
+        let conn = self.conn.safe_lock()?;
+        let (period, week_start, anchor_date, time_zone): (String, u32, NaiveDate, String) = conn
+            .query_row(
+            "SELECT period, week_start, anchor_date, time_zone FROM limit_settings",
+            [],
+            |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?, r.get(3)?)),
+        )?;
+        Ok(LimitSettings {
+            period: LimitPeriod::parse(&period)?,
+            week_start: weekday_from_sunday(week_start)?,
+            anchor_date,
+            time_zone,
+        })
+    }
+
+    fn set_settings(&self, settings: &LimitSettings) -> Result<(), AppError> {
+        let conn = self.conn.safe_lock()?;
+        conn.execute(
+            "UPDATE limit_settings
+             SET period = ?1, week_start = ?2, anchor_date = ?3, time_zone = ?4",
+            params![
+                settings.period.as_str(),
+                settings.week_start.num_days_from_sunday(),
+                settings.anchor_date,
+                settings.time_zone,
+            ],
+        )?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod repo_tests {
+    use super::*;
+    use crate::infrastructure::db::create_connection;
+
+    #[test]
+    fn settings_default_and_round_trip() {
+        let repo = SqliteWeeklyLimitRepo::new(Arc::new(create_connection(":memory:").unwrap()));
+        assert_eq!(repo.get_settings().unwrap(), LimitSettings::default());
+
+        let settings = LimitSettings {
+            period: LimitPeriod::BiWeekly,
+            week_start: Weekday::Sat,
+            anchor_date: NaiveDate::from_ymd_opt(2025, 2, 1).unwrap(),
+            time_zone: "America/Denver".into(),
+        };
+        repo.set_settings(&settings).unwrap();
+        assert_eq!(repo.get_settings().unwrap(), settings);
+    }
 }
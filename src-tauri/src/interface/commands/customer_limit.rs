@@ -0,0 +1,111 @@
+use crate::common::error::AppError;
+use crate::interface::controllers::customer_limit_controller::CustomerLimitController;
+use crate::interface::dto::customer_limit_dto::{
+    CustomerTierDto, LimitOverrideDto, LimitSettingsDto, QuantityLimitDto,
+};
+use std::sync::Arc;
+use tauri::State;
+
+#[tauri::command]
+pub fn list_customer_tiers(
+    ctrl: State<'_, Arc<CustomerLimitController>>,
+) -> Result<Vec<CustomerTierDto>, AppError> {
+    ctrl.list_tiers()
+}
+
+#[tauri::command]
+pub fn create_customer_tier(
+    ctrl: State<'_, Arc<CustomerLimitController>>,
+    dto: CustomerTierDto,
+) -> Result<i32, AppError> {
+    ctrl.create_tier(dto)
+}
+
+#[tauri::command]
+pub fn update_customer_tier(
+    ctrl: State<'_, Arc<CustomerLimitController>>,
+    dto: CustomerTierDto,
+) -> Result<(), AppError> {
+    ctrl.update_tier(dto)
+}
+
+#[tauri::command]
+pub fn delete_customer_tier(
+    ctrl: State<'_, Arc<CustomerLimitController>>,
+    id: i32,
+) -> Result<(), AppError> {
+    ctrl.delete_tier(id)
+}
+
+// Assign a customer to a tier, or clear it with `tier_id: null`.
+#[tauri::command]
+pub fn set_customer_tier(
+    ctrl: State<'_, Arc<CustomerLimitController>>,
+    customer_mdoc: i32,
+    tier_id: Option<i32>,
+) -> Result<(), AppError> {
+    ctrl.set_customer_tier(customer_mdoc, tier_id)
+}
+
+#[tauri::command]
+pub fn list_limit_overrides(
+    ctrl: State<'_, Arc<CustomerLimitController>>,
+    customer_mdoc: i32,
+) -> Result<Vec<LimitOverrideDto>, AppError> {
+    ctrl.list_overrides(customer_mdoc)
+}
+
+#[tauri::command]
+pub fn create_limit_override(
+    ctrl: State<'_, Arc<CustomerLimitController>>,
+    dto: LimitOverrideDto,
+) -> Result<i32, AppError> {
+    ctrl.create_override(dto)
+}
+
+#[tauri::command]
+pub fn delete_limit_override(
+    ctrl: State<'_, Arc<CustomerLimitController>>,
+    id: i32,
+) -> Result<(), AppError> {
+    ctrl.delete_override(id)
+}
+
+#[tauri::command]
+pub fn get_limit_settings(
+    ctrl: State<'_, Arc<CustomerLimitController>>,
+) -> Result<LimitSettingsDto, AppError> {
+    ctrl.get_settings()
+}
+
+// Limit period, week start day and facility time zone.
+#[tauri::command]
+pub fn set_limit_settings(
+    ctrl: State<'_, Arc<CustomerLimitController>>,
+    dto: LimitSettingsDto,
+) -> Result<(), AppError> {
+    ctrl.set_settings(dto)
+}
+
+#[tauri::command]
+pub fn list_quantity_limits(
+    ctrl: State<'_, Arc<CustomerLimitController>>,
+) -> Result<Vec<QuantityLimitDto>, AppError> {
+    ctrl.list_quantity_limits()
+}
+
+#[tauri::command]
+pub fn create_quantity_limit(
+    ctrl: State<'_, Arc<CustomerLimitController>>,
+    dto: QuantityLimitDto,
+) -> Result<i32, AppError> {
+    ctrl.create_quantity_limit(dto)
+}
+
+#[tauri::command]
+pub fn delete_quantity_limit(
+    ctrl: State<'_, Arc<CustomerLimitController>>,
+    id: i32,
+) -> Result<(), AppError> {
+    ctrl.delete_quantity_limit(id)
+}
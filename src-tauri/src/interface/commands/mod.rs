@@ -1,10 +1,15 @@
 pub mod auth;
 pub mod club;
+pub mod customer_limit;
 pub mod legacy_migration;
 pub mod operator;
 pub mod parse_pdf;
 pub mod pos;
 pub mod printer;
 pub mod product;
+pub mod promotion;
+pub mod purchasing;
 pub mod stats;
+pub mod stock_take;
+pub mod tax;
 pub mod transaction;
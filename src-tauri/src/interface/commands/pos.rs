@@ -6,8 +6,5 @@ use tauri::State;
 
 #[tauri::command]
 pub fn pos_init(controller: State<Arc<PosController>>) -> Result<PosDto, AppError> {
-    controller.pos_init().map(|(products, customers)| PosDto {
-        products,
-        customers,
-    })
+    controller.pos_init()
 }
@@ -1,6 +1,6 @@
 use crate::common::error::AppError;
 use crate::interface::controllers::printer_controller::PrinterController;
-use crate::interface::dto::printer_dto::PrinterDto;
+use crate::interface::dto::printer_dto::{PrintJobDto, PrinterDto};
 use std::sync::Arc;
 use tauri::State;
 
@@ -39,6 +39,16 @@ pub fn print_product_catalog(
     controller.print_product_catalog(printer_name, sumatra_location)
 }
 
+// Products at or below their reorder point, by category and vendor.
+#[tauri::command]
+pub fn print_reorder_report(
+    controller: State<'_, Arc<PrinterController>>,
+    printer_name: String,
+    sumatra_location: String,
+) -> Result<(), AppError> {
+    controller.print_reorder_report(printer_name, sumatra_location)
+}
+
 #[tauri::command]
 pub fn print_sales_detail_report(
     controller: State<'_, Arc<PrinterController>>,
@@ -61,6 +71,18 @@ pub fn print_product_sales_by_category(
     controller.print_product_sales_by_category(start_date, end_date, printer_name, sumatra_location)
 }
 
+// Revenue, cost of goods sold and margin % per category.
+#[tauri::command]
+pub fn print_margin_report(
+    controller: State<'_, Arc<PrinterController>>,
+    start_date: String,
+    end_date: String,
+    printer_name: String,
+    sumatra_location: String,
+) -> Result<(), AppError> {
+    controller.print_margin_report(start_date, end_date, printer_name, sumatra_location)
+}
+
 #[tauri::command]
 pub fn print_daily_sales_report(
     controller: State<'_, Arc<PrinterController>>,
@@ -82,3 +104,28 @@ pub fn print_club_import(
 ) -> Result<(), AppError> {
     controller.print_club_import(import_id, start_date, printer_name, sumatra_location)
 }
+
+// Recent print jobs, newest first; `status` filters to one state.
+#[tauri::command]
+pub fn list_print_jobs(
+    controller: State<'_, Arc<PrinterController>>,
+    status: Option<String>,
+) -> Result<Vec<PrintJobDto>, AppError> {
+    controller.list_print_jobs(status)
+}
+
+#[tauri::command]
+pub fn retry_print_job(
+    controller: State<'_, Arc<PrinterController>>,
+    id: i32,
+) -> Result<(), AppError> {
+    controller.retry_print_job(id)
+}
+
+#[tauri::command]
+pub fn cancel_print_job(
+    controller: State<'_, Arc<PrinterController>>,
+    id: i32,
+) -> Result<(), AppError> {
+    controller.cancel_print_job(id)
+}
@@ -1,11 +1,15 @@
 use crate::common::error::AppError;
 use crate::interface::controllers::product_controller::ProductController;
-use crate::interface::dto::category_dto::{CategoryDto, CreateCategoryDto, DeleteCategoryDto};
+use crate::interface::dto::category_dto::{
+    CategoryDto, CreateCategoryDto, DeleteCategoryDto, SetCategoryLimitExemptDto,
+};
 use crate::interface::dto::price_adjustment_dto::{
-    PriceAdjustmentDto, PriceAdjustmentSearchResult,
+    BulkPriceChangeDto, BulkPriceLineDto, PriceAdjustmentDto, PriceAdjustmentSearchResult,
 };
 use crate::interface::dto::product_dto::{
-    CreateProductDto, DeleteProductDto, ProductSearchResult, UpdateProductDto,
+    BarcodeAliasDto, BundleComponentDto, CreateProductDto, DeleteProductDto, ImportProductsDto,
+    LowStockDto, ProductImportPreviewDto, ProductSearchResult, SetBundleComponentsDto,
+    SetProductLimitExemptDto, SetReorderPointDto, UpdateProductDto,
 };
 use std::sync::Arc;
 use tauri::State;
@@ -34,6 +38,39 @@ pub fn price_adjustment(
     controller.price_adjustment(dto)
 }
 
+// Scheduled price changes that haven't taken effect yet.
+#[tauri::command]
+pub fn list_pending_price_changes(
+    controller: State<Arc<ProductController>>,
+) -> Result<Vec<PriceAdjustmentDto>, AppError> {
+    controller.list_pending_price_changes()
+}
+
+#[tauri::command]
+pub fn cancel_price_change(
+    controller: State<Arc<ProductController>>,
+    id: i32,
+) -> Result<(), AppError> {
+    controller.cancel_price_change(id)
+}
+
+// Old and new prices a bulk change would give, without saving anything.
+#[tauri::command]
+pub fn preview_bulk_price_change(
+    controller: State<Arc<ProductController>>,
+    dto: BulkPriceChangeDto,
+) -> Result<Vec<BulkPriceLineDto>, AppError> {
+    controller.preview_bulk_price_change(dto)
+}
+
+#[tauri::command]
+pub fn bulk_price_change(
+    controller: State<Arc<ProductController>>,
+    dto: BulkPriceChangeDto,
+) -> Result<Vec<PriceAdjustmentDto>, AppError> {
+    controller.bulk_price_change(dto)
+}
+
 #[tauri::command]
 pub fn list_price_adjust(
     controller: State<Arc<ProductController>>,
@@ -93,3 +130,101 @@ pub fn create_category(
 ) -> Result<(), AppError> {
     controller.create_category(dto)
 }
+
+#[tauri::command]
+pub fn set_category_limit_exempt(
+    controller: State<Arc<ProductController>>,
+    dto: SetCategoryLimitExemptDto,
+) -> Result<(), AppError> {
+    controller.set_category_limit_exempt(dto)
+}
+
+#[tauri::command]
+pub fn set_product_limit_exempt(
+    controller: State<Arc<ProductController>>,
+    dto: SetProductLimitExemptDto,
+) -> Result<(), AppError> {
+    controller.set_product_limit_exempt(dto)
+}
+
+// None clears the reorder point so the product never shows as low on stock.
+#[tauri::command]
+pub fn set_product_reorder_point(
+    controller: State<Arc<ProductController>>,
+    dto: SetReorderPointDto,
+) -> Result<(), AppError> {
+    controller.set_reorder_point(dto)
+}
+
+// Products at or below their reorder point, by category and vendor.
+#[tauri::command]
+pub fn list_low_stock(
+    controller: State<Arc<ProductController>>,
+) -> Result<Vec<LowStockDto>, AppError> {
+    controller.list_low_stock()
+}
+
+// Alternate and case barcodes that scan as this product.
+#[tauri::command]
+pub fn list_product_barcodes(
+    controller: State<Arc<ProductController>>,
+    upc: String,
+) -> Result<Vec<BarcodeAliasDto>, AppError> {
+    controller.list_product_barcodes(upc)
+}
+
+#[tauri::command]
+pub fn add_product_barcode(
+    controller: State<Arc<ProductController>>,
+    dto: BarcodeAliasDto,
+) -> Result<(), AppError> {
+    controller.add_product_barcode(dto)
+}
+
+#[tauri::command]
+pub fn remove_product_barcode(
+    controller: State<Arc<ProductController>>,
+    barcode: String,
+) -> Result<(), AppError> {
+    controller.remove_product_barcode(barcode)
+}
+
+// A kit's components and how many of each it holds.
+#[tauri::command]
+pub fn get_bundle_components(
+    controller: State<Arc<ProductController>>,
+    upc: String,
+) -> Result<Vec<BundleComponentDto>, AppError> {
+    controller.get_bundle_components(upc)
+}
+
+#[tauri::command]
+pub fn set_bundle_components(
+    controller: State<Arc<ProductController>>,
+    dto: SetBundleComponentsDto,
+) -> Result<(), AppError> {
+    controller.set_bundle_components(dto)
+}
+
+// Dry run of a catalog CSV: what it would create, restore and update, and
+// which rows fail.
+#[tauri::command]
+pub fn preview_product_import(
+    controller: State<Arc<ProductController>>,
+    csv: String,
+) -> Result<ProductImportPreviewDto, AppError> {
+    controller.preview_product_import(csv)
+}
+
+#[tauri::command]
+pub fn import_products(
+    controller: State<Arc<ProductController>>,
+    dto: ImportProductsDto,
+) -> Result<ProductImportPreviewDto, AppError> {
+    controller.import_products(dto)
+}
+
+#[tauri::command]
+pub fn export_products_csv(controller: State<Arc<ProductController>>) -> Result<String, AppError> {
+    controller.export_products_csv()
+}
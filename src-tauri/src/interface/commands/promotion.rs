@@ -0,0 +1,38 @@
+use crate::common::error::AppError;
+use crate::interface::controllers::promotion_controller::PromotionController;
+use crate::interface::dto::promotion_dto::{LineDiscountDto, PromotionDto, QuoteLineDto};
+use std::sync::Arc;
+use tauri::State;
+
+#[tauri::command]
+pub fn list_promotions(
+    ctrl: State<'_, Arc<PromotionController>>,
+) -> Result<Vec<PromotionDto>, AppError> {
+    ctrl.list_promotions()
+}
+
+#[tauri::command]
+pub fn create_promotion(
+    ctrl: State<'_, Arc<PromotionController>>,
+    dto: PromotionDto,
+) -> Result<i32, AppError> {
+    ctrl.create_promotion(dto)
+}
+
+#[tauri::command]
+pub fn delete_promotion(
+    ctrl: State<'_, Arc<PromotionController>>,
+    id: i32,
+) -> Result<(), AppError> {
+    ctrl.delete_promotion(id)
+}
+
+// What the running promotions take off the cart, for display before checkout;
+// the sale itself is re-priced on the backend.
+#[tauri::command]
+pub fn quote_promotions(
+    ctrl: State<'_, Arc<PromotionController>>,
+    lines: Vec<QuoteLineDto>,
+) -> Result<Vec<LineDiscountDto>, AppError> {
+    ctrl.quote_promotions(lines)
+}
@@ -0,0 +1,87 @@
+use crate::common::error::AppError;
+use crate::interface::controllers::purchasing_controller::PurchasingController;
+use crate::interface::dto::purchasing_dto::{
+    CreatePurchaseOrderDto, PurchaseOrderDto, ReceivePurchaseOrderDto, ReceivingReportDto,
+    VendorDto,
+};
+use std::sync::Arc;
+use tauri::State;
+
+#[tauri::command]
+pub fn list_vendors(
+    ctrl: State<'_, Arc<PurchasingController>>,
+) -> Result<Vec<VendorDto>, AppError> {
+    ctrl.list_vendors()
+}
+
+#[tauri::command]
+pub fn create_vendor(
+    ctrl: State<'_, Arc<PurchasingController>>,
+    dto: VendorDto,
+) -> Result<i32, AppError> {
+    ctrl.create_vendor(dto)
+}
+
+#[tauri::command]
+pub fn update_vendor(
+    ctrl: State<'_, Arc<PurchasingController>>,
+    dto: VendorDto,
+) -> Result<(), AppError> {
+    ctrl.update_vendor(dto)
+}
+
+#[tauri::command]
+pub fn delete_vendor(ctrl: State<'_, Arc<PurchasingController>>, id: i32) -> Result<(), AppError> {
+    ctrl.delete_vendor(id)
+}
+
+#[tauri::command]
+pub fn create_purchase_order(
+    ctrl: State<'_, Arc<PurchasingController>>,
+    dto: CreatePurchaseOrderDto,
+) -> Result<i32, AppError> {
+    ctrl.create_purchase_order(dto)
+}
+
+// All purchase orders, or only those with `status` (open | received | cancelled).
+#[tauri::command]
+pub fn list_purchase_orders(
+    ctrl: State<'_, Arc<PurchasingController>>,
+    status: Option<String>,
+) -> Result<Vec<PurchaseOrderDto>, AppError> {
+    ctrl.list_purchase_orders(status)
+}
+
+#[tauri::command]
+pub fn cancel_purchase_order(
+    ctrl: State<'_, Arc<PurchasingController>>,
+    po_id: i32,
+) -> Result<(), AppError> {
+    ctrl.cancel_purchase_order(po_id)
+}
+
+#[tauri::command]
+pub fn get_receiving_report(
+    ctrl: State<'_, Arc<PurchasingController>>,
+    po_id: i32,
+) -> Result<ReceivingReportDto, AppError> {
+    ctrl.get_receiving_report(po_id)
+}
+
+#[tauri::command]
+pub fn receive_purchase_order(
+    ctrl: State<'_, Arc<PurchasingController>>,
+    dto: ReceivePurchaseOrderDto,
+) -> Result<ReceivingReportDto, AppError> {
+    ctrl.receive_purchase_order(dto)
+}
+
+#[tauri::command]
+pub fn print_receiving_report(
+    ctrl: State<'_, Arc<PurchasingController>>,
+    po_id: i32,
+    printer_name: String,
+    sumatra_location: String,
+) -> Result<(), AppError> {
+    ctrl.print_receiving_report(po_id, printer_name, sumatra_location)
+}
@@ -0,0 +1,77 @@
+use crate::common::error::AppError;
+use crate::interface::controllers::stock_take_controller::StockTakeController;
+use crate::interface::dto::stock_take_dto::{
+    CountSheetDto, PostStockTakeDto, RecordCountsDto, StartStockTakeDto, StockTakeDto,
+    VarianceReportDto,
+};
+use std::sync::Arc;
+use tauri::State;
+
+// Freezes expected quantities and opens a count; returns the session id.
+#[tauri::command]
+pub fn start_stock_take(
+    ctrl: State<'_, Arc<StockTakeController>>,
+    dto: StartStockTakeDto,
+) -> Result<i32, AppError> {
+    ctrl.start_stock_take(dto)
+}
+
+// All stock takes, or only those with `status` (open | posted | cancelled).
+#[tauri::command]
+pub fn list_stock_takes(
+    ctrl: State<'_, Arc<StockTakeController>>,
+    status: Option<String>,
+) -> Result<Vec<StockTakeDto>, AppError> {
+    ctrl.list_stock_takes(status)
+}
+
+#[tauri::command]
+pub fn get_count_sheet(
+    ctrl: State<'_, Arc<StockTakeController>>,
+    stock_take_id: i32,
+) -> Result<CountSheetDto, AppError> {
+    ctrl.get_count_sheet(stock_take_id)
+}
+
+#[tauri::command]
+pub fn record_stock_counts(
+    ctrl: State<'_, Arc<StockTakeController>>,
+    dto: RecordCountsDto,
+) -> Result<(), AppError> {
+    ctrl.record_counts(dto)
+}
+
+#[tauri::command]
+pub fn cancel_stock_take(
+    ctrl: State<'_, Arc<StockTakeController>>,
+    stock_take_id: i32,
+) -> Result<(), AppError> {
+    ctrl.cancel_stock_take(stock_take_id)
+}
+
+#[tauri::command]
+pub fn get_stock_take_variance(
+    ctrl: State<'_, Arc<StockTakeController>>,
+    stock_take_id: i32,
+) -> Result<VarianceReportDto, AppError> {
+    ctrl.get_variance_report(stock_take_id)
+}
+
+// Posts every counted variance as an inventory adjustment and closes the count.
+#[tauri::command]
+pub fn post_stock_take(
+    ctrl: State<'_, Arc<StockTakeController>>,
+    dto: PostStockTakeDto,
+) -> Result<VarianceReportDto, AppError> {
+    ctrl.post_stock_take(dto)
+}
+
+#[tauri::command]
+pub fn print_stock_take_variance(
+    ctrl: State<'_, Arc<StockTakeController>>,
+    stock_take_id: i32,
+    printer_name: String,
+    sumatra_location: String,
+) -> Result<(), AppError> {
+    ctrl.print_variance_report(stock_take_id, printer_name, sumatra_location)
+}
@@ -0,0 +1,38 @@
+use crate::common::error::AppError;
+use crate::interface::controllers::tax_controller::TaxController;
+use crate::interface::dto::tax_dto::{TaxRateDto, TaxSettingsDto};
+use std::sync::Arc;
+use tauri::State;
+
+#[tauri::command]
+pub fn list_tax_rates(ctrl: State<'_, Arc<TaxController>>) -> Result<Vec<TaxRateDto>, AppError> {
+    ctrl.list_tax_rates()
+}
+
+// A rate for a category, or for one product to override its category's.
+#[tauri::command]
+pub fn create_tax_rate(
+    ctrl: State<'_, Arc<TaxController>>,
+    dto: TaxRateDto,
+) -> Result<i32, AppError> {
+    ctrl.create_tax_rate(dto)
+}
+
+#[tauri::command]
+pub fn delete_tax_rate(ctrl: State<'_, Arc<TaxController>>, id: i32) -> Result<(), AppError> {
+    ctrl.delete_tax_rate(id)
+}
+
+#[tauri::command]
+pub fn get_tax_settings(ctrl: State<'_, Arc<TaxController>>) -> Result<TaxSettingsDto, AppError> {
+    ctrl.get_settings()
+}
+
+// Whether tax counts toward the spending limit.
+#[tauri::command]
+pub fn set_tax_settings(
+    ctrl: State<'_, Arc<TaxController>>,
+    dto: TaxSettingsDto,
+) -> Result<(), AppError> {
+    ctrl.set_settings(dto)
+}
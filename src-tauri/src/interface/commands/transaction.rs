@@ -1,10 +1,17 @@
 use crate::common::error::AppError;
 use crate::interface::controllers::transaction_controller::TransactionController;
-use crate::interface::dto::customer_transaction_dto::CustomerTransactionSearchResult;
+use crate::interface::dto::customer_limit_dto::{CustomerLimitDto, ItemAllowanceDto};
+use crate::interface::dto::customer_transaction_dto::{
+    CustomerTransactionSearchResult, VoidOrderDto,
+};
 use crate::interface::dto::customer_tx_detail_dto::CustomerTxDetailDto;
 use crate::interface::dto::inventory_transaction_dto::{
-    CreateInventoryTransactionDto, InventoryTransactionSearchResult, ReadInventoryTransactionDto,
+    CreateInventoryTransactionDto, ExpiringLotDto, InventorySettingsDto,
+    InventoryTransactionSearchResult, ReadInventoryTransactionDto, ShrinkageReportDto,
+    WriteOffExpiredDto,
 };
+use crate::interface::dto::printer_dto::ReceiptCopies;
+use crate::interface::dto::return_dto::ReturnDto;
 use crate::interface::dto::sale_dto::SaleDto;
 use std::sync::Arc;
 use tauri::State;
@@ -28,15 +35,116 @@ pub fn sale_transaction(
     Ok(order_id)
 }
 
+#[tauri::command]
+pub fn void_order(
+    controller: State<Arc<TransactionController>>,
+    dto: VoidOrderDto,
+) -> Result<i32, AppError> {
+    controller.void_order(dto)
+}
+
+#[tauri::command]
+pub fn return_items(
+    controller: State<Arc<TransactionController>>,
+    dto: ReturnDto,
+    receipt_printer: String,
+    sumatra_location: String,
+) -> Result<i32, AppError> {
+    controller.return_items(dto, &receipt_printer, &sumatra_location)
+}
+
+#[tauri::command]
+pub fn reprint_receipt(
+    controller: State<Arc<TransactionController>>,
+    order_id: i32,
+    copies: Option<ReceiptCopies>,
+    operator_mdoc: i32,
+    receipt_printer: String,
+    sumatra_location: String,
+) -> Result<i32, AppError> {
+    controller.reprint_receipt(
+        order_id,
+        copies.unwrap_or_default(),
+        operator_mdoc,
+        &receipt_printer,
+        &sumatra_location,
+    )
+}
+
 #[tauri::command]
 pub fn search_inventory_transactions(
     controller: State<Arc<TransactionController>>,
     page: Option<i32>,
     date: Option<String>,
     search: Option<String>,
+    reason: Option<String>,
 ) -> Result<InventoryTransactionSearchResult, AppError> {
     let page = page.unwrap_or(1);
-    controller.search_inventory_transactions(page, date, search)
+    controller.search_inventory_transactions(page, date, search, reason)
+}
+
+// Whether sales and adjustments may take stock below zero on hand.
+#[tauri::command]
+pub fn get_inventory_settings(
+    controller: State<'_, Arc<TransactionController>>,
+) -> Result<InventorySettingsDto, AppError> {
+    controller.get_inventory_settings()
+}
+
+#[tauri::command]
+pub fn set_inventory_settings(
+    controller: State<'_, Arc<TransactionController>>,
+    dto: InventorySettingsDto,
+) -> Result<(), AppError> {
+    controller.set_inventory_settings(dto)
+}
+
+// Stock written off by reason and category over a date range.
+#[tauri::command]
+pub fn get_shrinkage_report(
+    controller: State<'_, Arc<TransactionController>>,
+    start_date: String,
+    end_date: String,
+) -> Result<ShrinkageReportDto, AppError> {
+    controller.get_shrinkage_report(start_date, end_date)
+}
+
+#[tauri::command]
+pub fn print_shrinkage_report(
+    controller: State<'_, Arc<TransactionController>>,
+    start_date: String,
+    end_date: String,
+    printer_name: String,
+    sumatra_location: String,
+) -> Result<(), AppError> {
+    controller.print_shrinkage_report(start_date, end_date, printer_name, sumatra_location)
+}
+
+// Lots with stock left that expire within `days`, already-expired ones first.
+#[tauri::command]
+pub fn get_expiring_lots(
+    controller: State<'_, Arc<TransactionController>>,
+    days: i64,
+) -> Result<Vec<ExpiringLotDto>, AppError> {
+    controller.get_expiring_lots(days)
+}
+
+#[tauri::command]
+pub fn print_expiring_lots(
+    controller: State<'_, Arc<TransactionController>>,
+    days: i64,
+    printer_name: String,
+    sumatra_location: String,
+) -> Result<(), AppError> {
+    controller.print_expiring_lots(days, printer_name, sumatra_location)
+}
+
+#[tauri::command]
+pub fn write_off_expired_lots(
+    controller: State<'_, Arc<TransactionController>>,
+    dto: WriteOffExpiredDto,
+) -> Result<Vec<ReadInventoryTransactionDto>, AppError> {
+    controller.write_off_expired_lots(dto)
 }
 
 #[tauri::command]
@@ -79,3 +187,21 @@ pub fn get_weekly_spent(
 ) -> Result<i32, AppError> {
     controller.get_weekly_spent(customer_mdoc)
 }
+
+#[tauri::command]
+pub fn get_customer_limit(
+    controller: State<Arc<TransactionController>>,
+    customer_mdoc: i32,
+) -> Result<CustomerLimitDto, AppError> {
+    controller.get_customer_limit(customer_mdoc)
+}
+
+// Remaining quantity-limit allowance per item for the selected customer.
+#[tauri::command]
+pub fn get_item_allowances(
+    controller: State<Arc<TransactionController>>,
+    customer_mdoc: i32,
+    upcs: Vec<String>,
+) -> Result<Vec<ItemAllowanceDto>, AppError> {
+    controller.get_item_allowances(customer_mdoc, upcs)
+}
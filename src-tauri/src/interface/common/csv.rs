@@ -0,0 +1,132 @@
+use crate::common::error::AppError;
+
+// Splits CSV text into records, each with the 1-based line it starts on.
+// Handles quoted fields (with "" escapes and embedded newlines) and skips
+// blank lines.
+pub fn parse_csv(text: &str) -> Result<Vec<(usize, Vec<String>)>, AppError> {
+    let mut records = vec![];
+    let mut fields = vec![];
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut line = 1;
+    let mut start = 1;
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes && chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            '"' if in_quotes => in_quotes = false,
+            '"' if field.is_empty() => in_quotes = true,
+            '\n' if in_quotes => {
+                line += 1;
+                field.push('\n');
+            }
+            ',' if !in_quotes => fields.push(std::mem::take(&mut field)),
+            '\r' if !in_quotes && chars.peek() == Some(&'\n') => {}
+            '\n' => {
+                fields.push(std::mem::take(&mut field));
+                if fields.iter().any(|f| !f.trim().is_empty()) {
+                    records.push((start, std::mem::take(&mut fields)));
+                }
+                fields.clear();
+                line += 1;
+                start = line;
+            }
+            c => field.push(c),
+        }
+    }
+    if in_quotes {
+        return Err(AppError::Validation(format!(
+            "unterminated quoted field starting on line {start}"
+        )));
+    }
+    fields.push(field);
+    if fields.iter().any(|f| !f.trim().is_empty()) {
+        records.push((start, fields));
+    }
+    Ok(records)
+}
+
+// One CSV record, quoting fields that need it.
+pub fn csv_row(fields: &[&str]) -> String {
+    fields
+        .iter()
+        .map(|f| {
+            if f.contains([',', '"', '\n', '\r']) {
+                format!("\"{}\"", f.replace('"', "\"\""))
+            } else {
+                (*f).to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+// "1.25", "$1.25" or "3" dollars to cents; None for anything else.
+pub fn parse_dollars(s: &str) -> Option<i32> {
+    let s = s.trim();
+    let s = s.strip_prefix('$').unwrap_or(s);
+    let (whole, frac) = s.split_once('.').unwrap_or((s, ""));
+    if whole.is_empty() && frac.is_empty()
+        || frac.len() > 2
+        || !whole
+            .chars()
+            .chain(frac.chars())
+            .all(|c| c.is_ascii_digit())
+    {
+        return None;
+    }
+    let whole: i32 = if whole.is_empty() {
+        0
+    } else {
+        whole.parse().ok()?
+    };
+    let frac: i32 = format!("{frac:0<2}").parse().ok()?;
+    whole.checked_mul(100)?.checked_add(frac)
+}
+
+// Cents as plain dollars, e.g. 125 -> "1.25", for files that get re-imported.
+pub fn format_dollars(cents: i32) -> String {
+    let sign = if cents < 0 { "-" } else { "" };
+    let cents = cents.unsigned_abs();
+    format!("{sign}{}.{:02}", cents / 100, cents % 100)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quoted_fields_and_line_numbers() {
+        let text =
+            "upc,desc\r\n\n00000001,\"Soap, \"\"fresh\"\"\"\n00000002,\"two\nlines\"\n00000003,x";
+        let records = parse_csv(text).unwrap();
+        let lines: Vec<_> = records.iter().map(|(line, _)| *line).collect();
+        assert_eq!(lines, vec![1, 3, 4, 6]);
+        assert_eq!(records[1].1, vec!["00000001", "Soap, \"fresh\""]);
+        assert_eq!(records[2].1[1], "two\nlines");
+        assert!(parse_csv("a,\"open").is_err());
+
+        let row = csv_row(&["00000001", "Soap, \"fresh\"", "1.25"]);
+        assert_eq!(row, "00000001,\"Soap, \"\"fresh\"\"\",1.25");
+        assert_eq!(
+            parse_csv(&row).unwrap()[0].1,
+            vec!["00000001", "Soap, \"fresh\"", "1.25"]
+        );
+    }
+
+    #[test]
+    fn dollar_amounts() {
+        assert_eq!(parse_dollars("1.25"), Some(125));
+        assert_eq!(parse_dollars(" $3 "), Some(300));
+        assert_eq!(parse_dollars("0.5"), Some(50));
+        assert_eq!(parse_dollars(".99"), Some(99));
+        for bad in ["", "$", "1.234", "1,25", "-1", "abc", "99999999999"] {
+            assert_eq!(parse_dollars(bad), None, "{bad}");
+        }
+        assert_eq!(format_dollars(125), "1.25");
+        assert_eq!(format_dollars(7), "0.07");
+    }
+}
@@ -1,5 +1,5 @@
 use crate::common::error::AppError;
-use chrono::{DateTime, FixedOffset, NaiveDateTime};
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime};
 
 /// Parse a required RFC3339 string into a local `NaiveDateTime`.
 pub fn parse_rfc3339(s: &str) -> Result<NaiveDateTime, AppError> {
@@ -8,6 +8,13 @@ pub fn parse_rfc3339(s: &str) -> Result<NaiveDateTime, AppError> {
         .map(|dt| dt.naive_local())
 }
 
+/// Parse a required RFC3339 string into a UTC `NaiveDateTime`.
+pub fn parse_rfc3339_utc(s: &str) -> Result<NaiveDateTime, AppError> {
+    DateTime::<FixedOffset>::parse_from_rfc3339(s)
+        .map_err(|e| AppError::Validation(format!("invalid timestamp: {e}")))
+        .map(|dt| dt.naive_utc())
+}
+
 /// Parse an optional RFC3339 string into Option<NaiveDateTime>.
 pub fn parse_optional_rfc3339(opt: &Option<String>) -> Result<Option<NaiveDateTime>, AppError> {
     if let Some(ref s) = opt {
@@ -16,3 +23,9 @@ pub fn parse_optional_rfc3339(opt: &Option<String>) -> Result<Option<NaiveDateTi
         Ok(None)
     }
 }
+
+/// Parse a calendar date in `YYYY-MM-DD` form.
+pub fn parse_date(s: &str) -> Result<NaiveDate, AppError> {
+    NaiveDate::parse_from_str(s, "%Y-%m-%d")
+        .map_err(|e| AppError::Validation(format!("invalid date: {e}")))
+}
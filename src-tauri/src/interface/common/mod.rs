@@ -1,2 +1,3 @@
+pub mod csv;
 pub mod date_utils;
 pub mod validators;
@@ -1,4 +1,4 @@
-use crate::interface::common::date_utils::parse_rfc3339;
+use crate::interface::common::date_utils::{parse_date, parse_rfc3339};
 use validator::ValidationError;
 
 pub fn validate_rfc3339(s: &str) -> Result<(), ValidationError> {
@@ -14,6 +14,14 @@ pub fn validate_optional_rfc3339_str(s: &str) -> Result<(), ValidationError> {
     validate_rfc3339(s)
 }
 
+pub fn validate_date_str(s: &str) -> Result<(), ValidationError> {
+    parse_date(s).map(|_| ()).map_err(|app_err| {
+        let mut ve = ValidationError::new("date");
+        ve.message = Some(app_err.to_string().into());
+        ve
+    })
+}
+
 pub fn validate_upc_str(s: &str) -> Result<(), validator::ValidationError> {
     if !s.chars().all(|c| c.is_ascii_digit()) {
         let mut err = ValidationError::new("invalid_upc");
@@ -0,0 +1,210 @@
+use crate::application::use_cases::limit_usecases::LimitUseCases;
+use crate::common::error::AppError;
+use crate::domain::models::{
+    CustomerTier, LimitOverride, LimitPeriod, LimitSettings, QuantityLimit, QuantityWindow,
+};
+use crate::domain::repos::{CustomerLimitRepoTrait, QuantityLimitRepoTrait, WeeklyLimitRepoTrait};
+use crate::interface::common::date_utils::parse_date;
+use crate::interface::dto::customer_limit_dto::{
+    CustomerTierDto, LimitOverrideDto, LimitSettingsDto, QuantityLimitDto,
+};
+use crate::interface::presenters::customer_limit_presenter::CustomerLimitPresenter;
+use chrono::Weekday;
+use std::sync::Arc;
+use validator::Validate;
+
+pub struct CustomerLimitController {
+    uc: LimitUseCases,
+}
+
+impl CustomerLimitController {
+    pub fn new(
+        limit_repo: Arc<dyn WeeklyLimitRepoTrait>,
+        customer_limit_repo: Arc<dyn CustomerLimitRepoTrait>,
+        quantity_limit_repo: Arc<dyn QuantityLimitRepoTrait>,
+    ) -> Self {
+        Self {
+            uc: LimitUseCases::new(limit_repo, customer_limit_repo, quantity_limit_repo),
+        }
+    }
+
+    pub fn get_settings(&self) -> Result<LimitSettingsDto, AppError> {
+        let settings = self.uc.get_settings()?;
+        Ok(CustomerLimitPresenter::to_settings_dto(settings))
+    }
+
+    pub fn set_settings(&self, dto: LimitSettingsDto) -> Result<(), AppError> {
+        dto.validate()
+            .map_err(|e| AppError::Validation(e.to_string()))?;
+        let week_start = (0..dto.week_start).fold(Weekday::Sun, |d, _| d.succ());
+        self.uc.set_settings(&LimitSettings {
+            period: LimitPeriod::parse(&dto.period)?,
+            week_start,
+            anchor_date: parse_date(&dto.anchor_date)?,
+            time_zone: dto.time_zone.trim().to_string(),
+        })
+    }
+
+    pub fn list_tiers(&self) -> Result<Vec<CustomerTierDto>, AppError> {
+        let tiers = self.uc.list_tiers()?;
+        Ok(CustomerLimitPresenter::to_tier_dtos(tiers))
+    }
+
+    pub fn create_tier(&self, dto: CustomerTierDto) -> Result<i32, AppError> {
+        dto.validate()
+            .map_err(|e| AppError::Validation(e.to_string()))?;
+        self.uc.create_tier(&CustomerTier {
+            id: 0,
+            name: dto.name.trim().to_string(),
+            limit_amount: dto.limit_amount,
+        })
+    }
+
+    pub fn update_tier(&self, dto: CustomerTierDto) -> Result<(), AppError> {
+        dto.validate()
+            .map_err(|e| AppError::Validation(e.to_string()))?;
+        self.uc.update_tier(&CustomerTier {
+            id: dto.id,
+            name: dto.name.trim().to_string(),
+            limit_amount: dto.limit_amount,
+        })
+    }
+
+    pub fn delete_tier(&self, id: i32) -> Result<(), AppError> {
+        self.uc.delete_tier(id)
+    }
+
+    pub fn set_customer_tier(
+        &self,
+        customer_mdoc: i32,
+        tier_id: Option<i32>,
+    ) -> Result<(), AppError> {
+        self.uc.set_customer_tier(customer_mdoc, tier_id)
+    }
+
+    pub fn list_overrides(&self, customer_mdoc: i32) -> Result<Vec<LimitOverrideDto>, AppError> {
+        let overrides = self.uc.list_overrides(customer_mdoc)?;
+        Ok(CustomerLimitPresenter::to_override_dtos(overrides))
+    }
+
+    pub fn create_override(&self, dto: LimitOverrideDto) -> Result<i32, AppError> {
+        dto.validate()
+            .map_err(|e| AppError::Validation(e.to_string()))?;
+        let ends_on = match &dto.ends_on {
+            Some(s) => Some(parse_date(s)?),
+            None => None,
+        };
+        self.uc.create_override(LimitOverride {
+            id: 0,
+            customer_mdoc: dto.customer_mdoc,
+            limit_amount: dto.limit_amount,
+            starts_on: parse_date(&dto.starts_on)?,
+            ends_on,
+            reason: dto.reason,
+            operator_mdoc: dto.operator_mdoc,
+            created_at: None,
+        })
+    }
+
+    pub fn delete_override(&self, id: i32) -> Result<(), AppError> {
+        self.uc.delete_override(id)
+    }
+
+    pub fn list_quantity_limits(&self) -> Result<Vec<QuantityLimitDto>, AppError> {
+        let limits = self.uc.list_quantity_limits()?;
+        Ok(CustomerLimitPresenter::to_quantity_limit_dtos(limits))
+    }
+
+    pub fn create_quantity_limit(&self, dto: QuantityLimitDto) -> Result<i32, AppError> {
+        dto.validate()
+            .map_err(|e| AppError::Validation(e.to_string()))?;
+        self.uc.create_quantity_limit(QuantityLimit {
+            id: 0,
+            upc: dto.upc,
+            category: dto.category,
+            max_quantity: dto.max_quantity,
+            window: QuantityWindow::parse(dto.window.trim(), dto.rolling_days)?,
+            note: dto.note,
+            created_at: None,
+        })
+    }
+
+    pub fn delete_quantity_limit(&self, id: i32) -> Result<(), AppError> {
+        self.uc.delete_quantity_limit(id)
+    }
+}
+
+#[cfg(test)]
+mod smoke {
+    use super::*;
+    use crate::test_support::mock_customer_limit_repo::MockCustomerLimitRepo;
+    use crate::test_support::mock_quantity_limit_repo::MockQuantityLimitRepo;
+    use crate::test_support::mock_weekly_limit_repo::MockWeeklyLimitRepo;
+
+    #[test]
+    fn controller_smoke_tiers_and_overrides() {
+        let ctrl = CustomerLimitController::new(
+            Arc::new(MockWeeklyLimitRepo::new()),
+            Arc::new(MockCustomerLimitRepo::new()),
+            Arc::new(MockQuantityLimitRepo::new()),
+        );
+        let id = ctrl
+            .create_tier(CustomerTierDto {
+                id: 0,
+                name: " Honor dorm ".into(),
+                limit_amount: 30000,
+            })
+            .expect("create tier");
+        ctrl.set_customer_tier(7, Some(id)).expect("assign tier");
+        assert_eq!(ctrl.list_tiers().unwrap()[0].name, "Honor dorm");
+
+        ctrl.create_override(LimitOverrideDto {
+            id: 0,
+            customer_mdoc: 7,
+            limit_amount: 1000,
+            starts_on: "2025-03-01".into(),
+            ends_on: Some("2025-03-31".into()),
+            reason: "restriction".into(),
+            operator_mdoc: 1,
+            created_at: None,
+        })
+        .expect("create override");
+        let listed = ctrl.list_overrides(7).unwrap();
+        assert_eq!(listed[0].ends_on.as_deref(), Some("2025-03-31"));
+
+        ctrl.set_settings(LimitSettingsDto {
+            period: "monthly".into(),
+            week_start: 1,
+            anchor_date: "2025-01-06".into(),
+            time_zone: "America/Phoenix".into(),
+        })
+        .expect("set settings");
+        let settings = ctrl.get_settings().unwrap();
+        assert_eq!(
+            (settings.period.as_str(), settings.week_start),
+            ("monthly", 1)
+        );
+
+        let stamps = QuantityLimitDto {
+            id: 0,
+            upc: Some("STAMP".into()),
+            category: None,
+            max_quantity: 10,
+            window: "weekly".into(),
+            rolling_days: None,
+            note: None,
+            created_at: None,
+        };
+        ctrl.create_quantity_limit(stamps.clone())
+            .expect("create quantity limit");
+        assert_eq!(ctrl.list_quantity_limits().unwrap()[0].window, "weekly");
+        let rolling = QuantityLimitDto {
+            window: "rolling".into(),
+            ..stamps
+        };
+        assert!(matches!(
+            ctrl.create_quantity_limit(rolling),
+            Err(AppError::Validation(_))
+        ));
+    }
+}
@@ -1,10 +1,15 @@
 pub mod auth_controller;
 pub mod club_controller;
+pub mod customer_limit_controller;
 pub mod legacy_migration_controller;
 pub mod operator_controller;
 pub mod parse_pdf_controller;
 pub mod pos_controller;
 pub mod printer_controller;
 pub mod product_controller;
+pub mod promotion_controller;
+pub mod purchasing_controller;
 pub mod stats_controller;
+pub mod stock_take_controller;
+pub mod tax_controller;
 pub mod transaction_controller;
@@ -1,10 +1,9 @@
-use crate::application::use_cases::pos_usecases::{PosInitData, PosUseCase};
+use crate::application::use_cases::pos_usecases::PosUseCase;
 use crate::common::error::AppError;
-use crate::domain::repos::{CustomerRepoTrait, ProductRepoTrait};
-use crate::interface::dto::{customer_dto::CustomerPosDto, product_dto::ProductDto};
-use crate::interface::presenters::customer_presenter::CustomerPresenter;
-use crate::interface::presenters::product_presenter::ProductPresenter;
-use std::sync::Arc;
+use crate::domain::repos::{CustomerRepoTrait, PriceAdjustmentRepoTrait, ProductRepoTrait};
+use crate::interface::dto::pos_dto::PosDto;
+use crate::interface::presenters::pos_presenter::PosPresenter;
+use std::sync::{Arc, Mutex};
 
 pub struct PosController {
     uc: PosUseCase,
@@ -13,23 +12,17 @@ pub struct PosController {
 impl PosController {
     pub fn new(
         product_repo: Arc<dyn ProductRepoTrait>,
+        price_repo: Arc<dyn PriceAdjustmentRepoTrait>,
         customer_repo: Arc<dyn CustomerRepoTrait>,
+        conn: Arc<Mutex<rusqlite::Connection>>,
     ) -> Self {
         Self {
-            uc: PosUseCase::new(product_repo, customer_repo),
+            uc: PosUseCase::new(product_repo, price_repo, customer_repo, conn),
         }
     }
 
-    pub fn pos_init(&self) -> Result<(Vec<ProductDto>, Vec<CustomerPosDto>), AppError> {
-        let PosInitData {
-            products,
-            customer_accounts,
-        } = self.uc.get_pos_init_data()?;
-        let product_dtos = products.into_iter().map(ProductPresenter::to_dto).collect();
-        let account_dtos = customer_accounts
-            .into_iter()
-            .map(|(c, b)| CustomerPresenter::to_pos_dto(c, b))
-            .collect();
-        Ok((product_dtos, account_dtos))
+    pub fn pos_init(&self) -> Result<PosDto, AppError> {
+        let data = self.uc.get_pos_init_data()?;
+        Ok(PosPresenter::to_dto(data))
     }
 }
@@ -1,7 +1,11 @@
+use crate::application::use_cases::print_queue_usecases::PrintQueueUseCases;
 use crate::application::use_cases::printer_usecases::PrinterUseCases;
 use crate::common::error::AppError;
+use crate::domain::models::PrintJobStatus;
 use crate::interface::common::date_utils::parse_rfc3339;
+use crate::interface::dto::printer_dto::PrintJobDto;
 use crate::interface::presenters::printer_presenter::PrinterPresenter;
+use std::sync::Arc;
 #[derive(Debug)]
 enum ReportType {
     Receipt,
@@ -20,12 +24,13 @@ impl std::str::FromStr for ReportType {
 
 pub struct PrinterController {
     uc: PrinterUseCases,
+    queue: Arc<PrintQueueUseCases>,
 }
 
 impl PrinterController {
     #[must_use]
-    pub const fn new(uc: PrinterUseCases) -> Self {
-        Self { uc }
+    pub const fn new(uc: PrinterUseCases, queue: Arc<PrintQueueUseCases>) -> Self {
+        Self { uc, queue }
     }
 
     // Returns a list of printers as DTOs.
@@ -66,6 +71,14 @@ impl PrinterController {
             .map(|_| ())
     }
 
+    pub fn print_reorder_report(
+        &self,
+        printer_name: String,
+        sumatra_location: String,
+    ) -> Result<(), AppError> {
+        self.uc.print_reorder_report(printer_name, sumatra_location)
+    }
+
     pub fn print_sales_detail_report(
         &self,
         start_date: String,
@@ -97,6 +110,20 @@ impl PrinterController {
             .map(|_| ())
     }
 
+    pub fn print_margin_report(
+        &self,
+        start_date: String,
+        end_date: String,
+        printer_name: String,
+        sumatra_location: String,
+    ) -> Result<(), AppError> {
+        let start_date = parse_rfc3339(&start_date)?;
+        let end_date = parse_rfc3339(&end_date)?;
+
+        self.uc
+            .margin_by_category(start_date, end_date, printer_name, sumatra_location)
+    }
+
     pub fn print_daily_sales_report(
         &self,
         start_date: String,
@@ -124,4 +151,18 @@ impl PrinterController {
             .print_club_import(import_id, start_date, printer_name, sumatra_location)
             .map(|_| ())
     }
+
+    pub fn list_print_jobs(&self, status: Option<String>) -> Result<Vec<PrintJobDto>, AppError> {
+        let status = status.as_deref().map(PrintJobStatus::parse).transpose()?;
+        let jobs = self.queue.list_jobs(status)?;
+        Ok(PrinterPresenter::to_job_dtos(jobs))
+    }
+
+    pub fn retry_print_job(&self, id: i32) -> Result<(), AppError> {
+        self.queue.retry_job(id)
+    }
+
+    pub fn cancel_print_job(&self, id: i32) -> Result<(), AppError> {
+        self.queue.cancel_job(id)
+    }
 }
@@ -1,13 +1,24 @@
 use crate::application::use_cases::product_usecases::ProductUseCases;
 use crate::common::error::AppError;
-use crate::domain::models::price_adjustment::PriceAdjustment;
+use crate::domain::models::barcode_alias::BarcodeAlias;
+use crate::domain::models::bulk_price_change::{
+    BulkPriceChange, PriceChange, PriceRounding, ProductSelection, RoundingMode,
+};
+use crate::domain::models::price_adjustment::{PriceAdjustment, PriceAdjustmentStatus};
 use crate::domain::models::product::Product;
-use crate::interface::dto::category_dto::{CategoryDto, CreateCategoryDto, DeleteCategoryDto};
+use crate::domain::models::product_import::ImportFailure;
+use crate::interface::common::csv::{csv_row, format_dollars, parse_csv, parse_dollars};
+use crate::interface::common::date_utils::parse_rfc3339_utc;
+use crate::interface::dto::category_dto::{
+    CategoryDto, CreateCategoryDto, DeleteCategoryDto, SetCategoryLimitExemptDto,
+};
 use crate::interface::dto::price_adjustment_dto::{
-    PriceAdjustmentDto, PriceAdjustmentSearchResult,
+    BulkPriceChangeDto, BulkPriceLineDto, PriceAdjustmentDto, PriceAdjustmentSearchResult,
 };
 use crate::interface::dto::product_dto::{
-    CreateProductDto, DeleteProductDto, ProductSearchResult, UpdateProductDto,
+    BarcodeAliasDto, BundleComponentDto, CreateProductDto, DeleteProductDto, ImportProductsDto,
+    LowStockDto, ProductImportPreviewDto, ProductSearchResult, SetBundleComponentsDto,
+    SetProductLimitExemptDto, SetReorderPointDto, UpdateProductDto,
 };
 use crate::interface::presenters::category_presenter::CategoryPresenter;
 use crate::interface::presenters::price_adjustment_presenter::PriceAdjustmentPresenter;
@@ -15,6 +26,12 @@ use crate::interface::presenters::product_presenter::ProductPresenter;
 use std::sync::Arc;
 use validator::Validate;
 
+// Columns of the catalog CSV, in order.
+const IMPORT_COLUMNS: [&str; 4] = ["upc", "description", "category", "price"];
+
+// Import rows that parsed, with their line numbers, and those that didn't.
+type ImportRows = (Vec<(usize, Product)>, Vec<ImportFailure>);
+
 pub struct ProductController {
     uc: ProductUseCases,
 }
@@ -34,7 +51,11 @@ impl ProductController {
     pub fn create_product(&self, dto: CreateProductDto) -> Result<(), AppError> {
         dto.validate()
             .map_err(|e| AppError::Validation(e.to_string()))?;
-        let product = Product {
+        self.uc.create_product(Self::new_product(dto))
+    }
+
+    fn new_product(dto: CreateProductDto) -> Product {
+        Product {
             upc: dto.upc,
             desc: dto.desc,
             category: dto.category,
@@ -42,8 +63,11 @@ impl ProductController {
             updated: None,
             added: None,
             deleted: None,
-        };
-        self.uc.create_product(product)
+            limit_exempt: None,
+            unit_cost: None,
+            reorder_point: None,
+            reorder_quantity: None,
+        }
     }
 
     pub fn delete_product(&self, dto: DeleteProductDto) -> Result<(), AppError> {
@@ -65,10 +89,85 @@ impl ProductController {
             old: dto.old,
             new: dto.new,
             created_at: None,
+            effective_at: dto
+                .effective_at
+                .as_deref()
+                .map(parse_rfc3339_utc)
+                .transpose()?,
+            status: PriceAdjustmentStatus::Applied, // the use case decides
         };
 
-        self.uc.price_adjustment(domain)?;
-        Ok(dto)
+        let saved = self.uc.price_adjustment(domain)?;
+        Ok(PriceAdjustmentPresenter::to_dto(saved))
+    }
+
+    pub fn apply_due_price_changes(&self) -> Result<(), AppError> {
+        self.uc.apply_due_price_changes().map(|_| ())
+    }
+
+    pub fn list_pending_price_changes(&self) -> Result<Vec<PriceAdjustmentDto>, AppError> {
+        let pending = self.uc.list_pending_price_changes()?;
+        Ok(PriceAdjustmentPresenter::to_dto_list(pending))
+    }
+
+    pub fn cancel_price_change(&self, id: i32) -> Result<(), AppError> {
+        self.uc.cancel_price_change(id)
+    }
+
+    pub fn preview_bulk_price_change(
+        &self,
+        dto: BulkPriceChangeDto,
+    ) -> Result<Vec<BulkPriceLineDto>, AppError> {
+        let change = Self::bulk_change(&dto)?;
+        let lines = self.uc.preview_bulk_price_change(&change)?;
+        Ok(lines
+            .into_iter()
+            .map(PriceAdjustmentPresenter::to_bulk_line_dto)
+            .collect())
+    }
+
+    pub fn bulk_price_change(
+        &self,
+        dto: BulkPriceChangeDto,
+    ) -> Result<Vec<PriceAdjustmentDto>, AppError> {
+        let change = Self::bulk_change(&dto)?;
+        let saved = self.uc.bulk_price_change(&change, dto.operator_mdoc)?;
+        Ok(PriceAdjustmentPresenter::to_dto_list(saved))
+    }
+
+    fn bulk_change(dto: &BulkPriceChangeDto) -> Result<BulkPriceChange, AppError> {
+        dto.validate()
+            .map_err(|e| AppError::Validation(e.to_string()))?;
+        let selection = match (&dto.category, dto.upcs.is_empty()) {
+            (Some(c), true) => ProductSelection::Category(c.trim().to_string()),
+            (None, false) => ProductSelection::Upcs(dto.upcs.clone()),
+            _ => {
+                return Err(AppError::Validation(
+                    "give either a category or a list of upcs".into(),
+                ))
+            }
+        };
+        let change = match (dto.percent, dto.amount) {
+            (Some(p), None) => PriceChange::Percent(p),
+            (None, Some(a)) => PriceChange::Amount(a),
+            _ => {
+                return Err(AppError::Validation(
+                    "give either a percent or an amount".into(),
+                ))
+            }
+        };
+        let mode = dto
+            .round_mode
+            .as_deref()
+            .map_or(Ok(RoundingMode::Nearest), RoundingMode::parse)?;
+        Ok(BulkPriceChange {
+            selection,
+            change,
+            rounding: PriceRounding {
+                step: dto.round_to,
+                mode,
+            },
+        })
     }
 
     pub fn update_product(&self, dto: UpdateProductDto) -> Result<(), AppError> {
@@ -137,6 +236,169 @@ impl ProductController {
             .map_err(|e| AppError::Validation(e.to_string()))?;
         self.uc.create_category(dto.name)
     }
+
+    pub fn set_category_limit_exempt(
+        &self,
+        dto: SetCategoryLimitExemptDto,
+    ) -> Result<(), AppError> {
+        dto.validate()
+            .map_err(|e| AppError::Validation(e.to_string()))?;
+        self.uc.set_category_limit_exempt(dto.id, dto.limit_exempt)
+    }
+
+    pub fn set_product_limit_exempt(&self, dto: SetProductLimitExemptDto) -> Result<(), AppError> {
+        dto.validate()
+            .map_err(|e| AppError::Validation(e.to_string()))?;
+        self.uc.set_product_limit_exempt(dto.upc, dto.limit_exempt)
+    }
+
+    pub fn set_reorder_point(&self, dto: SetReorderPointDto) -> Result<(), AppError> {
+        dto.validate()
+            .map_err(|e| AppError::Validation(e.to_string()))?;
+        self.uc
+            .set_reorder_point(dto.upc, dto.reorder_point, dto.reorder_quantity)
+    }
+
+    pub fn list_low_stock(&self) -> Result<Vec<LowStockDto>, AppError> {
+        let items = self.uc.low_stock()?;
+        Ok(items
+            .into_iter()
+            .map(ProductPresenter::to_low_stock_dto)
+            .collect())
+    }
+
+    pub fn list_product_barcodes(&self, upc: String) -> Result<Vec<BarcodeAliasDto>, AppError> {
+        let aliases = self.uc.list_barcodes(upc)?;
+        Ok(aliases
+            .into_iter()
+            .map(ProductPresenter::to_barcode_dto)
+            .collect())
+    }
+
+    pub fn add_product_barcode(&self, dto: BarcodeAliasDto) -> Result<(), AppError> {
+        dto.validate()
+            .map_err(|e| AppError::Validation(e.to_string()))?;
+        self.uc.add_barcode(BarcodeAlias {
+            barcode: dto.barcode,
+            upc: dto.upc,
+            multiplier: dto.multiplier,
+            created_at: None,
+        })
+    }
+
+    pub fn remove_product_barcode(&self, barcode: String) -> Result<(), AppError> {
+        self.uc.remove_barcode(barcode)
+    }
+
+    // Shows what importing the CSV would create, restore and update, and
+    // which rows fail, without changing anything.
+    pub fn preview_product_import(&self, csv: String) -> Result<ProductImportPreviewDto, AppError> {
+        let (rows, failures) = Self::read_import(&csv)?;
+        let mut plan = self.uc.preview_import(rows)?;
+        plan.failures.extend(failures);
+        plan.failures.sort_by_key(|f| f.line);
+        Ok(ProductPresenter::to_import_dto(plan))
+    }
+
+    pub fn import_products(
+        &self,
+        dto: ImportProductsDto,
+    ) -> Result<ProductImportPreviewDto, AppError> {
+        dto.validate()
+            .map_err(|e| AppError::Validation(e.to_string()))?;
+        let (rows, failures) = Self::read_import(&dto.csv)?;
+        if let Some(f) = failures.first() {
+            return Err(AppError::Validation(format!(
+                "{} row(s) can't be imported; line {}: {}",
+                failures.len(),
+                f.line,
+                f.message
+            )));
+        }
+        let plan = self.uc.import_products(rows, dto.operator_mdoc)?;
+        Ok(ProductPresenter::to_import_dto(plan))
+    }
+
+    // The catalog in the same layout the import reads.
+    pub fn export_products_csv(&self) -> Result<String, AppError> {
+        let mut out = csv_row(&IMPORT_COLUMNS);
+        out.push('\n');
+        for p in self.uc.export_products()? {
+            out.push_str(&csv_row(&[
+                &p.upc,
+                &p.desc,
+                &p.category,
+                &format_dollars(p.price),
+            ]));
+            out.push('\n');
+        }
+        Ok(out)
+    }
+
+    // Import rows that validate like `create_product` input, and the ones that
+    // don't. A leading header row is skipped.
+    fn read_import(csv: &str) -> Result<ImportRows, AppError> {
+        let mut rows = vec![];
+        let mut failures = vec![];
+        for (i, (line, fields)) in parse_csv(csv)?.into_iter().enumerate() {
+            let upc = fields[0].trim().to_string();
+            if i == 0 && upc.eq_ignore_ascii_case(IMPORT_COLUMNS[0]) {
+                continue;
+            }
+            let fail = |message: String| ImportFailure {
+                line,
+                upc: upc.clone(),
+                message,
+            };
+            let [_, desc, category, price] = fields.as_slice() else {
+                failures.push(fail(format!(
+                    "expected {} columns ({}), found {}",
+                    IMPORT_COLUMNS.len(),
+                    IMPORT_COLUMNS.join(", "),
+                    fields.len()
+                )));
+                continue;
+            };
+            let Some(price) = parse_dollars(price) else {
+                failures.push(fail(format!("invalid price {}", price.trim())));
+                continue;
+            };
+            let dto = CreateProductDto {
+                upc: upc.clone(),
+                desc: desc.trim().to_string(),
+                category: category.trim().to_string(),
+                price,
+            };
+            match dto.validate() {
+                Ok(()) => rows.push((line, Self::new_product(dto))),
+                Err(e) => failures.push(fail(e.to_string())),
+            }
+        }
+        if rows.is_empty() && failures.is_empty() {
+            return Err(AppError::Validation("the file has no product rows".into()));
+        }
+        Ok((rows, failures))
+    }
+
+    pub fn get_bundle_components(&self, upc: String) -> Result<Vec<BundleComponentDto>, AppError> {
+        let components = self.uc.bundle_components(upc)?;
+        Ok(components
+            .into_iter()
+            .map(ProductPresenter::to_component_dto)
+            .collect())
+    }
+
+    pub fn set_bundle_components(&self, dto: SetBundleComponentsDto) -> Result<(), AppError> {
+        dto.validate()
+            .map_err(|e| AppError::Validation(e.to_string()))?;
+        self.uc.set_bundle_components(
+            dto.upc,
+            dto.components
+                .into_iter()
+                .map(|c| (c.component_upc, c.quantity))
+                .collect(),
+        )
+    }
 }
 
 #[cfg(test)]
@@ -175,4 +437,43 @@ mod smoke {
             .expect("search_products should succeed");
         assert!(result.products.is_empty());
     }
+
+    #[test]
+    fn csv_import_reports_bad_rows_and_exports_what_it_imported() {
+        let ctrl = make_controller();
+        ctrl.create_category(CreateCategoryDto {
+            name: "Snacks".into(),
+        })
+        .unwrap();
+        let csv = "UPC,Description,Category,Price\n\
+                   00000011,\"Chips, BBQ\",Snacks,1.50\n\
+                   123,Gum,Snacks,0.50\n\
+                   00000033,Candy,Snacks,abc\n\
+                   00000044,Mints,Snacks\n";
+
+        let preview = ctrl.preview_product_import(csv.into()).unwrap();
+        assert_eq!(preview.creates, 1);
+        let failed: Vec<_> = preview.failures.iter().map(|f| f.line).collect();
+        assert_eq!(failed, vec![3, 4, 5]);
+        assert!(matches!(
+            ctrl.import_products(ImportProductsDto {
+                csv: csv.into(),
+                operator_mdoc: 1,
+            }),
+            Err(AppError::Validation(_))
+        ));
+
+        let good = "00000011,\"Chips, BBQ\",Snacks,1.50\n";
+        let done = ctrl
+            .import_products(ImportProductsDto {
+                csv: good.into(),
+                operator_mdoc: 1,
+            })
+            .unwrap();
+        assert_eq!(done.lines[0].price, 150);
+        assert_eq!(
+            ctrl.export_products_csv().unwrap(),
+            format!("upc,description,category,price\n{good}")
+        );
+    }
 }
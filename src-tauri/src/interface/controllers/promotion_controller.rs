@@ -0,0 +1,117 @@
+use crate::application::use_cases::promotion_usecases::PromotionUseCases;
+use crate::common::error::AppError;
+use crate::domain::models::{Promotion, PromotionRule};
+use crate::domain::repos::{ProductRepoTrait, PromotionRepoTrait};
+use crate::interface::common::date_utils::parse_rfc3339_utc;
+use crate::interface::dto::promotion_dto::{LineDiscountDto, PromotionDto, QuoteLineDto};
+use crate::interface::presenters::promotion_presenter::PromotionPresenter;
+use std::sync::Arc;
+use validator::Validate;
+
+pub struct PromotionController {
+    uc: PromotionUseCases,
+}
+
+impl PromotionController {
+    pub fn new(
+        promo_repo: Arc<dyn PromotionRepoTrait>,
+        prod_repo: Arc<dyn ProductRepoTrait>,
+    ) -> Self {
+        Self {
+            uc: PromotionUseCases::new(promo_repo, prod_repo),
+        }
+    }
+
+    pub fn list_promotions(&self) -> Result<Vec<PromotionDto>, AppError> {
+        let promotions = self.uc.list_promotions()?;
+        Ok(PromotionPresenter::to_dtos(promotions))
+    }
+
+    pub fn create_promotion(&self, dto: PromotionDto) -> Result<i32, AppError> {
+        dto.validate()
+            .map_err(|e| AppError::Validation(e.to_string()))?;
+        let buy_get = dto.buy_quantity.zip(dto.get_quantity);
+        self.uc.create_promotion(Promotion {
+            id: 0,
+            name: dto.name,
+            rule: PromotionRule::parse(dto.kind.trim(), dto.value, buy_get)?,
+            upc: dto.upc,
+            category: dto.category,
+            starts_at: dto
+                .starts_at
+                .as_deref()
+                .map(parse_rfc3339_utc)
+                .transpose()?,
+            ends_at: dto.ends_at.as_deref().map(parse_rfc3339_utc).transpose()?,
+            created_at: None,
+            deleted: None,
+        })
+    }
+
+    pub fn delete_promotion(&self, id: i32) -> Result<(), AppError> {
+        self.uc.delete_promotion(id)
+    }
+
+    pub fn quote_promotions(
+        &self,
+        lines: Vec<QuoteLineDto>,
+    ) -> Result<Vec<LineDiscountDto>, AppError> {
+        let lines: Vec<(String, i32)> = lines.into_iter().map(|l| (l.upc, l.quantity)).collect();
+        let discounts = self.uc.quote(&lines)?;
+        Ok(PromotionPresenter::to_discount_dtos(discounts))
+    }
+}
+
+#[cfg(test)]
+mod smoke {
+    use super::*;
+    use crate::test_support::mock_product_repo::MockProductRepo;
+    use crate::test_support::mock_promotion_repo::MockPromotionRepo;
+
+    #[test]
+    fn create_rejects_bad_kinds_and_lists_what_was_saved() {
+        let ctrl = PromotionController::new(
+            Arc::new(MockPromotionRepo::new()),
+            Arc::new(MockProductRepo::new()),
+        );
+        let dto = PromotionDto {
+            name: "Holiday snacks".into(),
+            kind: "percent_off".into(),
+            value: 20,
+            category: Some("Snacks".into()),
+            starts_at: Some("2025-12-20T00:00:00Z".into()),
+            ends_at: Some("2025-12-27T00:00:00Z".into()),
+            ..Default::default()
+        };
+        let id = ctrl.create_promotion(dto.clone()).unwrap();
+        assert!(ctrl
+            .create_promotion(PromotionDto {
+                kind: "half_off".into(),
+                ..dto.clone()
+            })
+            .is_err());
+        assert!(ctrl
+            .create_promotion(PromotionDto {
+                kind: "buy_get".into(),
+                buy_quantity: Some(2),
+                ..dto
+            })
+            .is_err());
+
+        let listed = ctrl.list_promotions().unwrap();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].id, id);
+        assert_eq!(
+            listed[0].ends_at.as_deref(),
+            Some("2025-12-27T00:00:00+00:00")
+        );
+        // not running yet, so nothing comes off
+        assert!(ctrl
+            .quote_promotions(vec![QuoteLineDto {
+                upc: "CHIPS".into(),
+                quantity: 1
+            }])
+            .unwrap()
+            .is_empty());
+    }
+}
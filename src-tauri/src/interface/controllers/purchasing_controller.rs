@@ -0,0 +1,140 @@
+use crate::application::use_cases::purchasing_usecases::PurchasingUseCases;
+use crate::common::error::AppError;
+use crate::domain::models::{LotLabel, PurchaseOrderStatus, ReceivedItem, Vendor};
+use crate::domain::repos::{
+    InventoryTransactionRepoTrait, ProductRepoTrait, PurchaseOrderRepoTrait, VendorRepoTrait,
+};
+use crate::interface::common::date_utils::parse_date;
+use crate::interface::dto::purchasing_dto::{
+    CreatePurchaseOrderDto, PurchaseOrderDto, PurchaseOrderItemDto, ReceivePurchaseOrderDto,
+    ReceivingReportDto, VendorDto,
+};
+use crate::interface::presenters::purchasing_presenter::PurchasingPresenter;
+use std::sync::{Arc, Mutex};
+use validator::Validate;
+
+pub struct PurchasingController {
+    uc: PurchasingUseCases,
+}
+
+fn to_items(dtos: Vec<PurchaseOrderItemDto>) -> Result<Vec<ReceivedItem>, AppError> {
+    dtos.into_iter()
+        .map(|d| {
+            let expires_on = d.expires_on.as_deref().map(parse_date).transpose()?;
+            Ok(ReceivedItem {
+                upc: d.upc.trim().to_string(),
+                quantity: d.quantity,
+                unit_cost: d.unit_cost,
+                lot: LotLabel::new(d.lot_number, expires_on),
+            })
+        })
+        .collect()
+}
+
+fn to_vendor(dto: VendorDto) -> Vendor {
+    let trimmed = |s: Option<String>| s.map(|s| s.trim().to_string()).filter(|s| !s.is_empty());
+    Vendor {
+        id: dto.id,
+        name: dto.name.trim().to_string(),
+        contact: trimmed(dto.contact),
+        phone: trimmed(dto.phone),
+        deleted: None,
+        created_at: None,
+    }
+}
+
+impl PurchasingController {
+    pub fn new(
+        vendor_repo: Arc<dyn VendorRepoTrait>,
+        po_repo: Arc<dyn PurchaseOrderRepoTrait>,
+        inv_repo: Arc<dyn InventoryTransactionRepoTrait>,
+        prod_repo: Arc<dyn ProductRepoTrait>,
+        conn: Arc<Mutex<rusqlite::Connection>>,
+    ) -> Self {
+        Self {
+            uc: PurchasingUseCases::new(vendor_repo, po_repo, inv_repo, prod_repo, conn),
+        }
+    }
+
+    pub fn list_vendors(&self) -> Result<Vec<VendorDto>, AppError> {
+        let vendors = self.uc.list_vendors()?;
+        Ok(PurchasingPresenter::to_vendor_dtos(vendors))
+    }
+
+    pub fn create_vendor(&self, dto: VendorDto) -> Result<i32, AppError> {
+        dto.validate()
+            .map_err(|e| AppError::Validation(e.to_string()))?;
+        self.uc.create_vendor(&to_vendor(dto))
+    }
+
+    pub fn update_vendor(&self, dto: VendorDto) -> Result<(), AppError> {
+        dto.validate()
+            .map_err(|e| AppError::Validation(e.to_string()))?;
+        self.uc.update_vendor(&to_vendor(dto))
+    }
+
+    pub fn delete_vendor(&self, id: i32) -> Result<(), AppError> {
+        self.uc.delete_vendor(id)
+    }
+
+    pub fn create_purchase_order(&self, dto: CreatePurchaseOrderDto) -> Result<i32, AppError> {
+        dto.validate()
+            .map_err(|e| AppError::Validation(e.to_string()))?;
+        self.uc.create_purchase_order(
+            dto.vendor_id,
+            dto.operator_mdoc,
+            dto.reference,
+            to_items(dto.lines)?,
+        )
+    }
+
+    pub fn list_purchase_orders(
+        &self,
+        status: Option<String>,
+    ) -> Result<Vec<PurchaseOrderDto>, AppError> {
+        let status = status
+            .as_deref()
+            .map(PurchaseOrderStatus::parse)
+            .transpose()?;
+        let rows = self.uc.list_purchase_orders(status)?;
+        Ok(PurchasingPresenter::to_po_dtos(rows))
+    }
+
+    pub fn cancel_purchase_order(&self, po_id: i32) -> Result<(), AppError> {
+        self.uc.cancel_purchase_order(po_id)
+    }
+
+    pub fn get_receiving_report(&self, po_id: i32) -> Result<ReceivingReportDto, AppError> {
+        let report = self.uc.receiving_report(po_id)?;
+        Ok(PurchasingPresenter::to_report_dto(report))
+    }
+
+    pub fn receive_purchase_order(
+        &self,
+        dto: ReceivePurchaseOrderDto,
+    ) -> Result<ReceivingReportDto, AppError> {
+        dto.validate()
+            .map_err(|e| AppError::Validation(e.to_string()))?;
+        let report =
+            self.uc
+                .receive_purchase_order(dto.po_id, dto.operator_mdoc, to_items(dto.items)?)?;
+        if let (Some(printer), Some(sumatra)) = (&dto.printer_name, &dto.sumatra_location) {
+            // stock is already posted; a printer fault must not fail the receipt
+            if let Err(e) = self.uc.print_receiving_report(&report, printer, sumatra) {
+                log::error!("receiving report for PO {} failed to print: {e}", dto.po_id);
+            }
+        }
+        Ok(PurchasingPresenter::to_report_dto(report))
+    }
+
+    pub fn print_receiving_report(
+        &self,
+        po_id: i32,
+        printer_name: String,
+        sumatra_location: String,
+    ) -> Result<(), AppError> {
+        let report = self.uc.receiving_report(po_id)?;
+        self.uc
+            .print_receiving_report(&report, &printer_name, &sumatra_location)
+    }
+}
@@ -11,17 +11,19 @@ impl StatsController {
     pub fn new(
         club_tx_repo: Arc<dyn crate::domain::repos::ClubTransactionRepoTrait>,
         customer_repo: Arc<dyn crate::domain::repos::CustomerRepoTrait>,
+        product_repo: Arc<dyn crate::domain::repos::ProductRepoTrait>,
     ) -> Self {
         Self {
-            uc: StatsUseCases::new(club_tx_repo, customer_repo),
+            uc: StatsUseCases::new(club_tx_repo, customer_repo, product_repo),
         }
     }
 
     pub fn get_stats(&self) -> Result<StatsDto, AppError> {
-        let (account_total, total_customer_balances) = self.uc.get_stats()?;
+        let (account_total, total_customer_balances, low_stock_count) = self.uc.get_stats()?;
         Ok(StatsDto {
             account_total,
             total_customer_balances,
+            low_stock_count,
         })
     }
 }
@@ -31,11 +33,13 @@ mod smoke {
     use super::*;
     use crate::test_support::mock_club_tx_repo::MockClubTransactionRepo;
     use crate::test_support::mock_customer_repo::MockCustomerRepo;
+    use crate::test_support::mock_product_repo::MockProductRepo;
 
     fn make_controller() -> StatsController {
         let club_repo = Arc::new(MockClubTransactionRepo::new());
         let cust_repo = Arc::new(MockCustomerRepo::new());
-        StatsController::new(club_repo, cust_repo)
+        let prod_repo = Arc::new(MockProductRepo::new());
+        StatsController::new(club_repo, cust_repo, prod_repo)
     }
 
     #[test]
@@ -44,5 +48,6 @@ mod smoke {
         let out = ctrl.get_stats().expect("get_stats should succeed");
         assert_eq!(out.account_total, 0);
         assert_eq!(out.total_customer_balances, 0);
+        assert_eq!(out.low_stock_count, 0);
     }
 }
@@ -0,0 +1,98 @@
+use crate::application::use_cases::stock_take_usecases::StockTakeUseCases;
+use crate::common::error::AppError;
+use crate::domain::models::{CountedItem, StockTakeStatus};
+use crate::domain::repos::{InventoryTransactionRepoTrait, ProductRepoTrait, StockTakeRepoTrait};
+use crate::interface::dto::stock_take_dto::{
+    CountSheetDto, PostStockTakeDto, RecordCountsDto, StartStockTakeDto, StockTakeDto,
+    VarianceReportDto,
+};
+use crate::interface::presenters::stock_take_presenter::StockTakePresenter;
+use std::sync::{Arc, Mutex};
+use validator::Validate;
+
+pub struct StockTakeController {
+    uc: StockTakeUseCases,
+}
+
+impl StockTakeController {
+    pub fn new(
+        stock_take_repo: Arc<dyn StockTakeRepoTrait>,
+        inv_repo: Arc<dyn InventoryTransactionRepoTrait>,
+        prod_repo: Arc<dyn ProductRepoTrait>,
+        conn: Arc<Mutex<rusqlite::Connection>>,
+    ) -> Self {
+        Self {
+            uc: StockTakeUseCases::new(stock_take_repo, inv_repo, prod_repo, conn),
+        }
+    }
+
+    pub fn start_stock_take(&self, dto: StartStockTakeDto) -> Result<i32, AppError> {
+        dto.validate()
+            .map_err(|e| AppError::Validation(e.to_string()))?;
+        self.uc
+            .start_stock_take(dto.operator_mdoc, dto.category, dto.blind, dto.note)
+    }
+
+    pub fn list_stock_takes(&self, status: Option<String>) -> Result<Vec<StockTakeDto>, AppError> {
+        let status = status.as_deref().map(StockTakeStatus::parse).transpose()?;
+        let sessions = self.uc.list_stock_takes(status)?;
+        Ok(StockTakePresenter::to_dtos(sessions))
+    }
+
+    pub fn get_count_sheet(&self, stock_take_id: i32) -> Result<CountSheetDto, AppError> {
+        let (st, lines) = self.uc.count_sheet(stock_take_id)?;
+        Ok(StockTakePresenter::to_count_sheet_dto(st, lines))
+    }
+
+    pub fn record_counts(&self, dto: RecordCountsDto) -> Result<(), AppError> {
+        dto.validate()
+            .map_err(|e| AppError::Validation(e.to_string()))?;
+        let items = dto
+            .items
+            .into_iter()
+            .map(|d| CountedItem {
+                upc: d.upc.trim().to_string(),
+                quantity: d.quantity,
+            })
+            .collect();
+        self.uc.record_counts(dto.stock_take_id, items)
+    }
+
+    pub fn cancel_stock_take(&self, stock_take_id: i32) -> Result<(), AppError> {
+        self.uc.cancel_stock_take(stock_take_id)
+    }
+
+    pub fn get_variance_report(&self, stock_take_id: i32) -> Result<VarianceReportDto, AppError> {
+        let report = self.uc.variance_report(stock_take_id)?;
+        Ok(StockTakePresenter::to_variance_dto(report))
+    }
+
+    pub fn post_stock_take(&self, dto: PostStockTakeDto) -> Result<VarianceReportDto, AppError> {
+        dto.validate()
+            .map_err(|e| AppError::Validation(e.to_string()))?;
+        let report = self
+            .uc
+            .post_stock_take(dto.stock_take_id, dto.operator_mdoc)?;
+        if let (Some(printer), Some(sumatra)) = (&dto.printer_name, &dto.sumatra_location) {
+            // variances are already posted; a printer fault must not fail the post
+            if let Err(e) = self.uc.print_variance_report(&report, printer, sumatra) {
+                log::error!(
+                    "variance report for stock take {} failed to print: {e}",
+                    dto.stock_take_id
+                );
+            }
+        }
+        Ok(StockTakePresenter::to_variance_dto(report))
+    }
+
+    pub fn print_variance_report(
+        &self,
+        stock_take_id: i32,
+        printer_name: String,
+        sumatra_location: String,
+    ) -> Result<(), AppError> {
+        let report = self.uc.variance_report(stock_take_id)?;
+        self.uc
+            .print_variance_report(&report, &printer_name, &sumatra_location)
+    }
+}
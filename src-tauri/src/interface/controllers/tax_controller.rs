@@ -0,0 +1,118 @@
+use crate::application::use_cases::tax_usecases::TaxUseCases;
+use crate::common::error::AppError;
+use crate::domain::models::{TaxRate, TaxSettings};
+use crate::domain::repos::{CategoryRepoTrait, ProductRepoTrait, TaxRateRepoTrait};
+use crate::interface::common::date_utils::parse_rfc3339_utc;
+use crate::interface::dto::tax_dto::{TaxRateDto, TaxSettingsDto};
+use crate::interface::presenters::tax_presenter::TaxPresenter;
+use std::sync::Arc;
+use validator::Validate;
+
+pub struct TaxController {
+    uc: TaxUseCases,
+}
+
+impl TaxController {
+    pub fn new(
+        tax_repo: Arc<dyn TaxRateRepoTrait>,
+        prod_repo: Arc<dyn ProductRepoTrait>,
+        category_repo: Arc<dyn CategoryRepoTrait>,
+    ) -> Self {
+        Self {
+            uc: TaxUseCases::new(tax_repo, prod_repo, category_repo),
+        }
+    }
+
+    pub fn list_tax_rates(&self) -> Result<Vec<TaxRateDto>, AppError> {
+        let rates = self.uc.list_rates()?;
+        Ok(TaxPresenter::to_dtos(rates))
+    }
+
+    pub fn create_tax_rate(&self, dto: TaxRateDto) -> Result<i32, AppError> {
+        dto.validate()
+            .map_err(|e| AppError::Validation(e.to_string()))?;
+        self.uc.create_rate(TaxRate {
+            id: 0,
+            upc: dto.upc,
+            category: dto.category,
+            rate: dto.rate,
+            effective_at: dto
+                .effective_at
+                .as_deref()
+                .map(parse_rfc3339_utc)
+                .transpose()?,
+            created_at: None,
+            deleted: None,
+        })
+    }
+
+    pub fn delete_tax_rate(&self, id: i32) -> Result<(), AppError> {
+        self.uc.delete_rate(id)
+    }
+
+    pub fn get_settings(&self) -> Result<TaxSettingsDto, AppError> {
+        let settings = self.uc.get_settings()?;
+        Ok(TaxPresenter::to_settings_dto(settings))
+    }
+
+    pub fn set_settings(&self, dto: TaxSettingsDto) -> Result<(), AppError> {
+        self.uc.set_settings(&TaxSettings {
+            counts_toward_limit: dto.counts_toward_limit,
+        })
+    }
+}
+
+#[cfg(test)]
+mod smoke {
+    use super::*;
+    use crate::test_support::mock_category_repo::MockCategoryRepo;
+    use crate::test_support::mock_product_repo::MockProductRepo;
+    use crate::test_support::mock_tax_rate_repo::MockTaxRateRepo;
+
+    #[test]
+    fn rates_and_settings_round_trip() {
+        let categories = Arc::new(MockCategoryRepo::new());
+        categories.create("Snacks".into()).unwrap();
+        let ctrl = TaxController::new(
+            Arc::new(MockTaxRateRepo::new()),
+            Arc::new(MockProductRepo::new()),
+            categories,
+        );
+        let dto = TaxRateDto {
+            category: Some("Snacks".into()),
+            rate: 825,
+            effective_at: Some("2026-01-01T05:00:00Z".into()),
+            ..Default::default()
+        };
+        let id = ctrl.create_tax_rate(dto.clone()).unwrap();
+        assert!(ctrl
+            .create_tax_rate(TaxRateDto {
+                rate: 10_001,
+                ..dto.clone()
+            })
+            .is_err());
+        assert!(ctrl
+            .create_tax_rate(TaxRateDto {
+                effective_at: Some("next year".into()),
+                ..dto
+            })
+            .is_err());
+
+        let listed = ctrl.list_tax_rates().unwrap();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].id, id);
+        assert_eq!(
+            listed[0].effective_at.as_deref(),
+            Some("2026-01-01T05:00:00+00:00")
+        );
+
+        assert!(ctrl.get_settings().unwrap().counts_toward_limit);
+        ctrl.set_settings(TaxSettingsDto {
+            counts_toward_limit: false,
+        })
+        .unwrap();
+        assert!(!ctrl.get_settings().unwrap().counts_toward_limit);
+        ctrl.delete_tax_rate(id).unwrap();
+        assert!(ctrl.list_tax_rates().unwrap().is_empty());
+    }
+}
@@ -1,17 +1,31 @@
-use crate::application::use_cases::printer_usecases::PrinterUseCases;
+use crate::application::use_cases::limit_usecases::LimitUseCases;
+use crate::application::use_cases::print_queue_usecases::PrintQueueUseCases;
+use crate::application::use_cases::promotion_usecases::PromotionUseCases;
+use crate::application::use_cases::tax_usecases::TaxUseCases;
 use crate::application::use_cases::transaction_usecases::TransactionUseCases;
 use crate::common::error::AppError;
 use crate::domain::models::customer_transaction::CustomerTransaction;
 use crate::domain::models::customer_tx_detail::CustomerTxDetail;
 use crate::domain::models::inventory_transaction::InventoryTransaction;
-use crate::interface::dto::customer_transaction_dto::CustomerTransactionSearchResult;
+use crate::domain::models::{
+    InventoryReason, LotLabel, NegativeStockPolicy, OrderReason, OrderReturnLine, PrintJob,
+    PrintJobStatus, SaleOutcome,
+};
+use crate::interface::common::date_utils::{parse_date, parse_rfc3339};
+use crate::interface::dto::customer_limit_dto::{CustomerLimitDto, ItemAllowanceDto};
+use crate::interface::dto::customer_transaction_dto::{
+    CustomerTransactionSearchResult, VoidOrderDto,
+};
 use crate::interface::dto::customer_tx_detail_dto::CustomerTxDetailDto;
 use crate::interface::dto::inventory_transaction_dto::{
-    CreateInventoryTransactionDto, InventoryTransactionSearchResult, ReadInventoryTransactionDto,
+    CreateInventoryTransactionDto, ExpiringLotDto, InventorySettingsDto,
+    InventoryTransactionSearchResult, ReadInventoryTransactionDto, ShrinkageReportDto,
+    WriteOffExpiredDto,
 };
-use crate::interface::dto::printer_dto::PrintableLineItem;
-use crate::interface::dto::printer_dto::PrintableSaleDto;
+use crate::interface::dto::printer_dto::{PrintableSaleDto, ReceiptCopies};
+use crate::interface::dto::return_dto::ReturnDto;
 use crate::interface::dto::sale_dto::SaleDto;
+use crate::interface::presenters::customer_limit_presenter::CustomerLimitPresenter;
 use crate::interface::presenters::customer_transaction_presenter::CustomerTransactionPresenter;
 use crate::interface::presenters::customer_tx_detail_presenter::CustomerTxDetailPresenter;
 use crate::interface::presenters::inventory_transaction_presenter::InventoryTransactionPresenter;
@@ -23,38 +37,52 @@ pub struct TransactionControllerDeps {
     pub cust_tx_repo: Arc<dyn crate::domain::repos::CustomerTransactionRepoTrait>,
     pub cust_tx_detail_repo: Arc<dyn crate::domain::repos::CustomerTxDetailRepoTrait>,
     pub limit_repo: Arc<dyn crate::domain::repos::WeeklyLimitRepoTrait>,
-    pub runner: Arc<dyn crate::infrastructure::command_runner::CommandRunner>,
-    pub customer_repo: Arc<dyn crate::domain::repos::CustomerRepoTrait>,
+    pub customer_limit_repo: Arc<dyn crate::domain::repos::CustomerLimitRepoTrait>,
+    pub quantity_limit_repo: Arc<dyn crate::domain::repos::QuantityLimitRepoTrait>,
     pub prod_repo: Arc<dyn crate::domain::repos::ProductRepoTrait>,
-    pub club_tx_repo: Arc<dyn crate::domain::repos::ClubTransactionRepoTrait>,
-    pub club_import_repo: Arc<dyn crate::domain::repos::ClubImportRepoTrait>,
+    pub return_repo: Arc<dyn crate::domain::repos::OrderReturnRepoTrait>,
+    pub price_repo: Arc<dyn crate::domain::repos::PriceAdjustmentRepoTrait>,
+    pub promo_repo: Arc<dyn crate::domain::repos::PromotionRepoTrait>,
+    pub tax_repo: Arc<dyn crate::domain::repos::TaxRateRepoTrait>,
+    pub category_repo: Arc<dyn crate::domain::repos::CategoryRepoTrait>,
+    pub print_queue: Arc<PrintQueueUseCases>,
     pub conn: Arc<Mutex<rusqlite::Connection>>,
 }
 
 pub struct TransactionController {
     tx_uc: TransactionUseCases,
-    printer_uc: PrinterUseCases,
+    print_queue: Arc<PrintQueueUseCases>,
 }
 
 impl TransactionController {
     pub fn new(deps: TransactionControllerDeps) -> Self {
         let tx_uc = TransactionUseCases::new(
-            deps.inv_repo.clone(),
-            deps.cust_tx_repo.clone(),
-            deps.cust_tx_detail_repo.clone(),
-            deps.limit_repo.clone(),
-            deps.conn.clone(),
-        );
-        let printer_uc = PrinterUseCases::new(
-            deps.runner,
-            deps.customer_repo,
-            deps.prod_repo,
+            deps.inv_repo,
             deps.cust_tx_repo,
             deps.cust_tx_detail_repo,
-            deps.club_import_repo,
-            deps.club_tx_repo,
+            Arc::new(LimitUseCases::new(
+                deps.limit_repo,
+                deps.customer_limit_repo,
+                deps.quantity_limit_repo,
+            )),
+            Arc::new(PromotionUseCases::new(
+                deps.promo_repo,
+                Arc::clone(&deps.prod_repo),
+            )),
+            Arc::new(TaxUseCases::new(
+                deps.tax_repo,
+                Arc::clone(&deps.prod_repo),
+                deps.category_repo,
+            )),
+            deps.prod_repo,
+            deps.return_repo,
+            deps.price_repo,
+            deps.conn,
         );
-        Self { tx_uc, printer_uc }
+        Self {
+            tx_uc,
+            print_queue: deps.print_queue,
+        }
     }
 
     pub fn inventory_adjustment(
@@ -63,6 +91,12 @@ impl TransactionController {
     ) -> Result<ReadInventoryTransactionDto, AppError> {
         dto.validate()
             .map_err(|e| AppError::Validation(e.to_string()))?;
+        // stock coming in with no reason given is a receipt
+        let reason = match dto.reason.as_deref() {
+            Some(r) => InventoryReason::parse(r)?,
+            None if dto.quantity_change > 0 => InventoryReason::Receipt,
+            None => InventoryReason::Other,
+        };
         let tx = InventoryTransaction {
             id: Some(0), // new record, gets auto-assigned by db
             upc: dto.upc,
@@ -72,9 +106,18 @@ impl TransactionController {
             ref_order_id: dto.ref_order_id,
             reference: dto.reference,
             created_at: None,
+            po_id: None,
+            unit_cost: dto.unit_cost,
+            stock_take_id: None,
+            reason,
+            lot_id: None,
         };
 
-        let itx = self.tx_uc.inventory_adjustment(tx)?;
+        let expires_on = dto.expires_on.as_deref().map(parse_date).transpose()?;
+        let itx = match LotLabel::new(dto.lot_number, expires_on) {
+            Some(lot) => self.tx_uc.receive_into_lot(tx, lot)?,
+            None => self.tx_uc.inventory_adjustment(tx)?,
+        };
         Ok(InventoryTransactionPresenter::to_dto(itx))
     }
 
@@ -84,12 +127,25 @@ impl TransactionController {
         printer_name: &str,
         sumatra_location: &str,
     ) -> Result<i32, AppError> {
+        dto.validate()
+            .map_err(|e| AppError::Validation(e.to_string()))?;
+        let reason = dto
+            .reason
+            .as_deref()
+            .map(OrderReason::parse)
+            .transpose()?
+            .unwrap_or_default();
         let cust_tx = CustomerTransaction {
             order_id: 0,
             customer_mdoc: dto.customer_mdoc,
             operator_mdoc: dto.operator_mdoc,
             date: None,
-            note: None,
+            note: dto
+                .note
+                .map(|n| n.trim().to_string())
+                .filter(|n| !n.is_empty()),
+            idempotency_key: dto.idempotency_key,
+            reason,
         };
 
         // build inventory transactions and customer transaction detail lines
@@ -99,12 +155,17 @@ impl TransactionController {
             invs.push(InventoryTransaction {
                 id: None,
                 upc: item.upc.clone(),
-                quantity_change: item.quantity,
+                quantity_change: -item.quantity,
                 operator_mdoc: dto.operator_mdoc,
                 customer_mdoc: Some(dto.customer_mdoc),
                 ref_order_id: None,
                 reference: None,
                 created_at: None,
+                po_id: None,
+                unit_cost: None,
+                stock_take_id: None,
+                reason: InventoryReason::Sale,
+                lot_id: None,
             });
             details.push(CustomerTxDetail {
                 detail_id: 0,
@@ -112,21 +173,34 @@ impl TransactionController {
                 upc: item.upc,
                 quantity: item.quantity,
                 price: item.price,
+                cost: None, // filled in from the product when the sale is recorded
+                discount: 0,
+                promotion_id: None,
+                promotion_name: None,
+                tax: 0,
+                tax_rate: None,
+                limit_exempt: false,
             });
         }
 
-        let order_id = self.tx_uc.sale_transaction(cust_tx, invs, details);
-        if let Ok(order_id) = order_id {
-            let printable = self.get_sale_details(order_id)?;
-            self.printer_uc.print_receipts(
-                &printable,
-                printer_name,
-                sumatra_location,
-                &dto.customer_name,
-                &dto.operator_name,
-            )?;
+        // a replay queues the receipt only if the first attempt never did
+        let (SaleOutcome::Created(order_id) | SaleOutcome::Replayed(order_id)) =
+            self.tx_uc.sale_transaction(cust_tx, invs, details)?;
+
+        // the sale is committed; a queueing failure must not be reported as a
+        // failed sale
+        if let Err(e) = self.print_queue.enqueue_receipt(Self::print_job(
+            order_id,
+            ReceiptCopies::Both,
+            printer_name,
+            sumatra_location,
+            dto.operator_name,
+            dto.customer_name,
+            None,
+        )) {
+            log::error!("receipt for order {order_id} could not be queued: {e}");
         }
-        order_id
+        Ok(order_id)
     }
 
     pub fn search_inventory_transactions(
@@ -134,11 +208,15 @@ impl TransactionController {
         page: i32,
         date: Option<String>,
         search: Option<String>,
+        reason: Option<String>,
     ) -> Result<InventoryTransactionSearchResult, AppError> {
-        let items = self
+        let reason = reason.as_deref().map(InventoryReason::parse).transpose()?;
+        let items =
+            self.tx_uc
+                .search_inventory_transactions(page, date.clone(), search.clone(), reason)?;
+        let total = self
             .tx_uc
-            .search_inventory_transactions(page, date.clone(), search.clone())?;
-        let total = self.tx_uc.count_inventory_transactions(date, search)?;
+            .count_inventory_transactions(date, search, reason)?;
         let rows = items
             .into_iter()
             .map(|(tx, pname, oname)| {
@@ -151,6 +229,71 @@ impl TransactionController {
         })
     }
 
+    pub fn get_inventory_settings(&self) -> Result<InventorySettingsDto, AppError> {
+        let policy = self.tx_uc.negative_stock_policy()?;
+        Ok(InventorySettingsDto {
+            negative_stock_policy: policy.as_str().to_string(),
+        })
+    }
+
+    pub fn set_inventory_settings(&self, dto: InventorySettingsDto) -> Result<(), AppError> {
+        let policy = NegativeStockPolicy::parse(&dto.negative_stock_policy)?;
+        self.tx_uc.set_negative_stock_policy(policy)
+    }
+
+    pub fn get_shrinkage_report(
+        &self,
+        start_date: String,
+        end_date: String,
+    ) -> Result<ShrinkageReportDto, AppError> {
+        let start_date = parse_rfc3339(&start_date)?;
+        let end_date = parse_rfc3339(&end_date)?;
+        let lines = self.tx_uc.shrinkage_report(start_date, end_date)?;
+        Ok(InventoryTransactionPresenter::to_shrinkage_dto(lines))
+    }
+
+    pub fn print_shrinkage_report(
+        &self,
+        start_date: String,
+        end_date: String,
+        printer_name: String,
+        sumatra_location: String,
+    ) -> Result<(), AppError> {
+        let start_date = parse_rfc3339(&start_date)?;
+        let end_date = parse_rfc3339(&end_date)?;
+        self.tx_uc
+            .print_shrinkage_report(start_date, end_date, &printer_name, &sumatra_location)
+    }
+
+    pub fn get_expiring_lots(&self, days: i64) -> Result<Vec<ExpiringLotDto>, AppError> {
+        let (today, lots) = self.tx_uc.expiring_lots(days)?;
+        Ok(InventoryTransactionPresenter::to_expiring_dto_list(
+            lots, today,
+        ))
+    }
+
+    pub fn print_expiring_lots(
+        &self,
+        days: i64,
+        printer_name: String,
+        sumatra_location: String,
+    ) -> Result<(), AppError> {
+        self.tx_uc
+            .print_expiring_lots(days, &printer_name, &sumatra_location)
+    }
+
+    pub fn write_off_expired_lots(
+        &self,
+        dto: WriteOffExpiredDto,
+    ) -> Result<Vec<ReadInventoryTransactionDto>, AppError> {
+        dto.validate()
+            .map_err(|e| AppError::Validation(e.to_string()))?;
+        let rows = self
+            .tx_uc
+            .write_off_expired_lots(&dto.lot_ids, dto.operator_mdoc)?;
+        Ok(InventoryTransactionPresenter::to_dto_list(rows))
+    }
+
     pub fn list_order_details(&self, order_id: i32) -> Result<Vec<CustomerTxDetailDto>, AppError> {
         let dets = self.tx_uc.list_order_details(order_id)?;
         Ok(CustomerTxDetailPresenter::to_dto_list(dets))
@@ -163,9 +306,9 @@ impl TransactionController {
         date: Option<String>,
         search: Option<String>,
     ) -> Result<CustomerTransactionSearchResult, AppError> {
-        let tuples: Vec<(CustomerTransaction, String, i32)> = self
-            .tx_uc
-            .search_customer_transactions(page, mdoc, date.clone(), search.clone())?;
+        let tuples =
+            self.tx_uc
+                .search_customer_transactions(page, mdoc, date.clone(), search.clone())?;
         let total = self.tx_uc.count_customer_transactions(mdoc, date, search)?;
         Ok(CustomerTransactionSearchResult {
             items: CustomerTransactionPresenter::to_search_rows(tuples),
@@ -173,23 +316,101 @@ impl TransactionController {
         })
     }
 
-    pub fn get_sale_details(&self, order_id: i32) -> Result<PrintableSaleDto, AppError> {
-        let (tx, details, balance) = self.tx_uc.get_sale_details(order_id)?;
-        let items = details
-            .into_iter()
-            .map(|(d, desc)| PrintableLineItem {
-                upc: d.upc,
-                desc,
-                quantity: d.quantity,
-                price: d.price,
+    pub fn void_order(&self, dto: VoidOrderDto) -> Result<i32, AppError> {
+        dto.validate()
+            .map_err(|e| AppError::Validation(e.to_string()))?;
+        self.tx_uc
+            .void_order(dto.order_id, dto.operator_mdoc, dto.reason)
+    }
+
+    // Records a partial return and queues a return receipt for it.
+    pub fn return_items(
+        &self,
+        dto: ReturnDto,
+        printer_name: &str,
+        sumatra_location: &str,
+    ) -> Result<i32, AppError> {
+        dto.validate()
+            .map_err(|e| AppError::Validation(e.to_string()))?;
+        let lines = dto
+            .items
+            .iter()
+            .map(|l| OrderReturnLine {
+                return_order_id: 0,
+                detail_id: l.detail_id,
+                quantity: l.quantity,
+                restocked: l.restock,
             })
             .collect();
+        let return_id =
+            self.tx_uc
+                .return_items(dto.order_id, dto.operator_mdoc, lines, dto.reason)?;
 
-        Ok(PrintableSaleDto {
-            transaction: tx,
-            items,
-            balance,
-        })
+        // as with sales, the return stands even if its receipt can't be queued
+        if let Err(e) = self.print_queue.enqueue(Self::print_job(
+            return_id,
+            ReceiptCopies::Both,
+            printer_name,
+            sumatra_location,
+            dto.operator_name,
+            dto.customer_name,
+            None,
+        )) {
+            log::error!("receipt for return order {return_id} could not be queued: {e}");
+        }
+        Ok(return_id)
+    }
+
+    pub fn reprint_receipt(
+        &self,
+        order_id: i32,
+        copies: ReceiptCopies,
+        operator_mdoc: i32,
+        printer_name: &str,
+        sumatra_location: &str,
+    ) -> Result<i32, AppError> {
+        // names are looked up by the printer when the reprint is stamped
+        self.print_queue.enqueue(Self::print_job(
+            order_id,
+            copies,
+            printer_name,
+            sumatra_location,
+            String::new(),
+            String::new(),
+            Some(operator_mdoc),
+        ))
+    }
+
+    pub fn get_sale_details(&self, order_id: i32) -> Result<PrintableSaleDto, AppError> {
+        let details = self.tx_uc.get_sale_details(order_id)?;
+        Ok(PrintableSaleDto::from_details(details))
+    }
+
+    fn print_job(
+        order_id: i32,
+        copies: ReceiptCopies,
+        printer_name: &str,
+        sumatra_location: &str,
+        operator_name: String,
+        customer_name: String,
+        reprint_by: Option<i32>,
+    ) -> PrintJob {
+        PrintJob {
+            id: 0,
+            order_id,
+            copies: copies.as_str().to_string(),
+            printer_name: printer_name.to_string(),
+            sumatra_location: sumatra_location.to_string(),
+            operator_name,
+            customer_name,
+            reprint_by,
+            status: PrintJobStatus::Pending,
+            attempts: 0,
+            last_error: None,
+            created_at: None,
+            updated_at: None,
+            balance: None, // captured by the queue
+        }
     }
 
     pub fn get_weekly_limit(&self) -> Result<i32, AppError> {
@@ -203,4 +424,132 @@ impl TransactionController {
     pub fn get_weekly_spent(&self, customer_mdoc: i32) -> Result<i32, AppError> {
         self.tx_uc.get_weekly_spent(customer_mdoc)
     }
+
+    pub fn get_customer_limit(&self, customer_mdoc: i32) -> Result<CustomerLimitDto, AppError> {
+        let resolved = self.tx_uc.get_customer_limit(customer_mdoc)?;
+        Ok(CustomerLimitPresenter::to_limit_dto(
+            customer_mdoc,
+            resolved,
+        ))
+    }
+
+    pub fn get_item_allowances(
+        &self,
+        customer_mdoc: i32,
+        upcs: Vec<String>,
+    ) -> Result<Vec<ItemAllowanceDto>, AppError> {
+        let items = self.tx_uc.get_item_allowances(customer_mdoc, upcs)?;
+        Ok(CustomerLimitPresenter::to_item_allowance_dtos(items))
+    }
+}
+
+#[cfg(test)]
+mod smoke {
+    use super::*;
+    use crate::application::use_cases::print_queue_usecases::ReceiptPrinter;
+    use crate::domain::models::Product;
+    use crate::domain::repos::{
+        CustomerTransactionRepoTrait, PrintJobRepoTrait, ProductRepoTrait, WeeklyLimitRepoTrait,
+    };
+    use crate::test_support::mock_category_repo::MockCategoryRepo;
+    use crate::test_support::mock_customer_limit_repo::MockCustomerLimitRepo;
+    use crate::test_support::mock_customer_tx_detail_repo::MockCustomerTxDetailRepo;
+    use crate::test_support::mock_customer_tx_repo::MockCustomerTransactionRepo;
+    use crate::test_support::mock_inventory_transaction_repo::MockInventoryTransactionRepo;
+    use crate::test_support::mock_order_return_repo::MockOrderReturnRepo;
+    use crate::test_support::mock_price_adjustment_repo::MockPriceAdjustmentRepo;
+    use crate::test_support::mock_print_job_repo::MockPrintJobRepo;
+    use crate::test_support::mock_product_repo::MockProductRepo;
+    use crate::test_support::mock_promotion_repo::MockPromotionRepo;
+    use crate::test_support::mock_quantity_limit_repo::MockQuantityLimitRepo;
+    use crate::test_support::mock_tax_rate_repo::MockTaxRateRepo;
+    use crate::test_support::mock_weekly_limit_repo::MockWeeklyLimitRepo;
+
+    struct NoPrinter;
+
+    impl ReceiptPrinter for NoPrinter {
+        fn print(&self, _job: &PrintJob, _printable: PrintableSaleDto) -> Result<(), AppError> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn a_retried_checkout_records_one_order() {
+        let prod_repo = Arc::new(MockProductRepo::default());
+        let cust_tx_repo = Arc::new(MockCustomerTransactionRepo::default());
+        let limit_repo = Arc::new(MockWeeklyLimitRepo::default());
+        let job_repo = Arc::new(MockPrintJobRepo::default());
+        let return_repo = Arc::new(MockOrderReturnRepo::default());
+        prod_repo
+            .create(&Product {
+                upc: "000000000555".into(),
+                price: 200,
+                ..Default::default()
+            })
+            .unwrap();
+        cust_tx_repo.set_balance(2, 1000);
+        limit_repo.set_limit(1000).unwrap();
+        let ctrl = TransactionController::new(TransactionControllerDeps {
+            inv_repo: Arc::new(MockInventoryTransactionRepo::default()),
+            cust_tx_repo: cust_tx_repo.clone(),
+            cust_tx_detail_repo: Arc::new(MockCustomerTxDetailRepo::default()),
+            limit_repo,
+            customer_limit_repo: Arc::new(MockCustomerLimitRepo::default()),
+            quantity_limit_repo: Arc::new(MockQuantityLimitRepo::default()),
+            prod_repo,
+            return_repo: return_repo.clone(),
+            price_repo: Arc::new(MockPriceAdjustmentRepo::default()),
+            promo_repo: Arc::new(MockPromotionRepo::default()),
+            tax_repo: Arc::new(MockTaxRateRepo::default()),
+            category_repo: Arc::new(MockCategoryRepo::default()),
+            print_queue: Arc::new(PrintQueueUseCases::new(
+                job_repo.clone(),
+                cust_tx_repo.clone(),
+                return_repo,
+                Arc::new(NoPrinter),
+            )),
+            conn: Arc::new(Mutex::new(rusqlite::Connection::open_in_memory().unwrap())),
+        });
+        ctrl.inventory_adjustment(
+            serde_json::from_value(serde_json::json!({
+                "upc": "000000000555",
+                "quantity_change": 5,
+                "operator_mdoc": 1,
+            }))
+            .unwrap(),
+        )
+        .unwrap();
+
+        // as the POS sends it: one key per checkout, resent on a retry
+        let sale: SaleDto = serde_json::from_value(serde_json::json!({
+            "customer_mdoc": 2,
+            "operator_mdoc": 1,
+            "operator_name": "Op",
+            "customer_name": "Cust",
+            "items": [{ "upc": "000000000555", "desc": "C", "quantity": 4, "price": 200 }],
+            "idempotency_key": "4f1c2a9e-checkout",
+        }))
+        .unwrap();
+        let first = ctrl.sale_transaction(sale.clone(), "", "").unwrap();
+        let again = ctrl.sale_transaction(sale.clone(), "", "").unwrap();
+
+        assert_eq!(again, first);
+        assert_eq!(cust_tx_repo.list().unwrap().len(), 1);
+        assert_eq!(job_repo.list(None, 10).unwrap().len(), 1);
+
+        // a receipt lost with the first attempt is queued by the retry, once
+        job_repo.clear();
+        ctrl.sale_transaction(sale.clone(), "", "").unwrap();
+        ctrl.sale_transaction(sale.clone(), "", "").unwrap();
+        assert_eq!(job_repo.list(None, 10).unwrap().len(), 1);
+
+        // the same key with a different cart isn't a retry
+        let mut changed = sale;
+        changed.items[0].quantity = 3;
+        assert!(matches!(
+            ctrl.sale_transaction(changed, "", ""),
+            Err(AppError::Validation(_))
+        ));
+        assert_eq!(cust_tx_repo.list().unwrap().len(), 1);
+    }
 }
@@ -23,6 +23,16 @@ pub struct CategoryDto {
 
     #[validate(custom(function = "validate_optional_rfc3339_str"))]
     pub deleted: Option<String>, // RFC3339 if soft-deleted
+
+    #[serde(default)]
+    pub limit_exempt: bool,
+}
+
+#[derive(serde::Deserialize, Validate)]
+pub struct SetCategoryLimitExemptDto {
+    #[validate(range(min = 1, message = "id must be non-zero and positive"))]
+    pub id: i32,
+    pub limit_exempt: bool,
 }
 
 #[cfg(test)]
@@ -36,6 +46,7 @@ mod tests {
             id: 42,
             name: "Widgets".into(),
             deleted: None,
+            limit_exempt: false,
         };
         assert!(dto.validate().is_ok());
     }
@@ -46,6 +57,7 @@ mod tests {
             id: 0,
             name: "".into(),
             deleted: None,
+            limit_exempt: false,
         };
         let err = dto.validate().unwrap_err().to_string();
         assert!(err.contains("id"));
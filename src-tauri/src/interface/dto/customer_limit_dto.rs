@@ -0,0 +1,108 @@
+use crate::interface::common::validators::validate_date_str;
+use serde::{Deserialize, Serialize};
+use validator_derive::Validate;
+
+#[derive(Serialize, Deserialize, Validate, Clone)]
+pub struct CustomerTierDto {
+    pub id: i32, // 0 when creating
+    #[validate(length(min = 1, message = "name cannot be empty"))]
+    pub name: String,
+    #[validate(range(min = 0, message = "limit_amount cannot be negative"))]
+    pub limit_amount: i32,
+}
+
+#[derive(Serialize, Deserialize, Validate, Clone)]
+pub struct LimitOverrideDto {
+    pub id: i32, // 0 when creating
+    #[validate(range(min = 1, message = "customer_mdoc must be non-zero and positive"))]
+    pub customer_mdoc: i32,
+    #[validate(range(min = 0, message = "limit_amount cannot be negative"))]
+    pub limit_amount: i32,
+    #[validate(custom(function = "validate_date_str"))]
+    pub starts_on: String, // YYYY-MM-DD
+    #[validate(custom(function = "validate_date_str"))]
+    pub ends_on: Option<String>, // YYYY-MM-DD, inclusive
+    #[validate(length(min = 1, message = "reason is required"))]
+    pub reason: String,
+    #[validate(range(min = 1, message = "operator_mdoc must be non-zero and positive"))]
+    pub operator_mdoc: i32,
+    pub created_at: Option<String>, // RFC3339, set by the server
+}
+
+#[derive(Serialize, Deserialize, Validate, Clone)]
+pub struct LimitSettingsDto {
+    pub period: String, // daily | weekly | biweekly | monthly | yearly
+    #[validate(range(min = 0, max = 6, message = "week_start must be 0 (Sunday) to 6"))]
+    pub week_start: u32,
+    #[validate(custom(function = "validate_date_str"))]
+    pub anchor_date: String, // YYYY-MM-DD, a day a bi-weekly period starts on
+    #[validate(length(min = 1, message = "time_zone is required"))]
+    pub time_zone: String, // IANA name
+}
+
+#[derive(Serialize, Deserialize, Validate, Clone)]
+pub struct QuantityLimitDto {
+    pub id: i32,             // 0 when creating
+    pub upc: Option<String>, // set exactly one of upc / category
+    pub category: Option<String>,
+    #[validate(range(min = 0, message = "max_quantity cannot be negative"))]
+    pub max_quantity: i32,
+    pub window: String, // rolling | daily | weekly | biweekly | monthly | yearly
+    pub rolling_days: Option<i32>, // required for rolling windows
+    pub note: Option<String>,
+    pub created_at: Option<String>, // RFC3339, set by the server
+}
+
+#[derive(Serialize)]
+pub struct QuantityAllowanceDto {
+    pub limit: QuantityLimitDto,
+    pub purchased: i32,
+    pub remaining: i32,
+    pub window_start: String, // RFC3339
+    pub window_end: String,   // RFC3339
+}
+
+// What the selected customer can still buy of one item; remaining is None when unlimited.
+#[derive(Serialize)]
+pub struct ItemAllowanceDto {
+    pub upc: String,
+    pub remaining: Option<i32>,
+    pub limits: Vec<QuantityAllowanceDto>,
+}
+
+// The limit in force for a customer today and why.
+#[derive(Serialize)]
+pub struct CustomerLimitDto {
+    pub customer_mdoc: i32,
+    pub amount: i32,
+    pub source: String, // default | tier | override
+    pub tier: Option<CustomerTierDto>,
+    pub limit_override: Option<LimitOverrideDto>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use validator::Validate;
+
+    #[test]
+    fn override_dates_and_reason_are_validated() {
+        let mut dto = LimitOverrideDto {
+            id: 0,
+            customer_mdoc: 7,
+            limit_amount: 5000,
+            starts_on: "2025-03-01".into(),
+            ends_on: None,
+            reason: "medical diet".into(),
+            operator_mdoc: 1,
+            created_at: None,
+        };
+        assert!(dto.validate().is_ok());
+
+        dto.ends_on = Some("03/10/2025".into());
+        dto.reason = String::new();
+        let err = dto.validate().unwrap_err().to_string();
+        assert!(err.contains("ends_on"));
+        assert!(err.contains("reason"));
+    }
+}
@@ -16,6 +16,7 @@ pub struct CustomerTransactionDto {
     pub date: Option<String>, // RFC3339 string, optional
 
     pub note: Option<String>,
+    pub reason: String, // reason code, e.g. "indigent_kit"
 }
 
 #[derive(Serialize)]
@@ -29,6 +30,17 @@ pub struct CustomerTransactionSearchRow {
     pub transaction: CustomerTransactionDto,
     pub operator_name: String,
     pub spent: i32,
+    pub voided: bool,
+}
+
+#[derive(Deserialize, Validate, Clone)]
+pub struct VoidOrderDto {
+    #[validate(range(min = 1, message = "order_id must be non-zero and positive"))]
+    pub order_id: i32,
+    #[validate(range(min = 1, message = "operator_mdoc must be non-zero and positive"))]
+    pub operator_mdoc: i32,
+    #[validate(length(min = 1, message = "reason is required"))]
+    pub reason: String,
 }
 
 #[cfg(test)]
@@ -44,6 +56,7 @@ mod tests {
             operator_mdoc: 8,
             date: None,
             note: None,
+            reason: "regular".into(),
         };
         assert!(dto.validate().is_ok());
     }
@@ -56,6 +69,7 @@ mod tests {
             operator_mdoc: 0,
             date: Some("nope".into()),
             note: None,
+            reason: "regular".into(),
         };
         let err = dto.validate().unwrap_err().to_string();
         assert!(err.contains("order_id"));
@@ -63,4 +77,14 @@ mod tests {
         assert!(err.contains("operator_mdoc"));
         assert!(err.contains("date"));
     }
+
+    #[test]
+    fn void_requires_reason() {
+        let dto = VoidOrderDto {
+            order_id: 3,
+            operator_mdoc: 1,
+            reason: String::new(),
+        };
+        assert!(dto.validate().unwrap_err().to_string().contains("reason"));
+    }
 }
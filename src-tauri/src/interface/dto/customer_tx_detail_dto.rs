@@ -26,6 +26,8 @@ pub struct CustomerTxDetailDto {
     pub product_name: String,
     pub quantity: i32,
     pub price: i32,
+    pub discount: i32, // cents a promotion took off the line
+    pub tax: i32,      // sales tax charged on the line
 }
 
 #[cfg(test)]
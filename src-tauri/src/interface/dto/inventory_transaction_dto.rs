@@ -1,4 +1,6 @@
-use crate::interface::common::validators::{validate_optional_rfc3339_str, validate_upc_str};
+use crate::interface::common::validators::{
+    validate_date_str, validate_optional_rfc3339_str, validate_upc_str,
+};
 use serde::{Deserialize, Serialize};
 use validator_derive::Validate;
 
@@ -22,6 +24,23 @@ pub struct CreateInventoryTransactionDto {
 
     #[validate(custom(function = "validate_optional_rfc3339_str"))]
     pub created_at: Option<String>, // RFC3339
+
+    // cents per unit paid; only for stock received (positive quantity_change)
+    #[serde(default)]
+    #[validate(range(min = 0, message = "unit_cost cannot be negative"))]
+    pub unit_cost: Option<i32>,
+
+    // receipt, shrinkage, damage, expiry, vendor_return, count_correction or other
+    #[serde(default)]
+    pub reason: Option<String>,
+
+    // the batch stock is received into; either one starts tracking a lot
+    #[serde(default)]
+    pub lot_number: Option<String>,
+
+    #[serde(default)]
+    #[validate(custom(function = "validate_date_str"))]
+    pub expires_on: Option<String>, // YYYY-MM-DD
 }
 
 #[derive(Serialize)]
@@ -34,6 +53,11 @@ pub struct ReadInventoryTransactionDto {
     pub customer_mdoc: Option<i32>,
     pub ref_order_id: Option<i32>,
     pub created_at: Option<String>, // RFC3339
+    pub po_id: Option<i32>,
+    pub unit_cost: Option<i32>,
+    pub stock_take_id: Option<i32>,
+    pub reason: String,
+    pub lot_id: Option<i32>,
 }
 
 #[derive(Serialize)]
@@ -49,6 +73,55 @@ pub struct InventoryTransactionSearchResult {
     pub total_count: i32,
 }
 
+// negative_stock_policy is block, warn or allow
+#[derive(Serialize, Deserialize)]
+pub struct InventorySettingsDto {
+    pub negative_stock_policy: String,
+}
+
+// stock written off for one reason in one category; the report's total has
+// an empty reason and category
+#[derive(Serialize)]
+pub struct ShrinkageLineDto {
+    pub reason: String,
+    pub reason_label: String,
+    pub category: String,
+    pub units: i32,
+    pub retail_value: i32,
+    pub cost_value: i32,
+    pub uncosted_units: i32,
+}
+
+#[derive(Serialize)]
+pub struct ShrinkageReportDto {
+    pub lines: Vec<ShrinkageLineDto>,
+    pub total: ShrinkageLineDto,
+}
+
+// a lot with stock left that expires within the report's window; days_left
+// is negative once it has expired
+#[derive(Serialize)]
+pub struct ExpiringLotDto {
+    pub lot_id: i32,
+    pub upc: String,
+    pub product_name: String,
+    pub category: String,
+    pub lot_number: Option<String>,
+    pub expires_on: Option<String>, // YYYY-MM-DD
+    pub days_left: i64,
+    pub on_hand: i32,
+    pub retail_value: i32,
+}
+
+#[derive(Deserialize, Validate)]
+pub struct WriteOffExpiredDto {
+    #[validate(length(min = 1, message = "choose at least one lot"))]
+    pub lot_ids: Vec<i32>,
+
+    #[validate(range(min = 1, message = "operator_mdoc must be non-zero and positive"))]
+    pub operator_mdoc: i32,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -64,6 +137,10 @@ mod tests {
             customer_mdoc: None,
             ref_order_id: None,
             created_at: None,
+            unit_cost: Some(125),
+            reason: Some("receipt".into()),
+            lot_number: None,
+            expires_on: None,
         };
         assert!(dto.validate().is_ok());
     }
@@ -78,6 +155,10 @@ mod tests {
             customer_mdoc: Some(0),
             ref_order_id: Some(0),
             created_at: Some("not-a-date".into()),
+            unit_cost: Some(-1),
+            reason: None,
+            lot_number: None,
+            expires_on: Some("2025-02-30".into()),
         };
         let err = dto.validate().unwrap_err().to_string();
         assert!(err.contains("upc"));
@@ -85,6 +166,8 @@ mod tests {
         assert!(err.contains("customer_mdoc"));
         assert!(err.contains("ref_order_id"));
         assert!(err.contains("created_at"));
+        assert!(err.contains("unit_cost"));
+        assert!(err.contains("expires_on"));
     }
 
     #[test]
@@ -98,6 +181,10 @@ mod tests {
             customer_mdoc: None,
             ref_order_id: None,
             created_at: None,
+            unit_cost: None,
+            reason: None,
+            lot_number: None,
+            expires_on: None,
         };
         let err = dto.validate().unwrap_err().to_string();
         assert!(
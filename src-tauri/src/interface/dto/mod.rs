@@ -2,6 +2,7 @@ pub mod category_dto;
 pub mod club_import_dto;
 pub mod club_transaction_dto;
 pub mod customer_dto;
+pub mod customer_limit_dto;
 pub mod customer_transaction_dto;
 pub mod customer_tx_detail_dto;
 pub mod inventory_transaction_dto;
@@ -11,5 +12,10 @@ pub mod pos_dto;
 pub mod price_adjustment_dto;
 pub mod printer_dto;
 pub mod product_dto;
+pub mod promotion_dto;
+pub mod purchasing_dto;
+pub mod return_dto;
 pub mod sale_dto;
 pub mod stats_dto;
+pub mod stock_take_dto;
+pub mod tax_dto;
@@ -1,9 +1,10 @@
 use crate::interface::dto::customer_dto::CustomerPosDto;
-use crate::interface::dto::product_dto::ProductDto;
+use crate::interface::dto::product_dto::{BarcodeAliasDto, ProductDto};
 use serde::Serialize;
 
 #[derive(Serialize)]
 pub struct PosDto {
     pub products: Vec<ProductDto>,
     pub customers: Vec<CustomerPosDto>,
+    pub barcodes: Vec<BarcodeAliasDto>,
 }
@@ -1,9 +1,13 @@
 use crate::interface::common::validators::{validate_optional_rfc3339_str, validate_upc_str};
 use serde::{Deserialize, Serialize};
+use validator::ValidationError;
 use validator_derive::Validate;
 
-#[derive(Serialize, Deserialize, Validate)]
+#[derive(Serialize, Deserialize, Validate, Default)]
 pub struct PriceAdjustmentDto {
+    #[serde(default)]
+    pub id: i32, // assigned when saved
+
     #[validate(custom(function = "validate_upc_str"))]
     pub upc: String,
 
@@ -18,6 +22,14 @@ pub struct PriceAdjustmentDto {
 
     #[validate(custom(function = "validate_optional_rfc3339_str"))]
     pub created_at: Option<String>, // RFC3339
+
+    // RFC3339; a future time schedules the change instead of applying it now
+    #[serde(default)]
+    #[validate(custom(function = "validate_optional_rfc3339_str"))]
+    pub effective_at: Option<String>,
+
+    #[serde(default)]
+    pub status: String, // pending, applied or cancelled; set by the backend
 }
 
 #[derive(Serialize)]
@@ -33,6 +45,52 @@ pub struct PriceAdjustmentSearchResult {
     pub total_count: i32,
 }
 
+// Reprices a category or a list of UPCs at once. Exactly one of `category` /
+// `upcs` and one of `percent` / `amount` must be given.
+#[derive(Serialize, Deserialize, Validate, Default)]
+pub struct BulkPriceChangeDto {
+    #[serde(default)]
+    pub category: Option<String>,
+
+    #[serde(default)]
+    #[validate(custom(function = "validate_upc_list"))]
+    pub upcs: Vec<String>,
+
+    #[serde(default)]
+    #[validate(range(min = -90.0, max = 1000.0, message = "percent must be between -90 and 1000"))]
+    pub percent: Option<f64>,
+
+    #[serde(default)]
+    pub amount: Option<i32>, // cents; negative lowers prices
+
+    #[serde(default = "default_round_to")]
+    #[validate(range(min = 1, max = 100, message = "round_to must be 1 to 100 cents"))]
+    pub round_to: i32,
+
+    #[serde(default)]
+    pub round_mode: Option<String>, // nearest (default), up or down
+
+    #[validate(range(min = 1, message = "operator_mdoc must be non-zero and positive"))]
+    pub operator_mdoc: i32,
+}
+
+const fn default_round_to() -> i32 {
+    1
+}
+
+fn validate_upc_list(upcs: &[String]) -> Result<(), ValidationError> {
+    upcs.iter().try_for_each(|u| validate_upc_str(u))
+}
+
+#[derive(Serialize)]
+pub struct BulkPriceLineDto {
+    pub upc: String,
+    pub desc: String,
+    pub category: String,
+    pub old: i32,
+    pub new: i32,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -46,6 +104,7 @@ mod tests {
             new: 600,
             operator_mdoc: 2,
             created_at: None,
+            ..Default::default()
         };
         assert!(dto.validate().is_ok());
     }
@@ -58,6 +117,8 @@ mod tests {
             new: 0,
             operator_mdoc: 0,
             created_at: Some("bad".into()),
+            effective_at: Some("next tuesday".into()),
+            ..Default::default()
         };
         let errs = dto.validate().unwrap_err();
         println!("Validation errors: {errs:?}");
@@ -68,6 +129,7 @@ mod tests {
         assert!(err_map.contains_key("new"));
         assert!(err_map.contains_key("operator_mdoc"));
         assert!(err_map.contains_key("created_at"));
+        assert!(err_map.contains_key("effective_at"));
 
         let created_at_errors = err_map.get("created_at").unwrap();
         assert!(created_at_errors.iter().any(|e| e.code == "rfc3339"));
@@ -82,6 +144,7 @@ mod tests {
             new: 600,
             operator_mdoc: 2,
             created_at: None,
+            ..Default::default()
         };
         let err = dto.validate().unwrap_err().to_string();
         assert!(
@@ -1,5 +1,8 @@
+use crate::common::error::AppError;
 use crate::domain::models::CustomerTransaction;
-use serde::Serialize;
+use crate::domain::repos::customer_tx_repo_trait::SaleDetailsTuple;
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
 
 #[derive(Serialize)]
 pub struct PrinterDto {
@@ -12,6 +15,84 @@ pub struct PrintableLineItem {
     pub desc: String,
     pub quantity: i32,
     pub price: i32,
+    pub discount: i32, // cents off the whole line; negative on reversals
+    pub promotion_id: Option<i32>, // the promotion behind the discount
+    pub promotion: Option<String>, // its name, printed under the line
+    pub tax: i32,      // sales tax charged on the line
+    pub limit_exempt: bool, // kept off the spending limit; totalled separately on the business copy
+    pub components: Vec<PrintableComponent>, // a kit's contents, listed on the business copy
+}
+
+#[derive(Serialize)]
+pub struct PrintableComponent {
+    pub desc: String,
+    pub quantity: i32, // units of it across the whole line
+}
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum ReceiptKind {
+    #[default]
+    Sale,
+    Return,
+}
+
+impl ReceiptKind {
+    #[must_use]
+    pub const fn title(self) -> &'static str {
+        match self {
+            Self::Sale => "Annex Receipt",
+            Self::Return => "Return Receipt",
+        }
+    }
+}
+
+// Which receipt copies to print.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ReceiptCopies {
+    Customer,
+    Business,
+    #[default]
+    Both,
+}
+
+impl ReceiptCopies {
+    #[must_use]
+    pub const fn customer(self) -> bool {
+        matches!(self, Self::Customer | Self::Both)
+    }
+
+    #[must_use]
+    pub const fn business(self) -> bool {
+        matches!(self, Self::Business | Self::Both)
+    }
+
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Customer => "customer",
+            Self::Business => "business",
+            Self::Both => "both",
+        }
+    }
+
+    pub fn parse(s: &str) -> Result<Self, AppError> {
+        match s {
+            "customer" => Ok(Self::Customer),
+            "business" => Ok(Self::Business),
+            "both" => Ok(Self::Both),
+            other => Err(AppError::Validation(format!(
+                "unknown receipt copies: {other}"
+            ))),
+        }
+    }
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct ReprintStamp {
+    pub operator_mdoc: i32,
+    pub operator_name: String,
+    pub reprinted_at: NaiveDateTime, // local time
 }
 
 #[derive(Serialize)]
@@ -19,4 +100,65 @@ pub struct PrintableSaleDto {
     pub transaction: CustomerTransaction,
     pub items: Vec<PrintableLineItem>,
     pub balance: i32,
+    pub balance_is_current: bool, // balance is as of now, not right after this order
+    pub kind: ReceiptKind,
+    pub reprint: Option<ReprintStamp>,
+}
+
+#[derive(Serialize)]
+pub struct PrintJobDto {
+    pub id: i32,
+    pub order_id: i32,
+    pub copies: String,
+    pub printer_name: String,
+    pub reprint: bool,
+    pub status: String,
+    pub attempts: i32,
+    pub last_error: Option<String>,
+    pub created_at: Option<String>, // RFC3339
+    pub updated_at: Option<String>, // RFC3339
+}
+
+impl PrintableSaleDto {
+    #[must_use]
+    pub fn from_details((transaction, details, balance): SaleDetailsTuple) -> Self {
+        let items = details
+            .into_iter()
+            .map(|(d, desc)| PrintableLineItem {
+                upc: d.upc,
+                desc,
+                quantity: d.quantity,
+                price: d.price,
+                discount: d.discount,
+                promotion_id: d.promotion_id,
+                promotion: d.promotion_name,
+                tax: d.tax,
+                limit_exempt: d.limit_exempt,
+                components: vec![],
+            })
+            .collect();
+        Self {
+            transaction,
+            items,
+            balance,
+            balance_is_current: true,
+            kind: ReceiptKind::Sale,
+            reprint: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn receipt_copies_parse_lowercase() {
+        let copies: ReceiptCopies = serde_json::from_str("\"business\"").unwrap();
+        assert_eq!(copies, ReceiptCopies::Business);
+        assert!(copies.business() && !copies.customer());
+        assert!(ReceiptCopies::default().customer() && ReceiptCopies::default().business());
+        assert!(serde_json::from_str::<ReceiptCopies>("\"all\"").is_err());
+        assert_eq!(ReceiptCopies::parse(copies.as_str()).unwrap(), copies);
+    }
 }
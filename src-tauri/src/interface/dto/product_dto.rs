@@ -1,5 +1,6 @@
 use crate::interface::common::validators::validate_upc_str;
 use serde::{Deserialize, Serialize};
+use validator::Validate;
 use validator_derive::Validate;
 
 #[derive(Deserialize, Validate)]
@@ -40,7 +41,110 @@ pub struct ProductDto {
     pub upc: String,
     pub desc: String,
     pub category: String,
-    pub price: i32, // integer cents
+    pub price: i32,                 // integer cents
+    pub limit_exempt: Option<bool>, // None follows the category
+    pub unit_cost: Option<i32>,     // average cost in cents, None until costed
+    pub reorder_point: Option<i32>,
+    pub reorder_quantity: Option<i32>,
+}
+
+// Sets or clears (None) a product's own limit-exempt flag.
+#[derive(Deserialize, Validate)]
+pub struct SetProductLimitExemptDto {
+    #[validate(custom(function = "validate_upc_str"))]
+    pub upc: String,
+    pub limit_exempt: Option<bool>,
+}
+
+// Sets or clears (None) the stock level at which a product shows as low.
+#[derive(Deserialize, Validate)]
+pub struct SetReorderPointDto {
+    #[validate(custom(function = "validate_upc_str"))]
+    pub upc: String,
+    #[validate(range(min = 0, message = "reorder_point cannot be negative"))]
+    pub reorder_point: Option<i32>,
+    #[validate(range(min = 1, message = "reorder_quantity must be positive"))]
+    pub reorder_quantity: Option<i32>,
+}
+
+#[derive(Serialize)]
+pub struct LowStockDto {
+    pub upc: String,
+    pub desc: String,
+    pub category: String,
+    pub available: i32,
+    pub reorder_point: i32,
+    pub reorder_quantity: Option<i32>,
+    pub vendor_name: Option<String>, // vendor of the last purchase order, if any
+}
+
+// Another barcode for a product; a case code stands for `multiplier` units.
+#[derive(Serialize, Deserialize, Validate)]
+pub struct BarcodeAliasDto {
+    #[validate(custom(function = "validate_upc_str"))]
+    pub barcode: String,
+    #[validate(custom(function = "validate_upc_str"))]
+    pub upc: String,
+    #[validate(range(min = 1, message = "multiplier must be at least 1"))]
+    pub multiplier: i32,
+}
+
+// One component of a kit; `quantity` units of it go out with each kit sold.
+#[derive(Serialize, Deserialize, Validate)]
+pub struct BundleComponentDto {
+    #[validate(custom(function = "validate_upc_str"))]
+    pub component_upc: String,
+    #[serde(default)]
+    pub desc: String, // filled in on reads; ignored on writes
+    #[validate(range(min = 1, message = "component quantity must be at least 1"))]
+    pub quantity: i32,
+}
+
+// Replaces a kit's contents; an empty list makes it a plain product again.
+#[derive(Serialize, Deserialize, Validate)]
+pub struct SetBundleComponentsDto {
+    #[validate(custom(function = "validate_upc_str"))]
+    pub upc: String,
+    #[validate(nested)]
+    pub components: Vec<BundleComponentDto>,
+}
+
+// A catalog CSV (upc, description, category, price in dollars) to apply.
+#[derive(Deserialize, Validate)]
+pub struct ImportProductsDto {
+    pub csv: String,
+    #[validate(range(min = 1, message = "operator_mdoc must be non-zero and positive"))]
+    pub operator_mdoc: i32, // recorded on the price adjustments
+}
+
+#[derive(Serialize)]
+pub struct ProductImportLineDto {
+    pub line: usize,
+    pub upc: String,
+    pub desc: String,
+    pub category: String,
+    pub price: i32,
+    pub action: String,         // create, restore, update or unchanged
+    pub old_price: Option<i32>, // set when the price changes
+}
+
+#[derive(Serialize)]
+pub struct ImportFailureDto {
+    pub line: usize,
+    pub upc: String,
+    pub message: String,
+}
+
+// What an import does (or did); nothing is applied while there are failures.
+#[derive(Serialize)]
+pub struct ProductImportPreviewDto {
+    pub lines: Vec<ProductImportLineDto>,
+    pub failures: Vec<ImportFailureDto>,
+    pub creates: usize,
+    pub restores: usize,
+    pub updates: usize,
+    pub unchanged: usize,
+    pub price_changes: usize,
 }
 
 #[derive(Serialize)]
@@ -101,4 +205,16 @@ mod tests {
             "should catch invalid upc with non-digit character"
         );
     }
+
+    #[test]
+    fn invalid_barcode_alias() {
+        let dto = BarcodeAliasDto {
+            barcode: "12-4".into(),
+            upc: "00000111".into(),
+            multiplier: 0,
+        };
+        let err = dto.validate().unwrap_err().to_string();
+        assert!(err.contains("barcode"));
+        assert!(err.contains("multiplier"));
+    }
 }
@@ -0,0 +1,54 @@
+use crate::interface::common::validators::validate_optional_rfc3339_str;
+use serde::{Deserialize, Serialize};
+use validator_derive::Validate;
+
+#[derive(Serialize, Deserialize, Validate, Clone, Default)]
+pub struct PromotionDto {
+    #[serde(default)]
+    pub id: i32, // 0 when creating
+
+    #[validate(length(min = 1, max = 60, message = "name must be 1 to 60 characters"))]
+    pub name: String, // printed on receipts
+
+    pub kind: String, // percent_off | amount_off | sale_price | buy_get
+
+    // percent off, cents off each unit, or the sale price in cents
+    #[serde(default)]
+    #[validate(range(min = 0, message = "value cannot be negative"))]
+    pub value: i32,
+
+    #[serde(default)]
+    pub buy_quantity: Option<i32>, // buy_get only
+    #[serde(default)]
+    pub get_quantity: Option<i32>,
+
+    #[serde(default)]
+    pub upc: Option<String>, // set exactly one of upc / category
+    #[serde(default)]
+    pub category: Option<String>,
+
+    #[serde(default)]
+    #[validate(custom(function = "validate_optional_rfc3339_str"))]
+    pub starts_at: Option<String>, // RFC3339; None starts now
+    #[serde(default)]
+    #[validate(custom(function = "validate_optional_rfc3339_str"))]
+    pub ends_at: Option<String>, // RFC3339, exclusive; None runs until deleted
+
+    #[serde(default)]
+    pub created_at: Option<String>, // RFC3339, set by the server
+}
+
+// A cart line to price against the running promotions.
+#[derive(Deserialize)]
+pub struct QuoteLineDto {
+    pub upc: String,
+    pub quantity: i32,
+}
+
+#[derive(Serialize)]
+pub struct LineDiscountDto {
+    pub upc: String,
+    pub promotion_id: i32,
+    pub promotion: String,
+    pub discount: i32, // cents off the whole line
+}
@@ -0,0 +1,86 @@
+use crate::interface::common::validators::validate_date_str;
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+use validator_derive::Validate;
+
+#[derive(Serialize, Deserialize, Validate, Clone)]
+pub struct VendorDto {
+    pub id: i32, // 0 when creating
+    #[validate(length(min = 1, max = 100, message = "name must be 1-100 characters"))]
+    pub name: String,
+    pub contact: Option<String>,
+    pub phone: Option<String>,
+    pub created_at: Option<String>, // RFC3339, set by the server
+}
+
+#[derive(Serialize, Deserialize, Validate, Clone)]
+pub struct PurchaseOrderItemDto {
+    #[validate(length(min = 1, message = "upc is required"))]
+    pub upc: String,
+    #[validate(range(min = 0, message = "quantity cannot be negative"))]
+    pub quantity: i32,
+    // cents per unit on the invoice; updates the product's average cost when received
+    #[serde(default)]
+    #[validate(range(min = 0, message = "unit_cost cannot be negative"))]
+    pub unit_cost: Option<i32>,
+    // lot tracking for perishables; ignored when creating a PO
+    #[serde(default)]
+    pub lot_number: Option<String>,
+    #[serde(default)]
+    #[validate(custom(function = "validate_date_str"))]
+    pub expires_on: Option<String>, // YYYY-MM-DD
+}
+
+#[derive(Serialize, Deserialize, Validate, Clone)]
+pub struct CreatePurchaseOrderDto {
+    #[validate(range(min = 1, message = "vendor_id must be non-zero and positive"))]
+    pub vendor_id: i32,
+    #[validate(range(min = 1, message = "operator_mdoc must be non-zero and positive"))]
+    pub operator_mdoc: i32,
+    #[validate(length(max = 100, message = "reference must be at most 100 characters"))]
+    pub reference: Option<String>, // vendor's order / invoice number
+    #[validate(length(min = 1, message = "at least one line is required"), nested)]
+    pub lines: Vec<PurchaseOrderItemDto>, // quantity = units ordered
+}
+
+#[derive(Serialize, Deserialize, Validate, Clone)]
+pub struct ReceivePurchaseOrderDto {
+    #[validate(range(min = 1, message = "po_id must be non-zero and positive"))]
+    pub po_id: i32,
+    #[validate(range(min = 1, message = "operator_mdoc must be non-zero and positive"))]
+    pub operator_mdoc: i32,
+    #[validate(length(min = 1, message = "at least one item is required"), nested)]
+    pub items: Vec<PurchaseOrderItemDto>, // quantity = units in this delivery
+    // when both are set the receiving report is printed straight away
+    pub printer_name: Option<String>,
+    pub sumatra_location: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct PurchaseOrderDto {
+    pub id: i32,
+    pub vendor_id: i32,
+    pub vendor_name: String,
+    pub reference: Option<String>,
+    pub status: String, // open | received | cancelled
+    pub operator_mdoc: i32,
+    pub created_at: Option<String>,  // RFC3339
+    pub received_at: Option<String>, // RFC3339
+}
+
+#[derive(Serialize)]
+pub struct ReceivingLineDto {
+    pub upc: String,
+    pub product_name: String,
+    pub expected_quantity: i32,
+    pub received_quantity: i32,
+    pub received_now: i32,
+    pub variance: i32,
+    pub flag: Option<String>, // OVER | SHORT
+}
+
+#[derive(Serialize)]
+pub struct ReceivingReportDto {
+    pub po: PurchaseOrderDto,
+    pub lines: Vec<ReceivingLineDto>,
+}
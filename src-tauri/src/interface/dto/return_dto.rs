@@ -0,0 +1,54 @@
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+use validator_derive::Validate;
+
+#[derive(Serialize, Deserialize, Validate, Clone)]
+pub struct ReturnLineDto {
+    #[validate(range(min = 1, message = "detail_id must be non-zero and positive"))]
+    pub detail_id: i32,
+    #[validate(range(min = 1, message = "quantity must be at least 1"))]
+    pub quantity: i32,
+    #[serde(default)]
+    pub restock: bool,
+}
+
+#[derive(Deserialize, Validate, Clone)]
+pub struct ReturnDto {
+    #[validate(range(min = 1, message = "order_id must be non-zero and positive"))]
+    pub order_id: i32,
+    #[validate(range(min = 1, message = "operator_mdoc must be non-zero and positive"))]
+    pub operator_mdoc: i32,
+    pub operator_name: String,
+    pub customer_name: String,
+    pub reason: Option<String>,
+    #[validate(length(min = 1, message = "at least one line is required"), nested)]
+    pub items: Vec<ReturnLineDto>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_empty_and_zero_quantity_lines() {
+        let mut dto = ReturnDto {
+            order_id: 4,
+            operator_mdoc: 1,
+            operator_name: "Op".into(),
+            customer_name: "Cust".into(),
+            reason: None,
+            items: vec![],
+        };
+        assert!(dto.validate().unwrap_err().to_string().contains("items"));
+
+        dto.items.push(ReturnLineDto {
+            detail_id: 9,
+            quantity: 0,
+            restock: true,
+        });
+        assert!(dto.validate().unwrap_err().to_string().contains("quantity"));
+
+        dto.items[0].quantity = 2;
+        assert!(dto.validate().is_ok());
+    }
+}
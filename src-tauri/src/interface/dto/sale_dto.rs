@@ -1,4 +1,5 @@
 use serde::Deserialize;
+use validator_derive::Validate;
 
 #[derive(Deserialize, Clone)]
 pub struct SaleItemDto {
@@ -8,11 +9,19 @@ pub struct SaleItemDto {
     pub price: i32,
 }
 
-#[derive(Deserialize, Clone)]
+#[derive(Deserialize, Validate, Clone)]
 pub struct SaleDto {
     pub customer_mdoc: i32,
     pub operator_mdoc: i32,
     pub operator_name: String,
     pub customer_name: String,
     pub items: Vec<SaleItemDto>,
+    // generated once per sale on the client and resent on retries
+    #[serde(default)]
+    pub idempotency_key: Option<String>,
+    #[serde(default)]
+    #[validate(length(max = 200, message = "note must be at most 200 characters"))]
+    pub note: Option<String>,
+    #[serde(default)]
+    pub reason: Option<String>, // reason code, e.g. "indigent_kit"; a regular sale when absent
 }
@@ -3,4 +3,5 @@ use serde::{Deserialize, Serialize};
 pub struct StatsDto {
     pub account_total: i64,
     pub total_customer_balances: i64,
+    pub low_stock_count: i32, // products at or below their reorder point
 }
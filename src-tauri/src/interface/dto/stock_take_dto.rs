@@ -0,0 +1,91 @@
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+use validator_derive::Validate;
+
+#[derive(Serialize, Deserialize, Validate, Clone)]
+pub struct StartStockTakeDto {
+    #[validate(range(min = 1, message = "operator_mdoc must be non-zero and positive"))]
+    pub operator_mdoc: i32,
+    #[validate(length(max = 100, message = "category must be at most 100 characters"))]
+    pub category: Option<String>, // None counts every product
+    // hide expected quantities from the count sheet while counting
+    #[serde(default)]
+    pub blind: bool,
+    #[validate(length(max = 200, message = "note must be at most 200 characters"))]
+    pub note: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Validate, Clone)]
+pub struct CountedItemDto {
+    #[validate(length(min = 1, message = "upc is required"))]
+    pub upc: String,
+    #[validate(range(min = 0, message = "quantity cannot be negative"))]
+    pub quantity: i32,
+}
+
+#[derive(Serialize, Deserialize, Validate, Clone)]
+pub struct RecordCountsDto {
+    #[validate(range(min = 1, message = "stock_take_id must be non-zero and positive"))]
+    pub stock_take_id: i32,
+    #[validate(length(min = 1, message = "at least one count is required"), nested)]
+    pub items: Vec<CountedItemDto>,
+}
+
+#[derive(Serialize, Deserialize, Validate, Clone)]
+pub struct PostStockTakeDto {
+    #[validate(range(min = 1, message = "stock_take_id must be non-zero and positive"))]
+    pub stock_take_id: i32,
+    #[validate(range(min = 1, message = "operator_mdoc must be non-zero and positive"))]
+    pub operator_mdoc: i32,
+    // when both are set the variance report is printed straight away
+    pub printer_name: Option<String>,
+    pub sumatra_location: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct StockTakeDto {
+    pub id: i32,
+    pub category: Option<String>,
+    pub blind: bool,
+    pub status: String, // open | posted | cancelled
+    pub note: Option<String>,
+    pub operator_mdoc: i32,
+    pub created_at: Option<String>, // RFC3339
+    pub posted_at: Option<String>,  // RFC3339
+}
+
+#[derive(Serialize)]
+pub struct CountSheetLineDto {
+    pub upc: String,
+    pub product_name: String,
+    pub expected_quantity: Option<i32>, // None on a blind count that is still open
+    pub counted_quantity: Option<i32>,
+    pub counted_at: Option<String>, // RFC3339
+}
+
+#[derive(Serialize)]
+pub struct CountSheetDto {
+    pub stock_take: StockTakeDto,
+    pub lines: Vec<CountSheetLineDto>,
+}
+
+#[derive(Serialize)]
+pub struct VarianceLineDto {
+    pub upc: String,
+    pub product_name: String,
+    pub price: i32,
+    pub expected_quantity: i32,
+    pub counted_quantity: Option<i32>,
+    pub variance: Option<i32>,
+    pub value_impact: Option<i32>, // variance * price, in cents
+}
+
+#[derive(Serialize)]
+pub struct VarianceReportDto {
+    pub stock_take: StockTakeDto,
+    pub lines: Vec<VarianceLineDto>,
+    pub uncounted: usize,
+    pub shrink_value: i32, // negative cents
+    pub overage_value: i32,
+    pub net_value: i32,
+}
@@ -0,0 +1,30 @@
+use crate::interface::common::validators::validate_optional_rfc3339_str;
+use serde::{Deserialize, Serialize};
+use validator_derive::Validate;
+
+#[derive(Serialize, Deserialize, Validate, Clone, Default)]
+pub struct TaxRateDto {
+    #[serde(default)]
+    pub id: i32, // 0 when creating
+
+    #[serde(default)]
+    pub upc: Option<String>, // set exactly one of upc / category
+    #[serde(default)]
+    pub category: Option<String>,
+
+    // hundredths of a percent: 825 is 8.25%; 0 exempts a product from its category's rate
+    #[validate(range(min = 0, max = 10000, message = "rate must be 0 to 10000 (100%)"))]
+    pub rate: i32,
+
+    #[serde(default)]
+    #[validate(custom(function = "validate_optional_rfc3339_str"))]
+    pub effective_at: Option<String>, // RFC3339; None takes effect now
+
+    #[serde(default)]
+    pub created_at: Option<String>, // RFC3339, set by the server
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct TaxSettingsDto {
+    pub counts_toward_limit: bool, // tax always comes out of the balance
+}
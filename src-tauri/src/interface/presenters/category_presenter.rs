@@ -10,6 +10,7 @@ impl CategoryPresenter {
             id: cat.id,
             name: cat.name,
             deleted: cat.deleted.map(|dt| dt.format("%+").to_string()),
+            limit_exempt: cat.limit_exempt,
         }
     }
 
@@ -0,0 +1,119 @@
+use crate::domain::models::{
+    CustomerTier, ItemAllowance, LimitOverride, LimitSettings, QuantityAllowance, QuantityLimit,
+    ResolvedLimit,
+};
+use crate::interface::dto::customer_limit_dto::{
+    CustomerLimitDto, CustomerTierDto, ItemAllowanceDto, LimitOverrideDto, LimitSettingsDto,
+    QuantityAllowanceDto, QuantityLimitDto,
+};
+use chrono::TimeZone;
+
+pub struct CustomerLimitPresenter;
+
+impl CustomerLimitPresenter {
+    #[must_use]
+    pub fn to_tier_dto(t: CustomerTier) -> CustomerTierDto {
+        CustomerTierDto {
+            id: t.id,
+            name: t.name,
+            limit_amount: t.limit_amount,
+        }
+    }
+
+    #[must_use]
+    pub fn to_tier_dtos(tiers: Vec<CustomerTier>) -> Vec<CustomerTierDto> {
+        tiers.into_iter().map(Self::to_tier_dto).collect()
+    }
+
+    #[must_use]
+    pub fn to_override_dto(o: LimitOverride) -> LimitOverrideDto {
+        LimitOverrideDto {
+            id: o.id,
+            customer_mdoc: o.customer_mdoc,
+            limit_amount: o.limit_amount,
+            starts_on: o.starts_on.format("%Y-%m-%d").to_string(),
+            ends_on: o.ends_on.map(|d| d.format("%Y-%m-%d").to_string()),
+            reason: o.reason,
+            operator_mdoc: o.operator_mdoc,
+            created_at: o
+                .created_at
+                .map(|dt| chrono::Utc.from_utc_datetime(&dt).to_rfc3339()),
+        }
+    }
+
+    #[must_use]
+    pub fn to_override_dtos(overrides: Vec<LimitOverride>) -> Vec<LimitOverrideDto> {
+        overrides.into_iter().map(Self::to_override_dto).collect()
+    }
+
+    #[must_use]
+    pub fn to_limit_dto(customer_mdoc: i32, r: ResolvedLimit) -> CustomerLimitDto {
+        CustomerLimitDto {
+            customer_mdoc,
+            amount: r.amount,
+            source: r.source.as_str().to_string(),
+            tier: r.tier.map(Self::to_tier_dto),
+            limit_override: r.limit_override.map(Self::to_override_dto),
+        }
+    }
+
+    #[must_use]
+    pub fn to_quantity_limit_dto(l: QuantityLimit) -> QuantityLimitDto {
+        QuantityLimitDto {
+            id: l.id,
+            upc: l.upc,
+            category: l.category,
+            max_quantity: l.max_quantity,
+            window: l.window.as_str().to_string(),
+            rolling_days: l.window.rolling_days(),
+            note: l.note,
+            created_at: l
+                .created_at
+                .map(|dt| chrono::Utc.from_utc_datetime(&dt).to_rfc3339()),
+        }
+    }
+
+    #[must_use]
+    pub fn to_quantity_limit_dtos(limits: Vec<QuantityLimit>) -> Vec<QuantityLimitDto> {
+        limits
+            .into_iter()
+            .map(Self::to_quantity_limit_dto)
+            .collect()
+    }
+
+    fn to_quantity_allowance_dto(a: QuantityAllowance) -> QuantityAllowanceDto {
+        QuantityAllowanceDto {
+            limit: Self::to_quantity_limit_dto(a.limit),
+            purchased: a.purchased,
+            remaining: a.remaining,
+            window_start: chrono::Utc.from_utc_datetime(&a.window_start).to_rfc3339(),
+            window_end: chrono::Utc.from_utc_datetime(&a.window_end).to_rfc3339(),
+        }
+    }
+
+    #[must_use]
+    pub fn to_item_allowance_dtos(items: Vec<ItemAllowance>) -> Vec<ItemAllowanceDto> {
+        items
+            .into_iter()
+            .map(|i| ItemAllowanceDto {
+                upc: i.upc,
+                remaining: i.remaining,
+                limits: i
+                    .limits
+                    .into_iter()
+                    .map(Self::to_quantity_allowance_dto)
+                    .collect(),
+            })
+            .collect()
+    }
+
+    #[must_use]
+    pub fn to_settings_dto(s: LimitSettings) -> LimitSettingsDto {
+        LimitSettingsDto {
+            period: s.period.as_str().to_string(),
+            week_start: s.week_start.num_days_from_sunday(),
+            anchor_date: s.anchor_date.format("%Y-%m-%d").to_string(),
+            time_zone: s.time_zone,
+        }
+    }
+}
@@ -1,4 +1,5 @@
 use crate::domain::models::CustomerTransaction;
+use crate::domain::repos::customer_tx_repo_trait::CustomerTxSearchRow;
 use crate::interface::dto::customer_transaction_dto::{
     CustomerTransactionDto, CustomerTransactionSearchRow,
 };
@@ -18,6 +19,7 @@ impl CustomerTransactionPresenter {
                     .date
                     .map(|dt| chrono::Utc.from_utc_datetime(&dt).to_rfc3339()),
                 note: t.note,
+                reason: t.reason.as_str().to_string(),
             })
             .collect()
     }
@@ -32,19 +34,21 @@ impl CustomerTransactionPresenter {
                 .date
                 .map(|dt| chrono::Utc.from_utc_datetime(&dt).to_rfc3339()),
             note: t.note,
+            reason: t.reason.as_str().to_string(),
         }
     }
 
     #[must_use]
-    pub fn to_search_rows(
-        rows: Vec<(CustomerTransaction, String, i32)>,
-    ) -> Vec<CustomerTransactionSearchRow> {
+    pub fn to_search_rows(rows: Vec<CustomerTxSearchRow>) -> Vec<CustomerTransactionSearchRow> {
         rows.into_iter()
-            .map(|(ct, operator_name, spent)| CustomerTransactionSearchRow {
-                transaction: Self::to_dto(ct),
-                operator_name,
-                spent,
-            })
+            .map(
+                |(ct, operator_name, spent, voided)| CustomerTransactionSearchRow {
+                    transaction: Self::to_dto(ct),
+                    operator_name,
+                    spent,
+                    voided,
+                },
+            )
             .collect()
     }
 }
@@ -14,6 +14,8 @@ impl CustomerTxDetailPresenter {
             product_name: String::new(),
             quantity: detail.quantity,
             price: detail.price,
+            discount: detail.discount,
+            tax: detail.tax,
         }
     }
 
@@ -27,6 +29,8 @@ impl CustomerTxDetailPresenter {
                 product_name,
                 quantity: d.quantity,
                 price: d.price,
+                discount: d.discount,
+                tax: d.tax,
             })
             .collect()
     }
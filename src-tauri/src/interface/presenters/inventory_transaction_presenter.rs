@@ -1,8 +1,11 @@
 use crate::domain::models::InventoryTransaction;
+use crate::domain::report_models::expiry_report::ExpiringLot;
+use crate::domain::report_models::shrinkage_report::ShrinkageLine;
 use crate::interface::dto::inventory_transaction_dto::{
-    InventoryTransactionSearchRow, ReadInventoryTransactionDto,
+    ExpiringLotDto, InventoryTransactionSearchRow, ReadInventoryTransactionDto, ShrinkageLineDto,
+    ShrinkageReportDto,
 };
-use chrono::{TimeZone, Utc};
+use chrono::{NaiveDate, TimeZone, Utc};
 
 pub struct InventoryTransactionPresenter;
 
@@ -21,6 +24,11 @@ impl InventoryTransactionPresenter {
             customer_mdoc: itx.customer_mdoc,
             ref_order_id: itx.ref_order_id,
             created_at,
+            po_id: itx.po_id,
+            unit_cost: itx.unit_cost,
+            stock_take_id: itx.stock_take_id,
+            reason: itx.reason.as_str().to_string(),
+            lot_id: itx.lot_id,
         }
     }
 
@@ -40,4 +48,50 @@ impl InventoryTransactionPresenter {
             operator_name: oname,
         }
     }
+
+    #[must_use]
+    pub fn to_shrinkage_dto(lines: Vec<ShrinkageLine>) -> ShrinkageReportDto {
+        let total = ShrinkageLine::total(&lines);
+        ShrinkageReportDto {
+            lines: lines.into_iter().map(Self::to_shrinkage_line).collect(),
+            total: ShrinkageLineDto {
+                reason: String::new(),
+                reason_label: String::new(),
+                ..Self::to_shrinkage_line(total)
+            },
+        }
+    }
+
+    fn to_shrinkage_line(l: ShrinkageLine) -> ShrinkageLineDto {
+        ShrinkageLineDto {
+            reason: l.reason.as_str().to_string(),
+            reason_label: l.reason.label().to_string(),
+            category: l.category,
+            units: l.units,
+            retail_value: l.retail_value,
+            cost_value: l.cost_value,
+            uncosted_units: l.uncosted_units,
+        }
+    }
+
+    #[must_use]
+    pub fn to_expiring_dto_list(lots: Vec<ExpiringLot>, today: NaiveDate) -> Vec<ExpiringLotDto> {
+        lots.into_iter()
+            .map(|l| ExpiringLotDto {
+                days_left: l.days_left(today),
+                retail_value: l.value(),
+                lot_id: l.lot.id,
+                upc: l.lot.upc,
+                product_name: l.product_name,
+                category: l.category,
+                lot_number: l.lot.label.lot_number,
+                expires_on: l
+                    .lot
+                    .label
+                    .expires_on
+                    .map(|d| d.format("%Y-%m-%d").to_string()),
+                on_hand: l.lot.on_hand,
+            })
+            .collect()
+    }
 }
@@ -1,6 +1,7 @@
 pub mod category_presenter;
 pub mod club_import_presenter;
 pub mod club_transaction_presenter;
+pub mod customer_limit_presenter;
 pub mod customer_presenter;
 pub mod customer_transaction_presenter;
 pub mod customer_tx_detail_presenter;
@@ -10,3 +11,7 @@ pub mod pos_presenter;
 pub mod price_adjustment_presenter;
 pub mod printer_presenter;
 pub mod product_presenter;
+pub mod promotion_presenter;
+pub mod purchasing_presenter;
+pub mod stock_take_presenter;
+pub mod tax_presenter;
@@ -1,4 +1,4 @@
-use crate::domain::models::{Customer, Product};
+use crate::application::use_cases::pos_usecases::PosInitData;
 use crate::interface::dto::pos_dto::PosDto;
 use crate::interface::presenters::customer_presenter::CustomerPresenter;
 use crate::interface::presenters::product_presenter::ProductPresenter;
@@ -6,17 +6,28 @@ use crate::interface::presenters::product_presenter::ProductPresenter;
 pub struct PosPresenter;
 
 impl PosPresenter {
-    pub fn to_dto(products: Vec<Product>, customers: Vec<(Customer, i32)>) -> PosDto {
+    pub fn to_dto(data: PosInitData) -> PosDto {
         // Reuse existing presenters to map each domain model
-        let product_dtos = products.into_iter().map(ProductPresenter::to_dto).collect();
-        let customer_dtos = customers
+        let product_dtos = data
+            .products
+            .into_iter()
+            .map(ProductPresenter::to_dto)
+            .collect();
+        let customer_dtos = data
+            .customer_accounts
             .into_iter()
             .map(|(c, b)| CustomerPresenter::to_pos_dto(c, b))
             .collect();
+        let barcode_dtos = data
+            .barcodes
+            .into_iter()
+            .map(ProductPresenter::to_barcode_dto)
+            .collect();
 
         PosDto {
             products: product_dtos,
             customers: customer_dtos,
+            barcodes: barcode_dtos,
         }
     }
 }
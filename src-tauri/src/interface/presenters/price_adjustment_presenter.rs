@@ -1,5 +1,7 @@
-use crate::domain::models::PriceAdjustment;
-use crate::interface::dto::price_adjustment_dto::{PriceAdjustmentDto, PriceAdjustmentSearchRow};
+use crate::domain::models::{BulkPriceLine, PriceAdjustment};
+use crate::interface::dto::price_adjustment_dto::{
+    BulkPriceLineDto, PriceAdjustmentDto, PriceAdjustmentSearchRow,
+};
 use chrono::{TimeZone, Utc};
 
 pub struct PriceAdjustmentPresenter;
@@ -8,6 +10,7 @@ impl PriceAdjustmentPresenter {
     #[must_use]
     pub fn to_dto(pa: PriceAdjustment) -> PriceAdjustmentDto {
         PriceAdjustmentDto {
+            id: pa.id,
             upc: pa.upc,
             old: pa.old,
             new: pa.new,
@@ -15,8 +18,23 @@ impl PriceAdjustmentPresenter {
             created_at: pa
                 .created_at
                 .map(|dt| Utc.from_utc_datetime(&dt).to_rfc3339()),
+            effective_at: pa
+                .effective_at
+                .map(|dt| Utc.from_utc_datetime(&dt).to_rfc3339()),
+            status: pa.status.as_str().to_string(),
         }
     }
+    #[must_use]
+    pub fn to_bulk_line_dto(l: BulkPriceLine) -> BulkPriceLineDto {
+        BulkPriceLineDto {
+            upc: l.upc,
+            desc: l.desc,
+            category: l.category,
+            old: l.old,
+            new: l.new,
+        }
+    }
+
     pub fn to_dto_list(pas: Vec<PriceAdjustment>) -> Vec<PriceAdjustmentDto> {
         pas.into_iter().map(Self::to_dto).collect()
     }
@@ -1,4 +1,6 @@
-use crate::interface::dto::printer_dto::PrinterDto;
+use crate::domain::models::PrintJob;
+use crate::interface::dto::printer_dto::{PrintJobDto, PrinterDto};
+use chrono::TimeZone;
 
 // Converts raw printer names into vec of DTOs
 pub struct PrinterPresenter;
@@ -8,4 +10,26 @@ impl PrinterPresenter {
     pub fn to_dto(names: Vec<String>) -> Vec<PrinterDto> {
         names.into_iter().map(|name| PrinterDto { name }).collect()
     }
+
+    #[must_use]
+    pub fn to_job_dtos(jobs: Vec<PrintJob>) -> Vec<PrintJobDto> {
+        jobs.into_iter()
+            .map(|j| PrintJobDto {
+                id: j.id,
+                order_id: j.order_id,
+                copies: j.copies,
+                printer_name: j.printer_name,
+                reprint: j.reprint_by.is_some(),
+                status: j.status.as_str().to_string(),
+                attempts: j.attempts,
+                last_error: j.last_error,
+                created_at: j
+                    .created_at
+                    .map(|dt| chrono::Utc.from_utc_datetime(&dt).to_rfc3339()),
+                updated_at: j
+                    .updated_at
+                    .map(|dt| chrono::Utc.from_utc_datetime(&dt).to_rfc3339()),
+            })
+            .collect()
+    }
 }
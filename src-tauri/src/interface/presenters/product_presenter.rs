@@ -1,5 +1,11 @@
-use crate::domain::models::Product;
-use crate::interface::dto::product_dto::{ProductDto, ProductSearchRow};
+use crate::domain::models::{
+    BarcodeAlias, BundleComponent, ImportAction, Product, ProductImportPlan,
+};
+use crate::domain::report_models::reorder_report::LowStockItem;
+use crate::interface::dto::product_dto::{
+    BarcodeAliasDto, BundleComponentDto, ImportFailureDto, LowStockDto, ProductDto,
+    ProductImportLineDto, ProductImportPreviewDto, ProductSearchRow,
+};
 
 pub struct ProductPresenter;
 
@@ -11,6 +17,10 @@ impl ProductPresenter {
             desc: p.desc,
             category: p.category,
             price: p.price,
+            limit_exempt: p.limit_exempt,
+            unit_cost: p.unit_cost,
+            reorder_point: p.reorder_point,
+            reorder_quantity: p.reorder_quantity,
         }
     }
 
@@ -25,4 +35,68 @@ impl ProductPresenter {
             available,
         }
     }
+
+    #[must_use]
+    pub fn to_low_stock_dto(i: LowStockItem) -> LowStockDto {
+        LowStockDto {
+            upc: i.upc,
+            desc: i.desc,
+            category: i.category,
+            available: i.available,
+            reorder_point: i.reorder_point,
+            reorder_quantity: i.reorder_quantity,
+            vendor_name: i.vendor_name,
+        }
+    }
+
+    #[must_use]
+    pub fn to_barcode_dto(a: BarcodeAlias) -> BarcodeAliasDto {
+        BarcodeAliasDto {
+            barcode: a.barcode,
+            upc: a.upc,
+            multiplier: a.multiplier,
+        }
+    }
+
+    #[must_use]
+    pub fn to_component_dto((c, desc): (BundleComponent, String)) -> BundleComponentDto {
+        BundleComponentDto {
+            component_upc: c.component_upc,
+            desc,
+            quantity: c.quantity,
+        }
+    }
+
+    #[must_use]
+    pub fn to_import_dto(plan: ProductImportPlan) -> ProductImportPreviewDto {
+        ProductImportPreviewDto {
+            creates: plan.count(ImportAction::Create),
+            restores: plan.count(ImportAction::Restore),
+            updates: plan.count(ImportAction::Update),
+            unchanged: plan.count(ImportAction::Unchanged),
+            price_changes: plan.price_changes(),
+            lines: plan
+                .lines
+                .into_iter()
+                .map(|l| ProductImportLineDto {
+                    line: l.line,
+                    upc: l.product.upc,
+                    desc: l.product.desc,
+                    category: l.product.category,
+                    price: l.product.price,
+                    action: l.action.as_str().to_string(),
+                    old_price: l.old_price,
+                })
+                .collect(),
+            failures: plan
+                .failures
+                .into_iter()
+                .map(|f| ImportFailureDto {
+                    line: f.line,
+                    upc: f.upc,
+                    message: f.message,
+                })
+                .collect(),
+        }
+    }
 }
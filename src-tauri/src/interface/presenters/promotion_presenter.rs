@@ -0,0 +1,42 @@
+use crate::domain::models::{LineDiscount, Promotion};
+use crate::interface::dto::promotion_dto::{LineDiscountDto, PromotionDto};
+use chrono::{TimeZone, Utc};
+
+pub struct PromotionPresenter;
+
+impl PromotionPresenter {
+    #[must_use]
+    pub fn to_dto(p: Promotion) -> PromotionDto {
+        let rfc3339 = |dt| Utc.from_utc_datetime(&dt).to_rfc3339();
+        PromotionDto {
+            id: p.id,
+            name: p.name,
+            kind: p.rule.as_str().to_string(),
+            value: p.rule.value(),
+            buy_quantity: p.rule.buy_get().map(|(buy, _)| buy),
+            get_quantity: p.rule.buy_get().map(|(_, get)| get),
+            upc: p.upc,
+            category: p.category,
+            starts_at: p.starts_at.map(rfc3339),
+            ends_at: p.ends_at.map(rfc3339),
+            created_at: p.created_at.map(rfc3339),
+        }
+    }
+
+    #[must_use]
+    pub fn to_dtos(ps: Vec<Promotion>) -> Vec<PromotionDto> {
+        ps.into_iter().map(Self::to_dto).collect()
+    }
+
+    #[must_use]
+    pub fn to_discount_dtos(ds: Vec<LineDiscount>) -> Vec<LineDiscountDto> {
+        ds.into_iter()
+            .map(|d| LineDiscountDto {
+                upc: d.upc,
+                promotion_id: d.promotion_id,
+                promotion: d.promotion,
+                discount: d.discount,
+            })
+            .collect()
+    }
+}
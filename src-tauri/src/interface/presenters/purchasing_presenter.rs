@@ -0,0 +1,74 @@
+use crate::domain::models::{PurchaseOrder, Vendor};
+use crate::domain::report_models::receiving_report::ReceivingReport;
+use crate::domain::repos::purchase_order_repo_trait::PurchaseOrderRow;
+use crate::interface::dto::purchasing_dto::{
+    PurchaseOrderDto, ReceivingLineDto, ReceivingReportDto, VendorDto,
+};
+use chrono::TimeZone;
+
+pub struct PurchasingPresenter;
+
+impl PurchasingPresenter {
+    #[must_use]
+    pub fn to_vendor_dto(v: Vendor) -> VendorDto {
+        VendorDto {
+            id: v.id,
+            name: v.name,
+            contact: v.contact,
+            phone: v.phone,
+            created_at: v
+                .created_at
+                .map(|dt| chrono::Utc.from_utc_datetime(&dt).to_rfc3339()),
+        }
+    }
+
+    #[must_use]
+    pub fn to_vendor_dtos(vendors: Vec<Vendor>) -> Vec<VendorDto> {
+        vendors.into_iter().map(Self::to_vendor_dto).collect()
+    }
+
+    #[must_use]
+    pub fn to_po_dto(po: PurchaseOrder, vendor_name: String) -> PurchaseOrderDto {
+        PurchaseOrderDto {
+            id: po.id,
+            vendor_id: po.vendor_id,
+            vendor_name,
+            reference: po.reference,
+            status: po.status.as_str().to_string(),
+            operator_mdoc: po.operator_mdoc,
+            created_at: po
+                .created_at
+                .map(|dt| chrono::Utc.from_utc_datetime(&dt).to_rfc3339()),
+            received_at: po
+                .received_at
+                .map(|dt| chrono::Utc.from_utc_datetime(&dt).to_rfc3339()),
+        }
+    }
+
+    #[must_use]
+    pub fn to_po_dtos(rows: Vec<PurchaseOrderRow>) -> Vec<PurchaseOrderDto> {
+        rows.into_iter()
+            .map(|(po, vendor_name)| Self::to_po_dto(po, vendor_name))
+            .collect()
+    }
+
+    #[must_use]
+    pub fn to_report_dto(report: ReceivingReport) -> ReceivingReportDto {
+        ReceivingReportDto {
+            lines: report
+                .lines
+                .into_iter()
+                .map(|l| ReceivingLineDto {
+                    flag: l.flag().map(str::to_string),
+                    variance: l.line.variance(),
+                    upc: l.line.upc,
+                    product_name: l.product_name,
+                    expected_quantity: l.line.expected_quantity,
+                    received_quantity: l.line.received_quantity,
+                    received_now: l.received_now,
+                })
+                .collect(),
+            po: Self::to_po_dto(report.po, report.vendor_name),
+        }
+    }
+}
@@ -0,0 +1,83 @@
+use crate::domain::models::{StockTake, StockTakeStatus};
+use crate::domain::report_models::stock_take_report::StockTakeReport;
+use crate::domain::repos::stock_take_repo_trait::StockTakeLineRow;
+use crate::interface::dto::stock_take_dto::{
+    CountSheetDto, CountSheetLineDto, StockTakeDto, VarianceLineDto, VarianceReportDto,
+};
+use chrono::TimeZone;
+
+pub struct StockTakePresenter;
+
+impl StockTakePresenter {
+    #[must_use]
+    pub fn to_dto(st: StockTake) -> StockTakeDto {
+        StockTakeDto {
+            id: st.id,
+            category: st.category,
+            blind: st.blind,
+            status: st.status.as_str().to_string(),
+            note: st.note,
+            operator_mdoc: st.operator_mdoc,
+            created_at: st
+                .created_at
+                .map(|dt| chrono::Utc.from_utc_datetime(&dt).to_rfc3339()),
+            posted_at: st
+                .posted_at
+                .map(|dt| chrono::Utc.from_utc_datetime(&dt).to_rfc3339()),
+        }
+    }
+
+    #[must_use]
+    pub fn to_dtos(sessions: Vec<StockTake>) -> Vec<StockTakeDto> {
+        sessions.into_iter().map(Self::to_dto).collect()
+    }
+
+    // Expected quantities stay hidden on a blind count until it is closed.
+    #[must_use]
+    pub fn to_count_sheet_dto(st: StockTake, lines: Vec<StockTakeLineRow>) -> CountSheetDto {
+        let hide_expected = st.blind && st.status == StockTakeStatus::Open;
+        CountSheetDto {
+            lines: lines
+                .into_iter()
+                .map(|(line, product_name, _)| CountSheetLineDto {
+                    upc: line.upc,
+                    product_name,
+                    expected_quantity: (!hide_expected).then_some(line.expected_quantity),
+                    counted_quantity: line.counted_quantity,
+                    counted_at: line
+                        .counted_at
+                        .map(|dt| chrono::Utc.from_utc_datetime(&dt).to_rfc3339()),
+                })
+                .collect(),
+            stock_take: Self::to_dto(st),
+        }
+    }
+
+    #[must_use]
+    pub fn to_variance_dto(report: StockTakeReport) -> VarianceReportDto {
+        let uncounted = report.uncounted();
+        let shrink_value = report.shrink_value();
+        let overage_value = report.overage_value();
+        let net_value = report.net_value();
+        VarianceReportDto {
+            lines: report
+                .lines
+                .into_iter()
+                .map(|l| VarianceLineDto {
+                    variance: l.line.variance(),
+                    value_impact: l.value_impact(),
+                    upc: l.line.upc,
+                    product_name: l.product_name,
+                    price: l.price,
+                    expected_quantity: l.line.expected_quantity,
+                    counted_quantity: l.line.counted_quantity,
+                })
+                .collect(),
+            stock_take: Self::to_dto(report.stock_take),
+            uncounted,
+            shrink_value,
+            overage_value,
+            net_value,
+        }
+    }
+}
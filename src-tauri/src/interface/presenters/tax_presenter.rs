@@ -0,0 +1,32 @@
+use crate::domain::models::{TaxRate, TaxSettings};
+use crate::interface::dto::tax_dto::{TaxRateDto, TaxSettingsDto};
+use chrono::{TimeZone, Utc};
+
+pub struct TaxPresenter;
+
+impl TaxPresenter {
+    #[must_use]
+    pub fn to_dto(r: TaxRate) -> TaxRateDto {
+        let rfc3339 = |dt| Utc.from_utc_datetime(&dt).to_rfc3339();
+        TaxRateDto {
+            id: r.id,
+            upc: r.upc,
+            category: r.category,
+            rate: r.rate,
+            effective_at: r.effective_at.map(rfc3339),
+            created_at: r.created_at.map(rfc3339),
+        }
+    }
+
+    #[must_use]
+    pub fn to_dtos(rs: Vec<TaxRate>) -> Vec<TaxRateDto> {
+        rs.into_iter().map(Self::to_dto).collect()
+    }
+
+    #[must_use]
+    pub const fn to_settings_dto(s: TaxSettings) -> TaxSettingsDto {
+        TaxSettingsDto {
+            counts_toward_limit: s.counts_toward_limit,
+        }
+    }
+}
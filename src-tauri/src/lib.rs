@@ -6,23 +6,31 @@ pub mod interface;
 pub mod test_support;
 
 use crate::application::use_cases::legacy_migration_usecases::LegacyMigrationDeps;
+use crate::application::use_cases::print_queue_usecases::PrintQueueUseCases;
 use crate::application::use_cases::printer_usecases::PrinterUseCases;
 use crate::domain::repos::{
     CategoryRepoTrait, ClubImportRepoTrait, ClubTransactionRepoTrait, CredentialRepoTrait,
-    CustomerRepoTrait, CustomerTransactionRepoTrait, CustomerTxDetailRepoTrait,
-    InventoryTransactionRepoTrait, OperatorRepoTrait, PriceAdjustmentRepoTrait, ProductRepoTrait,
-    WeeklyLimitRepoTrait,
+    CustomerLimitRepoTrait, CustomerRepoTrait, CustomerTransactionRepoTrait,
+    CustomerTxDetailRepoTrait, InventoryTransactionRepoTrait, OperatorRepoTrait,
+    OrderReturnRepoTrait, PriceAdjustmentRepoTrait, PrintJobRepoTrait, ProductRepoTrait,
+    PromotionRepoTrait, PurchaseOrderRepoTrait, QuantityLimitRepoTrait, StockTakeRepoTrait,
+    TaxRateRepoTrait, VendorRepoTrait, WeeklyLimitRepoTrait,
 };
 
 use crate::interface::controllers::auth_controller::AuthController;
 use crate::interface::controllers::club_controller::ClubController;
+use crate::interface::controllers::customer_limit_controller::CustomerLimitController;
 use crate::interface::controllers::legacy_migration_controller::LegacyMigrationController;
 use crate::interface::controllers::operator_controller::OperatorController;
 use crate::interface::controllers::parse_pdf_controller::PdfParseController;
 use crate::interface::controllers::pos_controller::PosController;
 use crate::interface::controllers::printer_controller::PrinterController;
 use crate::interface::controllers::product_controller::ProductController;
+use crate::interface::controllers::promotion_controller::PromotionController;
+use crate::interface::controllers::purchasing_controller::PurchasingController;
 use crate::interface::controllers::stats_controller::StatsController;
+use crate::interface::controllers::stock_take_controller::StockTakeController;
+use crate::interface::controllers::tax_controller::TaxController;
 use crate::interface::controllers::transaction_controller::{
     TransactionController, TransactionControllerDeps,
 };
@@ -32,9 +40,11 @@ use infrastructure::db::create_connection;
 use infrastructure::pdf_parser::LopdfParser;
 use infrastructure::repos::{
     SqliteCategoryRepo, SqliteClubImportRepo, SqliteClubTransactionRepo, SqliteCredentialRepo,
-    SqliteCustomerRepo, SqliteCustomerTransactionRepo, SqliteCustomerTxDetailRepo,
-    SqliteInventoryTransactionRepo, SqliteOperatorRepo, SqlitePriceAdjustmentRepo,
-    SqliteProductRepo, SqliteWeeklyLimitRepo,
+    SqliteCustomerLimitRepo, SqliteCustomerRepo, SqliteCustomerTransactionRepo,
+    SqliteCustomerTxDetailRepo, SqliteInventoryTransactionRepo, SqliteOperatorRepo,
+    SqliteOrderReturnRepo, SqlitePriceAdjustmentRepo, SqlitePrintJobRepo, SqliteProductRepo,
+    SqlitePromotionRepo, SqlitePurchaseOrderRepo, SqliteQuantityLimitRepo, SqliteStockTakeRepo,
+    SqliteTaxRateRepo, SqliteVendorRepo, SqliteWeeklyLimitRepo,
 };
 use std::sync::{Arc, RwLock};
 use tauri::{Builder, WindowEvent};
@@ -78,7 +88,43 @@ pub fn run() {
         Arc::new(SqliteCustomerTxDetailRepo::new(Arc::clone(&conn)));
     let limit_repo: Arc<dyn WeeklyLimitRepoTrait> =
         Arc::new(SqliteWeeklyLimitRepo::new(Arc::clone(&conn)));
+    let customer_limit_repo: Arc<dyn CustomerLimitRepoTrait> =
+        Arc::new(SqliteCustomerLimitRepo::new(Arc::clone(&conn)));
+    let quantity_limit_repo: Arc<dyn QuantityLimitRepoTrait> =
+        Arc::new(SqliteQuantityLimitRepo::new(Arc::clone(&conn)));
+    let return_repo: Arc<dyn OrderReturnRepoTrait> =
+        Arc::new(SqliteOrderReturnRepo::new(Arc::clone(&conn)));
+    let print_job_repo: Arc<dyn PrintJobRepoTrait> =
+        Arc::new(SqlitePrintJobRepo::new(Arc::clone(&conn)));
+    let vendor_repo: Arc<dyn VendorRepoTrait> = Arc::new(SqliteVendorRepo::new(Arc::clone(&conn)));
+    let po_repo: Arc<dyn PurchaseOrderRepoTrait> =
+        Arc::new(SqlitePurchaseOrderRepo::new(Arc::clone(&conn)));
+    let stock_take_repo: Arc<dyn StockTakeRepoTrait> =
+        Arc::new(SqliteStockTakeRepo::new(Arc::clone(&conn)));
+    let promo_repo: Arc<dyn PromotionRepoTrait> =
+        Arc::new(SqlitePromotionRepo::new(Arc::clone(&conn)));
+    let tax_repo: Arc<dyn TaxRateRepoTrait> = Arc::new(SqliteTaxRateRepo::new(Arc::clone(&conn)));
     let runner: Arc<dyn CommandRunner> = Arc::new(WindowsCommandRunner);
+    // receipts are printed by a background worker so a printer fault never fails a sale
+    let print_queue = Arc::new(PrintQueueUseCases::new(
+        Arc::clone(&print_job_repo),
+        Arc::clone(&cust_tx_repo),
+        Arc::clone(&return_repo),
+        Arc::new(PrinterUseCases::new(
+            Arc::clone(&runner),
+            Arc::clone(&customer_repo),
+            Arc::clone(&product_repo),
+            Arc::clone(&cust_tx_repo),
+            Arc::clone(&cust_tx_detail_repo),
+            Arc::clone(&club_import_repo),
+            Arc::clone(&club_tx_repo),
+            Arc::clone(&op_repo),
+            Arc::clone(&promo_repo),
+            Arc::clone(&inv_repo),
+            Arc::clone(&return_repo),
+        )),
+    ));
+    PrintQueueUseCases::spawn_worker(Arc::clone(&print_queue));
     let auth_ctrl = Arc::new(AuthController::new(auth_state.clone(), cred_repo.clone()));
     let op_ctrl = Arc::new(OperatorController::new(Arc::clone(&op_repo)));
     let product_ctrl = Arc::new(ProductController::new(
@@ -87,22 +133,58 @@ pub fn run() {
         Arc::clone(&category_repo),
         Arc::clone(&conn),
     ));
+    // price changes that came due while the app was closed
+    if let Err(e) = product_ctrl.apply_due_price_changes() {
+        log::error!("applying scheduled price changes failed: {e}");
+    }
     let stats_ctrl = Arc::new(StatsController::new(
         Arc::clone(&club_tx_repo),
         Arc::clone(&customer_repo),
+        Arc::clone(&product_repo),
     ));
     let tx_ctrl = Arc::new(TransactionController::new(TransactionControllerDeps {
         inv_repo: Arc::clone(&inv_repo),
         cust_tx_repo: Arc::clone(&cust_tx_repo),
         cust_tx_detail_repo: Arc::clone(&cust_tx_detail_repo),
         limit_repo: Arc::clone(&limit_repo),
-        runner: Arc::clone(&runner),
-        customer_repo: Arc::clone(&customer_repo),
+        customer_limit_repo: Arc::clone(&customer_limit_repo),
+        quantity_limit_repo: Arc::clone(&quantity_limit_repo),
         prod_repo: Arc::clone(&product_repo),
-        club_tx_repo: Arc::clone(&club_tx_repo),
-        club_import_repo: Arc::clone(&club_import_repo),
+        return_repo: Arc::clone(&return_repo),
+        price_repo: Arc::clone(&price_repo),
+        promo_repo: Arc::clone(&promo_repo),
+        tax_repo: Arc::clone(&tax_repo),
+        category_repo: Arc::clone(&category_repo),
+        print_queue: Arc::clone(&print_queue),
         conn: Arc::clone(&conn),
     }));
+    let limit_ctrl = Arc::new(CustomerLimitController::new(
+        Arc::clone(&limit_repo),
+        Arc::clone(&customer_limit_repo),
+        Arc::clone(&quantity_limit_repo),
+    ));
+    let promo_ctrl = Arc::new(PromotionController::new(
+        Arc::clone(&promo_repo),
+        Arc::clone(&product_repo),
+    ));
+    let tax_ctrl = Arc::new(TaxController::new(
+        Arc::clone(&tax_repo),
+        Arc::clone(&product_repo),
+        Arc::clone(&category_repo),
+    ));
+    let purchasing_ctrl = Arc::new(PurchasingController::new(
+        Arc::clone(&vendor_repo),
+        Arc::clone(&po_repo),
+        Arc::clone(&inv_repo),
+        Arc::clone(&product_repo),
+        Arc::clone(&conn),
+    ));
+    let stock_take_ctrl = Arc::new(StockTakeController::new(
+        Arc::clone(&stock_take_repo),
+        Arc::clone(&inv_repo),
+        Arc::clone(&product_repo),
+        Arc::clone(&conn),
+    ));
     let club_ctrl = Arc::new(ClubController::new(
         Arc::clone(&customer_repo),
         Arc::clone(&club_tx_repo),
@@ -110,7 +192,9 @@ pub fn run() {
     ));
     let pos_ctrl = Arc::new(PosController::new(
         Arc::clone(&product_repo),
+        Arc::clone(&price_repo),
         Arc::clone(&customer_repo),
+        Arc::clone(&conn),
     ));
     let legacy_ctrl = Arc::new(LegacyMigrationController::new(LegacyMigrationDeps {
         op_repo: Arc::clone(&op_repo),
@@ -139,8 +223,12 @@ pub fn run() {
         Arc::clone(&cust_tx_detail_repo),
         Arc::clone(&club_import_repo),
         Arc::clone(&club_tx_repo),
+        Arc::clone(&op_repo),
+        Arc::clone(&promo_repo),
+        Arc::clone(&inv_repo),
+        Arc::clone(&return_repo),
     );
-    let printer_ctrl = Arc::new(PrinterController::new(printer_uc));
+    let printer_ctrl = Arc::new(PrinterController::new(printer_uc, print_queue));
 
     // filter spammy tao / winit event loop spam in console
     std::env::set_var(
@@ -155,6 +243,11 @@ pub fn run() {
         .manage(op_ctrl)
         .manage(product_ctrl)
         .manage(tx_ctrl)
+        .manage(limit_ctrl)
+        .manage(promo_ctrl)
+        .manage(tax_ctrl)
+        .manage(purchasing_ctrl)
+        .manage(stock_take_ctrl)
         .manage(club_ctrl)
         .manage(pos_ctrl)
         .manage(printer_ctrl)
@@ -188,21 +281,89 @@ pub fn run() {
             interface::commands::product::create_product,
             interface::commands::product::list_price_adjust,
             interface::commands::product::price_adjustment,
+            interface::commands::product::list_pending_price_changes,
+            interface::commands::product::cancel_price_change,
+            interface::commands::product::preview_bulk_price_change,
+            interface::commands::product::bulk_price_change,
             interface::commands::product::delete_product,
             interface::commands::product::update_product,
             interface::commands::product::list_categories,
             interface::commands::product::delete_category,
             interface::commands::product::create_category,
+            interface::commands::product::set_category_limit_exempt,
+            interface::commands::product::set_product_limit_exempt,
+            interface::commands::product::set_product_reorder_point,
+            interface::commands::product::list_low_stock,
+            interface::commands::product::list_product_barcodes,
+            interface::commands::product::add_product_barcode,
+            interface::commands::product::remove_product_barcode,
+            interface::commands::product::get_bundle_components,
+            interface::commands::product::set_bundle_components,
+            interface::commands::product::preview_product_import,
+            interface::commands::product::import_products,
+            interface::commands::product::export_products_csv,
             interface::commands::product::search_products,
             interface::commands::product::search_price_adjustments,
             interface::commands::transaction::inventory_adjustment,
             interface::commands::transaction::sale_transaction,
+            interface::commands::transaction::void_order,
+            interface::commands::transaction::return_items,
+            interface::commands::transaction::reprint_receipt,
             interface::commands::transaction::list_order_details,
             interface::commands::transaction::search_customer_transactions,
             interface::commands::transaction::search_inventory_transactions,
+            interface::commands::transaction::get_inventory_settings,
+            interface::commands::transaction::set_inventory_settings,
+            interface::commands::transaction::get_shrinkage_report,
+            interface::commands::transaction::print_shrinkage_report,
+            interface::commands::transaction::get_expiring_lots,
+            interface::commands::transaction::print_expiring_lots,
+            interface::commands::transaction::write_off_expired_lots,
             interface::commands::transaction::set_weekly_limit,
             interface::commands::transaction::get_weekly_limit,
             interface::commands::transaction::get_weekly_spent,
+            interface::commands::transaction::get_customer_limit,
+            interface::commands::transaction::get_item_allowances,
+            interface::commands::customer_limit::list_customer_tiers,
+            interface::commands::customer_limit::create_customer_tier,
+            interface::commands::customer_limit::update_customer_tier,
+            interface::commands::customer_limit::delete_customer_tier,
+            interface::commands::customer_limit::set_customer_tier,
+            interface::commands::customer_limit::list_limit_overrides,
+            interface::commands::customer_limit::create_limit_override,
+            interface::commands::customer_limit::delete_limit_override,
+            interface::commands::customer_limit::get_limit_settings,
+            interface::commands::customer_limit::set_limit_settings,
+            interface::commands::customer_limit::list_quantity_limits,
+            interface::commands::customer_limit::create_quantity_limit,
+            interface::commands::customer_limit::delete_quantity_limit,
+            interface::commands::promotion::list_promotions,
+            interface::commands::promotion::create_promotion,
+            interface::commands::promotion::delete_promotion,
+            interface::commands::promotion::quote_promotions,
+            interface::commands::tax::list_tax_rates,
+            interface::commands::tax::create_tax_rate,
+            interface::commands::tax::delete_tax_rate,
+            interface::commands::tax::get_tax_settings,
+            interface::commands::tax::set_tax_settings,
+            interface::commands::purchasing::list_vendors,
+            interface::commands::purchasing::create_vendor,
+            interface::commands::purchasing::update_vendor,
+            interface::commands::purchasing::delete_vendor,
+            interface::commands::purchasing::create_purchase_order,
+            interface::commands::purchasing::list_purchase_orders,
+            interface::commands::purchasing::cancel_purchase_order,
+            interface::commands::purchasing::get_receiving_report,
+            interface::commands::purchasing::receive_purchase_order,
+            interface::commands::purchasing::print_receiving_report,
+            interface::commands::stock_take::start_stock_take,
+            interface::commands::stock_take::list_stock_takes,
+            interface::commands::stock_take::get_count_sheet,
+            interface::commands::stock_take::record_stock_counts,
+            interface::commands::stock_take::cancel_stock_take,
+            interface::commands::stock_take::get_stock_take_variance,
+            interface::commands::stock_take::post_stock_take,
+            interface::commands::stock_take::print_stock_take_variance,
             interface::commands::stats::get_stats,
             interface::commands::club::search_customers,
             interface::commands::club::search_club_transactions,
@@ -216,10 +377,15 @@ pub fn run() {
             interface::commands::printer::print_prod_inv_rpt,
             interface::commands::printer::print_cust_bal_rpt,
             interface::commands::printer::print_product_catalog,
+            interface::commands::printer::print_reorder_report,
             interface::commands::printer::print_sales_detail_report,
             interface::commands::printer::print_product_sales_by_category,
+            interface::commands::printer::print_margin_report,
             interface::commands::printer::print_daily_sales_report,
             interface::commands::printer::print_club_import,
+            interface::commands::printer::list_print_jobs,
+            interface::commands::printer::retry_print_job,
+            interface::commands::printer::cancel_print_job,
         ])
         .on_window_event(|_window, event| {
             if let WindowEvent::CloseRequested { .. } = event {
@@ -54,6 +54,7 @@ impl CategoryRepoTrait for MockCategoryRepo {
             id: (guard.len() as i32) + 1,
             name: c,
             deleted: None,
+            limit_exempt: false,
         };
         guard.push(new_category);
         Ok(())
@@ -84,4 +85,14 @@ impl CategoryRepoTrait for MockCategoryRepo {
         }
         Ok(())
     }
+
+    fn set_limit_exempt(&self, id: i32, limit_exempt: bool) -> Result<(), AppError> {
+        let mut guard = self.store.lock().unwrap();
+        let cat = guard
+            .iter_mut()
+            .find(|c| c.id == id)
+            .ok_or_else(|| AppError::NotFound(format!("category {id} not found")))?;
+        cat.limit_exempt = limit_exempt;
+        Ok(())
+    }
 }
@@ -0,0 +1,132 @@
+use crate::common::error::AppError;
+use crate::domain::models::{CustomerTier, LimitOverride};
+use crate::domain::repos::CustomerLimitRepoTrait;
+use chrono::NaiveDate;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+pub struct MockCustomerLimitRepo {
+    tiers: Mutex<Vec<CustomerTier>>,
+    members: Mutex<HashMap<i32, i32>>,
+    overrides: Mutex<Vec<LimitOverride>>,
+}
+
+impl MockCustomerLimitRepo {
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            tiers: Mutex::new(vec![]),
+            members: Mutex::new(HashMap::new()),
+            overrides: Mutex::new(vec![]),
+        }
+    }
+}
+
+impl Default for MockCustomerLimitRepo {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CustomerLimitRepoTrait for MockCustomerLimitRepo {
+    fn list_tiers(&self) -> Result<Vec<CustomerTier>, AppError> {
+        Ok(self.tiers.lock().unwrap().clone())
+    }
+
+    fn get_tier(&self, id: i32) -> Result<Option<CustomerTier>, AppError> {
+        Ok(self
+            .tiers
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|t| t.id == id)
+            .cloned())
+    }
+
+    fn create_tier(&self, tier: &CustomerTier) -> Result<i32, AppError> {
+        let mut tiers = self.tiers.lock().unwrap();
+        let id = tiers.iter().map(|t| t.id).max().unwrap_or(0) + 1;
+        tiers.push(CustomerTier { id, ..tier.clone() });
+        Ok(id)
+    }
+
+    fn update_tier(&self, tier: &CustomerTier) -> Result<(), AppError> {
+        let mut tiers = self.tiers.lock().unwrap();
+        let existing = tiers
+            .iter_mut()
+            .find(|t| t.id == tier.id)
+            .ok_or_else(|| AppError::NotFound(format!("tier {} not found", tier.id)))?;
+        *existing = tier.clone();
+        Ok(())
+    }
+
+    fn delete_tier(&self, id: i32) -> Result<(), AppError> {
+        self.members.lock().unwrap().retain(|_, t| *t != id);
+        self.tiers.lock().unwrap().retain(|t| t.id != id);
+        Ok(())
+    }
+
+    fn get_customer_tier(&self, customer_mdoc: i32) -> Result<Option<CustomerTier>, AppError> {
+        let tier_id = self.members.lock().unwrap().get(&customer_mdoc).copied();
+        match tier_id {
+            Some(id) => self.get_tier(id),
+            None => Ok(None),
+        }
+    }
+
+    fn set_customer_tier(&self, customer_mdoc: i32, tier_id: Option<i32>) -> Result<(), AppError> {
+        let mut members = self.members.lock().unwrap();
+        match tier_id {
+            Some(id) => members.insert(customer_mdoc, id),
+            None => members.remove(&customer_mdoc),
+        };
+        Ok(())
+    }
+
+    fn create_override(&self, o: &LimitOverride) -> Result<i32, AppError> {
+        let mut overrides = self.overrides.lock().unwrap();
+        let id = overrides.iter().map(|o| o.id).max().unwrap_or(0) + 1;
+        overrides.push(LimitOverride { id, ..o.clone() });
+        Ok(id)
+    }
+
+    fn list_overrides(&self, customer_mdoc: i32) -> Result<Vec<LimitOverride>, AppError> {
+        Ok(self
+            .overrides
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|o| o.customer_mdoc == customer_mdoc)
+            .cloned()
+            .collect())
+    }
+
+    fn delete_override(&self, id: i32) -> Result<(), AppError> {
+        let mut overrides = self.overrides.lock().unwrap();
+        let before = overrides.len();
+        overrides.retain(|o| o.id != id);
+        if overrides.len() == before {
+            return Err(AppError::NotFound(format!("limit override {id} not found")));
+        }
+        Ok(())
+    }
+
+    fn active_override(
+        &self,
+        customer_mdoc: i32,
+        on: NaiveDate,
+    ) -> Result<Option<LimitOverride>, AppError> {
+        Ok(self
+            .overrides
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|o| {
+                o.customer_mdoc == customer_mdoc
+                    && o.starts_on <= on
+                    && o.ends_on.is_none_or(|end| end >= on)
+            })
+            .max_by_key(|o| o.id)
+            .cloned())
+    }
+}
@@ -3,9 +3,11 @@ use crate::domain::models::customer_tx_detail::CustomerTxDetail;
 use crate::domain::models::product::Product;
 use crate::domain::models::CustomerTransaction;
 use crate::domain::report_models::daily_sales::DailySales;
+use crate::domain::report_models::margin_report::CategoryMargin;
 use crate::domain::report_models::product_sales::ProductSalesByCategory;
 
 use crate::domain::report_models::product_sales::SalesTotals;
+use crate::domain::report_models::tax_summary::TaxSummaryRow;
 use crate::domain::repos::customer_tx_detail_repo_trait::CustomerTxDetailRepoTrait;
 use chrono::NaiveDateTime;
 use std::collections::HashMap;
@@ -67,6 +69,14 @@ impl CustomerTxDetailRepoTrait for MockCustomerTxDetailRepo {
             .collect())
     }
 
+    fn list_by_order_with_tx(
+        &self,
+        order_id: i32,
+        _tx: &rusqlite::Transaction<'_>,
+    ) -> Result<Vec<(CustomerTxDetail, String)>, AppError> {
+        self.list_by_order(order_id)
+    }
+
     fn create_with_tx(
         &self,
         d: &CustomerTxDetail,
@@ -93,11 +103,15 @@ impl CustomerTxDetailRepoTrait for MockCustomerTxDetailRepo {
                 name: prod.desc.clone(),
                 quantity_sold: 0,
                 price: tx.price,
+                gross_sales: 0,
+                discounts: 0,
                 total_sales: 0,
                 is_summary: false,
             });
             entry.quantity_sold += tx.quantity;
-            entry.total_sales += tx.quantity * tx.price;
+            entry.gross_sales += tx.quantity * tx.price;
+            entry.discounts += tx.discount;
+            entry.total_sales += tx.net();
         }
         Ok(map.into_values().collect())
     }
@@ -113,11 +127,15 @@ impl CustomerTxDetailRepoTrait for MockCustomerTxDetailRepo {
             .lock()
             .unwrap()
             .iter()
-            .map(|tx| tx.quantity * tx.price)
+            .map(CustomerTxDetail::net)
             .sum();
+        let total_discount = self.data.lock().unwrap().iter().map(|tx| tx.discount).sum();
+        let total_tax = self.data.lock().unwrap().iter().map(|tx| tx.tax).sum();
         Ok(SalesTotals {
             total_quantity,
             total_value,
+            total_discount,
+            total_tax,
         })
     }
 
@@ -139,13 +157,13 @@ impl CustomerTxDetailRepoTrait for MockCustomerTxDetailRepo {
         }
         drop(txs);
 
-        // 2) Sum up `quantity * price` per day
+        // 2) Sum up what each line charged per day
         let mut daily_totals: HashMap<_, i32> = HashMap::new();
         for detail in self.data.lock().unwrap().iter() {
             if let Some(&tx_date) = date_map.get(&detail.order_id) {
                 // group by date (YYYY-MM-DD)
                 let day = tx_date.date();
-                let amount = detail.quantity * detail.price;
+                let amount = detail.net();
                 *daily_totals.entry(day).or_insert(0) += amount;
             }
         }
@@ -158,6 +176,58 @@ impl CustomerTxDetailRepoTrait for MockCustomerTxDetailRepo {
         result.sort_by_key(|r| r.day);
         Ok(result)
     }
+
+    fn margin_by_category(
+        &self,
+        _start: NaiveDateTime,
+        _end: NaiveDateTime,
+    ) -> Result<Vec<CategoryMargin>, AppError> {
+        let products = self.products.lock().unwrap();
+        let mut map: HashMap<String, CategoryMargin> = HashMap::new();
+        for d in self.data.lock().unwrap().iter() {
+            let Some(prod) = products.get(&d.upc) else {
+                continue;
+            };
+            let entry = map
+                .entry(prod.category.clone())
+                .or_insert_with(|| CategoryMargin {
+                    category: prod.category.clone(),
+                    ..CategoryMargin::default()
+                });
+            entry.quantity_sold += d.quantity;
+            entry.revenue += d.net();
+            if let Some(cost) = d.cost {
+                entry.costed_revenue += d.net();
+                entry.cogs += d.quantity * cost;
+            }
+        }
+        let mut rows: Vec<CategoryMargin> = map.into_values().collect();
+        rows.sort_by(|a, b| a.category.cmp(&b.category));
+        Ok(rows)
+    }
+
+    fn tax_summary(
+        &self,
+        _start: NaiveDateTime,
+        _end: NaiveDateTime,
+    ) -> Result<Vec<TaxSummaryRow>, AppError> {
+        let mut map: HashMap<i32, TaxSummaryRow> = HashMap::new();
+        for d in self.data.lock().unwrap().iter() {
+            let Some(rate) = d.tax_rate else {
+                continue;
+            };
+            let entry = map.entry(rate).or_insert(TaxSummaryRow {
+                rate,
+                taxable_sales: 0,
+                tax: 0,
+            });
+            entry.taxable_sales += d.net();
+            entry.tax += d.tax;
+        }
+        let mut rows: Vec<TaxSummaryRow> = map.into_values().collect();
+        rows.sort_by_key(|r| r.rate);
+        Ok(rows)
+    }
 }
 
 pub trait AsAny {
@@ -1,24 +1,35 @@
 use crate::common::error::AppError;
 use crate::domain::models::customer_tx_detail::CustomerTxDetail;
-use crate::domain::models::CustomerTransaction;
+use crate::domain::models::{CustomerTransaction, OrderReason, OrderVoid};
 use crate::domain::report_models::sales_details::SalesReportDetailRow;
 use crate::domain::report_models::sales_details::SalesReportDetails;
+use crate::domain::repos::customer_tx_repo_trait::CustomerTxSearchRow;
 use crate::domain::repos::CustomerTransactionRepoTrait;
 use chrono::{Duration, NaiveDateTime};
+use std::collections::HashMap;
 use std::sync::Mutex;
 
 /// Shared mock implementation for `CustomerTransactionRepoTrait`
 pub struct MockCustomerTransactionRepo {
     store: Mutex<Vec<CustomerTransaction>>,
+    balances: Mutex<HashMap<i32, i32>>,
+    voids: Mutex<Vec<OrderVoid>>,
 }
 
 impl MockCustomerTransactionRepo {
     #[must_use]
-    pub const fn new() -> Self {
+    pub fn new() -> Self {
         Self {
             store: Mutex::new(vec![]),
+            balances: Mutex::new(HashMap::new()),
+            voids: Mutex::new(vec![]),
         }
     }
+
+    // Test helper to seed the balance returned for a customer
+    pub fn set_balance(&self, customer_mdoc: i32, balance: i32) {
+        self.balances.lock().unwrap().insert(customer_mdoc, balance);
+    }
 }
 
 impl Default for MockCustomerTransactionRepo {
@@ -54,7 +65,8 @@ impl CustomerTransactionRepoTrait for MockCustomerTransactionRepo {
         mdoc: Option<i32>,
         date: Option<String>,
         search: Option<String>,
-    ) -> Result<Vec<(CustomerTransaction, String, i32)>, AppError> {
+    ) -> Result<Vec<CustomerTxSearchRow>, AppError> {
+        let voids = self.voids.lock().unwrap();
         let mut items = self
             .store
             .lock()
@@ -73,12 +85,16 @@ impl CustomerTransactionRepoTrait for MockCustomerTransactionRepo {
                         || ct.operator_mdoc.to_string().contains(s)
                         || ct.order_id.to_string().contains(s)
                         || ct.note.as_ref().is_some_and(|n| n.contains(s))
+                        || ct.reason.as_str().replace('_', " ").contains(s)
                 });
 
                 mdoc_match && date_match && search_match
             })
             .cloned()
-            .map(|ct| (ct, "operator".to_string(), 0_i32))
+            .map(|ct| {
+                let voided = voids.iter().any(|v| v.order_id == ct.order_id);
+                (ct, "operator".to_string(), 0_i32, voided)
+            })
             .collect::<Vec<_>>();
 
         items.sort_by(|a, b| b.0.date.cmp(&a.0.date));
@@ -111,6 +127,7 @@ impl CustomerTransactionRepoTrait for MockCustomerTransactionRepo {
                         || ct.operator_mdoc.to_string().contains(s)
                         || ct.order_id.to_string().contains(s)
                         || ct.note.as_ref().is_some_and(|n| n.contains(s))
+                        || ct.reason.as_str().replace('_', " ").contains(s)
                 });
 
                 mdoc_match && date_match && search_match
@@ -133,6 +150,20 @@ impl CustomerTransactionRepoTrait for MockCustomerTransactionRepo {
         Ok(new_id)
     }
 
+    fn find_by_idempotency_key_with_tx(
+        &self,
+        key: &str,
+        _tx: &rusqlite::Transaction<'_>,
+    ) -> Result<Option<CustomerTransaction>, AppError> {
+        Ok(self
+            .store
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|t| t.idempotency_key.as_deref() == Some(key))
+            .cloned())
+    }
+
     fn get_with_details_and_balance(
         &self,
         order_id: i32,
@@ -153,28 +184,45 @@ impl CustomerTransactionRepoTrait for MockCustomerTransactionRepo {
                 upc: "123456789012".to_string(),
                 quantity: 2,
                 price: 300,
+                cost: None,
+                discount: 0,
+                promotion_id: None,
+                promotion_name: None,
+                tax: 0,
+                tax_rate: None,
+                limit_exempt: false,
             },
             "Test Product".to_string(),
         )];
 
-        let balance = 1234;
+        let balance = self
+            .balances
+            .lock()
+            .unwrap()
+            .get(&tx.customer_mdoc)
+            .copied()
+            .unwrap_or(1234);
 
         Ok((tx, details, balance))
     }
 
-    fn get_weekly_spent(
+    fn get_spent(
         &self,
         customer_mdoc: i32,
-        week_start: NaiveDateTime,
+        start: NaiveDateTime,
+        end: NaiveDateTime,
     ) -> Result<i32, AppError> {
-        let week_end = week_start + Duration::days(7);
         let store = self.store.lock().unwrap();
+        let voids = self.voids.lock().unwrap();
 
         let total_spent = store
             .iter()
             .filter(|tx| {
                 tx.customer_mdoc == customer_mdoc
-                    && tx.date.is_some_and(|d| d >= week_start && d < week_end)
+                    && tx.date.is_some_and(|d| d >= start && d < end)
+                    && !voids
+                        .iter()
+                        .any(|v| v.order_id == tx.order_id || v.reversal_order_id == tx.order_id)
             })
             .map(|tx| {
                 let id_str = tx.order_id.to_string();
@@ -189,6 +237,60 @@ impl CustomerTransactionRepoTrait for MockCustomerTransactionRepo {
         Ok(total_spent)
     }
 
+    fn get_spent_with_tx(
+        &self,
+        customer_mdoc: i32,
+        start: NaiveDateTime,
+        end: NaiveDateTime,
+        _tx: &rusqlite::Transaction<'_>,
+    ) -> Result<i32, AppError> {
+        self.get_spent(customer_mdoc, start, end)
+    }
+
+    fn get_balance_with_tx(
+        &self,
+        customer_mdoc: i32,
+        _tx: &rusqlite::Transaction<'_>,
+    ) -> Result<i32, AppError> {
+        Ok(self
+            .balances
+            .lock()
+            .unwrap()
+            .get(&customer_mdoc)
+            .copied()
+            .unwrap_or(0))
+    }
+
+    fn create_void_with_tx(
+        &self,
+        void: &OrderVoid,
+        _tx: &rusqlite::Transaction<'_>,
+    ) -> Result<(), AppError> {
+        let mut voids = self.voids.lock().unwrap();
+        if voids.iter().any(|v| v.order_id == void.order_id) {
+            return Err(AppError::Unexpected(format!(
+                "order {} already voided",
+                void.order_id
+            )));
+        }
+        voids.push(void.clone());
+        Ok(())
+    }
+
+    fn find_void_with_tx(
+        &self,
+        order_id: i32,
+        _tx: &rusqlite::Transaction<'_>,
+    ) -> Result<Option<OrderVoid>, AppError> {
+        Ok(self
+            .voids
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|v| v.order_id == order_id || v.reversal_order_id == order_id)
+            .cloned())
+    }
+
     fn get_sales_details_data(
         &self,
         start: NaiveDateTime,
@@ -201,6 +303,8 @@ impl CustomerTransactionRepoTrait for MockCustomerTransactionRepo {
                 operator_mdoc: 888,
                 date: Some(start + chrono::Duration::hours(1)),
                 note: Some("Mocked transaction".to_string()),
+                idempotency_key: None,
+                reason: OrderReason::Regular,
             },
             customer_name: "Test Customer".to_string(),
             item_count: 3,
@@ -212,6 +316,8 @@ impl CustomerTransactionRepoTrait for MockCustomerTransactionRepo {
                     upc: "1111".to_string(),
                     quantity: 1,
                     price: 500,
+                    discount: 0,
+                    tax: 0,
                     product_name: "Mock Product A".to_string(),
                 },
                 SalesReportDetailRow {
@@ -220,6 +326,8 @@ impl CustomerTransactionRepoTrait for MockCustomerTransactionRepo {
                     upc: "2222".to_string(),
                     quantity: 2,
                     price: 500,
+                    discount: 0,
+                    tax: 0,
                     product_name: "Mock Product B".to_string(),
                 },
             ],
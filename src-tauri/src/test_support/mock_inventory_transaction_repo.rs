@@ -1,10 +1,17 @@
 use crate::common::error::AppError;
-use crate::domain::models::InventoryTransaction;
+use crate::domain::models::{
+    InventoryLot, InventoryReason, InventoryTransaction, LotLabel, NegativeStockPolicy,
+};
+use crate::domain::report_models::expiry_report::ExpiringLot;
+use crate::domain::report_models::shrinkage_report::ShrinkageLine;
 use crate::domain::repos::InventoryTransactionRepoTrait;
+use chrono::{NaiveDate, NaiveDateTime};
 use std::sync::Mutex;
 
 pub struct MockInventoryTransactionRepo {
     store: Mutex<Vec<InventoryTransaction>>,
+    policy: Mutex<NegativeStockPolicy>,
+    lots: Mutex<Vec<(i32, String, LotLabel)>>, // id, upc, label
 }
 
 impl MockInventoryTransactionRepo {
@@ -12,8 +19,37 @@ impl MockInventoryTransactionRepo {
     pub const fn new() -> Self {
         Self {
             store: Mutex::new(vec![]),
+            policy: Mutex::new(NegativeStockPolicy::Block),
+            lots: Mutex::new(vec![]),
         }
     }
+
+    fn lot(&self, id: i32, upc: &str, label: &LotLabel) -> InventoryLot {
+        let on_hand = self
+            .store
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|t| t.lot_id == Some(id))
+            .map(|t| t.quantity_change)
+            .sum();
+        InventoryLot {
+            id,
+            upc: upc.to_string(),
+            label: label.clone(),
+            on_hand,
+            created_at: None,
+        }
+    }
+
+    fn all_lots(&self) -> Vec<InventoryLot> {
+        self.lots
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(id, upc, label)| self.lot(*id, upc, label))
+            .collect()
+    }
 }
 
 impl Default for MockInventoryTransactionRepo {
@@ -49,6 +85,17 @@ impl InventoryTransactionRepoTrait for MockInventoryTransactionRepo {
             .collect())
     }
 
+    fn list_for_order(&self, order_id: i32) -> Result<Vec<InventoryTransaction>, AppError> {
+        Ok(self
+            .store
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|x| x.ref_order_id == Some(order_id))
+            .cloned()
+            .collect())
+    }
+
     fn list_for_operator(&self, op: i32) -> Result<Vec<InventoryTransaction>, AppError> {
         Ok(self
             .store
@@ -66,6 +113,7 @@ impl InventoryTransactionRepoTrait for MockInventoryTransactionRepo {
         offset: i32,
         date: Option<String>,
         search: Option<String>,
+        reason: Option<InventoryReason>,
     ) -> Result<Vec<(InventoryTransaction, String, String)>, AppError> {
         let guard = self.store.lock().unwrap();
 
@@ -88,7 +136,9 @@ impl InventoryTransactionRepoTrait for MockInventoryTransactionRepo {
                         || t.reference.as_ref().is_some_and(|r| r.contains(s))
                 });
 
-                date_match && search_match
+                let reason_match = reason.is_none_or(|r| t.reason == r);
+
+                date_match && search_match && reason_match
             })
             .cloned()
             .map(|t| (t, String::new(), String::new()))
@@ -103,7 +153,12 @@ impl InventoryTransactionRepoTrait for MockInventoryTransactionRepo {
         Ok(transactions.get(start..end).unwrap_or(&[]).to_vec())
     }
 
-    fn count(&self, date: Option<String>, search: Option<String>) -> Result<i32, AppError> {
+    fn count(
+        &self,
+        date: Option<String>,
+        search: Option<String>,
+        reason: Option<InventoryReason>,
+    ) -> Result<i32, AppError> {
         let guard = self.store.lock().unwrap();
         let count = guard
             .iter()
@@ -121,7 +176,9 @@ impl InventoryTransactionRepoTrait for MockInventoryTransactionRepo {
                         || t.reference.as_ref().is_some_and(|r| r.contains(s))
                 });
 
-                date_match && search_match
+                let reason_match = reason.is_none_or(|r| t.reason == r);
+
+                date_match && search_match && reason_match
             })
             .count();
 
@@ -154,4 +211,116 @@ impl InventoryTransactionRepoTrait for MockInventoryTransactionRepo {
     ) -> Result<(), AppError> {
         self.create(tx)
     }
+
+    fn get_available_with_tx(
+        &self,
+        upc: String,
+        _txn: &rusqlite::Transaction<'_>,
+    ) -> Result<i32, AppError> {
+        Ok(self
+            .store
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|x| x.upc == upc)
+            .map(|x| x.quantity_change)
+            .sum())
+    }
+
+    fn negative_stock_policy(&self) -> Result<NegativeStockPolicy, AppError> {
+        Ok(*self.policy.lock().unwrap())
+    }
+
+    fn set_negative_stock_policy(&self, policy: NegativeStockPolicy) -> Result<(), AppError> {
+        *self.policy.lock().unwrap() = policy;
+        Ok(())
+    }
+
+    fn negative_stock_policy_with_tx(
+        &self,
+        _txn: &rusqlite::Transaction<'_>,
+    ) -> Result<NegativeStockPolicy, AppError> {
+        self.negative_stock_policy()
+    }
+
+    // the mock knows no products, so losses are grouped by reason only and
+    // carry no value
+    fn shrinkage(
+        &self,
+        _start: NaiveDateTime,
+        _end: NaiveDateTime,
+    ) -> Result<Vec<ShrinkageLine>, AppError> {
+        let mut out: Vec<ShrinkageLine> = Vec::new();
+        for t in self.store.lock().unwrap().iter() {
+            if t.customer_mdoc.is_some()
+                || t.quantity_change >= 0
+                || t.reason == InventoryReason::Receipt
+            {
+                continue;
+            }
+            match out.iter_mut().find(|l| l.reason == t.reason) {
+                Some(l) => l.units -= t.quantity_change,
+                None => out.push(ShrinkageLine {
+                    reason: t.reason,
+                    units: -t.quantity_change,
+                    ..ShrinkageLine::default()
+                }),
+            }
+        }
+        Ok(out)
+    }
+
+    fn lot_id_with_tx(
+        &self,
+        upc: &str,
+        label: &LotLabel,
+        _txn: &rusqlite::Transaction<'_>,
+    ) -> Result<i32, AppError> {
+        let mut lots = self.lots.lock().unwrap();
+        if let Some((id, _, _)) = lots.iter().find(|(_, u, l)| u == upc && l == label) {
+            return Ok(*id);
+        }
+        let id = lots.len() as i32 + 1;
+        lots.push((id, upc.to_string(), label.clone()));
+        Ok(id)
+    }
+
+    fn open_lots_with_tx(
+        &self,
+        upc: &str,
+        _txn: &rusqlite::Transaction<'_>,
+    ) -> Result<Vec<InventoryLot>, AppError> {
+        let mut lots: Vec<InventoryLot> = self
+            .all_lots()
+            .into_iter()
+            .filter(|l| l.upc == upc && l.on_hand > 0)
+            .collect();
+        lots.sort_by_key(|l| (l.label.expires_on.is_none(), l.label.expires_on, l.id));
+        Ok(lots)
+    }
+
+    fn get_lot_with_tx(
+        &self,
+        id: i32,
+        _txn: &rusqlite::Transaction<'_>,
+    ) -> Result<Option<InventoryLot>, AppError> {
+        Ok(self.all_lots().into_iter().find(|l| l.id == id))
+    }
+
+    // the mock knows no products, so lots are listed under their upc
+    fn expiring_lots(&self, through: NaiveDate) -> Result<Vec<ExpiringLot>, AppError> {
+        let mut lots: Vec<ExpiringLot> = self
+            .all_lots()
+            .into_iter()
+            .filter(|l| l.on_hand > 0 && l.label.expires_on.is_some_and(|d| d <= through))
+            .map(|lot| ExpiringLot {
+                product_name: lot.upc.clone(),
+                category: String::new(),
+                price: 0,
+                lot,
+            })
+            .collect();
+        lots.sort_by_key(|e| (e.lot.label.expires_on, e.lot.id));
+        Ok(lots)
+    }
 }
@@ -0,0 +1,108 @@
+use crate::common::error::AppError;
+use crate::domain::models::{OrderReturn, OrderReturnLine};
+use crate::domain::repos::OrderReturnRepoTrait;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+pub struct MockOrderReturnRepo {
+    returns: Mutex<Vec<OrderReturn>>,
+    lines: Mutex<Vec<OrderReturnLine>>,
+}
+
+impl MockOrderReturnRepo {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            returns: Mutex::new(vec![]),
+            lines: Mutex::new(vec![]),
+        }
+    }
+}
+
+impl Default for MockOrderReturnRepo {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl OrderReturnRepoTrait for MockOrderReturnRepo {
+    fn create_with_tx(
+        &self,
+        ret: &OrderReturn,
+        _tx: &rusqlite::Transaction<'_>,
+    ) -> Result<(), AppError> {
+        self.returns.lock().unwrap().push(ret.clone());
+        Ok(())
+    }
+
+    fn create_line_with_tx(
+        &self,
+        line: &OrderReturnLine,
+        _tx: &rusqlite::Transaction<'_>,
+    ) -> Result<(), AppError> {
+        self.lines.lock().unwrap().push(line.clone());
+        Ok(())
+    }
+
+    fn returned_by_detail_with_tx(
+        &self,
+        order_id: i32,
+        _tx: &rusqlite::Transaction<'_>,
+    ) -> Result<HashMap<i32, i32>, AppError> {
+        let returns = self.returns.lock().unwrap();
+        let mut out = HashMap::new();
+        for line in self.lines.lock().unwrap().iter() {
+            let belongs = returns
+                .iter()
+                .any(|r| r.return_order_id == line.return_order_id && r.order_id == order_id);
+            if belongs {
+                *out.entry(line.detail_id).or_insert(0) += line.quantity;
+            }
+        }
+        Ok(out)
+    }
+
+    fn get_by_return_order(&self, return_order_id: i32) -> Result<Option<OrderReturn>, AppError> {
+        Ok(self
+            .returns
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|r| r.return_order_id == return_order_id)
+            .cloned())
+    }
+
+    fn get_by_return_order_with_tx(
+        &self,
+        return_order_id: i32,
+        _tx: &rusqlite::Transaction<'_>,
+    ) -> Result<Option<OrderReturn>, AppError> {
+        self.get_by_return_order(return_order_id)
+    }
+
+    fn list_for_order_with_tx(
+        &self,
+        order_id: i32,
+        _tx: &rusqlite::Transaction<'_>,
+    ) -> Result<Vec<OrderReturn>, AppError> {
+        Ok(self
+            .returns
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|r| r.order_id == order_id)
+            .cloned()
+            .collect())
+    }
+
+    fn list_lines(&self, return_order_id: i32) -> Result<Vec<OrderReturnLine>, AppError> {
+        Ok(self
+            .lines
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|l| l.return_order_id == return_order_id)
+            .cloned()
+            .collect())
+    }
+}
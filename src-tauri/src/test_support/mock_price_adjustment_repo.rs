@@ -1,6 +1,7 @@
 use crate::common::error::AppError;
-use crate::domain::models::PriceAdjustment;
+use crate::domain::models::{PriceAdjustment, PriceAdjustmentStatus};
 use crate::domain::repos::PriceAdjustmentRepoTrait;
+use chrono::NaiveDateTime;
 use std::sync::Mutex;
 
 pub struct MockPriceAdjustmentRepo {
@@ -136,4 +137,85 @@ impl PriceAdjustmentRepoTrait for MockPriceAdjustmentRepo {
 
         Ok(count as i32)
     }
+
+    fn list_pending(&self) -> Result<Vec<PriceAdjustment>, AppError> {
+        let mut pending: Vec<_> = self
+            .store
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|a| a.status == PriceAdjustmentStatus::Pending)
+            .cloned()
+            .collect();
+        pending.sort_by_key(|a| (a.effective_at, a.id));
+        Ok(pending)
+    }
+
+    // Marks due changes applied; the mock has no products to reprice.
+    fn due_with_tx(
+        &self,
+        now: NaiveDateTime,
+        _tx: &rusqlite::Transaction<'_>,
+    ) -> Result<Vec<PriceAdjustment>, AppError> {
+        let mut due: Vec<PriceAdjustment> = self
+            .store
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|a| {
+                a.status == PriceAdjustmentStatus::Pending
+                    && a.effective_at.is_some_and(|t| t <= now)
+            })
+            .cloned()
+            .collect();
+        due.sort_by_key(|a| (a.effective_at, a.id));
+        Ok(due)
+    }
+
+    fn mark_applied_with_tx(
+        &self,
+        id: i32,
+        old: i32,
+        _tx: &rusqlite::Transaction<'_>,
+    ) -> Result<(), AppError> {
+        let mut guard = self.store.lock().unwrap();
+        let a = guard
+            .iter_mut()
+            .find(|a| a.id == id && a.status == PriceAdjustmentStatus::Pending)
+            .ok_or_else(|| {
+                AppError::NotFound(format!("pending price adjustment {id} not found"))
+            })?;
+        a.old = old;
+        a.status = PriceAdjustmentStatus::Applied;
+        Ok(())
+    }
+
+    fn cancel(&self, id: i32) -> Result<(), AppError> {
+        let mut guard = self.store.lock().unwrap();
+        let a = guard
+            .iter_mut()
+            .find(|a| a.id == id && a.status == PriceAdjustmentStatus::Pending)
+            .ok_or_else(|| {
+                AppError::NotFound(format!("pending price adjustment {id} not found"))
+            })?;
+        a.status = PriceAdjustmentStatus::Cancelled;
+        Ok(())
+    }
+
+    fn cancel_pending_for_upc_with_tx(
+        &self,
+        upc: &str,
+        _tx: &rusqlite::Transaction<'_>,
+    ) -> Result<Vec<i32>, AppError> {
+        let mut guard = self.store.lock().unwrap();
+        let mut ids = vec![];
+        for a in guard
+            .iter_mut()
+            .filter(|a| a.upc == upc && a.status == PriceAdjustmentStatus::Pending)
+        {
+            a.status = PriceAdjustmentStatus::Cancelled;
+            ids.push(a.id);
+        }
+        Ok(ids)
+    }
 }
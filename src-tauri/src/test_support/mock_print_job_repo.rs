@@ -0,0 +1,121 @@
+use crate::common::error::AppError;
+use crate::domain::models::{PrintJob, PrintJobStatus};
+use crate::domain::repos::PrintJobRepoTrait;
+use std::sync::Mutex;
+
+pub struct MockPrintJobRepo {
+    store: Mutex<Vec<PrintJob>>,
+}
+
+impl MockPrintJobRepo {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            store: Mutex::new(vec![]),
+        }
+    }
+
+    // Drops every job, as if none had ever been queued.
+    pub fn clear(&self) {
+        self.store.lock().unwrap().clear();
+    }
+}
+
+impl Default for MockPrintJobRepo {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PrintJobRepoTrait for MockPrintJobRepo {
+    fn create(&self, job: &PrintJob) -> Result<i32, AppError> {
+        let mut store = self.store.lock().unwrap();
+        let id = store.iter().map(|j| j.id).max().unwrap_or(0) + 1;
+        store.push(PrintJob { id, ..job.clone() });
+        Ok(id)
+    }
+
+    fn get(&self, id: i32) -> Result<Option<PrintJob>, AppError> {
+        Ok(self
+            .store
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|j| j.id == id)
+            .cloned())
+    }
+
+    fn list(&self, status: Option<PrintJobStatus>, limit: i32) -> Result<Vec<PrintJob>, AppError> {
+        Ok(self
+            .store
+            .lock()
+            .unwrap()
+            .iter()
+            .rev()
+            .filter(|j| status.is_none_or(|s| j.status == s))
+            .take(limit as usize)
+            .cloned()
+            .collect())
+    }
+
+    fn claim_next(&self) -> Result<Option<PrintJob>, AppError> {
+        let mut store = self.store.lock().unwrap();
+        Ok(store
+            .iter_mut()
+            .find(|j| j.status == PrintJobStatus::Pending)
+            .map(|j| {
+                j.status = PrintJobStatus::Printing;
+                j.clone()
+            }))
+    }
+
+    fn update_status(
+        &self,
+        id: i32,
+        status: PrintJobStatus,
+        attempts: i32,
+        last_error: Option<String>,
+    ) -> Result<(), AppError> {
+        let mut store = self.store.lock().unwrap();
+        let job = store
+            .iter_mut()
+            .find(|j| j.id == id)
+            .ok_or_else(|| AppError::NotFound(format!("print job {id} not found")))?;
+        job.status = status;
+        job.attempts = attempts;
+        job.last_error = last_error;
+        Ok(())
+    }
+
+    fn sale_balance(&self, order_id: i32) -> Result<Option<i32>, AppError> {
+        Ok(self
+            .store
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|j| j.order_id == order_id)
+            .find_map(|j| j.balance))
+    }
+
+    fn has_receipt(&self, order_id: i32) -> Result<bool, AppError> {
+        Ok(self
+            .store
+            .lock()
+            .unwrap()
+            .iter()
+            .any(|j| j.order_id == order_id && j.reprint_by.is_none()))
+    }
+
+    fn requeue_interrupted(&self) -> Result<usize, AppError> {
+        let mut store = self.store.lock().unwrap();
+        let mut n = 0;
+        for job in store
+            .iter_mut()
+            .filter(|j| j.status == PrintJobStatus::Printing)
+        {
+            job.status = PrintJobStatus::Pending;
+            n += 1;
+        }
+        Ok(n)
+    }
+}
@@ -1,21 +1,50 @@
 use crate::common::error::AppError;
-use crate::domain::models::Product;
+use crate::domain::models::{BarcodeAlias, BundleComponent, Product};
 use crate::domain::report_models::product_inventory::ProductInventoryReport;
 use crate::domain::report_models::product_inventory::ProductInventoryTotals;
+use crate::domain::report_models::reorder_report::LowStockItem;
 use crate::domain::repos::ProductRepoTrait;
+use chrono::NaiveDateTime;
+use std::collections::{HashMap, HashSet};
 use std::sync::Mutex;
 
 pub struct MockProductRepo {
     store: Mutex<Vec<Product>>,
+    exempt_categories: Mutex<HashSet<String>>,
+    available: Mutex<HashMap<String, i32>>,
+    barcodes: Mutex<Vec<BarcodeAlias>>,
+    components: Mutex<Vec<BundleComponent>>,
 }
 
 impl MockProductRepo {
     #[must_use]
-    pub const fn new() -> Self {
+    pub fn new() -> Self {
         Self {
             store: Mutex::new(vec![]),
+            exempt_categories: Mutex::new(HashSet::new()),
+            available: Mutex::new(HashMap::new()),
+            barcodes: Mutex::new(vec![]),
+            components: Mutex::new(vec![]),
         }
     }
+
+    // Stands in for the category flag the real repo joins against.
+    pub fn set_category_limit_exempt(&self, category: &str, limit_exempt: bool) {
+        let mut exempt = self.exempt_categories.lock().unwrap();
+        if limit_exempt {
+            exempt.insert(category.to_string());
+        } else {
+            exempt.remove(category);
+        }
+    }
+
+    // Stands in for the on-hand quantity the real repo sums from inventory.
+    pub fn set_available(&self, upc: &str, available: i32) {
+        self.available
+            .lock()
+            .unwrap()
+            .insert(upc.to_string(), available);
+    }
 }
 
 impl Default for MockProductRepo {
@@ -44,11 +73,40 @@ impl ProductRepoTrait for MockProductRepo {
             .cloned())
     }
 
+    fn is_limit_exempt(&self, upc: &str) -> Result<bool, AppError> {
+        let store = self.store.lock().unwrap();
+        let Some(p) = store.iter().find(|p| p.upc == upc) else {
+            return Ok(false);
+        };
+        Ok(p.limit_exempt
+            .unwrap_or_else(|| self.exempt_categories.lock().unwrap().contains(&p.category)))
+    }
+
+    fn is_limit_exempt_with_tx(
+        &self,
+        upc: &str,
+        _tx: &rusqlite::Transaction<'_>,
+    ) -> Result<bool, AppError> {
+        self.is_limit_exempt(upc)
+    }
+
+    fn get_by_upc_with_tx(
+        &self,
+        upc: String,
+        _tx: &rusqlite::Transaction<'_>,
+    ) -> Result<Option<Product>, AppError> {
+        self.get_by_upc(upc)
+    }
+
     fn create(&self, p: &Product) -> Result<(), AppError> {
         self.store.lock().unwrap().push(p.clone());
         Ok(())
     }
 
+    fn create_with_tx(&self, p: &Product, _tx: &rusqlite::Transaction<'_>) -> Result<(), AppError> {
+        self.create(p)
+    }
+
     fn update_by_upc(&self, p: &Product) -> Result<(), AppError> {
         let mut v = self.store.lock().unwrap();
         if let Some(elem) = v.iter_mut().find(|e| e.upc == p.upc) {
@@ -65,6 +123,38 @@ impl ProductRepoTrait for MockProductRepo {
         self.update_by_upc(p)
     }
 
+    fn set_price_with_tx(
+        &self,
+        upc: &str,
+        price: i32,
+        updated: NaiveDateTime,
+        _tx: &rusqlite::Transaction<'_>,
+    ) -> Result<(), AppError> {
+        let mut v = self.store.lock().unwrap();
+        let p = v
+            .iter_mut()
+            .find(|p| p.upc == upc)
+            .ok_or_else(|| AppError::NotFound(format!("product {upc} not found")))?;
+        p.price = price;
+        p.updated = Some(updated);
+        Ok(())
+    }
+
+    fn set_unit_cost_with_tx(
+        &self,
+        upc: &str,
+        unit_cost: i32,
+        _tx: &rusqlite::Transaction<'_>,
+    ) -> Result<(), AppError> {
+        let mut v = self.store.lock().unwrap();
+        let p = v
+            .iter_mut()
+            .find(|p| p.upc == upc)
+            .ok_or_else(|| AppError::NotFound(format!("product {upc} not found")))?;
+        p.unit_cost = Some(unit_cost);
+        Ok(())
+    }
+
     fn list(&self) -> Result<Vec<Product>, AppError> {
         Ok(self.store.lock().unwrap().clone())
     }
@@ -189,4 +279,146 @@ impl ProductRepoTrait for MockProductRepo {
             total_value,
         })
     }
+
+    fn low_stock(&self) -> Result<Vec<LowStockItem>, AppError> {
+        let available = self.available.lock().unwrap();
+        let mut items: Vec<LowStockItem> = self
+            .store
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|p| p.deleted.is_none())
+            .filter_map(|p| {
+                let on_hand = available.get(&p.upc).copied().unwrap_or(0);
+                let point = p.reorder_point.filter(|point| on_hand <= *point)?;
+                Some(LowStockItem {
+                    upc: p.upc.clone(),
+                    desc: p.desc.clone(),
+                    category: p.category.clone(),
+                    available: on_hand,
+                    reorder_point: point,
+                    reorder_quantity: p.reorder_quantity,
+                    vendor_name: None,
+                })
+            })
+            .collect();
+        items.sort_by(|a, b| (&a.category, &a.desc).cmp(&(&b.category, &b.desc)));
+        Ok(items)
+    }
+
+    fn count_low_stock(&self) -> Result<i32, AppError> {
+        Ok(self.low_stock()?.len() as i32)
+    }
+
+    fn resolve_barcode(&self, code: &str) -> Result<Option<(Product, i32)>, AppError> {
+        let (upc, multiplier) = self
+            .get_barcode(code)?
+            .map_or((code.to_string(), 1), |a| (a.upc, a.multiplier));
+        Ok(self.get_by_upc(upc)?.map(|p| (p, multiplier)))
+    }
+
+    fn resolve_barcode_with_tx(
+        &self,
+        code: &str,
+        _tx: &rusqlite::Transaction<'_>,
+    ) -> Result<Option<(Product, i32)>, AppError> {
+        self.resolve_barcode(code)
+    }
+
+    fn get_barcode(&self, barcode: &str) -> Result<Option<BarcodeAlias>, AppError> {
+        Ok(self
+            .barcodes
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|a| a.barcode == barcode)
+            .cloned())
+    }
+
+    fn list_barcodes(&self, upc: Option<String>) -> Result<Vec<BarcodeAlias>, AppError> {
+        Ok(self
+            .barcodes
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|a| upc.as_ref().is_none_or(|u| *u == a.upc))
+            .cloned()
+            .collect())
+    }
+
+    fn add_barcode(&self, alias: &BarcodeAlias) -> Result<(), AppError> {
+        self.barcodes.lock().unwrap().push(alias.clone());
+        Ok(())
+    }
+
+    fn remove_barcode(&self, barcode: &str) -> Result<(), AppError> {
+        let mut barcodes = self.barcodes.lock().unwrap();
+        let before = barcodes.len();
+        barcodes.retain(|a| a.barcode != barcode);
+        if barcodes.len() == before {
+            return Err(AppError::NotFound(format!("barcode {barcode} not found")));
+        }
+        Ok(())
+    }
+
+    fn components(&self, bundle_upc: &str) -> Result<Vec<(BundleComponent, String)>, AppError> {
+        let store = self.store.lock().unwrap();
+        Ok(self
+            .components
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|c| c.bundle_upc == bundle_upc)
+            .map(|c| {
+                let desc = store
+                    .iter()
+                    .find(|p| p.upc == c.component_upc)
+                    .map(|p| p.desc.clone())
+                    .unwrap_or_default();
+                (c.clone(), desc)
+            })
+            .collect())
+    }
+
+    fn components_with_tx(
+        &self,
+        bundle_upc: &str,
+        _tx: &rusqlite::Transaction<'_>,
+    ) -> Result<Vec<BundleComponent>, AppError> {
+        Ok(self
+            .components
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|c| c.bundle_upc == bundle_upc)
+            .cloned()
+            .collect())
+    }
+
+    fn bundles_containing_with_tx(
+        &self,
+        component_upc: &str,
+        _tx: &rusqlite::Transaction<'_>,
+    ) -> Result<Vec<String>, AppError> {
+        Ok(self
+            .components
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|c| c.component_upc == component_upc)
+            .map(|c| c.bundle_upc.clone())
+            .collect())
+    }
+
+    fn set_components_with_tx(
+        &self,
+        bundle_upc: &str,
+        components: &[BundleComponent],
+        _tx: &rusqlite::Transaction<'_>,
+    ) -> Result<(), AppError> {
+        let mut all = self.components.lock().unwrap();
+        all.retain(|c| c.bundle_upc != bundle_upc);
+        all.extend(components.iter().cloned());
+        Ok(())
+    }
 }
@@ -0,0 +1,87 @@
+use crate::common::error::AppError;
+use crate::domain::models::Promotion;
+use crate::domain::repos::PromotionRepoTrait;
+use chrono::{NaiveDateTime, Utc};
+use std::sync::Mutex;
+
+pub struct MockPromotionRepo {
+    promotions: Mutex<Vec<Promotion>>,
+}
+
+impl MockPromotionRepo {
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            promotions: Mutex::new(vec![]),
+        }
+    }
+}
+
+impl Default for MockPromotionRepo {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PromotionRepoTrait for MockPromotionRepo {
+    fn list(&self) -> Result<Vec<Promotion>, AppError> {
+        Ok(self
+            .promotions
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|p| p.deleted.is_none())
+            .cloned()
+            .collect())
+    }
+
+    fn get(&self, id: i32) -> Result<Option<Promotion>, AppError> {
+        Ok(self
+            .promotions
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|p| p.id == id)
+            .cloned())
+    }
+
+    fn create(&self, promotion: &Promotion) -> Result<i32, AppError> {
+        let mut promotions = self.promotions.lock().unwrap();
+        let id = promotions.iter().map(|p| p.id).max().unwrap_or(0) + 1;
+        promotions.push(Promotion {
+            id,
+            created_at: Some(Utc::now().naive_utc()),
+            ..promotion.clone()
+        });
+        Ok(id)
+    }
+
+    fn delete(&self, id: i32) -> Result<(), AppError> {
+        let mut promotions = self.promotions.lock().unwrap();
+        let p = promotions
+            .iter_mut()
+            .find(|p| p.id == id && p.deleted.is_none())
+            .ok_or_else(|| AppError::NotFound(format!("promotion {id} not found")))?;
+        p.deleted = Some(Utc::now().naive_utc());
+        Ok(())
+    }
+
+    fn running(&self, now: NaiveDateTime) -> Result<Vec<Promotion>, AppError> {
+        Ok(self
+            .promotions
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|p| p.is_running(now))
+            .cloned()
+            .collect())
+    }
+
+    fn running_with_tx(
+        &self,
+        now: NaiveDateTime,
+        _tx: &rusqlite::Transaction<'_>,
+    ) -> Result<Vec<Promotion>, AppError> {
+        self.running(now)
+    }
+}
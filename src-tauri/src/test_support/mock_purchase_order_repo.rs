@@ -0,0 +1,159 @@
+use crate::common::error::AppError;
+use crate::domain::models::{PurchaseOrder, PurchaseOrderLine, PurchaseOrderStatus};
+use crate::domain::repos::purchase_order_repo_trait::{PurchaseOrderLineRow, PurchaseOrderRow};
+use crate::domain::repos::PurchaseOrderRepoTrait;
+use chrono::NaiveDateTime;
+use std::sync::Mutex;
+
+/// Shared mock implementation for `PurchaseOrderRepoTrait`. Vendor names and
+/// product descriptions aren't joined in; rows carry the vendor id and upc instead.
+pub struct MockPurchaseOrderRepo {
+    orders: Mutex<Vec<PurchaseOrder>>,
+    lines: Mutex<Vec<PurchaseOrderLine>>,
+}
+
+impl MockPurchaseOrderRepo {
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            orders: Mutex::new(vec![]),
+            lines: Mutex::new(vec![]),
+        }
+    }
+}
+
+impl Default for MockPurchaseOrderRepo {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PurchaseOrderRepoTrait for MockPurchaseOrderRepo {
+    fn list(&self, status: Option<PurchaseOrderStatus>) -> Result<Vec<PurchaseOrderRow>, AppError> {
+        Ok(self
+            .orders
+            .lock()
+            .unwrap()
+            .iter()
+            .rev()
+            .filter(|po| status.is_none_or(|s| po.status == s))
+            .map(|po| (po.clone(), po.vendor_id.to_string()))
+            .collect())
+    }
+
+    fn get(&self, id: i32) -> Result<Option<PurchaseOrder>, AppError> {
+        Ok(self
+            .orders
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|po| po.id == id)
+            .cloned())
+    }
+
+    fn lines(&self, po_id: i32) -> Result<Vec<PurchaseOrderLineRow>, AppError> {
+        Ok(self
+            .lines
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|l| l.po_id == po_id)
+            .map(|l| (l.clone(), l.upc.clone()))
+            .collect())
+    }
+
+    fn get_with_tx(
+        &self,
+        id: i32,
+        _tx: &rusqlite::Transaction<'_>,
+    ) -> Result<Option<PurchaseOrder>, AppError> {
+        self.get(id)
+    }
+
+    fn create_with_tx(
+        &self,
+        po: &PurchaseOrder,
+        _tx: &rusqlite::Transaction<'_>,
+    ) -> Result<i32, AppError> {
+        let mut orders = self.orders.lock().unwrap();
+        let id = orders.iter().map(|po| po.id).max().unwrap_or(0) + 1;
+        orders.push(PurchaseOrder {
+            id,
+            created_at: Some(chrono::Utc::now().naive_utc()),
+            ..po.clone()
+        });
+        Ok(id)
+    }
+
+    fn add_line_with_tx(
+        &self,
+        line: &PurchaseOrderLine,
+        _tx: &rusqlite::Transaction<'_>,
+    ) -> Result<i32, AppError> {
+        let mut lines = self.lines.lock().unwrap();
+        if lines
+            .iter()
+            .any(|l| l.po_id == line.po_id && l.upc == line.upc)
+        {
+            return Err(AppError::Validation(format!(
+                "upc {} is already on purchase order {}",
+                line.upc, line.po_id
+            )));
+        }
+        let id = lines.iter().map(|l| l.id).max().unwrap_or(0) + 1;
+        lines.push(PurchaseOrderLine { id, ..line.clone() });
+        Ok(id)
+    }
+
+    fn lines_with_tx(
+        &self,
+        po_id: i32,
+        _tx: &rusqlite::Transaction<'_>,
+    ) -> Result<Vec<PurchaseOrderLine>, AppError> {
+        Ok(self
+            .lines
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|l| l.po_id == po_id)
+            .cloned()
+            .collect())
+    }
+
+    fn add_received_with_tx(
+        &self,
+        line_id: i32,
+        quantity: i32,
+        _tx: &rusqlite::Transaction<'_>,
+    ) -> Result<(), AppError> {
+        let mut lines = self.lines.lock().unwrap();
+        let line = lines.iter_mut().find(|l| l.id == line_id).ok_or_else(|| {
+            AppError::NotFound(format!("purchase order line {line_id} not found"))
+        })?;
+        line.received_quantity += quantity;
+        Ok(())
+    }
+
+    fn close_with_tx(
+        &self,
+        id: i32,
+        status: PurchaseOrderStatus,
+        received_at: Option<NaiveDateTime>,
+        _tx: &rusqlite::Transaction<'_>,
+    ) -> Result<(), AppError> {
+        let mut orders = self.orders.lock().unwrap();
+        let po = orders
+            .iter_mut()
+            .find(|po| po.id == id)
+            .ok_or_else(|| AppError::NotFound(format!("purchase order {id} not found")))?;
+        if po.status != PurchaseOrderStatus::Open {
+            return Err(AppError::Validation(format!(
+                "purchase order {id} is {}",
+                po.status.as_str()
+            )));
+        }
+        po.status = status;
+        po.received_at = received_at;
+        Ok(())
+    }
+}
@@ -0,0 +1,108 @@
+use crate::common::error::AppError;
+use crate::domain::models::{Product, QuantityLimit};
+use crate::domain::repos::QuantityLimitRepoTrait;
+use chrono::NaiveDateTime;
+use std::sync::Mutex;
+
+struct Purchase {
+    customer_mdoc: i32,
+    product: Product,
+    quantity: i32,
+    at: NaiveDateTime,
+}
+
+pub struct MockQuantityLimitRepo {
+    limits: Mutex<Vec<QuantityLimit>>,
+    purchases: Mutex<Vec<Purchase>>,
+}
+
+impl MockQuantityLimitRepo {
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            limits: Mutex::new(vec![]),
+            purchases: Mutex::new(vec![]),
+        }
+    }
+
+    // Seeds purchase history, which the real repo reads from customer_tx_detail.
+    pub fn record_purchase(
+        &self,
+        customer_mdoc: i32,
+        product: &Product,
+        quantity: i32,
+        at: NaiveDateTime,
+    ) {
+        self.purchases.lock().unwrap().push(Purchase {
+            customer_mdoc,
+            product: product.clone(),
+            quantity,
+            at,
+        });
+    }
+}
+
+impl Default for MockQuantityLimitRepo {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl QuantityLimitRepoTrait for MockQuantityLimitRepo {
+    fn list(&self) -> Result<Vec<QuantityLimit>, AppError> {
+        Ok(self.limits.lock().unwrap().clone())
+    }
+
+    fn create(&self, limit: &QuantityLimit) -> Result<i32, AppError> {
+        let mut limits = self.limits.lock().unwrap();
+        let id = limits.iter().map(|l| l.id).max().unwrap_or(0) + 1;
+        limits.push(QuantityLimit {
+            id,
+            ..limit.clone()
+        });
+        Ok(id)
+    }
+
+    fn delete(&self, id: i32) -> Result<(), AppError> {
+        let mut limits = self.limits.lock().unwrap();
+        let before = limits.len();
+        limits.retain(|l| l.id != id);
+        if limits.len() == before {
+            return Err(AppError::NotFound(format!("quantity limit {id} not found")));
+        }
+        Ok(())
+    }
+
+    fn purchased(
+        &self,
+        customer_mdoc: i32,
+        limit: &QuantityLimit,
+        start: NaiveDateTime,
+        end: NaiveDateTime,
+    ) -> Result<i32, AppError> {
+        Ok(self
+            .purchases
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|p| {
+                p.customer_mdoc == customer_mdoc
+                    && limit.applies_to(&p.product)
+                    && p.at >= start
+                    && p.at < end
+            })
+            .map(|p| p.quantity)
+            .sum())
+    }
+
+    fn purchased_with_tx(
+        &self,
+        customer_mdoc: i32,
+        limit: &QuantityLimit,
+        start: NaiveDateTime,
+        end: NaiveDateTime,
+        _tx: &rusqlite::Transaction<'_>,
+    ) -> Result<i32, AppError> {
+        self.purchased(customer_mdoc, limit, start, end)
+    }
+}
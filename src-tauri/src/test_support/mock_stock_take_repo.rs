@@ -0,0 +1,181 @@
+use crate::common::error::AppError;
+use crate::domain::models::{StockTake, StockTakeLine, StockTakeStatus};
+use crate::domain::repos::stock_take_repo_trait::StockTakeLineRow;
+use crate::domain::repos::StockTakeRepoTrait;
+use chrono::NaiveDateTime;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// Shared mock implementation for `StockTakeRepoTrait`. Product descriptions
+/// aren't joined in (rows carry the upc instead); prices come from `set_price`
+/// and default to 0.
+pub struct MockStockTakeRepo {
+    sessions: Mutex<Vec<StockTake>>,
+    lines: Mutex<Vec<StockTakeLine>>,
+    prices: Mutex<HashMap<String, i32>>,
+}
+
+impl MockStockTakeRepo {
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            sessions: Mutex::new(vec![]),
+            lines: Mutex::new(vec![]),
+            prices: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn set_price(&self, upc: &str, price: i32) {
+        self.prices.lock().unwrap().insert(upc.to_string(), price);
+    }
+
+    // Mirrors the open-status guard on the real repo's writes.
+    fn require_open(&self, id: i32) -> Result<(), AppError> {
+        let sessions = self.sessions.lock().unwrap();
+        let session = sessions
+            .iter()
+            .find(|s| s.id == id)
+            .ok_or_else(|| AppError::NotFound(format!("stock take {id} not found")))?;
+        if session.status != StockTakeStatus::Open {
+            return Err(AppError::Validation(format!(
+                "stock take {id} is {}",
+                session.status.as_str()
+            )));
+        }
+        Ok(())
+    }
+}
+
+impl Default for MockStockTakeRepo {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StockTakeRepoTrait for MockStockTakeRepo {
+    fn list(&self, status: Option<StockTakeStatus>) -> Result<Vec<StockTake>, AppError> {
+        Ok(self
+            .sessions
+            .lock()
+            .unwrap()
+            .iter()
+            .rev()
+            .filter(|s| status.is_none_or(|st| s.status == st))
+            .cloned()
+            .collect())
+    }
+
+    fn get(&self, id: i32) -> Result<Option<StockTake>, AppError> {
+        Ok(self
+            .sessions
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|s| s.id == id)
+            .cloned())
+    }
+
+    fn lines(&self, stock_take_id: i32) -> Result<Vec<StockTakeLineRow>, AppError> {
+        let prices = self.prices.lock().unwrap();
+        Ok(self
+            .lines
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|l| l.stock_take_id == stock_take_id)
+            .map(|l| {
+                let price = prices.get(&l.upc).copied().unwrap_or(0);
+                (l.clone(), l.upc.clone(), price)
+            })
+            .collect())
+    }
+
+    fn create_with_tx(
+        &self,
+        stock_take: &StockTake,
+        _tx: &rusqlite::Transaction<'_>,
+    ) -> Result<i32, AppError> {
+        let mut sessions = self.sessions.lock().unwrap();
+        let id = sessions.iter().map(|s| s.id).max().unwrap_or(0) + 1;
+        sessions.push(StockTake {
+            id,
+            created_at: Some(chrono::Utc::now().naive_utc()),
+            ..stock_take.clone()
+        });
+        Ok(id)
+    }
+
+    fn add_line_with_tx(
+        &self,
+        line: &StockTakeLine,
+        _tx: &rusqlite::Transaction<'_>,
+    ) -> Result<i32, AppError> {
+        let mut lines = self.lines.lock().unwrap();
+        if lines
+            .iter()
+            .any(|l| l.stock_take_id == line.stock_take_id && l.upc == line.upc)
+        {
+            return Err(AppError::Validation(format!(
+                "upc {} is already on stock take {}",
+                line.upc, line.stock_take_id
+            )));
+        }
+        let id = lines.iter().map(|l| l.id).max().unwrap_or(0) + 1;
+        lines.push(StockTakeLine { id, ..line.clone() });
+        Ok(id)
+    }
+
+    fn lines_with_tx(
+        &self,
+        stock_take_id: i32,
+        _tx: &rusqlite::Transaction<'_>,
+    ) -> Result<Vec<StockTakeLine>, AppError> {
+        Ok(self
+            .lines
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|l| l.stock_take_id == stock_take_id)
+            .cloned()
+            .collect())
+    }
+
+    fn record_count_with_tx(
+        &self,
+        stock_take_id: i32,
+        upc: &str,
+        counted_quantity: i32,
+        counted_at: NaiveDateTime,
+        _tx: &rusqlite::Transaction<'_>,
+    ) -> Result<(), AppError> {
+        self.require_open(stock_take_id)?;
+        let mut lines = self.lines.lock().unwrap();
+        let line = lines
+            .iter_mut()
+            .find(|l| l.stock_take_id == stock_take_id && l.upc == upc)
+            .ok_or_else(|| {
+                AppError::NotFound(format!("upc {upc} is not on stock take {stock_take_id}"))
+            })?;
+        line.counted_quantity = Some(counted_quantity);
+        line.counted_at = Some(counted_at);
+        Ok(())
+    }
+
+    fn close_with_tx(
+        &self,
+        id: i32,
+        status: StockTakeStatus,
+        posted_at: Option<NaiveDateTime>,
+        _tx: &rusqlite::Transaction<'_>,
+    ) -> Result<(), AppError> {
+        self.require_open(id)?;
+        let mut sessions = self.sessions.lock().unwrap();
+        let session = sessions
+            .iter_mut()
+            .find(|s| s.id == id)
+            .ok_or_else(|| AppError::NotFound(format!("stock take {id} not found")))?;
+        session.status = status;
+        session.posted_at = posted_at;
+        Ok(())
+    }
+}
@@ -0,0 +1,97 @@
+use crate::common::error::AppError;
+use crate::domain::models::{TaxRate, TaxSettings};
+use crate::domain::repos::TaxRateRepoTrait;
+use chrono::{NaiveDateTime, Utc};
+use std::sync::Mutex;
+
+pub struct MockTaxRateRepo {
+    rates: Mutex<Vec<TaxRate>>,
+    settings: Mutex<TaxSettings>,
+}
+
+impl MockTaxRateRepo {
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            rates: Mutex::new(vec![]),
+            settings: Mutex::new(TaxSettings::default()),
+        }
+    }
+}
+
+impl Default for MockTaxRateRepo {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TaxRateRepoTrait for MockTaxRateRepo {
+    fn list(&self) -> Result<Vec<TaxRate>, AppError> {
+        Ok(self
+            .rates
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|r| r.deleted.is_none())
+            .cloned()
+            .collect())
+    }
+
+    fn create(&self, rate: &TaxRate) -> Result<i32, AppError> {
+        let mut rates = self.rates.lock().unwrap();
+        let id = rates.iter().map(|r| r.id).max().unwrap_or(0) + 1;
+        rates.push(TaxRate {
+            id,
+            created_at: Some(Utc::now().naive_utc()),
+            ..rate.clone()
+        });
+        Ok(id)
+    }
+
+    fn delete(&self, id: i32) -> Result<(), AppError> {
+        let mut rates = self.rates.lock().unwrap();
+        let r = rates
+            .iter_mut()
+            .find(|r| r.id == id && r.deleted.is_none())
+            .ok_or_else(|| AppError::NotFound(format!("tax rate {id} not found")))?;
+        r.deleted = Some(Utc::now().naive_utc());
+        Ok(())
+    }
+
+    fn in_effect(&self, now: NaiveDateTime) -> Result<Vec<TaxRate>, AppError> {
+        let mut rates: Vec<TaxRate> = self
+            .rates
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|r| r.in_effect(now))
+            .cloned()
+            .collect();
+        rates.sort_by_key(|r| (r.effective_at.or(r.created_at), r.id));
+        Ok(rates)
+    }
+
+    fn in_effect_with_tx(
+        &self,
+        now: NaiveDateTime,
+        _tx: &rusqlite::Transaction<'_>,
+    ) -> Result<Vec<TaxRate>, AppError> {
+        self.in_effect(now)
+    }
+
+    fn get_settings(&self) -> Result<TaxSettings, AppError> {
+        Ok(*self.settings.lock().unwrap())
+    }
+
+    fn set_settings(&self, settings: &TaxSettings) -> Result<(), AppError> {
+        *self.settings.lock().unwrap() = *settings;
+        Ok(())
+    }
+
+    fn get_settings_with_tx(
+        &self,
+        _tx: &rusqlite::Transaction<'_>,
+    ) -> Result<TaxSettings, AppError> {
+        self.get_settings()
+    }
+}
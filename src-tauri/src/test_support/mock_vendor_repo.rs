@@ -0,0 +1,86 @@
+use crate::common::error::AppError;
+use crate::domain::models::Vendor;
+use crate::domain::repos::VendorRepoTrait;
+use std::sync::Mutex;
+
+pub struct MockVendorRepo {
+    vendors: Mutex<Vec<Vendor>>,
+}
+
+impl MockVendorRepo {
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            vendors: Mutex::new(vec![]),
+        }
+    }
+}
+
+impl Default for MockVendorRepo {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl VendorRepoTrait for MockVendorRepo {
+    fn list(&self) -> Result<Vec<Vendor>, AppError> {
+        let mut vendors: Vec<Vendor> = self
+            .vendors
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|v| v.deleted.is_none())
+            .cloned()
+            .collect();
+        vendors.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(vendors)
+    }
+
+    fn get(&self, id: i32) -> Result<Option<Vendor>, AppError> {
+        Ok(self
+            .vendors
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|v| v.id == id)
+            .cloned())
+    }
+
+    fn create(&self, vendor: &Vendor) -> Result<i32, AppError> {
+        let mut vendors = self.vendors.lock().unwrap();
+        if vendors.iter().any(|v| v.name == vendor.name) {
+            return Err(AppError::Validation(format!(
+                "vendor {} already exists",
+                vendor.name
+            )));
+        }
+        let id = vendors.iter().map(|v| v.id).max().unwrap_or(0) + 1;
+        vendors.push(Vendor {
+            id,
+            ..vendor.clone()
+        });
+        Ok(id)
+    }
+
+    fn update(&self, vendor: &Vendor) -> Result<(), AppError> {
+        let mut vendors = self.vendors.lock().unwrap();
+        let existing = vendors
+            .iter_mut()
+            .find(|v| v.id == vendor.id && v.deleted.is_none())
+            .ok_or_else(|| AppError::NotFound(format!("vendor {} not found", vendor.id)))?;
+        existing.name.clone_from(&vendor.name);
+        existing.contact.clone_from(&vendor.contact);
+        existing.phone.clone_from(&vendor.phone);
+        Ok(())
+    }
+
+    fn delete(&self, id: i32) -> Result<(), AppError> {
+        let mut vendors = self.vendors.lock().unwrap();
+        let existing = vendors
+            .iter_mut()
+            .find(|v| v.id == id && v.deleted.is_none())
+            .ok_or_else(|| AppError::NotFound(format!("vendor {id} not found")))?;
+        existing.deleted = Some(chrono::Utc::now().naive_utc());
+        Ok(())
+    }
+}
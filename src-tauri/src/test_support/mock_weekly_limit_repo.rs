@@ -1,15 +1,18 @@
 use crate::common::error::AppError;
+use crate::domain::models::LimitSettings;
 use crate::domain::repos::WeeklyLimitRepoTrait;
 use std::sync::Mutex;
 
 pub struct MockWeeklyLimitRepo {
     inner: Mutex<i32>,
+    settings: Mutex<LimitSettings>,
 }
 
 impl MockWeeklyLimitRepo {
     pub fn new() -> Self {
         Self {
             inner: Mutex::new(0),
+            settings: Mutex::new(LimitSettings::default()),
         }
     }
 }
@@ -29,4 +32,13 @@ impl WeeklyLimitRepoTrait for MockWeeklyLimitRepo {
     fn get_limit(&self) -> Result<i32, AppError> {
         Ok(*self.inner.lock().unwrap())
     }
+
+    fn get_settings(&self) -> Result<LimitSettings, AppError> {
+        Ok(self.settings.lock().unwrap().clone())
+    }
+
+    fn set_settings(&self, settings: &LimitSettings) -> Result<(), AppError> {
+        *self.settings.lock().unwrap() = settings.clone();
+        Ok(())
+    }
 }
@@ -2,11 +2,20 @@ pub mod mock_category_repo;
 pub mod mock_club_import_repo;
 pub mod mock_club_tx_repo;
 pub mod mock_cred_repo;
+pub mod mock_customer_limit_repo;
 pub mod mock_customer_repo;
 pub mod mock_customer_tx_detail_repo;
 pub mod mock_customer_tx_repo;
 pub mod mock_inventory_transaction_repo;
 pub mod mock_operator_repo;
+pub mod mock_order_return_repo;
 pub mod mock_price_adjustment_repo;
+pub mod mock_print_job_repo;
 pub mod mock_product_repo;
+pub mod mock_promotion_repo;
+pub mod mock_purchase_order_repo;
+pub mod mock_quantity_limit_repo;
+pub mod mock_stock_take_repo;
+pub mod mock_tax_rate_repo;
+pub mod mock_vendor_repo;
 pub mod mock_weekly_limit_repo;
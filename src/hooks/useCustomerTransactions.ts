@@ -7,6 +7,7 @@ export interface CustomerTransactionSearchRow {
   operator_name: string;
   customer_name: string;
   spent: number;
+  voided: boolean;
 }
 
 export interface CustomerTransactionSearchResult {
@@ -5,4 +5,6 @@ export default interface CustomerTxDetail {
   product_name: string;
   quantity: number;
   price: number;
+  discount: number; // cents a promotion took off the line
+  tax: number; // sales tax charged on the line
 }
@@ -5,4 +5,6 @@ export default interface PriceAdjustmentDto {
   old: number;
   new: number;
   created_at: string; // RFC3339 string
+  effective_at?: string | null; // RFC3339; a future time schedules the change
+  status?: "pending" | "applied" | "cancelled";
 }
@@ -6,6 +6,7 @@ import { useTheme } from "@mui/material/styles";
 import BarcodeScanner from "../../lib/barcode";
 import TransactionFooter from "./components/TransactionFooter";
 import TransactionItems, {
+  type ScannedItem,
   type TransactionItem,
 } from "./components/TransactionItems";
 import AppSnackbar from "../../components/AppSnackbar";
@@ -23,7 +24,8 @@ type ScannerType = "Zebra" | "Generic";
 
 export default function Sales() {
   const theme = useTheme();
-  const { products, customers, loading, error, refetch } = usePosInit();
+  const { products, customers, barcodes, loading, error, refetch } =
+    usePosInit();
   const { activeOperator } = useAuth();
 
   // Transaction State
@@ -33,7 +35,7 @@ export default function Sales() {
     [],
   );
   const [transactionTotal, setTransactionTotal] = useState(0);
-  const [scannedUpc, setScannedUpc] = useState<string | null>(null);
+  const [scannedUpc, setScannedUpc] = useState<ScannedItem | null>(null);
   const [weeklyLimit, setWeeklyLimit] = useState<number>(Infinity);
   const [weeklySpent, setWeeklySpent] = useState<number>(0);
 
@@ -46,10 +48,13 @@ export default function Sales() {
     }
     (async () => {
       try {
-        const cap = await invoke<number>("get_weekly_limit");
-        setWeeklyLimit(cap);
+        // tier / override aware limit for this customer
+        const limit = await invoke<{ amount: number }>("get_customer_limit", {
+          customerMdoc: selectedCustomer.customer.mdoc,
+        });
+        setWeeklyLimit(limit.amount);
       } catch (err) {
-        console.error("get_weekly_limit failed:", err);
+        console.error("get_customer_limit failed:", err);
         showSnackbar("Failed fetching weekly limit", "error");
       }
       try {
@@ -83,6 +88,7 @@ export default function Sales() {
   // Refs
   const errorTimerRef = useRef<ReturnType<typeof setTimeout> | null>(null);
   const footerRef = useRef<HTMLDivElement>(null);
+  const checkoutKeyRef = useRef<string | null>(null);
 
   // Utilities
   const getScannerType = (): ScannerType => {
@@ -118,8 +124,14 @@ export default function Sales() {
     setTransactionTotal(0);
     setScannedUpc(null);
     setTransactionItems([]);
+    checkoutKeyRef.current = null;
   };
 
+  // a changed cart is a new checkout, so it mustn't replay the old one's key
+  useEffect(() => {
+    checkoutKeyRef.current = null;
+  }, [transactionItems]);
+
   // stabilize handlers passed to TransactionItems
   const handleInsufficientFunds = useCallback(() => {
     setIsInsufficientFundsDialogOpen(true);
@@ -157,16 +169,19 @@ export default function Sales() {
         }
       } else {
         // Product scan mode
-        const foundProduct = products.find((product) => product.upc === code);
+        // alternate and case barcodes scan as their product
+        const alias = barcodes.find((b) => b.barcode === code);
+        const upc = alias ? alias.upc : code;
+        const foundProduct = products.find((product) => product.upc === upc);
         if (foundProduct) {
-          setScannedUpc(code);
+          setScannedUpc({ upc, quantity: alias ? alias.multiplier : 1 });
           setTimeout(() => setScannedUpc(null), 100);
         } else {
           setIsUnknownUpcDialogOpen(true);
         }
       }
     },
-    [customers, selectedCustomer, products],
+    [customers, selectedCustomer, products, barcodes],
   );
 
   // Footer Button Handlers
@@ -196,12 +211,15 @@ export default function Sales() {
       price: item.price,
     }));
 
+    // kept while the cart is unchanged, so a retried submit replays the same order
+    checkoutKeyRef.current ??= crypto.randomUUID();
     const saleDto: SaleDto = {
       customer_mdoc: selectedCustomer.customer.mdoc,
       operator_mdoc: activeOperator?.mdoc ?? 0,
       operator_name: activeOperator?.name ?? "Unknown",
       customer_name: selectedCustomer.customer.name,
       items: saleItems,
+      idempotency_key: checkoutKeyRef.current,
     };
 
     try {
@@ -222,7 +240,13 @@ export default function Sales() {
 
       showSnackbar(`Order #${orderId} submitted successfully!`, "success");
     } catch (err) {
-      const errorMessage = err instanceof Error ? err.message : String(err);
+      // a short-stock rejection comes back as { message, insufficient_stock }
+      const errorMessage =
+        err instanceof Error
+          ? err.message
+          : typeof err === "object" && err !== null && "message" in err
+            ? String(err.message)
+            : String(err);
       showSnackbar(`Failed to submit transaction: ${errorMessage}`);
       console.error("Failed to submit sale transaction:", err);
     }
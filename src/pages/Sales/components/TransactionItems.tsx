@@ -21,8 +21,14 @@ export interface TransactionItem {
   quantity: number;
 }
 
+// a scan resolved to its product; a case barcode adds several units
+export interface ScannedItem {
+  upc: string;
+  quantity: number;
+}
+
 interface Props {
-  scannedUpc: string | null;
+  scannedUpc: ScannedItem | null;
   products: Product[];
   transactionItems: TransactionItem[];
   setTransactionItems: React.Dispatch<React.SetStateAction<TransactionItem[]>>;
@@ -83,14 +89,14 @@ export default function TransactionItems({
    */
   const getScannedCart = (
     prevItems: TransactionItem[],
-    upc: string,
+    { upc, quantity }: ScannedItem,
   ): TransactionItem[] => {
     // Find existing
     const idx = prevItems.findIndex((i) => i.upc === upc);
     if (idx >= 0) {
       // bump quantity
       return prevItems.map((it, i) =>
-        i === idx ? { ...it, quantity: it.quantity + 1 } : it,
+        i === idx ? { ...it, quantity: it.quantity + quantity } : it,
       );
     }
     // add new line
@@ -103,7 +109,7 @@ export default function TransactionItems({
             upc: p.upc,
             name: p.desc,
             price: p.price,
-            quantity: 1,
+            quantity,
           },
         ]
       : prevItems;
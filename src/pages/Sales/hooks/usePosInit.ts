@@ -8,9 +8,17 @@ export interface CustomerPosDto {
   balance: number;
 }
 
+// another barcode for a product; scanning it adds `multiplier` units
+export interface BarcodeAliasDto {
+  barcode: string;
+  upc: string;
+  multiplier: number;
+}
+
 export interface PosDto {
   products: ProductDto[];
   customers: CustomerPosDto[];
+  barcodes: BarcodeAliasDto[];
 }
 
 export interface SaleItemDto {
@@ -26,11 +34,14 @@ export interface SaleDto {
   operator_name: string;
   customer_name: string;
   items: SaleItemDto[];
+  // one per checkout, resent on retries so the order is only recorded once
+  idempotency_key?: string;
 }
 
 export default function usePosInit() {
   const [products, setProducts] = useState<ProductDto[]>([]);
   const [customers, setCustomers] = useState<CustomerPosDto[]>([]);
+  const [barcodes, setBarcodes] = useState<BarcodeAliasDto[]>([]);
   const [loading, setLoading] = useState(true);
   const [error, setError] = useState<Error | null>(null);
 
@@ -41,11 +52,13 @@ export default function usePosInit() {
       const result = await invoke<PosDto>("pos_init");
       setProducts(result.products);
       setCustomers(result.customers);
+      setBarcodes(result.barcodes);
     } catch (err) {
       console.error("Failed to load POS data:", err);
       setError(err as Error);
       setProducts([]);
       setCustomers([]);
+      setBarcodes([]);
     } finally {
       setLoading(false);
     }
@@ -58,6 +71,7 @@ export default function usePosInit() {
   return {
     products,
     customers,
+    barcodes,
     loading,
     error,
     refetch: fetchPosData,